@@ -51,6 +51,7 @@ echo "in root: gitoxide CLI"
 (enter gix-lfs && indent cargo diet -n --package-size-limit 5KB)
 (enter gix-note && indent cargo diet -n --package-size-limit 5KB)
 (enter gix-fetchhead && indent cargo diet -n --package-size-limit 5KB)
+(enter gix-bundle && indent cargo diet -n --package-size-limit 10KB)
 (enter gix-sec && indent cargo diet -n --package-size-limit 25KB)
 (enter gix-tix && indent cargo diet -n --package-size-limit 5KB)
 (enter gix-credentials && indent cargo diet -n --package-size-limit 35KB)
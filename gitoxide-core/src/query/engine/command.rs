@@ -18,10 +18,10 @@ impl query::Engine {
     ) -> anyhow::Result<()> {
         match cmd {
             Command::TracePath { mut spec } => {
-                if let Some(prefix) = self.repo.prefix() {
-                    spec.apply_prefix(&prefix?);
-                };
-                let relpath = spec.items().next().expect("spec has at least one item");
+                let prefix = self.repo.prefix().transpose()?.unwrap_or_default();
+                let relpath = spec
+                    .normalize(&prefix, self.repo.work_dir().unwrap_or_else(|| self.repo.git_dir()))?
+                    .path();
                 let file_id: usize = self
                     .con
                     .query_row(
@@ -1,7 +1,7 @@
 pub enum Command {
     TracePath {
-        /// The repo-relative path to the file to trace
-        spec: gix::path::Spec,
+        /// The pathspec naming the file to trace, relative to the current working directory.
+        spec: gix::pathspec::Pattern,
     },
 }
 
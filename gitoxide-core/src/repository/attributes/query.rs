@@ -9,7 +9,7 @@ pub(crate) mod function {
     use std::{io, path::Path};
 
     use anyhow::bail;
-    use gix::prelude::FindExt;
+    use gix::{bstr::ByteSlice, prelude::FindExt};
 
     use crate::{
         repository::attributes::query::{attributes_cache, Options},
@@ -18,7 +18,7 @@ pub(crate) mod function {
 
     pub fn query(
         repo: gix::Repository,
-        pathspecs: impl Iterator<Item = gix::path::Spec>,
+        pathspecs: impl Iterator<Item = anyhow::Result<gix::pathspec::Pattern>>,
         mut out: impl io::Write,
         mut err: impl io::Write,
         Options { format, statistics }: Options,
@@ -28,28 +28,37 @@ pub(crate) mod function {
         }
 
         let mut cache = attributes_cache(&repo)?;
-        let prefix = repo.prefix().expect("worktree - we have an index by now")?;
+        let prefix = repo.prefix().transpose()?.unwrap_or_default();
+        let root = repo.work_dir().unwrap_or_else(|| repo.git_dir()).to_owned();
         let mut matches = cache.attribute_matches();
 
-        for mut spec in pathspecs {
-            for path in spec.apply_prefix(&prefix).items() {
-                let is_dir = gix::path::from_bstr(path).metadata().ok().map(|m| m.is_dir());
-                let entry = cache.at_entry(path, is_dir, |oid, buf| repo.objects.find_blob(oid, buf))?;
+        for spec in pathspecs {
+            let mut spec = spec?;
+            let path = spec.normalize(&prefix, &root)?.path();
+            let is_dir = if path.ends_with(b"/") {
+                Some(true)
+            } else {
+                root.join(gix::path::from_bstr(path))
+                    .metadata()
+                    .ok()
+                    .map(|m| m.is_dir())
+            };
+            let path = path.strip_suffix(b"/").map_or(path, |p| p.as_bstr());
+            let entry = cache.at_entry(path, is_dir, |oid, buf| repo.objects.find_blob(oid, buf))?;
 
-                if !entry.matching_attributes(&mut matches) {
-                    continue;
-                }
-                for m in matches.iter() {
-                    writeln!(
-                        out,
-                        "{}:{}:{}\t{}\t{}",
-                        m.location.source.map(Path::to_string_lossy).unwrap_or_default(),
-                        m.location.sequence_number,
-                        m.pattern,
-                        path,
-                        m.assignment
-                    )?;
-                }
+            if !entry.matching_attributes(&mut matches) {
+                continue;
+            }
+            for m in matches.iter() {
+                writeln!(
+                    out,
+                    "{}:{}:{}\t{}\t{}",
+                    m.location.source.map(Path::to_string_lossy).unwrap_or_default(),
+                    m.location.sequence_number,
+                    m.pattern,
+                    path,
+                    m.assignment
+                )?;
             }
         }
 
@@ -1,7 +1,7 @@
 use std::io;
 
 use anyhow::bail;
-use gix::prelude::FindExt;
+use gix::{bstr::ByteSlice, prelude::FindExt};
 
 use crate::OutputFormat;
 
@@ -20,7 +20,7 @@ pub mod query {
 
 pub fn query(
     repo: gix::Repository,
-    pathspecs: impl Iterator<Item = gix::path::Spec>,
+    pathspecs: impl Iterator<Item = anyhow::Result<gix::pathspec::Pattern>>,
     mut out: impl io::Write,
     mut err: impl io::Write,
     query::Options {
@@ -42,27 +42,36 @@ pub fn query(
     )?;
 
     let prefix = repo.prefix().expect("worktree - we have an index by now")?;
+    let workdir = repo.work_dir().expect("worktree - we have an index by now");
 
-    for mut spec in pathspecs {
-        for path in spec.apply_prefix(&prefix).items() {
-            // TODO: what about paths that end in /? Pathspec might handle it, it's definitely something git considers
-            //       even if the directory doesn't exist. Seems to work as long as these are kept in the spec.
-            let is_dir = gix::path::from_bstr(path).metadata().ok().map(|m| m.is_dir());
-            let entry = cache.at_entry(path, is_dir, |oid, buf| repo.objects.find_blob(oid, buf))?;
-            let match_ = entry
-                .matching_exclude_pattern()
-                .and_then(|m| (show_ignore_patterns || !m.pattern.is_negative()).then_some(m));
-            match match_ {
-                Some(m) => writeln!(
-                    out,
-                    "{}:{}:{}\t{}",
-                    m.source.map(std::path::Path::to_string_lossy).unwrap_or_default(),
-                    m.sequence_number,
-                    m.pattern,
-                    path
-                )?,
-                None => writeln!(out, "::\t{path}")?,
-            }
+    for spec in pathspecs {
+        let mut spec = spec?;
+        let path = spec.normalize(&prefix, workdir)?.path();
+        // A trailing slash marks a directory, even if it doesn't exist.
+        let is_dir = if path.ends_with(b"/") {
+            Some(true)
+        } else {
+            workdir
+                .join(gix::path::from_bstr(path))
+                .metadata()
+                .ok()
+                .map(|m| m.is_dir())
+        };
+        let path = path.strip_suffix(b"/").map_or(path, |p| p.as_bstr());
+        let entry = cache.at_entry(path, is_dir, |oid, buf| repo.objects.find_blob(oid, buf))?;
+        let match_ = entry
+            .matching_exclude_pattern()
+            .and_then(|m| (show_ignore_patterns || !m.pattern.is_negative()).then_some(m));
+        match match_ {
+            Some(m) => writeln!(
+                out,
+                "{}:{}:{}\t{}",
+                m.source.map(std::path::Path::to_string_lossy).unwrap_or_default(),
+                m.sequence_number,
+                m.pattern,
+                path
+            )?,
+            None => writeln!(out, "::\t{path}")?,
         }
     }
 
@@ -0,0 +1,47 @@
+use gix_attributes::{AssignmentRef, StateRef};
+
+/// The names of attributes that affect how entries are placed into an archive, in addition to the ones
+/// [affecting the filter pipeline](gix_filter::pipeline::ATTRIBUTES).
+pub const ATTRIBUTES: &[&str] = &["export-ignore", "export-subst"];
+
+/// The error returned by [`Attributes::from_assignments()`].
+pub type Error = gix_filter::pipeline::attributes::Error;
+
+/// All information obtained from the attributes of a path that is relevant when placing it into an archive.
+#[derive(Debug, Clone)]
+pub struct Attributes {
+    /// If `true`, the `export-ignore` attribute is set and the entry, along with all of its children, is not archived.
+    pub export_ignore: bool,
+    /// If `true`, the `export-subst` attribute is set and `$Format:<placeholders>$` in the content of the file
+    /// is replaced with information about the [archived commit](crate::Options::commit_id).
+    pub export_subst: bool,
+    /// The attributes to use when converting the file with the filter pipeline.
+    pub filter: gix_filter::pipeline::Attributes,
+}
+
+impl Attributes {
+    /// Digest `assignments`, which should be the attributes matching a path, along with `config` to learn how the path
+    /// should be placed into the archive.
+    ///
+    /// Note that attributes that aren't relevant to archives or the filter pipeline are ignored.
+    pub fn from_assignments<'a>(
+        assignments: impl IntoIterator<Item = AssignmentRef<'a>>,
+        config: gix_filter::eol::Configuration,
+    ) -> Result<Self, Error> {
+        let mut export_ignore = false;
+        let mut export_subst = false;
+        let mut filter_assignments = Vec::new();
+        for assignment in assignments {
+            match assignment.name.as_str() {
+                "export-ignore" => export_ignore = assignment.state == StateRef::Set,
+                "export-subst" => export_subst = assignment.state == StateRef::Set,
+                _ => filter_assignments.push(assignment),
+            }
+        }
+        Ok(Attributes {
+            export_ignore,
+            export_subst,
+            filter: gix_filter::pipeline::Attributes::from_assignments(filter_assignments, config)?,
+        })
+    }
+}
@@ -2,9 +2,11 @@
 #![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
 
+use bstr::BString;
+
 /// The error returned by [`write_to()`].
 #[derive(Debug, thiserror::Error)]
-#[allow(missing_docs)]
+#[allow(missing_docs, clippy::large_enum_variant)]
 pub enum Error<E>
 where
     E: std::error::Error + Send + Sync + 'static,
@@ -13,6 +15,20 @@ where
     Io(#[from] std::io::Error),
     #[error("Could not find a blob or tree for archival")]
     Find(#[source] E),
+    #[error("Expected object {id} to be a {expected}, but it was a {actual}")]
+    ObjectKind {
+        id: gix_hash::ObjectId,
+        expected: gix_object::Kind,
+        actual: gix_object::Kind,
+    },
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error("Could not obtain the attributes of an entry")]
+    Attributes(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Filter(#[from] gix_filter::pipeline::convert_to_worktree::Error),
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
 }
 
 /// The supported container formats for use in [`write_to()`].
@@ -36,6 +52,20 @@ pub enum Format {
     },
 }
 
+/// An entry that isn't part of the archived tree, but is added to the archive after all of its entries,
+/// similar to `git archive --add-file`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdditionalEntry {
+    /// The path of the entry within the archive, relative to the [tree prefix](Options::tree_prefix).
+    pub relative_path: BString,
+    /// The kind of the entry.
+    pub mode: gix_object::tree::EntryMode,
+    /// The content of the entry, or the target of a symbolic link.
+    ///
+    /// It is ignored for directories and submodules.
+    pub data: Vec<u8>,
+}
+
 /// Options for configuring [`write_to()`].
 #[derive(Clone, Debug)]
 pub struct Options {
@@ -47,6 +77,12 @@ pub struct Options {
     ///
     /// Defaults to the current time. The caller may set this to the commit time if available.
     pub modification_time: std::time::SystemTime,
+    /// The id of the commit the archived tree belongs to, if there is one.
+    ///
+    /// It's written as comment into the archive, and used to expand placeholders in files with the `export-subst` attribute.
+    pub commit_id: Option<gix_hash::ObjectId>,
+    /// Entries to add to the archive after all entries of the tree.
+    pub additional_entries: Vec<AdditionalEntry>,
 }
 
 impl Default for Options {
@@ -55,9 +91,17 @@ impl Default for Options {
             format: Default::default(),
             tree_prefix: None,
             modification_time: std::time::SystemTime::now(),
+            commit_id: None,
+            additional_entries: Vec::new(),
         }
     }
 }
 
+///
+pub mod attributes;
+pub use attributes::Attributes;
+
+mod subst;
+
 mod write;
-pub use write::write_to;
+pub use write::{write_to, write_to_seek};
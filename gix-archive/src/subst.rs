@@ -0,0 +1,97 @@
+use std::io::Write;
+
+use bstr::ByteSlice;
+use gix_object::commit::MessageRef;
+
+/// Replace all `$Format:<placeholders>$` in `data` with information about `commit` with `id`, like `git archive` does
+/// for files with the `export-subst` attribute, and write the result to `out`.
+///
+/// Return `false` if there was nothing to replace, leaving `out` in an unspecified state.
+pub(crate) fn expand(data: &[u8], id: &gix_hash::oid, commit: &gix_object::Commit, out: &mut Vec<u8>) -> bool {
+    const START: &[u8] = b"$Format:";
+    out.clear();
+    let mut rest = data;
+    let mut expanded = false;
+    while let Some(start) = rest.find(START) {
+        let format_start = start + START.len();
+        let Some(format_len) = rest[format_start..].find_byte(b'$') else {
+            break;
+        };
+        out.extend_from_slice(&rest[..start]);
+        format(&rest[format_start..][..format_len], id, commit, out);
+        rest = &rest[format_start + format_len + 1..];
+        expanded = true;
+    }
+    out.extend_from_slice(rest);
+    expanded
+}
+
+/// Write `format` to `out` with all known placeholders expanded.
+fn format(format: &[u8], id: &gix_hash::oid, commit: &gix_object::Commit, out: &mut Vec<u8>) {
+    let mut rest = format;
+    while let Some(pos) = rest.find_byte(b'%') {
+        out.extend_from_slice(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        let consumed = placeholder(rest, id, commit, out);
+        if consumed == 0 {
+            out.push(b'%');
+        }
+        rest = &rest[consumed..];
+    }
+    out.extend_from_slice(rest);
+}
+
+/// Expand the placeholder at the beginning of `spec` into `out` and return the amount of consumed bytes, or `0` if
+/// it is unknown.
+///
+/// Note that abbreviated hashes always have 7 characters as there is no object database to assure they are unique.
+fn placeholder(spec: &[u8], id: &gix_hash::oid, commit: &gix_object::Commit, out: &mut Vec<u8>) -> usize {
+    const SHORT_HEX_LEN: usize = 7;
+    let ids = |ids: &mut dyn Iterator<Item = &gix_hash::oid>, hex_len: Option<usize>, out: &mut Vec<u8>| {
+        for (index, id) in ids.enumerate() {
+            if index != 0 {
+                out.push(b' ');
+            }
+            write!(out, "{}", id.to_hex_with_len(hex_len.unwrap_or(id.kind().len_in_hex()))).expect("no io error");
+        }
+    };
+    match spec {
+        [b'H', ..] => ids(&mut Some(id).into_iter(), None, out),
+        [b'h', ..] => ids(&mut Some(id).into_iter(), Some(SHORT_HEX_LEN), out),
+        [b'T', ..] => ids(&mut Some(commit.tree.as_ref()).into_iter(), None, out),
+        [b't', ..] => ids(&mut Some(commit.tree.as_ref()).into_iter(), Some(SHORT_HEX_LEN), out),
+        [b'P', ..] => ids(&mut commit.parents.iter().map(AsRef::as_ref), None, out),
+        [b'p', ..] => ids(&mut commit.parents.iter().map(AsRef::as_ref), Some(SHORT_HEX_LEN), out),
+        [who @ (b'a' | b'c'), field, ..] => {
+            let signature = if *who == b'a' {
+                &commit.author
+            } else {
+                &commit.committer
+            };
+            let time = signature.time;
+            match field {
+                b'n' => out.extend_from_slice(&signature.name),
+                b'e' => out.extend_from_slice(&signature.email),
+                b'd' => out.extend_from_slice(time.format(gix_date::time::format::DEFAULT).as_bytes()),
+                b'D' => out.extend_from_slice(time.format(gix_date::time::format::RFC2822).as_bytes()),
+                b'i' => out.extend_from_slice(time.format(gix_date::time::format::ISO8601).as_bytes()),
+                b'I' => out.extend_from_slice(time.format(gix_date::time::format::ISO8601_STRICT).as_bytes()),
+                b's' => out.extend_from_slice(time.format(gix_date::time::format::SHORT).as_bytes()),
+                b't' => out.extend_from_slice(time.format(gix_date::time::format::UNIX).as_bytes()),
+                _ => return 0,
+            }
+            return 2;
+        }
+        [b's', ..] => out.extend_from_slice(&MessageRef::from_bytes(&commit.message).summary()),
+        [b'b', ..] => {
+            if let Some(body) = MessageRef::from_bytes(&commit.message).body {
+                out.extend_from_slice(body);
+            }
+        }
+        [b'B', ..] => out.extend_from_slice(&commit.message),
+        [b'n', ..] => out.push(b'\n'),
+        [b'%', ..] => out.push(b'%'),
+        _ => return 0,
+    }
+    1
+}
@@ -1,15 +1,395 @@
-use crate::{Error, Options};
+use std::io::Write;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use gix_object::tree::EntryMode;
+
+use crate::{subst, Attributes, Error, Format, Options};
 
 /// Use `find` to traverse `tree` and fetch the contained blobs to write to `out` configured according to `opts`.
 ///
-/// ### Limitations
+/// `attributes(relative_path, mode)` is called for each entry of the tree to obtain its [`Attributes`], which control
+/// if it is included in the archive at all, how `pipeline` converts its content to what would be checked out, and if
+/// placeholders are substituted. Blobs are converted with `pipeline` just like they would be for a checkout.
+///
+/// Directories, symbolic links and the executable bit of files are stored in the archive, while submodules are stored
+/// as empty directories. If [`Options::commit_id`] is set, it's stored as comment in the archive, as global pax header
+/// for `tar` archives, and is used to expand placeholders in files with the `export-subst` attribute.
 ///
-/// * `.gitattributes` aren't considered, and filters are not applied, affecting correctness.
-pub fn write_to<W, Find, E>(_tree: &gix_hash::oid, mut _find: Find, mut _out: W, _opts: Options) -> Result<(), Error<E>>
+/// ### Note
+///
+/// As `zip` archives can only be written to seekable streams, they are created in memory first. Use [`write_to_seek()`]
+/// to avoid that.
+pub fn write_to<W, Find, E, Attrs, AE>(
+    tree: &gix_hash::oid,
+    find: Find,
+    attributes: Attrs,
+    pipeline: &mut gix_filter::Pipeline,
+    mut out: W,
+    opts: Options,
+) -> Result<(), Error<E>>
 where
     W: std::io::Write,
     Find: for<'a> FnMut(&gix_hash::oid, &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, E>,
     E: std::error::Error + Send + Sync + 'static,
+    Attrs: FnMut(&BStr, EntryMode) -> Result<Attributes, AE>,
+    AE: std::error::Error + Send + Sync + 'static,
 {
+    let state = State::new(find, attributes, pipeline, tree, &opts)?;
+    match opts.format {
+        Format::Tar => {
+            write_tar(state, &mut out, &opts)?;
+        }
+        Format::TarGz { compression_level } => {
+            let out = flate2::write::GzEncoder::new(out, flate2::Compression::new(compression_level.into()));
+            write_tar(state, out, &opts)?.finish()?;
+        }
+        Format::Zip { compression_level } => {
+            let archive = write_zip(state, std::io::Cursor::new(Vec::new()), compression_level, &opts)?;
+            out.write_all(archive.get_ref())?;
+        }
+    }
     Ok(())
 }
+
+/// Like [`write_to()`], but writes `zip` archives directly to `out` as it is seekable.
+pub fn write_to_seek<W, Find, E, Attrs, AE>(
+    tree: &gix_hash::oid,
+    find: Find,
+    attributes: Attrs,
+    pipeline: &mut gix_filter::Pipeline,
+    out: W,
+    opts: Options,
+) -> Result<(), Error<E>>
+where
+    W: std::io::Write + std::io::Seek,
+    Find: for<'a> FnMut(&gix_hash::oid, &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+    Attrs: FnMut(&BStr, EntryMode) -> Result<Attributes, AE>,
+    AE: std::error::Error + Send + Sync + 'static,
+{
+    match opts.format {
+        Format::Zip { compression_level } => {
+            let state = State::new(find, attributes, pipeline, tree, &opts)?;
+            write_zip(state, out, compression_level, &opts)?;
+            Ok(())
+        }
+        Format::Tar | Format::TarGz { .. } => write_to(tree, find, attributes, pipeline, out, opts),
+    }
+}
+
+/// An entry to place into the archive.
+struct Entry<'a> {
+    /// The path of the entry in the archive, including the tree prefix. Directories end with a slash.
+    path: &'a BStr,
+    mode: EntryMode,
+    /// The content of files, or the target of symbolic links.
+    data: &'a [u8],
+}
+
+fn write_tar<W, Find, E, Attrs, AE>(state: State<'_, Find, Attrs>, out: W, opts: &Options) -> Result<W, Error<E>>
+where
+    W: std::io::Write,
+    Find: for<'a> FnMut(&gix_hash::oid, &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+    Attrs: FnMut(&BStr, EntryMode) -> Result<Attributes, AE>,
+    AE: std::error::Error + Send + Sync + 'static,
+{
+    let mut archive = tar::Builder::new(out);
+    let mtime = opts
+        .modification_time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    if let Some(commit_id) = opts.commit_id {
+        let mut header = tar::Header::new_ustar();
+        header.set_entry_type(tar::EntryType::XGlobalHeader);
+        header.set_mode(0o666);
+        header.set_mtime(mtime);
+        let record = pax_record("comment", commit_id.to_string().as_bytes());
+        header.set_size(record.len() as u64);
+        archive.append_data(&mut header, "pax_global_header", record.as_slice())?;
+    }
+    state.traverse(opts, &mut |entry| {
+        let mut header = tar::Header::new_ustar();
+        header.set_mtime(mtime);
+        header.set_size(0);
+        let path = gix_path::from_bstr(entry.path);
+        match entry.mode {
+            EntryMode::Tree | EntryMode::Commit => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(0o775);
+                archive.append_data(&mut header, path, std::io::empty())?;
+            }
+            EntryMode::Blob | EntryMode::BlobExecutable => {
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_mode(if entry.mode == EntryMode::BlobExecutable {
+                    0o775
+                } else {
+                    0o664
+                });
+                header.set_size(entry.data.len() as u64);
+                archive.append_data(&mut header, path, entry.data)?;
+            }
+            EntryMode::Link => {
+                header.set_entry_type(tar::EntryType::Symlink);
+                header.set_mode(0o777);
+                archive.append_link(&mut header, path, gix_path::from_byte_slice(entry.data))?;
+            }
+        }
+        Ok(())
+    })?;
+    Ok(archive.into_inner()?)
+}
+
+fn write_zip<W, Find, E, Attrs, AE>(
+    state: State<'_, Find, Attrs>,
+    out: W,
+    compression_level: u8,
+    opts: &Options,
+) -> Result<W, Error<E>>
+where
+    W: std::io::Write + std::io::Seek,
+    Find: for<'a> FnMut(&gix_hash::oid, &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+    Attrs: FnMut(&BStr, EntryMode) -> Result<Attributes, AE>,
+    AE: std::error::Error + Send + Sync + 'static,
+{
+    let mut archive = zip::ZipWriter::new(out);
+    if let Some(commit_id) = opts.commit_id {
+        archive.set_comment(commit_id.to_string());
+    }
+    let options = zip::write::FileOptions::default()
+        .last_modified_time(zip_time(opts.modification_time))
+        .compression_method(if compression_level == 0 {
+            zip::CompressionMethod::Stored
+        } else {
+            zip::CompressionMethod::Deflated
+        })
+        .compression_level((compression_level != 0).then_some(compression_level.into()));
+    state.traverse(opts, &mut |entry| {
+        let path = entry.path.to_str_lossy();
+        match entry.mode {
+            EntryMode::Tree | EntryMode::Commit => {
+                archive.add_directory(path, options.unix_permissions(0o755))?;
+            }
+            EntryMode::Blob | EntryMode::BlobExecutable => {
+                let permissions = if entry.mode == EntryMode::BlobExecutable {
+                    0o755
+                } else {
+                    0o644
+                };
+                archive.start_file(
+                    path,
+                    options
+                        .unix_permissions(permissions)
+                        .large_file(entry.data.len() as u64 >= u32::MAX as u64),
+                )?;
+                archive.write_all(entry.data)?;
+            }
+            EntryMode::Link => {
+                archive.add_symlink(path, entry.data.to_str_lossy(), options.unix_permissions(0o777))?;
+            }
+        }
+        Ok(())
+    })?;
+    Ok(archive.finish()?)
+}
+
+/// Convert `time` to the time of a `zip` entry, which is the default time if it can't be represented.
+fn zip_time(time: std::time::SystemTime) -> zip::DateTime {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .and_then(|d| time::OffsetDateTime::from_unix_timestamp(d.as_secs() as i64).ok())
+        .and_then(|time| zip::DateTime::try_from(time).ok())
+        .unwrap_or_default()
+}
+
+/// Create a pax extended header record for `key` and `value`, which is prefixed with its own length in decimal.
+fn pax_record(key: &str, value: &[u8]) -> Vec<u8> {
+    let len_without_prefix = 1 /* space */ + key.len() + 1 /* = */ + value.len() + 1 /* newline */;
+    let mut len = len_without_prefix;
+    while len != len_without_prefix + len.to_string().len() {
+        len = len_without_prefix + len.to_string().len();
+    }
+    let mut record = format!("{len} {key}=").into_bytes();
+    record.extend_from_slice(value);
+    record.push(b'\n');
+    record
+}
+
+/// All state needed to traverse the tree and obtain the content of its entries.
+struct State<'p, Find, Attrs> {
+    find: Find,
+    attributes: Attrs,
+    pipeline: &'p mut gix_filter::Pipeline,
+    tree: gix_hash::ObjectId,
+    /// The commit the tree belongs to, for expanding placeholders.
+    commit: Option<(gix_hash::ObjectId, gix_object::Commit)>,
+    buf: Vec<u8>,
+    filtered: Vec<u8>,
+    substituted: Vec<u8>,
+}
+
+impl<'p, Find, E, Attrs, AE> State<'p, Find, Attrs>
+where
+    Find: for<'a> FnMut(&gix_hash::oid, &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+    Attrs: FnMut(&BStr, EntryMode) -> Result<Attributes, AE>,
+    AE: std::error::Error + Send + Sync + 'static,
+{
+    fn new(
+        mut find: Find,
+        attributes: Attrs,
+        pipeline: &'p mut gix_filter::Pipeline,
+        tree: &gix_hash::oid,
+        opts: &Options,
+    ) -> Result<Self, Error<E>> {
+        let mut buf = Vec::new();
+        let commit = match opts.commit_id {
+            Some(id) => {
+                let data = find(&id, &mut buf).map_err(Error::Find)?;
+                expect_kind(&id, &data, gix_object::Kind::Commit)?;
+                Some((id, gix_object::CommitRef::from_bytes(data.data)?.into()))
+            }
+            None => None,
+        };
+        Ok(State {
+            find,
+            attributes,
+            pipeline,
+            tree: tree.to_owned(),
+            commit,
+            buf,
+            filtered: Vec::new(),
+            substituted: Vec::new(),
+        })
+    }
+
+    /// Traverse the tree and call `emit` for each entry to place into the archive, followed by all additional entries.
+    fn traverse(
+        mut self,
+        opts: &Options,
+        emit: &mut dyn FnMut(Entry<'_>) -> Result<(), Error<E>>,
+    ) -> Result<(), Error<E>> {
+        let mut path = BString::default();
+        if let Some(prefix) = opts.tree_prefix.as_deref().filter(|prefix| !prefix.is_empty()) {
+            path.push_str(prefix);
+            if !path.ends_with(b"/") {
+                path.push_byte(b'/');
+            }
+            emit(Entry {
+                path: path.as_bstr(),
+                mode: EntryMode::Tree,
+                data: &[],
+            })?;
+        }
+        let prefix_len = path.len();
+        let tree = self.tree;
+        self.visit_tree(&tree, &mut path, prefix_len, emit)?;
+
+        for entry in &opts.additional_entries {
+            path.truncate(prefix_len);
+            path.push_str(&entry.relative_path);
+            if (entry.mode.is_tree() || entry.mode == EntryMode::Commit) && !path.ends_with(b"/") {
+                path.push_byte(b'/');
+            }
+            emit(Entry {
+                path: path.as_bstr(),
+                mode: entry.mode,
+                data: &entry.data,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn visit_tree(
+        &mut self,
+        id: &gix_hash::oid,
+        path: &mut BString,
+        prefix_len: usize,
+        emit: &mut dyn FnMut(Entry<'_>) -> Result<(), Error<E>>,
+    ) -> Result<(), Error<E>> {
+        let data = (self.find)(id, &mut self.buf).map_err(Error::Find)?;
+        expect_kind(id, &data, gix_object::Kind::Tree)?;
+        let entries = gix_object::TreeRefIter::from_bytes(data.data)
+            .map(|entry| entry.map(|entry| (entry.filename.to_owned(), entry.mode, entry.oid.to_owned())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (name, mode, id) in entries {
+            let len = path.len();
+            path.push_str(&name);
+            let attributes = (self.attributes)(path[prefix_len..].as_bstr(), mode)
+                .map_err(|err| Error::Attributes(Box::new(err)))?;
+            if !attributes.export_ignore {
+                match mode {
+                    EntryMode::Tree => {
+                        path.push_byte(b'/');
+                        emit(Entry {
+                            path: path.as_bstr(),
+                            mode,
+                            data: &[],
+                        })?;
+                        self.visit_tree(&id, path, prefix_len, emit)?;
+                    }
+                    EntryMode::Commit => {
+                        path.push_byte(b'/');
+                        emit(Entry {
+                            path: path.as_bstr(),
+                            mode,
+                            data: &[],
+                        })?;
+                    }
+                    EntryMode::Link => {
+                        let data = (self.find)(&id, &mut self.buf).map_err(Error::Find)?;
+                        expect_kind(&id, &data, gix_object::Kind::Blob)?;
+                        emit(Entry {
+                            path: path.as_bstr(),
+                            mode,
+                            data: data.data,
+                        })?;
+                    }
+                    EntryMode::Blob | EntryMode::BlobExecutable => {
+                        let data = (self.find)(&id, &mut self.buf).map_err(Error::Find)?;
+                        expect_kind(&id, &data, gix_object::Kind::Blob)?;
+                        self.pipeline.convert_to_worktree(
+                            data.data,
+                            path[prefix_len..].as_bstr(),
+                            &attributes.filter,
+                            &mut self.filtered,
+                        )?;
+                        let data = match &self.commit {
+                            Some((commit_id, commit))
+                                if attributes.export_subst
+                                    && subst::expand(&self.filtered, commit_id, commit, &mut self.substituted) =>
+                            {
+                                &self.substituted
+                            }
+                            _ => &self.filtered,
+                        };
+                        emit(Entry {
+                            path: path.as_bstr(),
+                            mode,
+                            data,
+                        })?;
+                    }
+                }
+            }
+            path.truncate(len);
+        }
+        Ok(())
+    }
+}
+
+fn expect_kind<E>(id: &gix_hash::oid, data: &gix_object::Data<'_>, expected: gix_object::Kind) -> Result<(), Error<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if data.kind == expected {
+        Ok(())
+    } else {
+        Err(Error::ObjectKind {
+            id: id.to_owned(),
+            expected,
+            actual: data.kind,
+        })
+    }
+}
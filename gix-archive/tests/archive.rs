@@ -1 +1,242 @@
+use std::{io::Read, path::Path};
 
+use gix_archive::{AdditionalEntry, Attributes, Format, Options};
+use gix_attributes::search::{MetadataCollection, Outcome};
+use gix_object::{bstr::ByteSlice, tree::EntryMode};
+use gix_odb::FindExt;
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// An entry of a `tar` archive, without the attributes that `git` and we may set differently.
+#[derive(Debug, PartialEq, Eq)]
+struct TarEntry {
+    path: String,
+    kind: tar::EntryType,
+    mode: u32,
+    mtime: u64,
+    link: Option<String>,
+    data: Vec<u8>,
+}
+
+fn tar_entries(archive: &[u8]) -> Result<Vec<TarEntry>> {
+    let mut out = Vec::new();
+    for entry in tar::Archive::new(archive).entries()? {
+        let mut entry = entry?;
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        let header = entry.header();
+        out.push(TarEntry {
+            path: entry.path()?.to_string_lossy().into_owned(),
+            kind: header.entry_type(),
+            mode: header.mode()?,
+            mtime: header.mtime()?,
+            link: entry.link_name()?.map(|link| link.to_string_lossy().into_owned()),
+            data,
+        });
+    }
+    Ok(out)
+}
+
+fn write_archive(dir: &Path, opts: Options) -> Result<Vec<u8>> {
+    let odb = gix_odb::at(dir.join(".git").join("objects"))?;
+    let commit_id = gix_hash::ObjectId::from_hex(std::fs::read(dir.join("head-id"))?.trim())?;
+    let mut buf = Vec::new();
+    let commit = odb.find_commit(commit_id, &mut buf)?;
+    let tree = commit.tree();
+    let time = commit.committer.time;
+
+    let mut collection = MetadataCollection::default();
+    let mut search = gix_attributes::Search::default();
+    search.add_patterns_buffer(
+        &std::fs::read(dir.join(".gitattributes"))?,
+        dir.join(".gitattributes"),
+        None,
+        &mut collection,
+        true,
+    );
+    let mut outcome = Outcome::default();
+    outcome.initialize(&collection);
+
+    let mut pipeline = gix_filter::Pipeline::new(Vec::new(), Default::default());
+    let eol_config = pipeline.options().eol_config;
+    let mut out = Vec::new();
+    gix_archive::write_to(
+        &tree,
+        |id, buf| odb.find(id, buf),
+        |path, _mode| {
+            outcome.reset();
+            search.pattern_matching_relative_path(path, gix_glob::pattern::Case::Sensitive, &mut outcome);
+            Attributes::from_assignments(outcome.iter().map(|m| m.assignment), eol_config)
+        },
+        &mut pipeline,
+        &mut out,
+        Options {
+            commit_id: Some(commit_id),
+            modification_time: std::time::UNIX_EPOCH + std::time::Duration::from_secs(time.seconds as u64),
+            ..opts
+        },
+    )?;
+    Ok(out)
+}
+
+#[test]
+fn tar_is_the_same_as_the_one_created_by_git() -> Result {
+    let dir = gix_testtools::scripted_fixture_read_only("basic.sh")?;
+    let actual = write_archive(&dir, Options::default())?;
+    let expected = tar_entries(&std::fs::read(dir.join("archive.tar"))?)?;
+    assert_eq!(tar_entries(&actual)?, expected);
+
+    let paths: Vec<_> = expected.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "pax_global_header",
+            ".gitattributes",
+            "a",
+            "crlf-file",
+            "dir/",
+            "dir/b",
+            "dir/exe",
+            "dir/subdir/",
+            "dir/subdir/c",
+            "subst",
+            "symlink-to-a"
+        ],
+        "ignored entries are not present"
+    );
+    let subst = expected.iter().find(|e| e.path == "subst").expect("present");
+    assert!(
+        !subst.data.contains_str("$Format"),
+        "placeholders were substituted: {:?}",
+        subst.data.as_bstr()
+    );
+    assert!(
+        subst.data.contains_str("unknown: %X\n"),
+        "unknown placeholders are kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn tar_with_prefix_is_the_same_as_the_one_created_by_git() -> Result {
+    let dir = gix_testtools::scripted_fixture_read_only("basic.sh")?;
+    let actual = write_archive(
+        &dir,
+        Options {
+            tree_prefix: Some("prefix".into()),
+            ..Default::default()
+        },
+    )?;
+    let expected = tar_entries(&std::fs::read(dir.join("archive-with-prefix.tar"))?)?;
+    assert_eq!(tar_entries(&actual)?, expected);
+    Ok(())
+}
+
+#[test]
+fn tar_gz_is_a_compressed_tar() -> Result {
+    let dir = gix_testtools::scripted_fixture_read_only("basic.sh")?;
+    let time = std::time::SystemTime::now();
+    let tar = write_archive(
+        &dir,
+        Options {
+            modification_time: time,
+            ..Default::default()
+        },
+    )?;
+    let tar_gz = write_archive(
+        &dir,
+        Options {
+            format: Format::TarGz { compression_level: 9 },
+            modification_time: time,
+            ..Default::default()
+        },
+    )?;
+    let mut decompressed = Vec::new();
+    flate2::read::GzDecoder::new(tar_gz.as_slice()).read_to_end(&mut decompressed)?;
+    assert_eq!(decompressed, tar);
+    Ok(())
+}
+
+#[test]
+fn zip_has_the_same_entries_as_the_one_created_by_git() -> Result {
+    let dir = gix_testtools::scripted_fixture_read_only("basic.sh")?;
+    for compression_level in [0, 9] {
+        let actual = write_archive(
+            &dir,
+            Options {
+                format: Format::Zip { compression_level },
+                ..Default::default()
+            },
+        )?;
+        let mut actual = zip::ZipArchive::new(std::io::Cursor::new(actual))?;
+        let mut expected = zip::ZipArchive::new(std::fs::File::open(dir.join("archive.zip"))?)?;
+        assert_eq!(actual.comment(), expected.comment(), "both contain the commit id");
+        let mut names: Vec<_> = expected.file_names().map(ToOwned::to_owned).collect();
+        names.sort();
+        let mut actual_names: Vec<_> = actual.file_names().map(ToOwned::to_owned).collect();
+        actual_names.sort();
+        assert_eq!(actual_names, names);
+        for name in names {
+            let mut expected = expected.by_name(&name)?;
+            let mut actual = actual.by_name(&name)?;
+            assert_eq!(actual.is_dir(), expected.is_dir(), "{}", expected.name());
+            let is_link_and_executable = |mode: Option<u32>| {
+                mode.map_or((false, false), |mode| (mode & 0o170000 == 0o120000, mode & 0o100 != 0))
+            };
+            assert_eq!(
+                is_link_and_executable(actual.unix_mode()),
+                is_link_and_executable(expected.unix_mode()),
+                "{}: `git` only stores the mode of symbolic links and executables",
+                expected.name()
+            );
+            let (mut actual_data, mut expected_data) = (Vec::new(), Vec::new());
+            actual.read_to_end(&mut actual_data)?;
+            expected.read_to_end(&mut expected_data)?;
+            assert_eq!(actual_data.as_bstr(), expected_data.as_bstr(), "{}", expected.name());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn additional_entries_are_placed_after_the_tree() -> Result {
+    let dir = gix_testtools::scripted_fixture_read_only("basic.sh")?;
+    let actual = write_archive(
+        &dir,
+        Options {
+            tree_prefix: Some("prefix/".into()),
+            additional_entries: vec![
+                AdditionalEntry {
+                    relative_path: "extra/dir".into(),
+                    mode: EntryMode::Tree,
+                    data: Vec::new(),
+                },
+                AdditionalEntry {
+                    relative_path: "extra/file".into(),
+                    mode: EntryMode::BlobExecutable,
+                    data: b"content".to_vec(),
+                },
+                AdditionalEntry {
+                    relative_path: "extra/link".into(),
+                    mode: EntryMode::Link,
+                    data: b"file".to_vec(),
+                },
+            ],
+            ..Default::default()
+        },
+    )?;
+    let entries = tar_entries(&actual)?;
+    let extra: Vec<_> = entries[entries.len() - 3..]
+        .iter()
+        .map(|e| (e.path.as_str(), e.kind, e.mode, e.link.as_deref(), e.data.as_slice()))
+        .collect();
+    assert_eq!(
+        extra,
+        [
+            ("prefix/extra/dir/", tar::EntryType::Directory, 0o775, None, &b""[..]),
+            ("prefix/extra/file", tar::EntryType::Regular, 0o775, None, b"content"),
+            ("prefix/extra/link", tar::EntryType::Symlink, 0o777, Some("file"), b""),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,29 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p dir/subdir ignored-dir
+echo "a" > a
+echo "b" > dir/b
+echo "c" > dir/subdir/c
+printf '#!/bin/sh\necho hi\n' > dir/exe && chmod +x dir/exe
+ln -s a symlink-to-a
+echo "ignored" > ignored-dir/file
+echo "ignored as well" > dir/ignored-file
+printf 'line one\nline two\n' > crlf-file
+printf 'commit: $Format:%%H$ (%%h)\ntree: $Format:%%T$\nauthor: $Format:%%an <%%ae> at %%ad$\nsubject: $Format:%%s$\nunknown: $Format:%%X$\n' > subst
+cat <<ATTRIBUTES > .gitattributes
+ignored-dir export-ignore
+dir/ignored-file export-ignore
+subst export-subst
+crlf-file eol=crlf
+ATTRIBUTES
+
+git add .
+git commit -q -m "the subject"
+
+git archive --format=tar HEAD > archive.tar
+git archive --format=tar --prefix=prefix/ HEAD > archive-with-prefix.tar
+git archive --format=zip HEAD > archive.zip
+git rev-parse HEAD > head-id
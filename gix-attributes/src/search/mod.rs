@@ -89,7 +89,7 @@ pub enum MatchKind {
 }
 
 /// The result of a search, containing all matching attributes.
-#[derive(Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Outcome {
     /// The list of all available attributes, by ascending order. Each slots index corresponds to an attribute with that order, i.e.
     /// `arr[attr.id] = <attr info>`.
@@ -111,7 +111,7 @@ pub struct Outcome {
     remaining: Option<usize>,
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
 struct Slot {
     r#match: Option<outcome::Match>,
     /// A list of all assignments, being an empty list for non-macro attributes, or all assignments (with order) for macros.
@@ -0,0 +1,109 @@
+use std::io::BufRead;
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{Header, Prerequisite, Ref, Version};
+
+/// The error returned by [`Header::from_read()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read bundle header")]
+    Io(#[from] std::io::Error),
+    #[error("Not a bundle file, signature was {line:?}")]
+    Signature { line: BString },
+    #[error("Capabilities like {line:?} are only allowed in bundles of version 3")]
+    CapabilityInV2 { line: BString },
+    #[error("Unknown capability {line:?}")]
+    UnknownCapability { line: BString },
+    #[error("Unsupported object format {name:?}")]
+    ObjectFormat { name: BString },
+    #[error("Could not decode object id in line {line:?}")]
+    Id {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+    #[error("Reference line {line:?} lacks a name")]
+    MissingRefName { line: BString },
+    #[error("The bundle header wasn't terminated by an empty line")]
+    UnexpectedEof,
+}
+
+impl Header {
+    /// Read a bundle header from `read`, consuming it up to and including the empty line that separates it from the pack,
+    /// so that `read` is positioned at the start of the pack afterwards.
+    pub fn from_read(read: &mut impl BufRead) -> Result<Header, Error> {
+        let mut buf = Vec::new();
+        let mut next_line = |buf: &mut Vec<u8>| -> Result<BString, Error> {
+            buf.clear();
+            if read.read_until(b'\n', buf)? == 0 {
+                return Err(Error::UnexpectedEof);
+            }
+            Ok(buf.strip_suffix(b"\n").ok_or(Error::UnexpectedEof)?.into())
+        };
+
+        let signature = next_line(&mut buf)?;
+        let version = match signature.as_bytes() {
+            b"# v2 git bundle" => Version::V2,
+            b"# v3 git bundle" => Version::V3,
+            _ => return Err(Error::Signature { line: signature }),
+        };
+        let mut header = Header {
+            version,
+            object_hash: gix_hash::Kind::Sha1,
+            filter: None,
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+        };
+        loop {
+            let line = next_line(&mut buf)?;
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@") {
+                if version == Version::V2 {
+                    return Err(Error::CapabilityInV2 { line });
+                }
+                let (name, value) = capability.split_once_str(b"=").unwrap_or((capability, &[]));
+                match name {
+                    b"object-format" => {
+                        header.object_hash = value
+                            .to_str()
+                            .ok()
+                            .and_then(|name| name.parse().ok())
+                            .ok_or_else(|| Error::ObjectFormat { name: value.into() })?;
+                    }
+                    b"filter" => header.filter = Some(value.into()),
+                    _ => return Err(Error::UnknownCapability { line }),
+                }
+            } else if let Some(prerequisite) = line.strip_prefix(b"-") {
+                let (id, comment) = split_id(prerequisite, &line)?;
+                header.prerequisites.push(Prerequisite {
+                    id,
+                    comment: comment.map(Into::into),
+                });
+            } else {
+                let (id, name) = split_id(&line, &line)?;
+                let name = name
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| Error::MissingRefName { line: line.clone() })?;
+                header.refs.push(Ref { id, name: name.into() });
+            }
+        }
+        Ok(header)
+    }
+}
+
+/// Split `input` into an object id and the optional remainder after the separating space.
+fn split_id<'a>(input: &'a [u8], line: &BString) -> Result<(ObjectId, Option<&'a [u8]>), Error> {
+    let (hex, rest) = match input.split_once_str(b" ") {
+        Some((hex, rest)) => (hex, Some(rest)),
+        None => (input, None),
+    };
+    let id = ObjectId::from_hex(hex).map_err(|source| Error::Id {
+        line: line.clone(),
+        source,
+    })?;
+    Ok((id, rest))
+}
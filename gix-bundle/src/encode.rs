@@ -0,0 +1,39 @@
+use std::io::Write;
+
+use crate::{Header, Version};
+
+impl Header {
+    /// Write this header to `out`, including the empty line that separates it from the pack which is expected to follow.
+    ///
+    /// Note that capabilities are only written for [`Version::V3`], hence headers of [`Version::V2`] should only be used
+    /// for SHA1 repositories and without [filter](Header::filter).
+    pub fn write_to(&self, mut out: impl Write) -> std::io::Result<()> {
+        out.write_all(self.version.signature().as_bytes())?;
+        out.write_all(b"\n")?;
+        if self.version == Version::V3 {
+            let object_format = match self.object_hash {
+                gix_hash::Kind::Sha1 => "sha1",
+            };
+            writeln!(out, "@object-format={object_format}")?;
+            if let Some(filter) = &self.filter {
+                out.write_all(b"@filter=")?;
+                out.write_all(filter)?;
+                out.write_all(b"\n")?;
+            }
+        }
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if let Some(comment) = &prerequisite.comment {
+                out.write_all(b" ")?;
+                out.write_all(comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for r in &self.refs {
+            write!(out, "{} ", r.id)?;
+            out.write_all(&r.name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")
+    }
+}
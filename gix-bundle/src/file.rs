@@ -0,0 +1,58 @@
+use std::{
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use crate::Header;
+
+/// The error returned by [`File::at()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open bundle file at '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Could not decode the header of the bundle at '{}'", path.display())]
+    Decode {
+        path: PathBuf,
+        source: crate::decode::Error,
+    },
+}
+
+/// A bundle file whose [header](Header) was read, ready to [provide its pack](File::into_pack()).
+///
+/// It serves as local source of references and objects, similar to a remote that was connected to.
+#[derive(Debug)]
+pub struct File {
+    /// The header of the bundle.
+    pub header: Header,
+    path: PathBuf,
+    pack: BufReader<std::fs::File>,
+}
+
+impl File {
+    /// Open the bundle file at `path` and read its header.
+    pub fn at(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let mut pack = std::fs::File::open(&path)
+            .map(BufReader::new)
+            .map_err(|source| Error::Io {
+                path: path.clone(),
+                source,
+            })?;
+        let header = Header::from_read(&mut pack).map_err(|source| Error::Decode {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(File { header, path, pack })
+    }
+
+    /// Return the path the bundle was opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Turn this instance into the reader for the pack that follows the header.
+    pub fn into_pack(self) -> BufReader<std::fs::File> {
+        self.pack
+    }
+}
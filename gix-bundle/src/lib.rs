@@ -0,0 +1,74 @@
+//! Read and write [bundle files](https://git-scm.com/docs/gitformat-bundle), which transport a pack along with the references
+//! it provides and the commits it requires, to move objects between repositories without a network connection.
+//!
+//! Bundles can also be offered by servers for download to bootstrap clones, which is described by a bundle [`List`].
+#![deny(rust_2018_idioms, missing_docs)]
+#![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+///
+pub mod decode;
+mod encode;
+///
+pub mod file;
+pub use file::File;
+///
+pub mod list;
+pub use list::List;
+
+/// The version of a bundle file.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Version {
+    /// The original format, which implies SHA1 and has no capabilities.
+    #[default]
+    V2,
+    /// The format that may declare capabilities like the `object-format` and `filter`.
+    V3,
+}
+
+impl Version {
+    /// Return the signature line that starts bundles of this version, without trailing newline.
+    pub fn signature(&self) -> &'static str {
+        match self {
+            Version::V2 => "# v2 git bundle",
+            Version::V3 => "# v3 git bundle",
+        }
+    }
+}
+
+/// A commit that has to exist in a repository before a bundle can be unpacked into it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Prerequisite {
+    /// The id of the required commit.
+    pub id: ObjectId,
+    /// Information about the commit, usually its subject line, which is for display only.
+    pub comment: Option<BString>,
+}
+
+/// A reference provided by a bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Ref {
+    /// The object the reference points to, which is contained in the bundle or one of its prerequisites.
+    pub id: ObjectId,
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+}
+
+/// The header of a bundle, which is followed by the pack.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Header {
+    /// The version of the bundle.
+    pub version: Version,
+    /// The kind of hash used for all object ids, as declared by the `object-format` capability.
+    pub object_hash: gix_hash::Kind,
+    /// The object filter the pack was created with, like `blob:none`, as declared by the `filter` capability.
+    ///
+    /// If set, the pack is incomplete similar to the one received by a partial clone.
+    pub filter: Option<BString>,
+    /// The commits which must exist in the receiving repository.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references provided by the bundle.
+    pub refs: Vec<Ref>,
+}
@@ -0,0 +1,131 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`List::from_key_value_pairs()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Bundle list version {version:?} is not supported, only version 1 is")]
+    UnsupportedVersion { version: BString },
+    #[error("Bundle list mode {mode:?} is unknown, it should be 'all' or 'any'")]
+    UnknownMode { mode: BString },
+    #[error("The bundle list has no mode")]
+    MissingMode,
+    #[error("Bundle {id:?} of the bundle list has no uri")]
+    MissingUri { id: BString },
+}
+
+/// How the bundles of a [`List`] relate to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// All bundles are needed to obtain the full set of objects, typically with later bundles building on earlier ones.
+    All,
+    /// Each bundle contains all objects, so any one of them suffices, which allows them to be mirrors of each other.
+    Any,
+}
+
+/// A hint as to how the bundles of a [`List`] should be ordered when downloading them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Heuristic {
+    /// Bundles have a [`creation_token`](Entry::creation_token), and those with lower tokens are to be unbundled first.
+    CreationToken,
+}
+
+/// A bundle mentioned in a [`List`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The identifier of the bundle which is unique within its list.
+    pub id: BString,
+    /// The location to download the bundle from, which may be relative to the location of the list.
+    ///
+    /// It may also point to another bundle list.
+    pub uri: BString,
+    /// A number that increases for bundles that were created later, if the list uses [`Heuristic::CreationToken`].
+    pub creation_token: Option<u64>,
+    /// The object filter the bundle was created with, like `blob:none`.
+    pub filter: Option<BString>,
+}
+
+/// A list of bundles, as advertised by a server via the `bundle-uri` command or downloaded from a URI.
+///
+/// The list is described by `bundle.*` keys of a git-configuration file, like `bundle.mode` or `bundle.<id>.uri`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct List {
+    /// How the bundles relate to each other.
+    pub mode: Mode,
+    /// The way bundles should be ordered, if known.
+    pub heuristic: Option<Heuristic>,
+    /// All bundles in the order in which they were first mentioned.
+    pub bundles: Vec<Entry>,
+}
+
+impl List {
+    /// Create a new instance from `pairs` of keys like `bundle.mode` or `bundle.<id>.uri` with their values.
+    ///
+    /// Like in git, section and key names are matched case-insensitively, and unknown keys as well as
+    /// unparsable creation tokens are ignored.
+    pub fn from_key_value_pairs<'a>(pairs: impl IntoIterator<Item = (&'a BStr, &'a BStr)>) -> Result<Self, Error> {
+        let mut mode = None;
+        let mut heuristic = None;
+        let mut bundles = Vec::<(BString, Option<BString>, Option<u64>, Option<BString>)>::new();
+        for (key, value) in pairs {
+            let Some(key) = key
+                .get(..7)
+                .filter(|prefix| prefix.eq_ignore_ascii_case(b"bundle."))
+                .map(|_| key[7..].as_bstr())
+            else {
+                continue;
+            };
+            match key.rfind_byte(b'.') {
+                None => {
+                    if key.eq_ignore_ascii_case(b"version") {
+                        if value != "1" {
+                            return Err(Error::UnsupportedVersion { version: value.into() });
+                        }
+                    } else if key.eq_ignore_ascii_case(b"mode") {
+                        mode = Some(match value.as_bytes() {
+                            b"all" => Mode::All,
+                            b"any" => Mode::Any,
+                            _ => return Err(Error::UnknownMode { mode: value.into() }),
+                        });
+                    } else if key.eq_ignore_ascii_case(b"heuristic") {
+                        heuristic = (value == "creationToken").then_some(Heuristic::CreationToken);
+                    }
+                }
+                Some(pos) => {
+                    let (id, name) = (key[..pos].as_bstr(), &key[pos + 1..]);
+                    let idx = match bundles.iter().position(|b| b.0 == id) {
+                        Some(idx) => idx,
+                        None => {
+                            bundles.push((id.into(), None, None, None));
+                            bundles.len() - 1
+                        }
+                    };
+                    let bundle = &mut bundles[idx];
+                    if name.eq_ignore_ascii_case(b"uri") {
+                        bundle.1 = Some(value.into());
+                    } else if name.eq_ignore_ascii_case(b"creationToken") {
+                        bundle.2 = value.to_str().ok().and_then(|token| token.parse().ok());
+                    } else if name.eq_ignore_ascii_case(b"filter") {
+                        bundle.3 = Some(value.into());
+                    }
+                }
+            }
+        }
+        Ok(List {
+            mode: mode.ok_or(Error::MissingMode)?,
+            heuristic,
+            bundles: bundles
+                .into_iter()
+                .map(|(id, uri, creation_token, filter)| match uri {
+                    Some(uri) => Ok(Entry {
+                        id,
+                        uri,
+                        creation_token,
+                        filter,
+                    }),
+                    None => Err(Error::MissingUri { id }),
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
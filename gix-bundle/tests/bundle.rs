@@ -0,0 +1,248 @@
+use std::{io::Read, path::PathBuf};
+
+use bstr::ByteSlice;
+use gix_bundle::{decode, File, Header, Prerequisite, Ref, Version};
+use gix_hash::ObjectId;
+
+pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error + 'static>>;
+
+fn fixture(name: &str) -> Result<PathBuf> {
+    Ok(gix_testtools::scripted_fixture_read_only("make_bundles.sh")?.join(name))
+}
+
+fn rev_parse(spec: &str) -> Result<ObjectId> {
+    let out = std::process::Command::new("git")
+        .args(["rev-parse", spec])
+        .current_dir(fixture("repo")?)
+        .output()?;
+    assert!(out.status.success());
+    Ok(ObjectId::from_hex(out.stdout.trim())?)
+}
+
+/// Split the bundle fixture `name` into its header and its pack.
+fn header_and_pack(name: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+    let data = std::fs::read(fixture(name)?)?;
+    let pos = data
+        .windows(6)
+        .position(|w| w == b"\n\nPACK")
+        .expect("pack follows header")
+        + 2;
+    let (header, pack) = data.split_at(pos);
+    Ok((header.to_vec(), pack.to_vec()))
+}
+
+#[test]
+fn v2_with_multiple_refs() -> Result {
+    let (header_bytes, _) = header_and_pack("v2.bundle")?;
+    let header = Header::from_read(&mut header_bytes.as_slice())?;
+    assert_eq!(
+        header,
+        Header {
+            version: Version::V2,
+            object_hash: gix_hash::Kind::Sha1,
+            filter: None,
+            prerequisites: Vec::new(),
+            refs: vec![
+                Ref {
+                    id: rev_parse("main")?,
+                    name: "refs/heads/main".into()
+                },
+                Ref {
+                    id: rev_parse("v1")?,
+                    name: "refs/tags/v1".into()
+                }
+            ]
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn v3_declares_object_format() -> Result {
+    let (header_bytes, _) = header_and_pack("v3.bundle")?;
+    let header = Header::from_read(&mut header_bytes.as_slice())?;
+    assert_eq!(header.version, Version::V3);
+    assert_eq!(header.object_hash, gix_hash::Kind::Sha1);
+    assert_eq!(header.filter, None);
+    assert_eq!(header.refs.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn incremental_has_prerequisites() -> Result {
+    let (header_bytes, _) = header_and_pack("incremental.bundle")?;
+    let header = Header::from_read(&mut header_bytes.as_slice())?;
+    assert_eq!(
+        header.prerequisites,
+        [Prerequisite {
+            id: rev_parse("main~1")?,
+            comment: Some("c2".into())
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn write_to_round_trips_headers_written_by_git() -> Result {
+    for name in ["v2.bundle", "v3.bundle", "incremental.bundle"] {
+        let (header_bytes, _) = header_and_pack(name)?;
+        let header = Header::from_read(&mut header_bytes.as_slice())?;
+        let mut out = Vec::new();
+        header.write_to(&mut out)?;
+        assert_eq!(out, header_bytes, "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn filter_capability() -> Result {
+    let input =
+        b"# v3 git bundle\n@object-format=sha1\n@filter=blob:none\n4626fe224c38056c1b92247f4eba1da9be7e63d2 HEAD\n\n";
+    let header = Header::from_read(&mut input.as_slice())?;
+    assert_eq!(header.filter.as_ref().map(AsRef::as_ref), Some(b"blob:none".as_slice()));
+    assert_eq!(header.refs[0].name, "HEAD");
+
+    let mut out = Vec::new();
+    header.write_to(&mut out)?;
+    assert_eq!(out, input);
+    Ok(())
+}
+
+#[test]
+fn invalid_headers() {
+    for (input, expected) in [
+        (
+            &b"# v4 git bundle\n\n"[..],
+            "Not a bundle file, signature was \"# v4 git bundle\"",
+        ),
+        (
+            b"# v2 git bundle\n@object-format=sha1\n\n",
+            "Capabilities like \"@object-format=sha1\" are only allowed in bundles of version 3",
+        ),
+        (b"# v3 git bundle\n@unknown\n\n", "Unknown capability \"@unknown\""),
+        (
+            b"# v3 git bundle\n@object-format=sha256\n\n",
+            "Unsupported object format \"sha256\"",
+        ),
+        (
+            b"# v2 git bundle\n4626fe224c38056c1b92247f4eba1da9be7e63d2\n\n",
+            "Reference line \"4626fe224c38056c1b92247f4eba1da9be7e63d2\" lacks a name",
+        ),
+        (
+            b"# v2 git bundle\n-4626fe22 c2\n\n",
+            "Could not decode object id in line \"-4626fe22 c2\"",
+        ),
+        (
+            b"# v2 git bundle\n4626fe224c38056c1b92247f4eba1da9be7e63d2 HEAD\n",
+            "The bundle header wasn't terminated by an empty line",
+        ),
+    ] {
+        let err: decode::Error = Header::from_read(&mut &*input).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+}
+
+#[test]
+fn file_provides_header_and_pack() -> Result {
+    let (_, expected_pack) = header_and_pack("incremental.bundle")?;
+    let file = File::at(fixture("incremental.bundle")?)?;
+    assert_eq!(file.header.refs.len(), 1);
+    assert!(file.path().ends_with("incremental.bundle"));
+
+    let mut pack = Vec::new();
+    file.into_pack().read_to_end(&mut pack)?;
+    assert_eq!(pack, expected_pack);
+    Ok(())
+}
+
+mod list {
+    use bstr::{BStr, ByteSlice};
+    use gix_bundle::{
+        list::{Entry, Error, Heuristic, Mode},
+        List,
+    };
+
+    fn parse(pairs: &[(&str, &str)]) -> Result<List, Error> {
+        List::from_key_value_pairs(
+            pairs
+                .iter()
+                .map(|(k, v)| -> (&BStr, &BStr) { (k.as_bytes().as_bstr(), v.as_bytes().as_bstr()) }),
+        )
+    }
+
+    #[test]
+    fn all_keys_in_order_of_first_mention() -> crate::Result {
+        let list = parse(&[
+            ("bundle.version", "1"),
+            ("bundle.mode", "all"),
+            ("bundle.heuristic", "creationToken"),
+            ("bundle.base.uri", "https://cdn.example.com/base.bundle"),
+            ("bundle.incremental.creationToken", "2"),
+            ("Bundle.base.CreationToken", "1"),
+            ("bundle.incremental.URI", "incremental.bundle"),
+            ("bundle.incremental.filter", "blob:none"),
+            ("bundle.incremental.unknown", "ignored"),
+            ("other.key", "ignored"),
+        ])?;
+        assert_eq!(
+            list,
+            List {
+                mode: Mode::All,
+                heuristic: Some(Heuristic::CreationToken),
+                bundles: vec![
+                    Entry {
+                        id: "base".into(),
+                        uri: "https://cdn.example.com/base.bundle".into(),
+                        creation_token: Some(1),
+                        filter: None,
+                    },
+                    Entry {
+                        id: "incremental".into(),
+                        uri: "incremental.bundle".into(),
+                        creation_token: Some(2),
+                        filter: Some("blob:none".into()),
+                    }
+                ]
+            },
+            "section and key names are case-insensitive"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ids_may_contain_dots_and_unknown_heuristics_are_ignored() -> crate::Result {
+        let list = parse(&[
+            ("bundle.mode", "any"),
+            ("bundle.heuristic", "unknown"),
+            ("bundle.mirror.eu.uri", "eu.bundle"),
+            ("bundle.mirror.eu.creationToken", "not-a-number"),
+        ])?;
+        assert_eq!(list.mode, Mode::Any);
+        assert_eq!(list.heuristic, None);
+        assert_eq!(list.bundles.len(), 1);
+        assert_eq!(list.bundles[0].id, "mirror.eu");
+        assert_eq!(list.bundles[0].creation_token, None, "invalid tokens are ignored");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        for (pairs, expected) in [
+            (
+                &[("bundle.version", "2"), ("bundle.mode", "all")][..],
+                "Bundle list version \"2\" is not supported, only version 1 is",
+            ),
+            (
+                &[("bundle.mode", "some")],
+                "Bundle list mode \"some\" is unknown, it should be 'all' or 'any'",
+            ),
+            (&[("bundle.b.uri", "b.bundle")], "The bundle list has no mode"),
+            (
+                &[("bundle.mode", "all"), ("bundle.b.creationToken", "1")],
+                "Bundle \"b\" of the bundle list has no uri",
+            ),
+        ] {
+            assert_eq!(parse(pairs).unwrap_err().to_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  echo 2 > file && git commit -q -am c2
+  git tag -a -m "annotated" v1
+  echo 3 > file && git commit -q -am c3
+)
+
+git -C repo bundle create -q ../v2.bundle main v1
+git -C repo bundle create -q --version=3 ../v3.bundle main
+git -C repo bundle create -q ../incremental.bundle main~1..main
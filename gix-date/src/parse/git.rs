@@ -0,0 +1,811 @@
+//! A port of the date parsing in `git`'s `date.c`, that is `parse_date_basic()` for dates with a time and `approxidate()`
+//! for everything else, from `Dec 6 1992` to `noon yesterday` or `3.days.ago`.
+//!
+//! Fields of [`Tm`] use `-1` to indicate they are unset, just like `git` does, as the algorithms depend on it.
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+use crate::{time::Sign, SecondsSinceUnixEpoch, Time};
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sundays",
+    "Mondays",
+    "Tuesdays",
+    "Wednesdays",
+    "Thursdays",
+    "Fridays",
+    "Saturdays",
+];
+
+/// Names of timezones along with their offset in hours, and whether they are daylight-saving time.
+///
+/// Just like in `git`, an hour is added to the offset of the latter.
+const TIMEZONE_NAMES: &[(&str, i64, bool)] = &[
+    ("IDLW", -12, false), // International Date Line West
+    ("NT", -11, false),   // Nome
+    ("CAT", -10, false),  // Central Alaska
+    ("HST", -10, false),  // Hawaii Standard
+    ("HDT", -10, true),   // Hawaii Daylight
+    ("YST", -9, false),   // Yukon Standard
+    ("YDT", -9, true),    // Yukon Daylight
+    ("PST", -8, false),   // Pacific Standard
+    ("PDT", -8, true),    // Pacific Daylight
+    ("MST", -7, false),   // Mountain Standard
+    ("MDT", -7, true),    // Mountain Daylight
+    ("CST", -6, false),   // Central Standard
+    ("CDT", -6, true),    // Central Daylight
+    ("EST", -5, false),   // Eastern Standard
+    ("EDT", -5, true),    // Eastern Daylight
+    ("AST", -3, false),   // Atlantic Standard
+    ("ADT", -3, true),    // Atlantic Daylight
+    ("WAT", -1, false),   // West Africa
+    ("GMT", 0, false),    // Greenwich Mean
+    ("UTC", 0, false),    // Universal (Coordinated)
+    ("Z", 0, false),      // Zulu, alias for UTC
+    ("WET", 0, false),    // Western European
+    ("BST", 0, true),     // British Summer
+    ("CET", 1, false),    // Central European
+    ("MET", 1, false),    // Middle European
+    ("MEWT", 1, false),   // Middle European Winter
+    ("MEST", 1, true),    // Middle European Summer
+    ("CEST", 1, true),    // Central European Summer
+    ("MESZ", 1, true),    // Middle European Summer
+    ("FWT", 1, false),    // French Winter
+    ("FST", 1, true),     // French Summer
+    ("EET", 2, false),    // Eastern Europe, USSR Zone 1
+    ("EEST", 2, true),    // Eastern European Daylight
+    ("WAST", 7, false),   // West Australian Standard
+    ("WADT", 7, true),    // West Australian Daylight
+    ("CCT", 8, false),    // China Coast, USSR Zone 7
+    ("JST", 9, false),    // Japan Standard, USSR Zone 8
+    ("EAST", 10, false),  // Eastern Australian Standard
+    ("EADT", 10, true),   // Eastern Australian Daylight
+    ("GST", 10, false),   // Guam Standard, USSR Zone 9
+    ("NZT", 12, false),   // New Zealand
+    ("NZST", 12, false),  // New Zealand Standard
+    ("NZDT", 12, true),   // New Zealand Daylight
+    ("IDLE", 12, false),  // International Date Line East
+];
+
+const NUMBER_NAMES: [&str; 11] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+/// Units of relative dates along with their length in seconds.
+const TYPE_LENGTHS: [(&str, i64); 5] = [
+    ("seconds", 1),
+    ("minutes", 60),
+    ("hours", 60 * 60),
+    ("days", 24 * 60 * 60),
+    ("weeks", 7 * 24 * 60 * 60),
+];
+
+type Special = fn(&mut Tm, &Tm, &mut i64) -> Option<()>;
+
+const SPECIALS: [(&str, Special); 8] = [
+    ("yesterday", date_yesterday),
+    ("noon", date_noon),
+    ("midnight", date_midnight),
+    ("tea", date_tea),
+    ("PM", date_pm),
+    ("AM", date_am),
+    ("never", date_never),
+    ("now", date_now),
+];
+
+/// A broken-down time like `struct tm`, with the year counted from 1900 and the month from 0.
+#[derive(Debug, Clone, Copy)]
+struct Tm {
+    year: i64,
+    mon: i64,
+    mday: i64,
+    hour: i64,
+    min: i64,
+    sec: i64,
+    wday: i64,
+}
+
+impl Tm {
+    fn unset() -> Self {
+        Tm {
+            year: -1,
+            mon: -1,
+            mday: -1,
+            hour: -1,
+            min: -1,
+            sec: -1,
+            wday: 0,
+        }
+    }
+
+    /// Return `true` if none of the date or time fields were filled in yet.
+    fn is_unset(&self) -> bool {
+        [self.year, self.mon, self.mday, self.hour, self.min, self.sec]
+            .iter()
+            .all(|field| *field < 0)
+    }
+
+    fn is_date_known(&self) -> bool {
+        self.year != -1 && self.mon != -1 && self.mday != -1
+    }
+
+    fn from_datetime(time: OffsetDateTime) -> Self {
+        Tm {
+            year: i64::from(time.year()) - 1900,
+            mon: i64::from(u8::from(time.month())) - 1,
+            mday: time.day().into(),
+            hour: time.hour().into(),
+            min: time.minute().into(),
+            sec: time.second().into(),
+            wday: time.weekday().number_days_from_sunday().into(),
+        }
+    }
+}
+
+/// Parse `input` the way `parse_date_basic()` does, which succeeds if at least a full date and time could be found
+/// in the years 1970 to 2099. `now` is used to reject dates in the far future.
+pub(crate) fn basic(input: &[u8], now: SecondsSinceUnixEpoch) -> Option<Time> {
+    if let Some(time) = input.strip_prefix(b"@").and_then(match_object_header_date) {
+        return Some(time);
+    }
+    let mut tm = Tm::unset();
+    let mut offset = None;
+    let mut tm_gmt = false;
+    let mut pos = 0;
+    while let Some(&c) = input.get(pos) {
+        if c == b'\n' {
+            break;
+        }
+        let date = &input[pos..];
+        let consumed = if c.is_ascii_alphabetic() {
+            match_alpha(date, &mut tm, &mut offset)
+        } else if c.is_ascii_digit() {
+            match_digit(date, &mut tm, &mut offset, &mut tm_gmt, now)
+        } else if (c == b'-' || c == b'+') && is_digit_at(date, 1) {
+            match_tz(date, &mut offset)
+        } else {
+            0
+        };
+        pos += consumed.max(1);
+    }
+
+    let mut seconds = tm_to_time_t(&tm)?;
+    let (minutes, sign) = offset.unwrap_or_else(|| {
+        let minutes = local_offset_at(seconds) / 60;
+        (minutes, sign_of(minutes))
+    });
+    if !tm_gmt {
+        seconds -= minutes * 60;
+    }
+    Some(Time {
+        seconds,
+        offset: (minutes * 60).try_into().ok()?,
+        sign,
+    })
+}
+
+/// Parse `input` the way `approxidate()` does, relative to `now`, and return the seconds since epoch, or `None` if
+/// nothing in `input` could be understood. `Some(None)` is returned if the date can't be represented.
+pub(crate) fn approximate(input: &[u8], now: SecondsSinceUnixEpoch) -> Option<Option<SecondsSinceUnixEpoch>> {
+    let mut touched = false;
+    let res = approximate_inner(input, now, &mut touched);
+    touched.then_some(res)
+}
+
+fn approximate_inner(input: &[u8], now: SecondsSinceUnixEpoch, touched: &mut bool) -> Option<SecondsSinceUnixEpoch> {
+    let now_tm = localtime(now)?;
+    let mut tm = now_tm;
+    tm.year = -1;
+    tm.mon = -1;
+    tm.mday = -1;
+    let mut number = 0;
+
+    let mut pos = 0;
+    while let Some(&c) = input.get(pos) {
+        let date = &input[pos..];
+        pos += if c.is_ascii_digit() {
+            pending_number(&mut tm, &mut number);
+            *touched = true;
+            approxidate_digit(date, &mut tm, &mut number, now)
+        } else if c.is_ascii_alphabetic() {
+            approxidate_alpha(date, &mut tm, &now_tm, &mut number, touched)?
+        } else {
+            1
+        };
+    }
+    pending_number(&mut tm, &mut number);
+    update_tm(&mut tm, &now_tm, 0)
+}
+
+/// Parse `<seconds> <+|-><hhmm>` as found in object headers, following `@`.
+fn match_object_header_date(date: &[u8]) -> Option<Time> {
+    if !is_digit_at(date, 0) {
+        return None;
+    }
+    let (seconds, end) = parse_number(date, 0);
+    if date.get(end) != Some(&b' ') || seconds == i64::MAX {
+        return None;
+    }
+    let sign = match date.get(end + 1)? {
+        b'+' => Sign::Plus,
+        b'-' => Sign::Minus,
+        _ => return None,
+    };
+    let start = end + 2;
+    let (hhmm, end) = parse_number(date, start);
+    if !matches!(date.get(end), None | Some(b'\n')) || end != start + 4 {
+        return None;
+    }
+    let mut offset = (hhmm / 100) * 60 * 60 + (hhmm % 100) * 60;
+    if sign == Sign::Minus {
+        offset = -offset;
+    }
+    Some(Time {
+        seconds,
+        offset: offset.try_into().ok()?,
+        sign,
+    })
+}
+
+/// Parse a month, weekday or timezone name.
+fn match_alpha(date: &[u8], tm: &mut Tm, offset: &mut Option<(i64, Sign)>) -> usize {
+    for (idx, name) in MONTH_NAMES.iter().enumerate() {
+        let matched = match_string(date, name);
+        if matched >= 3 {
+            tm.mon = idx as i64;
+            return matched;
+        }
+    }
+    for (idx, name) in WEEKDAY_NAMES.iter().enumerate() {
+        let matched = match_string(date, name);
+        if matched >= 3 {
+            tm.wday = idx as i64;
+            return matched;
+        }
+    }
+    for &(name, hours, dst) in TIMEZONE_NAMES {
+        let matched = match_string(date, name);
+        if matched >= 3 || matched == name.len() {
+            // This is bogus, but we like summer, says `git`.
+            let minutes = 60 * (hours + i64::from(dst));
+            // Only use the offset of the name if there is nothing better.
+            offset.get_or_insert((minutes, sign_of(minutes)));
+            return matched;
+        }
+    }
+    if match_string(date, "PM") == 2 {
+        tm.hour = (tm.hour % 12) + 12;
+        return 2;
+    }
+    if match_string(date, "AM") == 2 {
+        tm.hour %= 12;
+        return 2;
+    }
+    // ISO-8601 allows yyyymmDD'T'HHMMSS, with less precision
+    if date[0] == b'T' && is_digit_at(date, 1) && tm.hour == -1 {
+        tm.min = 0;
+        tm.sec = 0;
+        return 1;
+    }
+    1 + date[1..].iter().take_while(|b| b.is_ascii_alphabetic()).count()
+}
+
+/// We've seen a digit. Time? Date? Instance number?
+fn match_digit(
+    date: &[u8],
+    tm: &mut Tm,
+    offset: &mut Option<(i64, Sign)>,
+    tm_gmt: &mut bool,
+    now: SecondsSinceUnixEpoch,
+) -> usize {
+    let (num, end) = parse_number(date, 0);
+
+    // Seconds since 1970? We trigger on that for any numbers with more than 8 digits,
+    // as we don't want to rule out numbers like 20070606 as a YYYYMMDD date.
+    if num >= 100_000_000 && tm.is_unset() {
+        if let Some(time) = gmtime(num) {
+            *tm = time;
+            *tm_gmt = true;
+            return end;
+        }
+    }
+
+    if let Some(&separator @ (b':' | b'.' | b'/' | b'-')) = date.get(end) {
+        if is_digit_at(date, end + 1) {
+            let matched = match_multi_number(num, separator, date, end, tm, now);
+            if matched != 0 {
+                return matched;
+            }
+        }
+    }
+
+    // None of the special formats? Try to guess what the number meant by the number of its digits.
+    let n = end;
+    // 8 digits, compact style of ISO-8601's date: YYYYmmDD
+    // 6 digits, compact style of ISO-8601's time: HHMMSS
+    if n == 8 || n == 6 {
+        let (num1, num2, num3) = (num / 10000, (num % 10000) / 100, num % 100);
+        if n == 8 {
+            set_date(num1, num2, num3, None, now, tm);
+        } else if set_time(num1, num2, num3, tm) && date.get(end) == Some(&b'.') && is_digit_at(date, end + 1) {
+            return parse_number(date, end + 1).1;
+        }
+        return end;
+    }
+
+    // Four-digit year or a timezone?
+    if n == 4 {
+        if num <= 1400 && offset.is_none() {
+            *offset = Some(((num / 100) * 60 + num % 100, Sign::Plus));
+        } else if num > 1900 && num < 2100 {
+            tm.year = num - 1900;
+        }
+        return n;
+    }
+
+    // Ignore lots of numerals. Days or months must be one or two digits.
+    if n > 2 {
+        return n;
+    }
+
+    // Day-of-month takes precedence over month or year numbers in the 1-12 range,
+    // so `01 Apr 05` parses as April 1st, 2005.
+    if num > 0 && num < 32 && tm.mday < 0 {
+        tm.mday = num;
+        return n;
+    }
+
+    // Two-digit year?
+    if n == 2 && tm.year < 0 {
+        if num < 10 && tm.mday >= 0 {
+            tm.year = num + 100;
+            return n;
+        }
+        if num >= 70 {
+            tm.year = num;
+            return n;
+        }
+    }
+
+    if num > 0 && num < 13 && tm.mon < 0 {
+        tm.mon = num - 1;
+    }
+    n
+}
+
+/// Parse `num[-.:/]num[same]num` with `num` already parsed and `end` pointing to the first separator `c` in `date`.
+fn match_multi_number(num: i64, c: u8, date: &[u8], end: usize, tm: &mut Tm, now: SecondsSinceUnixEpoch) -> usize {
+    let (num2, mut end) = parse_number(date, end + 1);
+    let mut num3 = -1;
+    if date.get(end) == Some(&c) && is_digit_at(date, end + 1) {
+        (num3, end) = parse_number(date, end + 1);
+    }
+
+    match c {
+        b':' => {
+            if !set_time(num, num2, num3.max(0), tm) {
+                return 0;
+            }
+            // A fractional second after %H:%M:%S is discarded if the date is known.
+            if date.get(end) == Some(&b'.') && is_digit_at(date, end + 1) && tm.is_date_known() {
+                end = parse_number(date, end + 1).1;
+            }
+        }
+        b'-' | b'/' | b'.' => {
+            let refuse_future = gmtime(now);
+            let refuse_future = refuse_future.as_ref();
+            let matched = (num > 70 && (set_date(num, num2, num3, None, now, tm) || set_date(num, num3, num2, None, now, tm)))
+                // Eastern Europeans use dd.mm.yy[yy], so mm/dd/yy[yy] takes precedence only if the separator isn't '.'.
+                || (c != b'.' && set_date(num3, num, num2, refuse_future, now, tm))
+                // European dd.mm.yy[yy] or funny US dd/mm/yy[yy]
+                || set_date(num3, num2, num, refuse_future, now, tm)
+                // Funny European mm.dd.yy
+                || (c == b'.' && set_date(num3, num, num2, refuse_future, now, tm));
+            if !matched {
+                return 0;
+            }
+        }
+        _ => {}
+    }
+    end
+}
+
+/// Parse an offset like `+hhmm`, `-hh` or `+hh:mm`.
+fn match_tz(date: &[u8], offset: &mut Option<(i64, Sign)>) -> usize {
+    let (mut hour, mut end) = parse_number(date, 1);
+    let mut min = 0;
+    match end - 1 {
+        4 => {
+            min = hour % 100;
+            hour /= 100;
+        }
+        2 => {
+            if date.get(end) == Some(&b':') {
+                (min, end) = parse_number(date, end + 1);
+                if end - 1 != 5 {
+                    min = 99;
+                }
+            }
+        }
+        _ => min = 99,
+    }
+
+    // Don't accept any random crap, but allow for offsets beyond 12 hours which exist in some places.
+    if min < 60 && hour < 24 {
+        let minutes = hour * 60 + min;
+        *offset = Some(if date[0] == b'-' {
+            (-minutes, Sign::Minus)
+        } else {
+            (minutes, Sign::Plus)
+        });
+    }
+    end
+}
+
+/// Set the date of `tm` if the values are plausible and return `true`, or `false` otherwise.
+///
+/// If `now_tm` is set, dates more than ten days after `now` are rejected and `year` may be `-1` to use the year of `now_tm`.
+/// Otherwise, `tm` is changed even if the date wasn't plausible, as in `git`.
+fn set_date(year: i64, month: i64, day: i64, now_tm: Option<&Tm>, now: SecondsSinceUnixEpoch, tm: &mut Tm) -> bool {
+    if !(1..13).contains(&month) || !(1..32).contains(&day) {
+        return false;
+    }
+    let mut check = *tm;
+    check.mon = month - 1;
+    check.mday = day;
+    let year_is_valid = if year == -1 {
+        match now_tm {
+            Some(now_tm) => {
+                check.year = now_tm.year;
+                true
+            }
+            None => false,
+        }
+    } else if (1970..2100).contains(&year) {
+        check.year = year - 1900;
+        true
+    } else if year > 70 && year < 100 {
+        check.year = year;
+        true
+    } else if year < 38 {
+        check.year = year + 100;
+        true
+    } else {
+        false
+    };
+    if now_tm.is_none() {
+        *tm = check;
+        return year_is_valid;
+    }
+    if !year_is_valid {
+        return false;
+    }
+
+    // Be it commit time or author time, it doesn't make sense to specify a timestamp way into the future.
+    if tm_to_time_t(&check).map_or(false, |specified| now + 10 * 24 * 60 * 60 < specified) {
+        return false;
+    }
+    tm.mon = check.mon;
+    tm.mday = check.mday;
+    if year != -1 {
+        tm.year = check.year;
+    }
+    true
+}
+
+/// Set the time of `tm` and return `true` if the values are valid, accepting a 61st second due to leap-seconds.
+fn set_time(hour: i64, minute: i64, second: i64, tm: &mut Tm) -> bool {
+    if (0..=24).contains(&hour) && (0..60).contains(&minute) && (0..=60).contains(&second) {
+        tm.hour = hour;
+        tm.min = minute;
+        tm.sec = second;
+        true
+    } else {
+        false
+    }
+}
+
+/// Convert `tm` in UTC to seconds since epoch without consulting the local timezone, which only works from 1970 to 2099.
+fn tm_to_time_t(tm: &Tm) -> Option<SecondsSinceUnixEpoch> {
+    const MONTH_DAYS: [i64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+    let year = tm.year - 70;
+    let month = tm.mon;
+    let mut day = tm.mday;
+
+    if !(0..=129).contains(&year) || !(0..=11).contains(&month) {
+        return None;
+    }
+    if month < 2 || (year + 2) % 4 != 0 {
+        day -= 1;
+    }
+    if tm.hour < 0 || tm.min < 0 || tm.sec < 0 {
+        return None;
+    }
+    Some(
+        (year * 365 + (year + 1) / 4 + MONTH_DAYS[month as usize] + day) * 24 * 60 * 60
+            + tm.min * 60
+            + tm.hour * 60 * 60
+            + tm.sec,
+    )
+}
+
+fn approxidate_digit(date: &[u8], tm: &mut Tm, num: &mut i64, now: SecondsSinceUnixEpoch) -> usize {
+    let (number, end) = parse_number(date, 0);
+    if let Some(&separator @ (b':' | b'.' | b'/' | b'-')) = date.get(end) {
+        if is_digit_at(date, end + 1) {
+            let matched = match_multi_number(number, separator, date, end, tm, now);
+            if matched != 0 {
+                return matched;
+            }
+        }
+    }
+    // Accept zero-padding only for small numbers ("Dec 02", never "Dec 0002")
+    if date[0] != b'0' || end <= 2 {
+        *num = number;
+    }
+    end
+}
+
+/// Return the length of the word at the beginning of `date` after applying it to `tm`, or `None` if the date went out of range.
+fn approxidate_alpha(date: &[u8], tm: &mut Tm, now: &Tm, num: &mut i64, touched: &mut bool) -> Option<usize> {
+    let end = 1 + date[1..].iter().take_while(|b| b.is_ascii_alphabetic()).count();
+
+    for (idx, name) in MONTH_NAMES.iter().enumerate() {
+        if match_string(date, name) >= 3 {
+            tm.mon = idx as i64;
+            *touched = true;
+            return Some(end);
+        }
+    }
+
+    for (name, special) in SPECIALS {
+        if match_string(date, name) == name.len() {
+            special(tm, now, num)?;
+            *touched = true;
+            return Some(end);
+        }
+    }
+
+    if *num == 0 {
+        for (idx, name) in NUMBER_NAMES.iter().enumerate().skip(1) {
+            if match_string(date, name) == name.len() {
+                *num = idx as i64;
+                *touched = true;
+                return Some(end);
+            }
+        }
+        if match_string(date, "last") == 4 {
+            *num = 1;
+            *touched = true;
+        }
+        return Some(end);
+    }
+
+    for (name, length) in TYPE_LENGTHS {
+        if match_string(date, name) >= name.len() - 1 {
+            update_tm(tm, now, length.checked_mul(*num)?)?;
+            *num = 0;
+            *touched = true;
+            return Some(end);
+        }
+    }
+
+    for (idx, name) in WEEKDAY_NAMES.iter().enumerate() {
+        if match_string(date, name) >= 3 {
+            let mut n = *num - 1;
+            *num = 0;
+            let mut diff = tm.wday - idx as i64;
+            if diff <= 0 {
+                n += 1;
+            }
+            diff = diff.checked_add(n.checked_mul(7)?)?;
+            update_tm(tm, now, diff.checked_mul(24 * 60 * 60)?)?;
+            *touched = true;
+            return Some(end);
+        }
+    }
+
+    if match_string(date, "months") >= 5 {
+        update_tm(tm, now, 0)?; // fill in date fields if needed
+        let n = tm.mon.checked_sub(*num)?;
+        tm.year = tm.year.checked_add(n.div_euclid(12))?;
+        tm.mon = n.rem_euclid(12);
+        *num = 0;
+        *touched = true;
+        return Some(end);
+    }
+
+    if match_string(date, "years") >= 4 {
+        update_tm(tm, now, 0)?; // fill in date fields if needed
+        tm.year = tm.year.checked_sub(*num)?;
+        *num = 0;
+        *touched = true;
+        return Some(end);
+    }
+
+    Some(end)
+}
+
+/// Apply a pending number at the end, or when a new one is seen. It's assumed to be a day of the month, as in `Dec 6, 1992`.
+fn pending_number(tm: &mut Tm, num: &mut i64) {
+    let number = std::mem::take(num);
+    if number == 0 {
+        return;
+    }
+    if tm.mday < 0 && number < 32 {
+        tm.mday = number;
+    } else if tm.mon < 0 && number < 13 {
+        tm.mon = number - 1;
+    } else if tm.year < 0 {
+        if number > 1969 && number < 2100 {
+            tm.year = number - 1900;
+        } else if number > 69 && number < 100 {
+            tm.year = number;
+        } else if number < 38 {
+            tm.year = 100 + number;
+        }
+    }
+}
+
+/// Fill in unset date fields of `tm` from `now`, and set it to `sec` seconds before the time it then represents,
+/// which is also returned.
+fn update_tm(tm: &mut Tm, now: &Tm, sec: i64) -> Option<SecondsSinceUnixEpoch> {
+    if tm.mday < 0 {
+        tm.mday = now.mday;
+    }
+    if tm.mon < 0 {
+        tm.mon = now.mon;
+    }
+    if tm.year < 0 {
+        tm.year = now.year;
+        if tm.mon > now.mon {
+            tm.year -= 1;
+        }
+    }
+    let seconds = mktime(tm)?.checked_sub(sec)?;
+    *tm = localtime(seconds)?;
+    Some(seconds)
+}
+
+fn date_now(tm: &mut Tm, now: &Tm, num: &mut i64) -> Option<()> {
+    *num = 0;
+    update_tm(tm, now, 0).map(|_| ())
+}
+
+fn date_yesterday(tm: &mut Tm, now: &Tm, num: &mut i64) -> Option<()> {
+    *num = 0;
+    update_tm(tm, now, 24 * 60 * 60).map(|_| ())
+}
+
+fn date_time(tm: &mut Tm, now: &Tm, hour: i64) -> Option<()> {
+    if tm.hour < hour {
+        update_tm(tm, now, 24 * 60 * 60)?;
+    }
+    tm.hour = hour;
+    tm.min = 0;
+    tm.sec = 0;
+    Some(())
+}
+
+fn date_midnight(tm: &mut Tm, now: &Tm, num: &mut i64) -> Option<()> {
+    pending_number(tm, num);
+    date_time(tm, now, 0)
+}
+
+fn date_noon(tm: &mut Tm, now: &Tm, num: &mut i64) -> Option<()> {
+    pending_number(tm, num);
+    date_time(tm, now, 12)
+}
+
+fn date_tea(tm: &mut Tm, now: &Tm, num: &mut i64) -> Option<()> {
+    pending_number(tm, num);
+    date_time(tm, now, 17)
+}
+
+fn date_pm(tm: &mut Tm, _now: &Tm, num: &mut i64) -> Option<()> {
+    let hour = hour_of_pending_number(tm, num);
+    tm.hour = (hour % 12) + 12;
+    Some(())
+}
+
+fn date_am(tm: &mut Tm, _now: &Tm, num: &mut i64) -> Option<()> {
+    let hour = hour_of_pending_number(tm, num);
+    tm.hour = hour % 12;
+    Some(())
+}
+
+fn hour_of_pending_number(tm: &mut Tm, num: &mut i64) -> i64 {
+    match std::mem::take(num) {
+        0 => tm.hour,
+        hour => {
+            tm.min = 0;
+            tm.sec = 0;
+            hour
+        }
+    }
+}
+
+fn date_never(tm: &mut Tm, _now: &Tm, num: &mut i64) -> Option<()> {
+    *tm = localtime(0)?;
+    *num = 0;
+    Some(())
+}
+
+/// Return the length of the case-insensitive match of `name` at the beginning of `date`, or `0` if `date` continues
+/// with alphanumeric characters that don't match.
+fn match_string(date: &[u8], name: &str) -> usize {
+    let name = name.as_bytes();
+    for (idx, c) in date.iter().enumerate() {
+        if name.get(idx).map_or(false, |n| n.eq_ignore_ascii_case(c)) {
+            continue;
+        }
+        return if c.is_ascii_alphanumeric() { 0 } else { idx };
+    }
+    date.len()
+}
+
+fn sign_of(minutes: i64) -> Sign {
+    if minutes < 0 {
+        Sign::Minus
+    } else {
+        Sign::Plus
+    }
+}
+
+fn is_digit_at(date: &[u8], idx: usize) -> bool {
+    date.get(idx).map_or(false, u8::is_ascii_digit)
+}
+
+/// Parse the decimal number starting at `start` in `date`, saturating on overflow, and return it along with the
+/// position past its last digit.
+fn parse_number(date: &[u8], start: usize) -> (i64, usize) {
+    let digits = date.get(start..).unwrap_or_default();
+    let len = digits.iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = digits[..len].iter().fold(0_i64, |acc, b| {
+        acc.saturating_mul(10).saturating_add(i64::from(b - b'0'))
+    });
+    (number, start + len)
+}
+
+/// Return the offset of the local timezone at `seconds` in seconds, or `0` if it can't be determined.
+fn local_offset_at(seconds: SecondsSinceUnixEpoch) -> i64 {
+    OffsetDateTime::from_unix_timestamp(seconds)
+        .ok()
+        .and_then(|time| UtcOffset::local_offset_at(time).ok())
+        .map_or(0, |offset| offset.whole_seconds().into())
+}
+
+fn gmtime(seconds: SecondsSinceUnixEpoch) -> Option<Tm> {
+    OffsetDateTime::from_unix_timestamp(seconds).ok().map(Tm::from_datetime)
+}
+
+fn localtime(seconds: SecondsSinceUnixEpoch) -> Option<Tm> {
+    gmtime(seconds.checked_add(local_offset_at(seconds))?)
+}
+
+/// Convert `tm` in local time to seconds since epoch, normalizing values that are out of range like `mktime()`.
+fn mktime(tm: &Tm) -> Option<SecondsSinceUnixEpoch> {
+    let year = tm.year.checked_add(1900 + tm.mon.div_euclid(12))?;
+    let month = Month::try_from(tm.mon.rem_euclid(12) as u8 + 1).ok()?;
+    let first_of_month = Date::from_calendar_date(year.try_into().ok()?, month, 1).ok()?;
+    let days = i64::from(first_of_month.to_julian_day() - OffsetDateTime::UNIX_EPOCH.date().to_julian_day())
+        .checked_add(tm.mday - 1)?;
+    let local = days
+        .checked_mul(24 * 60 * 60)?
+        .checked_add(tm.hour * 60 * 60 + tm.min * 60 + tm.sec)?;
+    local.checked_sub(local_offset_at(local))
+}
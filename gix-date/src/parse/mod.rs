@@ -0,0 +1,95 @@
+#[derive(thiserror::Error, Debug, Clone)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not convert a duration into a date")]
+    RelativeTimeConversion,
+    #[error("Date string can not be parsed")]
+    InvalidDateString { input: String },
+    #[error("The heat-death of the universe happens before this date")]
+    InvalidDate(#[from] std::num::TryFromIntError),
+    #[error("Current time is missing but required to handle relative dates.")]
+    MissingCurrentTime,
+}
+
+pub(crate) mod function {
+    use std::{str::FromStr, time::SystemTime};
+
+    use time::Date;
+
+    use crate::{
+        parse::{git, Error},
+        time::{format::SHORT, Sign},
+        SecondsSinceUnixEpoch, Time,
+    };
+
+    /// Parse `input` as date the way `git` does, with `now` as reference for relative dates like `2 weeks ago` or `yesterday`.
+    ///
+    /// Besides the formats `git` produces, this includes approximate dates like `last friday`, `noon yesterday`,
+    /// `3.days.ago` or `Dec 6, 1992`, with dates and times without timezone being in the local timezone.
+    /// Relative dates yield an error if `now` is `None`.
+    ///
+    /// ### Deviation
+    ///
+    /// * Dates in the `SHORT` format like `2022-08-22` are at midnight in UTC, whereas `git` uses the current time of day.
+    /// * Plain numbers are always seconds since epoch, and `<seconds> <+|-><hhmm>` is always parsed like the `RAW` format.
+    pub fn parse(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
+        Ok(if let Ok(val) = Date::parse(input, SHORT) {
+            let val = val.with_hms(0, 0, 0).expect("date is in range").assume_utc();
+            Time::new(val.unix_timestamp(), val.offset().whole_seconds())
+        } else if let Ok(val) = SecondsSinceUnixEpoch::from_str(input) {
+            // Format::Unix
+            Time::new(val, 0)
+        } else if let Some(val) = parse_raw(input) {
+            // Format::Raw
+            val
+        } else if let Some(val) = git::basic(
+            input.as_bytes(),
+            seconds_since_epoch(now.unwrap_or_else(SystemTime::now))?,
+        ) {
+            val
+        } else {
+            let reference = now.map(seconds_since_epoch).transpose()?.unwrap_or_default();
+            let seconds = git::approximate(input.as_bytes(), reference)
+                .ok_or_else(|| Error::InvalidDateString { input: input.into() })?;
+            if now.is_none() {
+                return Err(Error::MissingCurrentTime);
+            }
+            Time::new(seconds.ok_or(Error::RelativeTimeConversion)?, 0)
+        })
+    }
+
+    fn seconds_since_epoch(time: SystemTime) -> Result<SecondsSinceUnixEpoch, Error> {
+        Ok(match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(since_epoch) => since_epoch.as_secs().try_into()?,
+            Err(err) => -SecondsSinceUnixEpoch::try_from(err.duration().as_secs())?,
+        })
+    }
+
+    fn parse_raw(input: &str) -> Option<Time> {
+        let mut split = input.split_whitespace();
+        let seconds: SecondsSinceUnixEpoch = split.next()?.parse().ok()?;
+        let offset = split.next()?;
+        if offset.len() != 5 || split.next().is_some() {
+            return None;
+        }
+        let sign = match offset.get(..1)? {
+            "-" => Some(Sign::Minus),
+            "+" => Some(Sign::Plus),
+            _ => None,
+        }?;
+        let hours: i32 = offset.get(1..3)?.parse().ok()?;
+        let minutes: i32 = offset.get(3..5)?.parse().ok()?;
+        let mut offset_in_seconds = hours * 3600 + minutes * 60;
+        if sign == Sign::Minus {
+            offset_in_seconds *= -1;
+        };
+        let time = Time {
+            seconds,
+            offset: offset_in_seconds,
+            sign,
+        };
+        Some(time)
+    }
+}
+
+mod git;
@@ -3,6 +3,9 @@ set -eu -o pipefail
 
 git init;
 
+# Relative dates are relative to this time, which is 2009-08-30 19:20:00 UTC, and local times are in UTC.
+export GIT_TEST_DATE_NOW=1251660000 TZ=UTC
+
 function baseline() {
     local test_date=$1 # first argument is the date to test
     local test_name=$2 # second argument is the format name for re-formatting
@@ -44,3 +47,52 @@ baseline '1660874655 +0800' 'RAW'
 
 # Note that we can't necessarily put 64bit dates here yet as `git` on the system might not yet support it.
 
+# approximate dates, see `approxidate` in git
+baseline '5 seconds ago' ''
+baseline '5.seconds.ago' ''
+baseline '10.minutes.ago' ''
+baseline 'yesterday' ''
+baseline '3.days.ago' ''
+baseline '12:34:56.3.days.ago' ''
+baseline '3.weeks.ago' ''
+baseline '3.months.ago' ''
+baseline '2.years.3.months.ago' ''
+baseline 'two weeks ago' ''
+baseline '6am yesterday' ''
+baseline '6pm yesterday' ''
+baseline '3:00' ''
+baseline '15:00' ''
+baseline 'noon today' ''
+baseline 'noon yesterday' ''
+baseline 'midnight' ''
+baseline 'tea' ''
+baseline 'January 5th noon pm' ''
+baseline '10am noon' ''
+baseline 'last tuesday' ''
+baseline 'last friday' ''
+baseline 'July 5th' ''
+baseline '06/05/2009' ''
+baseline '06.05.2009' ''
+baseline '13/05/2009' ''
+baseline 'Jun 6, 5AM' ''
+baseline '5AM Jun 6' ''
+baseline '6AM, June 7, 2009' ''
+baseline '2008/12/01' ''
+baseline '2009.12.01' ''
+baseline 'Dec 6 1992' ''
+baseline 'Dec 02' ''
+baseline 'never' ''
+baseline '1979-02-26 18:30:00' ''
+baseline '2009-08-29 10:00' ''
+baseline '20090829T101112' ''
+baseline '2009-08-29T10:11:12.123Z' ''
+baseline '2009-08-29 10:11:12 CEST' ''
+baseline '2009-08-29 10:11:12 PST' ''
+baseline '2009-08-29 10:11:12 -05:30' ''
+baseline '@1251660000 +0200' ''
+baseline 'Sat, 29 Aug 2009 10:11:12 GMT' ''
+baseline '8/29/2009 3pm' ''
+
+# failure
+baseline 'foobar' ''
+baseline 'today' ''
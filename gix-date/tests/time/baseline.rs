@@ -1,4 +1,7 @@
-use std::{collections::HashMap, time::SystemTime};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
 use gix_date::{
     time::{format, Format},
@@ -8,6 +11,9 @@ use once_cell::sync::Lazy;
 
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+/// The time `git` considers to be now when generating the baseline, see `GIT_TEST_DATE_NOW`.
+const NOW: Duration = Duration::from_secs(1251660000);
+
 struct Sample {
     format_name: Option<String>,
     exit_code: usize,
@@ -54,7 +60,7 @@ fn parse_compare_format() {
         },
     ) in BASELINE.iter()
     {
-        let res = gix_date::parse(pattern.as_str(), Some(SystemTime::now()));
+        let res = gix_date::parse(pattern.as_str(), Some(SystemTime::UNIX_EPOCH + NOW));
         assert_eq!(
             res.is_ok(),
             *exit_code == 0,
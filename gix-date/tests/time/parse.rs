@@ -3,15 +3,57 @@ use std::time::SystemTime;
 use gix_date::{time::Sign, Time};
 
 #[test]
-fn special_time_is_ok_for_now() {
+fn date_and_time_with_offset() {
     assert_eq!(
-        gix_date::parse("1979-02-26 18:30:00", Some(SystemTime::now())).unwrap(),
+        gix_date::parse("1979-02-26 18:30:00 +0030", Some(SystemTime::now())).unwrap(),
         Time {
-            seconds: 42,
+            seconds: 288900000,
             offset: 1800,
             sign: Sign::Plus,
         }
     );
+    assert_eq!(
+        gix_date::parse("1979-02-26 18:30:00 -0000", None).unwrap(),
+        Time {
+            seconds: 288901800,
+            offset: 0,
+            sign: Sign::Minus,
+        },
+        "the sign of the offset is retained"
+    );
+}
+
+#[test]
+fn timezone_names() {
+    assert_eq!(
+        gix_date::parse("Sat, 29 Aug 2009 10:11:12 PST", None).unwrap(),
+        Time {
+            seconds: 1251569472,
+            offset: -8 * 3600,
+            sign: Sign::Minus,
+        }
+    );
+    assert_eq!(
+        gix_date::parse("2009-08-29 10:11:12 CEST", None).unwrap(),
+        Time {
+            seconds: 1251533472,
+            offset: 2 * 3600,
+            sign: Sign::Plus,
+        },
+        "like git, we assume it's always summer and add an hour to daylight-saving times"
+    );
+}
+
+#[test]
+fn object_header_format_with_at() {
+    assert_eq!(
+        gix_date::parse("@1251660000 -0130", None).unwrap(),
+        Time {
+            seconds: 1251660000,
+            offset: -5400,
+            sign: Sign::Minus,
+        }
+    );
 }
 
 #[test]
@@ -146,6 +188,31 @@ mod relative {
         assert_eq!(date.seconds, -1);
     }
 
+    #[test]
+    fn approximate_dates_need_the_current_time() {
+        for input in ["yesterday", "3.days.ago", "last friday", "Dec 6"] {
+            assert!(matches!(
+                gix_date::parse(input, None),
+                Err(gix_date::parse::Error::MissingCurrentTime)
+            ));
+        }
+    }
+
+    #[test]
+    fn months_and_years() {
+        let now = Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1251660000));
+        assert_eq!(
+            gix_date::parse("2.years.3.months.ago", now).unwrap().seconds,
+            1180552800,
+            "2007-05-30 19:20:00"
+        );
+        assert_eq!(
+            gix_date::parse("14 months ago", now).unwrap().seconds,
+            1214853600,
+            "2008-06-30 19:20:00"
+        );
+    }
+
     #[test]
     fn various() {
         let now = Some(SystemTime::now());
@@ -166,9 +233,12 @@ mod relative {
 /// Various cases the fuzzer found
 mod fuzz {
     #[test]
-    fn invalid_but_does_not_cause_panic() {
+    fn approximate_but_does_not_cause_panic() {
         for input in ["7	-𬞋", "5 ڜ-09", "-4 week ago Z"] {
-            let _ = gix_date::parse(input, Some(std::time::UNIX_EPOCH)).unwrap_err();
+            assert!(
+                gix_date::parse(input, Some(std::time::UNIX_EPOCH)).is_ok(),
+                "git ignores what it doesn't understand as long as there is a number"
+            );
         }
     }
 }
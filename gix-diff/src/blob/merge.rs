@@ -0,0 +1,471 @@
+//! Three-way merges of blobs, similar to `git merge-file`.
+use std::ops::Range;
+
+use gix_object::bstr::BStr;
+use imara_diff::{
+    intern::{Interner, Token},
+    sources::byte_lines_with_terminator,
+    Algorithm,
+};
+
+/// The amount of bytes at the beginning of a buffer that are checked for null bytes to determine if it is binary.
+const FIRST_FEW_BYTES: usize = 8000;
+
+/// How conflicting changes are presented in the merged result.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConflictStyle {
+    /// Show the conflicting changes of both sides, as configured with `merge.conflictStyle=merge`.
+    ///
+    /// Lines that both sides have in common are moved out of the conflict, which may be split into multiple conflicts.
+    #[default]
+    Merge,
+    /// Show the conflicting changes of both sides, along with the lines of the merge base they replace, as configured
+    /// with `merge.conflictStyle=diff3`.
+    Diff3,
+    /// Like [`Diff3`](Self::Diff3), but lines that both sides have in common at the beginning and at the end of a conflict
+    /// are moved out of it, as configured with `merge.conflictStyle=zdiff3`.
+    ZealousDiff3,
+}
+
+/// How to resolve conflicting changes automatically instead of marking them as conflict.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Favor {
+    /// Use the lines of our side.
+    Ours,
+    /// Use the lines of their side.
+    Theirs,
+    /// Use the lines of our side, followed by the lines of their side.
+    Union,
+}
+
+/// Options for use in [`text()`].
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// The algorithm to use to diff the merge base with both sides.
+    pub algorithm: Algorithm,
+    /// How to present conflicts.
+    pub conflict_style: ConflictStyle,
+    /// If set, conflicts are resolved automatically in favor of the given side.
+    pub favor: Option<Favor>,
+    /// The amount of characters of conflict markers, which is 7 by default like in `<<<<<<<`.
+    pub marker_size: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::Myers,
+            conflict_style: Default::default(),
+            favor: None,
+            marker_size: 7,
+        }
+    }
+}
+
+/// The labels to show next to conflict markers, typically the names of the merged commits or paths.
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Labels<'a> {
+    /// The label of the merge base, shown only with the [`Diff3`](ConflictStyle::Diff3) conflict styles.
+    pub ancestor: Option<&'a BStr>,
+    /// The label of our side.
+    pub current: Option<&'a BStr>,
+    /// The label of their side.
+    pub other: Option<&'a BStr>,
+}
+
+/// Whether a merge could be performed without conflicts.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Resolution {
+    /// All changes were merged, or conflicts were resolved automatically in favor of one side.
+    Complete,
+    /// Conflicts are present in the merged result.
+    Conflict,
+}
+
+/// A region of conflicting changes, as ranges of lines of each of the merged inputs.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Conflict {
+    /// The lines of the merge base that both sides changed.
+    pub base: Range<u32>,
+    /// The lines of our side that replace the lines of the merge base.
+    pub ours: Range<u32>,
+    /// The lines of their side that replace the lines of the merge base.
+    pub theirs: Range<u32>,
+}
+
+/// The result of [`text()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// Whether or not the merged result contains conflicts.
+    pub resolution: Resolution,
+    /// All conflicting changes, in order, including the ones that were resolved with [`Options::favor`].
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Return `true` if `data` is considered binary, just like `git` would, i.e. if there is a null byte among its first
+/// 8000 bytes.
+pub fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(FIRST_FEW_BYTES)].contains(&0)
+}
+
+/// The side to use as result of a [`binary()`] merge.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Pick {
+    /// Use our side.
+    Ours,
+    /// Use their side.
+    Theirs,
+}
+
+/// Merge binary content, which always conflicts unless `favor` is set to [`Favor::Ours`] or [`Favor::Theirs`],
+/// and return the side to use as result.
+///
+/// Note that our side is used in case of conflict, as binary content can't be merged.
+pub fn binary(favor: Option<Favor>) -> (Pick, Resolution) {
+    match favor {
+        Some(Favor::Ours) => (Pick::Ours, Resolution::Complete),
+        Some(Favor::Theirs) => (Pick::Theirs, Resolution::Complete),
+        Some(Favor::Union) | None => (Pick::Ours, Resolution::Conflict),
+    }
+}
+
+/// Merge the changes of `ours` and `theirs`, both relative to `base`, line by line and write the result to `out`,
+/// with conflicts presented according to `options` and marked with `labels`.
+///
+/// Note that `out` is cleared first, and that the inputs are merged as text even if they [are binary](is_binary()).
+pub fn text(
+    out: &mut Vec<u8>,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    labels: Labels<'_>,
+    options: Options,
+) -> Outcome {
+    out.clear();
+    let mut interner = Interner::new(base.len() / 16 + ours.len() / 16 + theirs.len() / 16);
+    fn tokenize<'a>(interner: &mut Interner<&'a [u8]>, data: &'a [u8]) -> Vec<Token> {
+        byte_lines_with_terminator(data)
+            .map(|line| interner.intern(line))
+            .collect()
+    }
+    let (base_tokens, ours_tokens, theirs_tokens) = (
+        tokenize(&mut interner, base),
+        tokenize(&mut interner, ours),
+        tokenize(&mut interner, theirs),
+    );
+    let input = Input {
+        base: base_tokens,
+        ours: ours_tokens,
+        theirs: theirs_tokens,
+        interner: &interner,
+    };
+
+    let mut chunks = input.merge(options.algorithm);
+    match options.conflict_style {
+        ConflictStyle::Merge => {
+            chunks = input.refine_conflicts(chunks, options.algorithm);
+            chunks = simplify_conflicts(chunks);
+        }
+        ConflictStyle::ZealousDiff3 => chunks = input.trim_conflicts(chunks),
+        ConflictStyle::Diff3 => {}
+    }
+
+    let eol: &[u8] = if [ours, theirs, base]
+        .iter()
+        .find_map(|data| {
+            data.iter()
+                .position(|b| *b == b'\n')
+                .map(|pos| pos > 0 && data[pos - 1] == b'\r')
+        })
+        .unwrap_or(false)
+    {
+        b"\r\n"
+    } else {
+        b"\n"
+    };
+    let mut conflicts = Vec::new();
+    for chunk in chunks {
+        match chunk {
+            Chunk::Unchanged { base } => input.write(out, &input.base, base, false),
+            Chunk::Common { ours } | Chunk::Ours { ours } => input.write(out, &input.ours, ours, false),
+            Chunk::Theirs { theirs } => input.write(out, &input.theirs, theirs, false),
+            Chunk::Conflict(conflict) => {
+                match options.favor {
+                    Some(Favor::Ours) => input.write(out, &input.ours, conflict.ours.clone(), false),
+                    Some(Favor::Theirs) => input.write(out, &input.theirs, conflict.theirs.clone(), false),
+                    Some(Favor::Union) => {
+                        input.write(out, &input.ours, conflict.ours.clone(), true);
+                        input.write(out, &input.theirs, conflict.theirs.clone(), false);
+                    }
+                    None => {
+                        let marker = |out: &mut Vec<u8>, marker: u8, label: Option<&BStr>| {
+                            out.extend(std::iter::repeat(marker).take(options.marker_size));
+                            if let Some(label) = label {
+                                out.push(b' ');
+                                out.extend_from_slice(label);
+                            }
+                            out.extend_from_slice(eol);
+                        };
+                        marker(out, b'<', labels.current);
+                        input.write(out, &input.ours, conflict.ours.clone(), true);
+                        if options.conflict_style != ConflictStyle::Merge {
+                            marker(out, b'|', labels.ancestor);
+                            input.write(out, &input.base, conflict.base.clone(), true);
+                        }
+                        marker(out, b'=', None);
+                        input.write(out, &input.theirs, conflict.theirs.clone(), true);
+                        marker(out, b'>', labels.other);
+                    }
+                }
+                conflicts.push(conflict);
+            }
+        }
+    }
+    Outcome {
+        resolution: if conflicts.is_empty() || options.favor.is_some() {
+            Resolution::Complete
+        } else {
+            Resolution::Conflict
+        },
+        conflicts,
+    }
+}
+
+/// The lines of all inputs, as tokens of a shared interner.
+struct Input<'a> {
+    base: Vec<Token>,
+    ours: Vec<Token>,
+    theirs: Vec<Token>,
+    interner: &'a Interner<&'a [u8]>,
+}
+
+/// A consecutive region of the merged result.
+enum Chunk {
+    /// Lines that neither side changed.
+    Unchanged { base: Range<u32> },
+    /// Lines that both sides have in common within a conflicting region.
+    Common { ours: Range<u32> },
+    /// Lines only we changed, or that both sides changed in the same way.
+    Ours { ours: Range<u32> },
+    /// Lines only they changed.
+    Theirs { theirs: Range<u32> },
+    /// Lines both sides changed differently.
+    Conflict(Conflict),
+}
+
+/// A change of one side relative to the merge base.
+struct Hunk {
+    before: Range<u32>,
+    after: Range<u32>,
+}
+
+impl Hunk {
+    fn delta(&self) -> i64 {
+        self.after.len() as i64 - self.before.len() as i64
+    }
+}
+
+impl<'a> Input<'a> {
+    /// Write the lines in `range` of `lines` to `out`, with a newline added to the last line if it doesn't have one and
+    /// `ensure_newline` is set.
+    fn write(&self, out: &mut Vec<u8>, lines: &[Token], range: Range<u32>, ensure_newline: bool) {
+        for token in &lines[range.start as usize..range.end as usize] {
+            out.extend_from_slice(self.interner[*token]);
+        }
+        if ensure_newline && !range.is_empty() && out.last() != Some(&b'\n') {
+            out.push(b'\n');
+        }
+    }
+
+    fn tokens<'b>(&self, lines: &'b [Token], range: &Range<u32>) -> &'b [Token] {
+        &lines[range.start as usize..range.end as usize]
+    }
+
+    /// Diff `before` with `after` and return all changes.
+    fn diff(&self, algorithm: Algorithm, before: &[Token], after: &[Token]) -> Vec<Hunk> {
+        let mut hunks = Vec::new();
+        imara_diff::diff_with_tokens(
+            algorithm,
+            before,
+            after,
+            self.interner.num_tokens(),
+            |before: Range<u32>, after: Range<u32>| hunks.push(Hunk { before, after }),
+        );
+        hunks
+    }
+
+    /// Combine the changes of both sides relative to the merge base, and turn overlapping or adjacent changes into
+    /// conflicts, unless both sides changed the lines in the same way.
+    fn merge(&self, algorithm: Algorithm) -> Vec<Chunk> {
+        let ours = self.diff(algorithm, &self.base, &self.ours);
+        let theirs = self.diff(algorithm, &self.base, &self.theirs);
+        let (mut ours_idx, mut theirs_idx) = (0, 0);
+        let (mut ours_delta, mut theirs_delta) = (0i64, 0i64);
+        let mut base_pos = 0;
+        let mut chunks = Vec::new();
+        loop {
+            let start = match (ours.get(ours_idx), theirs.get(theirs_idx)) {
+                (Some(a), Some(b)) => a.before.start.min(b.before.start),
+                (Some(a), None) => a.before.start,
+                (None, Some(b)) => b.before.start,
+                (None, None) => break,
+            };
+            let mut end = start;
+            let (ours_group_start, theirs_group_start) = (ours_idx, theirs_idx);
+            loop {
+                let mut extended = false;
+                for (hunks, idx) in [(&ours, &mut ours_idx), (&theirs, &mut theirs_idx)] {
+                    while let Some(hunk) = hunks.get(*idx).filter(|hunk| hunk.before.start <= end) {
+                        end = end.max(hunk.before.end);
+                        *idx += 1;
+                        extended = true;
+                    }
+                }
+                if !extended {
+                    break;
+                }
+            }
+
+            if base_pos < start {
+                chunks.push(Chunk::Unchanged { base: base_pos..start });
+            }
+            base_pos = end;
+            let side_range = |hunks: &[Hunk], delta: &mut i64| {
+                let group_delta: i64 = hunks.iter().map(Hunk::delta).sum();
+                let range = (start as i64 + *delta) as u32..(end as i64 + *delta + group_delta) as u32;
+                *delta += group_delta;
+                range
+            };
+            let ours_hunks = &ours[ours_group_start..ours_idx];
+            let theirs_hunks = &theirs[theirs_group_start..theirs_idx];
+            let ours_range = side_range(ours_hunks, &mut ours_delta);
+            let theirs_range = side_range(theirs_hunks, &mut theirs_delta);
+            chunks.push(if theirs_hunks.is_empty() {
+                Chunk::Ours { ours: ours_range }
+            } else if ours_hunks.is_empty() {
+                Chunk::Theirs { theirs: theirs_range }
+            } else if self.tokens(&self.ours, &ours_range) == self.tokens(&self.theirs, &theirs_range) {
+                Chunk::Ours { ours: ours_range }
+            } else {
+                Chunk::Conflict(Conflict {
+                    base: start..end,
+                    ours: ours_range,
+                    theirs: theirs_range,
+                })
+            });
+        }
+        if (base_pos as usize) < self.base.len() {
+            chunks.push(Chunk::Unchanged {
+                base: base_pos..self.base.len() as u32,
+            });
+        }
+        chunks
+    }
+
+    /// Split conflicts into the parts that differ between both sides, with the lines they have in common between them.
+    fn refine_conflicts(&self, chunks: Vec<Chunk>, algorithm: Algorithm) -> Vec<Chunk> {
+        let mut out = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let conflict = match chunk {
+                Chunk::Conflict(conflict) if !conflict.ours.is_empty() && !conflict.theirs.is_empty() => conflict,
+                chunk => {
+                    out.push(chunk);
+                    continue;
+                }
+            };
+            let hunks = self.diff(
+                algorithm,
+                self.tokens(&self.ours, &conflict.ours),
+                self.tokens(&self.theirs, &conflict.theirs),
+            );
+            let (ours_start, theirs_start) = (conflict.ours.start, conflict.theirs.start);
+            let mut common_start = 0;
+            for hunk in hunks {
+                if common_start < hunk.before.start {
+                    out.push(Chunk::Common {
+                        ours: ours_start + common_start..ours_start + hunk.before.start,
+                    });
+                }
+                common_start = hunk.before.end;
+                out.push(Chunk::Conflict(Conflict {
+                    base: conflict.base.clone(),
+                    ours: ours_start + hunk.before.start..ours_start + hunk.before.end,
+                    theirs: theirs_start + hunk.after.start..theirs_start + hunk.after.end,
+                }));
+            }
+            if ours_start + common_start < conflict.ours.end {
+                out.push(Chunk::Common {
+                    ours: ours_start + common_start..conflict.ours.end,
+                });
+            }
+        }
+        out
+    }
+
+    /// Move the lines that both sides of a conflict have in common at its beginning and end out of the conflict.
+    fn trim_conflicts(&self, chunks: Vec<Chunk>) -> Vec<Chunk> {
+        let mut out = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            let mut conflict = match chunk {
+                Chunk::Conflict(conflict) => conflict,
+                chunk => {
+                    out.push(chunk);
+                    continue;
+                }
+            };
+            let ours = self.tokens(&self.ours, &conflict.ours);
+            let theirs = self.tokens(&self.theirs, &conflict.theirs);
+            let prefix = ours.iter().zip(theirs).take_while(|(a, b)| a == b).count() as u32;
+            let suffix = ours[prefix as usize..]
+                .iter()
+                .rev()
+                .zip(theirs[prefix as usize..].iter().rev())
+                .take_while(|(a, b)| a == b)
+                .count() as u32;
+            if prefix != 0 {
+                out.push(Chunk::Common {
+                    ours: conflict.ours.start..conflict.ours.start + prefix,
+                });
+            }
+            let suffix_chunk = (suffix != 0).then(|| Chunk::Common {
+                ours: conflict.ours.end - suffix..conflict.ours.end,
+            });
+            conflict.ours = conflict.ours.start + prefix..conflict.ours.end - suffix;
+            conflict.theirs = conflict.theirs.start + prefix..conflict.theirs.end - suffix;
+            out.push(Chunk::Conflict(conflict));
+            out.extend(suffix_chunk);
+        }
+        out
+    }
+}
+
+/// Merge conflicts that are separated by no more than three lines, which become part of both sides of the merged conflict.
+fn simplify_conflicts(chunks: Vec<Chunk>) -> Vec<Chunk> {
+    const MAX_GAP: usize = 3;
+    let mut out = Vec::<Chunk>::with_capacity(chunks.len());
+    let mut chunks = chunks.into_iter().peekable();
+    while let Some(chunk) = chunks.next() {
+        let gap = match &chunk {
+            Chunk::Unchanged { base: lines } | Chunk::Common { ours: lines } => lines.len(),
+            _ => {
+                out.push(chunk);
+                continue;
+            }
+        };
+        let previous_is_conflict = matches!(out.last(), Some(Chunk::Conflict(_)));
+        if gap <= MAX_GAP && previous_is_conflict && matches!(chunks.peek(), Some(Chunk::Conflict(_))) {
+            let Some(Chunk::Conflict(next)) = chunks.next() else {
+                unreachable!("checked above")
+            };
+            let Some(Chunk::Conflict(previous)) = out.last_mut() else {
+                unreachable!("checked above")
+            };
+            previous.base = previous.base.start.min(next.base.start)..previous.base.end.max(next.base.end);
+            previous.ours.end = next.ours.end;
+            previous.theirs.end = next.theirs.end;
+        } else {
+            out.push(chunk);
+        }
+    }
+    out
+}
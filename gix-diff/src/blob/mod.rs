@@ -1,3 +1,6 @@
 //! For using text diffs, please have a look at the [`imara-diff` documentation](https://docs.rs/imara-diff),
 //! maintained by [Pascal Kuthe](https://github.com/pascalkuthe).
 pub use imara_diff::*;
+
+///
+pub mod merge;
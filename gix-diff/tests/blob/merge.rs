@@ -0,0 +1,135 @@
+use gix_diff::blob::merge::{self, ConflictStyle, Favor, Labels, Resolution};
+use gix_object::bstr::ByteSlice;
+
+fn labels() -> Labels<'static> {
+    Labels {
+        ancestor: Some("base".into()),
+        current: Some("ours".into()),
+        other: Some("theirs".into()),
+    }
+}
+
+#[test]
+fn text_matches_git_merge_file() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only_standalone("make_blob_merge.sh")?;
+    let mut cases = 0;
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(&root)? {
+        let dir = entry?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let read = |name: &str| std::fs::read(dir.join(name));
+        let (base, ours, theirs) = (read("base")?, read("ours")?, read("theirs")?);
+        for (baseline, style, favor) in [
+            ("merge", ConflictStyle::Merge, None),
+            ("diff3", ConflictStyle::Diff3, None),
+            ("zdiff3", ConflictStyle::ZealousDiff3, None),
+            ("favor-ours", ConflictStyle::Merge, Some(Favor::Ours)),
+            ("favor-theirs", ConflictStyle::Merge, Some(Favor::Theirs)),
+            ("favor-union", ConflictStyle::Merge, Some(Favor::Union)),
+        ] {
+            let expected = read(baseline)?;
+            let outcome = merge::text(
+                &mut out,
+                &base,
+                &ours,
+                &theirs,
+                labels(),
+                merge::Options {
+                    conflict_style: style,
+                    favor,
+                    ..Default::default()
+                },
+            );
+            assert_eq!(
+                out.as_bstr(),
+                expected.as_bstr(),
+                "{dir:?}: {baseline} should match the output of `git merge-file`"
+            );
+            let has_markers = expected.find(b"<<<<<<< ours").is_some();
+            assert_eq!(
+                outcome.resolution,
+                if has_markers {
+                    Resolution::Conflict
+                } else {
+                    Resolution::Complete
+                },
+                "{dir:?}: {baseline}"
+            );
+            if favor.is_none() {
+                assert_eq!(outcome.conflicts.is_empty(), !has_markers, "{dir:?}: {baseline}");
+            }
+        }
+        cases += 1;
+    }
+    assert_eq!(cases, 17, "all cases were checked");
+    Ok(())
+}
+
+#[test]
+fn conflict_ranges_refer_to_lines_of_each_input() {
+    let mut out = Vec::new();
+    let outcome = merge::text(
+        &mut out,
+        b"1\n2\n3\n",
+        b"1\nx\nx\n3\n",
+        b"1\ny\n3\n",
+        Labels::default(),
+        Default::default(),
+    );
+    assert_eq!(outcome.resolution, Resolution::Conflict);
+    assert_eq!(
+        outcome.conflicts,
+        [merge::Conflict {
+            base: 1..2,
+            ours: 1..3,
+            theirs: 1..2
+        }]
+    );
+    assert_eq!(
+        out.as_bstr(),
+        "1\n<<<<<<<\nx\nx\n=======\ny\n>>>>>>>\n3\n",
+        "markers have no trailing space without labels"
+    );
+}
+
+#[test]
+fn marker_size_is_configurable() {
+    let mut out = Vec::new();
+    merge::text(
+        &mut out,
+        b"1\n",
+        b"x\n",
+        b"y\n",
+        labels(),
+        merge::Options {
+            marker_size: 3,
+            ..Default::default()
+        },
+    );
+    assert_eq!(out.as_bstr(), "<<< ours\nx\n===\ny\n>>> theirs\n");
+}
+
+#[test]
+fn binary() {
+    assert!(merge::is_binary(b"a\0b"));
+    assert!(!merge::is_binary(b"text\n"));
+    let mut data = vec![b'a'; 8000];
+    data.push(0);
+    assert!(!merge::is_binary(&data), "only the first 8000 bytes are checked");
+
+    assert_eq!(merge::binary(None), (merge::Pick::Ours, Resolution::Conflict));
+    assert_eq!(
+        merge::binary(Some(Favor::Union)),
+        (merge::Pick::Ours, Resolution::Conflict)
+    );
+    assert_eq!(
+        merge::binary(Some(Favor::Ours)),
+        (merge::Pick::Ours, Resolution::Complete)
+    );
+    assert_eq!(
+        merge::binary(Some(Favor::Theirs)),
+        (merge::Pick::Theirs, Resolution::Complete)
+    );
+}
@@ -1,2 +1 @@
-#[test]
-fn currently_there_is_no_api_surface_to_test_as_it_is_reexporting_imara_diff() {}
+mod merge;
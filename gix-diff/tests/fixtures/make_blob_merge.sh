@@ -0,0 +1,43 @@
+#!/bin/bash
+set -eu -o pipefail
+
+# Each case is a directory with `base`, `ours` and `theirs`, along with the result of `git merge-file`
+# for each conflict style and favor.
+function baseline() {
+  local name=${1:?need case name}
+  (cd "$name"
+    for args in "merge" "diff3 --diff3" "zdiff3 --zdiff3" "favor-ours --ours" "favor-theirs --theirs" "favor-union --union"; do
+      set -- $args
+      local out=$1
+      shift
+      git merge-file -p "$@" -L ours -L base -L theirs ours base theirs > "$out" || true
+    done
+  )
+}
+
+function make_case() {
+  local name=${1:?need case name}
+  mkdir "$name"
+  printf "$2" > "$name/base"
+  printf "$3" > "$name/ours"
+  printf "$4" > "$name/theirs"
+  baseline "$name"
+}
+
+make_case unchanged '1\n2\n3\n' '1\n2\n3\n' '1\n2\n3\n'
+make_case only-ours '1\n2\n3\n' '1\nx\n3\n' '1\n2\n3\n'
+make_case only-theirs '1\n2\n3\n' '1\n2\n3\n' '1\n2\ny\n'
+make_case separate-changes '1\n2\n3\n4\n5\n6\n' '1\nx\n3\n4\n5\n6\n' '1\n2\n3\n4\n5\ny\n'
+make_case same-change '1\n2\n3\n' '1\nx\n3\n' '1\nx\n3\n'
+make_case simple-conflict '1\n2\n3\n' '1\nx\n3\n' '1\ny\n3\n'
+make_case adjacent-changes '1\n2\n3\n4\n' '1\nx\n3\n4\n' '1\n2\ny\n4\n'
+make_case insertion-at-same-place '1\n2\n' '1\nx\n2\n' '1\ny\n2\n'
+make_case delete-vs-modify '1\n2\n3\n' '1\n3\n' '1\ny\n3\n'
+make_case common-lines-in-conflict '1\n2\n3\n4\n5\n6\n7\n' '1\na\nb\nc\nd\ne\n7\n' '1\nA\nb\nc\nd\nE\n7\n'
+make_case conflicts-with-large-gap '1\n2\n3\n4\n5\n6\n7\n8\n' '1\na\nb\nc\nd\ne\nf\n8\n' '1\nA\nb\nc\nd\ne\nF\n8\n'
+make_case common-prefix-and-suffix '1\n2\n3\n' '1\nc\nx\nd\n3\n' '1\nc\ny\nd\n3\n'
+make_case add-add '' 'a\nb\nc\n' 'a\nB\nc\n'
+make_case missing-newline-at-end '1\n2\n3' '1\n2\nx' '1\n2\ny'
+make_case missing-newline-one-side '1\n2\n' '1\nx' '1\ny\n'
+make_case crlf '1\r\n2\r\n3\r\n' '1\r\nx\r\n3\r\n' '1\r\ny\r\n3\r\n'
+make_case multiple-conflicts '1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n' 'x\n2\n3\n4\n5\n6\n7\n8\n9\nX\n' 'y\n2\n3\n4\n5\n6\n7\n8\n9\nY\n'
@@ -0,0 +1,52 @@
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::Entry;
+
+/// The error returned by [`parse()`][crate::parse()] and [`Entry::from_line()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not decode object id in line {line:?}")]
+    Id {
+        line: BString,
+        source: gix_hash::decode::Error,
+    },
+    #[error("Line {line:?} is not of the form '<oid>\\t[not-for-merge]\\t<description>'")]
+    Malformed { line: BString },
+}
+
+impl Entry {
+    /// Parse a single `line` of a `FETCH_HEAD` file, without its trailing newline.
+    pub fn from_line(line: &[u8]) -> Result<Entry, Error> {
+        let mut tokens = line.splitn(3, |b| *b == b'\t');
+        let (hex, marker, description) = match (tokens.next(), tokens.next(), tokens.next()) {
+            (Some(hex), Some(marker), Some(description)) => (hex, marker, description),
+            _ => return Err(Error::Malformed { line: line.into() }),
+        };
+        let id = ObjectId::from_hex(hex).map_err(|source| Error::Id {
+            line: line.into(),
+            source,
+        })?;
+        let not_for_merge = match marker {
+            b"" => false,
+            b"not-for-merge" => true,
+            _ => return Err(Error::Malformed { line: line.into() }),
+        };
+        Ok(Entry {
+            id,
+            not_for_merge,
+            description: description.into(),
+        })
+    }
+}
+
+/// Parse all entries of a `FETCH_HEAD` file from `data`, in order.
+///
+/// Empty lines are skipped, and `\r\n` line endings are tolerated.
+pub fn parse(data: &[u8]) -> Result<Vec<Entry>, Error> {
+    data.lines()
+        .filter(|line| !line.is_empty())
+        .map(Entry::from_line)
+        .collect()
+}
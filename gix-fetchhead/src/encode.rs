@@ -0,0 +1,64 @@
+use std::io::Write;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::Entry;
+
+impl Entry {
+    /// Write this entry as a single line including its trailing newline to `out`.
+    pub fn write_to(&self, mut out: impl Write) -> std::io::Result<()> {
+        write!(out, "{}\t", self.id)?;
+        if self.not_for_merge {
+            out.write_all(b"not-for-merge")?;
+        }
+        out.write_all(b"\t")?;
+        out.write_all(&self.description)?;
+        out.write_all(b"\n")
+    }
+}
+
+/// Write all `entries` to `out` in the order they are given.
+///
+/// Note that `git` expects merge candidates to come first, so `entries` should be ordered accordingly.
+pub fn write_to<'a>(entries: impl IntoIterator<Item = &'a Entry>, mut out: impl Write) -> std::io::Result<()> {
+    for entry in entries {
+        entry.write_to(&mut out)?;
+    }
+    Ok(())
+}
+
+/// Produce the description `git` uses for the fetched reference with `full_ref_name` (or object id) from the remote at `url`,
+/// like `branch 'main' of https://example.com/repo`.
+///
+/// Trailing slashes and a `.git` suffix are removed from `url`, and newlines are escaped to keep the entry on a single line.
+pub fn description(full_ref_name: &BStr, url: &BStr) -> BString {
+    let (kind, name) = if full_ref_name == "HEAD" {
+        ("", "".into())
+    } else if let Some(name) = full_ref_name.strip_prefix(b"refs/heads/") {
+        ("branch ", name.as_bstr())
+    } else if let Some(name) = full_ref_name.strip_prefix(b"refs/tags/") {
+        ("tag ", name.as_bstr())
+    } else if let Some(name) = full_ref_name.strip_prefix(b"refs/remotes/") {
+        ("remote-tracking branch ", name.as_bstr())
+    } else {
+        ("", full_ref_name)
+    };
+
+    let mut out = BString::default();
+    if !name.is_empty() {
+        out.push_str(kind);
+        out.push_byte(b'\'');
+        out.push_str(name);
+        out.push_str("' of ");
+    }
+    let url = url.trim_end_with(|c| c == '/');
+    let url = url.strip_suffix(b".git").filter(|url| !url.is_empty()).unwrap_or(url);
+    for byte in url.iter().copied() {
+        if byte == b'\n' {
+            out.push_str("\\n");
+        } else {
+            out.push_byte(byte);
+        }
+    }
+    out
+}
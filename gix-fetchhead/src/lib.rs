@@ -1,2 +1,44 @@
-#![deny(rust_2018_idioms)]
+//! Read and write `.git/FETCH_HEAD`, the file that records what was fetched most recently, one line per fetched reference.
+//!
+//! Each line has the form `<oid>\t[not-for-merge]\t<description>`, where the lines without the `not-for-merge` marker are
+//! the candidates for merging as used by `git pull`. These are written first, which is why `FETCH_HEAD` refers to the first
+//! [merge candidate](merge_candidate()) when used as revision.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+///
+pub mod decode;
+pub use decode::parse;
+///
+pub mod encode;
+pub use encode::write_to;
+
+/// A single line in a `FETCH_HEAD` file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Entry {
+    /// The object the fetched reference pointed to on the remote.
+    pub id: ObjectId,
+    /// If `true`, the entry is not a candidate for merging and was only fetched along with the merge candidates.
+    pub not_for_merge: bool,
+    /// A human-readable description of what was fetched and from where, like `branch 'main' of https://example.com/repo`.
+    pub description: BString,
+}
+
+impl Entry {
+    /// Return `true` if this entry is a candidate for merging, which is when it isn't marked `not-for-merge`.
+    pub fn is_merge_candidate(&self) -> bool {
+        !self.not_for_merge
+    }
+}
+
+/// Return the entry that `FETCH_HEAD` refers to when used as revision, which is the first merge candidate among `entries`,
+/// or the first entry if there is none.
+pub fn merge_candidate(entries: &[Entry]) -> Option<&Entry> {
+    entries
+        .iter()
+        .find(|entry| entry.is_merge_candidate())
+        .or_else(|| entries.first())
+}
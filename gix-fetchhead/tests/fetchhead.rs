@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use bstr::{BStr, ByteSlice};
+use gix_fetchhead::{decode, encode, Entry};
+use gix_hash::ObjectId;
+
+pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error + 'static>>;
+
+fn fixture(name: &str) -> Result<PathBuf> {
+    Ok(gix_testtools::scripted_fixture_read_only("make_fetch_heads.sh")?.join(name))
+}
+
+fn rev_parse(spec: &str) -> Result<ObjectId> {
+    let out = std::process::Command::new("git")
+        .args(["rev-parse", spec])
+        .current_dir(fixture("remote")?)
+        .output()?;
+    assert!(out.status.success());
+    Ok(ObjectId::from_hex(out.stdout.trim())?)
+}
+
+fn remote_url() -> Result<String> {
+    Ok(gix_testtools::scripted_fixture_read_only("make_fetch_heads.sh")?
+        .canonicalize()?
+        .join("remote")
+        .to_str()
+        .expect("valid UTF-8")
+        .to_owned())
+}
+
+#[test]
+fn tracking_branch_has_its_merge_ref_as_only_merge_candidate() -> Result {
+    let data = std::fs::read(fixture("tracking-branch")?)?;
+    let entries = gix_fetchhead::parse(&data)?;
+    let url = remote_url()?;
+    assert_eq!(
+        entries,
+        vec![
+            Entry {
+                id: rev_parse("main")?,
+                not_for_merge: false,
+                description: format!("branch 'main' of {url}").into(),
+            },
+            Entry {
+                id: rev_parse("other")?,
+                not_for_merge: true,
+                description: format!("branch 'other' of {url}").into(),
+            }
+        ]
+    );
+    assert_eq!(
+        gix_fetchhead::merge_candidate(&entries).map(|e| e.id),
+        Some(rev_parse("main")?)
+    );
+    Ok(())
+}
+
+#[test]
+fn without_merge_candidates_the_first_entry_is_used() -> Result {
+    let entries = gix_fetchhead::parse(&std::fs::read(fixture("detached")?)?)?;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| !e.is_merge_candidate()));
+    assert_eq!(
+        gix_fetchhead::merge_candidate(&entries).map(|e| e.id),
+        Some(rev_parse("main")?)
+    );
+    assert_eq!(gix_fetchhead::merge_candidate(&[]), None);
+    Ok(())
+}
+
+#[test]
+fn round_trips_are_lossless() -> Result {
+    for name in ["tracking-branch", "explicit", "detached"] {
+        let data = std::fs::read(fixture(name)?)?;
+        let entries = gix_fetchhead::parse(&data)?;
+        let mut buf = Vec::new();
+        gix_fetchhead::write_to(&entries, &mut buf)?;
+        assert_eq!(buf.as_bstr(), data.as_bstr(), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn descriptions_match_the_ones_written_by_git() -> Result {
+    let url = remote_url()?;
+    let entries = gix_fetchhead::parse(&std::fs::read(fixture("explicit")?)?)?;
+    assert_eq!(
+        encode::description("refs/heads/other".into(), format!("{url}.git/").as_str().into()),
+        entries[0].description
+    );
+
+    for (name, url, expected) in [
+        ("HEAD", "https://example.com/repo.git", "https://example.com/repo"),
+        (
+            "refs/tags/v1",
+            "https://example.com/repo/",
+            "tag 'v1' of https://example.com/repo",
+        ),
+        (
+            "refs/remotes/origin/main",
+            "file:///repo",
+            "remote-tracking branch 'origin/main' of file:///repo",
+        ),
+        ("refs/pull/1/head", "a\nb", "'refs/pull/1/head' of a\\nb"),
+    ] {
+        assert_eq!(encode::description(name.into(), url.into()), BStr::new(expected));
+    }
+    Ok(())
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    assert!(matches!(
+        Entry::from_line(b"0000000000000000000000000000000000000000 branch"),
+        Err(decode::Error::Malformed { .. })
+    ));
+    assert!(matches!(
+        Entry::from_line(b"0000000000000000000000000000000000000000\tmerge\tbranch"),
+        Err(decode::Error::Malformed { .. })
+    ));
+    assert!(matches!(
+        Entry::from_line(b"not-hex\t\tbranch"),
+        Err(decode::Error::Id { .. })
+    ));
+}
@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  git tag -a -m "annotated" v1
+  git branch other
+  echo 2 > file && git commit -q -am c2
+)
+
+git clone -q remote clone
+(cd clone
+  git fetch -q
+  cp .git/FETCH_HEAD ../tracking-branch
+  git fetch -q origin other
+  cp .git/FETCH_HEAD ../explicit
+  git checkout -q --detach
+  git fetch -q origin
+  cp .git/FETCH_HEAD ../detached
+)
@@ -24,6 +24,9 @@ pub mod worktree;
 /// Utilities around driver programs.
 pub mod driver;
 
+/// Types to configure and use a [`Pipeline`].
+pub mod pipeline;
+
 /// A declaration of a driver program.
 ///
 /// It consists of up to three program declarations.
@@ -49,6 +52,21 @@ pub struct Driver {
     pub required: bool,
 }
 
+/// A pipeline to apply all filters configured for a path, in the order `git` would apply them, to convert worktree content
+/// into content suitable for storage in `git` and back.
+///
+/// It keeps long-running filter processes alive for as long as it exists.
+pub struct Pipeline {
+    /// All drivers that are available for use with the `filter` attribute.
+    drivers: Vec<Driver>,
+    /// Options that apply to all paths.
+    options: pipeline::Options,
+    /// The state of long-running filter processes.
+    processes: driver::State,
+    /// A buffer to hold intermediate results.
+    buf: Vec<u8>,
+}
+
 fn clear_and_set_capacity(buf: &mut Vec<u8>, cap: usize) {
     buf.clear();
     if buf.capacity() < cap {
@@ -0,0 +1,347 @@
+use std::io::Read;
+
+use bstr::{BStr, BString};
+
+use crate::{driver, eol, ident, worktree, Driver, Pipeline};
+
+/// The names of all attributes that affect the pipeline, for use in attribute queries that should be limited to these.
+pub const ATTRIBUTES: &[&str] = &["crlf", "ident", "filter", "eol", "text", "working-tree-encoding"];
+
+/// How to handle line-ending conversions that can't be undone, configured with `core.safecrlf`.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CrlfRoundTripCheck {
+    /// Fail with an error if the conversion isn't round-trip safe.
+    Fail,
+    /// Emit a warning using `gix_trace::warn!`, but don't fail.
+    #[default]
+    Warn,
+    /// Do not check at all.
+    Skip,
+}
+
+/// Options for use with a [`Pipeline`].
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// Configuration related to EOL, typically obtained from `core.autocrlf` and `core.eol`.
+    pub eol_config: eol::Configuration,
+    /// How to perform round-trip checks on line-ending conversions.
+    pub crlf_roundtrip_check: CrlfRoundTripCheck,
+    /// The kind of object hash to use when substituting `$Id$` in `ident` filters.
+    pub object_hash: gix_hash::Kind,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            eol_config: eol::Configuration {
+                auto_crlf: Some(false),
+                eol: None,
+            },
+            crlf_roundtrip_check: Default::default(),
+            object_hash: gix_hash::Kind::Sha1,
+        }
+    }
+}
+
+/// All information obtained from the attributes of a path that is relevant to the pipeline.
+#[derive(Debug, Clone)]
+pub struct Attributes {
+    /// The name of the driver set with the `filter` attribute, if set.
+    pub driver: Option<BString>,
+    /// How to convert line endings, as derived from the `text`, `crlf` and `eol` attributes along with `core.autocrlf`.
+    pub eol: eol::AttributesDigest,
+    /// If `true`, the `ident` attribute was set and `$Id$` will be expanded.
+    pub ident: bool,
+    /// The encoding set with the `working-tree-encoding` attribute, or `None` if the worktree uses UTF-8.
+    pub encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+///
+pub mod attributes {
+    /// The error returned by [`Attributes::from_assignments()`][super::Attributes::from_assignments()].
+    pub type Error = crate::worktree::encoding::for_label::Error;
+}
+
+impl Attributes {
+    /// Digest `assignments`, which should be the attributes matching a path, along with `config`
+    /// to learn how the path should be converted, similar to how `git` does it.
+    ///
+    /// Note that attributes that aren't relevant to the pipeline are ignored.
+    pub fn from_assignments<'a>(
+        assignments: impl IntoIterator<Item = gix_attributes::AssignmentRef<'a>>,
+        config: eol::Configuration,
+    ) -> Result<Self, attributes::Error> {
+        use gix_attributes::StateRef;
+        #[derive(Copy, Clone)]
+        enum Kind {
+            Text,
+            Binary,
+            Auto,
+            Input,
+        }
+
+        let mut text = None;
+        let mut crlf = None;
+        let mut eol = None;
+        let mut out = Attributes {
+            driver: None,
+            eol: eol::AttributesDigest::Binary,
+            ident: false,
+            encoding: None,
+        };
+        for assignment in assignments {
+            let state = assignment.state;
+            match assignment.name.as_str() {
+                "text" => {
+                    text = match state {
+                        StateRef::Set => Some(Kind::Text),
+                        StateRef::Unset => Some(Kind::Binary),
+                        StateRef::Value(v) if v.as_bstr() == "auto" => Some(Kind::Auto),
+                        StateRef::Value(_) | StateRef::Unspecified => None,
+                    }
+                }
+                "crlf" => {
+                    crlf = match state {
+                        StateRef::Set => Some(Kind::Text),
+                        StateRef::Unset => Some(Kind::Binary),
+                        StateRef::Value(v) if v.as_bstr() == "input" => Some(Kind::Input),
+                        StateRef::Value(_) | StateRef::Unspecified => None,
+                    }
+                }
+                "eol" => {
+                    eol = match state {
+                        StateRef::Value(v) if v.as_bstr() == "lf" => Some(eol::Mode::Lf),
+                        StateRef::Value(v) if v.as_bstr() == "crlf" => Some(eol::Mode::CrLf),
+                        _ => None,
+                    }
+                }
+                "ident" => out.ident = state.is_set(),
+                "filter" => {
+                    out.driver = match state {
+                        StateRef::Value(name) => Some(name.as_bstr().to_owned()),
+                        _ => None,
+                    }
+                }
+                "working-tree-encoding" => {
+                    out.encoding = match state {
+                        StateRef::Value(label) if !label.as_bstr().eq_ignore_ascii_case(b"utf-8") => {
+                            Some(worktree::encoding::for_label(label.as_bstr())?)
+                        }
+                        _ => None,
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        use eol::AttributesDigest::*;
+        out.eol = match (text.or(crlf), eol) {
+            (Some(Kind::Binary), _) => Binary,
+            (Some(Kind::Auto), Some(eol::Mode::Lf)) => TextAutoInput,
+            (Some(Kind::Auto), Some(eol::Mode::CrLf)) => TextAutoCrlf,
+            (_, Some(eol::Mode::Lf)) => TextInput,
+            (_, Some(eol::Mode::CrLf)) => TextCrlf,
+            (Some(Kind::Auto), None) => TextAuto,
+            (Some(Kind::Input), None) => TextInput,
+            (Some(Kind::Text), None) => Text,
+            (None, None) => match config.auto_crlf {
+                Some(false) => Binary,
+                Some(true) => TextAutoCrlf,
+                None => TextAutoInput,
+            },
+        };
+        Ok(out)
+    }
+}
+
+///
+pub mod convert_to_git {
+    /// The error returned by [`Pipeline::convert_to_git()`][crate::Pipeline::convert_to_git()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the source data")]
+        ReadSource(#[source] std::io::Error),
+        #[error(transparent)]
+        Driver(#[from] Box<crate::driver::apply::Error>),
+        #[error("Could not read the output of the filter driver")]
+        ReadDriverOutput(#[source] std::io::Error),
+        #[error(transparent)]
+        Encoding(#[from] crate::worktree::encode_to_git::Error),
+        #[error(transparent)]
+        Eol(#[from] crate::eol::convert_to_git::Error),
+    }
+}
+
+///
+pub mod convert_to_worktree {
+    /// The error returned by [`Pipeline::convert_to_worktree()`][crate::Pipeline::convert_to_worktree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Encoding(#[from] crate::worktree::encode_to_worktree::Error),
+        #[error(transparent)]
+        Driver(#[from] Box<crate::driver::apply::Error>),
+        #[error("Could not read the output of the filter driver")]
+        ReadDriverOutput(#[source] std::io::Error),
+    }
+}
+
+/// Lifecycle
+impl Pipeline {
+    /// Create a new pipeline with all `drivers` that are configured for use with the `filter` attribute, along with `options`.
+    pub fn new(drivers: Vec<Driver>, options: Options) -> Self {
+        Pipeline {
+            drivers,
+            options,
+            processes: Default::default(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Return the options this instance was created with.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Return the state of all running filter processes, for instance to [shut them down][driver::State::shutdown()].
+    pub fn driver_state_mut(&mut self) -> &mut driver::State {
+        &mut self.processes
+    }
+}
+
+/// Conversions
+impl Pipeline {
+    /// Convert the worktree content read from `src` of the file at repository-relative `rela_path` with `attributes` into
+    /// the content that should be stored in `git`, and place it into `out`.
+    ///
+    /// `index_object` is called to write the current version of `rela_path` as stored in the index into the given buffer, or
+    /// to return `Ok(None)` if there is no such object. It's used to avoid converting files in auto-mode that were checked in
+    /// with `CRLF` line endings.
+    ///
+    /// The filters are applied in the order `git` uses: the `clean` driver, the working-tree-encoding, line-endings and `ident`.
+    pub fn convert_to_git<E>(
+        &mut self,
+        mut src: impl std::io::Read,
+        rela_path: &BStr,
+        attributes: &Attributes,
+        index_object: impl FnOnce(&BStr, &mut Vec<u8>) -> Result<Option<()>, E>,
+        out: &mut Vec<u8>,
+    ) -> Result<(), convert_to_git::Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        use convert_to_git::Error;
+        out.clear();
+        match driver_by_name(&self.drivers, attributes.driver.as_ref()) {
+            Some(driver) => {
+                let ctx = driver::apply::Context {
+                    rela_path,
+                    ref_name: None,
+                    treeish: None,
+                    blob: None,
+                };
+                match self
+                    .processes
+                    .apply(driver, &mut src, driver::Operation::Clean, ctx)
+                    .map_err(Box::new)?
+                {
+                    Some(mut filtered) => filtered.read_to_end(out).map_err(Error::ReadDriverOutput)?,
+                    None => src.read_to_end(out).map_err(Error::ReadSource)?,
+                };
+            }
+            None => {
+                src.read_to_end(out).map_err(Error::ReadSource)?;
+            }
+        }
+
+        if let Some(encoding) = attributes.encoding {
+            worktree::encode_to_git(
+                out,
+                encoding,
+                &mut self.buf,
+                worktree::encode_to_git::RoundTrip::Validate,
+            )?;
+            std::mem::swap(out, &mut self.buf);
+        }
+
+        let path = gix_path::from_bstr(rela_path);
+        let round_trip_check = match self.options.crlf_roundtrip_check {
+            CrlfRoundTripCheck::Fail => Some(eol::convert_to_git::RoundTripCheck::Fail { rela_path: &path }),
+            CrlfRoundTripCheck::Warn => Some(eol::convert_to_git::RoundTripCheck::Warn { rela_path: &path }),
+            CrlfRoundTripCheck::Skip => None,
+        };
+        if eol::convert_to_git(
+            out,
+            attributes.eol,
+            &mut self.buf,
+            |buf| index_object(rela_path, buf),
+            eol::convert_to_git::Context {
+                round_trip_check,
+                config: self.options.eol_config,
+            },
+        )? {
+            std::mem::swap(out, &mut self.buf);
+        }
+
+        if attributes.ident && ident::undo(out, &mut self.buf) {
+            std::mem::swap(out, &mut self.buf);
+        }
+        Ok(())
+    }
+
+    /// Convert `src`, the content of the file at repository-relative `rela_path` with `attributes` as stored in `git`,
+    /// into the content that should be placed into the worktree, and place it into `out`.
+    ///
+    /// The filters are applied in the order `git` uses: `ident`, line-endings, the working-tree-encoding and the `smudge` driver.
+    pub fn convert_to_worktree(
+        &mut self,
+        src: &[u8],
+        rela_path: &BStr,
+        attributes: &Attributes,
+        out: &mut Vec<u8>,
+    ) -> Result<(), convert_to_worktree::Error> {
+        use convert_to_worktree::Error;
+        out.clear();
+        out.extend_from_slice(src);
+
+        if attributes.ident && ident::apply(out, self.options.object_hash, &mut self.buf) {
+            std::mem::swap(out, &mut self.buf);
+        }
+
+        if eol::convert_to_worktree(out, attributes.eol, &mut self.buf, self.options.eol_config) {
+            std::mem::swap(out, &mut self.buf);
+        }
+
+        if let Some(encoding) = attributes.encoding {
+            worktree::encode_to_worktree(out, encoding, &mut self.buf)?;
+            std::mem::swap(out, &mut self.buf);
+        }
+
+        if let Some(driver) = driver_by_name(&self.drivers, attributes.driver.as_ref()) {
+            let ctx = driver::apply::Context {
+                rela_path,
+                ref_name: None,
+                treeish: None,
+                blob: None,
+            };
+            if let Some(mut filtered) = self
+                .processes
+                .apply(driver, out.as_slice(), driver::Operation::Smudge, ctx)
+                .map_err(Box::new)?
+            {
+                self.buf.clear();
+                filtered.read_to_end(&mut self.buf).map_err(Error::ReadDriverOutput)?;
+                drop(filtered);
+                std::mem::swap(out, &mut self.buf);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn driver_by_name<'a>(drivers: &'a [Driver], name: Option<&BString>) -> Option<&'a Driver> {
+    let name = name?;
+    drivers.iter().find(|d| &d.name == name)
+}
@@ -0,0 +1,125 @@
+use bstr::ByteSlice;
+use gix_filter::{
+    eol,
+    pipeline::{Attributes, Options},
+    Pipeline,
+};
+
+fn attributes(assignments: &str, auto_crlf: Option<bool>) -> Attributes {
+    Attributes::from_assignments(
+        gix_attributes::parse::Iter::new(assignments.into()).map(|a| a.expect("valid")),
+        eol::Configuration { auto_crlf, eol: None },
+    )
+    .expect("valid encoding")
+}
+
+fn no_index_object(_path: &bstr::BStr, _buf: &mut Vec<u8>) -> Result<Option<()>, std::convert::Infallible> {
+    Ok(None)
+}
+
+mod attributes {
+    use gix_filter::eol::AttributesDigest;
+
+    use crate::pipeline::attributes;
+
+    #[test]
+    fn eol_digest() {
+        for (input, auto_crlf, expected) in [
+            ("", Some(false), AttributesDigest::Binary),
+            ("", Some(true), AttributesDigest::TextAutoCrlf),
+            ("", None, AttributesDigest::TextAutoInput),
+            ("text", Some(false), AttributesDigest::Text),
+            ("-text", Some(true), AttributesDigest::Binary),
+            ("text=auto", Some(false), AttributesDigest::TextAuto),
+            ("text=auto eol=lf", Some(false), AttributesDigest::TextAutoInput),
+            ("text=auto eol=crlf", Some(false), AttributesDigest::TextAutoCrlf),
+            ("text eol=crlf", Some(false), AttributesDigest::TextCrlf),
+            ("eol=lf", Some(true), AttributesDigest::TextInput),
+            ("-text eol=crlf", Some(false), AttributesDigest::Binary),
+            ("crlf", Some(false), AttributesDigest::Text),
+            ("crlf=input", Some(false), AttributesDigest::TextInput),
+            ("-crlf", Some(true), AttributesDigest::Binary),
+            ("text -crlf", Some(false), AttributesDigest::Text),
+        ] {
+            assert_eq!(attributes(input, auto_crlf).eol, expected, "{input:?} {auto_crlf:?}");
+        }
+    }
+
+    #[test]
+    fn driver_ident_and_encoding() {
+        let attrs = attributes("filter=lfs ident working-tree-encoding=ISO-8859-1", Some(false));
+        assert_eq!(attrs.driver.as_ref().map(|n| n.as_slice()), Some(&b"lfs"[..]));
+        assert!(attrs.ident);
+        assert_eq!(attrs.encoding, Some(encoding_rs::WINDOWS_1252));
+
+        let attrs = attributes("-ident working-tree-encoding=utf-8", Some(false));
+        assert!(!attrs.ident);
+        assert_eq!(attrs.encoding, None, "UTF-8 means no conversion is needed");
+    }
+
+    #[test]
+    fn unknown_encodings_are_an_error() {
+        assert!(gix_filter::pipeline::Attributes::from_assignments(
+            gix_attributes::parse::Iter::new("working-tree-encoding=foo".into()).map(|a| a.expect("valid")),
+            Default::default(),
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn round_trip_with_eol_and_ident() -> crate::Result {
+    let mut pipeline = Pipeline::new(Vec::new(), Options::default());
+    let attrs = attributes("text eol=crlf ident", Some(false));
+
+    let mut out = Vec::new();
+    pipeline.convert_to_git(
+        &b"a\r\n$Id: something$\r\n"[..],
+        "file".into(),
+        &attrs,
+        no_index_object,
+        &mut out,
+    )?;
+    assert_eq!(
+        out.as_bstr(),
+        "a\n$Id$\n",
+        "line endings are normalized, and ident undone"
+    );
+
+    let mut worktree = Vec::new();
+    pipeline.convert_to_worktree(&out, "file".into(), &attrs, &mut worktree)?;
+    assert_eq!(
+        worktree.as_bstr(),
+        "a\r\n$Id: 0667178464fa6a45884c8d52229dd83ec37e8d9f$\r\n",
+        "the ident is expanded with the hash of the data in git"
+    );
+    Ok(())
+}
+
+#[test]
+fn binary_content_is_left_alone() -> crate::Result {
+    let mut pipeline = Pipeline::new(Vec::new(), Options::default());
+    let attrs = attributes("-text", Some(true));
+    let mut out = Vec::new();
+    pipeline.convert_to_git(&b"a\r\n"[..], "file".into(), &attrs, no_index_object, &mut out)?;
+    assert_eq!(out.as_bstr(), "a\r\n");
+    pipeline.convert_to_worktree(b"a\n", "file".into(), &attrs, &mut out)?;
+    assert_eq!(out.as_bstr(), "a\n");
+    Ok(())
+}
+
+#[test]
+fn round_trip_check_can_fail() {
+    let mut pipeline = Pipeline::new(
+        Vec::new(),
+        Options {
+            crlf_roundtrip_check: gix_filter::pipeline::CrlfRoundTripCheck::Fail,
+            ..Default::default()
+        },
+    );
+    let attrs = attributes("text eol=lf", Some(false));
+    let mut out = Vec::new();
+    assert!(pipeline
+        .convert_to_git(&b"a\r\n"[..], "file".into(), &attrs, no_index_object, &mut out)
+        .is_err());
+}
@@ -53,3 +53,18 @@ pub struct Stack {
 
 ///
 pub mod stack;
+
+/// Returns whether a file with `metadata` has the executable permission set, which is never the case on
+/// platforms without such a permission.
+#[cfg(unix)]
+pub fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.mode() & 0o100) != 0
+}
+
+/// Returns whether a file with `metadata` has the executable permission set, which is never the case on
+/// platforms without such a permission.
+#[cfg(not(unix))]
+pub fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
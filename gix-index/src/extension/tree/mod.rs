@@ -1,4 +1,6 @@
-use crate::extension::Signature;
+use bstr::{BStr, ByteSlice};
+
+use crate::extension::{Signature, Tree};
 
 /// The signature for tree extensions
 pub const SIGNATURE: Signature = *b"TREE";
@@ -11,8 +13,60 @@ pub use decode::decode;
 
 mod write;
 
+/// Mutation
+impl Tree {
+    /// Mark this tree and all of its children leading up to the repository-relative `path` of a changed entry as invalid,
+    /// causing them to be recomputed when trees are written from the index next time.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        self.num_entries = None;
+        let mut tree = self;
+        let mut components = path.split_str("/").peekable();
+        while let Some(component) = components.next() {
+            if components.peek().is_none() {
+                break;
+            }
+            match tree.children.iter_mut().find(|c| c.name.as_slice() == component) {
+                Some(child) => {
+                    child.num_entries = None;
+                    tree = child;
+                }
+                None => break,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use gix_hash::ObjectId;
+
+    use crate::extension::Tree;
+
+    fn tree(name: &str, children: Vec<Tree>) -> Tree {
+        Tree {
+            name: name.as_bytes().into(),
+            id: ObjectId::null(gix_hash::Kind::Sha1),
+            num_entries: Some(1),
+            children,
+        }
+    }
+
+    #[test]
+    fn invalidate_path() {
+        let mut root = tree("", vec![tree("a", vec![tree("b", vec![])]), tree("c", vec![])]);
+        root.invalidate_path("a/file".into());
+        assert_eq!(root.num_entries, None);
+        assert_eq!(
+            root.children[0].num_entries, None,
+            "the parent of the file is invalidated"
+        );
+        assert_eq!(
+            root.children[0].children[0].num_entries,
+            Some(1),
+            "trees below the file are unaffected"
+        );
+        assert_eq!(root.children[1].num_entries, Some(1), "siblings are unaffected");
+    }
 
     #[test]
     fn size_of_tree() {
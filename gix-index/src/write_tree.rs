@@ -0,0 +1,181 @@
+use std::ops::Range;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{entry, extension, Entry, State};
+
+/// The error returned by [`State::write_tree()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(
+        "The entry at '{path}' is unmerged and has stage {stage}, a tree can only be written from a fully merged index"
+    )]
+    Unmerged { path: BString, stage: entry::Stage },
+    #[error("The entry at '{path}' has an invalid mode: {mode:?}")]
+    InvalidMode { path: BString, mode: entry::Mode },
+    #[error("Could not serialize tree object")]
+    Encode(#[from] std::io::Error),
+    #[error("Could not write tree object")]
+    WriteTree(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Tree creation
+impl State {
+    /// Produce tree objects from all entries in this index, calling `write` for each tree that needs to be stored,
+    /// and return the id of the root tree.
+    ///
+    /// `write` receives the tree to store and returns its id, which must be the same as the id computed from the tree's
+    /// serialization.
+    /// Trees that are recorded as valid in the `TREE` cache extension and whose computed id didn't change aren't passed
+    /// to `write` as they are assumed to exist in the object database already.
+    /// Afterwards, the `TREE` extension is updated to match the trees that were produced, making subsequent calls cheaper.
+    ///
+    /// Entries marked for removal are ignored, just like entries that were added with `--intent-to-add`, which also
+    /// invalidates the cache for the trees containing them, similar to what `git` does.
+    /// If an entry has a non-zero stage, the index is unmerged and an error is returned.
+    pub fn write_tree<E>(
+        &mut self,
+        mut write: impl FnMut(&gix_object::Tree) -> Result<ObjectId, E>,
+    ) -> Result<ObjectId, Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let _span = gix_features::trace::coarse!("gix_index::State::write_tree()");
+        if let Some(entry) = self.entries.iter().find(|e| e.stage() != 0) {
+            return Err(Error::Unmerged {
+                path: entry.path(self).to_owned(),
+                stage: entry.stage(),
+            });
+        }
+
+        let mut buf = Vec::new();
+        let previous = self.tree.take();
+        let tree = one_recursive(
+            &self.entries,
+            &self.path_backing,
+            0..self.entries.len(),
+            0,
+            "".into(),
+            previous.as_ref(),
+            self.object_hash,
+            &mut buf,
+            &mut write,
+        )?;
+        let id = tree.id;
+        self.tree = Some(tree);
+        Ok(id)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn one_recursive<E>(
+    entries: &[Entry],
+    path_backing: &crate::PathStorageRef,
+    range: Range<usize>,
+    prefix_len: usize,
+    name: &BStr,
+    cached: Option<&extension::Tree>,
+    object_hash: gix_hash::Kind,
+    buf: &mut Vec<u8>,
+    write: &mut impl FnMut(&gix_object::Tree) -> Result<ObjectId, E>,
+) -> Result<extension::Tree, Error>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut tree = gix_object::Tree::empty();
+    let mut children = Vec::new();
+    let mut num_entries = 0u32;
+    let mut is_valid = true;
+
+    let mut idx = range.start;
+    while idx < range.end {
+        let entry = &entries[idx];
+        let path = entry.path_in(path_backing);
+        let relative_path = &path[prefix_len..];
+        match relative_path.find_byte(b'/') {
+            Some(slash) => {
+                let dir_name = &relative_path[..slash];
+                let dir_prefix_len = prefix_len + slash + 1;
+                let dir_prefix = &path[..dir_prefix_len];
+                // Sorting guarantees that all entries of a directory are adjacent.
+                let end = entries[idx..range.end]
+                    .iter()
+                    .position(|e| !e.path_in(path_backing).starts_with(dir_prefix))
+                    .map_or(range.end, |pos| idx + pos);
+                let cached_child = cached.and_then(|c| c.children.iter().find(|c| c.name.as_slice() == dir_name));
+                let child = one_recursive(
+                    entries,
+                    path_backing,
+                    idx..end,
+                    dir_prefix_len,
+                    dir_name.as_bstr(),
+                    cached_child,
+                    object_hash,
+                    buf,
+                    write,
+                )?;
+                match child.num_entries {
+                    Some(count) => num_entries += count,
+                    None => is_valid = false,
+                }
+                if !child.id.is_null() {
+                    tree.entries.push(gix_object::tree::Entry {
+                        mode: gix_object::tree::EntryMode::Tree,
+                        filename: dir_name.into(),
+                        oid: child.id,
+                    });
+                }
+                children.push(child);
+                idx = end;
+            }
+            None => {
+                idx += 1;
+                if entry.flags.contains(entry::Flags::REMOVE) {
+                    continue;
+                }
+                if entry.flags.contains(entry::Flags::INTENT_TO_ADD) {
+                    is_valid = false;
+                    continue;
+                }
+                let mode = entry.mode.to_tree_entry_mode().ok_or_else(|| Error::InvalidMode {
+                    path: path.to_owned(),
+                    mode: entry.mode,
+                })?;
+                num_entries += 1;
+                tree.entries.push(gix_object::tree::Entry {
+                    mode,
+                    filename: relative_path.into(),
+                    oid: entry.id,
+                });
+            }
+        }
+    }
+
+    tree.entries.sort();
+    let id = if tree.entries.is_empty() && prefix_len != 0 {
+        // Directories without any entry left don't make it into the tree.
+        ObjectId::null(object_hash)
+    } else {
+        use gix_object::WriteTo;
+        buf.clear();
+        tree.write_to(&mut *buf)?;
+        let id = gix_object::compute_hash(object_hash, gix_object::Kind::Tree, buf);
+        let is_cached = cached.map_or(false, |c| c.num_entries.is_some() && c.id == id);
+        if is_cached {
+            id
+        } else {
+            write(&tree).map_err(|err| Error::WriteTree(err.into()))?
+        }
+    };
+
+    children.retain(|c| !c.id.is_null());
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(extension::Tree {
+        name: name.as_bytes().into(),
+        id,
+        num_entries: is_valid.then_some(num_entries),
+        children,
+    })
+}
@@ -0,0 +1,96 @@
+use gix::prelude::FindExt;
+use gix_index::State;
+use gix_testtools::scripted_fixture_read_only_standalone;
+
+fn hash_tree(tree: &gix::objs::Tree) -> Result<gix_hash::ObjectId, std::io::Error> {
+    use gix::objs::WriteTo;
+    let mut buf = Vec::new();
+    tree.write_to(&mut buf)?;
+    Ok(gix::objs::compute_hash(
+        gix_hash::Kind::Sha1,
+        gix::objs::Kind::Tree,
+        &buf,
+    ))
+}
+
+fn num_trees(tree: &gix_index::extension::Tree) -> usize {
+    1 + tree.children.iter().map(num_trees).sum::<usize>()
+}
+
+#[test]
+fn matches_head_tree_and_tree_extension() -> crate::Result {
+    for fixture in [
+        "make_index/v2.sh",
+        "make_index/v2_more_files.sh",
+        "make_index/v2_all_file_kinds.sh",
+        "make_index/v4_more_files_IEOT.sh",
+    ] {
+        let repo = gix::open(scripted_fixture_read_only_standalone(fixture)?)?;
+        let expected_tree = repo.head_commit()?.tree_id()?;
+        let mut index = repo.open_index()?;
+        let expected_extension = index.tree().cloned().expect("git writes the tree cache on commit");
+
+        let mut writes = 0;
+        let actual = index.write_tree(|tree| {
+            writes += 1;
+            hash_tree(tree)
+        })?;
+        assert_eq!(actual, expected_tree, "{fixture}");
+        assert_eq!(
+            writes, 0,
+            "{fixture}: the tree cache is valid, nothing needs to be written"
+        );
+        assert_eq!(index.tree(), Some(&expected_extension), "{fixture}");
+
+        writes = 0;
+        let mut state = State::from_tree(&expected_tree, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?;
+        assert!(state.tree().is_none(), "there is no tree cache yet");
+        let actual = state.write_tree(|tree| {
+            writes += 1;
+            hash_tree(tree)
+        })?;
+        assert_eq!(actual, expected_tree, "{fixture}");
+        assert_eq!(
+            writes,
+            num_trees(&expected_extension),
+            "{fixture}: without cache, all trees are written"
+        );
+        assert_eq!(
+            state.tree(),
+            Some(&expected_extension),
+            "{fixture}: the tree cache is created just like git does it"
+        );
+        state.verify_extensions(true, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?;
+    }
+    Ok(())
+}
+
+#[test]
+fn intent_to_add_entries_are_skipped_and_invalidate_the_cache() -> crate::Result {
+    let mut index = crate::index::Fixture::Generated("v3_added_files").open();
+    let id = index.write_tree(hash_tree)?;
+    assert_eq!(id, gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha1));
+    let tree = index.tree().expect("set");
+    assert_eq!(
+        tree.num_entries, None,
+        "intent-to-add entries cause the tree to be invalid"
+    );
+    assert_eq!(tree.id, id, "but the tree is still produced");
+    Ok(())
+}
+
+#[test]
+fn unmerged_entries_cause_an_error() {
+    let mut state = State::new(gix_hash::Kind::Sha1);
+    state.dangerously_push_entry(
+        Default::default(),
+        gix_hash::Kind::Sha1.null(),
+        gix_index::entry::Flags::from_bits_retain(1 << 12),
+        gix_index::entry::Mode::FILE,
+        "a".into(),
+    );
+    assert!(matches!(
+        state.write_tree(hash_tree),
+        Err(gix_index::write_tree::Error::Unmerged { stage: 1, .. })
+    ));
+}
@@ -1,4 +1,4 @@
-use bstr::{BStr, ByteSlice};
+use bstr::{BStr, BString, ByteSlice};
 
 use crate::{Commit, CommitRef, TagRef};
 
@@ -73,6 +73,46 @@ impl<'a> CommitRef<'a> {
     }
 }
 
+/// Split the encoded commit in `data` into the signature stored in its `gpgsig` header and the data that was signed,
+/// which is `data` without that header, or return `None` if the commit isn't signed.
+///
+/// The signature is returned with the leading space of its continuation lines removed, and both are suitable for passing
+/// them to a program that verifies the signature.
+pub fn extract_signature(data: &[u8]) -> Option<(BString, BString)> {
+    const HEADER: &[u8] = b"gpgsig ";
+    let mut signature = None::<BString>;
+    let mut payload = BString::from(Vec::with_capacity(data.len()));
+    let mut in_signature = false;
+    let mut lines = data.lines_with_terminator();
+    for line in lines.by_ref() {
+        if line == b"\n" {
+            payload.extend_from_slice(line);
+            break;
+        }
+        if in_signature {
+            if let Some(continuation) = line.strip_prefix(b" ") {
+                signature
+                    .as_mut()
+                    .expect("set when in signature")
+                    .extend_from_slice(continuation);
+                continue;
+            }
+            in_signature = false;
+        }
+        match line.strip_prefix(HEADER) {
+            Some(value) if signature.is_none() => {
+                signature = Some(value.into());
+                in_signature = true;
+            }
+            _ => payload.extend_from_slice(line),
+        }
+    }
+    for line in lines {
+        payload.extend_from_slice(line);
+    }
+    signature.map(|signature| (signature, payload))
+}
+
 impl Commit {
     /// Returns a convenient iterator over all extra headers.
     pub fn extra_headers(&self) -> ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
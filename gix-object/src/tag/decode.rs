@@ -1,10 +1,8 @@
 use nom::{
-    branch::alt,
-    bytes::complete::{tag, take_until, take_while, take_while1},
+    bytes::complete::{tag, take_while1},
     character::is_alphabetic,
-    combinator::{all_consuming, opt, recognize},
+    combinator::{all_consuming, opt},
     error::{context, ContextError, ParseError},
-    sequence::{preceded, tuple},
     IResult,
 };
 
@@ -44,47 +42,31 @@ pub fn git_tag<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(i: &'a [u8]
 }
 
 pub fn message<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], (&'a BStr, Option<&'a BStr>), E> {
-    const PGP_SIGNATURE_BEGIN: &[u8] = b"\n-----BEGIN PGP SIGNATURE-----";
-    const PGP_SIGNATURE_END: &[u8] = b"-----END PGP SIGNATURE-----";
+    /// The beginning and end of all signatures we know, for OpenPGP, SSH and X.509 respectively.
+    const SIGNATURES: &[(&[u8], &[u8])] = &[
+        (b"\n-----BEGIN PGP SIGNATURE-----", b"-----END PGP SIGNATURE-----"),
+        (b"\n-----BEGIN SSH SIGNATURE-----", b"-----END SSH SIGNATURE-----"),
+        (b"\n-----BEGIN SIGNED MESSAGE-----", b"-----END SIGNED MESSAGE-----"),
+    ];
 
     if i.is_empty() {
         return Ok((i, (i.as_bstr(), None)));
     }
     let (i, _) = tag(NL)(i)?;
-    fn all_to_end<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], (&'a [u8], &'a [u8]), E> {
-        if i.is_empty() {
-            // Empty message. That's OK.
-            return Ok((&[], (&[], &[])));
-        }
-        // an empty signature message signals that there is none - the function signature is needed
-        // to work with 'alt(…)'. PGP signatures are never empty
-        Ok((&[], (i, &[])))
-    }
-    let (i, (message, signature)) = alt((
-        tuple((
-            take_until(PGP_SIGNATURE_BEGIN),
-            preceded(
-                tag(NL),
-                recognize(tuple((
-                    tag(&PGP_SIGNATURE_BEGIN[1..]),
-                    take_until(PGP_SIGNATURE_END),
-                    tag(PGP_SIGNATURE_END),
-                    take_while(|_| true),
-                ))),
-            ),
-        )),
-        all_to_end,
-    ))(i)?;
-    let (i, _) = opt(tag(NL))(i)?;
+    // Like `git`, use the last signature as the message may quote others.
+    let signature_start = SIGNATURES
+        .iter()
+        .filter_map(|(begin, end)| {
+            let start = i.rfind(begin)?;
+            i[start..].find(end)?;
+            Some(start)
+        })
+        .max();
     Ok((
-        i,
-        (
-            message.as_bstr(),
-            if signature.is_empty() {
-                None
-            } else {
-                Some(signature.as_bstr())
-            },
-        ),
+        &[],
+        match signature_start {
+            Some(start) => (i[..start].as_bstr(), Some(i[start + 1..].as_bstr())),
+            None => (i.as_bstr(), None),
+        },
     ))
 }
@@ -146,6 +146,53 @@ mod method {
     }
 }
 
+mod extract_signature {
+    use gix_object::{bstr::ByteSlice, commit::extract_signature};
+    use pretty_assertions::assert_eq;
+
+    use crate::fixture_name;
+
+    #[test]
+    fn unsigned() {
+        assert_eq!(extract_signature(&fixture_name("commit", "unsigned.txt")), None);
+    }
+
+    #[test]
+    fn signed() {
+        let fixture = fixture_name("commit", "signed-whitespace.txt");
+        let (signature, payload) = extract_signature(&fixture).expect("signed");
+        assert!(signature.starts_with(b"-----BEGIN PGP SIGNATURE-----\n\nwsBcBAAB"));
+        assert!(
+            signature.ends_with(b"\n-----END PGP SIGNATURE-----\n\n"),
+            "the leading space of continuation lines is removed, even if it's the only character"
+        );
+        assert_eq!(
+            payload.lines().take(6).map(ByteSlice::as_bstr).collect::<Vec<_>>(),
+            [
+                "tree aa32bbb2b635217db09218da0d9d4db872ddfa0b",
+                "parent eece58a8e35c444afba6fa34873bc0244e32cd29",
+                "parent cb9458d3ff7f64c309bc80776d71e4f73705f4ce",
+                "author Dylan DPC <dylan.dpc@gmail.com> 1588270520 +0200",
+                "committer GitHub <noreply@github.com> 1588270520 +0200",
+                "",
+            ]
+        );
+        assert_eq!(
+            payload.len() + signature.lines_with_terminator().count() + b"gpgsig".len() + signature.len(),
+            fixture.len(),
+            "everything else is kept verbatim"
+        );
+    }
+
+    #[test]
+    fn signed_with_encoding() {
+        let fixture = fixture_name("commit", "signed-with-encoding.txt");
+        let (_signature, payload) = extract_signature(&fixture).expect("signed");
+        assert!(payload.lines().any(|line| line.starts_with(b"encoding ")));
+        assert!(!payload.contains_str("gpgsig"));
+    }
+}
+
 mod from_bytes;
 mod iter;
 mod message;
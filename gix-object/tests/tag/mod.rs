@@ -129,6 +129,36 @@ mod from_bytes {
         Ok(())
     }
 
+    #[test]
+    fn ssh_signed() -> crate::Result {
+        let fixture = fixture_name("tag", "ssh-signed.txt");
+        let tag = TagRef::from_bytes(&fixture)?;
+        assert_eq!(tag.message, "signed with ssh");
+        let signature = tag.pgp_signature.expect("ssh signatures are detected as well");
+        assert!(signature.starts_with(b"-----BEGIN SSH SIGNATURE-----\n"));
+        assert!(signature.ends_with(b"-----END SSH SIGNATURE-----\n"));
+        assert!(
+            fixture.ends_with(signature),
+            "the signature is the last part of the tag, and everything before it is signed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn signature_quoted_in_message() -> crate::Result {
+        let fixture = fixture_name("tag", "quoted-signature.txt");
+        let tag = TagRef::from_bytes(&fixture)?;
+        assert_eq!(
+            tag.message,
+            "a message quoting a signature\n-----BEGIN PGP SIGNATURE-----\nquoted\n-----END PGP SIGNATURE-----",
+            "like git, only the last signature is the one of the tag"
+        );
+        let signature = tag.pgp_signature.expect("signed");
+        assert!(signature.starts_with(b"-----BEGIN SSH SIGNATURE-----\n"));
+        assert!(fixture.ends_with(signature));
+        Ok(())
+    }
+
     #[test]
     fn empty() -> crate::Result {
         assert_eq!(
@@ -131,6 +131,8 @@ pub struct Store {
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
     object_hash: gix_hash::Kind,
+    /// If set, objects that can't be found will be fetched with it.
+    pub(crate) promisor: Option<Arc<dyn store::Promisor>>,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
@@ -21,4 +21,9 @@ impl Store {
     pub fn replacements(&self) -> impl Iterator<Item = (gix_hash::ObjectId, gix_hash::ObjectId)> + '_ {
         self.replacements.iter().copied()
     }
+
+    /// The promisor to fetch missing objects with, if one is set.
+    pub fn promisor(&self) -> Option<&dyn super::Promisor> {
+        self.promisor.as_deref()
+    }
 }
@@ -26,6 +26,8 @@ pub(crate) mod error {
             /// The original object to lookup
             id: gix_hash::ObjectId,
         },
+        #[error("Could not fetch a missing object from the promisor")]
+        Promisor(#[source] crate::store::promisor::Error),
         #[error("The base object {} could not be found but is required to decode {}", .base_id, .id)]
         DeltaBaseMissing {
             /// the id of the base object which failed to lookup
@@ -107,6 +109,7 @@ where
             }
         }
 
+        let mut fetched_from_promisor = false;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -292,7 +295,12 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return Ok(None),
+                None => {
+                    if recursion.is_none() && self.fetch_from_promisor(id, snapshot, &mut fetched_from_promisor)? {
+                        continue 'outer;
+                    }
+                    return Ok(None);
+                }
             }
         }
     }
@@ -35,6 +35,7 @@ where
             }
         }
 
+        let mut fetched_from_promisor = false;
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -169,7 +170,12 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return Ok(None),
+                None => {
+                    if recursion.is_none() && self.fetch_from_promisor(id, snapshot, &mut fetched_from_promisor)? {
+                        continue 'outer;
+                    }
+                    return Ok(None);
+                }
             }
         }
     }
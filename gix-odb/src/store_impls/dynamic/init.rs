@@ -19,6 +19,8 @@ pub struct Options {
     /// The current directory of the process at the time of instantiation.
     /// If unset, it will be retrieved using `std::env::current_dir()`.
     pub current_dir: Option<std::path::PathBuf>,
+    /// If set, objects that can't be found will be fetched with the promisor before giving up, as needed in partial clones.
+    pub promisor: Option<Arc<dyn super::Promisor>>,
 }
 
 impl Default for Options {
@@ -28,6 +30,7 @@ impl Default for Options {
             object_hash: Default::default(),
             use_multi_pack_index: true,
             current_dir: None,
+            promisor: None,
         }
     }
 }
@@ -77,6 +80,7 @@ impl Store {
             object_hash,
             use_multi_pack_index,
             current_dir,
+            promisor,
         }: Options,
     ) -> std::io::Result<Self> {
         let _span = gix_features::trace::detail!("gix_odb::Store::at()");
@@ -122,6 +126,7 @@ impl Store {
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
+            promisor,
         })
     }
 }
@@ -22,6 +22,9 @@ where
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
 
+    /// If true, objects that can't be found won't be fetched from the [promisor](Promisor) of the store, even if it has one.
+    pub skip_promisor: bool,
+
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     packed_object_count: RefCell<Option<u64>>,
@@ -58,6 +61,10 @@ mod header;
 ///
 pub mod iter;
 
+///
+pub mod promisor;
+pub use promisor::Promisor;
+
 ///
 pub mod write;
 
@@ -0,0 +1,86 @@
+use std::ops::Deref;
+
+use crate::{
+    store::{find, load_index, RefreshMode},
+    Find,
+};
+
+/// The error returned by [`Promisor::fetch()`].
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A way to obtain objects that are missing locally, like from the promisor remote of a partial clone.
+///
+/// If one is set in the [store options](crate::store::init::Options::promisor), it's used to fetch objects that can't be found,
+/// which are then expected to be available on disk.
+pub trait Promisor: std::fmt::Debug + Send + Sync {
+    /// Make all objects with `ids` available in the object database, typically by writing a new pack.
+    ///
+    /// Note that it's not an error if some of the objects don't become available.
+    fn fetch(&self, ids: &[gix_hash::ObjectId]) -> Result<(), Error>;
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Fetch all objects in `ids` that aren't present locally with a single call to the [`Promisor`] of our store,
+    /// and return the amount of objects that were requested.
+    ///
+    /// This is useful to avoid fetching objects one by one when they are accessed, which happens if they are missing
+    /// when looking them up. Nothing is done if there is no promisor.
+    pub fn fetch_missing(&self, ids: impl IntoIterator<Item = gix_hash::ObjectId>) -> Result<usize, Error> {
+        let Some(promisor) = self.store.promisor.as_deref() else {
+            return Ok(0);
+        };
+        let mut missing: Vec<_> = ids.into_iter().filter(|id| !self.contains(id)).collect();
+        missing.sort();
+        missing.dedup();
+        if !missing.is_empty() {
+            promisor.fetch(&missing)?;
+            self.refresh_after_fetch();
+        }
+        Ok(missing.len())
+    }
+
+    /// Ask the promisor for `id` if there is one, if we are allowed to, and if that wasn't done before as indicated by `fetched`,
+    /// and update `snapshot` to include what it wrote.
+    ///
+    /// Return `true` if the lookup should be retried.
+    pub(crate) fn fetch_from_promisor(
+        &self,
+        id: &gix_hash::oid,
+        snapshot: &mut load_index::Snapshot,
+        fetched: &mut bool,
+    ) -> Result<bool, find::Error> {
+        let Some(promisor) = self.store.promisor.as_deref().filter(|_| !self.skip_promisor) else {
+            return Ok(false);
+        };
+        if *fetched {
+            return Ok(false);
+        }
+        *fetched = true;
+        promisor.fetch(&[id.to_owned()]).map_err(find::Error::Promisor)?;
+        // The promisor wrote to disk, so we have to look even if our refresh mode wouldn't allow it.
+        // Loose objects are always visible, so we retry even if there is no new pack.
+        if let Some(new_snapshot) = self
+            .store
+            .load_one_index(RefreshMode::AfterAllIndicesLoaded, snapshot.marker)?
+        {
+            *snapshot = new_snapshot;
+            self.clear_cache();
+        }
+        Ok(true)
+    }
+
+    /// Load all indices including the ones written by the promisor, as lookups may not be allowed to refresh from disk.
+    fn refresh_after_fetch(&self) {
+        let mut snapshot = self.snapshot.borrow_mut();
+        while let Ok(Some(new_snapshot)) = self
+            .store
+            .load_one_index(RefreshMode::AfterAllIndicesLoaded, snapshot.marker)
+        {
+            *snapshot = new_snapshot;
+            self.clear_cache();
+        }
+    }
+}
@@ -936,6 +936,149 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     Ok(())
 }
 
+mod promisor {
+    use std::sync::{Arc, Mutex};
+
+    use gix_hash::ObjectId;
+    use gix_odb::{Find, FindExt, Header, Write};
+    use gix_testtools::fixture_path;
+
+    use crate::hex_to_id;
+
+    /// Copies objects from the fixture database as loose objects into `objects_dir`, and remembers each call.
+    #[derive(Debug)]
+    struct CopyFromFixture {
+        objects_dir: std::path::PathBuf,
+        calls: Mutex<Vec<Vec<ObjectId>>>,
+    }
+
+    impl gix_odb::store::Promisor for CopyFromFixture {
+        fn fetch(&self, ids: &[ObjectId]) -> Result<(), gix_odb::store::promisor::Error> {
+            self.calls.lock().unwrap().push(ids.to_vec());
+            let source = gix_odb::at(fixture_path("objects"))?;
+            let destination = gix_odb::loose::Store::at(&self.objects_dir, gix_hash::Kind::Sha1);
+            let mut buf = Vec::new();
+            for id in ids {
+                if let Some(obj) = source.try_find(id, &mut buf)? {
+                    destination.write_buf(obj.kind, obj.data)?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn partial_db() -> crate::Result<(gix_odb::Handle, Arc<CopyFromFixture>, tempfile::TempDir)> {
+        let dir = tempfile::tempdir()?;
+        let promisor = Arc::new(CopyFromFixture {
+            objects_dir: dir.path().to_owned(),
+            calls: Default::default(),
+        });
+        let handle = gix_odb::at_opts(
+            dir.path(),
+            None,
+            gix_odb::store::init::Options {
+                promisor: Some(promisor.clone()),
+                ..Default::default()
+            },
+        )?;
+        Ok((handle, promisor, dir))
+    }
+
+    #[test]
+    fn missing_objects_are_fetched_on_lookup() -> crate::Result {
+        let (mut handle, promisor, _dir) = partial_db()?;
+        handle.refresh_never();
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        assert!(!handle.contains(id), "checking for existence doesn't fetch");
+        assert!(promisor.calls.lock().unwrap().is_empty());
+
+        let mut buf = Vec::new();
+        let obj = handle.find(id, &mut buf)?;
+        assert_eq!(obj.kind, gix_object::Kind::Commit);
+        assert_eq!(*promisor.calls.lock().unwrap(), vec![vec![id]]);
+
+        let hdr = handle.try_header(id)?.expect("present");
+        assert_eq!(hdr.kind(), gix_object::Kind::Commit);
+        assert_eq!(
+            promisor.calls.lock().unwrap().len(),
+            1,
+            "objects that are present aren't fetched again"
+        );
+
+        let unknown = hex_to_id("0000000000000000000000000000000000000001");
+        assert!(handle.try_find(unknown, &mut buf)?.is_none());
+        assert!(handle.try_header(unknown)?.is_none());
+        assert_eq!(
+            promisor.calls.lock().unwrap().len(),
+            3,
+            "objects the promisor doesn't have are asked for once per lookup"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_are_not_fetched_when_skipping_the_promisor() -> crate::Result {
+        let (mut handle, promisor, _dir) = partial_db()?;
+        handle.skip_promisor = true;
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let mut buf = Vec::new();
+        assert!(handle.try_find(id, &mut buf)?.is_none());
+        assert!(handle.try_header(id)?.is_none());
+        assert!(promisor.calls.lock().unwrap().is_empty());
+
+        handle.fetch_missing(Some(id)).expect("promisor works");
+        assert_eq!(
+            *promisor.calls.lock().unwrap(),
+            vec![vec![id]],
+            "explicit fetches still use the promisor"
+        );
+        assert!(handle.try_find(id, &mut buf)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_missing_asks_for_all_missing_objects_at_once() -> crate::Result {
+        let (handle, promisor, _dir) = partial_db()?;
+        let present = hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542");
+        handle.fetch_missing(Some(present)).expect("promisor works");
+
+        let missing = [
+            hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5"),
+            hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"),
+        ];
+        let num_fetched = handle
+            .fetch_missing(missing.iter().copied().chain(Some(present)).chain(Some(missing[0])))
+            .expect("promisor works");
+        assert_eq!(num_fetched, 2, "present objects and duplicates are ignored");
+        let mut expected = missing.to_vec();
+        expected.sort();
+        assert_eq!(*promisor.calls.lock().unwrap(), vec![vec![present], expected]);
+        for id in missing {
+            assert!(handle.contains(id));
+        }
+
+        assert_eq!(
+            handle.fetch_missing(missing).expect("promisor works"),
+            0,
+            "nothing to do"
+        );
+        assert_eq!(promisor.calls.lock().unwrap().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_missing_does_nothing_without_promisor() -> crate::Result {
+        let handle = gix_odb::at(tempfile::tempdir()?.path())?;
+        assert_eq!(
+            handle
+                .fetch_missing(Some(hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542")))
+                .expect("nothing to fail"),
+            0
+        );
+        Ok(())
+    }
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
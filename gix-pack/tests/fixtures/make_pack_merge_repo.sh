@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo base > base && git add base && git commit -qm base
+
+git checkout -q -b other
+echo other > other && git add other && git commit -qm other
+
+git checkout -q main
+echo main > main && git add main && git commit -qm main
+git merge -q --no-edit other
+
+git rev-parse HEAD > .git/merge-id
@@ -322,6 +322,48 @@ fn traversals() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn tree_additions_of_merge_commits_are_counted_against_all_parents() -> crate::Result {
+    let db = db(DbKind::MergeCommit)?;
+    let merge = crate::scripted_fixture_read_only("make_pack_merge_repo.sh")?
+        .join(".git")
+        .join("merge-id");
+    let merge = gix_hash::ObjectId::from_hex(std::fs::read_to_string(merge)?.trim().as_bytes())?;
+
+    let (counts, _stats) = output::count::objects(
+        db.clone(),
+        std::iter::once(Ok::<_, Infallible>(merge)),
+        progress::Discard,
+        &AtomicBool::new(false),
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+            thread_limit: Some(1),
+            ..Default::default()
+        },
+    )?;
+    let mut buf = Vec::new();
+    let mut blobs = Vec::new();
+    let (mut commits, mut trees) = (0, 0);
+    for count in &counts {
+        let (obj, _location) = db.find(count.id, &mut buf)?;
+        match obj.kind {
+            gix_object::Kind::Commit => commits += 1,
+            gix_object::Kind::Tree => trees += 1,
+            gix_object::Kind::Blob => blobs.push(obj.data.to_owned()),
+            gix_object::Kind::Tag => unreachable!("there are no tags"),
+        }
+    }
+    blobs.sort();
+    assert_eq!(commits, 3, "the merge commit and both of its parents");
+    assert_eq!(trees, 3, "the trees of all commits");
+    assert_eq!(
+        blobs,
+        [b"main\n".to_vec(), b"other\n".to_vec()],
+        "the blob added compared to one parent isn't forgotten when comparing to the next one"
+    );
+    Ok(())
+}
+
 #[test]
 fn empty_pack_is_allowed() {
     write_and_verify(
@@ -23,6 +23,7 @@ fn size_of_count() {
 enum DbKind {
     DeterministicGeneratedContent,
     DeterministicGeneratedContentMultiIndex,
+    MergeCommit,
 }
 
 fn db(kind: DbKind) -> crate::Result<gix_odb::HandleArc> {
@@ -30,6 +31,7 @@ fn db(kind: DbKind) -> crate::Result<gix_odb::HandleArc> {
     let name = match kind {
         DeterministicGeneratedContent => "make_pack_gen_repo.sh",
         DeterministicGeneratedContentMultiIndex => "make_pack_gen_repo_multi_index.sh",
+        MergeCommit => "make_pack_merge_repo.sh",
     };
     let path: PathBuf = crate::scripted_fixture_read_only(name)?.join(".git").join("objects");
     gix_odb::Store::at_opts(path, Vec::new(), gix_odb::store::init::Options::default())
@@ -9,6 +9,27 @@ use bstr::BString;
 ///
 pub mod parse;
 
+///
+pub mod normalize {
+    use std::path::PathBuf;
+
+    /// The error returned by [Pattern::normalize()][super::Pattern::normalize()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The path '{}' is not inside of the worktree '{}'", path.display(), worktree_path.display())]
+        AbsolutePathOutsideOfWorktree { path: PathBuf, worktree_path: PathBuf },
+        #[error("The path '{}' leaves the repository", path.display())]
+        OutsideOfWorktree { path: PathBuf },
+    }
+}
+
+mod pattern;
+
+///
+pub mod search;
+pub use search::Search;
+
 /// The output of a pathspec [parsing][parse()] operation. It can be used to match against a one or more paths.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub struct Pattern {
@@ -24,6 +45,9 @@ pub struct Pattern {
     ///
     /// `:(attr:a=one b=):path` would yield attribute `a` and `b`.
     pub attributes: Vec<gix_attributes::Assignment>,
+    /// The amount of bytes at the beginning of `path` that were added by [normalizing][Pattern::normalize()] it with
+    /// the prefix, i.e. the current working directory relative to the root of the worktree.
+    pub(crate) prefix_len: usize,
 }
 
 bitflags! {
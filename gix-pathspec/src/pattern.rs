@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::{normalize, MagicSignature, MatchMode, Pattern};
+
+/// Access
+impl Pattern {
+    /// Return `true` if this pattern is negated, i.e. it excludes the paths it matches.
+    pub fn is_excluded(&self) -> bool {
+        self.signature.contains(MagicSignature::EXCLUDE)
+    }
+
+    /// Return `true` if this pattern matches everything, which is the case if its path is empty.
+    pub fn is_nil(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Return the path of this pattern, which is relative to the root of the worktree after [normalization][Pattern::normalize()].
+    pub fn path(&self) -> &BStr {
+        self.path.as_bstr()
+    }
+
+    /// Return the portion of [`path()`][Pattern::path()] that was added by normalizing it with a prefix, i.e. the directory
+    /// in which the pattern was specified, without trailing slash. It's empty if the pattern wasn't normalized with a prefix.
+    pub fn prefix_directory(&self) -> &BStr {
+        self.path[..self.prefix_len].as_bstr()
+    }
+
+    /// Return the amount of leading bytes of [`path()`][Pattern::path()] that are free of wildcards and have to be matched verbatim.
+    pub fn literal_prefix_len(&self) -> usize {
+        match self.search_mode {
+            MatchMode::Literal => self.path.len(),
+            // The prefix is always matched verbatim, even if it contains wildcard characters.
+            MatchMode::ShellGlob | MatchMode::PathAwareGlob => self.path[self.prefix_len..]
+                .find_byteset(b"*?[\\")
+                .map_or(self.path.len(), |pos| self.prefix_len + pos),
+        }
+    }
+
+    /// Return `true` if this pattern contains wildcards that need to be matched with [`gix_glob::wildmatch()`].
+    pub fn has_wildcards(&self) -> bool {
+        self.literal_prefix_len() != self.path.len()
+    }
+}
+
+/// Mutation
+impl Pattern {
+    /// Turn this pattern into a path relative to the worktree at `root`, after placing it into the directory `prefix`,
+    /// which is the current working directory relative to `root`, similar to what `git` does.
+    ///
+    /// `prefix` is ignored if the pattern is marked as [top-level][MagicSignature::TOP]. Absolute paths are made relative
+    /// to `root`, which must then be absolute as well. `.` and `..` components are resolved and consecutive slashes are
+    /// collapsed, while a trailing slash is retained to indicate that only directories may match.
+    pub fn normalize(&mut self, prefix: &Path, root: &Path) -> Result<&mut Self, normalize::Error> {
+        let path = gix_path::to_unix_separators_on_windows(self.path.as_bstr()).into_owned();
+        let (prefix, path) = if gix_path::is_absolute(gix_path::from_bstr(path.as_bstr())) {
+            let root_bytes = gix_path::to_unix_separators_on_windows(gix_path::into_bstr(root)).into_owned();
+            let root_bytes = root_bytes.trim_end_with(|c| c == '/');
+            match path.strip_prefix(root_bytes) {
+                Some(rela_path) if rela_path.is_empty() || rela_path[0] == b'/' => {
+                    (BString::default(), rela_path.into())
+                }
+                _ => {
+                    return Err(normalize::Error::AbsolutePathOutsideOfWorktree {
+                        path: gix_path::from_bstring(path),
+                        worktree_path: root.to_owned(),
+                    })
+                }
+            }
+        } else if self.signature.contains(MagicSignature::TOP) {
+            (BString::default(), path)
+        } else {
+            (
+                gix_path::to_unix_separators_on_windows(gix_path::into_bstr(prefix)).into_owned(),
+                path,
+            )
+        };
+
+        let must_be_dir = path.ends_with(b"/");
+        let mut components = Vec::<(&[u8], bool)>::new();
+        for (component, is_prefix) in prefix
+            .split_str("/")
+            .map(|c| (c, true))
+            .chain(path.split_str("/").map(|c| (c, false)))
+        {
+            match component {
+                b"" | b"." => {}
+                b".." => {
+                    if components.pop().is_none() {
+                        return Err(normalize::Error::OutsideOfWorktree {
+                            path: gix_path::from_bstr(self.path.as_bstr()).into_owned(),
+                        });
+                    }
+                }
+                _ => components.push((component, is_prefix)),
+            }
+        }
+
+        let mut out = BString::default();
+        let mut prefix_len = 0;
+        for (idx, (component, is_prefix)) in components.iter().enumerate() {
+            if idx != 0 {
+                out.push_byte(b'/');
+            }
+            out.push_str(component);
+            if *is_prefix {
+                prefix_len = out.len();
+            }
+        }
+        if must_be_dir && !out.is_empty() {
+            out.push_byte(b'/');
+        }
+        self.path = out;
+        self.prefix_len = prefix_len;
+        Ok(self)
+    }
+}
@@ -0,0 +1,191 @@
+use bstr::{BStr, ByteSlice};
+use gix_glob::pattern::Case;
+
+use crate::{
+    search::{Match, Spec},
+    MagicSignature, MatchMode, Pattern, Search,
+};
+
+impl Search {
+    /// Return the first [`Match`] of `relative_path`, or `None` if it isn't matched by any of the patterns that aren't
+    /// excluded or if it is matched by an excluded one.
+    ///
+    /// `relative_path` is relative to the root of the worktree and uses slashes as separators. `is_dir` is `true` if it
+    /// is a directory, which is needed to match patterns with a trailing slash, or `None` if this isn't known.
+    ///
+    /// `attributes` is called for patterns with `attr:` constraints to fill the given outcome with the attributes of
+    /// `relative_path`, and should return `true` if this was successful. Its arguments are `relative_path`, the case
+    /// to use when matching attribute patterns, whether the path is a directory, and the outcome to fill.
+    pub fn pattern_matching_relative_path(
+        &mut self,
+        relative_path: &BStr,
+        is_dir: Option<bool>,
+        attributes: &mut dyn FnMut(&BStr, Case, bool, &mut gix_attributes::search::Outcome) -> bool,
+    ) -> Option<Match<'_>> {
+        let common_prefix = self.common_prefix();
+        if !relative_path.starts_with(common_prefix) && !common_prefix.starts_with(relative_path) {
+            return None;
+        }
+
+        let mut first_include = None;
+        let mut has_includes = false;
+        for (sequence_number, spec) in self.patterns.iter_mut().enumerate() {
+            let is_excluded = spec.pattern.is_excluded();
+            has_includes |= !is_excluded;
+            if !is_excluded && first_include.is_some() {
+                continue;
+            }
+            if spec.matches(relative_path, is_dir, attributes) {
+                if is_excluded {
+                    return None;
+                }
+                first_include = Some(sequence_number);
+            }
+        }
+
+        match first_include {
+            Some(sequence_number) => Some(Match {
+                pattern: Some(&self.patterns[sequence_number].pattern),
+                sequence_number,
+            }),
+            None if !has_includes => Some(Match {
+                pattern: None,
+                sequence_number: 0,
+            }),
+            None => None,
+        }
+    }
+
+    /// Return `true` if the directory at `relative_path` or any path below it could be matched by one of the patterns
+    /// that aren't excluded, which allows to skip directories when walking the worktree.
+    ///
+    /// This is a conservative check that doesn't consider excluded patterns or attributes.
+    pub fn can_match_relative_path(&self, relative_path: &BStr) -> bool {
+        let mut includes = self
+            .patterns
+            .iter()
+            .filter(|spec| !spec.pattern.is_excluded())
+            .peekable();
+        if includes.peek().is_none() {
+            return true;
+        }
+        includes.any(|spec| spec.pattern.may_match_below(relative_path))
+    }
+}
+
+impl Spec {
+    fn matches(
+        &mut self,
+        relative_path: &BStr,
+        is_dir: Option<bool>,
+        attributes: &mut dyn FnMut(&BStr, Case, bool, &mut gix_attributes::search::Outcome) -> bool,
+    ) -> bool {
+        if !self.pattern.matches_path(relative_path, is_dir) {
+            return false;
+        }
+        let Some(outcome) = self.attrs.as_mut() else {
+            return true;
+        };
+        outcome.reset();
+        if !attributes(relative_path, self.pattern.case(), is_dir.unwrap_or(false), outcome) {
+            return false;
+        }
+        self.pattern
+            .attributes
+            .iter()
+            .zip(outcome.iter_selected())
+            .all(|(expected, actual)| actual.assignment.state == expected.state.as_ref())
+    }
+}
+
+impl Pattern {
+    fn case(&self) -> Case {
+        if self.signature.contains(MagicSignature::ICASE) {
+            Case::Fold
+        } else {
+            Case::Sensitive
+        }
+    }
+
+    /// Return the path without its trailing slash, along with `true` if it had one, meaning only directories can match.
+    fn path_and_dir_constraint(&self) -> (&BStr, bool) {
+        match self.path.strip_suffix(b"/") {
+            Some(path) => (path.as_bstr(), true),
+            None => (self.path.as_bstr(), false),
+        }
+    }
+
+    /// Compare `path` to `pattern` while matching the portion that belongs to the prefix directory case-sensitively.
+    fn eq_respecting_prefix(&self, pattern: &[u8], path: &[u8]) -> bool {
+        if pattern.len() != path.len() {
+            return false;
+        }
+        let prefix_len = self.prefix_len.min(pattern.len());
+        if pattern[..prefix_len] != path[..prefix_len] {
+            return false;
+        }
+        match self.case() {
+            Case::Sensitive => pattern[prefix_len..] == path[prefix_len..],
+            Case::Fold => pattern[prefix_len..].eq_ignore_ascii_case(&path[prefix_len..]),
+        }
+    }
+
+    fn matches_path(&self, relative_path: &BStr, is_dir: Option<bool>) -> bool {
+        if self.is_nil() {
+            return true;
+        }
+        let (path, must_be_dir) = self.path_and_dir_constraint();
+
+        // Everything below a directory named verbatim is matched as well.
+        if relative_path.len() > path.len()
+            && relative_path[path.len()] == b'/'
+            && self.eq_respecting_prefix(path, &relative_path[..path.len()])
+        {
+            return true;
+        }
+        let is_dir_ok = !must_be_dir || is_dir.unwrap_or(true);
+        if relative_path.len() == path.len() && self.eq_respecting_prefix(path, relative_path) {
+            return is_dir_ok;
+        }
+        if !self.has_wildcards() || !is_dir_ok {
+            return false;
+        }
+
+        let prefix_len = self.prefix_len.min(path.len());
+        if relative_path.len() < prefix_len || relative_path[..prefix_len] != path[..prefix_len] {
+            return false;
+        }
+        let mut mode = match self.search_mode {
+            MatchMode::ShellGlob => gix_glob::wildmatch::Mode::empty(),
+            MatchMode::PathAwareGlob => gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+            MatchMode::Literal => unreachable!("literal patterns have no wildcards"),
+        };
+        if self.case() == Case::Fold {
+            mode |= gix_glob::wildmatch::Mode::IGNORE_CASE;
+        }
+        gix_glob::wildmatch(
+            path[prefix_len..].as_bstr(),
+            relative_path[prefix_len..].as_bstr(),
+            mode,
+        )
+    }
+
+    fn may_match_below(&self, directory: &BStr) -> bool {
+        if self.is_nil() || directory.is_empty() {
+            return true;
+        }
+        let (path, _must_be_dir) = self.path_and_dir_constraint();
+        let literal = &path[..self.literal_prefix_len().min(path.len())];
+        if directory.len() < literal.len() {
+            // The directory is a leading portion of the pattern, and must end at a component boundary.
+            return literal[directory.len()] == b'/'
+                && self.eq_respecting_prefix(&literal[..directory.len()], directory);
+        }
+        if self.has_wildcards() {
+            self.eq_respecting_prefix(literal, &directory[..literal.len()])
+        } else {
+            (directory.len() == path.len() || directory[path.len()] == b'/')
+                && self.eq_respecting_prefix(path, &directory[..path.len()])
+        }
+    }
+}
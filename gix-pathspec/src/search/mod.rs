@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use bstr::{BStr, ByteSlice};
+
+use crate::{normalize, Pattern};
+
+/// A lists of pathspec patterns to match paths against, similar to how `git` matches paths for commands like
+/// `git ls-files <pathspec>…`.
+///
+/// A path is matched if it matches any of the patterns that aren't [excluded][Pattern::is_excluded()] (or if there are none
+/// of these), and none of the excluded ones. All patterns with `attr:` constraints also require the path to have the given
+/// attributes.
+#[derive(Debug, Clone)]
+pub struct Search {
+    /// The patterns to match against, in the order they were provided.
+    patterns: Vec<Spec>,
+    /// The amount of leading bytes that all non-excluded patterns have in common, and which must be present verbatim in
+    /// each path for it to possibly match.
+    common_prefix_len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Spec {
+    pattern: Pattern,
+    /// The attributes to query for the pattern if it has `attr:` constraints.
+    attrs: Option<gix_attributes::search::Outcome>,
+}
+
+/// Describes a successful match of a path against a [`Search`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'a> {
+    /// The first pattern that matched the path, or `None` if there is no pattern that isn't excluded, which matches
+    /// all paths that aren't excluded.
+    pub pattern: Option<&'a Pattern>,
+    /// The position of `pattern` in the list of patterns the search was created with, or `0` if `pattern` is `None`.
+    pub sequence_number: usize,
+}
+
+/// Lifecycle
+impl Search {
+    /// Create a search from `pathspecs` after [normalizing][Pattern::normalize()] each of them with `prefix`,
+    /// the current working directory relative to `root`, the root of the worktree.
+    ///
+    /// A search without any pattern matches all paths, just like `git` does when no pathspec is given.
+    pub fn from_specs(
+        pathspecs: impl IntoIterator<Item = Pattern>,
+        prefix: Option<&Path>,
+        root: &Path,
+    ) -> Result<Self, normalize::Error> {
+        let prefix = prefix.unwrap_or(Path::new(""));
+        let patterns = pathspecs
+            .into_iter()
+            .map(|mut pattern| {
+                pattern.normalize(prefix, root)?;
+                let attrs = (!pattern.attributes.is_empty()).then(|| {
+                    let mut out = gix_attributes::search::Outcome::default();
+                    out.initialize_with_selection(
+                        &Default::default(),
+                        pattern.attributes.iter().map(|attr| attr.name.as_str()),
+                    );
+                    out
+                });
+                Ok(Spec { pattern, attrs })
+            })
+            .collect::<Result<Vec<_>, normalize::Error>>()?;
+        let common_prefix_len = common_prefix_len(&patterns);
+        Ok(Search {
+            patterns,
+            common_prefix_len,
+        })
+    }
+}
+
+/// Access
+impl Search {
+    /// Return an iterator over all normalized patterns, in the order they were provided.
+    pub fn patterns(&self) -> impl Iterator<Item = &Pattern> + '_ {
+        self.patterns.iter().map(|spec| &spec.pattern)
+    }
+
+    /// Return the portion of the path that all non-excluded patterns have in common, which can be used to prune
+    /// directory walks or to limit the paths to check in the first place.
+    ///
+    /// Note that it may end in the middle of a path component, and is empty if there is no non-excluded pattern.
+    pub fn common_prefix(&self) -> &BStr {
+        self.patterns
+            .iter()
+            .find(|spec| !spec.pattern.is_excluded())
+            .map_or("".into(), |spec| spec.pattern.path[..self.common_prefix_len].as_bstr())
+    }
+}
+
+mod matching;
+
+fn common_prefix_len(patterns: &[Spec]) -> usize {
+    let mut includes = patterns.iter().filter(|spec| !spec.pattern.is_excluded()).map(|spec| {
+        let pattern = &spec.pattern;
+        let len = if pattern.signature.contains(crate::MagicSignature::ICASE) {
+            pattern.prefix_len
+        } else {
+            pattern.literal_prefix_len()
+        };
+        &pattern.path[..len]
+    });
+    let Some(first) = includes.next() else {
+        return 0;
+    };
+    includes.fold(first.len(), |len, other| {
+        first[..len]
+            .iter()
+            .zip(other.iter())
+            .take_while(|(a, b)| a == b)
+            .count()
+    })
+}
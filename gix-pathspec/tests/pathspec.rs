@@ -471,3 +471,228 @@ mod parse {
         *base == 0
     }
 }
+
+mod normalize {
+    use std::path::Path;
+
+    use gix_pathspec::normalize::Error;
+
+    fn normalized(spec: &str, prefix: &str) -> Result<String, Error> {
+        let mut pattern = gix_pathspec::parse(spec.as_bytes()).expect("valid");
+        pattern.normalize(Path::new(prefix), Path::new("/repo"))?;
+        Ok(pattern.path().to_string())
+    }
+
+    #[test]
+    fn prefix_is_prepended_unless_the_pattern_is_top_level() -> crate::Result {
+        assert_eq!(normalized("file", "")?, "file");
+        assert_eq!(normalized("file", "dir/sub")?, "dir/sub/file");
+        assert_eq!(normalized(":(top)file", "dir/sub")?, "file");
+        assert_eq!(normalized(":/file", "dir/sub")?, "file");
+        assert_eq!(
+            normalized(":", "dir")?,
+            "dir",
+            "the nil pattern matches the prefix directory"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn relative_components_are_resolved() -> crate::Result {
+        assert_eq!(normalized("../file", "dir/sub")?, "dir/file");
+        assert_eq!(normalized("./a//b/./", "dir")?, "dir/a/b/", "trailing slashes are kept");
+        assert_eq!(normalized("..", "dir")?, "");
+        assert!(matches!(
+            normalized("../..", "dir"),
+            Err(Error::OutsideOfWorktree { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn absolute_paths_must_be_inside_of_the_worktree() -> crate::Result {
+        if cfg!(windows) {
+            return Ok(());
+        }
+        assert_eq!(normalized("/repo/dir/file", "ignored")?, "dir/file");
+        assert!(matches!(
+            normalized("/other/file", ""),
+            Err(Error::AbsolutePathOutsideOfWorktree { .. })
+        ));
+        assert!(matches!(
+            normalized("/repository/file", ""),
+            Err(Error::AbsolutePathOutsideOfWorktree { .. })
+        ));
+        Ok(())
+    }
+}
+
+mod search {
+    use std::path::Path;
+
+    use bstr::BStr;
+    use gix_attributes::search::{MetadataCollection, Outcome};
+    use gix_glob::pattern::Case;
+    use gix_pathspec::Search;
+
+    fn search(specs: &[&str], prefix: Option<&str>) -> Search {
+        Search::from_specs(
+            specs
+                .iter()
+                .map(|spec| gix_pathspec::parse(spec.as_bytes()).expect("valid")),
+            prefix.map(Path::new),
+            Path::new("/repo"),
+        )
+        .expect("normalizable")
+    }
+
+    fn no_attributes(_: &BStr, _: Case, _: bool, _: &mut Outcome) -> bool {
+        unreachable!("no pattern uses attributes")
+    }
+
+    fn is_match(search: &mut Search, path: &str, is_dir: Option<bool>) -> bool {
+        search
+            .pattern_matching_relative_path(path.into(), is_dir, &mut no_attributes)
+            .is_some()
+    }
+
+    #[test]
+    fn no_patterns_match_everything() {
+        let mut search = search(&[], None);
+        let m = search
+            .pattern_matching_relative_path("any/path".into(), None, &mut no_attributes)
+            .expect("match");
+        assert!(m.pattern.is_none());
+        assert_eq!(search.common_prefix(), "");
+        assert!(search.can_match_relative_path("any".into()));
+    }
+
+    #[test]
+    fn verbatim_paths_match_themselves_and_everything_below() {
+        let mut search = search(&["dir", "file", "only-dir/"], None);
+        assert!(is_match(&mut search, "dir", Some(true)));
+        assert!(is_match(&mut search, "dir/sub/file", Some(false)));
+        assert!(is_match(&mut search, "file", Some(false)));
+        assert!(!is_match(&mut search, "dirt", Some(false)));
+        assert!(!is_match(&mut search, "file.ext", Some(false)));
+        assert!(is_match(&mut search, "only-dir", Some(true)));
+        assert!(
+            !is_match(&mut search, "only-dir", Some(false)),
+            "trailing slashes match directories only"
+        );
+        assert!(is_match(&mut search, "only-dir/file", Some(false)));
+    }
+
+    #[test]
+    fn the_first_matching_include_is_returned() {
+        let mut search = search(&["a/*", "a/b"], None);
+        let m = search
+            .pattern_matching_relative_path("a/b".into(), None, &mut no_attributes)
+            .expect("match");
+        assert_eq!(m.sequence_number, 0);
+        assert_eq!(m.pattern.expect("set").path(), "a/*");
+    }
+
+    #[test]
+    fn globs_match_across_slashes_unless_they_are_path_aware() {
+        let mut search = search(&["*.rs", ":(glob)src/*.md"], None);
+        assert!(is_match(&mut search, "lib.rs", None));
+        assert!(is_match(&mut search, "deeply/nested/lib.rs", None));
+        assert!(is_match(&mut search, "src/readme.md", None));
+        assert!(!is_match(&mut search, "src/nested/readme.md", None));
+        assert!(!is_match(&mut search, "lib.rs.orig", None));
+    }
+
+    #[test]
+    fn literal_patterns_do_not_expand_wildcards() {
+        let mut search = search(&[":(literal)*.rs"], None);
+        assert!(is_match(&mut search, "*.rs", None));
+        assert!(!is_match(&mut search, "lib.rs", None));
+    }
+
+    #[test]
+    fn icase_affects_everything_but_the_prefix() {
+        let mut search = search(&[":(icase)file", ":(icase)*.MD"], Some("Dir"));
+        assert!(is_match(&mut search, "Dir/FILE", None));
+        assert!(is_match(&mut search, "Dir/readme.md", None));
+        assert!(
+            !is_match(&mut search, "dir/file", None),
+            "the prefix is always matched case-sensitively"
+        );
+        assert_eq!(search.common_prefix(), "Dir");
+    }
+
+    #[test]
+    fn excludes_take_precedence() {
+        let mut search = search(&["src", ":!src/generated"], None);
+        assert!(is_match(&mut search, "src/lib.rs", None));
+        assert!(!is_match(&mut search, "src/generated/code.rs", None));
+        assert!(!is_match(&mut search, "other", None));
+
+        let mut only_excludes = self::search(&[":(exclude)*.bin"], None);
+        let m = only_excludes
+            .pattern_matching_relative_path("file".into(), None, &mut no_attributes)
+            .expect("everything else matches");
+        assert!(m.pattern.is_none());
+        assert!(!is_match(&mut only_excludes, "data.bin", None));
+        assert_eq!(
+            only_excludes.common_prefix(),
+            "",
+            "excludes don't contribute to the common prefix"
+        );
+    }
+
+    #[test]
+    fn prefixes_place_patterns_into_subdirectories() {
+        let mut search = search(&["*.rs", ":/top-level", "../sibling"], Some("dir/sub"));
+        assert!(is_match(&mut search, "dir/sub/lib.rs", None));
+        assert!(!is_match(&mut search, "lib.rs", None));
+        assert!(is_match(&mut search, "top-level", None));
+        assert!(is_match(&mut search, "dir/sibling", None));
+        let paths: Vec<_> = search.patterns().map(|p| p.path().to_string()).collect();
+        assert_eq!(paths, ["dir/sub/*.rs", "top-level", "dir/sibling"]);
+    }
+
+    #[test]
+    fn common_prefix_and_directory_pruning() {
+        let search = search(&["src/lib.rs", "src/bin/*.rs", ":!src/bin/skip.rs"], None);
+        assert_eq!(search.common_prefix(), "src/");
+        assert!(search.can_match_relative_path("src".into()));
+        assert!(search.can_match_relative_path("src/bin".into()));
+        assert!(
+            search.can_match_relative_path("src/bin/nested".into()),
+            "wildcards may match anything"
+        );
+        assert!(!search.can_match_relative_path("src/other".into()));
+        assert!(!search.can_match_relative_path("tests".into()));
+        assert!(!search.can_match_relative_path("sr".into()));
+    }
+
+    #[test]
+    fn attributes_are_matched_through_the_callback() {
+        let mut collection = MetadataCollection::default();
+        let mut attributes = gix_attributes::Search::default();
+        attributes.add_patterns_buffer(
+            b"*.txt text\n*.bin -text\n*.data kind=raw",
+            "attributes",
+            None,
+            &mut collection,
+            true,
+        );
+        let mut search = search(&[":(attr:text)", ":(attr:kind=raw)"], None);
+        let mut lookup = |path: &BStr, case: Case, _is_dir: bool, out: &mut Outcome| {
+            out.initialize(&collection);
+            attributes.pattern_matching_relative_path(path, case, out);
+            true
+        };
+        for (path, expected) in [("a.txt", Some(0)), ("a.bin", None), ("a.data", Some(1)), ("a", None)] {
+            assert_eq!(
+                search
+                    .pattern_matching_relative_path(path.into(), Some(false), &mut lookup)
+                    .map(|m| m.sequence_number),
+                expected,
+                "{path}"
+            );
+        }
+    }
+}
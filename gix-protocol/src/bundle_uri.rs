@@ -0,0 +1,106 @@
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`bundle_uri()`][crate::bundle_uri()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        DecodePacketline(#[from] gix_transport::packetline::decode::Error),
+        #[error("The server does not support the 'bundle-uri' command")]
+        Unsupported,
+        #[error("Expected a line of the form 'key=value' with non-empty key and value, got {line:?}")]
+        MalformedLine { line: BString },
+    }
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+pub use error::Error;
+
+/// A `key=value` pair as sent by the server in response to the `bundle-uri` command, like `bundle.version=1`
+/// or `bundle.<id>.uri=<uri>`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyValue {
+    /// The key, like `bundle.mode`.
+    pub key: bstr::BString,
+    /// The value, like `all`.
+    pub value: bstr::BString,
+}
+
+impl KeyValue {
+    /// Parse a `KeyValue` from a `line` as received from the server.
+    pub fn from_line(line: &bstr::BStr) -> Result<KeyValue, Error> {
+        use bstr::ByteSlice;
+        let line = line.trim_end_with(|c| c == '\n');
+        match line.split_once_str("=") {
+            Some((key, value)) if !key.is_empty() && !value.is_empty() => Ok(KeyValue {
+                key: key.into(),
+                value: value.into(),
+            }),
+            _ => Err(Error::MalformedLine { line: line.into() }),
+        }
+    }
+}
+
+pub(crate) mod function {
+    use gix_features::progress::Progress;
+    use gix_transport::client::{Capabilities, Transport, TransportV2Ext};
+    use maybe_async::maybe_async;
+
+    use super::{Error, KeyValue};
+    use crate::{command::Feature, Command};
+
+    /// Invoke a bundle-uri V2 command on `transport`, which requires a prior handshake that yielded
+    /// server `capabilities`, to obtain the list of bundles the server recommends to download before fetching from it.
+    /// `features` are sent along with the command and are expected to contain the `(agent, Some(name))` pair.
+    /// `progress` is used to provide feedback.
+    ///
+    /// The returned key-value pairs describe a bundle list, and are empty if the server doesn't know any bundles.
+    #[maybe_async]
+    pub async fn bundle_uri(
+        mut transport: impl Transport,
+        capabilities: &Capabilities,
+        features: Vec<Feature>,
+        progress: &mut impl Progress,
+    ) -> Result<Vec<KeyValue>, Error> {
+        let bundle_uri = Command::BundleUri;
+        if !capabilities.contains(bundle_uri.as_str()) {
+            return Err(Error::Unsupported);
+        }
+        bundle_uri.validate_argument_prefixes_or_panic(gix_transport::Protocol::V2, capabilities, &[], &features);
+
+        progress.step();
+        progress.set_name("list bundles");
+        let mut lines = transport
+            .invoke(
+                bundle_uri.as_str(),
+                features.into_iter(),
+                None::<std::iter::Empty<bstr::BString>>,
+            )
+            .await?;
+        let mut out = Vec::new();
+        while let Some(line) = lines
+            .readline()
+            .await
+            .transpose()?
+            .transpose()?
+            .and_then(|l| l.as_bstr())
+        {
+            out.push(KeyValue::from_line(line)?);
+        }
+        Ok(out)
+    }
+}
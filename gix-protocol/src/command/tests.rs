@@ -93,7 +93,7 @@ mod v2 {
                         .iter()
                         .map(|s| s.as_bytes().as_bstr().to_owned())
                         .collect::<Vec<_>>(),
-                    "packfile-uris isn't really supported that well and we don't support it either yet"
+                    "packfile-uris must be requested explicitly as some servers advertise it and reject it later"
                 )
             }
         }
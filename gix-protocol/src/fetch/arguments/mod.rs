@@ -19,6 +19,8 @@ pub struct Arguments {
     deepen_relative: bool,
     ref_in_want: bool,
     supports_include_tag: bool,
+    packfile_uris: bool,
+    wait_for_done: bool,
 
     features_for_first_want: Option<Vec<String>>,
     #[cfg(any(feature = "async-client", feature = "blocking-client"))]
@@ -78,6 +80,18 @@ impl Arguments {
     pub fn can_use_include_tag(&self) -> bool {
         self.supports_include_tag
     }
+    /// Return true if the 'packfile-uris' capability is supported.
+    ///
+    /// This is a V2 only feature which allows the server to offload parts of the pack to other locations, like a CDN.
+    pub fn can_use_packfile_uris(&self) -> bool {
+        self.packfile_uris
+    }
+    /// Return true if the 'wait-for-done' capability is supported.
+    ///
+    /// This is a V2 only feature which allows the client to decide when the negotiation is over.
+    pub fn can_use_wait_for_done(&self) -> bool {
+        self.wait_for_done
+    }
     /// Return true if we will use a stateless mode of operation, which can be decided in conjunction with `transport_is_stateless`.
     ///
     /// * we are always stateless if the transport is stateless, i.e. doesn't support multiple interactions with a single connection.
@@ -179,6 +193,29 @@ impl Arguments {
             }
         }
     }
+    /// Tell the server that we are able to download additional packs via the given `protocols`, like `https`,
+    /// which it may then list in a `packfile-uris` section of its response.
+    ///
+    /// Needs to only be called once, and has no effect if `protocols` is empty.
+    pub fn use_packfile_uris<'a>(&mut self, protocols: impl IntoIterator<Item = &'a str>) {
+        debug_assert!(self.packfile_uris, "'packfile-uris' feature required");
+        if self.packfile_uris {
+            let protocols = protocols.into_iter().collect::<Vec<_>>();
+            if !protocols.is_empty() {
+                self.prefixed("packfile-uris ", protocols.join(","));
+            }
+        }
+    }
+    /// Tell the server to never send `ready`, but to keep negotiating until we send `done`, which leaves it to the client to decide
+    /// when enough common commits were found.
+    ///
+    /// Needs to only be called once.
+    pub fn use_wait_for_done(&mut self) {
+        debug_assert!(self.wait_for_done, "'wait-for-done' feature required");
+        if self.wait_for_done {
+            self.args.push("wait-for-done".into());
+        }
+    }
     fn prefixed(&mut self, prefix: &str, value: impl fmt::Display) {
         self.args.push(format!("{prefix}{value}").into());
     }
@@ -195,6 +232,8 @@ impl Arguments {
         let mut deepen_not = shallow;
         let mut deepen_relative = shallow;
         let supports_include_tag;
+        let mut packfile_uris = false;
+        let mut wait_for_done = false;
         let (initial_arguments, features_for_first_want) = match version {
             gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => {
                 deepen_since = has("deepen-since");
@@ -215,6 +254,8 @@ impl Arguments {
             }
             gix_transport::Protocol::V2 => {
                 supports_include_tag = true;
+                packfile_uris = has("packfile-uris");
+                wait_for_done = has("wait-for-done");
                 (Command::Fetch.initial_arguments(&features), None)
             }
         };
@@ -227,6 +268,8 @@ impl Arguments {
             filter,
             shallow,
             supports_include_tag,
+            packfile_uris,
+            wait_for_done,
             deepen_not,
             deepen_relative,
             ref_in_want,
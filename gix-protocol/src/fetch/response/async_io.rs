@@ -4,7 +4,7 @@ use gix_transport::{client, Protocol};
 
 use crate::fetch::{
     response,
-    response::{Acknowledgement, ShallowUpdate, WantedRef},
+    response::{Acknowledgement, PackfileUri, ShallowUpdate, WantedRef},
     Response,
 };
 
@@ -33,14 +33,17 @@ async fn parse_v2_section<T>(
 impl Response {
     /// Parse a response of the given `version` of the protocol from `reader`.
     ///
-    /// `client_expects_pack` is only relevant for V1 stateful connections, and if `false`, causes us to stop parsing when seeing `NAK`,
-    /// and if `true` we will keep parsing until we get a pack as the client already signalled to the server that it's done.
+    /// `client_expects_pack` is only relevant for V1, and if `false`, causes us to stop parsing when seeing `NAK` or when the server
+    /// stops negotiating, and if `true` we will keep parsing until we get a pack as the client already signalled to the server that it's done.
+    /// `no_done` is only relevant for V1 as well, and must be `true` if the `no-done` feature is in use, as the server then sends the pack
+    /// right after it signalled to be ready instead of waiting for the client to be done.
     /// This way of doing things allows us to exploit knowledge about more recent versions of the protocol, which keeps code easier
     /// and more localized without having to support all the cruft that there is.
     pub async fn from_line_reader(
         version: Protocol,
         reader: &mut (impl client::ExtendedBufRead + Unpin),
         client_expects_pack: bool,
+        no_done: bool,
     ) -> Result<Response, response::Error> {
         match version {
             Protocol::V0 | Protocol::V1 => {
@@ -82,22 +85,34 @@ impl Response {
                     if Response::parse_v1_ack_or_shallow_or_assume_pack(&mut acks, &mut shallows, &peeked_line) {
                         break 'lines true;
                     }
+                    let is_ack_without_status = Response::is_v1_ack_without_status(&peeked_line);
                     assert_ne!(
                         reader.readline_str(&mut line).await?,
                         0,
                         "consuming a peeked line works"
                     );
-                    // When the server sends ready, we know there is going to be a pack so no need to stop early.
+                    // When the server sends ready with `no-done`, we know there is going to be a pack so no need to stop early.
+                    // Otherwise it waits for us to be done after the round is over.
                     saw_ready |= matches!(acks.last(), Some(Acknowledgement::Ready));
-                    if let Some(Acknowledgement::Nak) = acks.last().filter(|_| !client_expects_pack && !saw_ready) {
-                        break 'lines false;
+                    let pack_follows_without_done = saw_ready && no_done;
+                    if !client_expects_pack && !pack_follows_without_done {
+                        if is_ack_without_status {
+                            // Servers without `multi_ack` stop negotiating after the first common commit and expect us to be done.
+                            saw_ready = true;
+                            break 'lines false;
+                        }
+                        if let Some(Acknowledgement::Nak) = acks.last() {
+                            break 'lines false;
+                        }
                     }
                 };
                 Ok(Response {
                     acks,
                     shallows,
                     wanted_refs: vec![],
+                    packfile_uris: vec![],
                     has_pack,
+                    is_ready: saw_ready,
                 })
             }
             Protocol::V2 => {
@@ -107,6 +122,7 @@ impl Response {
                 let mut acks = Vec::<Acknowledgement>::new();
                 let mut shallows = Vec::<ShallowUpdate>::new();
                 let mut wanted_refs = Vec::<WantedRef>::new();
+                let mut packfile_uris = Vec::<PackfileUri>::new();
                 let has_pack = 'section: loop {
                     line.clear();
                     if reader.readline_str(&mut line).await? == 0 {
@@ -132,6 +148,11 @@ impl Response {
                                 break 'section false;
                             }
                         }
+                        "packfile-uris" => {
+                            if parse_v2_section(&mut line, reader, &mut packfile_uris, PackfileUri::from_line).await? {
+                                break 'section false;
+                            }
+                        }
                         "packfile" => {
                             // what follows is the packfile itself, which can be read with a sideband enabled reader
                             break 'section true;
@@ -140,9 +161,11 @@ impl Response {
                     }
                 };
                 Ok(Response {
+                    is_ready: acks.contains(&Acknowledgement::Ready),
                     acks,
                     shallows,
                     wanted_refs,
+                    packfile_uris,
                     has_pack,
                 })
             }
@@ -79,6 +79,17 @@ pub struct WantedRef {
     pub path: BString,
 }
 
+/// A line of the `packfile-uris` section received from the server, pointing to a pack to download in addition to the one
+/// sent as part of the response.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackfileUri {
+    /// The hash of the pack as advertised by the server, which is the trailing checksum of the pack downloaded from `uri`.
+    pub hash: gix_hash::ObjectId,
+    /// The location from which to download the pack, using one of the protocols the client said it accepts.
+    pub uri: BString,
+}
+
 impl ShallowUpdate {
     /// Parse a `ShallowUpdate` from a `line` as received to the server.
     pub fn from_line(line: &str) -> Result<ShallowUpdate, Error> {
@@ -99,6 +110,9 @@ impl ShallowUpdate {
 
 impl Acknowledgement {
     /// Parse an `Acknowledgement` from a `line` as received to the server.
+    ///
+    /// All acknowledgement modes are supported, i.e. `multi_ack_detailed` (`ACK <id> common|ready`), `multi_ack` (`ACK <id> continue`)
+    /// as well as the final `ACK <id>` that is also used by servers without support for either of these.
     pub fn from_line(line: &str) -> Result<Acknowledgement, Error> {
         let mut tokens = line.trim_end().splitn(3, ' ');
         match (tokens.next(), tokens.next(), tokens.next()) {
@@ -113,7 +127,7 @@ impl Acknowledgement {
                     };
                     if let Some(description) = description {
                         match description {
-                            "common" => {}
+                            "common" | "continue" => {}
                             "ready" => return Ok(Acknowledgement::Ready),
                             _ => return Err(Error::UnknownLineType { line: line.to_owned() }),
                         }
@@ -148,13 +162,29 @@ impl WantedRef {
     }
 }
 
+impl PackfileUri {
+    /// Parse a `PackfileUri` from a `line` as received from the server.
+    pub fn from_line(line: &str) -> Result<PackfileUri, Error> {
+        match line.trim_end().split_once(' ') {
+            Some((hash, uri)) if !uri.is_empty() => {
+                let hash = gix_hash::ObjectId::from_hex(hash.as_bytes())
+                    .map_err(|_| Error::UnknownLineType { line: line.to_owned() })?;
+                Ok(PackfileUri { hash, uri: uri.into() })
+            }
+            _ => Err(Error::UnknownLineType { line: line.to_owned() }),
+        }
+    }
+}
+
 /// A representation of a complete fetch response
 #[derive(Debug)]
 pub struct Response {
     acks: Vec<Acknowledgement>,
     shallows: Vec<ShallowUpdate>,
     wanted_refs: Vec<WantedRef>,
+    packfile_uris: Vec<PackfileUri>,
     has_pack: bool,
+    is_ready: bool,
 }
 
 impl Response {
@@ -163,6 +193,12 @@ impl Response {
         self.has_pack
     }
 
+    /// Return true if the server stopped negotiating as it knows enough to send a pack once the client is done, either because it
+    /// sent [`Acknowledgement::Ready`], or because it doesn't support `multi_ack` and acknowledged the first common commit.
+    pub fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
     /// Return an error if the given `features` don't contain the required ones (the ones this implementation needs)
     /// for the given `version` of the protocol.
     ///
@@ -172,12 +208,6 @@ impl Response {
         match version {
             Protocol::V0 | Protocol::V1 => {
                 let has = |name: &str| features.iter().any(|f| f.0 == name);
-                // Let's focus on V2 standards, and simply not support old servers to keep our code simpler
-                if !has("multi_ack_detailed") {
-                    return Err(Error::MissingServerCapability {
-                        feature: "multi_ack_detailed",
-                    });
-                }
                 // It's easy to NOT do sideband for us, but then again, everyone supports it.
                 // CORRECTION: If side-band is off, it would send the packfile without packet line encoding,
                 // which is nothing we ever want to deal with (despite it being more efficient). In V2, this
@@ -207,10 +237,24 @@ impl Response {
     pub fn wanted_refs(&self) -> &[WantedRef] {
         &self.wanted_refs
     }
+
+    /// Return all packfile-uris [parsed previously][Response::from_line_reader()].
+    ///
+    /// Each of these packs must be downloaded in addition to the pack sent in the response to obtain all requested objects.
+    pub fn packfile_uris(&self) -> &[PackfileUri] {
+        &self.packfile_uris
+    }
 }
 
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
 impl Response {
+    /// Return `true` if `line` is an `ACK <id>` without status, which is sent by servers without `multi_ack` support as soon
+    /// as they found a common commit, or as the last acknowledgement before the pack.
+    fn is_v1_ack_without_status(line: &str) -> bool {
+        let mut tokens = line.trim_end().split(' ');
+        tokens.next() == Some("ACK") && tokens.next().is_some() && tokens.next().is_none()
+    }
+
     /// with a friendly server, we just assume that a non-ack line is a pack line
     /// which is our hint to stop here.
     fn parse_v1_ack_or_shallow_or_assume_pack(
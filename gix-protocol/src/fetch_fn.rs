@@ -115,6 +115,7 @@ where
 
     Response::check_required_features(protocol_version, &fetch_features)?;
     let sideband_all = fetch_features.iter().any(|(n, _)| *n == "sideband-all");
+    let no_done = fetch_features.iter().any(|(n, _)| *n == "no-done");
     fetch_features.push(("agent", Some(Cow::Owned(agent))));
     let mut arguments = Arguments::new(protocol_version, fetch_features);
     let mut previous_response = None::<Response>;
@@ -132,6 +133,7 @@ where
             protocol_version,
             &mut reader,
             true, /* hack, telling us we don't want this delegate approach anymore */
+            no_done,
         )
         .await?;
         previous_response = if response.has_pack() {
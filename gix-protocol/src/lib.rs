@@ -17,6 +17,10 @@ pub enum Command {
     LsRefs,
     /// Fetch a pack.
     Fetch,
+    /// Obtain a list of bundles which can be downloaded to bootstrap a clone.
+    BundleUri,
+    /// Query information about objects, like their size, without downloading them.
+    ObjectInfo,
 }
 pub mod command;
 
@@ -58,6 +62,18 @@ pub mod ls_refs;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use ls_refs::function::ls_refs;
 
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod bundle_uri;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use bundle_uri::function::bundle_uri;
+
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod object_info;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use object_info::function::object_info;
+
 mod util;
 pub use util::agent;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
@@ -0,0 +1,129 @@
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`object_info()`][crate::object_info()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        DecodePacketline(#[from] gix_transport::packetline::decode::Error),
+        #[error("The server does not support the 'object-info' command")]
+        Unsupported,
+        #[error("Expected the server to respond with the 'size' attribute, got {line:?}")]
+        UnexpectedAttributes { line: BString },
+        #[error("Expected a line of the form '<oid> <size>', got {line:?}")]
+        MalformedLine { line: BString },
+    }
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+pub use error::Error;
+
+/// Information about a single object as sent by the server in response to the `object-info` command.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Info {
+    /// The id of the object.
+    pub id: gix_hash::ObjectId,
+    /// The size of the object in bytes, or `None` if the server doesn't have the object.
+    pub size: Option<u64>,
+}
+
+impl Info {
+    /// Parse an `Info` from a `line` as received from the server, like `<oid> <size>`.
+    pub fn from_line(line: &bstr::BStr) -> Result<Info, Error> {
+        use bstr::ByteSlice;
+        let line = line.trim_end_with(|c| c == '\n');
+        let malformed = || Error::MalformedLine { line: line.into() };
+        let (id, size) = line.split_once_str(" ").ok_or_else(malformed)?;
+        Ok(Info {
+            id: gix_hash::ObjectId::from_hex(id).map_err(|_| malformed())?,
+            size: if size.is_empty() {
+                None
+            } else {
+                Some(
+                    size.to_str()
+                        .ok()
+                        .and_then(|size| size.parse().ok())
+                        .ok_or_else(malformed)?,
+                )
+            },
+        })
+    }
+}
+
+pub(crate) mod function {
+    use bstr::{BString, ByteSlice};
+    use gix_features::progress::Progress;
+    use gix_transport::client::{Capabilities, Transport, TransportV2Ext};
+    use maybe_async::maybe_async;
+
+    use super::{Error, Info};
+    use crate::{command::Feature, Command};
+
+    /// Invoke an object-info V2 command on `transport`, which requires a prior handshake that yielded
+    /// server `capabilities`, to obtain the sizes of the objects with `ids` without downloading them.
+    /// `features` are sent along with the command and are expected to contain the `(agent, Some(name))` pair.
+    /// `progress` is used to provide feedback.
+    ///
+    /// The returned information is in the order in which the server sent it, which is typically the order of `ids`.
+    #[maybe_async]
+    pub async fn object_info(
+        mut transport: impl Transport,
+        capabilities: &Capabilities,
+        features: Vec<Feature>,
+        ids: impl IntoIterator<Item = gix_hash::ObjectId>,
+        progress: &mut impl Progress,
+    ) -> Result<Vec<Info>, Error> {
+        let object_info = Command::ObjectInfo;
+        if !capabilities.contains(object_info.as_str()) {
+            return Err(Error::Unsupported);
+        }
+        let arguments: Vec<BString> = std::iter::once("size".into())
+            .chain(ids.into_iter().map(|id| format!("oid {id}").into()))
+            .collect();
+        object_info.validate_argument_prefixes_or_panic(
+            gix_transport::Protocol::V2,
+            capabilities,
+            &arguments,
+            &features,
+        );
+
+        progress.step();
+        progress.set_name("query object info");
+        let mut lines = transport
+            .invoke(object_info.as_str(), features.into_iter(), Some(arguments.into_iter()))
+            .await?;
+        let mut out = Vec::new();
+        let mut saw_attributes = false;
+        while let Some(line) = lines
+            .readline()
+            .await
+            .transpose()?
+            .transpose()?
+            .and_then(|l| l.as_bstr())
+        {
+            if !saw_attributes {
+                if line.trim_end_with(|c| c == '\n') != b"size".as_slice() {
+                    return Err(Error::UnexpectedAttributes { line: line.into() });
+                }
+                saw_attributes = true;
+                continue;
+            }
+            out.push(Info::from_line(line)?);
+        }
+        Ok(out)
+    }
+}
@@ -29,7 +29,7 @@ mod v1 {
         async fn clone() -> crate::Result {
             let mut provider = mock_reader("v1/clone-only.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, true).await?;
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak]);
             assert!(r.has_pack());
             let mut buf = Vec::new();
@@ -42,7 +42,7 @@ mod v1 {
         async fn shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v1/clone-deepen-1.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, true).await?;
             assert_eq!(
                 r.shallow_updates(),
                 &[ShallowUpdate::Shallow(id("808e50d724f604f69ab93c6da2919c014667bedb"))]
@@ -59,7 +59,7 @@ mod v1 {
         async fn empty_shallow_clone_due_to_depth_being_too_high() -> crate::Result {
             let mut provider = mock_reader("v1/clone-deepen-5.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, true).await?;
             assert!(r.shallow_updates().is_empty());
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak]);
             assert!(r.has_pack());
@@ -73,7 +73,7 @@ mod v1 {
         async fn unshallow_fetch() -> crate::Result {
             let mut provider = mock_reader("v1/fetch-unshallow.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, true).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
@@ -103,8 +103,9 @@ mod v1 {
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn fetch_acks_without_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch-no-pack.response");
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), true)
-                .await?;
+            let r =
+                fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), true, true)
+                    .await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
@@ -120,7 +121,7 @@ mod v1 {
         async fn fetch_acks_and_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, true).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
@@ -137,6 +138,87 @@ mod v1 {
             assert_eq!(bytes_read, 9703, "should be able to read the whole pack");
             Ok(())
         }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_ready_without_no_done_waits_for_client_to_be_done() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, false, false).await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(id("6504930888c9c5337e7e065c964f87b60d16a7d7")),
+                    Acknowledgement::Common(id("fe17165c392110d1305674c06e4aec35728bfab7")),
+                    Acknowledgement::Common(id("f22743895a3024bb0c958335981439f1fa747d57")),
+                    Acknowledgement::Ready,
+                    Acknowledgement::Nak,
+                ]
+            );
+            assert!(r.is_ready());
+            assert!(
+                !r.has_pack(),
+                "without 'no-done' the server waits for 'done' after the round, so we must not wait for the pack"
+            );
+
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, false).await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[Acknowledgement::Common(id("f22743895a3024bb0c958335981439f1fa747d57"))],
+                "the final acknowledgement after 'done'"
+            );
+            assert!(r.has_pack());
+            let mut buf = Vec::new();
+            let bytes_read = reader.read_to_end(&mut buf).await?;
+            assert_eq!(bytes_read, 9703, "should be able to read the whole pack");
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_multi_ack() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-multi-ack.response");
+            let r = fetch::Response::from_line_reader(
+                Protocol::V1,
+                &mut provider.as_read_without_sidebands(),
+                false,
+                false,
+            )
+            .await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(id("47ee0b7fe4f3a7d776c78794873e6467e1c47e59")),
+                    Acknowledgement::Common(id("3f02c0ad360d96e8dbba92f97b42ebbaa4319db1")),
+                    Acknowledgement::Nak,
+                ]
+            );
+            assert!(!r.has_pack());
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_without_multi_ack() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch-without-multi-ack.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, false, false).await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[Acknowledgement::Common(id("f22743895a3024bb0c958335981439f1fa747d57"))],
+                "only what the server sent is an acknowledgement"
+            );
+            assert!(
+                r.is_ready(),
+                "the first common commit ends the negotiation, which is signalled as the server being ready"
+            );
+            assert!(!r.has_pack(), "the server waits for 'done' before sending the pack");
+
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, false).await?;
+            assert!(r.acknowledgements().is_empty());
+            assert!(r.has_pack());
+            let mut buf = Vec::new();
+            let bytes_read = reader.read_to_end(&mut buf).await?;
+            assert_eq!(bytes_read, 9703, "should be able to read the whole pack");
+            Ok(())
+        }
     }
 
     mod arguments {
@@ -204,7 +286,7 @@ mod v2 {
         use gix_packetline::read::ProgressAction;
         use gix_protocol::fetch::{
             self,
-            response::{Acknowledgement, ShallowUpdate},
+            response::{Acknowledgement, PackfileUri, ShallowUpdate},
         };
         use gix_transport::Protocol;
 
@@ -219,7 +301,7 @@ mod v2 {
                 );
                 let mut provider = mock_reader(&fixture);
                 let mut reader = provider.as_read_without_sidebands();
-                let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
+                let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
                 assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
                 assert!(r.has_pack());
                 reader.set_progress_handler(Some(Box::new(|_is_err, _text| {
@@ -232,11 +314,40 @@ mod v2 {
             Ok(())
         }
 
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn clone_with_packfile_uris() -> crate::Result {
+            let mut provider = mock_reader("v2/clone-with-packfile-uris.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
+            assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
+            assert_eq!(
+                r.packfile_uris(),
+                &[
+                    PackfileUri {
+                        hash: id("c5d0a6e9a8d5c2e7b3ac5ba9a9d5a8d6d8e4f2a1"),
+                        uri: "https://cdn.example.com/packs/pack-c5d0a6e9a8d5c2e7b3ac5ba9a9d5a8d6d8e4f2a1.pack".into()
+                    },
+                    PackfileUri {
+                        hash: id("1c0d8a5e1b3e0d6a9f0c7a2e6b1b6f3d9e4a2c7b"),
+                        uri: "http://127.0.0.1:8080/pack-1c0d8a5e1b3e0d6a9f0c7a2e6b1b6f3d9e4a2c7b.pack".into()
+                    }
+                ]
+            );
+            assert!(r.has_pack());
+            reader.set_progress_handler(Some(Box::new(|_is_err, _text| {
+                gix_transport::packetline::read::ProgressAction::Continue
+            })));
+            let mut buf = Vec::new();
+            let bytes_read = reader.read_to_end(&mut buf).await?;
+            assert_eq!(bytes_read, 876, "the pack sent in the response is still readable");
+            Ok(())
+        }
+
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v2/clone-deepen-1.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
             assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
             assert_eq!(
                 r.shallow_updates(),
@@ -253,7 +364,7 @@ mod v2 {
         async fn unshallow_fetch() -> crate::Result {
             let mut provider = mock_reader("v2/fetch-unshallow.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
@@ -285,7 +396,7 @@ mod v2 {
         async fn empty_shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v2/clone-deepen-5.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
             assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
             assert!(r.shallow_updates().is_empty(), "it should go straight to the packfile");
             assert!(r.has_pack());
@@ -299,7 +410,7 @@ mod v2 {
         async fn clone_with_sidebands() -> crate::Result {
             let mut provider = mock_reader("v2/clone-only-2.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
             assert!(r.acknowledgements().is_empty(), "it should go straight to the packfile");
             assert!(r.has_pack());
 
@@ -321,8 +432,9 @@ mod v2 {
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn fetch_acks_without_pack() -> crate::Result {
             let mut provider = mock_reader("v2/fetch-no-pack.response");
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut provider.as_read_without_sidebands(), true)
-                .await?;
+            let r =
+                fetch::Response::from_line_reader(Protocol::V2, &mut provider.as_read_without_sidebands(), true, true)
+                    .await?;
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak]);
             Ok(())
         }
@@ -332,7 +444,7 @@ mod v2 {
             let mut provider = mock_reader("v2/fetch-err-line.response");
             provider.fail_on_err_lines(true);
             let mut sidebands = provider.as_read_without_sidebands();
-            match fetch::Response::from_line_reader(Protocol::V2, &mut sidebands, true).await {
+            match fetch::Response::from_line_reader(Protocol::V2, &mut sidebands, true, true).await {
                 Ok(_) => panic!("need error response"),
                 Err(err) => match err {
                     fetch::response::Error::UploadPack(err) => {
@@ -347,7 +459,7 @@ mod v2 {
         async fn fetch_acks_and_pack() -> crate::Result {
             let mut provider = mock_reader("v2/fetch.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
@@ -414,5 +526,59 @@ mod v2 {
 0032have bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\n0009done\n0000");
             Ok(())
         }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn packfile_uris() -> crate::Result {
+            let caps = Capabilities::from_lines("version 2\nfetch=shallow packfile-uris\n".into())?;
+            let mut args = fetch::Arguments::new(Protocol::V2, Command::Fetch.default_features(Protocol::V2, &caps));
+            assert!(args.can_use_packfile_uris());
+
+            args.use_packfile_uris(["http", "https"]);
+            args.want(id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+
+            let mut out = Vec::new();
+            let mut transport = transport(
+                &mut out,
+                "v1/clone.response",
+                Protocol::V2,
+                gix_transport::client::git::ConnectMode::Daemon,
+            );
+
+            let _response = args.send(&mut transport, true).await?;
+            drop(_response);
+            assert_eq!(
+                out.as_slice().as_bstr(),
+                "0012command=fetch\n0001000ethin-pack\n000eofs-delta\n001dpackfile-uris http,https\n0032want aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n0009done\n0000"
+            );
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn wait_for_done() -> crate::Result {
+            let caps = Capabilities::from_lines("version 2\nfetch=shallow wait-for-done\n".into())?;
+            let mut args = fetch::Arguments::new(Protocol::V2, Command::Fetch.default_features(Protocol::V2, &caps));
+            assert!(args.can_use_wait_for_done());
+
+            args.use_wait_for_done();
+            args.want(id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+            args.have(id("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"));
+
+            let mut out = Vec::new();
+            let mut transport = transport(
+                &mut out,
+                "v1/clone.response",
+                Protocol::V2,
+                gix_transport::client::git::ConnectMode::Daemon,
+            );
+
+            let _response = args.send(&mut transport, false).await?;
+            drop(_response);
+            assert_eq!(
+                out.as_slice().as_bstr(),
+                "0012command=fetch\n0001000ethin-pack\n000eofs-delta\n0012wait-for-done\n0032want aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\n0032have bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb\n0000",
+                "the argument is sent with every round, and the client decides when it's done"
+            );
+            Ok(())
+        }
     }
 }
@@ -197,3 +197,120 @@ async fn ref_in_want() -> crate::Result {
 
     Ok(())
 }
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn bundle_uri() -> crate::Result {
+    let out = Vec::new();
+    let mut transport = transport(
+        out,
+        "v2/bundle-uri.response",
+        Protocol::V2,
+        gix_transport::client::git::ConnectMode::Daemon,
+    );
+    let handshake = gix_protocol::handshake(
+        &mut transport,
+        gix_transport::Service::UploadPack,
+        helper_unused,
+        Vec::new(),
+        &mut progress::Discard,
+    )
+    .await?;
+    let agent = "agent";
+    let list = gix_protocol::bundle_uri(
+        &mut transport,
+        &handshake.capabilities,
+        vec![("agent", Some(gix_protocol::agent(agent).into()))],
+        &mut progress::Discard,
+    )
+    .await?;
+    assert_eq!(
+        list.iter()
+            .map(|kv| format!("{}={}", kv.key, kv.value))
+            .collect::<Vec<_>>(),
+        [
+            "bundle.version=1",
+            "bundle.mode=all",
+            "bundle.heuristic=creationToken",
+            "bundle.base.uri=https://cdn.example.com/base.bundle",
+            "bundle.base.creationToken=1",
+            "bundle.incremental.uri=incremental.bundle",
+            "bundle.incremental.creationToken=2"
+        ]
+    );
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        format!(
+            "002fgit-upload-pack does/not/matter\0\0version=2\00017command=bundle-uri
+0014agent={}
+0000",
+            gix_protocol::agent(agent)
+        )
+        .as_bytes()
+        .as_bstr()
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn object_info() -> crate::Result {
+    let out = Vec::new();
+    let mut transport = transport(
+        out,
+        "v2/object-info.response",
+        Protocol::V2,
+        gix_transport::client::git::ConnectMode::Daemon,
+    );
+    let handshake = gix_protocol::handshake(
+        &mut transport,
+        gix_transport::Service::UploadPack,
+        helper_unused,
+        Vec::new(),
+        &mut progress::Discard,
+    )
+    .await?;
+    let agent = "agent";
+    let ids = [
+        gix_hash::ObjectId::from_hex(b"e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")?,
+        gix_hash::ObjectId::from_hex(b"5f3d8a8f2d3a6a3c1a8e1c9d6d1d3b6f0c1b2a3e")?,
+        gix_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000001")?,
+    ];
+    let infos = gix_protocol::object_info(
+        &mut transport,
+        &handshake.capabilities,
+        vec![("agent", Some(gix_protocol::agent(agent).into()))],
+        ids,
+        &mut progress::Discard,
+    )
+    .await?;
+    assert_eq!(
+        infos,
+        [
+            gix_protocol::object_info::Info {
+                id: ids[0],
+                size: Some(0)
+            },
+            gix_protocol::object_info::Info {
+                id: ids[1],
+                size: Some(1234)
+            },
+            gix_protocol::object_info::Info { id: ids[2], size: None },
+        ],
+        "objects the server doesn't have have no size"
+    );
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        format!(
+            "002fgit-upload-pack does/not/matter\0\0version=2\00018command=object-info
+0014agent={}
+00010009size
+0031oid e69de29bb2d1d6434b8b29ae775ad8c2e48c5391
+0031oid 5f3d8a8f2d3a6a3c1a8e1c9d6d1d3b6f0c1b2a3e
+0031oid 0000000000000000000000000000000000000001
+0000",
+            gix_protocol::agent(agent)
+        )
+        .as_bytes()
+        .as_bstr()
+    );
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+use crate::{read, util};
+
+/// The kind of operation that uses the [`rebase-apply`][crate::APPLY_DIR] directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A rebase using the apply-backend, as started by `git rebase --apply`.
+    Rebase,
+    /// Patches are applied from a mailbox, as started by `git am`.
+    ApplyMailbox,
+    /// Neither of the markers is present, so it's unknown which operation is in progress.
+    Unknown,
+}
+
+/// The state of the apply-backend of `git rebase` or of `git am`, as stored in the [`rebase-apply`][crate::APPLY_DIR] directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// The kind of operation in progress.
+    pub kind: Kind,
+    /// The full name of the branch being rebased, or `None` if `HEAD` was detached or if the operation isn't a rebase.
+    pub head_name: Option<BString>,
+    /// The commit onto which the commits are rebased, if the operation is a rebase.
+    pub onto: Option<ObjectId>,
+    /// The commit `HEAD` pointed to when the operation started, if known.
+    pub orig_head: Option<ObjectId>,
+    /// The one-based number of the patch that is currently applied.
+    pub next: usize,
+    /// The total amount of patches to apply.
+    pub last: usize,
+}
+
+impl State {
+    /// Read the state from the `rebase-apply` directory at `dir`.
+    pub fn from_dir(dir: &Path) -> Result<Self, read::Error> {
+        let kind = if dir.join("rebasing").is_file() {
+            Kind::Rebase
+        } else if dir.join("applying").is_file() {
+            Kind::ApplyMailbox
+        } else {
+            Kind::Unknown
+        };
+        Ok(State {
+            kind,
+            head_name: util::read_head_name(dir)?,
+            onto: util::read_id(dir, "onto")?,
+            orig_head: util::read_id(dir, "orig-head")?,
+            next: util::read_number(dir, "next")?.unwrap_or_default(),
+            last: util::read_number(dir, "last")?.unwrap_or_default(),
+        })
+    }
+
+    /// Write this state to the `rebase-apply` directory at `dir`, which is created if needed.
+    ///
+    /// Note that the patches themselves are not part of the state and have to be written separately.
+    pub fn write_to_dir(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        util::write_or_remove(dir, "rebasing", (self.kind == Kind::Rebase).then_some(&[][..]))?;
+        util::write_or_remove(dir, "applying", (self.kind == Kind::ApplyMailbox).then_some(&[][..]))?;
+        if self.kind == Kind::Rebase || self.head_name.is_some() {
+            util::write_head_name(dir, self.head_name.as_ref().map(|name| name.as_slice()))?;
+        }
+        let hex_line = |id: &Option<ObjectId>| id.map(|id| format!("{}\n", id.to_hex()).into_bytes());
+        util::write_or_remove(dir, "onto", hex_line(&self.onto).as_deref())?;
+        util::write_or_remove(dir, "orig-head", hex_line(&self.orig_head).as_deref())?;
+        util::write_line(dir, "next", self.next.to_string().as_bytes())?;
+        util::write_line(dir, "last", self.last.to_string().as_bytes())
+    }
+}
@@ -0,0 +1,92 @@
+use bstr::{BString, ByteSlice};
+
+/// The error returned by [`parse()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line:?} is not an assignment of a quoted value")]
+    InvalidLine { line: BString },
+    #[error("The quoted value in line {line:?} isn't terminated")]
+    UnterminatedQuote { line: BString },
+    #[error("The variable {name} is missing")]
+    MissingVariable { name: &'static str },
+    #[error("Could not parse the author date")]
+    Time(#[from] gix_date::parse::Error),
+}
+
+/// Parse the `author-script` file as written by `git`, which contains the `GIT_AUTHOR_NAME`, `GIT_AUTHOR_EMAIL` and `GIT_AUTHOR_DATE`
+/// variables as shell assignments, into a signature.
+pub fn parse(data: &[u8]) -> Result<gix_actor::Signature, Error> {
+    let (mut name, mut email, mut date) = (None, None, None);
+    for line in data.lines().filter(|line| !line.trim().is_empty()) {
+        let (variable, value) = line
+            .split_once_str("=")
+            .ok_or_else(|| Error::InvalidLine { line: line.into() })?;
+        let value = unquote(value).ok_or_else(|| Error::UnterminatedQuote { line: line.into() })?;
+        match variable {
+            b"GIT_AUTHOR_NAME" => name = Some(value),
+            b"GIT_AUTHOR_EMAIL" => email = Some(value),
+            b"GIT_AUTHOR_DATE" => date = Some(value),
+            _ => {}
+        }
+    }
+    let date = date.ok_or(Error::MissingVariable {
+        name: "GIT_AUTHOR_DATE",
+    })?;
+    let date = date.to_str_lossy();
+    Ok(gix_actor::Signature {
+        name: name.ok_or(Error::MissingVariable {
+            name: "GIT_AUTHOR_NAME",
+        })?,
+        email: email.ok_or(Error::MissingVariable {
+            name: "GIT_AUTHOR_EMAIL",
+        })?,
+        time: gix_date::parse(date.strip_prefix('@').unwrap_or(&date), None)?,
+    })
+}
+
+/// Write `author` to `out` in the format used by `git` in `author-script` files.
+pub fn write(author: gix_actor::SignatureRef<'_>, mut out: impl std::io::Write) -> std::io::Result<()> {
+    out.write_all(b"GIT_AUTHOR_NAME=")?;
+    write_quoted(author.name, &mut out)?;
+    out.write_all(b"\nGIT_AUTHOR_EMAIL=")?;
+    write_quoted(author.email, &mut out)?;
+    out.write_all(b"\nGIT_AUTHOR_DATE='@")?;
+    author.time.write_to(&mut out)?;
+    out.write_all(b"'\n")
+}
+
+/// Quote `value` for use in a shell like `git` does, by placing it in single-quotes and escaping `'` and `!` outside of them.
+fn write_quoted(value: &[u8], mut out: impl std::io::Write) -> std::io::Result<()> {
+    out.write_all(b"'")?;
+    for byte in value {
+        match byte {
+            b'\'' | b'!' => {
+                out.write_all(b"'\\")?;
+                out.write_all(&[*byte])?;
+                out.write_all(b"'")?;
+            }
+            _ => out.write_all(&[*byte])?,
+        }
+    }
+    out.write_all(b"'")
+}
+
+/// Undo the quoting of [`write_quoted()`], or return `None` if `value` isn't properly quoted.
+fn unquote(value: &[u8]) -> Option<BString> {
+    let mut out = BString::default();
+    let mut rest = value.strip_prefix(b"'")?;
+    loop {
+        let end = rest.find_byte(b'\'')?;
+        out.extend_from_slice(&rest[..end]);
+        rest = &rest[end + 1..];
+        match rest {
+            [] => return Some(out),
+            [b'\\', escaped, b'\'', remainder @ ..] => {
+                out.push(*escaped);
+                rest = remainder;
+            }
+            _ => return None,
+        }
+    }
+}
@@ -1,2 +1,112 @@
-#![deny(rust_2018_idioms)]
+//! Read and write the state of a rebase in progress, as stored by `git rebase` in the `rebase-merge` and `rebase-apply`
+//! directories of a git repository.
+//!
+//! Driving a rebase is left to higher-level crates, which can use this crate to persist their progress in a way that is
+//! compatible with `git`.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+use std::path::Path;
+
+use bstr::BStr;
+
+/// The name of the directory, relative to the git directory, in which the merge-backend of `git rebase` keeps its state.
+pub const MERGE_DIR: &str = "rebase-merge";
+/// The name of the directory, relative to the git directory, in which the apply-backend of `git rebase` and `git am`
+/// keep their state.
+pub const APPLY_DIR: &str = "rebase-apply";
+
+/// The state of a rebase in progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum State {
+    /// The state of the merge-backend, which is used for interactive rebases and is the default.
+    Merge(merge::State),
+    /// The state of the apply-backend, as used by `git rebase --apply` and `git am`.
+    Apply(apply::State),
+}
+
+///
+pub mod author_script;
+
+///
+pub mod merge;
+
+///
+pub mod apply;
+
+mod util;
+
+///
+pub mod read {
+    use std::path::PathBuf;
+
+    /// The error returned when reading the state of a rebase.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read {path:?}")]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("Could not parse object id in {path:?}")]
+        ObjectId {
+            source: gix_hash::decode::Error,
+            path: PathBuf,
+        },
+        #[error("Could not parse number in {path:?}")]
+        Number {
+            source: std::num::ParseIntError,
+            path: PathBuf,
+        },
+        #[error("Could not parse the todo list at {path:?}")]
+        TodoList {
+            source: gix_sequencer::todo::parse::Error,
+            path: PathBuf,
+        },
+        #[error("Could not parse the author script at {path:?}")]
+        AuthorScript {
+            source: crate::author_script::Error,
+            path: PathBuf,
+        },
+    }
+}
+
+impl State {
+    /// Read the state of a rebase in progress from `git_dir`, or return `None` if no rebase is in progress.
+    ///
+    /// Note that the `rebase-apply` directory is also used by `git am`, which is reported as [`apply::Kind::ApplyMailbox`].
+    pub fn from_git_dir(git_dir: &Path) -> Result<Option<Self>, read::Error> {
+        let apply_dir = git_dir.join(APPLY_DIR);
+        if apply_dir.is_dir() {
+            return apply::State::from_dir(&apply_dir).map(|state| Some(State::Apply(state)));
+        }
+        let merge_dir = git_dir.join(MERGE_DIR);
+        if merge_dir.is_dir() {
+            return merge::State::from_dir(&merge_dir).map(|state| Some(State::Merge(state)));
+        }
+        Ok(None)
+    }
+
+    /// Return the full name of the branch that is rebased, or `None` if `HEAD` was detached when the rebase started.
+    pub fn head_name(&self) -> Option<&BStr> {
+        match self {
+            State::Merge(state) => state.head_name.as_ref().map(AsRef::as_ref),
+            State::Apply(state) => state.head_name.as_ref().map(AsRef::as_ref),
+        }
+    }
+
+    /// Return the commit onto which commits are rebased, if known.
+    pub fn onto(&self) -> Option<gix_hash::ObjectId> {
+        match self {
+            State::Merge(state) => Some(state.onto),
+            State::Apply(state) => state.onto,
+        }
+    }
+
+    /// Return the commit `HEAD` pointed to when the rebase started, if known.
+    pub fn orig_head(&self) -> Option<gix_hash::ObjectId> {
+        match self {
+            State::Merge(state) => Some(state.orig_head),
+            State::Apply(state) => state.orig_head,
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::Path;
+
+use bstr::{BString, ByteSlice};
+use gix_hash::ObjectId;
+use gix_sequencer::todo;
+
+use crate::{author_script, read, util};
+
+/// The state of the merge-backend of `git rebase`, as stored in the [`rebase-merge`][crate::MERGE_DIR] directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// The full name of the branch being rebased, or `None` if `HEAD` was detached when the rebase started.
+    pub head_name: Option<BString>,
+    /// The commit onto which the commits are rebased.
+    pub onto: ObjectId,
+    /// The commit `HEAD` pointed to when the rebase started.
+    pub orig_head: ObjectId,
+    /// If `true`, the rebase is interactive, as started with `git rebase --interactive`.
+    pub interactive: bool,
+    /// The instructions that are still to be executed.
+    pub todo: todo::List,
+    /// The instructions that were executed already, with the last one being the one currently in progress.
+    pub done: todo::List,
+    /// The commit of the instruction at which the rebase stopped, for instance due to a conflict or an `edit` instruction.
+    pub stopped_at: Option<ObjectId>,
+    /// The message of the commit that is to be created when the rebase continues.
+    pub message: Option<BString>,
+    /// The author of the commit that is to be created when the rebase continues.
+    pub author: Option<gix_actor::Signature>,
+    /// The commit that is to be amended when the rebase continues, as after stopping due to an `edit` instruction.
+    pub amend: Option<ObjectId>,
+    /// The references to update once the rebase is done, as added by `update-ref` instructions.
+    pub update_refs: Vec<UpdateRef>,
+    /// The full names of the references to delete once the rebase is done, like the ones created by `label` instructions.
+    pub refs_to_delete: Vec<BString>,
+}
+
+/// A reference to update once the rebase is done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateRef {
+    /// The full name of the reference.
+    pub name: BString,
+    /// The commit the reference pointed to when the rebase started.
+    pub before: ObjectId,
+    /// The commit the reference should point to, which is the null-id if the `update-ref` instruction wasn't executed yet.
+    pub after: ObjectId,
+}
+
+impl State {
+    /// Create a new instance to rebase the commits in `todo` onto `onto`, with `orig_head` being the commit `HEAD` pointed to,
+    /// which is possibly the tip of the branch named `head_name`.
+    pub fn new(head_name: Option<BString>, onto: ObjectId, orig_head: ObjectId, todo: todo::List) -> Self {
+        State {
+            head_name,
+            onto,
+            orig_head,
+            interactive: false,
+            todo,
+            done: Default::default(),
+            stopped_at: None,
+            message: None,
+            author: None,
+            amend: None,
+            update_refs: Vec::new(),
+            refs_to_delete: Vec::new(),
+        }
+    }
+
+    /// Read the state from the `rebase-merge` directory at `dir`.
+    ///
+    /// Note that the todo lists are parsed assuming that `#` is the comment character.
+    pub fn from_dir(dir: &Path) -> Result<Self, read::Error> {
+        let read_todo = |name: &str| -> Result<todo::List, read::Error> {
+            util::read_file(dir, name)?
+                .map(|data| todo::List::from_bytes(&data, b'#'))
+                .transpose()
+                .map(Option::unwrap_or_default)
+                .map_err(|source| read::Error::TodoList {
+                    source,
+                    path: dir.join(name),
+                })
+        };
+        Ok(State {
+            head_name: util::read_head_name(dir)?,
+            onto: util::read_existing_id(dir, "onto")?,
+            orig_head: util::read_existing_id(dir, "orig-head")?,
+            interactive: dir.join("interactive").is_file(),
+            todo: read_todo("git-rebase-todo")?,
+            done: read_todo("done")?,
+            stopped_at: util::read_id(dir, "stopped-sha")?,
+            message: util::read_file(dir, "message")?.map(Into::into),
+            author: util::read_file(dir, "author-script")?
+                .map(|data| author_script::parse(&data))
+                .transpose()
+                .map_err(|source| read::Error::AuthorScript {
+                    source,
+                    path: dir.join("author-script"),
+                })?,
+            amend: util::read_id(dir, "amend")?,
+            update_refs: parse_update_refs(&util::read_file(dir, "update-refs")?.unwrap_or_default(), dir)?,
+            refs_to_delete: util::read_file(dir, "refs-to-delete")?
+                .unwrap_or_default()
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(Into::into)
+                .collect(),
+        })
+    }
+
+    /// Write this state to the `rebase-merge` directory at `dir`, which is created if needed.
+    ///
+    /// Optional values that aren't set will have their files removed, and the `msgnum` and `end` files are written for compatibility
+    /// with tools that display the progress of the rebase.
+    pub fn write_to_dir(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        util::write_head_name(dir, self.head_name.as_ref().map(|name| name.as_slice()))?;
+        util::write_line(dir, "onto", self.onto.to_hex().to_string().as_bytes())?;
+        util::write_line(dir, "orig-head", self.orig_head.to_hex().to_string().as_bytes())?;
+        util::write_or_remove(dir, "interactive", self.interactive.then_some(&[][..]))?;
+
+        let mut buf = Vec::new();
+        self.todo.write_to(&mut buf)?;
+        util::write_file(dir, "git-rebase-todo", &buf)?;
+        buf.clear();
+        self.done.write_to(&mut buf)?;
+        util::write_file(dir, "done", &buf)?;
+        let done = self.done.instructions.len();
+        util::write_line(dir, "msgnum", done.to_string().as_bytes())?;
+        util::write_line(dir, "end", (done + self.todo.instructions.len()).to_string().as_bytes())?;
+
+        let hex_line = |id: &Option<ObjectId>| id.map(|id| format!("{}\n", id.to_hex()).into_bytes());
+        util::write_or_remove(dir, "stopped-sha", hex_line(&self.stopped_at).as_deref())?;
+        util::write_or_remove(dir, "message", self.message.as_ref().map(|m| m.as_slice()))?;
+        let author = self
+            .author
+            .as_ref()
+            .map(|author| {
+                let mut buf = Vec::new();
+                author_script::write(author.to_ref(), &mut buf).map(|_| buf)
+            })
+            .transpose()?;
+        util::write_or_remove(dir, "author-script", author.as_deref())?;
+        util::write_or_remove(dir, "amend", hex_line(&self.amend).as_deref())?;
+
+        let update_refs = (!self.update_refs.is_empty()).then(|| {
+            let mut buf = Vec::new();
+            for update in &self.update_refs {
+                buf.extend_from_slice(&update.name);
+                buf.extend_from_slice(format!("\n{}\n{}\n", update.before, update.after).as_bytes());
+            }
+            buf
+        });
+        util::write_or_remove(dir, "update-refs", update_refs.as_deref())?;
+        let refs_to_delete = (!self.refs_to_delete.is_empty()).then(|| {
+            let mut buf = Vec::new();
+            for name in &self.refs_to_delete {
+                buf.extend_from_slice(name);
+                buf.push(b'\n');
+            }
+            buf
+        });
+        util::write_or_remove(dir, "refs-to-delete", refs_to_delete.as_deref())
+    }
+}
+
+fn parse_update_refs(data: &[u8], dir: &Path) -> Result<Vec<UpdateRef>, read::Error> {
+    let parse_id = |hex: &[u8]| {
+        ObjectId::from_hex(hex).map_err(|source| read::Error::ObjectId {
+            source,
+            path: dir.join("update-refs"),
+        })
+    };
+    let mut out = Vec::new();
+    let mut lines = data.lines().filter(|line| !line.is_empty());
+    while let Some(name) = lines.next() {
+        let (before, after) = match (lines.next(), lines.next()) {
+            (Some(before), Some(after)) => (before, after),
+            _ => {
+                return Err(read::Error::Io {
+                    source: std::io::ErrorKind::UnexpectedEof.into(),
+                    path: dir.join("update-refs"),
+                })
+            }
+        };
+        out.push(UpdateRef {
+            name: name.into(),
+            before: parse_id(before)?,
+            after: parse_id(after)?,
+        });
+    }
+    Ok(out)
+}
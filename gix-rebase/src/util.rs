@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use bstr::{BString, ByteSlice};
+
+use crate::read::Error;
+
+/// The value git writes into `head-name` if `HEAD` was detached when the rebase started.
+const DETACHED_HEAD: &[u8] = b"detached HEAD";
+
+pub(crate) fn read_file(dir: &Path, name: &str) -> Result<Option<Vec<u8>>, Error> {
+    let path = dir.join(name);
+    match std::fs::read(&path) {
+        Ok(data) => Ok(Some(data)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(Error::Io { source, path }),
+    }
+}
+
+pub(crate) fn read_line(dir: &Path, name: &str) -> Result<Option<BString>, Error> {
+    Ok(read_file(dir, name)?.map(|data| data.trim_end().into()))
+}
+
+pub(crate) fn read_id(dir: &Path, name: &str) -> Result<Option<gix_hash::ObjectId>, Error> {
+    read_line(dir, name)?
+        .map(|line| {
+            gix_hash::ObjectId::from_hex(&line).map_err(|source| Error::ObjectId {
+                source,
+                path: dir.join(name),
+            })
+        })
+        .transpose()
+}
+
+pub(crate) fn read_existing_id(dir: &Path, name: &str) -> Result<gix_hash::ObjectId, Error> {
+    read_id(dir, name)?.ok_or_else(|| Error::Io {
+        source: std::io::ErrorKind::NotFound.into(),
+        path: dir.join(name),
+    })
+}
+
+pub(crate) fn read_number(dir: &Path, name: &str) -> Result<Option<usize>, Error> {
+    read_line(dir, name)?
+        .map(|line| {
+            line.to_str_lossy().parse().map_err(|source| Error::Number {
+                source,
+                path: dir.join(name),
+            })
+        })
+        .transpose()
+}
+
+pub(crate) fn read_head_name(dir: &Path) -> Result<Option<BString>, Error> {
+    Ok(read_line(dir, "head-name")?.filter(|name| name != DETACHED_HEAD))
+}
+
+pub(crate) fn write_file(dir: &Path, name: &str, data: &[u8]) -> std::io::Result<()> {
+    std::fs::write(dir.join(name), data)
+}
+
+pub(crate) fn write_line(dir: &Path, name: &str, line: &[u8]) -> std::io::Result<()> {
+    let mut data = Vec::with_capacity(line.len() + 1);
+    data.extend_from_slice(line);
+    data.push(b'\n');
+    write_file(dir, name, &data)
+}
+
+pub(crate) fn write_head_name(dir: &Path, head_name: Option<&[u8]>) -> std::io::Result<()> {
+    write_line(dir, "head-name", head_name.unwrap_or(DETACHED_HEAD))
+}
+
+/// Write `data` to the file `name` in `dir` if it is set, or remove the file if it is not.
+pub(crate) fn write_or_remove(dir: &Path, name: &str, data: Option<&[u8]>) -> std::io::Result<()> {
+    match data {
+        Some(data) => write_file(dir, name, data),
+        None => match std::fs::remove_file(dir.join(name)) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        },
+    }
+}
@@ -0,0 +1,48 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function repo_with_conflicting_branch() {
+  git init -q "$1"
+  (cd "$1"
+    echo base > file
+    git add file && git commit -q -m base
+    git checkout -q -b feature
+    echo feature > file && git commit -q -am "feature's change"
+    echo other > other && git add other && git commit -q -m "add other"
+    git checkout -q main 2>/dev/null || git checkout -q master
+    echo main > file && git commit -q -am "main's change"
+    git checkout -q feature
+    # the commit to rebase onto, the tip of the branch to rebase and the first commit to pick
+    git rev-parse @{-1} feature feature~1 > ids
+  )
+}
+
+git init -q none
+(cd none && git commit -q --allow-empty -m "no rebase")
+
+repo_with_conflicting_branch merge-conflict
+(cd merge-conflict
+  git rebase --merge master >/dev/null 2>&1 || git rebase --merge main >/dev/null 2>&1 || :
+)
+
+repo_with_conflicting_branch apply-conflict
+(cd apply-conflict
+  git rebase --apply master >/dev/null 2>&1 || git rebase --apply main >/dev/null 2>&1 || :
+)
+
+repo_with_conflicting_branch am-conflict
+(cd am-conflict
+  git format-patch -q -2 -o ../patches
+  git checkout -q master 2>/dev/null || git checkout -q main
+  git am ../patches/*.patch >/dev/null 2>&1 || :
+)
+
+git init -q interactive-edit
+(cd interactive-edit
+  for name in 1 2 3; do
+    touch $name && git add $name && git commit -q -m "$name" $name
+  done
+  git branch stacked HEAD~1
+  git rev-parse HEAD~2 HEAD HEAD~1 > ids
+  GIT_EDITOR="sed -i.bak 's/^pick/edit/g'" git rebase -q --interactive --update-refs --rebase-merges HEAD~2
+)
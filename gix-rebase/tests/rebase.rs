@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+use gix_rebase::{apply, merge, State};
+use gix_sequencer::todo::Instruction;
+
+pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error + 'static>>;
+
+fn fixture(name: &str) -> Result<PathBuf> {
+    Ok(gix_testtools::scripted_fixture_read_only("make_rebase_states.sh")?.join(name))
+}
+
+/// Return the commit to rebase onto, the tip of the branch to rebase and the commit at which the rebase stopped.
+fn ids(repo: &Path) -> Result<[ObjectId; 3]> {
+    let ids = std::fs::read_to_string(repo.join("ids"))?;
+    let ids: Vec<_> = ids
+        .lines()
+        .map(|hex| ObjectId::from_hex(hex.as_bytes()))
+        .collect::<std::result::Result<_, _>>()?;
+    Ok([ids[0], ids[1], ids[2]])
+}
+
+fn state(repo: &Path) -> Result<State> {
+    Ok(State::from_git_dir(&repo.join(".git"))?.expect("rebase in progress"))
+}
+
+#[test]
+fn no_rebase_in_progress() -> Result {
+    assert_eq!(State::from_git_dir(&fixture("none")?.join(".git"))?, None);
+    Ok(())
+}
+
+#[test]
+fn merge_backend_stopped_at_conflict() -> Result {
+    let repo = fixture("merge-conflict")?;
+    let [onto, orig_head, stopped_at] = ids(&repo)?;
+    let State::Merge(state) = state(&repo)? else {
+        unreachable!("merge backend")
+    };
+    assert_eq!(state.head_name.as_ref().expect("on branch"), "refs/heads/feature");
+    assert_eq!(state.onto, onto);
+    assert_eq!(state.orig_head, orig_head);
+    assert_eq!(state.stopped_at, Some(stopped_at));
+    assert_eq!(state.amend, None, "there is nothing to amend");
+    assert_eq!(state.message.as_ref().expect("set"), "feature's change\n\n");
+    let author = state.author.as_ref().expect("set");
+    assert_eq!(author.name, "author");
+    assert_eq!(author.email, "author@example.com");
+    assert_eq!(author.time, gix_date::Time::new(946684800, 0));
+    assert_eq!(
+        state.done.instructions,
+        [Instruction::Pick {
+            commit: stopped_at.to_string().into(),
+            subject: "feature's change".into()
+        }]
+    );
+    assert_eq!(state.todo.instructions.len(), 1);
+    assert_eq!(state.todo.instructions[0].name(), "pick");
+    assert!(state.update_refs.is_empty());
+    Ok(())
+}
+
+#[test]
+fn interactive_with_update_refs() -> Result {
+    let repo = fixture("interactive-edit")?;
+    let [onto, orig_head, stopped_at] = ids(&repo)?;
+    let State::Merge(state) = state(&repo)? else {
+        unreachable!("merge backend")
+    };
+    assert!(state.interactive);
+    assert_eq!(state.onto, onto);
+    assert_eq!(state.orig_head, orig_head);
+    assert_eq!(state.stopped_at, Some(stopped_at));
+    assert_eq!(
+        state.amend,
+        Some(stopped_at),
+        "the commit was fast-forwarded and can be amended"
+    );
+    assert_eq!(
+        state.update_refs,
+        [merge::UpdateRef {
+            name: "refs/heads/stacked".into(),
+            before: stopped_at,
+            after: ObjectId::null(gix_hash::Kind::Sha1),
+        }]
+    );
+    assert_eq!(state.refs_to_delete, ["refs/rewritten/onto"]);
+    let names: Vec<_> = state.done.instructions.iter().map(Instruction::name).collect();
+    assert_eq!(names, ["label", "reset", "edit"]);
+    let names: Vec<_> = state.todo.instructions.iter().map(Instruction::name).collect();
+    assert_eq!(names, ["update-ref", "edit"]);
+    Ok(())
+}
+
+#[test]
+fn apply_backend_stopped_at_conflict() -> Result {
+    let repo = fixture("apply-conflict")?;
+    let [onto, orig_head, _] = ids(&repo)?;
+    let state = state(&repo)?;
+    assert_eq!(state.head_name().expect("on branch"), "refs/heads/feature");
+    assert_eq!(state.onto(), Some(onto));
+    assert_eq!(state.orig_head(), Some(orig_head));
+    let State::Apply(state) = state else {
+        unreachable!("apply backend")
+    };
+    assert_eq!(state.kind, apply::Kind::Rebase);
+    assert_eq!((state.next, state.last), (1, 2));
+    Ok(())
+}
+
+#[test]
+fn apply_mailbox_stopped_at_conflict() -> Result {
+    let State::Apply(state) = state(&fixture("am-conflict")?)? else {
+        unreachable!("apply backend")
+    };
+    assert_eq!(
+        state,
+        apply::State {
+            kind: apply::Kind::ApplyMailbox,
+            head_name: None,
+            onto: None,
+            orig_head: None,
+            next: 1,
+            last: 2,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn write_and_read_roundtrip_with_files_like_git() -> Result {
+    for name in ["merge-conflict", "interactive-edit"] {
+        let git_dir = fixture(name)?.join(".git").join(gix_rebase::MERGE_DIR);
+        let state = merge::State::from_dir(&git_dir)?;
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let dir = tmp.path().join(gix_rebase::MERGE_DIR);
+        state.write_to_dir(&dir)?;
+        assert_eq!(merge::State::from_dir(&dir)?, state, "{name}");
+
+        for file in [
+            "head-name",
+            "onto",
+            "orig-head",
+            "interactive",
+            "stopped-sha",
+            "message",
+            "author-script",
+            "amend",
+            "update-refs",
+            "refs-to-delete",
+            "msgnum",
+        ] {
+            let expected = std::fs::read(git_dir.join(file)).ok();
+            let actual = std::fs::read(dir.join(file)).ok();
+            assert_eq!(actual, expected, "{name}: {file} is written like git does");
+        }
+    }
+
+    let state = State::from_git_dir(&fixture("apply-conflict")?.join(".git"))?.expect("present");
+    let State::Apply(state) = state else {
+        unreachable!("apply backend")
+    };
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    state.write_to_dir(tmp.path())?;
+    assert_eq!(apply::State::from_dir(tmp.path())?, state);
+    Ok(())
+}
+
+#[test]
+fn author_script_quoting() -> Result {
+    let author = gix_actor::Signature {
+        name: "O'Neil!".into(),
+        email: "o@example.com".into(),
+        time: gix_date::Time::new(42, -3600),
+    };
+    let mut buf = Vec::new();
+    gix_rebase::author_script::write(author.to_ref(), &mut buf)?;
+    assert_eq!(
+        buf,
+        b"GIT_AUTHOR_NAME='O'\\''Neil'\\!''\nGIT_AUTHOR_EMAIL='o@example.com'\nGIT_AUTHOR_DATE='@42 -0100'\n"
+    );
+    assert_eq!(gix_rebase::author_script::parse(&buf)?, author);
+    assert!(gix_rebase::author_script::parse(b"GIT_AUTHOR_NAME='unterminated").is_err());
+    assert!(
+        gix_rebase::author_script::parse(b"GIT_AUTHOR_NAME='name'").is_err(),
+        "missing variables"
+    );
+    Ok(())
+}
@@ -405,6 +405,12 @@ impl<'s, 'p> Transaction<'s, 'p> {
             .map(|updates| updates.into_iter().map(|u| u.update).collect())
             .unwrap_or_default()
     }
+
+    /// Return the `RefEdits` as they will be committed, after splitting symbolic references and with the previous
+    /// values set to their current state on disk, or an empty iterator if the transaction wasn't prepared.
+    pub fn edits(&self) -> impl Iterator<Item = &RefEdit> + '_ {
+        self.updates.iter().flatten().map(|u| &u.update)
+    }
 }
 
 fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
@@ -1,4 +1,5 @@
-//! Interact with git revisions by parsing them from rev-specs and describing them in terms of reference names.
+//! Interact with git revisions by parsing them from rev-specs, describing them in terms of reference names
+//! and finding their merge-bases.
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -12,6 +13,10 @@
 pub mod describe;
 pub use describe::function::describe;
 
+///
+pub mod merge_base;
+pub use merge_base::function::merge_base;
+
 ///
 pub mod spec;
 pub use gix_revwalk::{graph, Graph, PriorityQueue};
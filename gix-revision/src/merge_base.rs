@@ -0,0 +1,424 @@
+use gix_revwalk::graph;
+
+bitflags::bitflags! {
+    /// The flags used to mark commits in the [graph](Graph) while searching for merge-bases.
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+    pub struct Flags: u8 {
+        /// The commit is reachable from the first commit.
+        const COMMIT1 = 1 << 0;
+        /// The commit is reachable from any of the other commits.
+        const COMMIT2 = 1 << 1;
+        /// The commit is reachable from a merge-base candidate, which is why it can't be a merge-base itself.
+        const STALE = 1 << 2;
+        /// The commit was recorded as merge-base candidate.
+        const RESULT = 1 << 3;
+    }
+}
+
+/// The graph type all functions in this module operate on.
+///
+/// Note that it is cleared before each use, so it merely serves as re-usable allocation.
+pub type Graph<'find> = crate::Graph<'find, graph::Commit<Flags>>;
+
+/// The error returned by the [`merge_base()`][function::merge_base()] function and its siblings.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A commit could not be inserted into the graph")]
+    InsertCommit(#[from] graph::lookup::commit::Error),
+    #[error("The commit {id} could not be found")]
+    MissingCommit { id: gix_hash::ObjectId },
+}
+
+pub use function::{ahead_behind, exclusive, independent, is_ancestor, octopus};
+
+pub(crate) mod function {
+    use gix_date::SecondsSinceUnixEpoch;
+    use gix_hash::ObjectId;
+    use gix_revwalk::graph::Generation;
+
+    use super::{Error, Flags, Graph};
+    use crate::PriorityQueue;
+
+    /// Given a commit at `first` id, traverse the commit `graph` and return all possible merge-base between it and `others`,
+    /// sorted from best to worst, or `None` if there is no merge-base.
+    ///
+    /// If there is more than one commit in `others`, the merge-base is the one of `first` and a hypothetical merge
+    /// of all `others`, similar to `git merge-base --all <first> <others>…`.
+    ///
+    /// Note that if a commit-graph is available to the `graph`, generation numbers will be used to speed up the traversal.
+    pub fn merge_base(
+        first: ObjectId,
+        others: &[ObjectId],
+        graph: &mut Graph<'_>,
+    ) -> Result<Option<Vec<ObjectId>>, Error> {
+        if others.contains(&first) {
+            return Ok(Some(vec![first]));
+        }
+
+        let bases = paint_down_to_common(first, others, graph, None)?;
+        let mut bases: Vec<_> = bases
+            .into_iter()
+            .filter_map(|id| {
+                let commit = &graph[&id];
+                (!commit.data.contains(Flags::STALE)).then_some((id, commit.commit_time))
+            })
+            .collect();
+        bases.sort_by_key(|(_id, time)| std::cmp::Reverse(*time));
+        let bases: Vec<_> = bases.into_iter().map(|(id, _time)| id).collect();
+
+        let bases = remove_redundant(&bases, graph)?;
+        Ok((!bases.is_empty()).then_some(bases))
+    }
+
+    /// Return the merge-bases that are suitable for merging all `commits` at once in an octopus merge, or `None` if there
+    /// is no such merge-base, similar to `git merge-base --octopus --all <commits>…`.
+    pub fn octopus(commits: &[ObjectId], graph: &mut Graph<'_>) -> Result<Option<Vec<ObjectId>>, Error> {
+        let Some((first, rest)) = commits.split_first() else {
+            return Ok(None);
+        };
+        let mut bases = vec![*first];
+        for commit in rest {
+            let mut next_bases = Vec::new();
+            for base in &bases {
+                if let Some(new_bases) = merge_base(*commit, &[*base], graph)? {
+                    next_bases.extend(new_bases);
+                }
+            }
+            bases = next_bases;
+        }
+
+        let bases = independent(&bases, graph)?;
+        Ok((!bases.is_empty()).then_some(bases))
+    }
+
+    /// Return all `commits` that can't be reached from any other commit in `commits`, in the order in which they appear,
+    /// similar to `git merge-base --independent <commits>…`.
+    ///
+    /// Duplicates are removed as well.
+    pub fn independent(commits: &[ObjectId], graph: &mut Graph<'_>) -> Result<Vec<ObjectId>, Error> {
+        let mut unique = Vec::with_capacity(commits.len());
+        for id in commits {
+            if !unique.contains(id) {
+                unique.push(*id);
+            }
+        }
+        remove_redundant(&unique, graph)
+    }
+
+    /// Return `true` if `ancestor` can be reached from `descendant`, similar to `git merge-base --is-ancestor <ancestor> <descendant>`.
+    ///
+    /// Note that each commit is its own ancestor.
+    pub fn is_ancestor(ancestor: ObjectId, descendant: ObjectId, graph: &mut Graph<'_>) -> Result<bool, Error> {
+        if ancestor == descendant {
+            return Ok(true);
+        }
+        let ancestor_generation = generation(ancestor, graph)?;
+        if let (Some(ancestor_generation), Some(descendant_generation)) =
+            (ancestor_generation, generation(descendant, graph)?)
+        {
+            if ancestor_generation >= descendant_generation {
+                return Ok(false);
+            }
+        }
+
+        paint_down_to_common(ancestor, &[descendant], graph, ancestor_generation)?;
+        Ok(graph[&ancestor].data.contains(Flags::COMMIT2))
+    }
+
+    /// Return `(ahead, behind)`, the amount of commits reachable from `first` but not from `second`, and the amount of commits
+    /// reachable from `second` but not from `first`, similar to `git rev-list --left-right --count <first>...<second>`.
+    ///
+    /// The traversal stops once only commits reachable from both are left to visit, so only the commits down to the
+    /// merge-bases of both are looked at. Note that if a commit-graph is available to the `graph`, generation numbers will be
+    /// used to order the traversal, otherwise commit times are used which may lead to miscounts in case of clock skew.
+    pub fn ahead_behind(first: ObjectId, second: ObjectId, graph: &mut Graph<'_>) -> Result<(usize, usize), Error> {
+        if first == second {
+            return Ok((0, 0));
+        }
+
+        let both = Flags::COMMIT1 | Flags::COMMIT2;
+        Ok(paint_left_right(&[first], &[second], graph)?
+            .iter()
+            .fold((0, 0), |(ahead, behind), id| match graph[id].data & both {
+                flags if flags == Flags::COMMIT1 => (ahead + 1, behind),
+                flags if flags == Flags::COMMIT2 => (ahead, behind + 1),
+                _ => (ahead, behind),
+            }))
+    }
+
+    /// Return all commits reachable from any of `included` but not from any of `excluded`, similar to
+    /// `git rev-list <included>… ^<excluded>…`, in no particular order.
+    ///
+    /// Just like [`ahead_behind()`], only the commits down to the merge-bases of both sides are looked at, and the commits
+    /// are left in `graph` afterwards so their parents can be obtained without looking them up again.
+    pub fn exclusive(
+        included: &[ObjectId],
+        excluded: &[ObjectId],
+        graph: &mut Graph<'_>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        if included.iter().all(|id| excluded.contains(id)) {
+            return Ok(Vec::new());
+        }
+
+        let both = Flags::COMMIT1 | Flags::COMMIT2;
+        let mut commits = paint_left_right(included, excluded, graph)?;
+        commits.retain(|id| graph[id].data & both == Flags::COMMIT1);
+        Ok(commits)
+    }
+
+    /// Mark all commits reachable from `first` with [`Flags::COMMIT1`] and all commits reachable from `second` with
+    /// [`Flags::COMMIT2`], stopping once only commits reachable from both are left, and return all commits that were marked.
+    fn paint_left_right(
+        first: &[ObjectId],
+        second: &[ObjectId],
+        graph: &mut Graph<'_>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        graph.clear();
+        let both = Flags::COMMIT1 | Flags::COMMIT2;
+        let mut queue = Queue::new(both);
+        let mut seen = Vec::new();
+        for (ids, flags) in [(first, Flags::COMMIT1), (second, Flags::COMMIT2)] {
+            for id in ids {
+                let key = insert_commit(*id, Flags::empty(), graph)?;
+                let commit = graph.get_mut(id).expect("just inserted");
+                let previous = commit.data;
+                if previous.is_empty() {
+                    seen.push(*id);
+                }
+                commit.data |= flags;
+                queue.insert_or_update(*id, key, previous, commit.data);
+            }
+        }
+
+        let mut only_shared_commits_queued = false;
+        let mut oldest_exclusive_commit = None;
+        loop {
+            if !only_shared_commits_queued && !queue.has_non_stale() {
+                only_shared_commits_queued = true;
+                oldest_exclusive_commit = seen
+                    .iter()
+                    .map(|id| &graph[id])
+                    .filter(|c| !c.data.contains(both))
+                    .map(GenThenTime::from)
+                    .min();
+            }
+            let Some((key, id)) = queue.pop(graph) else {
+                break;
+            };
+            // Shared commits only matter as long as they could be descendants of commits we counted as exclusive,
+            // which may happen without generation numbers as commit times don't necessarily increase.
+            if only_shared_commits_queued && oldest_exclusive_commit.map_or(true, |oldest| key < oldest) {
+                break;
+            }
+
+            let commit = graph.get(&id).expect("queued commits are in the graph");
+            let flags = commit.data & both;
+            for parent_id in commit.parents.clone() {
+                // Parents may be missing in shallow repositories.
+                let Some(parent) = graph.try_lookup_or_insert_commit(parent_id, |_| {})? else {
+                    continue;
+                };
+                if parent.data.contains(flags) {
+                    continue;
+                }
+                if parent.data.is_empty() {
+                    seen.push(parent_id);
+                }
+                let previous = parent.data;
+                parent.data |= flags;
+                queue.insert_or_update(parent_id, GenThenTime::from(&*parent), previous, parent.data);
+            }
+        }
+
+        Ok(seen)
+    }
+
+    /// Remove all commits from `commits` which are reachable from any other commit in `commits`, retaining their order.
+    fn remove_redundant(commits: &[ObjectId], graph: &mut Graph<'_>) -> Result<Vec<ObjectId>, Error> {
+        if commits.len() < 2 {
+            return Ok(commits.to_owned());
+        }
+
+        let mut min_generation = Some(GENERATION_NUMBER_INFINITY);
+        for id in commits {
+            min_generation = min_generation
+                .zip(generation(*id, graph)?)
+                .map(|(min, generation)| min.min(generation));
+        }
+
+        let mut redundant = vec![false; commits.len()];
+        for (idx, id) in commits.iter().enumerate() {
+            if redundant[idx] {
+                continue;
+            }
+            let others: Vec<_> = commits
+                .iter()
+                .zip(redundant.iter())
+                .enumerate()
+                .filter_map(|(other_idx, (other, is_redundant))| (other_idx != idx && !is_redundant).then_some(*other))
+                .collect();
+            if others.is_empty() {
+                break;
+            }
+
+            paint_down_to_common(*id, &others, graph, min_generation)?;
+            if graph[id].data.contains(Flags::COMMIT2) {
+                redundant[idx] = true;
+            }
+            for (other_idx, other) in commits.iter().enumerate() {
+                if other_idx != idx && graph.get(other).map_or(false, |c| c.data.contains(Flags::COMMIT1)) {
+                    redundant[other_idx] = true;
+                }
+            }
+        }
+
+        Ok(commits
+            .iter()
+            .zip(redundant)
+            .filter_map(|(id, is_redundant)| (!is_redundant).then_some(*id))
+            .collect())
+    }
+
+    /// Walk the graph from `first` and `others` at the same time and mark all commits that are reachable from both,
+    /// returning them in the order in which they were found.
+    ///
+    /// If `min_generation` is set, commits with a lower generation will not be traversed.
+    fn paint_down_to_common(
+        first: ObjectId,
+        others: &[ObjectId],
+        graph: &mut Graph<'_>,
+        min_generation: Option<Generation>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        graph.clear();
+        let mut queue = Queue::new(Flags::STALE);
+        queue.insert_or_update(
+            first,
+            insert_commit(first, Flags::COMMIT1, graph)?,
+            Flags::empty(),
+            Flags::COMMIT1,
+        );
+        for other in others {
+            queue.insert_or_update(
+                *other,
+                insert_commit(*other, Flags::COMMIT2, graph)?,
+                Flags::empty(),
+                Flags::COMMIT2,
+            );
+        }
+
+        let mut out = Vec::new();
+        while queue.has_non_stale() {
+            let (key, id) = queue.pop(graph).expect("at least one non-stale commit is queued");
+            if min_generation.map_or(false, |min_generation| key.generation < min_generation) {
+                break;
+            }
+
+            let commit = graph.get_mut(&id).expect("queued commits are in the graph");
+            let mut flags = commit.data & (Flags::COMMIT1 | Flags::COMMIT2 | Flags::STALE);
+            if flags == Flags::COMMIT1 | Flags::COMMIT2 {
+                if !commit.data.contains(Flags::RESULT) {
+                    commit.data |= Flags::RESULT;
+                    out.push(id);
+                }
+                flags |= Flags::STALE;
+            }
+
+            for parent_id in commit.parents.clone() {
+                // Parents may be missing in shallow repositories.
+                let Some(parent) = graph.try_lookup_or_insert_commit(parent_id, |_| {})? else {
+                    continue;
+                };
+                if parent.data.contains(flags) {
+                    continue;
+                }
+                let previous = parent.data;
+                parent.data |= flags;
+                queue.insert_or_update(parent_id, GenThenTime::from(&*parent), previous, parent.data);
+            }
+        }
+        Ok(out)
+    }
+
+    fn insert_commit(id: ObjectId, flags: Flags, graph: &mut Graph<'_>) -> Result<GenThenTime, Error> {
+        let commit = graph
+            .try_lookup_or_insert_commit(id, |data| *data |= flags)?
+            .ok_or(Error::MissingCommit { id })?;
+        Ok(GenThenTime::from(&*commit))
+    }
+
+    fn generation(id: ObjectId, graph: &mut Graph<'_>) -> Result<Option<Generation>, Error> {
+        Ok(graph
+            .try_lookup_or_insert_commit(id, |_| {})?
+            .ok_or(Error::MissingCommit { id })?
+            .generation)
+    }
+
+    /// A priority queue of commits that contains each commit only once, and which keeps track of the amount of queued commits
+    /// whose flags don't contain all of the `stale` flags, to know when to stop without having to look at all queued commits.
+    ///
+    /// Commits don't have to be queued again if their flags change while they are queued as their priority stays the same.
+    struct Queue {
+        commits: PriorityQueue<GenThenTime, ObjectId>,
+        queued: gix_hashtable::HashSet<ObjectId>,
+        num_non_stale: usize,
+        stale: Flags,
+    }
+
+    impl Queue {
+        fn new(stale: Flags) -> Self {
+            Queue {
+                commits: PriorityQueue::new(),
+                queued: Default::default(),
+                num_non_stale: 0,
+                stale,
+            }
+        }
+
+        /// Queue the commit with `id` and `key` unless it is queued already, after its flags were changed from `previous` to `current`.
+        fn insert_or_update(&mut self, id: ObjectId, key: GenThenTime, previous: Flags, current: Flags) {
+            let is_stale = current.contains(self.stale);
+            if self.queued.insert(id) {
+                self.commits.insert(key, id);
+                if !is_stale {
+                    self.num_non_stale += 1;
+                }
+            } else if is_stale && !previous.contains(self.stale) {
+                self.num_non_stale -= 1;
+            }
+        }
+
+        fn pop(&mut self, graph: &Graph<'_>) -> Option<(GenThenTime, ObjectId)> {
+            let (key, id) = self.commits.pop()?;
+            self.queued.remove(&id);
+            if !graph[&id].data.contains(self.stale) {
+                self.num_non_stale -= 1;
+            }
+            Some((key, id))
+        }
+
+        fn has_non_stale(&self) -> bool {
+            self.num_non_stale > 0
+        }
+    }
+
+    /// The generation number assumed for commits that aren't part of the commit-graph.
+    const GENERATION_NUMBER_INFINITY: Generation = Generation::MAX;
+
+    /// The priority of a commit in the queue, where commits with higher generation are processed first,
+    /// followed by those with later commit time.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+    struct GenThenTime {
+        generation: Generation,
+        time: SecondsSinceUnixEpoch,
+    }
+
+    impl From<&gix_revwalk::graph::Commit<Flags>> for GenThenTime {
+        fn from(commit: &gix_revwalk::graph::Commit<Flags>) -> Self {
+            GenThenTime {
+                generation: commit.generation.unwrap_or(GENERATION_NUMBER_INFINITY),
+                time: commit.commit_time,
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+function commit() {
+  git commit -q --allow-empty -m "$1"
+  git tag "$1"
+}
+
+function baseline() {
+  local kind=${1:?first argument is the kind of merge-base invocation}
+  shift
+  {
+    echo "$kind $(git rev-parse "$@" | tr '\n' ' ')"
+    case "$kind" in
+      all) git merge-base --all "$@" || :;;
+      octopus) git merge-base --octopus --all "$@" || :;;
+      independent) git merge-base --independent "$@";;
+      is-ancestor) git merge-base --is-ancestor "$@" && echo true || echo false;;
+    esac
+    echo
+  } >> baseline
+}
+
+# A - B - C - D (main)
+#      \
+#       X1 - X2 (x)
+#        \
+#         Y1 (y)
+git checkout -q -b main
+commit A
+commit B
+git checkout -q -b x
+commit X1
+commit X2
+git checkout -q -b y X1
+commit Y1
+git checkout -q main
+commit C
+commit D
+
+git commit-graph write --no-progress --reachable
+
+# criss-cross merge, with commits not in the commit-graph
+git checkout -q -b c1 D
+commit C1
+git checkout -q -b c2 D
+commit C2
+git checkout -q c1
+git merge -q -m M1 C2 && git tag M1
+git checkout -q c2
+git merge -q -m M2 C1 && git tag M2
+
+# unrelated history
+git checkout -q --orphan unrelated
+commit U1
+
+# an octopus of the criss-cross, x and y
+git checkout -q -b octopus M1
+git merge -q -m O1 X2 Y1 && git tag O1
+
+baseline all A A
+baseline all B D
+baseline all D B
+baseline all X2 Y1
+baseline all X2 D
+baseline all M1 M2
+baseline all M1 C2
+baseline all O1 M2
+baseline all U1 D
+baseline all D X2 Y1
+baseline all C1 X2 C2
+baseline octopus X2 Y1 D
+baseline octopus M1 M2 X2
+baseline octopus C1 C2 Y1
+baseline octopus D U1 X2
+baseline octopus D
+baseline independent A B C D
+baseline independent X2 Y1 D
+baseline independent M1 M2 C1 C2 X1
+baseline independent O1 M1 M2 U1 U1
+baseline is-ancestor A D
+baseline is-ancestor D A
+baseline is-ancestor D D
+baseline is-ancestor X1 O1
+baseline is-ancestor C1 M2
+baseline is-ancestor M1 M2
+baseline is-ancestor U1 O1
+baseline is-ancestor X2 Y1
+
+function ahead_behind_baseline() {
+  {
+    echo "$(git rev-parse "$1" "$2" | tr '\n' ' ')"
+    git rev-list --left-right --count "$1...$2"
+    echo $(git rev-list "$2..$1")
+  } >> ahead-behind-baseline
+}
+
+ahead_behind_baseline A A
+ahead_behind_baseline D B
+ahead_behind_baseline B D
+ahead_behind_baseline X2 D
+ahead_behind_baseline X2 Y1
+ahead_behind_baseline M1 M2
+ahead_behind_baseline C1 M2
+ahead_behind_baseline O1 M2
+ahead_behind_baseline O1 A
+ahead_behind_baseline U1 D
+ahead_behind_baseline Y1 O1
@@ -0,0 +1,176 @@
+use gix_hash::ObjectId;
+use gix_odb::Find;
+use gix_revision::merge_base;
+
+use crate::hex_to_id;
+
+#[test]
+fn all_baseline_cases_match_git_with_and_without_commitgraph() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_merge_base_repo.sh")?;
+    let store = gix_odb::at(root.join(".git/objects"))?;
+    let baseline = parse_baseline(&std::fs::read_to_string(root.join("baseline"))?);
+    assert_eq!(baseline.len(), 28, "all cases are parsed");
+
+    for use_commitgraph in [false, true] {
+        let cache = use_commitgraph
+            .then(|| gix_commitgraph::Graph::from_info_dir(store.store_ref().path().join("info")).ok())
+            .flatten();
+        assert_eq!(cache.is_some(), use_commitgraph, "the commit-graph is present");
+        let mut graph = gix_revision::Graph::new(
+            |id, buf| {
+                store
+                    .try_find(id, buf)
+                    .map(|r| r.and_then(gix_object::Data::try_into_commit_iter))
+            },
+            cache,
+        );
+
+        for Case { kind, input, expected } in &baseline {
+            let actual = match kind.as_str() {
+                "all" => sorted(gix_revision::merge_base(input[0], &input[1..], &mut graph)?.unwrap_or_default()),
+                "octopus" => sorted(merge_base::octopus(input, &mut graph)?.unwrap_or_default()),
+                "independent" => merge_base::independent(input, &mut graph)?,
+                "is-ancestor" => {
+                    let is_ancestor = merge_base::is_ancestor(input[0], input[1], &mut graph)?;
+                    assert_eq!(
+                        expected,
+                        &[hex_to_id(if is_ancestor { TRUE } else { FALSE })],
+                        "{kind} {input:?} (commitgraph: {use_commitgraph})"
+                    );
+                    continue;
+                }
+                unknown => unreachable!("unknown baseline kind: {unknown}"),
+            };
+            let expected = if kind == "independent" {
+                expected.clone()
+            } else {
+                sorted(expected.clone())
+            };
+            assert_eq!(actual, expected, "{kind} {input:?} (commitgraph: {use_commitgraph})");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn ahead_behind_and_exclusive_match_git_with_and_without_commitgraph() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_merge_base_repo.sh")?;
+    let store = gix_odb::at(root.join(".git/objects"))?;
+    let baseline = std::fs::read_to_string(root.join("ahead-behind-baseline"))?;
+    let mut lines = baseline.lines();
+    let mut cases = Vec::new();
+    while let (Some(input), Some(expected), Some(exclusive)) = (lines.next(), lines.next(), lines.next()) {
+        let mut input = input.split_whitespace().map(hex_to_id);
+        let mut expected = expected
+            .split_whitespace()
+            .map(|count| count.parse::<usize>().expect("number"));
+        cases.push((
+            (input.next().expect("first"), input.next().expect("second")),
+            (expected.next().expect("ahead"), expected.next().expect("behind")),
+            sorted(exclusive.split_whitespace().map(hex_to_id).collect()),
+        ));
+    }
+    assert_eq!(cases.len(), 11, "all cases are parsed");
+
+    for use_commitgraph in [false, true] {
+        let cache = use_commitgraph
+            .then(|| gix_commitgraph::Graph::from_info_dir(store.store_ref().path().join("info")).ok())
+            .flatten();
+        let mut graph = gix_revision::Graph::new(
+            |id, buf| {
+                store
+                    .try_find(id, buf)
+                    .map(|r| r.and_then(gix_object::Data::try_into_commit_iter))
+            },
+            cache,
+        );
+        for ((first, second), expected, exclusive) in &cases {
+            assert_eq!(
+                merge_base::ahead_behind(*first, *second, &mut graph)?,
+                *expected,
+                "{first}...{second} (commitgraph: {use_commitgraph})"
+            );
+            assert_eq!(
+                sorted(merge_base::exclusive(&[*first], &[*second], &mut graph)?),
+                *exclusive,
+                "{second}..{first} (commitgraph: {use_commitgraph})"
+            );
+            assert_eq!(
+                sorted(merge_base::exclusive(&[*first, *second], &[*second], &mut graph)?),
+                *exclusive,
+                "excluded tips exclude themselves (commitgraph: {use_commitgraph})"
+            );
+            assert!(
+                merge_base::exclusive(&[*first], &[*second, *first], &mut graph)?.is_empty(),
+                "any excluded commit is enough for exclusion (commitgraph: {use_commitgraph})"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn missing_commits_are_an_error() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("make_merge_base_repo.sh")?;
+    let store = gix_odb::at(root.join(".git/objects"))?;
+    let mut graph = gix_revision::Graph::new(
+        |id, buf| {
+            store
+                .try_find(id, buf)
+                .map(|r| r.and_then(gix_object::Data::try_into_commit_iter))
+        },
+        None,
+    );
+    let missing = ObjectId::null(gix_hash::Kind::Sha1);
+    let head = store.store_ref().path().parent().expect("in .git").join("refs/tags/D");
+    let head = hex_to_id(std::fs::read_to_string(head)?.trim());
+    assert!(matches!(
+        gix_revision::merge_base(head, &[missing], &mut graph),
+        Err(merge_base::Error::MissingCommit { id }) if id == missing
+    ));
+    assert!(matches!(
+        merge_base::is_ancestor(missing, head, &mut graph),
+        Err(merge_base::Error::MissingCommit { id }) if id == missing
+    ));
+    assert!(matches!(
+        merge_base::ahead_behind(head, missing, &mut graph),
+        Err(merge_base::Error::MissingCommit { id }) if id == missing
+    ));
+    Ok(())
+}
+
+// Stand-ins for the boolean outcome of `--is-ancestor`, to be able to use the same data structure.
+const TRUE: &str = "1111111111111111111111111111111111111111";
+const FALSE: &str = "0000000000000000000000000000000000000000";
+
+struct Case {
+    kind: String,
+    input: Vec<ObjectId>,
+    expected: Vec<ObjectId>,
+}
+
+fn parse_baseline(baseline: &str) -> Vec<Case> {
+    baseline
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut lines = block.lines();
+            let mut header = lines.next().expect("header").split_whitespace();
+            let kind = header.next().expect("kind").to_owned();
+            let input = header.map(hex_to_id).collect();
+            let expected = lines
+                .map(|line| match line {
+                    "true" => hex_to_id(TRUE),
+                    "false" => hex_to_id(FALSE),
+                    hex => hex_to_id(hex),
+                })
+                .collect();
+            Case { kind, input, expected }
+        })
+        .collect()
+}
+
+fn sorted(mut ids: Vec<ObjectId>) -> Vec<ObjectId> {
+    ids.sort();
+    ids
+}
@@ -1,4 +1,5 @@
 mod describe;
+mod merge_base;
 mod spec;
 pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error + 'static>>;
 
@@ -1,2 +1,7 @@
-#![deny(rust_2018_idioms)]
+//! Handle sequences of human-aided operations, like the todo-lists used by `git rebase --interactive` and
+//! `git cherry-pick`/`git revert` when operating on multiple commits.
+#![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
+
+///
+pub mod todo;
@@ -0,0 +1,398 @@
+use std::collections::VecDeque;
+
+use bstr::{BStr, BString, ByteSlice};
+
+/// Determines which commit message to keep when squashing a commit with [`Instruction::Fixup`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FixupMessage {
+    /// Keep the message of the commit that is fixed up, as in `fixup <commit>`.
+    #[default]
+    Discard,
+    /// Use the message of the fixup commit instead, as in `fixup -C <commit>`.
+    Use,
+    /// Use the message of the fixup commit, but let the user edit it, as in `fixup -c <commit>`.
+    Edit,
+}
+
+/// A single instruction of a todo list, as used by `git rebase --interactive` and multi-commit `git cherry-pick` or `git revert`.
+///
+/// Commits are specified as they are written in the list, which is typically a full or abbreviated hexadecimal hash,
+/// and `subject`s are kept as written for display purposes only.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    /// `pick <commit>`, to apply the changes of `commit`.
+    Pick {
+        /// The commit to pick.
+        commit: BString,
+        /// The subject of the commit, if written.
+        subject: BString,
+    },
+    /// `revert <commit>`, to apply the reverse changes of `commit`.
+    Revert {
+        /// The commit to revert.
+        commit: BString,
+        /// The subject of the commit, if written.
+        subject: BString,
+    },
+    /// `edit <commit>`, to pick `commit` and stop for amending it.
+    Edit {
+        /// The commit to pick and stop at.
+        commit: BString,
+        /// The subject of the commit, if written.
+        subject: BString,
+    },
+    /// `reword <commit>`, to pick `commit` and edit its message.
+    Reword {
+        /// The commit to pick and edit the message of.
+        commit: BString,
+        /// The subject of the commit, if written.
+        subject: BString,
+    },
+    /// `squash <commit>`, to meld `commit` into the previous commit and combine both messages.
+    Squash {
+        /// The commit to meld into the previous one.
+        commit: BString,
+        /// The subject of the commit, if written.
+        subject: BString,
+    },
+    /// `fixup [-C | -c] <commit>`, to meld `commit` into the previous commit and keep only one of the messages.
+    Fixup {
+        /// The commit to meld into the previous one.
+        commit: BString,
+        /// The subject of the commit, if written.
+        subject: BString,
+        /// Which message to keep.
+        message: FixupMessage,
+    },
+    /// `drop <commit>`, to remove `commit`.
+    Drop {
+        /// The commit to drop.
+        commit: BString,
+        /// The subject of the commit, if written.
+        subject: BString,
+    },
+    /// `exec <command>`, to run `command` using the shell.
+    Exec {
+        /// The command to run, which is the remainder of the line.
+        command: BString,
+    },
+    /// `break`, to stop at this point.
+    Break,
+    /// `label <name>`, to label the current commit with `name`.
+    Label {
+        /// The name of the label.
+        name: BString,
+    },
+    /// `reset <name>`, to reset the current commit to the one labelled `name`.
+    Reset {
+        /// The name of the label to reset to.
+        name: BString,
+    },
+    /// `merge [-C <commit> | -c <commit>] <label>… [# <subject>]`, to merge the commits labelled `labels` into the current one.
+    Merge {
+        /// The original merge commit whose message should be used, if any.
+        commit: Option<BString>,
+        /// If `true`, the message of `commit` should be edited, as in `-c <commit>`.
+        edit_message: bool,
+        /// The labels of the commits to merge, more than one indicates an octopus merge.
+        labels: Vec<BString>,
+        /// The subject of the merge, used as message if there is no `commit`.
+        subject: BString,
+    },
+    /// `update-ref <name>`, to update the reference `name` to the current commit once the sequence is done.
+    UpdateRef {
+        /// The full name of the reference to update.
+        name: BString,
+    },
+    /// `noop`, which does nothing.
+    Noop,
+}
+
+impl Instruction {
+    /// Return the full name of the command of this instruction, like `pick`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Instruction::Pick { .. } => "pick",
+            Instruction::Revert { .. } => "revert",
+            Instruction::Edit { .. } => "edit",
+            Instruction::Reword { .. } => "reword",
+            Instruction::Squash { .. } => "squash",
+            Instruction::Fixup { .. } => "fixup",
+            Instruction::Drop { .. } => "drop",
+            Instruction::Exec { .. } => "exec",
+            Instruction::Break => "break",
+            Instruction::Label { .. } => "label",
+            Instruction::Reset { .. } => "reset",
+            Instruction::Merge { .. } => "merge",
+            Instruction::UpdateRef { .. } => "update-ref",
+            Instruction::Noop => "noop",
+        }
+    }
+
+    /// Return the commit this instruction refers to, if there is one.
+    pub fn commit(&self) -> Option<&BStr> {
+        match self {
+            Instruction::Pick { commit, .. }
+            | Instruction::Revert { commit, .. }
+            | Instruction::Edit { commit, .. }
+            | Instruction::Reword { commit, .. }
+            | Instruction::Squash { commit, .. }
+            | Instruction::Fixup { commit, .. }
+            | Instruction::Drop { commit, .. } => Some(commit.as_ref()),
+            Instruction::Merge { commit, .. } => commit.as_ref().map(AsRef::as_ref),
+            Instruction::Exec { .. }
+            | Instruction::Break
+            | Instruction::Label { .. }
+            | Instruction::Reset { .. }
+            | Instruction::UpdateRef { .. }
+            | Instruction::Noop => None,
+        }
+    }
+
+    /// Serialize this instruction as single line, including the trailing newline, to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        out.write_all(self.name().as_bytes())?;
+        match self {
+            Instruction::Pick { commit, subject }
+            | Instruction::Revert { commit, subject }
+            | Instruction::Edit { commit, subject }
+            | Instruction::Reword { commit, subject }
+            | Instruction::Squash { commit, subject }
+            | Instruction::Drop { commit, subject } => write_commit_and_subject(&mut out, commit, subject)?,
+            Instruction::Fixup {
+                commit,
+                subject,
+                message,
+            } => {
+                match message {
+                    FixupMessage::Discard => {}
+                    FixupMessage::Use => out.write_all(b" -C")?,
+                    FixupMessage::Edit => out.write_all(b" -c")?,
+                }
+                write_commit_and_subject(&mut out, commit, subject)?;
+            }
+            Instruction::Exec { command: value }
+            | Instruction::Label { name: value }
+            | Instruction::Reset { name: value }
+            | Instruction::UpdateRef { name: value } => {
+                out.write_all(b" ")?;
+                out.write_all(value)?;
+            }
+            Instruction::Merge {
+                commit,
+                edit_message,
+                labels,
+                subject,
+            } => {
+                if let Some(commit) = commit {
+                    out.write_all(if *edit_message { b" -c " } else { b" -C " })?;
+                    out.write_all(commit)?;
+                }
+                for label in labels {
+                    out.write_all(b" ")?;
+                    out.write_all(label)?;
+                }
+                if !subject.is_empty() {
+                    out.write_all(b" # ")?;
+                    out.write_all(subject)?;
+                }
+            }
+            Instruction::Break | Instruction::Noop => {}
+        }
+        out.write_all(b"\n")
+    }
+}
+
+fn write_commit_and_subject(mut out: impl std::io::Write, commit: &[u8], subject: &[u8]) -> std::io::Result<()> {
+    out.write_all(b" ")?;
+    out.write_all(commit)?;
+    if !subject.is_empty() {
+        out.write_all(b" ")?;
+        out.write_all(subject)?;
+    }
+    Ok(())
+}
+
+/// A list of instructions, as stored in files like `.git/rebase-merge/git-rebase-todo` or `.git/sequencer/todo`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct List {
+    /// The instructions in order of execution, so the next instruction to execute can be taken from the front.
+    pub instructions: VecDeque<Instruction>,
+}
+
+///
+pub mod parse {
+    use bstr::BString;
+
+    /// The error returned by [`List::from_bytes()`][super::List::from_bytes()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Line {line_number}: unknown command in {line:?}")]
+        UnknownCommand { line_number: usize, line: BString },
+        #[error("Line {line_number}: '{command}' does not accept arguments, got {line:?}")]
+        UnexpectedArgument {
+            line_number: usize,
+            command: &'static str,
+            line: BString,
+        },
+        #[error("Line {line_number}: '{command}' is missing an argument")]
+        MissingArgument { line_number: usize, command: &'static str },
+    }
+}
+
+impl List {
+    /// Parse all instructions from `data`, skipping empty lines and those starting with `comment_char`, typically `#`.
+    ///
+    /// Commands may be spelled out or abbreviated to a single character, as in `p` for `pick`.
+    pub fn from_bytes(data: &[u8], comment_char: u8) -> Result<Self, parse::Error> {
+        let mut instructions = VecDeque::new();
+        for (line_number, line) in data.lines().enumerate().map(|(idx, line)| (idx + 1, line.trim())) {
+            if line.is_empty() || line[0] == comment_char {
+                continue;
+            }
+            instructions.push_back(parse_line(line, line_number)?);
+        }
+        Ok(List { instructions })
+    }
+
+    /// Serialize all instructions to `out`, one per line.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        for instruction in &self.instructions {
+            instruction.write_to(&mut out)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_line(line: &[u8], line_number: usize) -> Result<Instruction, parse::Error> {
+    let (command, args) = split_word(line);
+    let name = match command {
+        b"pick" | b"p" => "pick",
+        b"revert" => "revert",
+        b"edit" | b"e" => "edit",
+        b"reword" | b"r" => "reword",
+        b"squash" | b"s" => "squash",
+        b"fixup" | b"f" => "fixup",
+        b"drop" | b"d" => "drop",
+        b"exec" | b"x" => "exec",
+        b"break" | b"b" => "break",
+        b"label" | b"l" => "label",
+        b"reset" | b"t" => "reset",
+        b"merge" | b"m" => "merge",
+        b"update-ref" | b"u" => "update-ref",
+        b"noop" => "noop",
+        _ => {
+            return Err(parse::Error::UnknownCommand {
+                line_number,
+                line: line.into(),
+            })
+        }
+    };
+    let missing_argument = || parse::Error::MissingArgument {
+        line_number,
+        command: name,
+    };
+
+    Ok(match name {
+        "break" | "noop" => {
+            if !args.is_empty() {
+                return Err(parse::Error::UnexpectedArgument {
+                    line_number,
+                    command: name,
+                    line: line.into(),
+                });
+            }
+            if name == "break" {
+                Instruction::Break
+            } else {
+                Instruction::Noop
+            }
+        }
+        "exec" => {
+            if args.is_empty() {
+                return Err(missing_argument());
+            }
+            Instruction::Exec { command: args.into() }
+        }
+        "label" | "reset" | "update-ref" => {
+            // Anything after the name is a comment, as in `reset onto # subject`.
+            let (value, _comment) = split_word(args);
+            if value.is_empty() {
+                return Err(missing_argument());
+            }
+            let value = value.into();
+            match name {
+                "label" => Instruction::Label { name: value },
+                "reset" => Instruction::Reset { name: value },
+                _ => Instruction::UpdateRef { name: value },
+            }
+        }
+        "merge" => {
+            let (mut commit, mut edit_message, mut args) = (None, false, args);
+            let (flag, rest) = split_word(args);
+            if flag == b"-C" || flag == b"-c" {
+                let (id, rest) = split_word(rest);
+                if id.is_empty() {
+                    return Err(missing_argument());
+                }
+                commit = Some(id.into());
+                edit_message = flag == b"-c";
+                args = rest;
+            }
+            let (labels, subject) = match args.find_byte(b'#') {
+                Some(pos) => (&args[..pos], args[pos + 1..].trim_start()),
+                None => (args, &b""[..]),
+            };
+            let labels: Vec<BString> = labels.fields().map(Into::into).collect();
+            if labels.is_empty() {
+                return Err(missing_argument());
+            }
+            Instruction::Merge {
+                commit,
+                edit_message,
+                labels,
+                subject: subject.into(),
+            }
+        }
+        _ => {
+            let (mut flag, mut rest) = split_word(args);
+            let mut message = FixupMessage::Discard;
+            if name == "fixup" && (flag == b"-C" || flag == b"-c") {
+                message = if flag == b"-C" {
+                    FixupMessage::Use
+                } else {
+                    FixupMessage::Edit
+                };
+                (flag, rest) = split_word(rest);
+            }
+            let commit: BString = flag.into();
+            if commit.is_empty() {
+                return Err(missing_argument());
+            }
+            let subject = rest.into();
+            match name {
+                "pick" => Instruction::Pick { commit, subject },
+                "revert" => Instruction::Revert { commit, subject },
+                "edit" => Instruction::Edit { commit, subject },
+                "reword" => Instruction::Reword { commit, subject },
+                "squash" => Instruction::Squash { commit, subject },
+                "fixup" => Instruction::Fixup {
+                    commit,
+                    subject,
+                    message,
+                },
+                "drop" => Instruction::Drop { commit, subject },
+                _ => unreachable!("all commands are handled"),
+            }
+        }
+    })
+}
+
+/// Split `input` at the first whitespace, and return the word before it along with the remainder without leading whitespace.
+fn split_word(input: &[u8]) -> (&[u8], &[u8]) {
+    match input.find_byteset(b" \t") {
+        Some(pos) => (&input[..pos], input[pos..].trim_start()),
+        None => (input, &[]),
+    }
+}
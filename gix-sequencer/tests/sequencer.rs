@@ -0,0 +1,5 @@
+mod todo;
+
+fn fixture_bytes(name: &str) -> Vec<u8> {
+    std::fs::read(gix_testtools::fixture_path(name)).expect("fixture is present")
+}
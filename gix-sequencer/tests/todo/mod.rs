@@ -0,0 +1,162 @@
+use gix_sequencer::todo::{parse, FixupMessage, Instruction, List};
+
+use crate::fixture_bytes;
+
+#[test]
+fn list_as_written_by_git() -> Result<(), parse::Error> {
+    let list = List::from_bytes(&fixture_bytes("rebase-merges.txt"), b'#')?;
+    assert_eq!(
+        list.instructions,
+        [
+            Instruction::Label { name: "onto".into() },
+            Instruction::Reset { name: "onto".into() },
+            Instruction::Pick {
+                commit: "a88d47fd2f4bde3e18f5362bea15c371b20c20f9".into(),
+                subject: "2".into()
+            },
+            Instruction::Pick {
+                commit: "5e80a5fcd806c01d604e009c3802100e7ad0c63e".into(),
+                subject: "3".into()
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn all_commands_and_abbreviations() -> Result<(), parse::Error> {
+    let list = List::from_bytes(&fixture_bytes("all-commands.txt"), b'#')?;
+    let names: Vec<_> = list.instructions.iter().map(Instruction::name).collect();
+    assert_eq!(
+        names,
+        [
+            "pick",
+            "pick",
+            "revert",
+            "edit",
+            "reword",
+            "squash",
+            "fixup",
+            "fixup",
+            "fixup",
+            "drop",
+            "exec",
+            "break",
+            "label",
+            "label",
+            "reset",
+            "reset",
+            "merge",
+            "merge",
+            "merge",
+            "update-ref",
+            "noop"
+        ]
+    );
+    assert_eq!(
+        list.instructions[1],
+        Instruction::Pick {
+            commit: "5e80a5f".into(),
+            subject: "".into()
+        },
+        "the subject is optional"
+    );
+    let fixup_messages: Vec<_> = list
+        .instructions
+        .range(6..9)
+        .map(|i| match i {
+            Instruction::Fixup { message, .. } => *message,
+            _ => unreachable!("only fixups"),
+        })
+        .collect();
+    assert_eq!(
+        fixup_messages,
+        [FixupMessage::Discard, FixupMessage::Use, FixupMessage::Edit]
+    );
+    assert_eq!(
+        list.instructions[10],
+        Instruction::Exec {
+            command: "make test && echo done".into()
+        },
+        "the command is the remainder of the line"
+    );
+    assert_eq!(
+        list.instructions[14],
+        Instruction::Reset { name: "onto".into() },
+        "comments after the label are ignored"
+    );
+    assert_eq!(
+        list.instructions[16],
+        Instruction::Merge {
+            commit: Some("5e80a5f".into()),
+            edit_message: false,
+            labels: vec!["feature".into()],
+            subject: "Merge branch 'feature'".into()
+        }
+    );
+    assert_eq!(
+        list.instructions[17],
+        Instruction::Merge {
+            commit: Some("5e80a5f".into()),
+            edit_message: true,
+            labels: vec!["one".into(), "two".into()],
+            subject: "".into()
+        },
+        "octopus merges have multiple labels"
+    );
+    assert_eq!(
+        list.instructions[18],
+        Instruction::Merge {
+            commit: None,
+            edit_message: false,
+            labels: vec!["feature".into()],
+            subject: "the subject".into()
+        }
+    );
+    assert_eq!(list.instructions[18].commit(), None);
+    assert_eq!(
+        list.instructions[0].commit().expect("present"),
+        "5e80a5fcd806c01d604e009c3802100e7ad0c63e"
+    );
+    Ok(())
+}
+
+#[test]
+fn write_and_parse_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+    let list = List::from_bytes(&fixture_bytes("all-commands.txt"), b'#')?;
+    let mut buf = Vec::new();
+    list.write_to(&mut buf)?;
+    assert_eq!(List::from_bytes(&buf, b'#')?, list);
+
+    let mut buf = Vec::new();
+    list.instructions[8].write_to(&mut buf)?;
+    assert_eq!(buf, b"fixup -c a88d47f fixup with edited message\n");
+    Ok(())
+}
+
+#[test]
+fn custom_comment_char() -> Result<(), parse::Error> {
+    let list = List::from_bytes(b";pick is not a command\npick abc\n", b';')?;
+    assert_eq!(list.instructions.len(), 1);
+    assert!(List::from_bytes(b";pick is not a command\n", b'#').is_err());
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    for (input, expected) in [
+        ("unknown abc", "Line 1: unknown command in \"unknown abc\""),
+        (
+            "\nbreak now",
+            "Line 2: 'break' does not accept arguments, got \"break now\"",
+        ),
+        ("pick", "Line 1: 'pick' is missing an argument"),
+        ("fixup -C", "Line 1: 'fixup' is missing an argument"),
+        ("merge -C abc", "Line 1: 'merge' is missing an argument"),
+        ("label", "Line 1: 'label' is missing an argument"),
+        ("exec", "Line 1: 'exec' is missing an argument"),
+    ] {
+        let err = List::from_bytes(input.as_bytes(), b'#').unwrap_err();
+        assert_eq!(err.to_string(), expected, "{input:?}");
+    }
+}
@@ -1,15 +1,16 @@
 pub use crate::client::non_io_types::connect::{Error, Options};
 
-#[cfg(any(feature = "async-std"))]
 pub(crate) mod function {
     use std::convert::TryInto;
 
-    use crate::client::{git, non_io_types::connect::Error};
+    use crate::client::non_io_types::connect::Error;
 
     /// A general purpose connector connecting to a repository identified by the given `url`.
     ///
     /// This includes connections to
-    /// [git daemons][crate::client::git::connect()] only at the moment.
+    /// [local repositories][crate::client::file::connect()],
+    /// [repositories over ssh][crate::client::ssh::connect()],
+    /// and if the `async-std` feature is enabled, [git daemons][crate::client::git::connect()].
     ///
     /// Use `options` to further control specifics of the transport resulting from the connection.
     pub async fn connect<Url, E>(
@@ -20,8 +21,26 @@ pub(crate) mod function {
         Url: TryInto<gix_url::Url, Error = E>,
         gix_url::parse::Error: From<E>,
     {
+        #[cfg_attr(not(feature = "async-std"), allow(unused_mut))]
         let mut url = url.try_into().map_err(gix_url::parse::Error::from)?;
         Ok(match url.scheme {
+            gix_url::Scheme::File => {
+                if url.user().is_some() || url.password().is_some() || url.host().is_some() || url.port.is_some() {
+                    return Err(Error::UnsupportedUrlTokens {
+                        url: url.to_bstring(),
+                        scheme: url.scheme,
+                    });
+                }
+                Box::new(
+                    crate::client::file::connect(url.path, options.version)
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
+                )
+            }
+            gix_url::Scheme::Ssh => Box::new({
+                crate::client::ssh::connect(url, options.version, options.ssh)
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
+            }),
+            #[cfg(feature = "async-std")]
             gix_url::Scheme::Git => {
                 if url.user().is_some() {
                     return Err(Error::UnsupportedUrlTokens {
@@ -31,7 +50,7 @@ pub(crate) mod function {
                 }
                 let path = std::mem::take(&mut url.path);
                 Box::new(
-                    git::Connection::new_tcp(
+                    crate::client::git::Connection::new_tcp(
                         url.host().expect("host is present in url"),
                         url.port,
                         path,
@@ -30,12 +30,12 @@ pub(crate) mod function {
                     });
                 }
                 Box::new(
-                    crate::client::blocking_io::file::connect(url.path, options.version)
+                    crate::client::file::connect(url.path, options.version)
                         .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?,
                 )
             }
             gix_url::Scheme::Ssh => Box::new({
-                crate::client::blocking_io::ssh::connect(url, options.version, options.ssh)
+                crate::client::ssh::connect(url, options.version, options.ssh)
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?
             }),
             gix_url::Scheme::Git => {
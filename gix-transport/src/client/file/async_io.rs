@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use blocking::Unblock;
+
+use crate::{
+    client::{self, file::SpawnProcessOnDemand, git, SetServiceResponse},
+    Service,
+};
+
+#[async_trait(?Send)]
+impl client::Transport for SpawnProcessOnDemand {
+    async fn handshake<'a, 's>(
+        &'s mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'s>, client::Error> {
+        let (stdout, stdin) = self.spawn(service)?;
+        // Pipes of the spawned process are blocking, so reads and writes are performed on the thread-pool of `blocking`,
+        // which keeps this independent of any particular async runtime.
+        self.connection = Some(git::Connection::new(
+            Unblock::new(stdout),
+            Unblock::new(stdin),
+            self.desired_version,
+            self.path.clone(),
+            None::<(&str, _)>,
+            git::ConnectMode::Process,
+        ));
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+            .await
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{
+    client::{self, file::SpawnProcessOnDemand, git, SetServiceResponse},
+    Service,
+};
+
+impl client::Transport for SpawnProcessOnDemand {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        let (stdout, stdin) = self.spawn(service)?;
+        self.connection = Some(git::Connection::new_for_spawned_process(
+            stdout,
+            stdin,
+            self.desired_version,
+            self.path.clone(),
+        ));
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+    }
+}
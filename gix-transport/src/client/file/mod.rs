@@ -10,10 +10,15 @@ use std::{
 use bstr::{io::BufReadExt, BStr, BString, ByteSlice};
 
 use crate::{
-    client::{self, git, ssh, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+    client::{self, git, ssh, MessageKind, RequestWriter, WriteMode},
     Protocol, Service,
 };
 
+#[cfg(feature = "async-client")]
+mod async_io;
+#[cfg(feature = "blocking-client")]
+mod blocking_io;
+
 // from https://github.com/git/git/blob/20de7e7e4f4e9ae52e6cc7cfaa6469f186ddb0fa/environment.c#L115:L115
 const ENV_VARS_TO_REMOVE: &[&str] = &[
     "GIT_ALTERNATE_OBJECT_DIRECTORIES",
@@ -34,6 +39,14 @@ const ENV_VARS_TO_REMOVE: &[&str] = &[
     "GIT_CONFIG_COUNT",
 ];
 
+/// The connection to the spawned process, whose standard output is read while its standard input is written to.
+#[cfg(feature = "blocking-client")]
+type Connection = git::Connection<Box<dyn std::io::Read + Send>, process::ChildStdin>;
+/// The connection to the spawned process, whose blocking pipes are driven by a thread-pool.
+#[cfg(feature = "async-client")]
+type Connection =
+    git::Connection<blocking::Unblock<Box<dyn std::io::Read + Send>>, blocking::Unblock<process::ChildStdin>>;
+
 /// A utility to spawn a helper process to actually transmit data, possibly over `ssh`.
 ///
 /// It can only be instantiated using the local [`connect()`] or [ssh connect][crate::client::ssh::connect()].
@@ -45,7 +58,7 @@ pub struct SpawnProcessOnDemand {
     /// The environment variables to set in the invoked command.
     envs: Vec<(&'static str, String)>,
     ssh_disallow_shell: bool,
-    connection: Option<git::Connection<Box<dyn std::io::Read + Send>, process::ChildStdin>>,
+    connection: Option<Connection>,
     child: Option<process::Child>,
 }
 
@@ -189,12 +202,12 @@ fn supervise_stderr(
     ReadStdoutFailOnError { read: stdout, recv }
 }
 
-impl client::Transport for SpawnProcessOnDemand {
-    fn handshake<'a>(
+impl SpawnProcessOnDemand {
+    /// Spawn the program to provide `service`, and return its possibly supervised standard output along with its standard input.
+    fn spawn(
         &mut self,
         service: Service,
-        extra_parameters: &'a [(&'a str, Option<&'a str>)],
-    ) -> Result<SetServiceResponse<'_>, client::Error> {
+    ) -> Result<(Box<dyn std::io::Read + Send>, process::ChildStdin), client::Error> {
         let (mut cmd, ssh_kind, cmd_name) = match &self.ssh_cmd {
             Some((command, kind)) => (
                 kind.prepare_invocation(command, &self.url, self.desired_version, self.ssh_disallow_shell)
@@ -237,17 +250,9 @@ impl client::Transport for SpawnProcessOnDemand {
             )),
             None => Box::new(child.stdout.take().expect("stdout configured")),
         };
-        self.connection = Some(git::Connection::new_for_spawned_process(
-            stdout,
-            child.stdin.take().expect("stdin configured"),
-            self.desired_version,
-            self.path.clone(),
-        ));
+        let stdin = child.stdin.take().expect("stdin configured");
         self.child = Some(child);
-        self.connection
-            .as_mut()
-            .expect("connection to be there right after setting it")
-            .handshake(service, extra_parameters)
+        Ok((stdout, stdin))
     }
 }
 
@@ -265,7 +270,7 @@ pub fn connect(
 mod tests {
     mod ssh {
         mod connect {
-            use crate::{client::blocking_io::ssh::connect, Protocol};
+            use crate::{client::ssh::connect, Protocol};
 
             #[test]
             fn path() {
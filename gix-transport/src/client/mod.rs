@@ -15,7 +15,7 @@ mod blocking_io;
 pub use blocking_io::http;
 #[cfg(feature = "blocking-client")]
 pub use blocking_io::{
-    connect, file, ssh, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
+    connect, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
     TransportV2Ext,
 };
 #[cfg(feature = "blocking-client")]
@@ -31,6 +31,12 @@ mod non_io_types;
 pub use gix_sec::identity::Account;
 pub use non_io_types::{Error, MessageKind, WriteMode};
 
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod file;
 ///
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub mod git;
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod ssh;
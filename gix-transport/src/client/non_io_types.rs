@@ -37,7 +37,6 @@ pub(crate) mod connect {
     pub struct Options {
         /// Use `version` to set the desired protocol version to use when connecting, but note that the server may downgrade it.
         pub version: crate::Protocol,
-        #[cfg(feature = "blocking-client")]
         /// Options to use if the scheme of the URL is `ssh`.
         pub ssh: crate::client::ssh::connect::Options,
     }
@@ -94,16 +93,16 @@ mod error {
     use crate::client::capabilities;
     #[cfg(feature = "http-client")]
     use crate::client::http;
-    #[cfg(feature = "blocking-client")]
+    #[cfg(any(feature = "blocking-client", feature = "async-client"))]
     use crate::client::ssh;
 
     #[cfg(feature = "http-client")]
     type HttpError = http::Error;
-    #[cfg(feature = "blocking-client")]
+    #[cfg(any(feature = "blocking-client", feature = "async-client"))]
     type SshInvocationError = ssh::invocation::Error;
     #[cfg(not(feature = "http-client"))]
     type HttpError = std::convert::Infallible;
-    #[cfg(not(feature = "blocking-client"))]
+    #[cfg(not(any(feature = "blocking-client", feature = "async-client")))]
     type SshInvocationError = std::convert::Infallible;
 
     /// The error used in most methods of the [`client`][crate::client] module
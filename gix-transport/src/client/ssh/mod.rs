@@ -1,6 +1,6 @@
 use std::process::Stdio;
 
-use crate::{client::blocking_io, Protocol};
+use crate::{client::file::SpawnProcessOnDemand, Protocol};
 
 /// The error used in [`connect()`].
 #[derive(Debug, thiserror::Error)]
@@ -91,7 +91,7 @@ pub fn connect(
     url: gix_url::Url,
     desired_version: Protocol,
     options: connect::Options,
-) -> Result<blocking_io::file::SpawnProcessOnDemand, Error> {
+) -> Result<SpawnProcessOnDemand, Error> {
     if url.scheme != gix_url::Scheme::Ssh || url.host().is_none() {
         return Err(Error::UnsupportedScheme(url));
     }
@@ -118,7 +118,7 @@ pub fn connect(
     }
 
     let path = gix_url::expand_path::for_shell(url.path.clone());
-    Ok(blocking_io::file::SpawnProcessOnDemand::new_ssh(
+    Ok(SpawnProcessOnDemand::new_ssh(
         url,
         ssh_cmd,
         path,
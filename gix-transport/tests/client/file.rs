@@ -0,0 +1,46 @@
+#[cfg(feature = "blocking-client")]
+use std::io::BufRead;
+
+#[cfg(feature = "async-client")]
+use futures_lite::{AsyncBufReadExt, StreamExt};
+use gix_transport::{
+    client,
+    client::{Transport, TransportV2Ext, TransportWithoutIO},
+    Protocol, Service,
+};
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn handshake_and_ls_refs_with_spawned_upload_pack() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only("make_repo.sh")?;
+    let mut c = client::file::connect(dir.to_str().expect("valid UTF-8 in test path"), Protocol::V2)?;
+    assert!(
+        c.connection_persists_across_multiple_requests(),
+        "the spawned process serves all requests"
+    );
+
+    let res = c.handshake(Service::UploadPack, &[]).await?;
+    assert_eq!(res.actual_protocol, Protocol::V2);
+    assert!(res.refs.is_none(), "V2 needs a separate call for refs");
+    assert!(
+        res.capabilities.contains("ls-refs"),
+        "the actual upload-pack program advertises its capabilities"
+    );
+    drop(res);
+
+    let reader = c
+        .invoke(
+            "ls-refs",
+            [("agent", Some("git/2.28.0"))].iter().copied(),
+            Some(["ref-prefix refs/"].iter().map(|s| (*s).into())),
+        )
+        .await?;
+    let mut lines = reader.lines();
+    let mut refs = Vec::new();
+    #[allow(clippy::while_let_on_iterator)] // needed in async version of test
+    while let Some(line) = lines.next().await {
+        let line = line?;
+        refs.push(line.split_once(' ').expect("<oid> <name>").1.to_owned());
+    }
+    assert_eq!(refs, ["refs/heads/main", "refs/tags/v1"]);
+    Ok(())
+}
@@ -1,4 +1,5 @@
 #[cfg(feature = "blocking-client")]
 mod blocking_io;
 mod capabilities;
+mod file;
 mod git;
@@ -184,11 +184,11 @@ impl<'index> State<'_, 'index> {
                 // index entries are normally only for files/symlinks
                 // if a file turned into a directory it was removed
                 // the only exception here are submodules which are
-                // part of the index despite being directories
-                //
-                // TODO: submodules:
-                //   if entry.mode.contains(Mode::COMMIT) &&
-                //     resolve_gitlink_ref(ce->name, "HEAD", &sub))
+                // part of the index despite being directories.
+                // Their state is up to the caller to determine.
+                if entry.mode == gix_index::entry::Mode::COMMIT {
+                    return Ok(None);
+                }
                 return Ok(Some(Change::Removed));
             }
             Ok(metadata) => metadata,
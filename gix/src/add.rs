@@ -0,0 +1,72 @@
+//! Add files from the worktree to the index, similar to `git add`.
+use crate::bstr::BString;
+
+/// How to alter the executable bit of the entries that are added, similar to `git add --chmod`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Chmod {
+    /// Mark all matching files as executable, like `--chmod=+x`.
+    Executable,
+    /// Mark all matching files as non-executable, like `--chmod=-x`.
+    NonExecutable,
+}
+
+/// Options for use in [`Repository::add_to_index()`][crate::Repository::add_to_index()].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// If `true`, only record that matching untracked paths will be added later, similar to `git add --intent-to-add`.
+    ///
+    /// These entries point to the empty blob and are ignored when writing trees.
+    pub intent_to_add: bool,
+    /// If `true`, only update entries that are already tracked in the index, similar to `git add --update`.
+    /// Untracked files will not be added.
+    pub update_only: bool,
+    /// If `true`, tracked files which don't exist in the worktree anymore will be marked for removal from the index,
+    /// similar to `git add --all`.
+    pub record_removals: bool,
+    /// If set, alter the executable bit of all matching files in the index, independently of their mode in the worktree.
+    pub chmod: Option<Chmod>,
+    /// If `true`, add untracked files even if they are excluded, similar to `git add --force`.
+    pub force: bool,
+}
+
+/// The outcome of [`Repository::add_to_index()`][crate::Repository::add_to_index()].
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// The repository-relative paths of all untracked files that were added to the index.
+    pub added: Vec<BString>,
+    /// The repository-relative paths of all tracked files whose content or mode changed.
+    pub modified: Vec<BString>,
+    /// The repository-relative paths of all tracked files that were marked for removal.
+    pub removed: Vec<BString>,
+}
+
+/// The error returned by [`Repository::add_to_index()`][crate::Repository::add_to_index()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to add files to the index")]
+    MissingWorktree,
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    PathspecMatch(#[from] crate::pathspec::matching::Error),
+    #[error("The pathspec '{pattern}' did not match any files")]
+    PathspecMismatch { pattern: BString },
+    #[error("The path '{path}' is ignored by one of the .gitignore files, use 'force' to add it anyway")]
+    Ignored { path: BString },
+    #[error(transparent)]
+    CheckoutOptions(#[from] Box<crate::config::checkout_options::Error>),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::filter::pipeline::new::Error),
+    #[error("Could not access '{}' in the worktree", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not obtain the file modification time")]
+    Time(#[from] std::time::SystemTimeError),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    WriteBlob(#[from] crate::object::write::Error),
+}
@@ -0,0 +1,180 @@
+//! Obtain information about local and remote branches, and how local branches relate to their upstream, similar to `git branch -avv`.
+//!
+//! The amount of commits a branch is ahead or behind its upstream is computed by walking the commit graph from both tips at
+//! the same time until only commits reachable from both are left, so only the commits down to their merge-bases are visited.
+//! Generation numbers of the commit-graph are used to guide the traversal if it is present.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_ref::{FullName, FullNameRef};
+
+use crate::{remote, Repository};
+
+/// The kind of a branch as listed by [`Repository::branches()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Kind {
+    /// A local branch, whose name starts with `refs/heads/`.
+    Local,
+    /// A remote-tracking branch, whose name starts with `refs/remotes/`.
+    Remote,
+}
+
+/// The amount of commits that differ between a branch and its upstream.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AheadBehind {
+    /// The amount of commits reachable from the branch but not from its upstream.
+    pub ahead: usize,
+    /// The amount of commits reachable from the upstream but not from the branch.
+    pub behind: usize,
+}
+
+/// How a local branch relates to its upstream, as returned by [`Repository::branch_tracking()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tracking {
+    /// The name of the reference tracking the upstream branch, like `refs/remotes/origin/main`, or the name of a local branch
+    /// if `branch.<name>.remote` is `.`.
+    pub upstream: FullName,
+    /// The commit the upstream points to, or `None` if it doesn't exist, like after it was deleted on the remote and pruned locally.
+    pub upstream_id: Option<ObjectId>,
+    /// The amount of commits the branch is ahead and behind its upstream, or `None` if the branch or its upstream doesn't exist.
+    pub ahead_behind: Option<AheadBehind>,
+}
+
+impl Tracking {
+    /// Return `true` if the upstream reference exists.
+    pub fn upstream_exists(&self) -> bool {
+        self.upstream_id.is_some()
+    }
+}
+
+/// A branch as listed by [`Repository::branches()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The full name of the branch, like `refs/heads/main` or `refs/remotes/origin/main`.
+    pub name: FullName,
+    /// Whether the branch is local or remote-tracking.
+    pub kind: Kind,
+    /// The commit the branch points to.
+    pub id: ObjectId,
+    /// How the branch relates to its upstream, or `None` if it is a remote-tracking branch or if no upstream is configured.
+    pub tracking: Option<Tracking>,
+}
+
+///
+pub mod tracking {
+    /// The error returned by [`Repository::branch_tracking()`][crate::Repository::branch_tracking()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        UpstreamName(#[from] crate::repository::branch_remote_tracking_ref_name::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error("Could not count the commits between a branch and its upstream")]
+        AheadBehind(#[from] gix_revision::merge_base::Error),
+    }
+}
+
+///
+pub mod list {
+    /// The error returned by [`Repository::branches()`][crate::Repository::branches()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenPackedRefs(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterateReferences(#[from] crate::reference::iter::init::Error),
+        #[error("A branch could not be read")]
+        ReadReference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        Tracking(#[from] super::tracking::Error),
+    }
+}
+
+impl Repository {
+    /// Return how the local branch `name`, like `refs/heads/main`, relates to its upstream as configured with
+    /// `branch.<name>.remote` and `branch.<name>.merge`, or `None` if `name` isn't a local branch or has no upstream.
+    ///
+    /// The upstream doesn't have to exist, and neither does the branch itself, which is the case if it is unborn.
+    pub fn branch_tracking(&self, name: &FullNameRef) -> Result<Option<Tracking>, tracking::Error> {
+        let Some(upstream) = self.branch_remote_tracking_ref_name(name, remote::Direction::Fetch) else {
+            return Ok(None);
+        };
+        let upstream = upstream?.into_owned();
+        let branch_id = self.peeled_id(name)?;
+        let upstream_id = self.peeled_id(upstream.as_ref())?;
+        compute_tracking(upstream, branch_id, upstream_id, &mut self.revision_graph()).map(Some)
+    }
+
+    /// Return all local branches followed by all remote-tracking branches, each sorted by name, along with how local branches
+    /// relate to their upstream.
+    ///
+    /// All references are read only once, and the same commit graph is used for counting the commits ahead and behind of all
+    /// local branches. Symbolic references, like `refs/remotes/origin/HEAD`, aren't listed.
+    pub fn branches(&self) -> Result<Vec<Entry>, list::Error> {
+        let mut ids = BTreeMap::new();
+        let mut entries = Vec::new();
+        let platform = self.references()?;
+        for (kind, iter) in [
+            (Kind::Local, platform.local_branches()?),
+            (Kind::Remote, platform.remote_branches()?),
+        ] {
+            for reference in iter {
+                let reference = reference.map_err(list::Error::ReadReference)?;
+                let Some(id) = reference.target().try_id().map(ToOwned::to_owned) else {
+                    continue;
+                };
+                let name = reference.name().to_owned();
+                ids.insert(name.clone(), id);
+                entries.push(Entry {
+                    name,
+                    kind,
+                    id,
+                    tracking: None,
+                });
+            }
+        }
+
+        let mut graph = self.revision_graph();
+        for entry in entries.iter_mut().filter(|entry| entry.kind == Kind::Local) {
+            let Some(upstream) = self.branch_remote_tracking_ref_name(entry.name.as_ref(), remote::Direction::Fetch)
+            else {
+                continue;
+            };
+            let upstream = upstream.map_err(tracking::Error::from)?.into_owned();
+            let upstream_id = ids.get(&upstream).copied();
+            entry.tracking = Some(compute_tracking(upstream, Some(entry.id), upstream_id, &mut graph)?);
+        }
+        Ok(entries)
+    }
+
+    fn peeled_id(&self, name: &FullNameRef) -> Result<Option<ObjectId>, tracking::Error> {
+        Ok(match self.try_find_reference(name)? {
+            Some(mut reference) => Some(reference.peel_to_id_in_place()?.detach()),
+            None => None,
+        })
+    }
+}
+
+fn compute_tracking(
+    upstream: FullName,
+    branch_id: Option<ObjectId>,
+    upstream_id: Option<ObjectId>,
+    graph: &mut gix_revision::merge_base::Graph<'_>,
+) -> Result<Tracking, tracking::Error> {
+    let ahead_behind = match (branch_id, upstream_id) {
+        (Some(branch_id), Some(upstream_id)) => {
+            let (ahead, behind) = gix_revision::merge_base::ahead_behind(branch_id, upstream_id, graph)?;
+            Some(AheadBehind { ahead, behind })
+        }
+        _ => None,
+    };
+    Ok(Tracking {
+        upstream,
+        upstream_id,
+        ahead_behind,
+    })
+}
@@ -0,0 +1,298 @@
+use std::{convert::Infallible, io::Write, sync::atomic::AtomicBool};
+
+use gix_features::parallel::InOrderIter;
+use gix_hash::ObjectId;
+use gix_pack::data::output;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    bundle::{Header, Prerequisite, Ref, Version},
+    Progress, Repository,
+};
+
+/// Options for use in [`Repository::bundle_create()`].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The version of the bundle to write.
+    ///
+    /// Note that [`Version::V2`] is understood by more `git` versions, and [`Version::V3`] is needed for repositories that
+    /// don't use SHA1.
+    pub version: Version,
+}
+
+/// The error returned by [`Repository::bundle_create()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    RevParse(#[from] Box<crate::revision::spec::parse::Error>),
+    #[error(transparent)]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[error("Refusing to create a bundle without references, as none of the revisions were named by a reference")]
+    NoReferences,
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkIteration(#[from] gix_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    PackThreads(#[from] crate::config::unsigned_integer::Error),
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[source] std::io::Error),
+    #[error(transparent)]
+    CountObjects(
+        #[from] output::count::objects::Error<gix_pack::find::existing::Error<gix_odb::store::find::Error>, Infallible>,
+    ),
+    #[error(transparent)]
+    WritePack(#[from] output::bytes::Error<output::entry::iter_from_counts::Error<gix_odb::store::find::Error>>),
+    #[error("Could not write bundle header")]
+    WriteHeader(#[from] std::io::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+impl Repository {
+    /// Write a bundle to `out` containing the objects of all revisions in `specs`, similar to `git bundle create`.
+    ///
+    /// Each spec is [parsed](Repository::rev_parse()) and may include or exclude revisions, like `main`, `^v1.0` or `v1.0..main`.
+    /// Revisions named by a reference are listed in the bundle header, and the excluded commits that are parents of included
+    /// ones become its prerequisites, which the receiving repository has to have.
+    ///
+    /// Return the header that was written before the pack.
+    pub fn bundle_create<P>(
+        &self,
+        mut out: impl Write,
+        specs: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+        progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Header, Error>
+    where
+        P: Progress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("gix::Repository::bundle_create()");
+        let mut tips = Vec::new();
+        let mut excluded = Vec::new();
+        let mut refs = Vec::<Ref>::new();
+        for spec in specs {
+            let spec = self.rev_parse(spec.as_ref()).map_err(Box::new)?;
+            let mut add_ref = |reference: Option<&gix_ref::Reference>, id: ObjectId| {
+                if let Some(reference) = reference {
+                    if !refs.iter().any(|r| r.name == reference.name.as_bstr()) {
+                        refs.push(Ref {
+                            id: reference.target.try_id().map_or(id, ToOwned::to_owned),
+                            name: reference.name.as_bstr().to_owned(),
+                        });
+                    }
+                }
+            };
+            use gix_revision::Spec::*;
+            match spec.inner {
+                Include(id) => {
+                    add_ref(spec.first_reference(), id);
+                    tips.push(id);
+                }
+                Exclude(id) => excluded.push(id),
+                Range { from, to } => {
+                    add_ref(spec.second_reference(), to);
+                    excluded.push(from);
+                    tips.push(to);
+                }
+                Merge { theirs, ours } => {
+                    add_ref(spec.first_reference(), theirs);
+                    add_ref(spec.second_reference(), ours);
+                    excluded.extend(
+                        spec.merge_bases()?
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(crate::Id::detach),
+                    );
+                    tips.extend([theirs, ours]);
+                }
+                IncludeOnlyParents(id) => tips.extend(self.parent_ids_of(id)?),
+                ExcludeParents(id) => {
+                    add_ref(spec.first_reference(), id);
+                    excluded.extend(self.parent_ids_of(id)?);
+                    tips.push(id);
+                }
+            }
+        }
+        if refs.is_empty() {
+            return Err(Error::NoReferences);
+        }
+
+        let input = self.pack_input(tips, excluded)?;
+        let mut prerequisites = Vec::<Prerequisite>::new();
+        for id in &input.boundary {
+            let commit = self.find_object(*id)?.into_commit();
+            let comment = commit.message_raw_sloppy().lines().next().map(ToOwned::to_owned);
+            prerequisites.push(Prerequisite {
+                id: *id,
+                comment: comment.map(BString::from),
+            });
+        }
+
+        let header = Header {
+            version: options.version,
+            object_hash: self.object_hash(),
+            filter: None,
+            prerequisites,
+            refs,
+        };
+        header.write_to(&mut out)?;
+
+        self.write_pack(input, true, out, progress, should_interrupt)?;
+        Ok(header)
+    }
+
+    /// Return the objects to put into a pack to transfer all commits reachable from `tips` but not from `excluded`.
+    ///
+    /// Tips that aren't commits are transferred as well, along with the commit they point to, if any.
+    pub(crate) fn pack_input(
+        &self,
+        tips: impl IntoIterator<Item = ObjectId>,
+        excluded: impl IntoIterator<Item = ObjectId>,
+    ) -> Result<PackInput, Error> {
+        let mut tip_commits = Vec::new();
+        let mut objects = Vec::new();
+        for id in tips {
+            match self.find_object(id)?.peel_to_kind(gix_object::Kind::Commit) {
+                Ok(commit) => {
+                    tip_commits.push(commit.id);
+                    if commit.id != id {
+                        objects.push(id);
+                    }
+                }
+                Err(_) => objects.push(id),
+            }
+        }
+        let mut excluded_commits = Vec::new();
+        for id in excluded {
+            if let Ok(commit) = self.find_object(id)?.peel_to_kind(gix_object::Kind::Commit) {
+                excluded_commits.push(commit.id);
+            }
+        }
+        let mut commits = Vec::new();
+        let mut parents = Vec::new();
+        if excluded_commits.is_empty() {
+            for info in self.rev_walk(tip_commits).all()? {
+                let info = info?;
+                commits.push(info.id);
+                parents.extend(info.parent_ids.iter().copied());
+            }
+        } else {
+            // Walk both sides at once so only the commits down to their merge-bases are looked at.
+            let mut graph = self.revision_graph();
+            commits = gix_revision::merge_base::exclusive(&tip_commits, &excluded_commits, &mut graph)?;
+            for id in &commits {
+                parents.extend(graph[id].parents.iter().copied());
+            }
+        }
+
+        let included: gix_hashtable::HashSet = commits.iter().copied().collect();
+        let mut boundary = Vec::new();
+        for id in parents {
+            if !included.contains(&id) && !boundary.contains(&id) {
+                boundary.push(id);
+            }
+        }
+        objects.extend(commits);
+        Ok(PackInput {
+            objects,
+            #[cfg(feature = "blocking-network-client")]
+            commits: included,
+            boundary,
+        })
+    }
+
+    /// Write a pack with all objects needed to transfer `input` to `out`, leaving out the boundary commits and their trees
+    /// as the receiver already has them.
+    ///
+    /// If `allow_thin_pack` is `true`, objects may be stored as deltas against objects that aren't part of the pack.
+    pub(crate) fn write_pack<P>(
+        &self,
+        input: PackInput,
+        allow_thin_pack: bool,
+        out: impl Write,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error>
+    where
+        P: Progress,
+        P::SubProgress: 'static,
+    {
+        let thread_limit = super::pack_threads(self)?;
+        let mut db = self.objects.clone().into_arc().map_err(Error::OpenArcOdb)?;
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+        let (mut counts, _) = output::count::objects_unthreaded(
+            db.clone(),
+            input.objects.into_iter().map(Ok::<_, Infallible>),
+            progress.add_child("counting"),
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        )?;
+        if !input.boundary.is_empty() {
+            let mut known = gix_hashtable::HashSet::default();
+            for id in input.boundary {
+                known.insert(self.find_object(id)?.into_commit().tree_id()?.detach());
+                known.insert(id);
+            }
+            counts.retain(|count| !known.contains(&count.id));
+        }
+        let num_objects = counts.len() as u32;
+        let entries = InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            db,
+            progress.add_child("creating entries"),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack,
+                chunk_size: 1000,
+                version: Default::default(),
+            },
+        ));
+        let write_pack = output::bytes::FromEntriesIter::new(
+            entries,
+            out,
+            num_objects,
+            gix_pack::data::Version::default(),
+            self.object_hash(),
+        );
+        for written in write_pack {
+            if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            written?;
+        }
+        Ok(())
+    }
+
+    fn parent_ids_of(&self, id: ObjectId) -> Result<Vec<ObjectId>, Error> {
+        Ok(self
+            .find_object(id)?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .into_commit()
+            .parent_ids()
+            .map(crate::Id::detach)
+            .collect())
+    }
+}
+
+/// The objects to put into a pack, as obtained by [`Repository::pack_input()`].
+pub(crate) struct PackInput {
+    /// The objects to count, including all commits to transfer.
+    pub objects: Vec<ObjectId>,
+    /// All commits that will be transferred.
+    #[cfg(feature = "blocking-network-client")]
+    pub commits: gix_hashtable::HashSet,
+    /// The parents of transferred commits which aren't transferred themselves, as the receiver is expected to have them.
+    pub boundary: Vec<ObjectId>,
+}
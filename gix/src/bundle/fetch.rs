@@ -0,0 +1,134 @@
+use std::{borrow::Cow, sync::atomic::AtomicBool};
+
+use gix_odb::FindExt;
+
+use crate::{
+    bstr::ByteSlice,
+    bundle::{File, Header},
+    config::{cache::util::ApplyLeniency, tree::Pack},
+    remote, Progress, Repository,
+};
+
+/// The error returned by [`Repository::bundle_fetch()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Verify(#[from] super::verify::Error),
+    #[error(transparent)]
+    PackThreads(#[from] crate::config::unsigned_integer::Error),
+    #[error(transparent)]
+    PackIndexVersion(#[from] crate::config::key::GenericError),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    InvalidRefSpecs(#[from] gix_refspec::match_group::validate::Error),
+    #[error(transparent)]
+    UpdateRefs(#[from] Box<crate::remote::fetch::refs::update::Error>),
+}
+
+/// The outcome of [`Repository::bundle_fetch()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The header of the bundle that was fetched from.
+    pub header: Header,
+    /// Information about the pack and index written to the object database.
+    pub write_pack_bundle: gix_pack::bundle::write::Outcome,
+    /// The mappings of references in the bundle to local references, as produced by the ref-specs.
+    pub mappings: Vec<remote::fetch::Mapping>,
+    /// Information collected while updating references, with one update for each of the `mappings`.
+    pub update_refs: remote::fetch::refs::update::Outcome,
+}
+
+impl Repository {
+    /// Receive the pack of `bundle` into the object database and update local references as mapped by `refspecs`,
+    /// similar to `git fetch <bundle> <refspecs>…`.
+    ///
+    /// The bundle is [verified](Repository::bundle_verify()) first. References are updated exactly like in a regular fetch, i.e.
+    /// only as fast-forward unless the ref-spec allows otherwise, while existing tags, symbolic references and branches that
+    /// are checked out aren't changed at all. Without `refspecs`, only objects are received, similar to `git bundle unbundle`.
+    pub fn bundle_fetch<P>(
+        &self,
+        bundle: File,
+        refspecs: &[gix_refspec::RefSpec],
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error>
+    where
+        P: Progress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("gix::Repository::bundle_fetch()");
+        self.bundle_verify(&bundle.header)?;
+        let path = bundle.path().to_owned();
+        let header = bundle.header.clone();
+
+        let options = gix_pack::bundle::write::Options {
+            thread_limit: super::pack_threads(self)?,
+            index_version: self
+                .config
+                .resolved
+                .integer("pack", None, Pack::INDEX_VERSION.name)
+                .map(|value| Pack::INDEX_VERSION.try_into_index_version(value))
+                .transpose()
+                .with_leniency(self.options.lenient_config)?
+                .unwrap_or(gix_pack::index::Version::V2),
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            object_hash: self.object_hash(),
+        };
+        let write_pack_bundle = gix_pack::Bundle::write_to_directory(
+            bundle.into_pack(),
+            Some(self.objects.store_ref().path().join("pack")),
+            progress.add_child("receive pack"),
+            should_interrupt,
+            Some(Box::new({
+                let repo = self.clone();
+                move |oid, buf| repo.objects.find(oid, buf).ok()
+            })),
+            options,
+        )?;
+
+        let (group, _fixes) = gix_refspec::MatchGroup::from_fetch_specs(refspecs.iter().map(|spec| spec.to_ref()))
+            .match_remotes(header.refs.iter().map(|r| gix_refspec::match_group::Item {
+                full_ref_name: r.name.as_bstr(),
+                target: &r.id,
+                object: None,
+            }))
+            .validated()?;
+
+        let mappings: Vec<_> = group
+            .mappings
+            .into_iter()
+            .filter_map(|mapping| {
+                let remote_ref = &header.refs[mapping.item_index?];
+                Some(remote::fetch::Mapping {
+                    remote: remote::fetch::Source::Ref(gix_protocol::handshake::Ref::Direct {
+                        full_ref_name: remote_ref.name.clone(),
+                        object: remote_ref.id,
+                    }),
+                    local: mapping.rhs.map(Cow::into_owned),
+                    spec_index: remote::fetch::SpecIndex::ExplicitInRemote(mapping.spec_index),
+                })
+            })
+            .collect();
+        let update_refs = remote::fetch::refs::update(
+            self,
+            remote::fetch::RefLogMessage::Prefixed {
+                action: format!("fetch {}", path.display()),
+            },
+            &mappings,
+            refspecs,
+            &[],
+            remote::fetch::Tags::None,
+            remote::fetch::DryRun::No,
+            remote::fetch::WritePackedRefs::Never,
+        )
+        .map_err(Box::new)?;
+        Ok(Outcome {
+            header,
+            write_pack_bundle,
+            mappings,
+            update_refs,
+        })
+    }
+}
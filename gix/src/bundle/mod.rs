@@ -0,0 +1,33 @@
+//! Read, verify and create [bundle files](https://git-scm.com/docs/git-bundle), and fetch objects and references from them.
+//!
+//! Bundles are opened as [`File`], which provides the references of the bundle in its [header](Header) similar to the
+//! reference listing of a remote, and the pack to receive with [`Repository::bundle_fetch()`](crate::Repository::bundle_fetch()).
+//! Bundles and bundle lists can also be downloaded to bootstrap a repository with
+//! [`Repository::bundle_uri_fetch()`](crate::Repository::bundle_uri_fetch()).
+pub use gix_bundle::*;
+
+use crate::{
+    config::{cache::util::ApplyLeniency, tree::Pack},
+    Repository,
+};
+
+///
+pub mod create;
+///
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub mod fetch;
+///
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub mod uri;
+///
+pub mod verify;
+
+/// Return the amount of threads to use for pack operations as configured by `pack.threads`.
+fn pack_threads(repo: &Repository) -> Result<Option<usize>, crate::config::unsigned_integer::Error> {
+    repo.config
+        .resolved
+        .integer_filter("pack", None, Pack::THREADS.name, &mut repo.filter_config_section())
+        .map(|threads| Pack::THREADS.try_into_usize(threads))
+        .transpose()
+        .with_leniency(repo.options.lenient_config)
+}
@@ -0,0 +1,387 @@
+use std::{
+    io::Read,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    bundle::{list::Mode, File, List},
+    Progress, Repository,
+};
+
+/// The maximum amount of bundle lists that may refer to each other, like in `git`.
+const MAX_DEPTH: usize = 4;
+
+/// The error returned by [`Repository::bundle_uri_fetch()`] and [`Repository::bundle_list_fetch()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The scheme of {uri:?} isn't supported for downloading bundles")]
+    UnsupportedScheme { uri: BString },
+    #[error(transparent)]
+    Url(#[from] gix_url::parse::Error),
+    #[error("Could not read bundle or bundle list at {uri:?}")]
+    Read { uri: BString, source: std::io::Error },
+    #[error("Failed to download {uri:?}")]
+    Download {
+        uri: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("Could not write the download of {uri:?} to a temporary file")]
+    TempFile { uri: BString, source: std::io::Error },
+    #[error("The bundle list at {uri:?} could not be parsed as configuration file")]
+    ListSyntax {
+        uri: BString,
+        source: gix_config::file::init::Error,
+    },
+    #[error("The bundle list at {uri:?} is invalid")]
+    List {
+        uri: BString,
+        source: gix_bundle::list::Error,
+    },
+    #[error("The bundle list at {list:?} isn't local and may not refer to the local bundle at {uri:?}")]
+    LocalUriInRemoteList { uri: BString, list: BString },
+    #[error("Bundle lists may not refer to other bundle lists more than {max} levels deep")]
+    TooDeeplyNested { max: usize },
+    #[error(transparent)]
+    Open(#[from] gix_bundle::file::Error),
+    #[error("Could not unbundle {uri:?}")]
+    Fetch {
+        uri: BString,
+        source: Box<super::fetch::Error>,
+    },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// A bundle that was downloaded and unbundled.
+#[derive(Debug, Clone)]
+pub struct Unbundled {
+    /// The location the bundle was obtained from.
+    pub uri: BString,
+    /// The result of fetching from the bundle.
+    pub outcome: super::fetch::Outcome,
+}
+
+/// The outcome of [`Repository::bundle_uri_fetch()`] and [`Repository::bundle_list_fetch()`].
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    /// All bundles that were unbundled, in order, including those of nested bundle lists.
+    pub unbundled: Vec<Unbundled>,
+}
+
+/// What was found at a bundle URI.
+enum Content {
+    Bundle(File),
+    List(List),
+}
+
+impl Repository {
+    /// Download the bundle or bundle list at `uri` and receive the objects of all bundles into the object database, while
+    /// storing their branches as `refs/bundles/*`, similar to `git clone --bundle-uri=<uri>`.
+    ///
+    /// `uri` may be a path or `file://` URL, or an `http(s)://` URL if an HTTP transport is compiled in. Relative locations in
+    /// bundle lists are resolved against the location of the list, and absolute paths against its scheme and host. Lists that
+    /// aren't local may not refer to local bundles. The created references serve as starting point for a
+    /// following fetch, which then only has to receive what the bundles didn't provide.
+    pub fn bundle_uri_fetch<P>(
+        &self,
+        uri: &BStr,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error>
+    where
+        P: Progress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("gix::Repository::bundle_uri_fetch()");
+        let mut out = Outcome::default();
+        self.fetch_bundle_uri(uri, 0, &mut progress, should_interrupt, &mut out)?;
+        Ok(out)
+    }
+
+    /// Like [`bundle_uri_fetch()`](Self::bundle_uri_fetch()), but use the bundles of `list`, typically as advertised by a
+    /// server, and resolve relative locations against `base_uri`.
+    pub fn bundle_list_fetch<P>(
+        &self,
+        list: &List,
+        base_uri: &BStr,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error>
+    where
+        P: Progress,
+        P::SubProgress: 'static,
+    {
+        let _span = gix_trace::coarse!("gix::Repository::bundle_list_fetch()");
+        let mut out = Outcome::default();
+        self.fetch_bundle_list(list, base_uri, 0, &mut progress, should_interrupt, &mut out)?;
+        Ok(out)
+    }
+
+    fn fetch_bundle_uri<P>(
+        &self,
+        uri: &BStr,
+        depth: usize,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+        out: &mut Outcome,
+    ) -> Result<(), Error>
+    where
+        P: Progress,
+        P::SubProgress: 'static,
+    {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        progress.info(format!("obtaining {uri}"));
+        // Keep the temporary file alive until the bundle was unbundled.
+        let (content, _tempfile) = self.download_bundle_uri(uri)?;
+        match content {
+            Content::Bundle(bundle) => {
+                let outcome = self
+                    .bundle_fetch(bundle, &[bundles_refspec()], &mut *progress, should_interrupt)
+                    .map_err(|source| Error::Fetch {
+                        uri: uri.into(),
+                        source: Box::new(source),
+                    })?;
+                out.unbundled.push(Unbundled {
+                    uri: uri.into(),
+                    outcome,
+                });
+            }
+            Content::List(list) => {
+                if depth == MAX_DEPTH {
+                    return Err(Error::TooDeeplyNested { max: MAX_DEPTH });
+                }
+                self.fetch_bundle_list(&list, uri, depth + 1, progress, should_interrupt, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fetch_bundle_list<P>(
+        &self,
+        list: &List,
+        base_uri: &BStr,
+        depth: usize,
+        progress: &mut P,
+        should_interrupt: &AtomicBool,
+        out: &mut Outcome,
+    ) -> Result<(), Error>
+    where
+        P: Progress,
+        P::SubProgress: 'static,
+    {
+        let mut bundles: Vec<_> = list.bundles.iter().collect();
+        if list.heuristic.is_some() {
+            bundles.sort_by_key(|bundle| bundle.creation_token.unwrap_or(u64::MAX));
+        }
+        match list.mode {
+            Mode::Any => {
+                let mut last_err = None;
+                for bundle in bundles {
+                    let res = resolve(base_uri, bundle.uri.as_ref())
+                        .and_then(|uri| self.fetch_bundle_uri(uri.as_ref(), depth, progress, should_interrupt, out));
+                    match res {
+                        Ok(()) => return Ok(()),
+                        Err(Error::Interrupted) => return Err(Error::Interrupted),
+                        Err(err) => {
+                            progress.fail(format!(
+                                "Ignoring bundle at {:?} which couldn't be used: {err}",
+                                bundle.uri
+                            ));
+                            last_err = Some(err);
+                        }
+                    }
+                }
+                match last_err {
+                    Some(err) => Err(err),
+                    None => Ok(()),
+                }
+            }
+            Mode::All => {
+                // Bundles may build on each other, so those which lack prerequisites are retried once others were unbundled.
+                let mut pending = bundles;
+                loop {
+                    let num_pending = pending.len();
+                    let mut last_err = None;
+                    let mut still_pending = Vec::new();
+                    for bundle in pending {
+                        let res = resolve(base_uri, bundle.uri.as_ref()).and_then(|uri| {
+                            self.fetch_bundle_uri(uri.as_ref(), depth, progress, should_interrupt, out)
+                        });
+                        match res {
+                            Ok(()) => {}
+                            Err(Error::Interrupted) => return Err(Error::Interrupted),
+                            Err(err) => {
+                                still_pending.push(bundle);
+                                last_err = Some(err);
+                            }
+                        }
+                    }
+                    match last_err {
+                        None => return Ok(()),
+                        Some(err) if still_pending.len() == num_pending => return Err(err),
+                        Some(_) => pending = still_pending,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Obtain the content at `uri`, along with the temporary file that holds it if it had to be downloaded.
+    fn download_bundle_uri(
+        &self,
+        uri: &BStr,
+    ) -> Result<(Content, Option<gix_tempfile::Handle<gix_tempfile::handle::Writable>>), Error> {
+        let url = gix_url::parse(uri)?;
+        let (path, tempfile) = match url.scheme {
+            gix_url::Scheme::File => (gix_path::from_bstr(url.path.as_bstr()).into_owned(), None),
+            #[cfg(any(
+                feature = "blocking-http-transport-reqwest",
+                feature = "blocking-http-transport-curl"
+            ))]
+            gix_url::Scheme::Http | gix_url::Scheme::Https => {
+                let (tempfile, path) = self.download_to_tempfile(uri)?;
+                (path, Some(tempfile))
+            }
+            _ => return Err(Error::UnsupportedScheme { uri: uri.into() }),
+        };
+
+        let mut signature = [0u8; 16];
+        let is_bundle = std::fs::File::open(&path)
+            .and_then(|mut file| file.read(&mut signature))
+            .map_err(|source| Error::Read {
+                uri: uri.into(),
+                source,
+            })
+            .map(|read| matches!(&signature[..read], b"# v2 git bundle\n" | b"# v3 git bundle\n"))?;
+        let content = if is_bundle {
+            Content::Bundle(File::at(path)?)
+        } else {
+            let buf = std::fs::read(&path).map_err(|source| Error::Read {
+                uri: uri.into(),
+                source,
+            })?;
+            let config =
+                gix_config::File::from_bytes_no_includes(&buf, gix_config::file::Metadata::api(), Default::default())
+                    .map_err(|source| Error::ListSyntax {
+                    uri: uri.into(),
+                    source,
+                })?;
+            let pairs: Vec<(BString, BString)> = config
+                .sections_by_name("bundle")
+                .into_iter()
+                .flatten()
+                .flat_map(|section| {
+                    let prefix = match section.header().subsection_name() {
+                        Some(id) => format!("bundle.{id}."),
+                        None => "bundle.".into(),
+                    };
+                    section.keys().filter_map(move |key| {
+                        let value = section.value(key.as_ref())?;
+                        Some((format!("{prefix}{key}").into(), value.into_owned()))
+                    })
+                })
+                .collect();
+            Content::List(
+                List::from_key_value_pairs(pairs.iter().map(|(key, value)| (key.as_bstr(), value.as_bstr()))).map_err(
+                    |source| Error::List {
+                        uri: uri.into(),
+                        source,
+                    },
+                )?,
+            )
+        };
+        Ok((content, tempfile))
+    }
+
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    fn download_to_tempfile(
+        &self,
+        uri: &BStr,
+    ) -> Result<(gix_tempfile::Handle<gix_tempfile::handle::Writable>, std::path::PathBuf), Error> {
+        use std::io::Write;
+        let url = uri.to_str().map_err(|_| Error::UnsupportedScheme { uri: uri.into() })?;
+        let mut tempfile = gix_tempfile::new(
+            self.git_dir(),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )
+        .map_err(|source| Error::TempFile {
+            uri: uri.into(),
+            source,
+        })?;
+        self.http_get(url, None, |body| -> std::io::Result<_> {
+            std::io::copy(body, &mut tempfile)?;
+            tempfile.flush()
+        })
+        .map_err(|source| Error::Download {
+            uri: uri.into(),
+            source,
+        })?;
+        let path = tempfile
+            .with_mut(|file| file.path().to_owned())
+            .map_err(|source| Error::TempFile {
+                uri: uri.into(),
+                source,
+            })?;
+        Ok((tempfile, path))
+    }
+}
+
+/// Branches of bundles are stored in their own namespace to not interfere with anything a following fetch creates.
+fn bundles_refspec() -> gix_refspec::RefSpec {
+    gix_refspec::parse(
+        "+refs/heads/*:refs/bundles/*".into(),
+        gix_refspec::parse::Operation::Fetch,
+    )
+    .expect("valid")
+    .to_owned()
+}
+
+/// Resolve `uri` relative to the location of the bundle list at `base` similar to `git`, unless it is a URL itself.
+///
+/// Absolute paths are resolved against the scheme and host of `base`, and local bundles may only be used by local bundle lists.
+fn resolve(base: &BStr, uri: &BStr) -> Result<BString, Error> {
+    let base_is_local = gix_url::parse(base)?.scheme == gix_url::Scheme::File;
+    if uri.contains_str("://") {
+        if !base_is_local && gix_url::parse(uri)?.scheme == gix_url::Scheme::File {
+            return Err(Error::LocalUriInRemoteList {
+                uri: uri.into(),
+                list: base.into(),
+            });
+        }
+        return Ok(uri.into());
+    }
+    // The end of the scheme and host of `base`, which are kept in any case.
+    let root_end = if base_is_local {
+        0
+    } else {
+        let host_start = base.find("://").map_or(0, |pos| pos + 3);
+        base[host_start..]
+            .find_byte(b'/')
+            .map_or(base.len(), |pos| host_start + pos)
+    };
+    if uri.starts_with(b"/") {
+        let mut out: BString = base[..root_end].into();
+        out.extend_from_slice(uri);
+        return Ok(out);
+    }
+    let base = base.trim_end_with(|c| c == '/');
+    let mut out: BString = match base[root_end.min(base.len())..].rfind_byte(b'/') {
+        Some(pos) => base[..=root_end + pos].into(),
+        None if root_end == 0 => BString::default(),
+        None => {
+            let mut out: BString = base.into();
+            out.push(b'/');
+            out
+        }
+    };
+    out.extend_from_slice(uri);
+    Ok(out)
+}
@@ -0,0 +1,44 @@
+use gix_hash::ObjectId;
+use gix_odb::Find;
+
+use crate::{bundle::Header, Repository};
+
+/// The error returned by [`Repository::bundle_verify()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The bundle uses object format {actual}, but the repository uses {expected}")]
+    ObjectHash {
+        expected: gix_hash::Kind,
+        actual: gix_hash::Kind,
+    },
+    #[error("The repository lacks {} prerequisite commit(s) of the bundle, like {}", ids.len(), ids[0])]
+    MissingPrerequisites { ids: Vec<ObjectId> },
+}
+
+impl Repository {
+    /// Verify that the bundle with `header` can be unpacked into this repository, similar to `git bundle verify`.
+    ///
+    /// This is the case if it uses the same object format and if all of its prerequisite commits are present.
+    ///
+    /// Note that only the existence of the prerequisite objects is checked, not that their history is complete,
+    /// which is what `git` additionally asserts by traversing from them.
+    pub fn bundle_verify(&self, header: &Header) -> Result<(), Error> {
+        if header.object_hash != self.object_hash() {
+            return Err(Error::ObjectHash {
+                expected: self.object_hash(),
+                actual: header.object_hash,
+            });
+        }
+        let missing: Vec<_> = header
+            .prerequisites
+            .iter()
+            .map(|prerequisite| prerequisite.id)
+            .filter(|id| !self.objects.contains(id))
+            .collect();
+        if !missing.is_empty() {
+            return Err(Error::MissingPrerequisites { ids: missing });
+        }
+        Ok(())
+    }
+}
@@ -35,6 +35,8 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[error("Could not fetch the blobs to check out from the promisor remote")]
+        FetchMissing(#[source] gix_odb::store::promisor::Error),
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -77,7 +79,8 @@ pub mod main_worktree {
             let workdir = repo.work_dir().ok_or_else(|| Error::BareRepository {
                 git_dir: repo.git_dir().to_owned(),
             })?;
-            let root_tree = match repo.head()?.peel_to_id_in_place().transpose()? {
+            let head_id = repo.head()?.peel_to_id_in_place().transpose()?;
+            let root_tree = match head_id {
                 Some(id) => id.object().expect("downloaded from remote").peel_to_tree()?.id,
                 None => {
                     return Ok((
@@ -102,6 +105,17 @@ pub mod main_worktree {
             files.init(Some(index.entries().len()), crate::progress::count("files"));
             bytes.init(None, crate::progress::bytes());
 
+            // In partial clones, fetch all blobs at once instead of one by one while checking them out.
+            repo.objects
+                .fetch_missing(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|entry| entry.mode != gix_index::entry::Mode::COMMIT)
+                        .map(|entry| entry.id),
+                )
+                .map_err(Error::FetchMissing)?;
+
             let start = std::time::Instant::now();
             let outcome = gix_worktree::checkout(
                 &mut index,
@@ -119,6 +133,15 @@ pub mod main_worktree {
             bytes.show_throughput(start);
 
             index.write(Default::default())?;
+            if let Some(head_id) = head_id {
+                // Like `git`, ignore the exit status of the hook as the checkout is already done.
+                repo.run_hook(
+                    "post-checkout",
+                    [repo.object_hash().null().to_string(), head_id.to_string(), "1".into()],
+                    None,
+                )
+                .ok();
+            }
             Ok((self.repo.take().expect("still present"), outcome))
         }
     }
@@ -31,6 +31,8 @@ pub enum Error {
     },
     #[error("Failed to update HEAD with values from remote")]
     HeadUpdate(#[from] crate::reference::edit::Error),
+    #[error("The value for 'transfer.bundleURI' is invalid")]
+    TransferBundleUri(#[from] crate::config::boolean::Error),
 }
 
 /// Modification
@@ -76,6 +78,28 @@ impl PrepareFetch {
                 .unwrap_or_else(|| "origin".into()),
         };
 
+        let bundle_uri = self.bundle_uri.clone().or_else(|| {
+            repo.config
+                .resolved
+                .string_filter_by_key("clone.bundleURI", &mut repo.filter_config_section())
+                .map(std::borrow::Cow::into_owned)
+        });
+        if let Some(uri) = bundle_uri.as_ref() {
+            if let Err(err) = repo.bundle_uri_fetch(uri.as_ref(), &mut progress, should_interrupt) {
+                progress.fail(format!(
+                    "Failed to fetch bundles from {uri:?}, continuing without them: {err}"
+                ));
+            }
+        }
+        let use_advertised_bundles = bundle_uri.is_none()
+            && repo
+                .config
+                .resolved
+                .boolean_filter_by_key("transfer.bundleURI", &mut repo.filter_config_section())
+                .map(|val| crate::config::tree::Transfer::BUNDLE_URI.enrich_error(val))
+                .transpose()?
+                .unwrap_or(false);
+
         let mut remote = repo
             .remote_at(self.url.clone())?
             .with_refspecs(
@@ -105,7 +129,7 @@ impl PrepareFetch {
         )
         .expect("valid")
         .to_owned();
-        let pending_pack: remote::fetch::Prepare<'_, '_, _> = {
+        let mut pending_pack: remote::fetch::Prepare<'_, '_, _> = {
             let mut connection = remote.connect(remote::Direction::Fetch).await?;
             if let Some(f) = self.configure_connection.as_mut() {
                 f(&mut connection).map_err(|err| Error::RemoteConnection(err))?;
@@ -123,6 +147,18 @@ impl PrepareFetch {
         if pending_pack.ref_map().object_hash != repo.object_hash() {
             unimplemented!("configure repository to expect a different object hash as advertised by the server")
         }
+        if use_advertised_bundles && pending_pack.ref_map().handshake.capabilities.contains("bundle-uri") {
+            match pending_pack.bundle_uri(&mut progress).await {
+                Ok(pairs) => util::fetch_advertised_bundles(
+                    repo,
+                    &pairs,
+                    self.url.to_bstring().as_ref(),
+                    &mut progress,
+                    should_interrupt,
+                ),
+                Err(err) => progress.fail(format!("Failed to obtain the bundle list of the remote: {err}")),
+            }
+        }
         let reflog_message = {
             let mut b = self.url.to_bstring();
             b.insert_str(0, "clone: from ");
@@ -130,6 +166,7 @@ impl PrepareFetch {
         };
         let outcome = pending_pack
             .with_write_packed_refs_only(true)
+            .with_write_fetch_head(false)
             .with_reflog_message(RefLogMessage::Override {
                 message: reflog_message.clone(),
             })
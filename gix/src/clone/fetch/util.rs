@@ -54,6 +54,34 @@ fn write_to_local_config(config: &gix_config::File<'static>, mode: WriteMode) ->
     config.write_to_filter(&mut local_config, |s| s.meta().source == gix_config::Source::Local)
 }
 
+/// Unbundle the bundles of the list described by `pairs` as advertised by the remote at `url`, ignoring all errors
+/// as the following fetch will receive everything the bundles didn't provide.
+pub fn fetch_advertised_bundles<P>(
+    repo: &Repository,
+    pairs: &[gix_protocol::bundle_uri::KeyValue],
+    url: &BStr,
+    mut progress: P,
+    should_interrupt: &std::sync::atomic::AtomicBool,
+) where
+    P: crate::Progress,
+    P::SubProgress: 'static,
+{
+    if pairs.is_empty() {
+        return;
+    }
+    let res = crate::bundle::List::from_key_value_pairs(pairs.iter().map(|kv| (kv.key.as_bstr(), kv.value.as_bstr())))
+        .map_err(|err| Box::new(err) as Box<dyn std::error::Error>)
+        .and_then(|list| {
+            repo.bundle_list_fetch(&list, url, &mut progress, should_interrupt)
+                .map_err(|err| Box::new(err) as Box<dyn std::error::Error>)
+        });
+    if let Err(err) = res {
+        progress.fail(format!(
+            "Failed to fetch bundles advertised by the remote, continuing without them: {err}"
+        ));
+    }
+}
+
 pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::File<'static>) {
     let repo_config = gix_features::threading::OwnShared::make_mut(&mut repo.config.resolved);
     repo_config.append(config);
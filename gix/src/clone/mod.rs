@@ -34,6 +34,9 @@ pub struct PrepareFetch {
     /// How to handle shallow clones
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     shallow: remote::fetch::Shallow,
+    /// The location of a bundle or bundle list to bootstrap the clone from.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    bundle_uri: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -114,6 +117,7 @@ impl PrepareFetch {
             #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
+            bundle_uri: None,
         })
     }
 }
@@ -156,11 +160,21 @@ mod access_feat {
             self.fetch_options = opts;
             self
         }
+
+        /// Download the bundle or bundle list at `uri` and unbundle it before fetching from the remote, which then only has
+        /// to send what the bundles didn't provide. Their branches are stored as `refs/bundles/*`.
+        ///
+        /// If not set here, `clone.bundleURI` is used, and otherwise the bundle list advertised by the remote if
+        /// `transfer.bundleURI` is enabled. Failing to obtain or unbundle bundles isn't fatal, just like in `git`.
+        pub fn with_bundle_uri(mut self, uri: impl Into<crate::bstr::BString>) -> Self {
+            self.bundle_uri = Some(uri.into());
+            self
+        }
     }
 }
 
 ///
-#[cfg(any(feature = "async-network-client-async-std", feature = "blocking-network-client"))]
+#[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
 pub mod fetch;
 
 ///
@@ -19,6 +19,14 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    SigningProgram(#[from] crate::signing::program::Error),
+    #[error("Could not sign the commit")]
+    Sign(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Hook(#[from] crate::hook::Error),
 }
 
 ///
@@ -197,10 +205,12 @@ pub mod describe {
         /// to save ~40% of time.
         pub fn try_resolve(&self) -> Result<Option<Resolution<'repo>>, Error> {
             // TODO: dirty suffix with respective dirty-detection
+            let objects = self.repo.objects_without_promisor();
             let mut graph = gix_revision::Graph::new(
                 |id, buf| {
-                    self.repo
-                        .objects
+                    objects
+                        .as_ref()
+                        .unwrap_or(&self.repo.objects)
                         .try_find(id, buf)
                         .map(|r| r.and_then(gix_object::Data::try_into_commit_iter))
                 },
@@ -7,6 +7,8 @@ impl Clone {
     /// The `clone.defaultRemoteName` key.
     pub const DEFAULT_REMOTE_NAME: keys::RemoteName =
         keys::RemoteName::new_remote_name("defaultRemoteName", &config::Tree::CLONE);
+    /// The `clone.bundleURI` key, the location of a bundle or bundle list to bootstrap a clone from before fetching.
+    pub const BUNDLE_URI: keys::Any = keys::Any::new("bundleURI", &config::Tree::CLONE);
     /// The `clone.rejectShallow` key.
     pub const REJECT_SHALLOW: keys::Boolean = keys::Boolean::new_boolean("rejectShallow", &config::Tree::CLONE);
 }
@@ -17,6 +19,6 @@ impl Section for Clone {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::DEFAULT_REMOTE_NAME, &Self::REJECT_SHALLOW]
+        &[&Self::DEFAULT_REMOTE_NAME, &Self::BUNDLE_URI, &Self::REJECT_SHALLOW]
     }
 }
@@ -6,6 +6,8 @@ use crate::{
 impl Core {
     /// The `core.abbrev` key.
     pub const ABBREV: Abbrev = Abbrev::new_with_validate("abbrev", &config::Tree::CORE, validate::Abbrev);
+    /// The `core.autocrlf` key.
+    pub const AUTO_CRLF: AutoCrlf = AutoCrlf::new_with_validate("autocrlf", &config::Tree::CORE, validate::AutoCrlf);
     /// The `core.bare` key.
     pub const BARE: keys::Boolean = keys::Boolean::new_boolean("bare", &config::Tree::CORE);
     /// The `core.checkStat` key.
@@ -19,6 +21,8 @@ impl Core {
     /// The `core.disambiguate` key.
     pub const DISAMBIGUATE: Disambiguate =
         Disambiguate::new_with_validate("disambiguate", &config::Tree::CORE, validate::Disambiguate);
+    /// The `core.eol` key.
+    pub const EOL: Eol = Eol::new_with_validate("eol", &config::Tree::CORE, validate::Eol);
     /// The `core.fileMode` key.
     pub const FILE_MODE: keys::Boolean = keys::Boolean::new_boolean("fileMode", &config::Tree::CORE);
     /// The `core.ignoreCase` key.
@@ -42,6 +46,8 @@ impl Core {
     /// The `core.repositoryFormatVersion` key.
     pub const REPOSITORY_FORMAT_VERSION: keys::UnsignedInteger =
         keys::UnsignedInteger::new_unsigned_integer("repositoryFormatVersion", &config::Tree::CORE);
+    /// The `core.safecrlf` key.
+    pub const SAFE_CRLF: SafeCrlf = SafeCrlf::new_with_validate("safecrlf", &config::Tree::CORE, validate::SafeCrlf);
     /// The `core.symlinks` key.
     pub const SYMLINKS: keys::Boolean = keys::Boolean::new_boolean("symlinks", &config::Tree::CORE);
     /// The `core.trustCTime` key.
@@ -68,6 +74,8 @@ impl Core {
         .with_environment_override("GIT_NO_REPLACE_OBJECTS");
     /// The `core.commitGraph` key.
     pub const COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("commitGraph", &config::Tree::CORE);
+    /// The `core.hooksPath` key.
+    pub const HOOKS_PATH: keys::Path = keys::Path::new_path("hooksPath", &config::Tree::CORE);
 }
 
 impl Section for Core {
@@ -78,10 +86,12 @@ impl Section for Core {
     fn keys(&self) -> &[&dyn Key] {
         &[
             &Self::ABBREV,
+            &Self::AUTO_CRLF,
             &Self::BARE,
             &Self::CHECK_STAT,
             &Self::DELTA_BASE_CACHE_LIMIT,
             &Self::DISAMBIGUATE,
+            &Self::EOL,
             &Self::FILE_MODE,
             &Self::IGNORE_CASE,
             &Self::FILES_REF_LOCK_TIMEOUT,
@@ -90,6 +100,7 @@ impl Section for Core {
             &Self::LOG_ALL_REF_UPDATES,
             &Self::PRECOMPOSE_UNICODE,
             &Self::REPOSITORY_FORMAT_VERSION,
+            &Self::SAFE_CRLF,
             &Self::SYMLINKS,
             &Self::TRUST_C_TIME,
             &Self::WORKTREE,
@@ -99,6 +110,7 @@ impl Section for Core {
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
             &Self::COMMIT_GRAPH,
+            &Self::HOOKS_PATH,
         ]
     }
 }
@@ -115,6 +127,77 @@ pub type LogAllRefUpdates = keys::Any<validate::LogAllRefUpdates>;
 /// The `core.disambiguate` key.
 pub type Disambiguate = keys::Any<validate::Disambiguate>;
 
+/// The `core.autocrlf` key.
+pub type AutoCrlf = keys::Any<validate::AutoCrlf>;
+
+/// The `core.eol` key.
+pub type Eol = keys::Any<validate::Eol>;
+
+/// The `core.safecrlf` key.
+pub type SafeCrlf = keys::Any<validate::SafeCrlf>;
+
+mod filter {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::core::{AutoCrlf, Eol, SafeCrlf},
+    };
+
+    impl AutoCrlf {
+        /// Convert `value` into the value used for [`gix_filter::eol::Configuration::auto_crlf`], which is `None` for `input`.
+        pub fn try_into_autocrlf(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Option<bool>, config::key::GenericErrorWithValue> {
+            if value.as_ref().eq_ignore_ascii_case(b"input") {
+                return Ok(None);
+            }
+            gix_config::Boolean::try_from(value.as_ref())
+                .map(|b| Some(b.0))
+                .map_err(|_| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+
+    impl Eol {
+        /// Convert `value` into the line-ending mode to use, or `None` if it's `native`.
+        pub fn try_into_eol(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Option<gix_filter::eol::Mode>, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"lf" => Some(gix_filter::eol::Mode::Lf),
+                b"crlf" => Some(gix_filter::eol::Mode::CrLf),
+                b"native" => None,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+
+    impl SafeCrlf {
+        /// Convert `value` into the round-trip check to perform when converting line endings, with `warn` being
+        /// a valid value alongside booleans.
+        pub fn try_into_safecrlf(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_filter::pipeline::CrlfRoundTripCheck, config::key::GenericErrorWithValue> {
+            if value.as_ref().eq_ignore_ascii_case(b"warn") {
+                return Ok(gix_filter::pipeline::CrlfRoundTripCheck::Warn);
+            }
+            gix_config::Boolean::try_from(value.as_ref())
+                .map(|b| {
+                    if b.0 {
+                        gix_filter::pipeline::CrlfRoundTripCheck::Fail
+                    } else {
+                        gix_filter::pipeline::CrlfRoundTripCheck::Skip
+                    }
+                })
+                .map_err(|_| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
 mod disambiguate {
     use std::borrow::Cow;
 
@@ -290,6 +373,30 @@ mod validate {
         }
     }
 
+    pub struct AutoCrlf;
+    impl keys::Validate for AutoCrlf {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::AUTO_CRLF.try_into_autocrlf(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct Eol;
+    impl keys::Validate for Eol {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::EOL.try_into_eol(value.into())?;
+            Ok(())
+        }
+    }
+
+    pub struct SafeCrlf;
+    impl keys::Validate for SafeCrlf {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::SAFE_CRLF.try_into_safecrlf(value.into())?;
+            Ok(())
+        }
+    }
+
     pub struct CheckStat;
     impl keys::Validate for CheckStat {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
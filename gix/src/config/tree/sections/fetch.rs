@@ -10,6 +10,20 @@ impl Fetch {
         &config::Tree::FETCH,
         validate::NegotiationAlgorithm,
     );
+    /// The `fetch.uriProtocols` key.
+    ///
+    /// A comma-separated list of protocols like `https` which, if set, enables downloading parts of the pack
+    /// from locations advertised by the server using `packfile-uris`.
+    pub const URI_PROTOCOLS: keys::String = keys::String::new_string("uriProtocols", &config::Tree::FETCH);
+    /// The `fetch.negotiationTip` key.
+    ///
+    /// A multi-value key with revisions or ref globs like `refs/heads/*` which, if set, are the only tips from which
+    /// `have` lines are sent to the server during negotiation.
+    pub const NEGOTIATION_TIP: keys::String = keys::String::new_string("negotiationTip", &config::Tree::FETCH);
+    /// The `fetch.writeFetchHead` key.
+    ///
+    /// If `false`, `FETCH_HEAD` isn't written after fetching. It defaults to `true`.
+    pub const WRITE_FETCH_HEAD: keys::Boolean = keys::Boolean::new_boolean("writeFetchHead", &config::Tree::FETCH);
 }
 
 impl Section for Fetch {
@@ -18,7 +32,12 @@ impl Section for Fetch {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::NEGOTIATION_ALGORITHM]
+        &[
+            &Self::NEGOTIATION_ALGORITHM,
+            &Self::NEGOTIATION_TIP,
+            &Self::URI_PROTOCOLS,
+            &Self::WRITE_FETCH_HEAD,
+        ]
     }
 }
 
@@ -80,6 +80,12 @@ mod subsections {
         pub const USE_STDEV: keys::Boolean = keys::Boolean::new_boolean("useStdev", &Gitoxide::CORE)
             .with_note("A runtime version of the USE_STDEV build flag.");
 
+        /// The `gitoxide.core.inProcessUploadPack` key.
+        pub const IN_PROCESS_UPLOAD_PACK: keys::Boolean =
+            keys::Boolean::new_boolean("inProcessUploadPack", &Gitoxide::CORE).with_note(
+                "If true, local repositories are served from within the process instead of spawning `git-upload-pack`, which works without `git` being installed.",
+            );
+
         /// The `gitoxide.core.shallowFile` key.
         pub const SHALLOW_FILE: keys::Path = keys::Path::new_path("shallowFile", &Gitoxide::CORE)
             .with_environment_override("GIT_SHALLOW_FILE")
@@ -98,6 +104,7 @@ mod subsections {
                 &Self::DEFAULT_PACK_CACHE_MEMORY_LIMIT,
                 &Self::USE_NSEC,
                 &Self::USE_STDEV,
+                &Self::IN_PROCESS_UPLOAD_PACK,
                 &Self::SHALLOW_FILE,
             ]
         }
@@ -0,0 +1,94 @@
+use crate::{
+    config,
+    config::tree::{keys, traits::SubSectionRequirement, Key, Merge, Section},
+};
+
+const DRIVER_PARAMETER: Option<SubSectionRequirement> = Some(SubSectionRequirement::Parameter("driver"));
+
+impl Merge {
+    /// The `merge.conflictStyle` key.
+    pub const CONFLICT_STYLE: ConflictStyle =
+        ConflictStyle::new_with_validate("conflictStyle", &config::Tree::MERGE, validate::ConflictStyle);
+    /// The `merge.renames` key.
+    pub const RENAMES: super::diff::Renames = super::diff::Renames::new_renames("renames", &config::Tree::MERGE)
+        .with_note("Copies are never tracked, 'copies' is the same as 'true'");
+    /// The `merge.renameLimit` key.
+    pub const RENAME_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("renameLimit", &config::Tree::MERGE);
+    /// The `merge.default` key.
+    pub const DEFAULT: keys::String = keys::String::new_string("default", &config::Tree::MERGE)
+        .with_note("The name of the merge driver to use for paths without the 'merge' attribute");
+    /// The `merge.<driver>.name` key.
+    pub const DRIVER_NAME: keys::String =
+        keys::String::new_string("name", &config::Tree::MERGE).with_subsection_requirement(DRIVER_PARAMETER);
+    /// The `merge.<driver>.driver` key.
+    pub const DRIVER_COMMAND: keys::Program =
+        keys::Program::new_program("driver", &config::Tree::MERGE).with_subsection_requirement(DRIVER_PARAMETER);
+    /// The `merge.<driver>.recursive` key.
+    pub const DRIVER_RECURSIVE: keys::String = keys::String::new_string("recursive", &config::Tree::MERGE)
+        .with_subsection_requirement(DRIVER_PARAMETER)
+        .with_note("Virtual merge bases aren't created, so this key is ignored");
+}
+
+impl Section for Merge {
+    fn name(&self) -> &str {
+        "merge"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::CONFLICT_STYLE,
+            &Self::RENAMES,
+            &Self::RENAME_LIMIT,
+            &Self::DEFAULT,
+            &Self::DRIVER_NAME,
+            &Self::DRIVER_COMMAND,
+            &Self::DRIVER_RECURSIVE,
+        ]
+    }
+}
+
+/// The `merge.conflictStyle` key.
+pub type ConflictStyle = keys::Any<validate::ConflictStyle>;
+
+mod conflict_style {
+    use std::borrow::Cow;
+
+    use gix_diff::blob::merge;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::sections::merge::ConflictStyle,
+    };
+
+    impl ConflictStyle {
+        /// Convert `value` into the style in which conflicts are presented.
+        pub fn try_into_conflict_style(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<merge::ConflictStyle, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"merge" => merge::ConflictStyle::Merge,
+                b"diff3" => merge::ConflictStyle::Diff3,
+                b"zdiff3" => merge::ConflictStyle::ZealousDiff3,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Merge},
+    };
+
+    pub struct ConflictStyle;
+    impl keys::Validate for ConflictStyle {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Merge::CONFLICT_STYLE.try_into_conflict_style(value.into())?;
+            Ok(())
+        }
+    }
+}
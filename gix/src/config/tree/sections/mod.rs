@@ -20,6 +20,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commit` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Commit;
+mod commit;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -55,6 +60,11 @@ pub mod fetch;
 pub struct Gitoxide;
 pub mod gitoxide;
 
+/// The `gpg` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gpg;
+pub mod gpg;
+
 /// The `http` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Http;
@@ -70,6 +80,11 @@ pub mod index;
 pub struct Init;
 mod init;
 
+/// The `merge` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Merge;
+pub mod merge;
+
 /// The `pack` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Pack;
@@ -80,6 +95,11 @@ pub mod pack;
 pub struct Protocol;
 pub mod protocol;
 
+/// The `push` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Push;
+pub mod push;
+
 /// The `remote` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Remote;
@@ -95,6 +115,16 @@ mod safe;
 pub struct Ssh;
 pub mod ssh;
 
+/// The `tag` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Tag;
+mod tag;
+
+/// The `transfer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Transfer;
+mod transfer;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
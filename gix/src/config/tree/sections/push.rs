@@ -0,0 +1,76 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Push, Section},
+};
+
+impl Push {
+    /// The `push.default` key
+    pub const DEFAULT: Default = Default::new_with_validate("default", &config::Tree::PUSH, validate::Default);
+}
+
+impl Section for Push {
+    fn name(&self) -> &str {
+        "push"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::DEFAULT]
+    }
+}
+
+/// The `push.default` key type.
+pub type Default = keys::Any<validate::Default>;
+
+/// Define what to push if no refspecs are given, and where to push it to.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Mode {
+    /// Push nothing, and fail if no refspecs are given.
+    Nothing,
+    /// Push the current branch to the branch of the same name on the remote.
+    Current,
+    /// Push the current branch to the branch it is configured to merge from, `branch.<name>.merge`.
+    ///
+    /// This is also known as `tracking`.
+    Upstream,
+    /// Push the current branch to the branch of the same name on the remote, but only if it is also its upstream branch.
+    #[default]
+    Simple,
+    /// Push all branches that have a branch of the same name on the remote.
+    Matching,
+}
+
+mod default {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::push::{Default, Mode},
+    };
+
+    impl Default {
+        /// Try to interpret `value` as the mode to use when pushing without refspecs.
+        pub fn try_into_mode(&'static self, value: Cow<'_, BStr>) -> Result<Mode, config::key::GenericErrorWithValue> {
+            Ok(match value.as_ref().as_bytes() {
+                b"nothing" => Mode::Nothing,
+                b"current" => Mode::Current,
+                b"upstream" | b"tracking" => Mode::Upstream,
+                b"simple" => Mode::Simple,
+                b"matching" => Mode::Matching,
+                _ => return Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Default;
+    impl keys::Validate for Default {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Push::DEFAULT.try_into_mode(value.into())?;
+            Ok(())
+        }
+    }
+}
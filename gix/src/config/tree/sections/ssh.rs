@@ -13,7 +13,7 @@ impl Ssh {
 /// The `ssh.variant` key.
 pub type Variant = keys::Any<validate::Variant>;
 
-#[cfg(feature = "blocking-network-client")]
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 mod variant {
     use std::borrow::Cow;
 
@@ -57,7 +57,7 @@ mod validate {
     pub struct Variant;
     impl keys::Validate for Variant {
         fn validate(&self, _value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
-            #[cfg(feature = "blocking-network-client")]
+            #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
             super::Ssh::VARIANT.try_into_variant(_value.into())?;
             Ok(())
         }
@@ -0,0 +1,143 @@
+//! A walk through the worktree to find paths that aren't tracked, shared by everything that needs to find them.
+use std::path::{Path, PathBuf};
+
+use gix_index::entry;
+use gix_odb::FindExt;
+
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The error returned if the worktree couldn't be accessed during a [walk](Walk::run()).
+#[derive(Debug, thiserror::Error)]
+#[error("Could not access '{}' in the worktree", path.display())]
+pub(crate) struct Error {
+    pub path: PathBuf,
+    #[source]
+    pub source: std::io::Error,
+}
+
+/// Decide what to do with the paths encountered during a [walk](Walk::run()).
+pub(crate) trait Delegate {
+    /// The error the delegate may return, which also has to be able to hold errors of the walk itself.
+    type Error: From<Error>;
+
+    /// Called for the directory at `rela_path`, which doesn't end with a slash, and return `true` to walk into it.
+    fn enter_dir(&mut self, walk: &mut Walk<'_>, rela_path: &BStr) -> Result<bool, Self::Error>;
+
+    /// Called once all entries of the directory at `rela_path` that was entered were visited.
+    fn leave_dir(&mut self, _walk: &mut Walk<'_>, _rela_path: &BStr) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called for each file or symlink at `rela_path`, no matter whether it's tracked or not.
+    fn visit_file(&mut self, walk: &mut Walk<'_>, rela_path: &BStr) -> Result<(), Self::Error>;
+}
+
+/// A walk through all directories of the worktree in sorted order, which skips `.git` and everything that isn't
+/// a file, symlink or directory, along with utilities to classify the paths it encounters.
+pub(crate) struct Walk<'a> {
+    /// The root of the worktree.
+    pub workdir: &'a Path,
+    /// The index whose entries are tracked.
+    pub index: &'a gix_index::State,
+    /// Only the first `num_tracked` entries of `index` are considered, as more may have been pushed after them.
+    pub num_tracked: usize,
+    /// The cache to determine if paths are excluded.
+    pub excludes: &'a mut gix_worktree::Cache,
+    /// The objects to read exclude files from.
+    pub objects: &'a crate::OdbHandle,
+}
+
+impl<'a> Walk<'a> {
+    /// Walk the entire worktree and let `delegate` decide what to do with each path.
+    pub fn run<D: Delegate>(&mut self, delegate: &mut D) -> Result<(), D::Error> {
+        let mut rela_path = BString::default();
+        self.walk_dir(&mut rela_path, delegate)
+    }
+
+    /// Visit all entries of the directory at `rela_path`, which is empty for the root or ends with a slash.
+    fn walk_dir<D: Delegate>(&mut self, rela_path: &mut BString, delegate: &mut D) -> Result<(), D::Error> {
+        let path = self.workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&path).map_err(|source| Error {
+            path: path.clone(),
+            source,
+        })? {
+            let entry = entry.map_err(|source| Error {
+                path: path.clone(),
+                source,
+            })?;
+            let file_type = entry.file_type().map_err(|source| Error {
+                path: entry.path(),
+                source,
+            })?;
+            if !(file_type.is_dir() || file_type.is_file() || file_type.is_symlink()) {
+                continue;
+            }
+            let Ok(name) = gix_path::os_string_into_bstring(entry.file_name()) else {
+                continue;
+            };
+            if name == ".git" {
+                continue;
+            }
+            entries.push((name, file_type.is_dir()));
+        }
+        entries.sort();
+
+        for (name, is_dir) in entries {
+            let len = rela_path.len();
+            rela_path.push_str(&name);
+            if is_dir {
+                if delegate.enter_dir(self, rela_path.as_bstr())? {
+                    rela_path.push_byte(b'/');
+                    self.walk_dir(rela_path, delegate)?;
+                    rela_path.pop();
+                    delegate.leave_dir(self, rela_path.as_bstr())?;
+                }
+            } else {
+                delegate.visit_file(self, rela_path.as_bstr())?;
+            }
+            rela_path.truncate(len);
+        }
+        Ok(())
+    }
+
+    /// Return `true` if `rela_path` is tracked in any stage, unless it is a merged entry that is marked for removal.
+    pub fn is_tracked(&self, rela_path: &BStr) -> bool {
+        (0..=3).any(|stage| {
+            self.index
+                .entry_index_by_path_and_stage_bounded(rela_path, stage, self.num_tracked)
+                .map_or(false, |idx| {
+                    stage != 0 || !self.index.entries()[idx].flags.contains(entry::Flags::REMOVE)
+                })
+        })
+    }
+
+    /// Return `true` if there is a tracked entry below the directory at `rela_path`, which doesn't end with a slash.
+    pub fn has_tracked_files_below(&self, rela_path: &BStr) -> bool {
+        let mut dir = BString::from(rela_path);
+        dir.push_byte(b'/');
+        let entries = &self.index.entries()[..self.num_tracked];
+        let pos = entries.partition_point(|entry| entry.path(self.index) < dir.as_bstr());
+        entries
+            .get(pos)
+            .map_or(false, |entry| entry.path(self.index).starts_with(&dir))
+    }
+
+    /// Return `true` if the directory at `rela_path` contains a repository of its own.
+    pub fn is_repository(&self, rela_path: &BStr) -> bool {
+        self.workdir.join(gix_path::from_bstr(rela_path)).join(".git").exists()
+    }
+
+    /// Return `true` if the file or directory at `rela_path` is excluded.
+    pub fn is_excluded(&mut self, rela_path: &BStr, is_dir: bool) -> Result<bool, Error> {
+        let objects = self.objects;
+        Ok(self
+            .excludes
+            .at_entry(rela_path, Some(is_dir), |id, buf| objects.find_blob(id, buf))
+            .map_err(|source| Error {
+                path: self.workdir.join(gix_path::from_bstr(rela_path)),
+                source,
+            })?
+            .is_excluded())
+    }
+}
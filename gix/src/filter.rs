@@ -0,0 +1,266 @@
+//! lower-level access to filters which are applied to create working tree checkouts or to 'clean' working tree contents for storage in git.
+use std::borrow::Cow;
+
+pub use gix_filter as plumbing;
+use gix_odb::{Find, FindExt};
+
+use crate::{bstr::BStr, config::tree::Core, Repository};
+
+///
+pub mod pipeline {
+    ///
+    pub mod options {
+        use crate::{bstr::BString, config};
+
+        /// The error returned by [Pipeline::options()][crate::filter::Pipeline::options()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            Configuration(#[from] config::key::GenericErrorWithValue),
+            #[error("The filter driver '{name}' was configured with an invalid 'required' value")]
+            DriverRequired {
+                name: BString,
+                source: gix_config::value::Error,
+            },
+        }
+    }
+
+    ///
+    pub mod new {
+        /// The error returned by [Pipeline::new()][crate::filter::Pipeline::new()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            Options(#[from] super::options::Error),
+            #[error(transparent)]
+            Attributes(#[from] crate::attributes::Error),
+        }
+    }
+
+    ///
+    pub mod convert_to_git {
+        /// The error returned by [Pipeline::convert_to_git()][crate::filter::Pipeline::convert_to_git()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs, clippy::large_enum_variant)]
+        pub enum Error {
+            #[error("Failed to prime attributes to the path at which the data resides")]
+            WorktreeCacheAtPath(#[from] std::io::Error),
+            #[error(transparent)]
+            Attributes(#[from] gix_filter::pipeline::attributes::Error),
+            #[error(transparent)]
+            Convert(#[from] gix_filter::pipeline::convert_to_git::Error),
+        }
+    }
+
+    ///
+    pub mod convert_to_worktree {
+        /// The error returned by [Pipeline::convert_to_worktree()][crate::filter::Pipeline::convert_to_worktree()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs, clippy::large_enum_variant)]
+        pub enum Error {
+            #[error("Failed to prime attributes to the path at which the data resides")]
+            WorktreeCacheAtPath(#[from] std::io::Error),
+            #[error(transparent)]
+            Attributes(#[from] gix_filter::pipeline::attributes::Error),
+            #[error(transparent)]
+            Convert(#[from] gix_filter::pipeline::convert_to_worktree::Error),
+        }
+    }
+}
+
+/// A git pipeline for transforming data *to-git* and *to-worktree*, based
+/// [on git configuration and attributes](https://git-scm.com/docs/gitattributes).
+pub struct Pipeline<'repo> {
+    inner: gix_filter::Pipeline,
+    cache: gix_worktree::Cache,
+    attributes: gix_attributes::search::Outcome,
+    repo: &'repo Repository,
+}
+
+/// Lifecycle
+impl<'repo> Pipeline<'repo> {
+    /// Extract options from `repo` that are needed to properly drive a standard git filter pipeline.
+    pub fn options(repo: &'repo Repository) -> Result<gix_filter::pipeline::Options, pipeline::options::Error> {
+        let config = &repo.config.resolved;
+        let auto_crlf = config
+            .string_by_key("core.autocrlf")
+            .map(|value| Core::AUTO_CRLF.try_into_autocrlf(value))
+            .transpose()?
+            .unwrap_or(Some(false));
+        let eol = config
+            .string_by_key("core.eol")
+            .map(|value| Core::EOL.try_into_eol(value))
+            .transpose()?
+            .flatten();
+        let crlf_roundtrip_check = config
+            .string_by_key("core.safecrlf")
+            .map(|value| Core::SAFE_CRLF.try_into_safecrlf(value))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(gix_filter::pipeline::Options {
+            eol_config: gix_filter::eol::Configuration { auto_crlf, eol },
+            crlf_roundtrip_check,
+            object_hash: repo.object_hash(),
+        })
+    }
+
+    /// Return all filter drivers configured in the `filter.<name>` sections of the configuration of `repo`.
+    pub fn drivers(repo: &'repo Repository) -> Result<Vec<gix_filter::Driver>, pipeline::options::Error> {
+        let mut out = Vec::<gix_filter::Driver>::new();
+        let Some(sections) = repo.config.resolved.sections_by_name("filter") else {
+            return Ok(out);
+        };
+        for section in sections {
+            let Some(name) = section.header().subsection_name() else {
+                continue;
+            };
+            let driver = match out.iter().position(|d| d.name == name) {
+                Some(pos) => &mut out[pos],
+                None => {
+                    out.push(gix_filter::Driver {
+                        name: name.to_owned(),
+                        clean: None,
+                        smudge: None,
+                        process: None,
+                        required: false,
+                    });
+                    out.last_mut().expect("just pushed")
+                }
+            };
+            let body = section.body();
+            let value = |key: &str| body.value(key).map(Cow::into_owned);
+            driver.clean = value("clean").or(driver.clean.take());
+            driver.smudge = value("smudge").or(driver.smudge.take());
+            driver.process = value("process").or(driver.process.take());
+            if let Some(required) = body.value("required") {
+                driver.required = gix_config::Boolean::try_from(required.as_ref())
+                    .map_err(|err| pipeline::options::Error::DriverRequired {
+                        name: driver.name.clone(),
+                        source: err,
+                    })?
+                    .0;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Create a new instance by extracting all necessary information and configuration from a `repo` along with `cache` for accessing
+    /// attributes.
+    pub fn new(repo: &'repo Repository, cache: gix_worktree::Cache) -> Result<Self, pipeline::options::Error> {
+        let options = Self::options(repo)?;
+        let drivers = Self::drivers(repo)?;
+        let attributes = cache.selected_attribute_matches(gix_filter::pipeline::ATTRIBUTES.iter().copied());
+        Ok(Pipeline {
+            inner: gix_filter::Pipeline::new(drivers, options),
+            cache,
+            attributes,
+            repo,
+        })
+    }
+
+    /// Detach the repository and obtain the individual functional parts.
+    pub fn into_parts(self) -> (gix_filter::Pipeline, gix_worktree::Cache) {
+        (self.inner, self.cache)
+    }
+}
+
+/// Conversions
+impl<'repo> Pipeline<'repo> {
+    /// Convert the worktree content read from `src` of the file at repository-relative `rela_path` into the content
+    /// that should be stored in `git` and place it into `out`.
+    ///
+    /// `index` is used to find the currently staged version of the file to decide if line-endings may be normalized.
+    pub fn convert_to_git(
+        &mut self,
+        src: impl std::io::Read,
+        rela_path: &BStr,
+        index: &gix_index::State,
+        out: &mut Vec<u8>,
+    ) -> Result<(), pipeline::convert_to_git::Error> {
+        let attributes = self.attributes_at(rela_path)?;
+        let repo = self.repo;
+        self.inner.convert_to_git(
+            src,
+            rela_path,
+            &attributes,
+            |rela_path, buf| match index.entry_by_path_and_stage(rela_path, 0) {
+                Some(entry) => repo.objects.try_find(entry.id, buf).map(|obj| obj.map(|_| ())),
+                None => Ok(None),
+            },
+            out,
+        )?;
+        Ok(())
+    }
+
+    /// Convert `src`, the content of the file at repository-relative `rela_path` as stored in `git`, into the content
+    /// to place into the worktree and place it into `out`.
+    pub fn convert_to_worktree(
+        &mut self,
+        src: &[u8],
+        rela_path: &BStr,
+        out: &mut Vec<u8>,
+    ) -> Result<(), pipeline::convert_to_worktree::Error> {
+        let attributes = self.attributes_at(rela_path)?;
+        self.inner.convert_to_worktree(src, rela_path, &attributes, out)?;
+        Ok(())
+    }
+
+    /// Return the attributes of the pipeline that apply to `rela_path`.
+    fn attributes_at(&mut self, rela_path: &BStr) -> Result<gix_filter::pipeline::Attributes, AttributesAtError> {
+        let objects = &self.repo.objects;
+        let platform = self
+            .cache
+            .at_entry(rela_path, Some(false), |id, buf| objects.find_blob(id, buf))?;
+        platform.matching_attributes(&mut self.attributes);
+        Ok(gix_filter::pipeline::Attributes::from_assignments(
+            self.attributes.iter_selected().map(|m| m.assignment),
+            self.inner.options().eol_config,
+        )?)
+    }
+}
+
+/// Access
+impl<'repo> Pipeline<'repo> {
+    /// Return the underlying [`Repository`].
+    pub fn repo(&self) -> &'repo Repository {
+        self.repo
+    }
+
+    /// Return the attribute and exclude cache used by this pipeline.
+    pub fn cache_mut(&mut self) -> &mut gix_worktree::Cache {
+        &mut self.cache
+    }
+
+    /// Return the lower-level pipeline, for instance to shut down long-running filter processes.
+    pub fn inner_mut(&mut self) -> &mut gix_filter::Pipeline {
+        &mut self.inner
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum AttributesAtError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Attributes(#[from] gix_filter::pipeline::attributes::Error),
+}
+
+impl From<AttributesAtError> for pipeline::convert_to_git::Error {
+    fn from(err: AttributesAtError) -> Self {
+        match err {
+            AttributesAtError::Io(err) => Self::WorktreeCacheAtPath(err),
+            AttributesAtError::Attributes(err) => Self::Attributes(err),
+        }
+    }
+}
+
+impl From<AttributesAtError> for pipeline::convert_to_worktree::Error {
+    fn from(err: AttributesAtError) -> Self {
+        match err {
+            AttributesAtError::Io(err) => Self::WorktreeCacheAtPath(err),
+            AttributesAtError::Attributes(err) => Self::Attributes(err),
+        }
+    }
+}
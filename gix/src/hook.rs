@@ -0,0 +1,274 @@
+//! Find and run hooks, executables in the hooks directory which `git` invokes at certain points of its operation.
+//!
+//! Hooks are looked up in the directory configured with `core.hooksPath`, or in the `hooks` directory of the
+//! [common directory](crate::Repository::common_dir()), and only run if they are executable and if
+//! [permitted](crate::open::Permissions::hooks) in a repository with [full trust](gix_sec::Trust::Full).
+use std::{
+    ffi::OsString,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+};
+
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+use crate::{config::tree::Core, Repository};
+
+/// The arguments to pass to hooks which don't take any.
+pub(crate) const NO_ARGS: [&str; 0] = [];
+
+/// The error returned by [`Repository::run_hook()`] and operations that run hooks.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Running hooks is forbidden")]
+    Forbidden,
+    #[error(transparent)]
+    HooksPath(#[from] gix_config::path::interpolate::Error),
+    #[error("Could not run hook '{name}' at '{}'", path.display())]
+    Spawn {
+        name: String,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Hook '{name}' failed with {status}")]
+    Failed { name: String, status: ExitStatus },
+    #[error("Could not write or read the commit message file at '{}'", path.display())]
+    MessageFile { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error("Could not write the index of the tree to commit")]
+    WriteIndex(#[source] std::io::Error),
+    #[error("Could not obtain the current directory to make paths passed to hooks absolute")]
+    CurrentDir(#[source] std::io::Error),
+}
+
+impl Repository {
+    /// Return the directory in which hooks are located, which is `core.hooksPath` if set, or the `hooks` directory in the
+    /// [common directory](Repository::common_dir()).
+    ///
+    /// Relative paths in `core.hooksPath` are relative to the directory in which hooks run, the work tree or the
+    /// git directory of bare repositories.
+    pub fn hooks_dir(&self) -> Result<PathBuf, Error> {
+        let hooks_path = self
+            .config
+            .trusted_file_path("core", None, Core::HOOKS_PATH.name)
+            .map(|res| res.map(std::borrow::Cow::into_owned));
+        Ok(match self.config.apply_leniency(hooks_path)? {
+            Some(path) if path.is_relative() => self.hook_cwd().join(path),
+            Some(path) => path,
+            None => self.common_dir().join("hooks"),
+        })
+    }
+
+    /// Return the path to the executable hook named `name`, like `pre-commit`, or `None` if it doesn't exist or
+    /// isn't executable.
+    pub fn hook_path(&self, name: &str) -> Result<Option<PathBuf>, Error> {
+        let path = self.hooks_dir()?.join(name);
+        Ok(std::fs::metadata(&path)
+            .ok()
+            .filter(|md| md.is_file() && (cfg!(not(unix)) || gix_fs::is_executable(md)))
+            .map(|_| path))
+    }
+
+    /// Run the hook named `name` with `args` and write `stdin` to its standard input, returning its exit status,
+    /// or `None` if there was no such hook, if running hooks is [denied](crate::open::Permissions::hooks) or if
+    /// the repository isn't [fully trusted](gix_sec::Trust::Full).
+    ///
+    /// Hooks run in the work tree, or in the git directory of bare repositories, with `GIT_DIR` and `GIT_INDEX_FILE`
+    /// set to absolute paths accordingly. Like in `git`, their standard output is redirected to the standard error of
+    /// this process, which they write to as well.
+    pub fn run_hook(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = impl Into<OsString>>,
+        stdin: Option<&[u8]>,
+    ) -> Result<Option<ExitStatus>, Error> {
+        self.run_hook_with_index(name, args, stdin, &self.index_path())
+    }
+
+    /// Like [`run_hook()`](Self::run_hook()), but set `GIT_INDEX_FILE` to `index_path` in repositories with a worktree.
+    fn run_hook_with_index(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = impl Into<OsString>>,
+        stdin: Option<&[u8]>,
+        index_path: &Path,
+    ) -> Result<Option<ExitStatus>, Error> {
+        if !self.hooks_allowed()? {
+            return Ok(None);
+        }
+        let Some(path) = self.hook_path(name)? else {
+            return Ok(None);
+        };
+        let spawn_error = |source| Error::Spawn {
+            name: name.into(),
+            path: path.clone(),
+            source,
+        };
+        let cwd = std::env::current_dir().map_err(Error::CurrentDir)?;
+        let mut cmd = gix_command::prepare(path.as_os_str())
+            .args(args)
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(stderr_as_stdio().map_err(spawn_error)?)
+            .stderr(Stdio::inherit())
+            .env("GIT_DIR", cwd.join(self.git_dir()));
+        if self.work_dir().is_some() {
+            cmd = cmd.env("GIT_INDEX_FILE", cwd.join(index_path));
+        }
+        let mut child = std::process::Command::from(cmd)
+            .current_dir(self.hook_cwd())
+            .spawn()
+            .map_err(spawn_error)?;
+        if let Some((input, data)) = child.stdin.take().zip(stdin) {
+            let mut input = input;
+            match input.write_all(data) {
+                Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => return Err(spawn_error(err)),
+                _ => {}
+            }
+        }
+        child.wait().map(Some).map_err(spawn_error)
+    }
+
+    /// Like [`run_hook()`](Self::run_hook()), but fail if the hook exits with a non-zero status.
+    pub(crate) fn run_hook_checked(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = impl Into<OsString>>,
+        stdin: Option<&[u8]>,
+    ) -> Result<(), Error> {
+        check_status(name, self.run_hook(name, args, stdin)?)
+    }
+
+    /// Run the `pre-commit` hook with an index created from `tree` and fail if it rejects the commit.
+    pub(crate) fn run_pre_commit_hook(&self, tree: gix_hash::ObjectId) -> Result<(), Error> {
+        const NAME: &str = "pre-commit";
+        if !self.hooks_allowed()? || self.hook_path(NAME)?.is_none() {
+            return Ok(());
+        }
+        let index = if tree.is_empty_tree() {
+            gix_index::File::from_state(gix_index::State::new(self.object_hash()), self.index_path())
+        } else {
+            self.index_from_tree(&tree)?
+        };
+        let mut file = gix_tempfile::new(
+            self.git_dir(),
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )
+        .map_err(Error::WriteIndex)?;
+        index
+            .write_to(&mut file, Default::default())
+            .and_then(|_| file.flush())
+            .map_err(Error::WriteIndex)?;
+        let index_path = file
+            .with_mut(|file| file.path().to_owned())
+            .map_err(Error::WriteIndex)?;
+        check_status(NAME, self.run_hook_with_index(NAME, NO_ARGS, None, &index_path)?)
+    }
+
+    /// Run the `reference-transaction` hook with `state` for all `edits`, unless there is no such hook.
+    pub(crate) fn run_reference_transaction_hook<'a>(
+        &self,
+        state: &str,
+        edits: impl IntoIterator<Item = &'a RefEdit>,
+    ) -> Result<(), Error> {
+        const NAME: &str = "reference-transaction";
+        if !self.hooks_allowed()? || self.hook_path(NAME)?.is_none() {
+            return Ok(());
+        }
+        let null = self.object_hash().null();
+        let mut stdin = Vec::new();
+        for edit in edits {
+            let (previous, new) = match &edit.change {
+                Change::Update {
+                    log: LogChange { mode: RefLog::Only, .. },
+                    ..
+                } => continue,
+                Change::Update { expected, new, .. } => match new.try_id() {
+                    Some(new) => (expected, new.to_owned()),
+                    None => continue,
+                },
+                Change::Delete { expected, log, .. } => {
+                    if *log == RefLog::Only {
+                        continue;
+                    }
+                    (expected, null)
+                }
+            };
+            // Once prepared, the previous value is the one on disk if the reference exists.
+            let previous = match previous {
+                PreviousValue::MustExistAndMatch(previous) => previous.try_id().map_or(null, ToOwned::to_owned),
+                _ => null,
+            };
+            stdin.extend_from_slice(format!("{previous} {new} {}\n", edit.name.as_bstr()).as_bytes());
+        }
+        if stdin.is_empty() {
+            return Ok(());
+        }
+        self.run_hook_checked(NAME, [state], Some(&stdin))
+    }
+
+    /// Run the `prepare-commit-msg` and `commit-msg` hooks on `message`, returning the message as possibly changed
+    /// by them, or `None` if there are no such hooks.
+    pub(crate) fn run_commit_message_hooks(&self, message: &str) -> Result<Option<String>, Error> {
+        if !self.hooks_allowed()?
+            || (self.hook_path("prepare-commit-msg")?.is_none() && self.hook_path("commit-msg")?.is_none())
+        {
+            return Ok(None);
+        }
+        let path = self.git_dir().join("COMMIT_EDITMSG");
+        let io_error = |source| Error::MessageFile {
+            path: path.clone(),
+            source,
+        };
+        std::fs::write(&path, message).map_err(io_error)?;
+        self.run_hook_checked("prepare-commit-msg", [path.as_os_str(), "message".as_ref()], None)?;
+        self.run_hook_checked("commit-msg", [path.as_os_str()], None)?;
+        let message = std::fs::read(&path).map_err(io_error)?;
+        Ok(Some(String::from_utf8_lossy(&message).into_owned()))
+    }
+
+    fn hooks_allowed(&self) -> Result<bool, Error> {
+        let allowed = self
+            .options
+            .permissions
+            .hooks
+            .check(())
+            .map_err(|_| Error::Forbidden)?
+            .is_some();
+        Ok(allowed && self.git_dir_trust() == gix_sec::Trust::Full)
+    }
+
+    fn hook_cwd(&self) -> &std::path::Path {
+        self.work_dir().unwrap_or(self.git_dir())
+    }
+}
+
+fn check_status(name: &str, status: Option<ExitStatus>) -> Result<(), Error> {
+    match status {
+        Some(status) if !status.success() => Err(Error::Failed {
+            name: name.into(),
+            status,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Return a handle to our standard error for use as standard output of a hook, as `git` does.
+fn stderr_as_stdio() -> std::io::Result<Stdio> {
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsFd;
+        Ok(std::io::stderr().as_fd().try_clone_to_owned()?.into())
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsHandle;
+        Ok(std::io::stderr().as_handle().try_clone_to_owned()?.into())
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        Ok(Stdio::inherit())
+    }
+}
@@ -85,6 +85,7 @@ pub use gix_date as date;
 pub use gix_features as features;
 use gix_features::threading::OwnShared;
 pub use gix_features::{parallel, progress::Progress, threading};
+pub use gix_fetchhead as fetchhead;
 pub use gix_fs as fs;
 pub use gix_glob as glob;
 pub use gix_hash as hash;
@@ -103,6 +104,7 @@ pub use gix_protocol as protocol;
 pub use gix_ref as refs;
 pub use gix_refspec as refspec;
 pub use gix_sec as sec;
+pub use gix_sequencer as sequencer;
 pub use gix_tempfile as tempfile;
 pub use gix_trace as trace;
 pub use gix_traverse as traverse;
@@ -114,6 +116,7 @@ pub use hash::{oid, ObjectId};
 
 pub mod interrupt;
 
+pub mod add;
 ///
 pub mod attributes;
 
@@ -153,6 +156,28 @@ pub mod progress;
 ///
 pub mod diff;
 
+mod dirwalk;
+
+pub mod branch;
+
+pub mod bundle;
+
+pub mod filter;
+
+pub mod hook;
+
+pub mod merge;
+
+pub mod pathspec;
+
+pub mod rebase;
+
+pub mod signing;
+
+pub mod stash;
+
+pub mod status;
+
 /// See [`ThreadSafeRepository::discover()`], but returns a [`Repository`] instead.
 ///
 /// # Note
@@ -0,0 +1,430 @@
+//! Merge blobs while respecting the `merge` and `conflict-marker-size` attributes, along with merge drivers
+//! configured in `merge.<driver>`.
+use std::{borrow::Cow, io::Write, path::PathBuf, process::Stdio};
+
+use gix_diff::blob::merge::{self, Favor, Labels, Resolution};
+use gix_odb::FindExt;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::tree::Merge,
+    Repository,
+};
+
+/// The names of the attributes that affect how blobs are merged.
+pub const ATTRIBUTES: &[&str] = &["merge", "conflict-marker-size"];
+
+/// A merge driver as configured in a `merge.<driver>` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Driver {
+    /// The name of the driver, as referred to by the `merge` attribute.
+    pub name: BString,
+    /// The human-readable name of the driver from `merge.<driver>.name`.
+    pub display_name: Option<BString>,
+    /// The command to run from `merge.<driver>.driver`, with `%O`, `%A`, `%B`, `%L` and `%P` substituted
+    /// with the paths to the files of the merge base, ours and theirs, the conflict marker size and the path of
+    /// the merged file respectively.
+    ///
+    /// The command writes the merged result to the file at `%A` and exits with a non-zero status if there are conflicts.
+    pub command: Option<BString>,
+    /// The name of the driver to use for merging merge bases, from `merge.<driver>.recursive`.
+    pub recursive: Option<BString>,
+}
+
+/// Options for merging blobs, typically obtained from the configuration of a repository.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Options for the built-in text merge.
+    pub text: merge::Options,
+    /// The name of the driver to use for paths that don't have the `merge` attribute, from `merge.default`.
+    pub default_driver: Option<BString>,
+    /// All merge drivers configured in `merge.<driver>` sections.
+    pub drivers: Vec<Driver>,
+}
+
+///
+pub mod options {
+    use crate::config;
+
+    /// The error returned by [Platform::options()][crate::merge::blob::Platform::options()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConflictStyle(#[from] config::key::GenericErrorWithValue),
+    }
+}
+
+///
+pub mod new {
+    /// The error returned by [Platform::new()][crate::merge::blob::Platform::new()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Options(#[from] super::options::Error),
+        #[error(transparent)]
+        Attributes(#[from] crate::attributes::Error),
+    }
+}
+
+///
+pub mod merge_blobs {
+    use crate::bstr::BString;
+
+    /// The error returned by [Platform::merge()][crate::merge::blob::Platform::merge()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to obtain the attributes of the merged path")]
+        Attributes(#[source] std::io::Error),
+        #[error("Could not write the temporary files for the merge driver '{name}'")]
+        TemporaryFile { name: BString, source: std::io::Error },
+        #[error("Could not run the merge driver '{name}' with command '{command}'")]
+        SpawnDriver {
+            name: BString,
+            command: BString,
+            source: std::io::Error,
+        },
+        #[error("Could not read the result of the merge driver '{name}'")]
+        ReadResult { name: BString, source: std::io::Error },
+    }
+}
+
+/// The way a blob was merged.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Method {
+    /// The built-in line-based text merge was used.
+    Text,
+    /// The content was considered binary and one side was picked.
+    Binary,
+    /// The merge driver at the given index into [`Options::drivers`] was used.
+    Driver(usize),
+}
+
+/// The result of [`Platform::merge()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// Whether or not the merged result contains conflicts.
+    pub resolution: Resolution,
+    /// How the blobs were merged.
+    pub method: Method,
+}
+
+/// A utility to merge blobs at a path with all merge drivers and attributes taken into account.
+pub struct Platform<'repo> {
+    cache: gix_worktree::Cache,
+    attributes: gix_attributes::search::Outcome,
+    repo: &'repo Repository,
+    /// The options used for merging, which may be adjusted for each merge.
+    pub options: Options,
+}
+
+/// Lifecycle
+impl<'repo> Platform<'repo> {
+    /// Extract the options for merging blobs from the configuration of `repo`.
+    ///
+    /// Note that the histogram diff algorithm is used, just like `git merge` does.
+    pub fn options(repo: &'repo Repository) -> Result<Options, options::Error> {
+        let config = &repo.config.resolved;
+        let conflict_style = config
+            .string_by_key("merge.conflictStyle")
+            .map(|value| Merge::CONFLICT_STYLE.try_into_conflict_style(value))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Options {
+            text: merge::Options {
+                algorithm: gix_diff::blob::Algorithm::Histogram,
+                conflict_style,
+                ..Default::default()
+            },
+            default_driver: config.string_by_key("merge.default").map(Cow::into_owned),
+            drivers: Self::drivers(repo),
+        })
+    }
+
+    /// Return all merge drivers configured in the `merge.<driver>` sections of the configuration of `repo`.
+    pub fn drivers(repo: &'repo Repository) -> Vec<Driver> {
+        let mut out = Vec::<Driver>::new();
+        let Some(sections) = repo.config.resolved.sections_by_name("merge") else {
+            return out;
+        };
+        for section in sections {
+            let Some(name) = section.header().subsection_name() else {
+                continue;
+            };
+            let driver = match out.iter().position(|d| d.name == name) {
+                Some(pos) => &mut out[pos],
+                None => {
+                    out.push(Driver {
+                        name: name.to_owned(),
+                        display_name: None,
+                        command: None,
+                        recursive: None,
+                    });
+                    out.last_mut().expect("just pushed")
+                }
+            };
+            let body = section.body();
+            let value = |key: &str| body.value(key).map(Cow::into_owned);
+            driver.display_name = value("name").or(driver.display_name.take());
+            driver.command = value("driver").or(driver.command.take());
+            driver.recursive = value("recursive").or(driver.recursive.take());
+        }
+        out
+    }
+
+    /// Create a new instance with all options extracted from `repo` along with `cache` for accessing attributes.
+    pub fn new(repo: &'repo Repository, cache: gix_worktree::Cache) -> Result<Self, options::Error> {
+        let options = Self::options(repo)?;
+        let attributes = cache.selected_attribute_matches(ATTRIBUTES.iter().copied());
+        Ok(Platform {
+            cache,
+            attributes,
+            repo,
+            options,
+        })
+    }
+
+    /// Detach the repository and obtain the attribute cache.
+    pub fn into_cache(self) -> gix_worktree::Cache {
+        self.cache
+    }
+}
+
+/// The driver to use for a merge, as determined by attributes.
+enum Choice<'a> {
+    Text { favor: Option<Favor> },
+    Binary,
+    Driver { index: usize, command: &'a BStr },
+}
+
+/// Merging
+impl<'repo> Platform<'repo> {
+    /// Merge `ours` and `theirs`, the versions of the file at repository-relative `rela_path` on each side, with `base` as
+    /// their common ancestor and write the result to `out`. `labels` are written next to conflict markers.
+    ///
+    /// The `merge` attribute of `rela_path` selects the merge driver, and `conflict-marker-size` the length of conflict markers.
+    /// Text merges turn into binary merges if one of the inputs [is binary](merge::is_binary()), in which case our side is
+    /// written to `out`, unless [`favor`](merge::Options::favor) is set to their side.
+    pub fn merge(
+        &mut self,
+        rela_path: &BStr,
+        base: &[u8],
+        ours: &[u8],
+        theirs: &[u8],
+        labels: Labels<'_>,
+        out: &mut Vec<u8>,
+    ) -> Result<Outcome, merge_blobs::Error> {
+        let objects = &self.repo.objects;
+        let platform = self
+            .cache
+            .at_entry(rela_path, Some(false), |id, buf| objects.find_blob(id, buf))
+            .map_err(merge_blobs::Error::Attributes)?;
+        platform.matching_attributes(&mut self.attributes);
+
+        let mut text_options = self.options.text;
+        let mut merge_attribute = None;
+        for assignment in self.attributes.iter_selected().map(|m| m.assignment) {
+            match assignment.name.as_str() {
+                "merge" => merge_attribute = Some(assignment.state),
+                "conflict-marker-size" => {
+                    if let gix_attributes::StateRef::Value(value) = assignment.state {
+                        if let Some(size) = value.as_bstr().to_str().ok().and_then(|v| v.parse::<usize>().ok()) {
+                            if size > 0 {
+                                text_options.marker_size = size;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let driver_name = match &merge_attribute {
+            Some(gix_attributes::StateRef::Set) => None,
+            Some(gix_attributes::StateRef::Unset) => Some("binary".as_bytes()),
+            Some(gix_attributes::StateRef::Value(name)) => Some(name.as_bstr().as_bytes()),
+            Some(gix_attributes::StateRef::Unspecified) | None => {
+                self.options.default_driver.as_ref().map(|name| name.as_bytes())
+            }
+        };
+        let choice = match driver_name {
+            None | Some(b"text") => Choice::Text {
+                favor: text_options.favor,
+            },
+            Some(b"binary") => Choice::Binary,
+            Some(b"union") => Choice::Text {
+                favor: Some(Favor::Union),
+            },
+            Some(name) => self
+                .options
+                .drivers
+                .iter()
+                .enumerate()
+                .find_map(|(index, driver)| {
+                    (driver.name == name).then_some(driver.command.as_ref().map(|command| Choice::Driver {
+                        index,
+                        command: command.as_ref(),
+                    }))
+                })
+                .flatten()
+                .unwrap_or(Choice::Text {
+                    favor: text_options.favor,
+                }),
+        };
+
+        Ok(match choice {
+            Choice::Text { favor } => {
+                if [base, ours, theirs].iter().any(|data| merge::is_binary(data)) {
+                    binary(ours, theirs, text_options.favor, out)
+                } else {
+                    text_options.favor = favor;
+                    let outcome = merge::text(out, base, ours, theirs, labels, text_options);
+                    Outcome {
+                        resolution: outcome.resolution,
+                        method: Method::Text,
+                    }
+                }
+            }
+            Choice::Binary => binary(ours, theirs, text_options.favor, out),
+            Choice::Driver { index, command } => {
+                let name = self.options.drivers[index].name.as_ref();
+                let resolution = run_driver(
+                    name,
+                    command,
+                    rela_path,
+                    base,
+                    ours,
+                    theirs,
+                    text_options.marker_size,
+                    out,
+                )?;
+                Outcome {
+                    resolution,
+                    method: Method::Driver(index),
+                }
+            }
+        })
+    }
+}
+
+/// Access
+impl<'repo> Platform<'repo> {
+    /// Return the underlying [`Repository`].
+    pub fn repo(&self) -> &'repo Repository {
+        self.repo
+    }
+
+    /// Return the attribute cache used by this platform.
+    pub fn cache_mut(&mut self) -> &mut gix_worktree::Cache {
+        &mut self.cache
+    }
+}
+
+fn binary(ours: &[u8], theirs: &[u8], favor: Option<Favor>, out: &mut Vec<u8>) -> Outcome {
+    let (pick, resolution) = merge::binary(favor);
+    out.clear();
+    out.extend_from_slice(match pick {
+        merge::Pick::Ours => ours,
+        merge::Pick::Theirs => theirs,
+    });
+    Outcome {
+        resolution,
+        method: Method::Binary,
+    }
+}
+
+/// Run the driver `name` with `command`, substituting all placeholders, and place the result into `out`.
+#[allow(clippy::too_many_arguments)]
+fn run_driver(
+    name: &BStr,
+    command: &BStr,
+    rela_path: &BStr,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    marker_size: usize,
+    out: &mut Vec<u8>,
+) -> Result<Resolution, merge_blobs::Error> {
+    let temporary_file = |data: &[u8]| {
+        temporary_file(data).map_err(|source| merge_blobs::Error::TemporaryFile {
+            name: name.to_owned(),
+            source,
+        })
+    };
+    let (_base_file, base_path) = temporary_file(base)?;
+    let (_ours_file, ours_path) = temporary_file(ours)?;
+    let (_theirs_file, theirs_path) = temporary_file(theirs)?;
+
+    let mut cmd = BString::default();
+    let mut rest = command.as_bytes();
+    while let Some(pos) = rest.find_byte(b'%') {
+        cmd.extend_from_slice(&rest[..pos]);
+        let path = |path: &PathBuf| gix_path::into_bstr(path.as_path()).into_owned();
+        match rest.get(pos + 1) {
+            Some(b'O') => cmd.extend_from_slice(&path(&base_path)),
+            Some(b'A') => cmd.extend_from_slice(&path(&ours_path)),
+            Some(b'B') => cmd.extend_from_slice(&path(&theirs_path)),
+            Some(b'L') => cmd.extend_from_slice(marker_size.to_string().as_bytes()),
+            Some(b'P') => {
+                cmd.push(b'\'');
+                for byte in rela_path.iter() {
+                    if *byte == b'\'' {
+                        cmd.extend_from_slice(b"'\\''");
+                    } else {
+                        cmd.push(*byte);
+                    }
+                }
+                cmd.push(b'\'');
+            }
+            Some(b'%') => cmd.push(b'%'),
+            Some(other) => cmd.extend_from_slice(&[b'%', *other]),
+            None => {
+                cmd.push(b'%');
+                rest = &[];
+                break;
+            }
+        }
+        rest = &rest[pos + 2..];
+    }
+    cmd.extend_from_slice(rest);
+
+    let status = gix_command::prepare(gix_path::from_bstr(cmd.as_bstr()).into_owned())
+        .with_shell()
+        .stdin(Stdio::null())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .and_then(|mut child| child.wait())
+        .map_err(|source| merge_blobs::Error::SpawnDriver {
+            name: name.to_owned(),
+            command: cmd.clone(),
+            source,
+        })?;
+    out.clear();
+    out.extend_from_slice(
+        &std::fs::read(&ours_path).map_err(|source| merge_blobs::Error::ReadResult {
+            name: name.to_owned(),
+            source,
+        })?,
+    );
+    Ok(if status.success() {
+        Resolution::Complete
+    } else {
+        Resolution::Conflict
+    })
+}
+
+/// Write `data` into a temporary file that is removed when the returned handle is dropped, along with its path.
+fn temporary_file(data: &[u8]) -> std::io::Result<(gix_tempfile::Handle<gix_tempfile::handle::Writable>, PathBuf)> {
+    let mut file = gix_tempfile::new(
+        std::env::temp_dir(),
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    )?;
+    file.write_all(data)?;
+    file.flush()?;
+    let path = file.with_mut(|file| file.path().to_owned())?;
+    Ok((file, path))
+}
@@ -0,0 +1,302 @@
+//! Apply the changes of a commit onto another commit, or undo them, similar to `git cherry-pick` and `git revert`.
+//!
+//! Both operations merge trees in memory and create a new commit without touching a worktree or the index, which is why they
+//! also work in bare repositories.
+use gix_diff::blob::merge::Labels;
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    merge::tree,
+    Id, Repository,
+};
+
+/// Options for use in [`Repository::cherry_pick()`] and [`Repository::revert()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The options to use when merging trees.
+    pub merge: tree::Options,
+    /// The 1-based number of the parent of a merge commit to consider its mainline, relative to which the changes
+    /// of the merge commit are applied, similar to `git cherry-pick --mainline`.
+    ///
+    /// It must be set for merge commits and must not be set for any other commit.
+    pub mainline: Option<usize>,
+    /// If `true`, a `(cherry picked from commit …)` line is appended to the message of cherry-picked commits,
+    /// similar to `git cherry-pick -x`.
+    pub record_origin: bool,
+    /// If `true`, create a commit even if it doesn't change the tree of the commit it is placed onto.
+    pub allow_empty: bool,
+}
+
+/// The result of [`Repository::cherry_pick()`] and [`Repository::revert()`].
+#[derive(Debug, Clone)]
+pub struct Outcome<'repo> {
+    /// The newly created commit, or `None` if there were conflicts.
+    pub commit: Option<Id<'repo>>,
+    /// The outcome of the tree merge, with all conflicts.
+    pub merge: tree::Outcome<'repo>,
+    /// The message of the new commit, for use when creating it after the conflicts were resolved.
+    pub message: BString,
+    /// The encoding of `message` if it isn't UTF-8, for use when creating the commit after the conflicts were resolved.
+    pub encoding: Option<BString>,
+    /// The author of the new commit, for use when creating it after the conflicts were resolved.
+    pub author: gix_actor::Signature,
+}
+
+/// The error returned by [`Repository::cherry_pick()`] and [`Repository::revert()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    IntoCommit(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    MergeTrees(#[from] tree::Error),
+    #[error(transparent)]
+    Commit(#[from] Box<crate::commit::Error>),
+    #[error("The author is not configured")]
+    AuthorMissing,
+    #[error("The committer is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    IdentityTime(#[from] crate::config::time::Error),
+    #[error("Commit {id} is a merge commit but no mainline was specified")]
+    MainlineMissing { id: ObjectId },
+    #[error("Commit {id} has no parent number {mainline} to use as mainline")]
+    MainlineInvalid { id: ObjectId, mainline: usize },
+    #[error("The changes of commit {id} are already present, and empty commits are not allowed")]
+    Empty { id: ObjectId },
+}
+
+impl Repository {
+    /// Return the options for [cherry-picking](Repository::cherry_pick()) and [reverting](Repository::revert()) commits
+    /// as configured in this repository.
+    ///
+    /// The origin of cherry-picked commits isn't recorded, just like `git cherry-pick` doesn't unless `-x` is given.
+    pub fn commit_merge_options(&self) -> Result<Options, tree::options::Error> {
+        Ok(Options {
+            merge: self.tree_merge_options()?,
+            mainline: None,
+            record_origin: false,
+            allow_empty: false,
+        })
+    }
+
+    /// Apply the changes that `commit` introduced relative to its parent to `onto` and create a new commit with `onto` as
+    /// its only parent, similar to `git cherry-pick`.
+    ///
+    /// The author and message of `commit` are retained, and no reference is changed.
+    /// If there are conflicts, no commit is created and the [outcome](Outcome) provides everything needed to create it once
+    /// the conflicts are resolved.
+    pub fn cherry_pick(
+        &self,
+        commit: impl Into<ObjectId>,
+        onto: impl Into<ObjectId>,
+        options: Options,
+    ) -> Result<Outcome<'_>, Error> {
+        let commit = Info::new::<Error>(self, commit.into())?;
+        let parent = commit.parent(self, options.mainline)?;
+
+        let mut message = commit.message.clone();
+        if options.record_origin {
+            append_origin(&mut message, commit.id);
+        }
+        let ancestor_label = format!("parent of {}", commit.label());
+        let other_label = commit.label();
+        let labels = Labels {
+            ancestor: Some(ancestor_label.as_str().into()),
+            current: Some("HEAD".into()),
+            other: Some(other_label.as_ref()),
+        };
+        self.merge_commit(
+            commit.id,
+            parent,
+            onto.into(),
+            commit.id,
+            labels,
+            message,
+            commit.encoding,
+            commit.author,
+            options,
+        )
+    }
+
+    /// Undo the changes that `commit` introduced relative to its parent on top of `onto` and create a new commit with `onto`
+    /// as its only parent, similar to `git revert`.
+    ///
+    /// The message says which commit was reverted, and the author is the one [configured](Repository::author()) in this repository. No reference is changed.
+    /// If there are conflicts, no commit is created and the [outcome](Outcome) provides everything needed to create it once
+    /// the conflicts are resolved.
+    pub fn revert(
+        &self,
+        commit: impl Into<ObjectId>,
+        onto: impl Into<ObjectId>,
+        options: Options,
+    ) -> Result<Outcome<'_>, Error> {
+        let commit = Info::new::<Error>(self, commit.into())?;
+        let parent = commit.parent(self, options.mainline)?;
+
+        let mut message = BString::from("Revert \"");
+        message.push_str(commit.subject());
+        message.push_str(format!("\"\n\nThis reverts commit {}", commit.id));
+        if options.mainline.is_some() {
+            message.push_str(format!(", reversing\nchanges made to {parent}"));
+        }
+        message.push_str(".\n");
+        let author = self.author().ok_or(Error::AuthorMissing)??.to_owned();
+        let ancestor_label = commit.label();
+        let other_label = format!("parent of {}", commit.label());
+        let labels = Labels {
+            ancestor: Some(ancestor_label.as_ref()),
+            current: Some("HEAD".into()),
+            other: Some(other_label.as_str().into()),
+        };
+        self.merge_commit(
+            commit.id,
+            commit.id,
+            onto.into(),
+            parent,
+            labels,
+            message,
+            commit.encoding,
+            author,
+            options,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn merge_commit(
+        &self,
+        id: ObjectId,
+        base: ObjectId,
+        onto: ObjectId,
+        theirs: ObjectId,
+        labels: Labels<'_>,
+        message: BString,
+        encoding: Option<BString>,
+        author: gix_actor::Signature,
+        options: Options,
+    ) -> Result<Outcome<'_>, Error> {
+        let merge = self.merge_trees(base, onto, theirs, labels, options.merge)?;
+        if merge.has_conflicts() {
+            return Ok(Outcome {
+                commit: None,
+                merge,
+                message,
+                encoding,
+                author,
+            });
+        }
+        if !options.allow_empty && merge.tree == self.find_object(onto)?.peel_to_tree()?.id {
+            return Err(Error::Empty { id });
+        }
+        let committer = self.committer().ok_or(Error::CommitterMissing)??.to_owned();
+        let (commit, _) = self
+            .write_commit_object(gix_object::Commit {
+                tree: merge.tree.detach(),
+                parents: std::iter::once(onto).collect(),
+                author: author.clone(),
+                committer,
+                encoding: encoding.clone(),
+                message: message.clone(),
+                extra_headers: Vec::new(),
+            })
+            .map_err(Box::new)?;
+        Ok(Outcome {
+            commit: Some(commit),
+            merge,
+            message,
+            encoding,
+            author,
+        })
+    }
+}
+
+/// Append `(cherry picked from commit <id>)` to `message`, in the same paragraph as other trailers if there are any.
+fn append_origin(message: &mut BString, id: ObjectId) {
+    if !message.ends_with(b"\n") {
+        message.push(b'\n');
+    }
+    if !has_trailers(message.as_ref()) {
+        message.push(b'\n');
+    }
+    message.push_str(format!("(cherry picked from commit {id})\n"));
+}
+
+/// Return `true` if the last paragraph of `message`, which isn't the subject, only consists of trailers.
+fn has_trailers(message: &BStr) -> bool {
+    let paragraphs: Vec<_> = message
+        .trim_end()
+        .split_str("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .collect();
+    match paragraphs.as_slice() {
+        [_subject, .., last] => last.lines().all(|line| {
+            line.starts_with(b"(cherry picked from commit ")
+                || line.find_byte(b':').map_or(false, |pos| {
+                    pos > 0
+                        && line[..pos].iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+                        && line.get(pos + 1) == Some(&b' ')
+                })
+        }),
+        _ => false,
+    }
+}
+
+/// The information about a commit we need to apply or undo its changes, or to recreate it elsewhere.
+pub(crate) struct Info {
+    pub(crate) id: ObjectId,
+    pub(crate) tree: ObjectId,
+    pub(crate) parents: Vec<ObjectId>,
+    pub(crate) message: BString,
+    /// The encoding of `message` if it isn't UTF-8.
+    pub(crate) encoding: Option<BString>,
+    pub(crate) author: gix_actor::Signature,
+}
+
+impl Info {
+    pub(crate) fn new<E>(repo: &Repository, id: ObjectId) -> Result<Self, E>
+    where
+        E: From<crate::object::find::existing::Error>
+            + From<crate::object::try_into::Error>
+            + From<gix_object::decode::Error>,
+    {
+        let commit = repo.find_object(id)?.try_into_commit()?;
+        let decoded = commit.decode()?;
+        Ok(Info {
+            id,
+            tree: decoded.tree(),
+            parents: decoded.parents().collect(),
+            message: decoded.message.to_owned(),
+            encoding: decoded.encoding.map(ToOwned::to_owned),
+            author: decoded.author.to_owned(),
+        })
+    }
+
+    /// Return the parent to compare this commit to, or the empty tree if it is a root commit.
+    fn parent(&self, repo: &Repository, mainline: Option<usize>) -> Result<ObjectId, Error> {
+        match (self.parents.len(), mainline) {
+            (0, None) => Ok(ObjectId::empty_tree(repo.object_hash())),
+            (1, None) => Ok(self.parents[0]),
+            (_, None) => Err(Error::MainlineMissing { id: self.id }),
+            (num_parents, Some(mainline)) if num_parents > 1 && mainline > 0 && mainline <= num_parents => {
+                Ok(self.parents[mainline - 1])
+            }
+            (_, Some(mainline)) => Err(Error::MainlineInvalid { id: self.id, mainline }),
+        }
+    }
+
+    pub(crate) fn subject(&self) -> &BStr {
+        self.message.lines().next().unwrap_or_default().as_bstr()
+    }
+
+    /// Return a label like `abcdef1 (subject)` for use in conflict markers.
+    pub(crate) fn label(&self) -> BString {
+        format!("{} ({})", self.id.to_hex_with_len(7), self.subject()).into()
+    }
+}
@@ -0,0 +1,11 @@
+//! Merge blobs and trees, similar to `git merge-file` and `git merge-tree`, and apply or undo the changes of commits.
+//!
+//! The lower-level implementation of text merges is available in [`gix_diff::blob::merge`].
+
+///
+pub mod blob;
+
+pub mod commit;
+
+///
+pub mod tree;
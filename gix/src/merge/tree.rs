@@ -0,0 +1,569 @@
+//! Merge trees against their merge base, similar to `git merge-tree --write-tree`.
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use gix_diff::blob::merge::{Labels, Resolution};
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+use gix_odb::FindExt;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    merge::blob,
+    object::tree::diff::{change::Event, Action, Rewrites},
+    Id, Repository,
+};
+
+/// Options for use in [`Repository::merge_trees()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// If `Some(…)`, renames are tracked on both sides. Copies are never tracked.
+    pub rewrites: Option<Rewrites>,
+    /// Options for merging the content of files that were changed on both sides.
+    pub blob: blob::Options,
+}
+
+///
+pub mod options {
+    /// The error returned by [Repository::tree_merge_options()][crate::Repository::tree_merge_options()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Blob(#[from] crate::merge::blob::options::Error),
+        #[error(transparent)]
+        Renames(#[from] crate::config::key::GenericError),
+        #[error(transparent)]
+        RenameLimit(#[from] crate::config::unsigned_integer::Error),
+    }
+}
+
+/// The error returned by [Repository::merge_trees()][crate::Repository::merge_trees()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindExistingObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    DiffRewrites(#[from] Box<crate::object::tree::diff::rewrites::Error>),
+    #[error(transparent)]
+    Diff(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    BlobMergePlatform(#[from] blob::new::Error),
+    #[error(transparent)]
+    MergeBlobs(#[from] blob::merge_blobs::Error),
+    #[error(transparent)]
+    WriteBlob(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    WriteTree(#[from] crate::repository::write_tree_from_index::Error),
+    #[error("The changed path '{path}' isn't a file in the merge base, as directories replaced by files can't be merged yet")]
+    MissingBaseEntry { path: BString },
+}
+
+/// A non-tree entry in a tree.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The kind of entry.
+    pub mode: EntryMode,
+    /// The id of the object the entry points to.
+    pub id: ObjectId,
+}
+
+/// One of the two sides of a merge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// Our side, the tree that is merged into.
+    Ours,
+    /// Their side, the tree that is merged.
+    Theirs,
+}
+
+/// A conflict encountered while merging trees.
+///
+/// Note that the tree written by [`Repository::merge_trees()`] contains one version of each conflicting path, which may contain
+/// conflict markers, so it can be checked out and resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// Both sides changed the file at `path` in different ways, or added different files at the same path, and the changes
+    /// couldn't be merged without conflict.
+    ///
+    /// This also happens if their kinds differ, for instance when one side turns a file into a symbolic link, or if both sides
+    /// changed the executable bit differently.
+    Content {
+        /// The path at which the merged file was placed.
+        path: BString,
+        /// The merge base version of the file, or `None` if both sides added it.
+        base: Option<Entry>,
+        /// Our version of the file.
+        ours: Entry,
+        /// Their version of the file.
+        theirs: Entry,
+        /// The entry placed into the merged tree, which typically contains conflict markers.
+        merged: Entry,
+    },
+    /// One side modified the file at `path` while the other side deleted it. The modified version is kept.
+    ModifyDelete {
+        /// The path of the modified file.
+        path: BString,
+        /// The side that deleted the file.
+        deleted_by: Side,
+        /// The modified version of the file, which was kept.
+        modified: Entry,
+    },
+    /// One side renamed the file at `base_path` to `renamed_path` while the other side deleted it. The renamed version is kept.
+    RenameDelete {
+        /// The path of the file in the merge base.
+        base_path: BString,
+        /// The path the file was renamed to.
+        renamed_path: BString,
+        /// The side that deleted the file.
+        deleted_by: Side,
+    },
+    /// Both sides renamed the file at `base_path`, but to different paths. The merged file is placed at both paths.
+    RenameRename {
+        /// The path of the file in the merge base.
+        base_path: BString,
+        /// The path our side renamed the file to.
+        ours_path: BString,
+        /// The path their side renamed the file to.
+        theirs_path: BString,
+    },
+    /// A file at `path` is in the way of a directory of the same name, so the file was moved to `moved_to`.
+    DirectoryFile {
+        /// The path of the file, which also is the path of the directory.
+        path: BString,
+        /// The path the file was moved to in the merged tree.
+        moved_to: BString,
+        /// The side that has the file at `path`.
+        file_side: Side,
+    },
+}
+
+impl Conflict {
+    /// Return the path of the conflict in the merged tree.
+    pub fn path(&self) -> &BStr {
+        match self {
+            Conflict::Content { path, .. } | Conflict::ModifyDelete { path, .. } => path.as_ref(),
+            Conflict::RenameDelete { renamed_path, .. } => renamed_path.as_ref(),
+            Conflict::RenameRename { ours_path, .. } => ours_path.as_ref(),
+            Conflict::DirectoryFile { moved_to, .. } => moved_to.as_ref(),
+        }
+    }
+}
+
+/// The result of [`Repository::merge_trees()`].
+#[derive(Debug, Clone)]
+pub struct Outcome<'repo> {
+    /// The id of the merged tree, which was written to the object database even if there are conflicts.
+    pub tree: Id<'repo>,
+    /// All conflicts that were encountered, in the order they were detected.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl<'repo> Outcome<'repo> {
+    /// Return `true` if there are conflicts that need to be resolved.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// The state of a file of the merge base on one side.
+enum Status<'a> {
+    Unchanged,
+    Modified(Entry),
+    Deleted,
+    Renamed(&'a BString, Entry),
+}
+
+/// All changes of one side compared to the merge base, limited to non-tree entries.
+#[derive(Default)]
+struct Changes {
+    modified: HashMap<BString, Entry>,
+    deleted: HashSet<BString>,
+    added: BTreeMap<BString, Entry>,
+    /// Renames from the path in the merge base to the new path and entry.
+    renamed: HashMap<BString, (BString, Entry)>,
+}
+
+impl Changes {
+    fn status(&self, base_path: &BStr) -> Status<'_> {
+        if let Some(entry) = self.modified.get(base_path) {
+            Status::Modified(*entry)
+        } else if let Some((path, entry)) = self.renamed.get(base_path) {
+            Status::Renamed(path, *entry)
+        } else if self.deleted.contains(base_path) {
+            Status::Deleted
+        } else {
+            Status::Unchanged
+        }
+    }
+
+    /// Return `true` if this side placed an entry at `path`.
+    fn placed(&self, path: &BStr) -> bool {
+        self.added.contains_key(path)
+            || self.modified.contains_key(path)
+            || self.renamed.values().any(|(dest, _)| dest == path)
+    }
+}
+
+impl Repository {
+    /// Merge `ours` and `theirs`, which are trees or objects that peel to trees, with `base` as their merge base and write
+    /// the merged tree to the object database, without touching a worktree or the index.
+    ///
+    /// Renames are tracked on both sides according to `options`, and conflicts are returned as part of the outcome.
+    /// `labels` are written next to conflict markers.
+    ///
+    /// The `merge` and `conflict-marker-size` attributes are read from the `.gitattributes` files in `ours`, along with the ones
+    /// in the git directory and the global configuration.
+    pub fn merge_trees(
+        &self,
+        base: impl Into<ObjectId>,
+        ours: impl Into<ObjectId>,
+        theirs: impl Into<ObjectId>,
+        labels: Labels<'_>,
+        options: Options,
+    ) -> Result<Outcome<'_>, Error> {
+        let base = self.find_object(base)?.peel_to_tree()?;
+        let ours = self.find_object(ours)?.peel_to_tree()?;
+        let theirs = self.find_object(theirs)?.peel_to_tree()?;
+
+        let base_files = files(&base)?;
+
+        let rewrites = options.rewrites.map(|rewrites| Rewrites {
+            copies: None,
+            ..rewrites
+        });
+        let changes = |side: &crate::Tree<'_>| -> Result<Changes, Error> {
+            let mut out = Changes::default();
+            base.changes()
+                .map_err(Box::new)?
+                .track_path()
+                .track_rewrites(rewrites)
+                .for_each_to_obtain_tree(side, |change| {
+                    let path = change.location.to_owned();
+                    match change.event {
+                        Event::Addition { entry_mode, id } if entry_mode.is_no_tree() => {
+                            out.added.insert(path, entry(entry_mode, id.detach()));
+                        }
+                        Event::Deletion { entry_mode, .. } if entry_mode.is_no_tree() => {
+                            out.deleted.insert(path);
+                        }
+                        Event::Modification { entry_mode, id, .. } if entry_mode.is_no_tree() => {
+                            out.modified.insert(path, entry(entry_mode, id.detach()));
+                        }
+                        Event::Rewrite {
+                            source_location,
+                            entry_mode,
+                            id,
+                            copy,
+                            ..
+                        } => {
+                            if copy {
+                                out.added.insert(path, entry(entry_mode, id.detach()));
+                            } else {
+                                out.renamed
+                                    .insert(source_location.to_owned(), (path, entry(entry_mode, id.detach())));
+                            }
+                        }
+                        Event::Addition { .. } | Event::Deletion { .. } | Event::Modification { .. } => {}
+                    }
+                    Ok::<_, std::convert::Infallible>(Action::Continue)
+                })?;
+            // Tree diffs don't see changes to the mode alone, like the executable bit, so look for them separately.
+            for (path, side_entry) in files(side)? {
+                let mode_changed = base_files
+                    .get(&path)
+                    .map_or(false, |base| base.id == side_entry.id && base.mode != side_entry.mode);
+                if mode_changed && !out.renamed.contains_key(&path) {
+                    out.modified.entry(path).or_insert(side_entry);
+                }
+            }
+            Ok(out)
+        };
+        let ours_changes = changes(&ours)?;
+        let theirs_changes = changes(&theirs)?;
+
+        let attributes_index =
+            gix_index::State::from_tree(&ours.id, |oid, buf| self.objects.find_tree_iter(oid, buf).ok())?;
+        let cache = self
+            .attributes(
+                &attributes_index,
+                gix_worktree::cache::state::attributes::Source::IdMapping,
+                gix_worktree::cache::state::ignore::Source::IdMapping,
+                None,
+            )
+            .map_err(blob::new::Error::from)?;
+        let mut blobs = blob::Platform::new(self, cache).map_err(blob::new::Error::from)?;
+        blobs.options = options.blob;
+
+        let mut state = State {
+            repo: self,
+            blobs,
+            labels,
+            result: base_files.clone(),
+            placed: HashSet::new(),
+            conflicts: Vec::new(),
+            buf: Vec::new(),
+        };
+
+        let touched: BTreeSet<&BString> = [&ours_changes, &theirs_changes]
+            .into_iter()
+            .flat_map(|changes| {
+                changes
+                    .modified
+                    .keys()
+                    .chain(changes.deleted.iter())
+                    .chain(changes.renamed.keys())
+            })
+            .collect();
+        for path in &touched {
+            state.result.remove(*path);
+        }
+        for path in touched {
+            let base_entry = *base_files
+                .get(path)
+                .ok_or_else(|| Error::MissingBaseEntry { path: path.clone() })?;
+            let path = path.as_bstr();
+            match (ours_changes.status(path), theirs_changes.status(path)) {
+                (Status::Unchanged | Status::Deleted, Status::Unchanged | Status::Deleted) => {}
+                (Status::Unchanged, Status::Modified(entry)) | (Status::Modified(entry), Status::Unchanged) => {
+                    state.place(path, entry)?;
+                }
+                (Status::Modified(ours), Status::Modified(theirs)) => {
+                    let merged = state.merge(path, Some(base_entry), ours, theirs)?;
+                    state.place(path, merged)?;
+                }
+                (Status::Deleted, Status::Modified(modified)) | (Status::Modified(modified), Status::Deleted) => {
+                    let deleted_by = if ours_changes.deleted.contains(path) {
+                        Side::Ours
+                    } else {
+                        Side::Theirs
+                    };
+                    state.place(path, modified)?;
+                    state.conflicts.push(Conflict::ModifyDelete {
+                        path: path.to_owned(),
+                        deleted_by,
+                        modified,
+                    });
+                }
+                (Status::Renamed(renamed_path, entry), Status::Unchanged)
+                | (Status::Unchanged, Status::Renamed(renamed_path, entry)) => {
+                    state.place(renamed_path.as_ref(), entry)?;
+                }
+                (Status::Renamed(renamed_path, ours), Status::Modified(theirs))
+                | (Status::Modified(ours), Status::Renamed(renamed_path, theirs)) => {
+                    let merged = state.merge(renamed_path.as_ref(), Some(base_entry), ours, theirs)?;
+                    state.place(renamed_path.as_ref(), merged)?;
+                }
+                (Status::Renamed(ours_path, ours), Status::Renamed(theirs_path, theirs)) => {
+                    let merged = state.merge(ours_path.as_ref(), Some(base_entry), ours, theirs)?;
+                    state.place(ours_path.as_ref(), merged)?;
+                    if ours_path != theirs_path {
+                        state.place(theirs_path.as_ref(), merged)?;
+                        state.conflicts.push(Conflict::RenameRename {
+                            base_path: path.to_owned(),
+                            ours_path: ours_path.clone(),
+                            theirs_path: theirs_path.clone(),
+                        });
+                    }
+                }
+                (Status::Renamed(renamed_path, entry), Status::Deleted)
+                | (Status::Deleted, Status::Renamed(renamed_path, entry)) => {
+                    let deleted_by = if ours_changes.deleted.contains(path) {
+                        Side::Ours
+                    } else {
+                        Side::Theirs
+                    };
+                    state.place(renamed_path.as_ref(), entry)?;
+                    state.conflicts.push(Conflict::RenameDelete {
+                        base_path: path.to_owned(),
+                        renamed_path: renamed_path.clone(),
+                        deleted_by,
+                    });
+                }
+            }
+        }
+
+        for (path, ours) in &ours_changes.added {
+            match theirs_changes.added.get(path) {
+                Some(theirs) => {
+                    let merged = state.merge(path.as_ref(), None, *ours, *theirs)?;
+                    state.place(path.as_ref(), merged)?;
+                }
+                None => state.place(path.as_ref(), *ours)?,
+            }
+        }
+        for (path, theirs) in &theirs_changes.added {
+            if !ours_changes.added.contains_key(path) {
+                state.place(path.as_ref(), *theirs)?;
+            }
+        }
+
+        let files_in_the_way: Vec<BString> = state
+            .result
+            .keys()
+            .filter(|path| {
+                let mut dir = (*path).clone();
+                dir.push(b'/');
+                state
+                    .result
+                    .range(dir.clone()..)
+                    .next()
+                    .map_or(false, |(next, _)| next.starts_with(&dir))
+            })
+            .cloned()
+            .collect();
+        for path in files_in_the_way {
+            let file_side = if theirs_changes.placed(path.as_ref()) && !ours_changes.placed(path.as_ref()) {
+                Side::Theirs
+            } else {
+                Side::Ours
+            };
+            let label = match file_side {
+                Side::Ours => state.labels.current.unwrap_or("ours".into()),
+                Side::Theirs => state.labels.other.unwrap_or("theirs".into()),
+            };
+            let mut moved_to = path.clone();
+            moved_to.push(b'~');
+            moved_to.push_str(label);
+            let unique_prefix_len = moved_to.len();
+            let mut suffix = 0;
+            while state.result.contains_key(&moved_to) {
+                suffix += 1;
+                moved_to.truncate(unique_prefix_len);
+                moved_to.push_str(format!("_{suffix}"));
+            }
+            let entry = state.result.remove(&path).expect("present");
+            state.result.insert(moved_to.clone(), entry);
+            state.conflicts.push(Conflict::DirectoryFile {
+                path,
+                moved_to,
+                file_side,
+            });
+        }
+
+        let mut index = gix_index::State::new(self.object_hash());
+        for (path, entry) in &state.result {
+            let mode = match entry.mode {
+                EntryMode::Tree => unreachable!("only non-tree entries are merged"),
+                EntryMode::Blob => gix_index::entry::Mode::FILE,
+                EntryMode::BlobExecutable => gix_index::entry::Mode::FILE_EXECUTABLE,
+                EntryMode::Link => gix_index::entry::Mode::SYMLINK,
+                EntryMode::Commit => gix_index::entry::Mode::COMMIT,
+            };
+            index.dangerously_push_entry(
+                Default::default(),
+                entry.id,
+                gix_index::entry::Flags::empty(),
+                mode,
+                path.as_ref(),
+            );
+        }
+        index.sort_entries();
+        let tree = self.write_tree_from_index(&mut index)?;
+        Ok(Outcome {
+            tree,
+            conflicts: state.conflicts,
+        })
+    }
+}
+
+fn entry(mode: EntryMode, id: ObjectId) -> Entry {
+    Entry { mode, id }
+}
+
+/// Return all non-tree entries of `tree`, recursively, by their path.
+fn files(tree: &crate::Tree<'_>) -> Result<BTreeMap<BString, Entry>, Error> {
+    let mut recorder = gix_traverse::tree::Recorder::default();
+    tree.traverse().breadthfirst(&mut recorder)?;
+    Ok(recorder
+        .records
+        .into_iter()
+        .filter(|record| record.mode.is_no_tree())
+        .map(|record| (record.filepath, entry(record.mode, record.oid)))
+        .collect())
+}
+
+/// The state of an ongoing tree merge.
+struct State<'repo, 'a> {
+    repo: &'repo Repository,
+    blobs: blob::Platform<'repo>,
+    labels: Labels<'a>,
+    /// The merged tree, as all non-tree entries by path.
+    result: BTreeMap<BString, Entry>,
+    /// The paths in `result` that were changed by the merge.
+    placed: HashSet<BString>,
+    conflicts: Vec<Conflict>,
+    buf: Vec<u8>,
+}
+
+impl<'repo, 'a> State<'repo, 'a> {
+    /// Place `entry` at `path` in the merged tree, and merge it with an entry previously placed there if they differ.
+    fn place(&mut self, path: &BStr, entry: Entry) -> Result<(), Error> {
+        let entry = match self.result.get(path) {
+            Some(existing) if *existing != entry && self.placed.contains(path) => {
+                let existing = *existing;
+                self.merge(path, None, existing, entry)?
+            }
+            _ => entry,
+        };
+        self.result.insert(path.to_owned(), entry);
+        self.placed.insert(path.to_owned());
+        Ok(())
+    }
+
+    /// Merge `ours` and `theirs` at `path`, record a conflict if necessary and return the entry to place into the merged tree.
+    fn merge(&mut self, path: &BStr, base: Option<Entry>, ours: Entry, theirs: Entry) -> Result<Entry, Error> {
+        if ours == theirs || base == Some(theirs) {
+            return Ok(ours);
+        }
+        if base == Some(ours) {
+            return Ok(theirs);
+        }
+
+        let (merged, resolution) = if ours.mode.is_blob() && theirs.mode.is_blob() {
+            let base_mode = base.map(|base| base.mode);
+            let (mode, mut resolution) = if ours.mode == theirs.mode || Some(theirs.mode) == base_mode {
+                (ours.mode, Resolution::Complete)
+            } else if Some(ours.mode) == base_mode {
+                (theirs.mode, Resolution::Complete)
+            } else {
+                (ours.mode, Resolution::Conflict)
+            };
+            let id = if ours.id == theirs.id {
+                ours.id
+            } else {
+                let base_data = match base {
+                    Some(base) if base.mode.is_blob() => self.repo.find_object(base.id)?.detach().data,
+                    _ => Vec::new(),
+                };
+                let ours_data = self.repo.find_object(ours.id)?.detach().data;
+                let theirs_data = self.repo.find_object(theirs.id)?.detach().data;
+                let outcome =
+                    self.blobs
+                        .merge(path, &base_data, &ours_data, &theirs_data, self.labels, &mut self.buf)?;
+                if outcome.resolution == Resolution::Conflict {
+                    resolution = Resolution::Conflict;
+                }
+                self.repo.write_blob(&self.buf)?.detach()
+            };
+            (Entry { mode, id }, resolution)
+        } else {
+            (ours, Resolution::Conflict)
+        };
+
+        if resolution == Resolution::Conflict {
+            self.conflicts.push(Conflict::Content {
+                path: path.to_owned(),
+                base,
+                ours,
+                theirs,
+                merged,
+            });
+        }
+        Ok(merged)
+    }
+}
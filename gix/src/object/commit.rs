@@ -135,6 +135,14 @@ impl<'repo> Commit<'repo> {
         self.id().ancestors()
     }
 
+    /// Return the signature of this commit along with the data it signs, or `None` if the commit isn't signed.
+    ///
+    /// Use [`Repository::verify_signature()`][crate::Repository::verify_signature()] to verify it.
+    pub fn signature(&self) -> Option<crate::signing::Signed> {
+        gix_object::commit::extract_signature(&self.data)
+            .map(|(signature, payload)| crate::signing::Signed { signature, payload })
+    }
+
     /// Create a platform to further configure a `git describe` operation to find a name for this commit by looking
     /// at the closest annotated tags (by default) in its past.
     pub fn describe(&self) -> crate::commit::describe::Platform<'repo> {
@@ -23,4 +23,15 @@ impl<'repo> Tag<'repo> {
     pub fn tagger(&self) -> Result<Option<gix_actor::SignatureRef<'_>>, gix_object::decode::Error> {
         gix_object::TagRefIter::from_bytes(&self.data).tagger()
     }
+
+    /// Return the signature of this tag along with the data it signs, or `None` if the tag isn't signed.
+    ///
+    /// Use [`Repository::verify_signature()`][crate::Repository::verify_signature()] to verify it.
+    pub fn signature(&self) -> Result<Option<crate::signing::Signed>, gix_object::decode::Error> {
+        let tag = self.decode()?;
+        Ok(tag.pgp_signature.map(|signature| crate::signing::Signed {
+            signature: signature.to_owned(),
+            payload: self.data[..self.data.len() - signature.len()].into(),
+        }))
+    }
 }
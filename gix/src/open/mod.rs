@@ -11,6 +11,11 @@ pub struct Permissions {
     pub config: permissions::Config,
     /// Permissions related to where `gitattributes` should be loaded from.
     pub attributes: permissions::Attributes,
+    /// Control whether [hooks](crate::hook) may be run.
+    ///
+    /// Note that hooks never run in repositories with [reduced trust](gix_sec::Trust::Reduced), and that
+    /// [`gix_sec::Permission::Forbid`] will cause operations that would run a hook to fail.
+    pub hooks: gix_sec::Permission,
 }
 
 /// The options used in [`ThreadSafeRepository::open_opts()`][crate::ThreadSafeRepository::open_opts()].
@@ -35,6 +40,7 @@ pub struct Options {
     pub(crate) api_config_overrides: Vec<BString>,
     pub(crate) cli_config_overrides: Vec<BString>,
     pub(crate) open_path_as_is: bool,
+    pub(crate) lazy_fetch: bool,
     /// Internal to pass an already obtained CWD on to where it may also be used. This avoids the CWD being queried more than once per repo.
     pub(crate) current_dir: Option<PathBuf>,
 }
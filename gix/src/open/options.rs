@@ -14,6 +14,7 @@ impl Default for Options {
             lenient_config: true,
             bail_if_untrusted: false,
             open_path_as_is: false,
+            lazy_fetch: true,
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
@@ -142,6 +143,15 @@ impl Options {
         self
     }
 
+    /// If `true`, the default, objects that are missing in partial clones will be fetched from the promisor remotes
+    /// when they are accessed, which requires the `blocking-network-client` feature.
+    ///
+    /// Set it to `false` to treat these objects as missing, similar to `GIT_NO_LAZY_FETCH=1`.
+    pub fn lazy_fetch(mut self, toggle: bool) -> Self {
+        self.lazy_fetch = toggle;
+        self
+    }
+
     /// Open a repository at `path` with the options set so far.
     #[allow(clippy::result_large_err)]
     pub fn open(self, path: impl Into<PathBuf>) -> Result<ThreadSafeRepository, Error> {
@@ -161,6 +171,7 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                lazy_fetch: true,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
@@ -173,6 +184,7 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                lazy_fetch: true,
                 lossy_config: None,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
@@ -177,6 +177,7 @@ impl Permissions {
             env: Environment::all(),
             config: Config::all(),
             attributes: Attributes::all(),
+            hooks: gix_sec::Permission::Allow,
         }
     }
 
@@ -187,6 +188,7 @@ impl Permissions {
             env: Environment::all(),
             config: Config::all(),
             attributes: Attributes::all(),
+            hooks: gix_sec::Permission::Allow,
         }
     }
 
@@ -196,6 +198,7 @@ impl Permissions {
             config: Config::isolated(),
             attributes: Attributes::isolated(),
             env: Environment::isolated(),
+            hooks: gix_sec::Permission::Deny,
         }
     }
 }
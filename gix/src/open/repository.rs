@@ -158,11 +158,13 @@ impl ThreadSafeRepository {
             lenient_config,
             bail_if_untrusted,
             open_path_as_is: _,
+            lazy_fetch,
             permissions:
                 Permissions {
                     ref env,
                     config,
                     attributes,
+                    hooks: _,
                 },
             ref api_config_overrides,
             ref cli_config_overrides,
@@ -266,6 +268,21 @@ impl ThreadSafeRepository {
             })
             .unwrap_or_default();
 
+        #[cfg(feature = "blocking-network-client")]
+        let promisor = (lazy_fetch && !crate::remote::promisor::promisor_remote_names(&config.resolved).is_empty())
+            .then(|| {
+                std::sync::Arc::new(crate::remote::promisor::Fetcher {
+                    git_dir: git_dir.clone(),
+                    options: options.clone(),
+                    repo: Default::default(),
+                }) as std::sync::Arc<dyn gix_odb::store::Promisor>
+            });
+        #[cfg(not(feature = "blocking-network-client"))]
+        let promisor = {
+            let _ = lazy_fetch;
+            None
+        };
+
         Ok(ThreadSafeRepository {
             objects: OwnShared::new(gix_odb::Store::at_opts(
                 common_dir_ref.join("objects"),
@@ -275,6 +292,7 @@ impl ThreadSafeRepository {
                     object_hash: config.object_hash,
                     use_multi_pack_index: config.use_multi_pack_index,
                     current_dir: current_dir.to_owned().into(),
+                    promisor,
                 },
             )?),
             common_dir,
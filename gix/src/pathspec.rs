@@ -0,0 +1,162 @@
+//! Pathspec plumbing and abstractions
+use gix_odb::FindExt;
+pub use gix_pathspec::*;
+
+use crate::{bstr::BStr, Repository};
+
+///
+pub mod init {
+    /// The error returned by [`Pathspec::new()`][super::Pathspec::new()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        MakeAttributes(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        ParseSpec(#[from] gix_pathspec::parse::Error),
+        #[error(transparent)]
+        Normalize(#[from] gix_pathspec::normalize::Error),
+        #[error("Could not obtain the current working directory")]
+        CurrentDir(#[source] std::io::Error),
+        #[error(transparent)]
+        Realpath(#[from] gix_path::realpath::Error),
+    }
+}
+
+///
+pub mod matching {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Pathspec::pattern_matching_relative_path()`][super::Pathspec::pattern_matching_relative_path()].
+    #[derive(Debug, thiserror::Error)]
+    #[error("Could not obtain the attributes of '{path}' to match it against pathspecs")]
+    pub struct Error {
+        /// The path whose attributes couldn't be obtained.
+        pub path: BString,
+        /// The underlying error.
+        pub source: std::io::Error,
+    }
+}
+
+/// A pathspec search that matches paths in the worktree or index, along with everything needed to evaluate
+/// pathspecs with `attr:` constraints.
+pub struct Pathspec<'repo> {
+    repo: &'repo Repository,
+    /// The cache to power attribute access. It's only initialized if we have a pattern with attributes.
+    cache: Option<gix_worktree::Cache>,
+    /// The prepared search to use for checking matches.
+    search: gix_pathspec::Search,
+}
+
+/// Lifecycle
+impl<'repo> Pathspec<'repo> {
+    /// Create a new instance by parsing `patterns` into [`Pathspecs`](Pattern) to make them usable for searches.
+    /// `make_attributes` is called if one of the patterns has an `attr:` element which requires attribute matching. It should
+    /// be used to control where attributes are coming from.
+    /// If `inherit_ignore_case` is `true`, the 'core.ignoreCase' configuration variable will be used to make all patterns
+    /// case-insensitive, which is what `git` does.
+    ///
+    /// Patterns are placed into the directory the current process is in if it is inside of the worktree, and
+    /// a search without any pattern matches all paths.
+    pub fn new(
+        repo: &'repo Repository,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        inherit_ignore_case: bool,
+        make_attributes: impl FnOnce() -> Result<gix_worktree::Cache, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Result<Self, init::Error> {
+        let ignore_case = inherit_ignore_case && repo.config.ignore_case;
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                let mut pattern = gix_pathspec::parse(pattern.as_ref())?;
+                if ignore_case {
+                    pattern.signature |= MagicSignature::ICASE;
+                }
+                Ok(pattern)
+            })
+            .collect::<Result<Vec<_>, init::Error>>()?;
+        let needs_cache = patterns.iter().any(|p| !p.attributes.is_empty());
+        // Without a worktree, or if the current directory is outside of it, patterns are relative to the root.
+        let prefix = match repo.work_dir() {
+            Some(root) => {
+                let cwd = std::env::current_dir().map_err(init::Error::CurrentDir)?;
+                let root = gix_path::realpath_opts(root, &cwd, gix_path::realpath::MAX_SYMLINKS)?;
+                cwd.strip_prefix(root).ok().map(ToOwned::to_owned)
+            }
+            None => None,
+        };
+        let search = Search::from_specs(
+            patterns,
+            prefix.as_deref(),
+            repo.work_dir().unwrap_or_else(|| repo.git_dir()),
+        )?;
+        let cache = needs_cache.then(make_attributes).transpose()?;
+        Ok(Self { repo, cache, search })
+    }
+
+    /// Turn ourselves into the functional parts for direct usage.
+    /// Note that the [`cache`](gix_worktree::Cache) is only set if one of the [`search` patterns](Search)
+    /// is specifying attributes to match for.
+    pub fn into_parts(self) -> (Search, Option<gix_worktree::Cache>) {
+        (self.search, self.cache)
+    }
+}
+
+/// Access
+impl<'repo> Pathspec<'repo> {
+    /// Return the attributes cache which is used when matching attributes in pathspecs, or `None` if none of the pathspecs require that.
+    pub fn attributes(&self) -> Option<&gix_worktree::Cache> {
+        self.cache.as_ref()
+    }
+
+    /// Return the search itself which can be used for matching paths or accessing the actual patterns that will be used.
+    pub fn search(&self) -> &gix_pathspec::Search {
+        &self.search
+    }
+
+    /// Return the first [`Match`](search::Match) of `relative_path`, or `None` if it isn't included or is excluded.
+    /// `is_dir` is true if `relative_path` is a directory, or `None` if this isn't known.
+    ///
+    /// Fail if the attributes of `relative_path` couldn't be obtained for patterns with `attr:` constraints.
+    pub fn pattern_matching_relative_path<'a>(
+        &mut self,
+        relative_path: impl Into<&'a BStr>,
+        is_dir: Option<bool>,
+    ) -> Result<Option<gix_pathspec::search::Match<'_>>, matching::Error> {
+        let relative_path = relative_path.into();
+        let cache = &mut self.cache;
+        let objects = &self.repo.objects;
+        let mut err = None;
+        let m = self.search.pattern_matching_relative_path(
+            relative_path,
+            is_dir,
+            &mut |relative_path, _case, is_dir, out| {
+                let cache = cache.as_mut().expect("initialized in advance");
+                match cache.at_entry(relative_path, Some(is_dir), |id, buf| objects.find_blob(id, buf)) {
+                    Ok(platform) => platform.matching_attributes(out),
+                    Err(source) => {
+                        err.get_or_insert(matching::Error {
+                            path: relative_path.to_owned(),
+                            source,
+                        });
+                        false
+                    }
+                }
+            },
+        );
+        match err {
+            Some(err) => Err(err),
+            None => Ok(m),
+        }
+    }
+
+    /// The simplified version of [`pattern_matching_relative_path()`](Self::pattern_matching_relative_path()) which returns
+    /// `true` if `relative_path` is included in the set of positive pathspecs, while not being excluded.
+    pub fn is_included<'a>(
+        &mut self,
+        relative_path: impl Into<&'a BStr>,
+        is_dir: Option<bool>,
+    ) -> Result<bool, matching::Error> {
+        Ok(self.pattern_matching_relative_path(relative_path, is_dir)?.is_some())
+    }
+}
@@ -0,0 +1,974 @@
+//! Rebase commits onto another commit without a worktree, similar to `git rebase --merge` and `git rebase --interactive`.
+//!
+//! All instructions of a [todo list](gix_sequencer::todo::List) are executed in memory by merging trees, which is why
+//! neither the index nor the worktree are touched. That way it works in bare repositories, too.
+use gix_hash::ObjectId;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+use gix_sequencer::todo::{FixupMessage, Instruction, List};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    ext::ObjectIdExt,
+    merge,
+    merge::commit::Info,
+    Id, Repository,
+};
+
+/// The on-disk state of a rebase, as shared with `git`.
+pub use gix_rebase as state;
+
+/// Options for use in [`Repository::rebase()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The options to use when merging trees to apply the changes of a commit.
+    pub merge: merge::tree::Options,
+    /// If `true`, commits are always recreated even if they could be reused as is because their parents didn't change,
+    /// similar to `git rebase --force-rebase`.
+    pub force: bool,
+    /// What to do with commits that become empty because their changes are already present, similar to `git rebase --empty`.
+    pub empty: Empty,
+    /// If `true`, commits that were empty to begin with are kept, similar to `git rebase --keep-empty`, which is the default.
+    /// Otherwise they are dropped like with `--no-keep-empty`.
+    pub keep_empty: bool,
+}
+
+/// What to do with commits that become empty while they are picked, as all of their changes are already present.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Empty {
+    /// Drop the commit, like `--empty=drop`.
+    ///
+    /// `edit` instructions still stop so the current commit can be amended, while `reword` instructions have nothing to reword.
+    #[default]
+    Drop,
+    /// Create the commit even though it is empty, like `--empty=keep`.
+    Keep,
+    /// Stop with [`Stop::Empty`] to let the caller decide, like `--empty=stop`.
+    Stop,
+}
+
+/// The reason for a [`Rebase`] to stop before all instructions were executed.
+#[derive(Debug)]
+pub enum Stop<'repo> {
+    /// Applying the changes of `commit` caused conflicts.
+    ///
+    /// Use [`Rebase::resolve_conflicts()`] with the resolved tree to create the commit, or [`Rebase::skip()`] to drop it.
+    Conflict {
+        /// The commit whose changes couldn't be applied.
+        commit: ObjectId,
+        /// The outcome of the tree merge, with all conflicts.
+        merge: merge::tree::Outcome<'repo>,
+    },
+    /// Picking `commit` didn't change anything as its changes are already present, and [`Options::empty`] is [`Empty::Stop`].
+    ///
+    /// Use [`Rebase::resolve_conflicts()`] with the tree of the [`head`](Rebase::head()) to keep it as empty commit,
+    /// or [`Rebase::skip()`] to drop it.
+    Empty {
+        /// The commit whose changes are already present.
+        commit: ObjectId,
+    },
+    /// An `edit` instruction applied `commit` as the new [`head`](Rebase::head()) so it can be amended with [`Rebase::set_head()`].
+    Edit {
+        /// The commit of the instruction.
+        commit: ObjectId,
+    },
+    /// An instruction like `reword` requires the message of the [`head`](Rebase::head()) to be edited with [`Rebase::reword()`].
+    EditMessage {
+        /// The commit of the instruction.
+        commit: ObjectId,
+    },
+    /// A `break` instruction was encountered.
+    Break,
+    /// An `exec` instruction was encountered, which is left to the caller to execute.
+    Exec {
+        /// The command to run.
+        command: BString,
+    },
+}
+
+/// A rebase in progress, which is executed instruction by instruction and which can be [persisted](Rebase::persist()) in bare
+/// repositories so it can be [resumed](Repository::rebase_resume()) later.
+///
+/// Note that it is created with [`Repository::rebase()`].
+pub struct Rebase<'repo> {
+    repo: &'repo Repository,
+    state: gix_rebase::merge::State,
+    /// The commit created last, which is the parent of the next commit to be created.
+    head: ObjectId,
+    /// Additional parents of a merge commit that couldn't be created due to conflicts.
+    pending_merge_parents: Vec<ObjectId>,
+    /// The encoding of the message of the commit that couldn't be created due to conflicts, if it isn't UTF-8.
+    pending_encoding: Option<BString>,
+    /// If `true`, the state was written to disk and `HEAD` was detached.
+    persisted: bool,
+    /// The options used to execute the instructions.
+    pub options: Options,
+}
+
+/// The name of the file storing the additional parents of a conflicting merge.
+const MERGE_HEAD: &str = "MERGE_HEAD";
+
+///
+pub mod init {
+    /// The error returned by [`Repository::rebase()`][crate::Repository::rebase()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        MergeBase(#[from] gix_revision::merge_base::Error),
+        #[error(transparent)]
+        FindCommit(#[from] Box<super::Error>),
+        #[error(transparent)]
+        MergeOptions(#[from] Box<crate::merge::tree::options::Error>),
+    }
+}
+
+///
+pub mod resume {
+    /// The error returned by [`Repository::rebase_resume()`][crate::Repository::rebase_resume()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadState(#[from] gix_rebase::read::Error),
+        #[error("The rebase in progress uses the apply-backend, which can't be resumed")]
+        UnsupportedBackend,
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error("Could not read the parents of the pending merge")]
+        ReadMergeHead(#[source] std::io::Error),
+        #[error("Could not parse the parents of the pending merge")]
+        ParseMergeHead(#[from] gix_hash::decode::Error),
+        #[error(transparent)]
+        MergeOptions(#[from] Box<crate::merge::tree::options::Error>),
+    }
+}
+
+/// The error returned by the methods of [`Rebase`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    IntoCommit(#[from] crate::object::try_into::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    RevParse(#[from] Box<crate::revision::spec::parse::single::Error>),
+    #[error(transparent)]
+    MergeTrees(#[from] merge::tree::Error),
+    #[error(transparent)]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[error(transparent)]
+    Revert(#[from] merge::commit::Error),
+    #[error(transparent)]
+    Commit(#[from] Box<crate::commit::Error>),
+    #[error("The committer is not configured")]
+    CommitterMissing,
+    #[error(transparent)]
+    CommitterTime(#[from] crate::config::time::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    ReferenceName(#[from] gix_validate::reference::name::Error),
+    #[error("Commit {id} is a merge commit and can't be picked, use a 'merge' instruction instead")]
+    PickMergeCommit { id: ObjectId },
+    #[error("Cannot '{command}' without a previous commit")]
+    NoPreviousCommit { command: &'static str },
+    #[error("There are no conflicts to resolve")]
+    NoConflict,
+    #[error("The conflicts of the last instruction must be resolved or skipped first")]
+    UnresolvedConflict,
+    #[error("There are {remaining} instructions left to execute")]
+    Unfinished { remaining: usize },
+    #[error("Could not write the rebase state")]
+    WriteState(#[from] std::io::Error),
+    #[error("A rebase can only be persisted in bare repositories as the index and worktree aren't updated")]
+    PersistWithWorktree,
+}
+
+impl Repository {
+    /// Prepare a rebase of all commits reachable from `branch`, but not from `upstream`, onto `onto`, or onto `upstream`
+    /// if `onto` is `None`, similar to `git rebase --onto <onto> <upstream> <branch>`.
+    ///
+    /// `branch` is the full name of a reference, or `None` to rebase `HEAD`.
+    /// The commits are picked in topological order, with merge commits being skipped, and the todo list can be
+    /// [altered](Rebase::todo_mut()) before [running](Rebase::run()) the rebase.
+    ///
+    /// # Deviation
+    ///
+    /// Unlike `git`, commits whose changes are already present upstream are not skipped.
+    pub fn rebase(
+        &self,
+        branch: Option<&gix_ref::FullNameRef>,
+        upstream: impl Into<ObjectId>,
+        onto: Option<ObjectId>,
+    ) -> Result<Rebase<'_>, init::Error> {
+        let (head_name, orig_head) = match branch {
+            Some(name) => (
+                Some(name.to_owned()),
+                self.find_reference(name)?.into_fully_peeled_id()?.detach(),
+            ),
+            None => (self.head_name()?, self.head_id()?.detach()),
+        };
+        let upstream = upstream.into();
+        let onto = onto.unwrap_or(upstream);
+
+        let mut todo = List::default();
+        for id in commits_to_rebase(self, orig_head, upstream)? {
+            let commit = Info::new::<Error>(self, id).map_err(Box::new)?;
+            todo.instructions.push_back(Instruction::Pick {
+                commit: id.to_string().into(),
+                subject: commit.subject().into(),
+            });
+        }
+        Ok(Rebase::new(
+            self,
+            gix_rebase::merge::State::new(head_name.map(|name| name.as_bstr().to_owned()), onto, orig_head, todo),
+            self.rebase_options().map_err(Box::new)?,
+        ))
+    }
+
+    /// Resume the rebase that was [persisted](Rebase::persist()) previously, possibly by `git`, or return `None` if no rebase
+    /// is in progress.
+    pub fn rebase_resume(&self) -> Result<Option<Rebase<'_>>, resume::Error> {
+        let state = match gix_rebase::State::from_git_dir(self.git_dir())? {
+            None => return Ok(None),
+            Some(gix_rebase::State::Apply(_)) => return Err(resume::Error::UnsupportedBackend),
+            Some(gix_rebase::State::Merge(state)) => state,
+        };
+        let mut rebase = Rebase::new(self, state, self.rebase_options().map_err(Box::new)?);
+        rebase.head = self.head_id()?.detach();
+        rebase.persisted = true;
+        match std::fs::read(self.git_dir().join(MERGE_HEAD)) {
+            Ok(data) => {
+                for line in data.lines().filter(|line| !line.is_empty()) {
+                    rebase.pending_merge_parents.push(ObjectId::from_hex(line)?);
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(resume::Error::ReadMergeHead(err)),
+        }
+        Ok(Some(rebase))
+    }
+
+    /// Return the state of the rebase that is currently in progress, or `None` if there is none.
+    ///
+    /// This also works for rebases started by `git`, including those using the apply-backend.
+    pub fn rebase_state(&self) -> Result<Option<gix_rebase::State>, gix_rebase::read::Error> {
+        gix_rebase::State::from_git_dir(self.git_dir())
+    }
+
+    fn rebase_options(&self) -> Result<Options, merge::tree::options::Error> {
+        Ok(Options {
+            merge: self.tree_merge_options()?,
+            force: false,
+            empty: Empty::default(),
+            keep_empty: true,
+        })
+    }
+}
+
+/// Return all commits reachable from `tip` but not from `upstream`, excluding merge commits, with parents before their children.
+fn commits_to_rebase(repo: &Repository, tip: ObjectId, upstream: ObjectId) -> Result<Vec<ObjectId>, init::Error> {
+    let mut graph = repo.revision_graph();
+    let to_rebase: gix_hashtable::HashSet<_> = gix_revision::merge_base::exclusive(&[tip], &[upstream], &mut graph)?
+        .into_iter()
+        .collect();
+    let mut seen = gix_hashtable::HashSet::default();
+    let mut out = Vec::new();
+    let mut stack = vec![(tip, false)];
+    while let Some((id, parents_done)) = stack.pop() {
+        if parents_done {
+            if graph[&id].parents.len() < 2 {
+                out.push(id);
+            }
+            continue;
+        }
+        if !to_rebase.contains(&id) || !seen.insert(id) {
+            continue;
+        }
+        stack.push((id, true));
+        stack.extend(graph[&id].parents.iter().rev().map(|parent| (*parent, false)));
+    }
+    Ok(out)
+}
+
+/// Lifecycle
+impl<'repo> Rebase<'repo> {
+    /// Create a new instance to execute the todo list of `state` with `options`, starting at `state.onto`.
+    ///
+    /// Use it to execute a custom todo list.
+    pub fn new(repo: &'repo Repository, state: gix_rebase::merge::State, options: Options) -> Self {
+        Rebase {
+            repo,
+            head: state.onto,
+            state,
+            pending_merge_parents: Vec::new(),
+            pending_encoding: None,
+            persisted: false,
+            options,
+        }
+    }
+
+    /// Write the state of this rebase to the `rebase-merge` directory of the repository and detach `HEAD` at the
+    /// [current commit](Self::head()), so the rebase can be [resumed](Repository::rebase_resume()) later.
+    ///
+    /// As neither the index nor the worktree are updated, this fails if the repository has a worktree, which would
+    /// otherwise not match `HEAD` anymore.
+    pub fn persist(&mut self) -> Result<(), Error> {
+        if self.repo.work_dir().is_some() {
+            return Err(Error::PersistWithWorktree);
+        }
+        let git_dir = self.repo.git_dir();
+        self.state.write_to_dir(&git_dir.join(gix_rebase::MERGE_DIR))?;
+        let merge_head = git_dir.join(MERGE_HEAD);
+        if self.pending_merge_parents.is_empty() {
+            if merge_head.is_file() {
+                std::fs::remove_file(merge_head)?;
+            }
+        } else {
+            let mut buf = String::new();
+            for id in &self.pending_merge_parents {
+                buf.push_str(&format!("{id}\n"));
+            }
+            std::fs::write(merge_head, buf)?;
+        }
+        self.set_head_ref(
+            Target::Peeled(self.head),
+            format!("rebase: updating HEAD to {}", self.head),
+        )?;
+        self.persisted = true;
+        Ok(())
+    }
+
+    /// Finish the rebase after all instructions were executed, by pointing the rebased branch (or `HEAD` if it was detached)
+    /// to the [current commit](Self::head()), updating all references of `update-ref` instructions and cleaning up.
+    ///
+    /// Return the commit the rebased branch points to now.
+    pub fn finish(mut self) -> Result<Id<'repo>, Error> {
+        if !self.state.todo.instructions.is_empty() {
+            return Err(Error::Unfinished {
+                remaining: self.state.todo.instructions.len(),
+            });
+        }
+        if self.state.stopped_at.is_some() && self.state.message.is_some() {
+            return Err(Error::UnresolvedConflict);
+        }
+
+        let onto = self.state.onto.to_hex_with_len(7).to_string();
+        let mut edits = Vec::new();
+        for update in self.state.update_refs.iter().filter(|update| !update.after.is_null()) {
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("rebase (update-ref): updating {}", update.after).into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Peeled(update.after),
+                },
+                name: FullName::try_from(update.name.clone())?,
+                deref: false,
+            });
+        }
+        let head_name = self.state.head_name.clone();
+        let message = format!(
+            "rebase (finish): {} onto {onto}",
+            head_name.as_ref().map_or("HEAD".into(), |name| name.as_bstr())
+        );
+        edits.push(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.into(),
+                },
+                expected: if self.persisted {
+                    PreviousValue::Any
+                } else {
+                    PreviousValue::ExistingMustMatch(Target::Peeled(self.state.orig_head))
+                },
+                new: Target::Peeled(self.head),
+            },
+            name: match &head_name {
+                Some(name) => FullName::try_from(name.clone())?,
+                None => "HEAD".try_into().expect("valid"),
+            },
+            deref: false,
+        });
+        self.repo.edit_references(edits)?;
+
+        if self.persisted {
+            if let Some(name) = head_name {
+                self.set_head_ref(
+                    Target::Symbolic(FullName::try_from(name.clone())?),
+                    format!("rebase (finish): returning to {name}"),
+                )?;
+            }
+        }
+        self.cleanup()?;
+        Ok(self.head.attach(self.repo))
+    }
+
+    /// Abort the rebase by cleaning up, which restores `HEAD` if the rebase was [persisted](Self::persist()).
+    ///
+    /// Return the commit the rebased branch still points to.
+    pub fn abort(mut self) -> Result<Id<'repo>, Error> {
+        if self.persisted {
+            let (target, name) = match &self.state.head_name {
+                Some(name) => (Target::Symbolic(FullName::try_from(name.clone())?), name.clone()),
+                None => (
+                    Target::Peeled(self.state.orig_head),
+                    self.state.orig_head.to_string().into(),
+                ),
+            };
+            self.set_head_ref(target, format!("rebase (abort): returning to {name}"))?;
+        }
+        self.cleanup()?;
+        Ok(self.state.orig_head.attach(self.repo))
+    }
+
+    fn cleanup(&mut self) -> Result<(), Error> {
+        let edits: Vec<_> = self
+            .state
+            .refs_to_delete
+            .drain(..)
+            .map(|name| -> Result<_, Error> {
+                Ok(RefEdit {
+                    change: Change::Delete {
+                        expected: PreviousValue::Any,
+                        log: RefLog::AndReference,
+                    },
+                    name: FullName::try_from(name)?,
+                    deref: false,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        self.repo.edit_references(edits)?;
+
+        if self.persisted {
+            let git_dir = self.repo.git_dir();
+            std::fs::remove_dir_all(git_dir.join(gix_rebase::MERGE_DIR))?;
+            let merge_head = git_dir.join(MERGE_HEAD);
+            if merge_head.is_file() {
+                std::fs::remove_file(merge_head)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_head_ref(&self, target: Target, message: String) -> Result<(), Error> {
+        self.repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new: target,
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        })?;
+        Ok(())
+    }
+}
+
+/// Access
+impl<'repo> Rebase<'repo> {
+    /// Return the state of the rebase, which is what would be [persisted](Self::persist()).
+    pub fn state(&self) -> &gix_rebase::merge::State {
+        &self.state
+    }
+
+    /// Return the instructions that are still to be executed, for editing them before or while the rebase is running.
+    pub fn todo_mut(&mut self) -> &mut List {
+        &mut self.state.todo
+    }
+
+    /// Return the last commit that was created or reused, which becomes the parent of the next commit to be created.
+    pub fn head(&self) -> Id<'repo> {
+        self.head.attach(self.repo)
+    }
+}
+
+/// Execution
+impl<'repo> Rebase<'repo> {
+    /// Execute all instructions until the todo list is empty, in which case `None` is returned, or until the rebase has to stop.
+    ///
+    /// Once done, call [`finish()`](Self::finish()) to update the rebased branch.
+    pub fn run(&mut self) -> Result<Option<Stop<'repo>>, Error> {
+        while !self.state.todo.instructions.is_empty() {
+            if let Some(stop) = self.step()? {
+                return Ok(Some(stop));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Execute the next instruction, if there is one, and return the reason for stopping if the caller has to intervene.
+    pub fn step(&mut self) -> Result<Option<Stop<'repo>>, Error> {
+        if self.state.stopped_at.is_some() && self.state.message.is_some() {
+            return Err(Error::UnresolvedConflict);
+        }
+        let Some(instruction) = self.state.todo.instructions.pop_front() else {
+            return Ok(None);
+        };
+        self.state.done.instructions.push_back(instruction.clone());
+        self.state.stopped_at = None;
+        self.state.amend = None;
+
+        Ok(match instruction {
+            Instruction::Pick { commit, .. } => self.pick(commit.as_ref(), None)?,
+            Instruction::Reword { commit, .. } => self.pick(commit.as_ref(), Some(StopAfter::EditMessage))?,
+            Instruction::Edit { commit, .. } => self.pick(commit.as_ref(), Some(StopAfter::Edit))?,
+            Instruction::Revert { commit, .. } => self.revert(commit.as_ref())?,
+            Instruction::Squash { commit, .. } => self.squash(commit.as_ref(), None)?,
+            Instruction::Fixup { commit, message, .. } => self.squash(commit.as_ref(), Some(message))?,
+            Instruction::Drop { .. } | Instruction::Noop => None,
+            Instruction::Exec { command } => Some(Stop::Exec { command }),
+            Instruction::Break => Some(Stop::Break),
+            Instruction::Label { name } => {
+                let name = label_ref_name(name.as_ref())?;
+                self.repo.reference(
+                    name.clone(),
+                    self.head,
+                    PreviousValue::Any,
+                    format!("rebase (label) '{}'", name.shorten()),
+                )?;
+                if !self
+                    .state
+                    .refs_to_delete
+                    .iter()
+                    .any(|existing| existing == name.as_bstr())
+                {
+                    self.state.refs_to_delete.push(name.into_inner());
+                }
+                None
+            }
+            Instruction::Reset { name } => {
+                self.head = self.resolve_label(name.as_ref())?;
+                None
+            }
+            Instruction::Merge {
+                commit,
+                edit_message,
+                labels,
+                subject,
+            } => self.merge(
+                commit.as_ref().map(AsRef::as_ref),
+                edit_message,
+                &labels,
+                subject.as_ref(),
+            )?,
+            Instruction::UpdateRef { name } => {
+                let head = self.head;
+                match self.state.update_refs.iter_mut().find(|update| update.name == name) {
+                    Some(update) => update.after = head,
+                    None => {
+                        let before = match self.repo.try_find_reference(name.as_bstr())? {
+                            Some(reference) => reference.into_fully_peeled_id()?.detach(),
+                            None => ObjectId::null(self.repo.object_hash()),
+                        };
+                        self.state.update_refs.push(gix_rebase::merge::UpdateRef {
+                            name,
+                            before,
+                            after: head,
+                        });
+                    }
+                }
+                None
+            }
+        })
+    }
+
+    /// Create a commit from the resolved `tree` after [stopping due to conflicts](Stop::Conflict) or due to a commit that
+    /// [became empty](Stop::Empty), using the message
+    /// and author of the commit that couldn't be applied, and return it.
+    ///
+    /// Call [`run()`](Self::run()) afterwards to continue the rebase.
+    pub fn resolve_conflicts(&mut self, tree: impl Into<ObjectId>) -> Result<Id<'repo>, Error> {
+        let (message, author) = match (self.state.message.take(), self.state.author.take()) {
+            (Some(message), Some(author)) => (message, author),
+            _ => return Err(Error::NoConflict),
+        };
+        self.state.stopped_at = None;
+        let parents: Vec<_> = std::iter::once(self.head)
+            .chain(self.pending_merge_parents.drain(..))
+            .collect();
+        let encoding = self.pending_encoding.take();
+        let id = self.commit(&author, message.as_ref(), encoding, tree.into(), parents)?;
+        self.head = id;
+        Ok(id.attach(self.repo))
+    }
+
+    /// Drop the commit that couldn't be applied after [stopping due to conflicts](Stop::Conflict) or because it
+    /// [became empty](Stop::Empty).
+    ///
+    /// Call [`run()`](Self::run()) afterwards to continue the rebase.
+    pub fn skip(&mut self) -> Result<(), Error> {
+        if self.state.message.take().is_none() {
+            return Err(Error::NoConflict);
+        }
+        self.state.author = None;
+        self.state.stopped_at = None;
+        self.pending_merge_parents.clear();
+        self.pending_encoding = None;
+        Ok(())
+    }
+
+    /// Replace the message of the [current commit](Self::head()) with `message`, as needed after
+    /// [stopping to edit the message](Stop::EditMessage), and return the new commit.
+    pub fn reword(&mut self, message: &BStr) -> Result<Id<'repo>, Error> {
+        let head = Info::new::<Error>(self.repo, self.head)?;
+        let id = self.commit(&head.author, message, head.encoding, head.tree, head.parents)?;
+        self.head = id;
+        Ok(id.attach(self.repo))
+    }
+
+    /// Make `commit` the [current commit](Self::head()), to be used as parent for the next commit, for instance after amending
+    /// the current commit after [stopping to edit it](Stop::Edit).
+    pub fn set_head(&mut self, commit: impl Into<ObjectId>) {
+        self.head = commit.into();
+    }
+}
+
+/// What to do after successfully picking a commit.
+enum StopAfter {
+    Edit,
+    EditMessage,
+}
+
+/// Utilities
+impl<'repo> Rebase<'repo> {
+    fn pick(&mut self, commit: &BStr, stop_after: Option<StopAfter>) -> Result<Option<Stop<'repo>>, Error> {
+        let commit = Info::new::<Error>(
+            self.repo,
+            self.repo.rev_parse_single(commit).map_err(Box::new)?.detach(),
+        )?;
+        if commit.parents.len() > 1 {
+            return Err(Error::PickMergeCommit { id: commit.id });
+        }
+        let parent = commit.parents.first().copied();
+        let base = match parent {
+            Some(parent) => parent,
+            None => ObjectId::empty_tree(self.repo.object_hash()),
+        };
+        let base_tree = self.repo.find_object(base)?.peel_to_tree()?.id;
+        let was_empty = commit.tree == base_tree;
+        let mut dropped = was_empty && !self.options.keep_empty;
+        if dropped {
+            // Commits that are empty to begin with are dropped on request, like `--no-keep-empty` does.
+        } else if !self.options.force && parent == Some(self.head) {
+            self.head = commit.id;
+        } else {
+            let ancestor_label: BString = format!("parent of {}", commit.label()).into();
+            let outcome = self.merge_trees(
+                base,
+                self.head,
+                commit.id,
+                ancestor_label.as_ref(),
+                commit.label().as_ref(),
+            )?;
+            if outcome.has_conflicts() {
+                return Ok(Some(self.stop_at_conflict(commit, outcome)));
+            }
+            let head_tree = Info::new::<Error>(self.repo, self.head)?.tree;
+            let became_empty = outcome.tree == head_tree && !was_empty;
+            match self.options.empty {
+                Empty::Drop if became_empty => dropped = true,
+                Empty::Stop if became_empty => {
+                    let commit = self.stop_with_pending(commit);
+                    return Ok(Some(Stop::Empty { commit }));
+                }
+                Empty::Drop | Empty::Stop | Empty::Keep => {
+                    self.head = self.commit(
+                        &commit.author,
+                        commit.message.as_ref(),
+                        commit.encoding.clone(),
+                        outcome.tree.detach(),
+                        Some(self.head),
+                    )?;
+                }
+            }
+        }
+
+        if !dropped {
+            self.state.amend = Some(self.head);
+        }
+        Ok(stop_after
+            .filter(|stop| !dropped || matches!(stop, StopAfter::Edit))
+            .map(|stop| {
+                self.state.stopped_at = Some(commit.id);
+                match stop {
+                    StopAfter::Edit => Stop::Edit { commit: commit.id },
+                    StopAfter::EditMessage => Stop::EditMessage { commit: commit.id },
+                }
+            }))
+    }
+
+    fn revert(&mut self, commit: &BStr) -> Result<Option<Stop<'repo>>, Error> {
+        let commit = self.repo.rev_parse_single(commit).map_err(Box::new)?.detach();
+        let outcome = self.repo.revert(
+            commit,
+            self.head,
+            merge::commit::Options {
+                merge: self.options.merge.clone(),
+                mainline: None,
+                record_origin: false,
+                allow_empty: true,
+            },
+        )?;
+        match outcome.commit {
+            Some(id) => {
+                self.head = id.detach();
+                Ok(None)
+            }
+            None => {
+                self.state.stopped_at = Some(commit);
+                self.state.message = Some(outcome.message);
+                self.state.author = Some(outcome.author);
+                self.pending_encoding = outcome.encoding;
+                Ok(Some(Stop::Conflict {
+                    commit,
+                    merge: outcome.merge,
+                }))
+            }
+        }
+    }
+
+    fn squash(&mut self, commit: &BStr, fixup: Option<FixupMessage>) -> Result<Option<Stop<'repo>>, Error> {
+        let command = if fixup.is_some() { "fixup" } else { "squash" };
+        if self.head == self.state.onto {
+            return Err(Error::NoPreviousCommit { command });
+        }
+        let commit = Info::new::<Error>(
+            self.repo,
+            self.repo.rev_parse_single(commit).map_err(Box::new)?.detach(),
+        )?;
+        if commit.parents.len() > 1 {
+            return Err(Error::PickMergeCommit { id: commit.id });
+        }
+        let head = Info::new::<Error>(self.repo, self.head)?;
+        let base = commit
+            .parents
+            .first()
+            .copied()
+            .unwrap_or_else(|| ObjectId::empty_tree(self.repo.object_hash()));
+        let ancestor_label: BString = format!("parent of {}", commit.label()).into();
+        let outcome = self.merge_trees(
+            base,
+            self.head,
+            commit.id,
+            ancestor_label.as_ref(),
+            commit.label().as_ref(),
+        )?;
+
+        let (message, encoding): (BString, _) = match fixup {
+            None => {
+                let mut message = head.message.trim_end().to_owned();
+                message.extend_from_slice(b"\n\n");
+                message.extend_from_slice(&commit.message);
+                (message.into(), head.encoding.clone())
+            }
+            Some(FixupMessage::Discard) => (head.message.clone(), head.encoding.clone()),
+            Some(FixupMessage::Use | FixupMessage::Edit) => (commit.message.clone(), commit.encoding.clone()),
+        };
+        if outcome.has_conflicts() {
+            // Resolving the conflict amends the current commit, so it gets the same parents.
+            let mut parents = head.parents.iter().copied();
+            self.head = parents.next().unwrap_or(self.head);
+            self.pending_merge_parents = parents.collect();
+            self.pending_encoding = encoding;
+            self.state.stopped_at = Some(commit.id);
+            self.state.message = Some(message);
+            self.state.author = Some(head.author);
+            return Ok(Some(Stop::Conflict {
+                commit: commit.id,
+                merge: outcome,
+            }));
+        }
+        self.head = self.commit(
+            &head.author,
+            message.as_ref(),
+            encoding,
+            outcome.tree.detach(),
+            head.parents,
+        )?;
+        self.state.amend = Some(self.head);
+        Ok(matches!(fixup, Some(FixupMessage::Edit)).then(|| {
+            self.state.stopped_at = Some(commit.id);
+            Stop::EditMessage { commit: commit.id }
+        }))
+    }
+
+    fn merge(
+        &mut self,
+        original: Option<&BStr>,
+        edit_message: bool,
+        labels: &[BString],
+        subject: &BStr,
+    ) -> Result<Option<Stop<'repo>>, Error> {
+        let original = original
+            .map(|commit| -> Result<_, Error> {
+                Info::new::<Error>(
+                    self.repo,
+                    self.repo.rev_parse_single(commit).map_err(Box::new)?.detach(),
+                )
+            })
+            .transpose()?;
+        let mut parents = vec![self.head];
+        for label in labels {
+            parents.push(self.resolve_label(label.as_ref())?);
+        }
+
+        if let Some(original) = original.as_ref().filter(|original| original.parents == parents) {
+            if !self.options.force {
+                self.head = original.id;
+                return Ok(edit_message.then(|| {
+                    self.state.stopped_at = Some(original.id);
+                    Stop::EditMessage { commit: original.id }
+                }));
+            }
+        }
+
+        let message: BString = match &original {
+            Some(original) => original.message.clone(),
+            None if !subject.is_empty() => format!("{subject}\n").into(),
+            None => format!(
+                "Merge branch{} '{}'\n",
+                if labels.len() > 1 { "es" } else { "" },
+                labels
+                    .iter()
+                    .map(|label| label.to_str_lossy())
+                    .collect::<Vec<_>>()
+                    .join("' and '")
+            )
+            .into(),
+        };
+        let author = match &original {
+            Some(original) => original.author.clone(),
+            None => self.committer()?,
+        };
+
+        let mut tree = self.repo.find_object(self.head)?.peel_to_tree()?.id;
+        let mut merged = vec![self.head];
+        for (label, target) in labels.iter().zip(parents.iter().skip(1)) {
+            let base = match gix_revision::merge_base(*target, &merged, &mut self.repo.revision_graph())? {
+                Some(bases) => bases[0],
+                None => ObjectId::empty_tree(self.repo.object_hash()),
+            };
+            let outcome = self.merge_trees(base, tree, *target, "merge base".into(), label.as_ref())?;
+            if outcome.has_conflicts() {
+                let commit = original.as_ref().map_or(*target, |original| original.id);
+                self.state.stopped_at = Some(commit);
+                self.state.message = Some(message);
+                self.state.author = Some(author);
+                self.pending_merge_parents = parents.into_iter().skip(1).collect();
+                self.pending_encoding = original.and_then(|original| original.encoding);
+                return Ok(Some(Stop::Conflict { commit, merge: outcome }));
+            }
+            tree = outcome.tree.detach();
+            merged.push(*target);
+        }
+        let encoding = original.as_ref().and_then(|original| original.encoding.clone());
+        self.head = self.commit(&author, message.as_ref(), encoding, tree, parents)?;
+        Ok(original.filter(|_| edit_message).map(|original| {
+            self.state.stopped_at = Some(original.id);
+            Stop::EditMessage { commit: original.id }
+        }))
+    }
+
+    fn merge_trees(
+        &self,
+        base: ObjectId,
+        ours: ObjectId,
+        theirs: ObjectId,
+        base_label: &BStr,
+        theirs_label: &BStr,
+    ) -> Result<merge::tree::Outcome<'repo>, Error> {
+        Ok(self.repo.merge_trees(
+            base,
+            ours,
+            theirs,
+            gix_diff::blob::merge::Labels {
+                ancestor: Some(base_label),
+                current: Some("HEAD".into()),
+                other: Some(theirs_label),
+            },
+            self.options.merge.clone(),
+        )?)
+    }
+
+    fn stop_at_conflict(&mut self, commit: Info, merge: merge::tree::Outcome<'repo>) -> Stop<'repo> {
+        Stop::Conflict {
+            commit: self.stop_with_pending(commit),
+            merge,
+        }
+    }
+
+    /// Stop at `commit` and keep what's needed to create it once the caller resolved the situation, returning its id.
+    fn stop_with_pending(&mut self, commit: Info) -> ObjectId {
+        self.state.stopped_at = Some(commit.id);
+        self.state.message = Some(commit.message);
+        self.state.author = Some(commit.author);
+        self.pending_encoding = commit.encoding;
+        commit.id
+    }
+
+    fn resolve_label(&self, label: &BStr) -> Result<ObjectId, Error> {
+        let name = label_ref_name(label)?;
+        Ok(match self.repo.try_find_reference(name.as_ref())? {
+            Some(reference) => reference.into_fully_peeled_id()?.detach(),
+            None => self.repo.rev_parse_single(label).map_err(Box::new)?.detach(),
+        })
+    }
+
+    fn committer(&self) -> Result<gix_actor::Signature, Error> {
+        Ok(self.repo.committer().ok_or(Error::CommitterMissing)??.to_owned())
+    }
+
+    fn commit(
+        &self,
+        author: &gix_actor::Signature,
+        message: &BStr,
+        encoding: Option<BString>,
+        tree: ObjectId,
+        parents: impl IntoIterator<Item = ObjectId>,
+    ) -> Result<ObjectId, Error> {
+        let commit = gix_object::Commit {
+            tree,
+            parents: parents.into_iter().collect(),
+            author: author.clone(),
+            committer: self.committer()?,
+            encoding,
+            message: message.to_owned(),
+            extra_headers: Vec::new(),
+        };
+        Ok(self.repo.write_commit_object(commit).map_err(Box::new)?.0.detach())
+    }
+}
+
+fn label_ref_name(label: &BStr) -> Result<FullName, Error> {
+    let mut name = BString::from("refs/rewritten/");
+    name.extend_from_slice(label);
+    Ok(FullName::try_from(name)?)
+}
@@ -17,6 +17,8 @@ pub mod edit {
         LockTimeoutConfiguration(#[from] config::lock_timeout::Error),
         #[error(transparent)]
         ParseCommitterTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        Hook(#[from] crate::hook::Error),
     }
 }
 
@@ -1,4 +1,8 @@
-use crate::{config, config::tree::Branch, remote, Reference};
+use std::borrow::Cow;
+
+use gix_ref::FullNameRef;
+
+use crate::{config, config::tree::Branch, remote, repository::branch_remote_tracking_ref_name, Reference};
 
 /// Remotes
 impl<'repo> Reference<'repo> {
@@ -46,4 +50,17 @@ impl<'repo> Reference<'repo> {
             }),
         })
     }
+
+    /// Return the name of the local reference that tracks the remote counterpart of this branch in the given `direction`,
+    /// like `refs/remotes/origin/main` for `refs/heads/main`.
+    ///
+    /// See [`Repository::branch_remote_tracking_ref_name()`][crate::Repository::branch_remote_tracking_ref_name()] for details.
+    pub fn remote_tracking_ref_name(
+        &self,
+        direction: remote::Direction,
+    ) -> Option<Result<Cow<'repo, FullNameRef>, branch_remote_tracking_ref_name::Error>> {
+        self.repo
+            .branch_remote_tracking_ref_name(self.name(), direction)
+            .map(|res| res.map(|name| Cow::Owned(name.into_owned())))
+    }
 }
@@ -1,6 +1,8 @@
 #![allow(clippy::result_large_err)]
 use gix_protocol::transport::client::Transport;
 
+#[cfg(feature = "blocking-network-client")]
+use crate::config;
 use crate::{remote::Connection, Remote};
 
 mod error {
@@ -31,6 +33,18 @@ mod error {
             source: Box<gix_discover::is_git::Error>,
             url: gix_url::Url,
         },
+        #[cfg(feature = "blocking-network-client")]
+        #[error(transparent)]
+        InProcessUploadPack(#[from] config::boolean::Error),
+        #[cfg(feature = "blocking-network-client")]
+        #[error("Could not open the repository at \"{}\" to serve it in-process", path.display())]
+        OpenInProcess {
+            path: std::path::PathBuf,
+            source: Box<crate::open::Error>,
+        },
+        #[cfg(feature = "blocking-network-client")]
+        #[error("Could not start the thread serving the local repository")]
+        SpawnInProcess(#[source] std::io::Error),
     }
 
     impl gix_protocol::transport::IsSpuriousError for Error {
@@ -71,7 +85,10 @@ impl<'repo> Remote<'repo> {
     /// The transport used for connection can be configured via `transport_mut().configure()` assuming the actually
     /// used transport is well known. If that's not the case, the transport can be created by hand and passed to
     /// [to_connection_with_transport()][Self::to_connection_with_transport()].
-    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client-async-std"))]
+    ///
+    /// When fetching from a local repository with `gitoxide.core.inProcessUploadPack` enabled, it will be
+    /// [served from within this process](crate::remote::in_process) instead of by `git-upload-pack`.
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn connect(
         &self,
@@ -79,12 +96,23 @@ impl<'repo> Remote<'repo> {
     ) -> Result<Connection<'_, 'repo, Box<dyn Transport + Send>>, Error> {
         let (url, version) = self.sanitized_url_and_version(direction)?;
         #[cfg(feature = "blocking-network-client")]
+        if url.scheme == gix_url::Scheme::File
+            && direction == crate::remote::Direction::Fetch
+            && self.serve_in_process()?
+        {
+            let path = gix_path::from_bstr(url.path.as_ref()).into_owned();
+            let repo = crate::open(&path).map_err(|source| Error::OpenInProcess {
+                path,
+                source: Box::new(source),
+            })?;
+            let connection = crate::remote::in_process::connect(&repo).map_err(Error::SpawnInProcess)?;
+            return Ok(self.to_connection_with_transport(Box::new(connection)));
+        }
         let scheme_is_ssh = url.scheme == gix_url::Scheme::Ssh;
         let transport = gix_protocol::transport::connect(
             url,
             gix_protocol::transport::client::connect::Options {
                 version,
-                #[cfg(feature = "blocking-network-client")]
                 ssh: scheme_is_ssh
                     .then(|| self.repo.ssh_connect_options())
                     .transpose()?
@@ -95,6 +123,22 @@ impl<'repo> Remote<'repo> {
         Ok(self.to_connection_with_transport(transport))
     }
 
+    #[cfg(feature = "blocking-network-client")]
+    fn serve_in_process(&self) -> Result<bool, config::boolean::Error> {
+        use crate::config::tree::{gitoxide, Key};
+        Ok(self
+            .repo
+            .config
+            .resolved
+            .boolean_filter_by_key(
+                gitoxide::Core::IN_PROCESS_UPLOAD_PACK.logical_name().as_str(),
+                &mut self.repo.filter_config_section(),
+            )
+            .map(|value| gitoxide::Core::IN_PROCESS_UPLOAD_PACK.enrich_error(value))
+            .transpose()?
+            .unwrap_or(false))
+    }
+
     /// Produce the sanitized URL and protocol version to use as obtained by querying the repository configuration.
     ///
     /// This can be useful when using custom transports to allow additional configuration.
@@ -28,6 +28,15 @@ pub enum Error {
         path: std::path::PathBuf,
         source: std::io::Error,
     },
+    #[error("Could not obtain the current branch to learn which of the fetched refs are merge candidates")]
+    FetchHeadCurrentBranch(#[source] crate::reference::find::existing::Error),
+    #[error("Failed to write FETCH_HEAD at \"{}\"", path.display())]
+    WriteFetchHead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not obtain configuration to learn if FETCH_HEAD should be written")]
+    WriteFetchHeadConfig(#[source] config::boolean::Error),
     #[error(transparent)]
     ShallowOpen(#[from] crate::shallow::open::Error),
     #[error("Server lack feature {feature:?}: {description}")]
@@ -45,6 +54,24 @@ pub enum Error {
     RejectShallowRemote,
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[error("The server advertised the pack at {uri:?} which doesn't use any of the protocols configured in `fetch.uriProtocols`")]
+    PackfileUriProtocol { uri: crate::bstr::BString },
+    #[error("Could not download the pack at {uri:?}")]
+    PackfileUriDownload {
+        uri: crate::bstr::BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The pack downloaded from {uri:?} was expected to have hash {expected}, but it was {actual}")]
+    PackfileUriHashMismatch {
+        uri: crate::bstr::BString,
+        expected: gix_hash::ObjectId,
+        actual: gix_hash::ObjectId,
+    },
+    #[error("Could not move the pack downloaded from {uri:?} into the object database")]
+    PackfileUriStore {
+        uri: crate::bstr::BString,
+        source: std::io::Error,
+    },
 }
 
 impl gix_protocol::transport::IsSpuriousError for Error {
@@ -52,6 +79,9 @@ impl gix_protocol::transport::IsSpuriousError for Error {
         match self {
             Error::FetchResponse(err) => err.is_spurious(),
             Error::Client(err) => err.is_spurious(),
+            Error::PackfileUriDownload { source, .. } => source
+                .downcast_ref::<gix_protocol::transport::client::Error>()
+                .map_or(false, gix_protocol::transport::IsSpuriousError::is_spurious),
             _ => false,
         }
     }
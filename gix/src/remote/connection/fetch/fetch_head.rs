@@ -0,0 +1,86 @@
+use std::borrow::Cow;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote,
+    remote::fetch::{Error, RefMap, SpecIndex},
+    Remote,
+};
+
+/// Write `FETCH_HEAD` into the git directory of the repository of `remote` with one entry per mapping in `ref_map`,
+/// with merge candidates first, like `git` would.
+///
+/// If the refspecs of `remote` were set by the caller instead of being the configured ones, all refs matched by them are merge candidates.
+/// Otherwise, merge candidates are the remote refs configured in `branch.<name>.merge` if `remote` is the one configured for the
+/// current branch, or the ones matched by the first refspec if it isn't a pattern. All other refs are written as `not-for-merge`.
+#[allow(clippy::result_large_err)]
+pub(crate) fn write(remote: &Remote<'_>, ref_map: &RefMap) -> Result<(), Error> {
+    let repo = remote.repo;
+    let refspecs = remote.refspecs(remote::Direction::Fetch);
+    let uses_configured_refspecs = remote
+        .name()
+        .and_then(|name| repo.find_remote(name.as_bstr()).ok())
+        .map_or(false, |configured| {
+            configured.refspecs(remote::Direction::Fetch) == refspecs
+        });
+    let branch_merge_ref = if uses_configured_refspecs {
+        repo.head_name()
+            .map_err(Error::FetchHeadCurrentBranch)?
+            .and_then(|head| {
+                let short_name = head.shorten().to_owned();
+                let branch_remote = repo.branch_remote_name(short_name.as_bstr())?;
+                (Some(branch_remote.as_bstr()) == remote.name().map(remote::Name::as_bstr))
+                    .then(|| repo.branch_remote_ref(short_name.as_bstr()))
+                    .flatten()
+                    .and_then(Result::ok)
+                    .map(Cow::into_owned)
+            })
+    } else {
+        None
+    };
+    let first_spec_is_exact = refspecs.first().map_or(false, |spec| {
+        spec.to_ref().source().map_or(false, |source| !source.contains(&b'*'))
+    });
+
+    let url = remote
+        .url(remote::Direction::Fetch)
+        .map(|url| {
+            let mut url = url.clone();
+            url.set_user(None);
+            url.to_bstring()
+        })
+        .or_else(|| remote.name().map(|name| name.as_bstr().to_owned()))
+        .unwrap_or_default();
+
+    let mut entries: Vec<_> = ref_map
+        .mappings
+        .iter()
+        .filter_map(|mapping| {
+            let id = mapping.remote.as_id()?.to_owned();
+            let name: Cow<'_, BStr> = match mapping.remote.as_name() {
+                Some(name) => name.into(),
+                None => BString::from(id.to_string()).into(),
+            };
+            let is_merge_candidate = if uses_configured_refspecs {
+                match &branch_merge_ref {
+                    Some(merge_ref) => mapping.remote.as_name() == Some(merge_ref.as_bstr()),
+                    None => first_spec_is_exact && mapping.spec_index == SpecIndex::ExplicitInRemote(0),
+                }
+            } else {
+                mapping.spec_index.implicit_index().is_none()
+            };
+            Some(gix_fetchhead::Entry {
+                id,
+                not_for_merge: !is_merge_candidate,
+                description: gix_fetchhead::encode::description(name.as_ref(), url.as_bstr()),
+            })
+        })
+        .collect();
+    // `sort_by_key()` is stable, so merge candidates come first but the order of the remote refs is retained otherwise.
+    entries.sort_by_key(|entry| entry.not_for_merge);
+
+    let mut buf = Vec::new();
+    gix_fetchhead::write_to(&entries, &mut buf).expect("write to memory always works");
+    let path = repo.git_dir().join("FETCH_HEAD");
+    std::fs::write(&path, buf).map_err(|source| Error::WriteFetchHead { path, source })
+}
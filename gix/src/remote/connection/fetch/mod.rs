@@ -62,6 +62,11 @@ pub enum Status {
         negotiate: outcome::Negotiate,
         /// Information collected while writing the pack and its index.
         write_pack_bundle: gix_pack::bundle::write::Outcome,
+        /// Information collected while writing each pack downloaded from the locations the server advertised
+        /// via `packfile-uris`, in the order they were advertised.
+        ///
+        /// It is empty unless `fetch.uriProtocols` is configured and the server made use of it.
+        packfile_uri_bundles: Vec<gix_pack::bundle::write::Outcome>,
         /// Information collected while updating references.
         update_refs: refs::update::Outcome,
     },
@@ -193,6 +198,9 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            wait_for_done: false,
+            write_fetch_head: None,
+            filter: None,
         })
     }
 }
@@ -205,9 +213,33 @@ where
     pub fn ref_map(&self) -> &RefMap {
         &self.ref_map
     }
+
+    /// Ask the server for the bundles it recommends to download before fetching from it, returned as the `key=value` pairs
+    /// that describe a [bundle list](crate::bundle::List), using `progress` for feedback.
+    ///
+    /// This requires the server to advertise the `bundle-uri` capability, which is only possible in protocol V2.
+    #[gix_protocol::maybe_async::maybe_async]
+    pub async fn bundle_uri(
+        &mut self,
+        mut progress: impl Progress,
+    ) -> Result<Vec<gix_protocol::bundle_uri::KeyValue>, gix_protocol::bundle_uri::Error> {
+        let con = self
+            .con
+            .as_mut()
+            .expect("connection is present until the pack is received");
+        let agent_feature = con.remote.repo.config.user_agent_tuple();
+        gix_protocol::bundle_uri(
+            &mut con.transport,
+            &self.ref_map.handshake.capabilities,
+            vec![agent_feature],
+            &mut progress,
+        )
+        .await
+    }
 }
 
 mod config;
+mod fetch_head;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
@@ -224,6 +256,9 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    wait_for_done: bool,
+    write_fetch_head: Option<bool>,
+    filter: Option<BString>,
 }
 
 /// Builder
@@ -265,6 +300,33 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// If enabled and if the server supports it, ask the server to never end the negotiation by sending `ready`,
+    /// so that we decide when enough common commits were found.
+    ///
+    /// This can lead to smaller packs when the server would otherwise consider itself ready too early, at the cost of more rounds.
+    /// *Has no effect if the server doesn't support the `wait-for-done` feature, which is only available in protocol V2.*
+    pub fn with_wait_for_done(mut self, enabled: bool) -> Self {
+        self.wait_for_done = enabled;
+        self
+    }
+
+    /// If `enabled`, write `FETCH_HEAD` after a successful fetch to record which refs were fetched and which of them are
+    /// candidates for merging, overriding the `fetch.writeFetchHead` configuration which defaults to `true`.
+    ///
+    /// *Has no effect in dry-run mode, which never writes `FETCH_HEAD`.*
+    pub fn with_write_fetch_head(mut self, enabled: bool) -> Self {
+        self.write_fetch_head = Some(enabled);
+        self
+    }
+
+    /// If set, ask the server to omit objects as described by the filter `spec`, like `blob:none`, similar to `git fetch --filter`.
+    ///
+    /// *Has no effect if the server doesn't support the `filter` feature.*
+    pub fn with_filter(mut self, spec: Option<BString>) -> Self {
+        self.filter = spec;
+        self
+    }
 }
 
 impl<'remote, 'repo, T> Drop for Prepare<'remote, 'repo, T>
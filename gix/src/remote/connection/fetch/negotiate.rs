@@ -5,7 +5,10 @@ use gix_negotiate::Flags;
 use gix_odb::HeaderExt;
 use gix_pack::Find;
 
-use crate::remote::{fetch, fetch::Shallow};
+use crate::{
+    config::tree::{Fetch, Key},
+    remote::{fetch, fetch::Shallow},
+};
 
 type Queue = gix_revision::PriorityQueue<SecondsSinceUnixEpoch, gix_hash::ObjectId>;
 
@@ -25,6 +28,11 @@ pub enum Error {
     ObtainRefDuringIteration(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error(transparent)]
     LoadIndex(#[from] gix_odb::store::load_index::Error),
+    #[error("The negotiation tip {tip:?} could not be resolved to an object")]
+    NegotiationTip {
+        tip: crate::bstr::BString,
+        source: Box<crate::revision::spec::parse::single::Error>,
+    },
 }
 
 #[must_use]
@@ -59,7 +67,7 @@ pub(crate) enum Action {
 /// Additionally, it does what's done in `transport.c` and we check if a fetch is actually needed as at least one advertised ref changed.
 ///
 /// Finally, we also mark tips in the `negotiator` in one go to avoid traversing all refs twice, since we naturally encounter all tips during
-/// our own walk.
+/// our own walk. If `negotiation_tips` are set, only these are used as tips instead, just like `git` does with `fetch.negotiationTip`.
 ///
 /// Return whether or not we should negotiate, along with a queue for later use.
 pub(crate) fn mark_complete_and_common_ref(
@@ -68,6 +76,7 @@ pub(crate) fn mark_complete_and_common_ref(
     graph: &mut gix_negotiate::Graph<'_>,
     ref_map: &fetch::RefMap,
     shallow: &fetch::Shallow,
+    negotiation_tips: Option<&[gix_hash::ObjectId]>,
     mapping_is_ignored: impl Fn(&fetch::Mapping) -> bool,
 ) -> Result<Action, Error> {
     let _span = gix_trace::detail!("mark_complete_and_common_ref", mappings = ref_map.mappings.len());
@@ -173,8 +182,20 @@ pub(crate) fn mark_complete_and_common_ref(
     // As negotiators currently may rely on getting `known_common` calls first and tips after, we adhere to that which is the only
     // reason we cached the set of tips.
     gix_trace::detail!("mark tips", num_tips = tips.len()).into_scope(|| -> Result<_, Error> {
-        for tip in tips.iter_unordered() {
-            negotiator.add_tip(*tip, graph)?;
+        match negotiation_tips {
+            Some(negotiation_tips) => {
+                for tip in negotiation_tips {
+                    // Tips that aren't commits can't be negotiated, just like in `git`.
+                    if graph.try_lookup_or_insert_commit(*tip, |_| {})?.is_some() {
+                        negotiator.add_tip(*tip, graph)?;
+                    }
+                }
+            }
+            None => {
+                for tip in tips.iter_unordered() {
+                    negotiator.add_tip(*tip, graph)?;
+                }
+            }
         }
         Ok(())
     })?;
@@ -184,6 +205,48 @@ pub(crate) fn mark_complete_and_common_ref(
     })
 }
 
+/// Resolve the tips configured in `fetch.negotiationTip`, which are either revisions or globs to match ref names with,
+/// or return `None` if there are none so all refs should be used as tips.
+///
+/// Like in `git`, globs are prefixed with `refs/` if needed and can match across path separators.
+pub(crate) fn negotiation_tips(repo: &crate::Repository) -> Result<Option<Vec<gix_hash::ObjectId>>, Error> {
+    use crate::bstr::{ByteSlice, ByteVec};
+    let Some(values) = repo.config.resolved.strings_filter_by_key(
+        Fetch::NEGOTIATION_TIP.logical_name().as_str(),
+        &mut repo.filter_config_section(),
+    ) else {
+        return Ok(None);
+    };
+    let mut tips = Vec::new();
+    for value in values {
+        if !value.contains_str("*") && !value.contains_str("?") && !value.contains_str("[") {
+            let id = repo
+                .rev_parse_single(value.as_bstr())
+                .map_err(|err| Error::NegotiationTip {
+                    tip: value.as_ref().to_owned(),
+                    source: err.into(),
+                })?;
+            tips.push(id.detach());
+            continue;
+        }
+        let mut pattern = value.into_owned();
+        if !pattern.starts_with(b"refs/") {
+            pattern.insert_str(0, "refs/");
+        }
+        for reference in repo.references()?.all()?.peeled() {
+            let reference = reference?;
+            if gix_glob::wildmatch(
+                pattern.as_bstr(),
+                reference.name().as_bstr(),
+                gix_glob::wildmatch::Mode::empty(),
+            ) {
+                tips.push(reference.id().detach());
+            }
+        }
+    }
+    Ok(Some(tips))
+}
+
 /// Create a predicate that checks if a refspec mapping should be ignored.
 ///
 /// We want to ignore mappings during negotiation if they would be handled implicitly by the server, which is the case
@@ -227,6 +290,8 @@ pub(crate) fn add_wants(
         .zip(mapping_known)
         .filter_map(|(m, known)| (is_shallow || !*known).then_some(m))
         .filter(|m| !mapping_is_ignored(m));
+    // Wanted objects are typically missing, and must not be fetched from the promisor remote one by one.
+    let objects = repo.objects_without_promisor();
     for want in wants {
         let id_on_remote = want.remote.as_id();
         if !arguments.can_use_ref_in_want() || matches!(want.remote, fetch::Source::ObjectId(_)) {
@@ -241,7 +306,14 @@ pub(crate) fn add_wants(
             )
         }
         let id_is_annotated_tag_we_have = id_on_remote
-            .and_then(|id| repo.objects.header(id).ok().map(|h| (id, h)))
+            .and_then(|id| {
+                objects
+                    .as_ref()
+                    .unwrap_or(&repo.objects)
+                    .header(id)
+                    .ok()
+                    .map(|h| (id, h))
+            })
             .filter(|(_, h)| h.kind() == gix_object::Kind::Tag)
             .map(|(id, _)| id);
         if let Some(tag_on_remote) = id_is_annotated_tag_we_have {
@@ -4,6 +4,7 @@ use std::{
 };
 
 use gix_odb::{store::RefreshMode, FindExt};
+use gix_protocol::transport::bstr::{BStr, ByteSlice};
 use gix_protocol::{
     fetch::Arguments,
     transport::{client::Transport, packetline::read::ProgressAction},
@@ -68,9 +69,19 @@ where
     /// Currently the entire process of resolving a pack is blocking the executor. This can be fixed using the `blocking` crate, but it
     /// didn't seem worth the tradeoff of having more complex code.
     ///
+    /// ### Packfile URIs
+    ///
+    /// If `fetch.uriProtocols` is set to protocols we can download with, i.e. `http` or `https` if an HTTP transport is compiled in,
+    /// and the server supports it, the server may send only parts of the pack and advertise other packs to download instead.
+    /// These are downloaded after the main pack was received, verified to match the hash advertised by the server and written
+    /// next to it into the object database, along with their own `.keep` files.
+    /// If any of them cannot be obtained, all packs written as part of this operation are removed again and an error is returned.
+    ///
     /// ### Configuration
     ///
     /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
+    /// - `fetch.uriProtocols` is read to learn which protocols are acceptable to download additional packs with.
+    /// - `fetch.writeFetchHead` is read to learn if `FETCH_HEAD` should be written, unless [overridden](Self::with_write_fetch_head()).
     ///
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn receive<P>(mut self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
@@ -89,12 +100,17 @@ where
         let repo = con.remote.repo;
         let fetch_features = {
             let mut f = fetch.default_features(protocol_version, &handshake.capabilities);
+            if con.transport.connection_persists_across_multiple_requests() {
+                // Like `git`, only skip sending `done` on stateless connections as it's only there to save a round-trip.
+                f.retain(|(name, _)| *name != "no-done");
+            }
             f.push(repo.config.user_agent_tuple());
             f
         };
 
         gix_protocol::fetch::Response::check_required_features(protocol_version, &fetch_features)?;
         let sideband_all = fetch_features.iter().any(|(n, _)| *n == "sideband-all");
+        let no_done = fetch_features.iter().any(|(n, _)| *n == "no-done");
         let mut arguments = gix_protocol::fetch::Arguments::new(protocol_version, fetch_features);
         if matches!(con.remote.fetch_tags, crate::remote::fetch::Tags::Included) {
             if !arguments.can_use_include_tag() {
@@ -107,7 +123,17 @@ where
             }
             arguments.use_include_tag();
         }
+        if self.wait_for_done && arguments.can_use_wait_for_done() {
+            arguments.use_wait_for_done();
+        }
+        let packfile_uri_protocols = packfile_uri_protocols(repo);
+        if !packfile_uri_protocols.is_empty() && arguments.can_use_packfile_uris() {
+            arguments.use_packfile_uris(packfile_uri_protocols.iter().map(String::as_str));
+        }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
+        if let Some(spec) = self.filter.as_ref().filter(|_| arguments.can_use_filter()) {
+            arguments.filter(spec.to_str_lossy().as_ref());
+        }
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -134,6 +160,7 @@ where
             r.objects.unset_object_cache();
             r
         };
+        let negotiation_tips = negotiate::negotiation_tips(repo)?;
         let mut graph = graph_repo.revision_graph();
         let action = negotiate::mark_complete_and_common_ref(
             &graph_repo,
@@ -141,13 +168,14 @@ where
             &mut graph,
             &self.ref_map,
             &self.shallow,
+            negotiation_tips.as_deref(),
             negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
         )?;
         let mut previous_response = None::<gix_protocol::fetch::Response>;
-        let (mut write_pack_bundle, negotiate) = match &action {
+        let (mut write_pack_bundle, mut packfile_uri_bundles, negotiate) = match &action {
             negotiate::Action::NoChange | negotiate::Action::SkipToRefUpdate => {
                 gix_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
-                (None, None)
+                (None, Vec::new(), None)
             }
             negotiate::Action::MustNegotiate {
                 remote_ref_target_known,
@@ -172,10 +200,15 @@ where
                     progress.step();
                     progress.set_name(format!("negotiate (round {})", rounds.len() + 1));
 
+                    // Without `no-done`, or if the server doesn't support `multi_ack`, it waits for us to be done after it signalled
+                    // to be ready, so there is no need to send more haves.
+                    let server_is_ready = previous_response
+                        .as_ref()
+                        .map_or(false, gix_protocol::fetch::Response::is_ready);
                     let is_done = match negotiate::one_round(
                         negotiator.deref_mut(),
                         &mut graph,
-                        haves_to_send,
+                        if server_is_ready { 0 } else { haves_to_send },
                         &mut arguments,
                         previous_response.as_ref(),
                         common.as_mut(),
@@ -192,7 +225,8 @@ where
                                 haves_to_send,
                                 previous_response_had_at_least_one_in_common: ack_seen,
                             });
-                            let is_done = haves_sent != haves_to_send || (seen_ack && in_vain >= 256);
+                            let is_done =
+                                server_is_ready || haves_sent != haves_to_send || (seen_ack && in_vain >= 256);
                             haves_to_send = gix_negotiate::window_size(is_stateless, haves_to_send);
                             is_done
                         }
@@ -205,8 +239,13 @@ where
                     if sideband_all {
                         setup_remote_progress(progress, &mut reader, should_interrupt);
                     }
-                    let response =
-                        gix_protocol::fetch::Response::from_line_reader(protocol_version, &mut reader, is_done).await?;
+                    let response = gix_protocol::fetch::Response::from_line_reader(
+                        protocol_version,
+                        &mut reader,
+                        is_done,
+                        no_done,
+                    )
+                    .await?;
                     let has_pack = response.has_pack();
                     previous_response = Some(response);
                     if has_pack {
@@ -255,13 +294,13 @@ where
                             reader
                         },
                         Some(repo.objects.store_ref().path().join("pack")),
-                        progress,
+                        &mut *progress,
                         should_interrupt,
                         Some(Box::new({
                             let repo = repo.clone();
                             move |oid, buf| repo.objects.find(oid, buf).ok()
                         })),
-                        options,
+                        options.clone(),
                     )?)
                 } else {
                     drop(reader);
@@ -272,12 +311,37 @@ where
                     gix_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
                 }
 
+                let packfile_uri_bundles = match write_pack_bundle.as_ref() {
+                    Some(bundle) if !previous_response.packfile_uris().is_empty() => {
+                        progress.step();
+                        progress.set_name("receiving packs from URIs");
+                        download_packfile_uris(
+                            repo,
+                            previous_response.packfile_uris(),
+                            &packfile_uri_protocols,
+                            con.remote.name().map(crate::remote::Name::as_bstr),
+                            progress,
+                            should_interrupt,
+                            options,
+                        )
+                        .map_err(|err| {
+                            remove_bundle_files(bundle);
+                            err
+                        })?
+                    }
+                    _ => Vec::new(),
+                };
+
                 if let Some(shallow_lock) = shallow_lock {
                     if !previous_response.shallow_updates().is_empty() {
                         crate::shallow::write(shallow_lock, shallow_commits, previous_response.shallow_updates())?;
                     }
                 }
-                (write_pack_bundle, Some(outcome::Negotiate { graph, rounds }))
+                (
+                    write_pack_bundle,
+                    packfile_uri_bundles,
+                    Some(outcome::Negotiate { graph, rounds }),
+                )
             }
         };
 
@@ -295,18 +359,43 @@ where
         )?;
 
         if let Some(bundle) = write_pack_bundle.as_mut() {
-            if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
-                if let Some(path) = bundle.keep_path.take() {
-                    std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+            let refs_changed = !update_refs.edits.is_empty();
+            for bundle in std::iter::once(bundle).chain(packfile_uri_bundles.iter_mut()) {
+                if refs_changed || bundle.index.num_objects == 0 {
+                    if let Some(path) = bundle.keep_path.take() {
+                        std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+                    }
                 }
             }
         }
 
+        if matches!(self.dry_run, fetch::DryRun::No) {
+            let write_fetch_head = match self.write_fetch_head {
+                Some(enabled) => enabled,
+                None => repo
+                    .config
+                    .resolved
+                    .boolean_filter_by_key(
+                        Fetch::WRITE_FETCH_HEAD.logical_name().as_str(),
+                        &mut repo.filter_config_section(),
+                    )
+                    .map(|val| Fetch::WRITE_FETCH_HEAD.enrich_error(val))
+                    .transpose()
+                    .with_leniency(repo.config.lenient_config)
+                    .map_err(Error::WriteFetchHeadConfig)?
+                    .unwrap_or(true),
+            };
+            if write_fetch_head {
+                super::fetch_head::write(con.remote, &self.ref_map)?;
+            }
+        }
+
         let out = Outcome {
             ref_map: std::mem::take(&mut self.ref_map),
             status: match write_pack_bundle {
                 Some(write_pack_bundle) => Status::Change {
                     write_pack_bundle,
+                    packfile_uri_bundles,
                     update_refs,
                     negotiate: negotiate.expect("if we have a pack, we always negotiated it"),
                 },
@@ -321,6 +410,164 @@ where
     }
 }
 
+/// Return the protocols configured in `fetch.uriProtocols` which we are able to download packs with.
+fn packfile_uri_protocols(repo: &Repository) -> Vec<String> {
+    let can_download_via_http = cfg!(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ));
+    repo.config
+        .resolved
+        .string_filter_by_key(
+            Fetch::URI_PROTOCOLS.logical_name().as_str(),
+            &mut repo.filter_config_section(),
+        )
+        .map(|protocols| {
+            protocols
+                .to_str_lossy()
+                .split(',')
+                .map(str::trim)
+                .filter(|protocol| can_download_via_http && matches!(*protocol, "http" | "https"))
+                .map(ToOwned::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Download all packs at `uris`, which must use one of the given `protocols`, and write them into the object database of `repo`.
+/// On error, all packs written so far are removed.
+fn download_packfile_uris<P>(
+    repo: &Repository,
+    uris: &[gix_protocol::fetch::response::PackfileUri],
+    protocols: &[String],
+    remote_name: Option<&BStr>,
+    progress: &mut P,
+    should_interrupt: &AtomicBool,
+    options: gix_pack::bundle::write::Options,
+) -> Result<Vec<gix_pack::bundle::write::Outcome>, Error>
+where
+    P: Progress,
+    P::SubProgress: 'static,
+{
+    let mut bundles = Vec::with_capacity(uris.len());
+    for uri in uris {
+        let res = download_packfile_uri(
+            repo,
+            uri,
+            protocols,
+            remote_name,
+            &mut *progress,
+            should_interrupt,
+            options.clone(),
+        );
+        match res {
+            Ok(bundle) => bundles.push(bundle),
+            Err(err) => {
+                for bundle in &bundles {
+                    remove_bundle_files(bundle);
+                }
+                return Err(err);
+            }
+        }
+    }
+    Ok(bundles)
+}
+
+fn download_packfile_uri<P>(
+    repo: &Repository,
+    uri: &gix_protocol::fetch::response::PackfileUri,
+    protocols: &[String],
+    remote_name: Option<&BStr>,
+    progress: &mut P,
+    should_interrupt: &AtomicBool,
+    options: gix_pack::bundle::write::Options,
+) -> Result<gix_pack::bundle::write::Outcome, Error>
+where
+    P: Progress,
+    P::SubProgress: 'static,
+{
+    let url = uri
+        .uri
+        .to_str()
+        .ok()
+        .filter(|url| {
+            url.split_once("://")
+                .map_or(false, |(scheme, _)| protocols.iter().any(|p| p == scheme))
+        })
+        .ok_or_else(|| Error::PackfileUriProtocol { uri: uri.uri.clone() })?;
+    // Like `git receive-pack`, keep the pack in quarantine until it is known to be the one that was advertised.
+    let objects_dir = repo.objects.store_ref().path();
+    let incoming = objects_dir.join(format!("incoming-{}-{}", uri.hash.to_hex(), std::process::id()));
+    let store_error = |source| Error::PackfileUriStore {
+        uri: uri.uri.clone(),
+        source,
+    };
+    std::fs::create_dir(&incoming).map_err(store_error)?;
+    let res = repo
+        .http_get(url, remote_name, |pack| {
+            gix_pack::Bundle::write_to_directory(
+                pack,
+                Some(&incoming),
+                progress,
+                should_interrupt,
+                Some(Box::new({
+                    let repo = repo.clone();
+                    move |oid, buf| repo.objects.find(oid, buf).ok()
+                })),
+                options,
+            )
+        })
+        .map_err(|source| Error::PackfileUriDownload {
+            uri: uri.uri.clone(),
+            source,
+        })
+        .and_then(|bundle| {
+            if bundle.index.data_hash != uri.hash {
+                return Err(Error::PackfileUriHashMismatch {
+                    uri: uri.uri.clone(),
+                    expected: uri.hash,
+                    actual: bundle.index.data_hash,
+                });
+            }
+            move_bundle_files(bundle, &objects_dir.join("pack")).map_err(store_error)
+        });
+    std::fs::remove_dir_all(&incoming).ok();
+    res
+}
+
+/// Move all files written for `bundle` into `pack_dir`, with the index last so the pack isn't picked up before it is complete.
+fn move_bundle_files(
+    mut bundle: gix_pack::bundle::write::Outcome,
+    pack_dir: &std::path::Path,
+) -> std::io::Result<gix_pack::bundle::write::Outcome> {
+    let mut moved = Vec::new();
+    for path in [&mut bundle.keep_path, &mut bundle.data_path, &mut bundle.index_path]
+        .into_iter()
+        .filter_map(Option::as_mut)
+    {
+        let destination = pack_dir.join(path.file_name().expect("written files have a name"));
+        if let Err(err) = std::fs::rename(&*path, &destination) {
+            for path in moved {
+                std::fs::remove_file(path).ok();
+            }
+            return Err(err);
+        }
+        moved.push(destination.clone());
+        *path = destination;
+    }
+    Ok(bundle)
+}
+
+/// Remove all files written for `bundle`, ignoring errors as this is only done to clean up after a failure.
+fn remove_bundle_files(bundle: &gix_pack::bundle::write::Outcome) {
+    for path in [&bundle.data_path, &bundle.index_path, &bundle.keep_path]
+        .into_iter()
+        .flatten()
+    {
+        std::fs::remove_file(path).ok();
+    }
+}
+
 fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
     gix_lock::File::acquire_to_update_resource(repo.shallow_file(), gix_lock::acquire::Fail::Immediately, None)
         .map_err(Into::into)
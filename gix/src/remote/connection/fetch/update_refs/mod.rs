@@ -248,9 +248,8 @@ pub(crate) fn update(
                     }
                 )
                 .prepare(edits, file_lock_fail, packed_refs_lock_fail)
-                .map_err(crate::reference::edit::Error::from)?
-                .commit(repo.committer().transpose().map_err(|err| update::Error::EditReferences(crate::reference::edit::Error::ParseCommitterTime(err)))?)
-                .map_err(crate::reference::edit::Error::from)?
+                .map_err(crate::reference::edit::Error::from)
+                .and_then(|transaction| repo.commit_reference_transaction(transaction))?
         }
         fetch::DryRun::Yes => edits,
     };
@@ -0,0 +1,46 @@
+//! A transport to local repositories which serves them from within this process, without spawning `git-upload-pack`.
+//!
+//! The repository to fetch from is opened as [`Repository`](crate::Repository) and served by a thread that speaks
+//! protocol V2 over in-memory pipes, which makes it usable in environments where `git` isn't installed.
+//! It is used by [`Remote::connect()`](crate::Remote::connect()) for local repositories if
+//! `gitoxide.core.inProcessUploadPack` is set, or it can be used with
+//! [`Remote::to_connection_with_transport()`](crate::Remote::to_connection_with_transport()) directly.
+//!
+//! Note that it's only available for the blocking client, and that shallow fetches and partial clones aren't supported
+//! by the server yet.
+//! Like with protocol V2 in `git-upload-pack`, clients may ask for any object in the object database, even if it isn't
+//! reachable from any reference, so only serve repositories whose objects may all be read by the client.
+use gix_features::io::pipe;
+use gix_protocol::transport::{client::git, Protocol};
+
+mod upload_pack;
+
+/// The amount of writes to buffer in each pipe, similar to the buffer of pipes provided by the operating system.
+///
+/// This is needed as both sides may write without reading, like when the client ends the interaction while the server
+/// finishes its response.
+const IN_FLIGHT_WRITES: usize = 64;
+
+/// The connection to a repository served in-process, as returned by [`connect()`].
+pub type Connection = git::Connection<pipe::Reader, pipe::Writer>;
+
+/// Connect to `repo` by serving it from a thread within this process, similar to what `git-upload-pack` would do.
+///
+/// The returned connection always uses protocol V2, and the thread stops once the connection is dropped.
+pub fn connect(repo: &crate::Repository) -> std::io::Result<Connection> {
+    let (client_write, server_read) = pipe::unidirectional(IN_FLIGHT_WRITES);
+    let (server_write, client_read) = pipe::unidirectional(IN_FLIGHT_WRITES);
+    let path = gix_path::into_bstr(repo.git_dir()).into_owned();
+    let repo = repo.clone().into_sync();
+    std::thread::Builder::new()
+        .name("gix-upload-pack".into())
+        .spawn(move || upload_pack::serve(repo.to_thread_local(), server_read, server_write))?;
+    Ok(git::Connection::new(
+        client_read,
+        client_write,
+        Protocol::V2,
+        path,
+        None::<(&str, _)>,
+        git::ConnectMode::Process,
+    ))
+}
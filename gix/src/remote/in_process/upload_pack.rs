@@ -0,0 +1,308 @@
+use std::{io::Write, sync::atomic::AtomicBool};
+
+use gix_features::io::pipe;
+use gix_hash::ObjectId;
+use gix_odb::Find;
+use gix_protocol::transport::packetline::{encode, Channel, PacketLineRef, StreamingPeekableIter};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    Repository,
+};
+
+/// The largest amount of pack data that fits into a single side-band packet line.
+const MAX_BAND_DATA_LEN: usize = 65515;
+/// The maximum amount of symbolic references to follow before giving up.
+const MAX_SYMBOLIC_REF_DEPTH: usize = 5;
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    DecodeLine(#[from] gix_protocol::transport::packetline::decode::Error),
+    #[error("unknown command {command:?}")]
+    UnknownCommand { command: BString },
+    #[error("unexpected line: {line:?}")]
+    UnexpectedArgument { line: BString },
+    #[error(transparent)]
+    InvalidObjectId(#[from] gix_hash::decode::Error),
+    #[error("upload-pack: not our ref {id}")]
+    NotOurRef { id: ObjectId },
+    #[error(transparent)]
+    Head(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    OpenReferences(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    InitReferenceIter(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    IterateReferences(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    Pack(#[from] Box<crate::bundle::create::Error>),
+}
+
+/// Serve `repo` to the client on the other end of `read` and `out` until the client disconnects.
+///
+/// Errors are sent to the client, which is the only one who could act on them.
+pub(super) fn serve(repo: Repository, read: pipe::Reader, mut out: pipe::Writer) {
+    if let Err(err) = serve_requests(&repo, read, &mut out) {
+        // The client might be gone already, in which case there is nobody to tell.
+        encode::error_to_write(err.to_string().as_bytes(), &mut out).ok();
+    }
+}
+
+fn serve_requests(repo: &Repository, read: pipe::Reader, out: &mut pipe::Writer) -> Result<(), Error> {
+    for line in [
+        "version 2".to_string(),
+        format!("agent={}", gix_protocol::agent(crate::env::agent())),
+        "ls-refs=unborn".into(),
+        "fetch".into(),
+        format!(
+            "object-format={}",
+            match repo.object_hash() {
+                gix_hash::Kind::Sha1 => "sha1",
+            }
+        ),
+    ] {
+        encode::text_to_write(line.as_bytes(), &mut *out)?;
+    }
+    encode::flush_to_write(&mut *out)?;
+
+    let mut lines = StreamingPeekableIter::new(read, &[PacketLineRef::Flush]);
+    loop {
+        let mut command = None;
+        let mut arguments = Vec::<BString>::new();
+        let mut in_arguments = false;
+        let mut is_empty = true;
+        while let Some(line) = lines.read_line() {
+            let line = match line {
+                Ok(line) => line?,
+                Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof && is_empty => return Ok(()),
+                Err(err) => return Err(err.into()),
+            };
+            is_empty = false;
+            match line {
+                PacketLineRef::Delimiter => in_arguments = true,
+                PacketLineRef::Data(data) => {
+                    let data = data.trim_end_with(|c| c == '\n');
+                    if in_arguments {
+                        arguments.push(data.into());
+                    } else if let Some(name) = data.strip_prefix(b"command=") {
+                        command = Some(BString::from(name));
+                    }
+                    // Capabilities like `agent` or `object-format` don't affect what we send.
+                }
+                PacketLineRef::Flush | PacketLineRef::ResponseEnd => {}
+            }
+        }
+        if lines.stopped_at().is_none() {
+            return Ok(());
+        }
+        lines.reset();
+
+        match command.as_ref().map(|c| c.as_slice()) {
+            Some(b"ls-refs") => ls_refs(repo, &arguments, out)?,
+            Some(b"fetch") => fetch(repo, &arguments, out)?,
+            Some(command) => {
+                return Err(Error::UnknownCommand {
+                    command: command.into(),
+                })
+            }
+            None => {}
+        }
+    }
+}
+
+fn ls_refs(repo: &Repository, arguments: &[BString], out: &mut pipe::Writer) -> Result<(), Error> {
+    let (mut peel, mut symrefs, mut unborn) = (false, false, false);
+    let mut prefixes = Vec::<&[u8]>::new();
+    for argument in arguments {
+        match argument.as_slice() {
+            b"peel" => peel = true,
+            b"symrefs" => symrefs = true,
+            b"unborn" => unborn = true,
+            arg => match arg.strip_prefix(b"ref-prefix ") {
+                Some(prefix) => prefixes.push(prefix),
+                None => {
+                    return Err(Error::UnexpectedArgument {
+                        line: argument.to_owned(),
+                    })
+                }
+            },
+        }
+    }
+    let is_wanted = |name: &[u8]| prefixes.is_empty() || prefixes.iter().any(|prefix| name.starts_with(prefix));
+
+    if is_wanted(b"HEAD") {
+        let head = repo.find_reference("HEAD")?;
+        let line = match head.target() {
+            gix_ref::TargetRef::Symbolic(target) => match ref_line(repo, head.clone(), peel)? {
+                Some(mut line) => {
+                    if symrefs {
+                        line.extend_from_slice(b" symref-target:");
+                        line.extend_from_slice(target.as_bstr());
+                    }
+                    Some(line)
+                }
+                None => unborn.then(|| {
+                    let mut line = BString::from("unborn HEAD");
+                    if symrefs {
+                        line.extend_from_slice(b" symref-target:");
+                        line.extend_from_slice(target.as_bstr());
+                    }
+                    line
+                }),
+            },
+            gix_ref::TargetRef::Peeled(_) => ref_line(repo, head, peel)?,
+        };
+        if let Some(line) = line {
+            encode::text_to_write(&line, &mut *out)?;
+        }
+    }
+
+    let platform = repo.references()?;
+    for reference in platform.all()? {
+        let reference = reference?;
+        if !is_wanted(reference.name().as_bstr()) {
+            continue;
+        }
+        let target = match reference.target() {
+            gix_ref::TargetRef::Symbolic(target) => symrefs.then(|| target.as_bstr().to_owned()),
+            gix_ref::TargetRef::Peeled(_) => None,
+        };
+        if let Some(mut line) = ref_line(repo, reference, peel)? {
+            if let Some(target) = target {
+                line.extend_from_slice(b" symref-target:");
+                line.extend_from_slice(&target);
+            }
+            encode::text_to_write(&line, &mut *out)?;
+        }
+    }
+    encode::flush_to_write(&mut *out)?;
+    Ok(())
+}
+
+/// Produce `<id> <name>` for `reference`, along with the peeled object if `peel` is `true` and if it points to a tag,
+/// or `None` if it is a symbolic reference to a reference that doesn't exist.
+fn ref_line(repo: &Repository, reference: crate::Reference<'_>, peel: bool) -> Result<Option<BString>, Error> {
+    let mut id = reference.try_id().map(crate::Id::detach);
+    let mut target = reference.target().try_name().map(ToOwned::to_owned);
+    for _ in 0..MAX_SYMBOLIC_REF_DEPTH {
+        let Some(name) = target.take() else { break };
+        match repo.try_find_reference(name.as_ref())? {
+            Some(next) => {
+                id = next.try_id().map(crate::Id::detach);
+                target = next.target().try_name().map(ToOwned::to_owned);
+            }
+            None => return Ok(None),
+        }
+    }
+    let Some(id) = id else { return Ok(None) };
+
+    let mut line = BString::from(format!("{id} "));
+    line.extend_from_slice(reference.name().as_bstr());
+    if peel {
+        let peeled = reference.into_fully_peeled_id()?;
+        if peeled != id {
+            line.extend_from_slice(format!(" peeled:{peeled}").as_bytes());
+        }
+    }
+    Ok(Some(line))
+}
+
+fn fetch(repo: &Repository, arguments: &[BString], out: &mut pipe::Writer) -> Result<(), Error> {
+    let mut wants = Vec::new();
+    let mut haves = Vec::new();
+    let (mut done, mut include_tag) = (false, false);
+    for argument in arguments {
+        match argument.as_slice() {
+            b"done" => done = true,
+            b"include-tag" => include_tag = true,
+            // We never send deltas against objects outside of the pack, nor do we send progress.
+            b"thin-pack" | b"ofs-delta" | b"no-progress" => {}
+            arg => {
+                if let Some(id) = arg.strip_prefix(b"want ") {
+                    // Like in protocol V2, wants aren't limited to advertised objects, so any object
+                    // in the object database can be requested whether it's reachable from a reference or not.
+                    let id = ObjectId::from_hex(id)?;
+                    if !repo.objects.contains(id) {
+                        return Err(Error::NotOurRef { id });
+                    }
+                    wants.push(id);
+                } else if let Some(id) = arg.strip_prefix(b"have ") {
+                    haves.push(ObjectId::from_hex(id)?);
+                } else {
+                    return Err(Error::UnexpectedArgument {
+                        line: argument.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    let common: Vec<_> = haves.into_iter().filter(|id| repo.objects.contains(id)).collect();
+    if !done {
+        encode::text_to_write(b"acknowledgments", &mut *out)?;
+        if common.is_empty() {
+            // Let the client send more `have` lines, or `done` if it has nothing else to offer.
+            encode::text_to_write(b"NAK", &mut *out)?;
+            encode::flush_to_write(&mut *out)?;
+            return Ok(());
+        }
+        for id in &common {
+            encode::text_to_write(format!("ACK {id}").as_bytes(), &mut *out)?;
+        }
+        encode::text_to_write(b"ready", &mut *out)?;
+        encode::delim_to_write(&mut *out)?;
+    }
+
+    let mut input = repo.pack_input(wants, common).map_err(Box::new)?;
+    if include_tag {
+        let mut objects: gix_hashtable::HashSet = input.objects.iter().copied().collect();
+        let platform = repo.references()?;
+        for reference in platform.tags()? {
+            let reference = reference?;
+            let Some(id) = reference.try_id() else { continue };
+            if objects.contains(&id.detach()) || id.object()?.kind != gix_object::Kind::Tag {
+                continue;
+            }
+            if input.commits.contains(&reference.into_fully_peeled_id()?.detach()) && objects.insert(id.detach()) {
+                input.objects.push(id.detach());
+            }
+        }
+    }
+
+    encode::text_to_write(b"packfile", &mut *out)?;
+    repo.write_pack(
+        input,
+        false,
+        SideBand(&mut *out),
+        gix_features::progress::Discard,
+        &AtomicBool::default(),
+    )
+    .map_err(Box::new)?;
+    encode::flush_to_write(&mut *out)?;
+    Ok(())
+}
+
+/// Write everything as data packet lines on the side-band channel for pack data.
+struct SideBand<W>(W);
+
+impl<W: Write> Write for SideBand<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for chunk in buf.chunks(MAX_BAND_DATA_LEN) {
+            encode::band_to_write(Channel::Data, chunk, &mut self.0)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
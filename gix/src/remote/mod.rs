@@ -49,6 +49,14 @@ pub mod fetch;
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
 pub mod connect;
 
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod in_process;
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod promisor;
+
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
 mod connection;
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
@@ -0,0 +1,193 @@
+//! Fetching objects that are missing in partial clones from the remotes that promised to have them.
+//!
+//! A repository is a partial clone if `extensions.partialClone` names a remote, or if any remote has `remote.<name>.promisor`
+//! set. When opened, its object database fetches objects that can't be found from these remotes, unless
+//! [lazy fetching](crate::open::Options::lazy_fetch()) is disabled.
+use std::{borrow::Cow, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+use gix_odb::Find;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::{Extensions, Fetch, Key, Remote, Section},
+    remote, Repository,
+};
+
+/// The error returned by [`Repository::fetch_from_promisor_remotes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("There is no promisor remote to fetch missing objects from")]
+    NoPromisorRemote,
+    #[error(transparent)]
+    FindRemote(#[from] Box<remote::find::existing::Error>),
+    #[error(transparent)]
+    RefSpec(#[from] gix_refspec::parse::Error),
+    #[error(transparent)]
+    Connect(#[from] Box<remote::connect::Error>),
+    #[error(transparent)]
+    PrepareFetch(#[from] Box<remote::fetch::prepare::Error>),
+    #[error(transparent)]
+    Fetch(#[from] Box<remote::fetch::Error>),
+    #[error("Could not mark the pack at \"{}\" as obtained from a promisor remote", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl Repository {
+    /// Return the names of all remotes that promise to have objects that are missing locally, with the one configured
+    /// in `extensions.partialClone` first, followed by all remotes with `remote.<name>.promisor` set.
+    ///
+    /// The returned list is empty if this repository isn't a partial clone.
+    pub fn promisor_remote_names(&self) -> Vec<BString> {
+        promisor_remote_names(&self.config.resolved)
+    }
+
+    /// Fetch the objects with `ids` from the [promisor remotes](Self::promisor_remote_names()), trying one after another
+    /// until all objects are present, similar to what `git` does when it encounters a missing object in a partial clone.
+    ///
+    /// All missing objects are requested at once, using the filter in `remote.<name>.partialCloneFilter` or `blob:none` so
+    /// only the objects themselves are received. References aren't changed, and the received packs are marked as coming from
+    /// a promisor remote just like `git` would.
+    /// Note that it's not an error if objects are still missing afterwards, for instance if no remote has them.
+    pub fn fetch_from_promisor_remotes(
+        &self,
+        ids: &[ObjectId],
+        progress: impl crate::Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let _span = gix_trace::coarse!("gix::Repository::fetch_from_promisor_remotes()", num_ids = ids.len());
+        let names = self.promisor_remote_names();
+        if names.is_empty() {
+            return Err(Error::NoPromisorRemote);
+        }
+        let mut progress = progress;
+        let mut missing: Vec<_> = ids.iter().filter(|id| !self.objects.contains(id)).copied().collect();
+        missing.sort();
+        missing.dedup();
+        let mut last_err = None;
+        for name in names {
+            if missing.is_empty() {
+                break;
+            }
+            match self.fetch_from_promisor_remote(name.as_bstr(), &missing, &mut progress, should_interrupt) {
+                Ok(()) => last_err = None,
+                Err(err) => last_err = Some(err),
+            }
+            missing.retain(|id| !self.objects.contains(id));
+        }
+        match last_err {
+            Some(err) if !missing.is_empty() => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn fetch_from_promisor_remote(
+        &self,
+        name: &crate::bstr::BStr,
+        ids: &[ObjectId],
+        progress: impl crate::Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let filter = self
+            .config
+            .resolved
+            .string(Remote.name(), Some(name), Remote::PARTIAL_CLONE_FILTER.name())
+            .filter(|spec| !spec.is_empty())
+            .map_or_else(|| "blob:none".into(), Cow::into_owned);
+        let mut remote = self
+            .find_remote(name)
+            .map_err(Box::new)?
+            .with_fetch_tags(remote::fetch::Tags::None);
+        remote.replace_refspecs(
+            ids.iter().map(|id| BString::from(id.to_string())),
+            remote::Direction::Fetch,
+        )?;
+        let outcome = remote
+            .connect(remote::Direction::Fetch)
+            .map_err(Box::new)?
+            .prepare_fetch(progress, Default::default())
+            .map_err(Box::new)?
+            .with_write_fetch_head(false)
+            .with_filter(Some(filter))
+            .receive(crate::progress::Discard, should_interrupt)
+            .map_err(Box::new)?;
+        if let remote::fetch::Status::Change {
+            write_pack_bundle,
+            packfile_uri_bundles,
+            ..
+        } = outcome.status
+        {
+            for path in std::iter::once(write_pack_bundle)
+                .chain(packfile_uri_bundles)
+                .filter_map(|bundle| bundle.data_path)
+            {
+                let path = path.with_extension("promisor");
+                std::fs::write(&path, b"").map_err(|source| Error::WritePromisorFile { path, source })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Return the names of all promisor remotes in `config`, see [`Repository::promisor_remote_names()`].
+pub(crate) fn promisor_remote_names(config: &gix_config::File<'static>) -> Vec<BString> {
+    let mut names = Vec::new();
+    if let Some(name) = config
+        .string_by_key(Extensions::PARTIAL_CLONE.logical_name().as_str())
+        .filter(|name| !name.is_empty())
+    {
+        names.push(name.into_owned());
+    }
+    for name in config
+        .sections_by_name(Remote.name())
+        .into_iter()
+        .flatten()
+        .filter_map(|section| section.header().subsection_name())
+    {
+        if names.iter().any(|existing| existing == name) {
+            continue;
+        }
+        if let Some(Ok(true)) = config.boolean(Remote.name(), Some(name), Remote::PROMISOR.name()) {
+            names.push(name.to_owned());
+        }
+    }
+    names
+}
+
+/// Fetch missing objects for the object database of a partial clone by opening the repository once more, with lazy
+/// fetching disabled so the fetch itself can't trigger it.
+pub(crate) struct Fetcher {
+    pub(crate) git_dir: std::path::PathBuf,
+    pub(crate) options: crate::open::Options,
+    /// The repository to fetch with, opened on first use and reused for all following fetches.
+    pub(crate) repo: once_cell::sync::OnceCell<crate::ThreadSafeRepository>,
+}
+
+impl std::fmt::Debug for Fetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fetcher").field("git_dir", &self.git_dir).finish()
+    }
+}
+
+impl gix_odb::store::Promisor for Fetcher {
+    fn fetch(&self, ids: &[ObjectId]) -> Result<(), gix_odb::store::promisor::Error> {
+        let repo = self
+            .repo
+            .get_or_try_init(|| {
+                let mut options = self.options.clone().lazy_fetch(false);
+                // Like `git`, don't advertise what we have as only the missing objects themselves are requested.
+                options
+                    .cli_config_overrides
+                    .push(format!("{}=noop", Fetch::NEGOTIATION_ALGORITHM.logical_name()).into());
+                crate::ThreadSafeRepository::open_opts(&self.git_dir, options)
+                    .map_err(gix_odb::store::promisor::Error::from)
+            })?
+            .to_thread_local();
+        repo.fetch_from_promisor_remotes(ids, crate::progress::Discard, &AtomicBool::default())?;
+        Ok(())
+    }
+}
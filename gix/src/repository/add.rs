@@ -0,0 +1,407 @@
+use std::path::Path;
+
+use gix_index::entry;
+
+use crate::{
+    add,
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    dirwalk, Repository,
+};
+
+impl Repository {
+    /// Add all files in the worktree that match `patterns` to `index`, similar to `git add`, and return information
+    /// about the paths that changed.
+    ///
+    /// `patterns` are pathspecs relative to the current working directory if it's inside of the worktree, or relative to
+    /// its root otherwise. If there is no pattern, all files match.
+    /// Untracked files are only added if they aren't excluded by `.gitignore` files, and their content is passed through
+    /// the clean filters configured via `.gitattributes` before it's written as blob.
+    /// Tracked files whose stat information indicates they didn't change are not rehashed, while all updated entries
+    /// receive fresh stat information.
+    ///
+    /// Note that `index` isn't written, which is left to the caller, and that submodules and nested repositories are skipped.
+    pub fn add_to_index(
+        &self,
+        index: &mut gix_index::State,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: add::Options,
+    ) -> Result<add::Outcome, add::Error> {
+        let workdir = self.work_dir().ok_or(add::Error::MissingWorktree)?;
+        let mut pathspecs = Pathspecs::new(self.pathspec(
+            patterns,
+            true, /* inherit ignore case */
+            index,
+            gix_worktree::cache::state::attributes::Source::WorktreeThenIdMapping,
+        )?);
+        let checkout_options = self.config.checkout_options(self.git_dir()).map_err(Box::new)?;
+        let capabilities = checkout_options.fs;
+        let stat_options = checkout_options.stat_options;
+        let mut pipeline = self.filter_pipeline(index)?;
+        let mut out = add::Outcome::default();
+        let mut buf = Vec::new();
+        let mut resolved_conflicts = false;
+
+        let num_tracked = index.entries().len();
+        for idx in 0..num_tracked {
+            let entry = &index.entries()[idx];
+            if entry.flags.contains(entry::Flags::REMOVE) || entry.mode == entry::Mode::COMMIT {
+                continue;
+            }
+            let path = entry.path(index).to_owned();
+            if !pathspecs.matches(path.as_ref())? {
+                continue;
+            }
+            let fs_path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+            let md = match fs_path.symlink_metadata() {
+                Ok(md) if !md.is_dir() => md,
+                Ok(_) => {
+                    // A directory replaced the file, which is always recorded to make room for what's inside of it.
+                    remove_entry(index, idx, num_tracked, &path, &mut out);
+                    continue;
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound || !parent_is_dir(&fs_path) => {
+                    if options.record_removals {
+                        remove_entry(index, idx, num_tracked, &path, &mut out);
+                    }
+                    continue;
+                }
+                Err(err) => {
+                    return Err(add::Error::Io {
+                        path: fs_path,
+                        source: err,
+                    })
+                }
+            };
+            if entry.stage() != 0 {
+                // Unmerged entries are resolved by replacing all of their stages with the version in the worktree.
+                let stat = entry::Stat::from_fs(&md)?;
+                let mode = mode_from_fs(&md, Some(entry.mode), &capabilities, options.chmod);
+                let id = self.blob_from_worktree(&mut pipeline, &fs_path, &md, path.as_ref(), mode, index, &mut buf)?;
+                mark_for_removal(index, idx, num_tracked, path.as_ref());
+                index.dangerously_push_entry(stat, id, entry::Flags::empty(), mode, path.as_ref());
+                invalidate_tree(index, path.as_ref());
+                out.modified.push(path);
+                resolved_conflicts = true;
+                continue;
+            }
+
+            let is_intent_to_add = entry.flags.contains(entry::Flags::INTENT_TO_ADD);
+            if is_intent_to_add && options.intent_to_add {
+                continue;
+            }
+            let stat = entry::Stat::from_fs(&md)?;
+            let mode = mode_from_fs(&md, Some(entry.mode), &capabilities, options.chmod);
+            if !is_intent_to_add
+                && mode == entry.mode
+                && entry.stat.matches(&stat, stat_options)
+                && !entry.stat.is_racy(index.timestamp(), stat_options)
+            {
+                continue;
+            }
+
+            let id = self.blob_from_worktree(&mut pipeline, &fs_path, &md, path.as_ref(), mode, index, &mut buf)?;
+            let entry = &mut index.entries_mut()[idx];
+            let has_changed = is_intent_to_add || entry.id != id || entry.mode != mode;
+            entry.id = id;
+            entry.mode = mode;
+            entry.stat = stat;
+            if is_intent_to_add {
+                entry.flags.remove(entry::Flags::INTENT_TO_ADD);
+                if !entry.flags.contains(entry::Flags::SKIP_WORKTREE) {
+                    entry.flags.remove(entry::Flags::EXTENDED);
+                }
+            }
+            if has_changed {
+                invalidate_tree(index, path.as_ref());
+                out.modified.push(path);
+            }
+        }
+
+        if !options.update_only {
+            let mut candidates = Candidates {
+                pathspecs: &mut pathspecs,
+                force: options.force,
+                out: Vec::new(),
+            };
+            dirwalk::Walk {
+                workdir,
+                index,
+                num_tracked,
+                excludes: pipeline.cache_mut(),
+                objects: &self.objects,
+            }
+            .run(&mut candidates)?;
+
+            for rela_path in candidates.out {
+                let fs_path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+                let md = fs_path.symlink_metadata().map_err(|err| add::Error::Io {
+                    path: fs_path.clone(),
+                    source: err,
+                })?;
+                let mode = mode_from_fs(&md, None, &capabilities, options.chmod);
+                let stat = entry::Stat::from_fs(&md)?;
+                let (id, flags) = if options.intent_to_add {
+                    (
+                        gix_hash::ObjectId::empty_blob(self.object_hash()),
+                        entry::Flags::INTENT_TO_ADD | entry::Flags::EXTENDED,
+                    )
+                } else {
+                    let id = self.blob_from_worktree(
+                        &mut pipeline,
+                        &fs_path,
+                        &md,
+                        rela_path.as_ref(),
+                        mode,
+                        index,
+                        &mut buf,
+                    )?;
+                    (id, entry::Flags::empty())
+                };
+                remove_directory_file_conflicts(index, num_tracked, rela_path.as_ref(), &mut out);
+                index.dangerously_push_entry(stat, id, flags, mode, rela_path.as_ref());
+                invalidate_tree(index, rela_path.as_ref());
+                out.added.push(rela_path);
+            }
+            out.added.sort();
+        }
+        if resolved_conflicts || !out.added.is_empty() {
+            index.sort_entries();
+        }
+
+        if let Some(pattern) = pathspecs.unmatched(index) {
+            return Err(add::Error::PathspecMismatch {
+                pattern: pattern.path().to_owned(),
+            });
+        }
+        Ok(out)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn blob_from_worktree(
+        &self,
+        pipeline: &mut crate::filter::Pipeline<'_>,
+        fs_path: &Path,
+        md: &std::fs::Metadata,
+        rela_path: &BStr,
+        mode: entry::Mode,
+        index: &gix_index::State,
+        buf: &mut Vec<u8>,
+    ) -> Result<gix_hash::ObjectId, add::Error> {
+        let io_err = |err| add::Error::Io {
+            path: fs_path.to_owned(),
+            source: err,
+        };
+        if md.is_symlink() {
+            let target = std::fs::read_link(fs_path).map_err(io_err)?;
+            buf.clear();
+            buf.extend_from_slice(gix_path::into_bstr(target).as_ref());
+        } else if mode == entry::Mode::SYMLINK {
+            // Without symlink support, the link target is stored in a file, verbatim.
+            *buf = std::fs::read(fs_path).map_err(io_err)?;
+        } else {
+            let file = std::fs::File::open(fs_path).map_err(io_err)?;
+            pipeline.convert_to_git(file, rela_path, index, buf)?;
+        }
+        Ok(self.write_blob_stream(std::io::Cursor::new(buf.as_slice()))?.detach())
+    }
+}
+
+fn parent_is_dir(path: &Path) -> bool {
+    path.parent().map_or(true, Path::is_dir)
+}
+
+fn remove_entry(index: &mut gix_index::State, idx: usize, num_tracked: usize, path: &BString, out: &mut add::Outcome) {
+    mark_for_removal(index, idx, num_tracked, path.as_ref());
+    invalidate_tree(index, path.as_ref());
+    out.removed.push(path.clone());
+}
+
+/// Mark the entry at `idx` for removal, along with all other stages of `path` that follow it if it is unmerged.
+fn mark_for_removal(index: &mut gix_index::State, idx: usize, num_tracked: usize, path: &BStr) {
+    let num_stages = index.entries()[idx..num_tracked]
+        .iter()
+        .take_while(|e| e.path(index) == path)
+        .count();
+    for entry in &mut index.entries_mut()[idx..idx + num_stages] {
+        entry.flags.insert(entry::Flags::REMOVE);
+    }
+}
+
+fn invalidate_tree(index: &mut gix_index::State, path: &BStr) {
+    if let Some(tree) = index.tree_mut() {
+        tree.invalidate_path(path);
+    }
+}
+
+/// Mark tracked files that are leading directories of `rela_path`, or tracked entries below `rela_path` as directory,
+/// for removal as they would conflict with a new entry at `rela_path`.
+fn remove_directory_file_conflicts(
+    index: &mut gix_index::State,
+    num_tracked: usize,
+    rela_path: &BStr,
+    out: &mut add::Outcome,
+) {
+    let mut conflicting = Vec::new();
+    for pos in rela_path.find_iter(b"/") {
+        if let Some(idx) = index.entry_index_by_path_and_stage_bounded(rela_path[..pos].as_bstr(), 0, num_tracked) {
+            conflicting.push(idx);
+        }
+    }
+    let mut dir = BString::from(rela_path);
+    dir.push_byte(b'/');
+    let entries = &index.entries()[..num_tracked];
+    let start = entries.partition_point(|e| e.path(index) < dir.as_bstr());
+    conflicting.extend(
+        entries[start..]
+            .iter()
+            .take_while(|e| e.path(index).starts_with(&dir))
+            .enumerate()
+            .map(|(offset, _)| start + offset),
+    );
+    for idx in conflicting {
+        if !index.entries()[idx].flags.contains(entry::Flags::REMOVE) {
+            let path = index.entries()[idx].path(index).to_owned();
+            remove_entry(index, idx, num_tracked, &path, out);
+        }
+    }
+}
+
+fn mode_from_fs(
+    md: &std::fs::Metadata,
+    previous: Option<entry::Mode>,
+    capabilities: &gix_fs::Capabilities,
+    chmod: Option<add::Chmod>,
+) -> entry::Mode {
+    let mode = if md.is_symlink() || (previous == Some(entry::Mode::SYMLINK) && !capabilities.symlink) {
+        entry::Mode::SYMLINK
+    } else if capabilities.executable_bit {
+        if gix_fs::is_executable(md) {
+            entry::Mode::FILE_EXECUTABLE
+        } else {
+            entry::Mode::FILE
+        }
+    } else {
+        previous
+            .filter(|mode| *mode == entry::Mode::FILE_EXECUTABLE)
+            .unwrap_or(entry::Mode::FILE)
+    };
+    match (mode, chmod) {
+        (entry::Mode::SYMLINK, _) | (_, None) => mode,
+        (_, Some(add::Chmod::Executable)) => entry::Mode::FILE_EXECUTABLE,
+        (_, Some(add::Chmod::NonExecutable)) => entry::Mode::FILE,
+    }
+}
+
+/// Pathspecs along with the information which of them matched a path, to find those that didn't match anything.
+struct Pathspecs<'repo> {
+    inner: crate::pathspec::Pathspec<'repo>,
+    matched: Vec<bool>,
+}
+
+impl<'repo> Pathspecs<'repo> {
+    fn new(inner: crate::pathspec::Pathspec<'repo>) -> Self {
+        Pathspecs {
+            matched: vec![false; inner.search().patterns().count()],
+            inner,
+        }
+    }
+
+    /// Return `true` if the file at `path` matches, and remember which pattern matched.
+    fn matches(&mut self, path: &BStr) -> Result<bool, crate::pathspec::matching::Error> {
+        Ok(match self.inner.pattern_matching_relative_path(path, Some(false))? {
+            Some(m) => {
+                if m.pattern.is_some() {
+                    self.matched[m.sequence_number] = true;
+                }
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Return `true` if the directory at `dir` may contain paths that match.
+    fn may_match_below(&self, dir: &BStr) -> bool {
+        self.inner.search().can_match_relative_path(dir)
+    }
+
+    /// Return `true` if a pattern names `path` exactly.
+    fn names_exactly(&self, path: &BStr) -> bool {
+        self.inner
+            .search()
+            .patterns()
+            .any(|pattern| !pattern.is_excluded() && pattern.path().trim_end_with(|c| c == '/') == path)
+    }
+
+    /// Return the first pattern that doesn't match any path in `index`.
+    ///
+    /// Only the first matching pattern is recorded for each path, so patterns that weren't recorded are
+    /// checked against all paths in `index`, which contains everything that matched by now.
+    fn unmatched(&self, index: &gix_index::State) -> Option<&gix_pathspec::Pattern> {
+        self.inner
+            .search()
+            .patterns()
+            .zip(self.matched.iter())
+            .filter(|(pattern, matched)| !**matched && !pattern.is_excluded())
+            .map(|(pattern, _)| pattern)
+            .find(|pattern| {
+                let mut pattern = (*pattern).clone();
+                pattern.attributes.clear();
+                let mut search = gix_pathspec::Search::from_specs(Some(pattern), None, Path::new(""))
+                    .expect("normalized patterns can be normalized again");
+                !index.entries().iter().any(|entry| {
+                    search
+                        .pattern_matching_relative_path(entry.path(index), Some(false), &mut |_, _, _, _| true)
+                        .is_some()
+                })
+            })
+    }
+}
+
+/// Collect untracked files that match the pathspecs during a [walk](dirwalk::Walk) through the worktree.
+struct Candidates<'a, 'repo> {
+    pathspecs: &'a mut Pathspecs<'repo>,
+    force: bool,
+    out: Vec<BString>,
+}
+
+impl Candidates<'_, '_> {
+    /// Return `true` if `rela_path` is excluded and should be skipped, or fail if it was named exactly.
+    fn skip_excluded(&self, walk: &mut dirwalk::Walk<'_>, rela_path: &BStr, is_dir: bool) -> Result<bool, add::Error> {
+        if self.force || !walk.is_excluded(rela_path, is_dir)? {
+            return Ok(false);
+        }
+        if self.pathspecs.names_exactly(rela_path) {
+            return Err(add::Error::Ignored {
+                path: rela_path.to_owned(),
+            });
+        }
+        Ok(true)
+    }
+}
+
+impl dirwalk::Delegate for Candidates<'_, '_> {
+    type Error = add::Error;
+
+    fn enter_dir(&mut self, walk: &mut dirwalk::Walk<'_>, rela_path: &BStr) -> Result<bool, Self::Error> {
+        if walk.is_repository(rela_path) || !self.pathspecs.may_match_below(rela_path) {
+            return Ok(false);
+        }
+        Ok(!self.skip_excluded(walk, rela_path, true)?)
+    }
+
+    fn visit_file(&mut self, walk: &mut dirwalk::Walk<'_>, rela_path: &BStr) -> Result<(), Self::Error> {
+        if walk.is_tracked(rela_path) || !self.pathspecs.matches(rela_path)? {
+            return Ok(());
+        }
+        if !self.skip_excluded(walk, rela_path, false)? {
+            self.out.push(rela_path.to_owned());
+        }
+        Ok(())
+    }
+}
+
+impl From<dirwalk::Error> for add::Error {
+    fn from(dirwalk::Error { path, source }: dirwalk::Error) -> Self {
+        add::Error::Io { path, source }
+    }
+}
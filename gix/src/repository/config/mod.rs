@@ -28,7 +28,7 @@ impl crate::Repository {
     }
 
     /// Obtain options for use when connecting via `ssh`.
-    #[cfg(feature = "blocking-network-client")]
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
     pub fn ssh_connect_options(
         &self,
     ) -> Result<gix_protocol::transport::client::ssh::connect::Options, config::ssh_connect_options::Error> {
@@ -122,10 +122,20 @@ mod remote {
 mod branch {
     use std::{borrow::Cow, collections::BTreeSet, convert::TryInto};
 
-    use gix_ref::FullNameRef;
+    use gix_ref::{FullName, FullNameRef};
     use gix_validate::reference::name::Error as ValidateNameError;
 
-    use crate::bstr::BStr;
+    use super::{push_destination, tracking_ref_name};
+    use crate::{
+        bstr::{BStr, BString, ByteSlice},
+        config,
+        config::{
+            cache::util::ApplyLeniency,
+            tree::{push, Branch, Key, Push},
+        },
+        remote,
+        repository::branch_remote_tracking_ref_name,
+    };
 
     impl crate::Repository {
         /// Return a set of unique short branch names for which custom configuration exists in the configuration,
@@ -166,9 +176,210 @@ mod branch {
                 .string("branch", Some(short_branch_name.into()), "remote")
                 .and_then(|name| name.try_into().ok())
         }
+
+        /// Return the name of the local reference that tracks the remote counterpart of the branch with the full `name`,
+        /// like `refs/remotes/origin/main` for `refs/heads/main`, in the given `direction`.
+        ///
+        /// For [fetching](crate::remote::Direction::Fetch), this is the upstream branch as known to `git` as `<name>@{upstream}`,
+        /// which is `branch.<name>.merge` mapped through the fetch refspecs of the remote in `branch.<name>.remote`.
+        /// If that remote is `.`, the local branch to merge is returned instead.
+        ///
+        /// For [pushing](crate::remote::Direction::Push), this is the branch that `git push` would update, mapped through
+        /// the fetch refspecs of the remote to push to, as known to `git` as `<name>@{push}`.
+        /// The remote is determined by `branch.<name>.pushRemote`, `remote.pushDefault` and `branch.<name>.remote`,
+        /// and the branch on the remote side by its push refspecs, or by `push.default` if there are none.
+        ///
+        /// Return `None` if `name` isn't a local branch, or if no upstream or remote to push to is configured.
+        pub fn branch_remote_tracking_ref_name(
+            &self,
+            name: &FullNameRef,
+            direction: remote::Direction,
+        ) -> Option<Result<Cow<'_, FullNameRef>, branch_remote_tracking_ref_name::Error>> {
+            let short_name = name.as_bstr().strip_prefix(b"refs/heads/")?.as_bstr();
+            match direction {
+                remote::Direction::Fetch => self.upstream_tracking_ref_name(name, short_name),
+                remote::Direction::Push => self.push_tracking_ref_name(name, short_name),
+            }
+        }
+
+        fn upstream_tracking_ref_name(
+            &self,
+            name: &FullNameRef,
+            short_name: &BStr,
+        ) -> Option<Result<Cow<'_, FullNameRef>, branch_remote_tracking_ref_name::Error>> {
+            let merge_ref = match self.branch_remote_ref(short_name)? {
+                Ok(merge_ref) => merge_ref,
+                Err(err) => return Some(Err(err.into())),
+            };
+            let remote_name = self.branch_remote_name(short_name)?;
+            if remote_name.as_bstr() == "." {
+                return Some(Ok(merge_ref));
+            }
+            Some(
+                self.find_remote(remote_name.as_bstr())
+                    .map_err(|err| Box::new(err).into())
+                    .and_then(|remote| {
+                        tracking_ref_name(
+                            remote.refspecs(remote::Direction::Fetch),
+                            merge_ref.as_ref(),
+                            self.object_hash(),
+                        )
+                        .ok_or_else(|| {
+                            branch_remote_tracking_ref_name::Error::UpstreamNotTracked {
+                                branch: name.as_bstr().into(),
+                                merge_ref: merge_ref.as_bstr().into(),
+                            }
+                        })
+                    })
+                    .map(Cow::Owned),
+            )
+        }
+
+        fn push_tracking_ref_name(
+            &self,
+            name: &FullNameRef,
+            short_name: &BStr,
+        ) -> Option<Result<Cow<'_, FullNameRef>, branch_remote_tracking_ref_name::Error>> {
+            use branch_remote_tracking_ref_name::Error;
+
+            let remote_name: BString = self
+                .config
+                .resolved
+                .string("branch", Some(short_name), Branch::PUSH_REMOTE.name)
+                .or_else(|| {
+                    self.config.resolved.string_filter_by_key(
+                        config::tree::Remote::PUSH_DEFAULT.logical_name().as_str(),
+                        &mut self.filter_config_section(),
+                    )
+                })
+                .map(Cow::into_owned)
+                .or_else(|| self.branch_remote_name(short_name).map(|name| name.as_bstr().into()))
+                .or_else(|| {
+                    self.remote_default_name(remote::Direction::Fetch)
+                        .map(|name| name.as_ref().into())
+                })?;
+            let remote = match self.find_remote(remote_name.as_bstr()) {
+                Ok(remote) => remote,
+                Err(err) => return Some(Err(Box::new(err).into())),
+            };
+            let tracking_for_push_destination = |destination: &FullNameRef| -> Result<FullName, Error> {
+                tracking_ref_name(
+                    remote.refspecs(remote::Direction::Fetch),
+                    destination,
+                    self.object_hash(),
+                )
+                .ok_or_else(|| Error::PushDestinationNotTracked {
+                    destination: destination.as_bstr().into(),
+                    remote: remote_name.as_bstr().into(),
+                })
+            };
+
+            let push_specs = remote.refspecs(remote::Direction::Push);
+            if !push_specs.is_empty() {
+                return Some(
+                    push_destination(push_specs, name, self.object_hash())
+                        .ok_or_else(|| Error::PushRefSpecsExcludeBranch {
+                            branch: name.as_bstr().into(),
+                            remote: remote_name.as_bstr().into(),
+                        })
+                        .and_then(|destination| tracking_for_push_destination(destination.as_ref()))
+                        .map(Cow::Owned),
+                );
+            }
+
+            let mode = match self
+                .config
+                .resolved
+                .string_by_key(Push::DEFAULT.logical_name().as_str())
+                .map(|value| Push::DEFAULT.try_into_mode(value))
+                .transpose()
+                .with_leniency(self.config.lenient_config)
+            {
+                Ok(mode) => mode.unwrap_or_default(),
+                Err(err) => return Some(Err(err.into())),
+            };
+            Some(match mode {
+                push::Mode::Nothing => Err(Error::PushDefaultNothing {
+                    branch: name.as_bstr().into(),
+                }),
+                push::Mode::Current | push::Mode::Matching => tracking_for_push_destination(name).map(Cow::Owned),
+                push::Mode::Upstream => return self.upstream_tracking_ref_name(name, short_name),
+                push::Mode::Simple => {
+                    let upstream = match self.upstream_tracking_ref_name(name, short_name)? {
+                        Ok(upstream) => upstream,
+                        Err(err) => return Some(Err(err)),
+                    };
+                    tracking_for_push_destination(name).and_then(|current| {
+                        if current.as_ref() == upstream.as_ref() {
+                            Ok(Cow::Owned(current))
+                        } else {
+                            Err(Error::SimplePushMismatch {
+                                branch: name.as_bstr().into(),
+                            })
+                        }
+                    })
+                }
+            })
+        }
     }
 }
 
+/// Map the remote ref `name` through the fetch `specs` to obtain the name of the local ref that tracks it.
+fn tracking_ref_name(
+    specs: &[gix_refspec::RefSpec],
+    name: &gix_ref::FullNameRef,
+    object_hash: gix_hash::Kind,
+) -> Option<gix_ref::FullName> {
+    let null_id = gix_hash::ObjectId::null(object_hash);
+    let item = gix_refspec::match_group::Item {
+        full_ref_name: name.as_bstr(),
+        target: &null_id,
+        object: None,
+    };
+    gix_refspec::MatchGroup::from_fetch_specs(specs.iter().map(gix_refspec::RefSpec::to_ref))
+        .match_remotes(std::iter::once(item))
+        .mappings
+        .into_iter()
+        .find_map(|mapping| mapping.rhs)
+        .and_then(|local| local.into_owned().try_into().ok())
+}
+
+/// Map the local branch `name` through the push `specs` to obtain the name of the remote ref it would be pushed to.
+fn push_destination(
+    specs: &[gix_refspec::RefSpec],
+    name: &gix_ref::FullNameRef,
+    object_hash: gix_hash::Kind,
+) -> Option<gix_ref::FullName> {
+    let null_id = gix_hash::ObjectId::null(object_hash);
+    let item = gix_refspec::match_group::Item {
+        full_ref_name: name.as_bstr(),
+        target: &null_id,
+        object: None,
+    };
+    // Push specs without source, like deletions or `:` for matching branches, can't be matched against a local branch.
+    let group = gix_refspec::MatchGroup {
+        specs: specs
+            .iter()
+            .map(gix_refspec::RefSpec::to_ref)
+            .filter(|spec| spec.source().is_some())
+            .collect(),
+    };
+    group
+        .match_remotes(std::iter::once(item))
+        .mappings
+        .into_iter()
+        .next()
+        .and_then(|mapping| match mapping.rhs {
+            None => Some(name.to_owned()),
+            Some(remote) if remote.starts_with(b"refs/") => remote.into_owned().try_into().ok(),
+            Some(remote) => {
+                let mut full_name = crate::bstr::BString::from("refs/heads/");
+                full_name.extend_from_slice(&remote);
+                full_name.try_into().ok()
+            }
+        })
+}
+
 impl crate::Repository {
     pub(crate) fn filter_config_section(&self) -> fn(&gix_config::file::Metadata) -> bool {
         self.options
@@ -422,4 +422,46 @@ impl crate::Repository {
             File | Git | Ssh | Ext(_) => Ok(None),
         }
     }
+
+    /// Obtain the body of `url` via `GET` and pass it to `consume`, configuring the HTTP client just like the transport of
+    /// the remote named `remote_name`.
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    pub(crate) fn http_get<T, E>(
+        &self,
+        url: &str,
+        remote_name: Option<&BStr>,
+        consume: impl FnOnce(&mut dyn std::io::BufRead) -> Result<T, E>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        use gix_protocol::transport::client::http::{self, Http};
+        let mut client = http::Impl::default();
+        if let Some(options) = self.transport_options(url, remote_name)? {
+            client.configure(options.as_ref())?;
+        }
+        let mut response = client.get(url, url, None::<&str>)?;
+        // Consuming the headers is required before the body can be read, and surfaces unsuccessful HTTP statuses.
+        std::io::copy(&mut response.headers, &mut std::io::sink())?;
+        Ok(consume(&mut response.body)?)
+    }
+
+    #[cfg(not(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    )))]
+    pub(crate) fn http_get<T, E>(
+        &self,
+        _url: &str,
+        _remote_name: Option<&BStr>,
+        _consume: impl FnOnce(&mut dyn std::io::BufRead) -> Result<T, E>,
+    ) -> Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        unreachable!("BUG: without HTTP transport, callers must not attempt to download anything")
+    }
 }
@@ -0,0 +1,25 @@
+use crate::{filter, Repository};
+
+impl Repository {
+    /// Configure a pipeline for converting worktree content to and from its representation in `git`, using `index`
+    /// to learn about in-tree `.gitattributes` files.
+    ///
+    /// If there is a worktree, `.gitattributes` files are read from it first, otherwise they are read from the `index` only.
+    pub fn filter_pipeline(
+        &self,
+        index: &gix_index::State,
+    ) -> Result<filter::Pipeline<'_>, filter::pipeline::new::Error> {
+        let attributes_source = if self.work_dir().is_some() {
+            gix_worktree::cache::state::attributes::Source::WorktreeThenIdMapping
+        } else {
+            gix_worktree::cache::state::attributes::Source::IdMapping
+        };
+        let cache = self.attributes(
+            index,
+            attributes_source,
+            gix_worktree::cache::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+            None,
+        )?;
+        Ok(filter::Pipeline::new(self, cache)?)
+    }
+}
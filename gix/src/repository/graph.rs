@@ -14,10 +14,15 @@ impl crate::Repository {
     ///
     /// Note that the [Graph][gix_revision::Graph] can be sensitive to various object database settings that may affect the performance
     /// of the commit walk.
+    ///
+    /// Missing commits aren't fetched from the promisor remote of partial clones.
     pub fn revision_graph<T>(&self) -> gix_revision::Graph<'_, T> {
+        let objects = self.objects_without_promisor();
         gix_revision::Graph::new(
-            |id, buf| {
-                self.objects
+            move |id, buf| {
+                objects
+                    .as_ref()
+                    .unwrap_or(&self.objects)
                     .try_find(id, buf)
                     .map(|r| r.and_then(gix_object::Data::try_into_commit_iter))
             },
@@ -1,6 +1,9 @@
+use gix_object::WriteTo;
 use gix_odb::FindExt;
 
-use crate::{config::cache::util::ApplyLeniencyDefault, repository::IndexPersistedOrInMemory, worktree};
+use crate::{
+    config::cache::util::ApplyLeniencyDefault, ext::ObjectIdExt, repository::IndexPersistedOrInMemory, worktree,
+};
 
 /// Index access
 impl crate::Repository {
@@ -76,6 +79,96 @@ impl crate::Repository {
             Err(err) => return Err(err.into()),
         })
     }
+
+    /// Write all trees needed to represent the entries of `index` into the object database and return the id of the root tree.
+    ///
+    /// Trees that are recorded in the `TREE` extension of `index` and that didn't change aren't written again, and the extension
+    /// is updated to reflect the written trees so subsequent calls can reuse it. Write `index` to persist the updated extension.
+    ///
+    /// Note that the `index` must not contain conflicts, and that entries added with `--intent-to-add` are ignored.
+    pub fn write_tree_from_index(
+        &self,
+        index: &mut gix_index::State,
+    ) -> Result<crate::Id<'_>, crate::repository::write_tree_from_index::Error> {
+        let id = index.write_tree(|tree| self.write_object(tree).map(crate::Id::detach))?;
+        Ok(id.attach(self))
+    }
+
+    /// Create an index from the tree at `tree` which is supposed to be written to the location of this repository's index.
+    ///
+    /// Entries which have a counterpart in the worktree with identical content receive the stat information of the file
+    /// on disk, so that they are not considered changed. All other entries, or all entries in bare repositories, keep
+    /// default stat information, which forces their content to be checked when comparing them with the worktree.
+    /// The `TREE` extension is populated as well.
+    ///
+    /// Note that the index isn't written, and that the content on disk isn't converted with filters before it is compared.
+    pub fn index_from_tree(
+        &self,
+        tree: &gix_hash::oid,
+    ) -> Result<gix_index::File, crate::repository::index_from_tree::Error> {
+        let mut index = self.index_from_tree_without_stat(tree)?;
+        if let Some(work_dir) = self.work_dir() {
+            let mut buf = Vec::new();
+            for (entry, path) in index.entries_mut_with_paths() {
+                let path = work_dir.join(gix_path::from_bstr(path));
+                if let Some(stat) = stat_if_content_matches(entry, &path, self.object_hash(), &mut buf)? {
+                    entry.stat = stat;
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// Like [`index_from_tree()`](Self::index_from_tree()), but leave the stat information of all entries at their
+    /// defaults for callers who know better than comparing all files in the worktree.
+    pub(crate) fn index_from_tree_without_stat(
+        &self,
+        tree: &gix_hash::oid,
+    ) -> Result<gix_index::File, crate::repository::index_from_tree::Error> {
+        let mut state = gix_index::State::from_tree(tree, |oid, buf| self.objects.find_tree_iter(oid, buf).ok())?;
+        let mut buf = Vec::new();
+        state.write_tree(|tree| -> Result<_, std::io::Error> {
+            buf.clear();
+            tree.write_to(&mut buf)?;
+            Ok(gix_object::compute_hash(
+                self.object_hash(),
+                gix_object::Kind::Tree,
+                &buf,
+            ))
+        })?;
+        Ok(gix_index::File::from_state(state, self.index_path()))
+    }
+}
+
+/// Return the stat information of the file at `path` if its kind and content are the same as the ones of `entry`.
+fn stat_if_content_matches(
+    entry: &gix_index::Entry,
+    path: &std::path::Path,
+    object_hash: gix_hash::Kind,
+    buf: &mut Vec<u8>,
+) -> Result<Option<gix_index::entry::Stat>, crate::repository::index_from_tree::Error> {
+    use gix_index::entry::Mode;
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    buf.clear();
+    if entry.mode == Mode::SYMLINK && metadata.is_symlink() {
+        buf.extend_from_slice(gix_path::into_bstr(std::fs::read_link(path)?).as_ref());
+    } else if (entry.mode == Mode::FILE || entry.mode == Mode::FILE_EXECUTABLE) && metadata.is_file() {
+        if entry.mode.change_to_match_fs(&metadata, true, true).is_some() {
+            return Ok(None);
+        }
+        std::io::Read::read_to_end(&mut std::fs::File::open(path)?, buf)?;
+    } else {
+        return Ok(None);
+    }
+    Ok(
+        (gix_object::compute_hash(object_hash, gix_object::Kind::Blob, buf) == entry.id)
+            .then(|| gix_index::entry::Stat::from_fs(&metadata))
+            .transpose()?,
+    )
 }
 
 impl std::ops::Deref for IndexPersistedOrInMemory {
@@ -0,0 +1,83 @@
+use crate::{merge, Repository};
+
+impl Repository {
+    /// Configure a platform for merging blobs with all merge drivers and attributes taken into account, using `index`
+    /// to learn about in-tree `.gitattributes` files.
+    ///
+    /// If there is a worktree, `.gitattributes` files are read from it first, otherwise they are read from the `index` only.
+    pub fn blob_merge_platform(
+        &self,
+        index: &gix_index::State,
+    ) -> Result<merge::blob::Platform<'_>, merge::blob::new::Error> {
+        let attributes_source = if self.work_dir().is_some() {
+            gix_worktree::cache::state::attributes::Source::WorktreeThenIdMapping
+        } else {
+            gix_worktree::cache::state::attributes::Source::IdMapping
+        };
+        let cache = self.attributes(
+            index,
+            attributes_source,
+            gix_worktree::cache::state::ignore::Source::IdMapping,
+            None,
+        )?;
+        Ok(merge::blob::Platform::new(self, cache)?)
+    }
+}
+
+impl Repository {
+    /// Return the options for [merging trees](Repository::merge_trees()) as configured in this repository.
+    ///
+    /// Renames are tracked according to `merge.renames` and `merge.renameLimit`, which default to `diff.renames` and
+    /// `diff.renameLimit` respectively. Unlike diffs, merges track renames even if nothing is configured.
+    pub fn tree_merge_options(&self) -> Result<merge::tree::Options, merge::tree::options::Error> {
+        use crate::{
+            config::{
+                cache::util::ApplyLeniency,
+                tree::{Diff, Merge},
+            },
+            diff::rename::Tracking,
+        };
+
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let mut renames = None;
+        for (key, key_name) in [(&Merge::RENAMES, "merge.renames"), (&Diff::RENAMES, "diff.renames")] {
+            renames = config
+                .boolean_by_key(key_name)
+                .map(|value| key.try_into_renames(value, || config.string_by_key(key_name)))
+                .transpose()
+                .with_leniency(lenient)?;
+            if renames.is_some() {
+                break;
+            }
+        }
+        let rewrites = match renames.unwrap_or(Tracking::Renames) {
+            Tracking::Disabled => None,
+            Tracking::Renames | Tracking::RenamesAndCopies => {
+                let mut limit = None;
+                for (key, key_name) in [
+                    (&Merge::RENAME_LIMIT, "merge.renameLimit"),
+                    (&Diff::RENAME_LIMIT, "diff.renameLimit"),
+                ] {
+                    limit = config
+                        .integer_by_key(key_name)
+                        .map(|value| key.try_into_usize(value))
+                        .transpose()
+                        .with_leniency(lenient)?;
+                    if limit.is_some() {
+                        break;
+                    }
+                }
+                let default = crate::object::tree::diff::Rewrites::default();
+                Some(crate::object::tree::diff::Rewrites {
+                    limit: limit.unwrap_or(default.limit),
+                    ..default
+                })
+            }
+        };
+        Ok(merge::tree::Options {
+            rewrites,
+            blob: merge::blob::Platform::options(self)?,
+        })
+    }
+}
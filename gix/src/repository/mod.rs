@@ -33,10 +33,12 @@ impl crate::Repository {
     }
 }
 
+mod add;
 mod attributes;
 mod cache;
 mod config;
 mod excludes;
+mod filter;
 mod graph;
 pub(crate) mod identity;
 mod impls;
@@ -44,13 +46,17 @@ mod index;
 mod init;
 mod kind;
 mod location;
+mod merge;
 mod object;
+mod pathspec;
 mod reference;
 mod remote;
 mod revision;
 mod shallow;
+mod signing;
 mod snapshots;
 mod state;
+mod status;
 mod thread_safe;
 mod worktree;
 
@@ -64,6 +70,72 @@ pub enum IndexPersistedOrInMemory {
     InMemory(gix_index::File),
 }
 
+///
+pub mod write_tree_from_index {
+    /// The error returned by [`Repository::write_tree_from_index()`][crate::Repository::write_tree_from_index()].
+    pub type Error = gix_index::write_tree::Error;
+}
+
+///
+pub mod branch_remote_tracking_ref_name {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::branch_remote_tracking_ref_name()`][crate::Repository::branch_remote_tracking_ref_name()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The configured name of the remote ref to merge wasn't valid")]
+        ValidateMergeRef(#[from] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        FindRemote(#[from] Box<crate::remote::find::existing::Error>),
+        #[error(transparent)]
+        PushDefault(#[from] crate::config::key::GenericErrorWithValue),
+        #[error("Upstream branch {merge_ref:?} of branch {branch:?} is not stored as a remote-tracking branch")]
+        UpstreamNotTracked { branch: BString, merge_ref: BString },
+        #[error("Push has no destination for branch {branch:?} as push.default is 'nothing'")]
+        PushDefaultNothing { branch: BString },
+        #[error("The push refspecs of remote {remote:?} don't include branch {branch:?}")]
+        PushRefSpecsExcludeBranch { branch: BString, remote: BString },
+        #[error("Push destination {destination:?} on remote {remote:?} has no local tracking branch")]
+        PushDestinationNotTracked { destination: BString, remote: BString },
+        #[error("Cannot resolve 'simple' push of branch {branch:?} to a single destination as its upstream has a different name")]
+        SimplePushMismatch { branch: BString },
+    }
+}
+
+///
+pub mod merge_base {
+    /// The error returned by [`Repository::merge_base()`][crate::Repository::merge_base()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Compute(#[from] gix_revision::merge_base::Error),
+        #[error("No merge-base found between {first} and {second}")]
+        NotFound {
+            first: gix_hash::ObjectId,
+            second: gix_hash::ObjectId,
+        },
+    }
+}
+
+///
+pub mod index_from_tree {
+    /// The error returned by [`Repository::index_from_tree()`][crate::Repository::index_from_tree()].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        WriteTree(#[from] gix_index::write_tree::Error),
+        #[error("Could not compare worktree file with its index entry")]
+        Io(#[from] std::io::Error),
+        #[error("Could not obtain stat information of worktree file")]
+        Time(#[from] std::time::SystemTimeError),
+    }
+}
+
 ///
 pub mod index_or_load_from_head {
     /// The error returned by [`Repository::index_or_load_from_head()`][crate::Repository::index_or_load_from_head()].
@@ -2,13 +2,24 @@
 use std::{convert::TryInto, ops::DerefMut};
 
 use gix_hash::ObjectId;
+use gix_object::WriteTo;
 use gix_odb::{Find, FindExt, Write};
 use gix_ref::{
     transaction::{LogChange, PreviousValue, RefLog},
     FullName,
 };
 
-use crate::{commit, ext::ObjectIdExt, object, tag, Id, Object, Reference, Tree};
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    commit, config,
+    config::tree::Key,
+    ext::ObjectIdExt,
+    hook::NO_ARGS,
+    object, tag, Id, Object, Reference, Tree,
+};
+
+/// A function to sign the given payload, returning the signature.
+type Sign<'a> = &'a mut dyn FnMut(&BStr) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
 /// Methods related to object creation.
 impl crate::Repository {
@@ -22,6 +33,16 @@ impl crate::Repository {
     /// In order to get the kind of the object, is must be fully decoded from storage if it is packed with deltas.
     /// Loose object could be partially decoded, even though that's not implemented.
     pub fn find_object(&self, id: impl Into<ObjectId>) -> Result<Object<'_>, object::find::existing::Error> {
+        self.find_object_in(&self.objects, id)
+    }
+
+    /// Like [`find_object()`](Self::find_object()), but look up `id` in `objects`, which is expected to be a handle to our
+    /// object database.
+    pub(crate) fn find_object_in(
+        &self,
+        objects: &crate::OdbHandle,
+        id: impl Into<ObjectId>,
+    ) -> Result<Object<'_>, object::find::existing::Error> {
         let id = id.into();
         if id == gix_hash::ObjectId::empty_tree(self.object_hash()) {
             return Ok(Object {
@@ -32,10 +53,23 @@ impl crate::Repository {
             });
         }
         let mut buf = self.free_buf();
-        let kind = self.objects.find(id, &mut buf)?.kind;
+        let kind = objects.find(id, &mut buf)?.kind;
         Ok(Object::from_data(id, kind, buf, self))
     }
 
+    /// Return a handle to the object database that doesn't fetch missing objects from the promisor remote of a partial clone,
+    /// or `None` if there is no promisor.
+    ///
+    /// It's used where missing objects are expected or only local objects matter, so that only the public lookup methods
+    /// fetch objects lazily.
+    pub(crate) fn objects_without_promisor(&self) -> Option<crate::OdbHandle> {
+        self.objects.store_ref().promisor().map(|_| {
+            let mut objects = self.objects.clone();
+            objects.skip_promisor = true;
+            objects
+        })
+    }
+
     /// Try to find the object with `id` or return `None` if it wasn't found.
     pub fn try_find_object(&self, id: impl Into<ObjectId>) -> Result<Option<Object<'_>>, object::find::Error> {
         let id = id.into();
@@ -131,6 +165,8 @@ impl crate::Repository {
     ///
     /// It will be created with `constraint` which is most commonly to [only create it][PreviousValue::MustNotExist]
     /// or to [force overwriting a possibly existing tag](PreviousValue::Any).
+    ///
+    /// If `tag.gpgSign` is set, the tag is signed with the [configured program][crate::Repository::signing_program()].
     pub fn tag(
         &self,
         name: impl AsRef<str>,
@@ -140,7 +176,50 @@ impl crate::Repository {
         message: impl AsRef<str>,
         constraint: PreviousValue,
     ) -> Result<Reference<'_>, tag::Error> {
-        let tag = gix_object::Tag {
+        if self.gpg_sign(&config::tree::Tag::GPG_SIGN)? {
+            let program = self.signing_program(None)?;
+            self.tag_inner(
+                name,
+                target,
+                target_kind,
+                tagger,
+                message,
+                constraint,
+                Some(&mut |payload| program.sign(payload).map_err(Into::into)),
+            )
+        } else {
+            self.tag_inner(name, target, target_kind, tagger, message, constraint, None)
+        }
+    }
+
+    /// Like [`tag(…)`][Self::tag()], but sign the tag by passing its encoded form to `sign`, which returns the signature
+    /// to store in the tag, similar to `git tag -s`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn tag_signed(
+        &self,
+        name: impl AsRef<str>,
+        target: impl AsRef<gix_hash::oid>,
+        target_kind: gix_object::Kind,
+        tagger: Option<gix_actor::SignatureRef<'_>>,
+        message: impl AsRef<str>,
+        constraint: PreviousValue,
+        mut sign: impl FnMut(&BStr) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Result<Reference<'_>, tag::Error> {
+        self.tag_inner(name, target, target_kind, tagger, message, constraint, Some(&mut sign))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn tag_inner(
+        &self,
+        name: impl AsRef<str>,
+        target: impl AsRef<gix_hash::oid>,
+        target_kind: gix_object::Kind,
+        tagger: Option<gix_actor::SignatureRef<'_>>,
+        message: impl AsRef<str>,
+        constraint: PreviousValue,
+        sign: Option<Sign<'_>>,
+    ) -> Result<Reference<'_>, tag::Error> {
+        let mut tag = gix_object::Tag {
             target: target.as_ref().into(),
             target_kind,
             name: name.as_ref().into(),
@@ -148,6 +227,13 @@ impl crate::Repository {
             message: message.as_ref().into(),
             pgp_signature: None,
         };
+        if let Some(sign) = sign {
+            // The signature is separated from the message by a newline, which is part of the signed data.
+            let mut payload = Vec::new();
+            tag.write_to(&mut payload).expect("write to memory works");
+            payload.push(b'\n');
+            tag.pgp_signature = Some(sign(payload.as_bstr()).map_err(tag::Error::Sign)?);
+        }
         let tag_id = self.write_object(&tag)?;
         self.tag_reference(name, tag_id, constraint).map_err(Into::into)
     }
@@ -164,6 +250,72 @@ impl crate::Repository {
         tree: impl Into<ObjectId>,
         parents: impl IntoIterator<Item = impl Into<ObjectId>>,
     ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        if self.gpg_sign(&config::tree::Commit::GPG_SIGN)? {
+            let program = self.signing_program(None)?;
+            self.commit_as_inner(
+                committer,
+                author,
+                reference,
+                message,
+                tree,
+                parents,
+                Some(&mut |payload| program.sign(payload).map_err(Into::into)),
+            )
+        } else {
+            self.commit_as_inner(committer, author, reference, message, tree, parents, None)
+        }
+    }
+
+    /// Like [`commit_as(…)`][Self::commit_as()], but only write the commit object without updating any reference.
+    ///
+    /// The commit is signed if `commit.gpgSign` is set, just like it would be by [`commit_as(…)`][Self::commit_as()].
+    pub fn new_commit_as<'a, 'c>(
+        &self,
+        committer: impl Into<gix_actor::SignatureRef<'c>>,
+        author: impl Into<gix_actor::SignatureRef<'a>>,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Id<'_>, commit::Error> {
+        let commit = new_commit(committer, author, message, tree, parents);
+        Ok(self.write_commit_object(commit)?.0)
+    }
+
+    /// Like [`commit_as(…)`][Self::commit_as()], but sign the commit by passing its encoded form to `sign`, which returns
+    /// the signature to store in its `gpgsig` header, similar to `git commit -S`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn commit_as_signed<'a, 'c, Name, E>(
+        &self,
+        committer: impl Into<gix_actor::SignatureRef<'c>>,
+        author: impl Into<gix_actor::SignatureRef<'a>>,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+        mut sign: impl FnMut(&BStr) -> Result<BString, Box<dyn std::error::Error + Send + Sync + 'static>>,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        self.commit_as_inner(committer, author, reference, message, tree, parents, Some(&mut sign))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn commit_as_inner<'a, 'c, Name, E>(
+        &self,
+        committer: impl Into<gix_actor::SignatureRef<'c>>,
+        author: impl Into<gix_actor::SignatureRef<'a>>,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+        sign: Option<Sign<'_>>,
+    ) -> Result<Id<'_>, commit::Error>
     where
         Name: TryInto<FullName, Error = E>,
         commit::Error: From<E>,
@@ -173,20 +325,9 @@ impl crate::Repository {
             Target,
         };
 
-        // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
-        //       This can be made vastly more efficient though if we wanted to, so we lie in the API
         let reference = reference.try_into()?;
-        let commit = gix_object::Commit {
-            message: message.as_ref().into(),
-            tree: tree.into(),
-            author: author.into().to_owned(),
-            committer: committer.into().to_owned(),
-            encoding: None,
-            parents: parents.into_iter().map(Into::into).collect(),
-            extra_headers: Default::default(),
-        };
-
-        let commit_id = self.write_object(&commit)?;
+        let (commit_id, commit) =
+            self.write_commit_inner(new_commit(committer, author, message, tree, parents), sign)?;
         self.edit_reference(RefEdit {
             change: Change::Update {
                 log: LogChange {
@@ -212,6 +353,39 @@ impl crate::Repository {
         Ok(commit_id)
     }
 
+    /// Write `commit` and sign it first if `commit.gpgSign` is set, returning its id along with the written commit.
+    pub(crate) fn write_commit_object(
+        &self,
+        commit: gix_object::Commit,
+    ) -> Result<(Id<'_>, gix_object::Commit), commit::Error> {
+        if self.gpg_sign(&config::tree::Commit::GPG_SIGN)? {
+            let program = self.signing_program(None)?;
+            self.write_commit_inner(commit, Some(&mut |payload| program.sign(payload).map_err(Into::into)))
+        } else {
+            self.write_commit_inner(commit, None)
+        }
+    }
+
+    fn write_commit_inner(
+        &self,
+        mut commit: gix_object::Commit,
+        sign: Option<Sign<'_>>,
+    ) -> Result<(Id<'_>, gix_object::Commit), commit::Error> {
+        if let Some(sign) = sign {
+            let mut payload = Vec::new();
+            commit.write_to(&mut payload).expect("write to memory works");
+            let mut signature = sign(payload.as_bstr()).map_err(commit::Error::Sign)?;
+            // Each line of the signature becomes a line of the header, which is terminated by a newline anyway.
+            if signature.ends_with(b"\n") {
+                signature.pop();
+            }
+            commit.extra_headers.push(("gpgsig".into(), signature));
+        }
+
+        let commit_id = self.write_object(&commit)?;
+        Ok((commit_id, commit))
+    }
+
     /// Create a new commit object with `message` referring to `tree` with `parents`, and point `reference`
     /// to it. The commit is written without message encoding field, which can be assumed to be UTF-8.
     /// `author` and `committer` fields are pre-set from the configuration, which can be altered
@@ -241,6 +415,44 @@ impl crate::Repository {
         self.commit_as(committer, author, reference, message, tree, parents)
     }
 
+    /// Like [`commit(…)`][Self::commit()], but run the `pre-commit`, `prepare-commit-msg`, `commit-msg` and `post-commit`
+    /// [hooks](crate::hook) like `git commit` does, with the message as possibly changed by the message hooks.
+    ///
+    /// `pre-commit` sees an index created from `tree` as `GIT_INDEX_FILE`, so it checks what is about to be committed.
+    pub fn commit_with_hooks<Name, E>(
+        &self,
+        reference: Name,
+        message: impl AsRef<str>,
+        tree: impl Into<ObjectId>,
+        parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Id<'_>, commit::Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        commit::Error: From<E>,
+    {
+        let tree = tree.into();
+        self.run_pre_commit_hook(tree)?;
+        let hook_message = self.run_commit_message_hooks(message.as_ref())?;
+        let message = hook_message.as_deref().unwrap_or(message.as_ref());
+        let commit_id = self.commit(reference, message, tree, parents)?;
+        // Like `git`, ignore the exit status of the hook as the commit was already created.
+        self.run_hook("post-commit", NO_ARGS, None).ok();
+        Ok(commit_id)
+    }
+
+    /// Return `true` if objects should be signed according to `key`.
+    fn gpg_sign(&self, key: &'static config::tree::keys::Boolean) -> Result<bool, config::boolean::Error> {
+        Ok(self
+            .config
+            .apply_leniency(
+                self.config
+                    .resolved
+                    .boolean_by_key(key.logical_name().as_str())
+                    .map(|value| key.enrich_error(value)),
+            )?
+            .unwrap_or(false))
+    }
+
     /// Return an empty tree object, suitable for [getting changes](crate::Tree::changes()).
     ///
     /// Note that it is special and doesn't physically exist in the object database even though it can be returned.
@@ -251,3 +463,23 @@ impl crate::Repository {
             .into_tree()
     }
 }
+
+fn new_commit<'a, 'c>(
+    committer: impl Into<gix_actor::SignatureRef<'c>>,
+    author: impl Into<gix_actor::SignatureRef<'a>>,
+    message: impl AsRef<str>,
+    tree: impl Into<ObjectId>,
+    parents: impl IntoIterator<Item = impl Into<ObjectId>>,
+) -> gix_object::Commit {
+    // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
+    //       This can be made vastly more efficient though if we wanted to, so we lie in the API
+    gix_object::Commit {
+        message: message.as_ref().into(),
+        tree: tree.into(),
+        author: author.into().to_owned(),
+        committer: committer.into().to_owned(),
+        encoding: None,
+        parents: parents.into_iter().map(Into::into).collect(),
+        extra_headers: Default::default(),
+    }
+}
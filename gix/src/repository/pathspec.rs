@@ -0,0 +1,29 @@
+use crate::{bstr::BStr, pathspec::Pathspec, Repository};
+
+impl Repository {
+    /// Create a new pathspec abstraction that allows to conduct searches using `patterns`.
+    /// `inherit_ignore_case` should be `true` if `patterns` will match against files on disk, or `false` otherwise, for more natural matching
+    /// (but also note that `git` does not do that).
+    /// `index` may be needed to load attributes which is required only if `patterns` refer to attributes via `:(attr:…)` syntax.
+    /// In the same vein, `attributes_source` affects where `.gitattributes` files are read from if pathspecs need to match against attributes.
+    ///
+    /// It will be initialized exactly how it would, and attribute matching will be conducted by reading the worktree first if available.
+    /// If that is not desirable, consider calling [`Pathspec::new()`] directly.
+    pub fn pathspec(
+        &self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        inherit_ignore_case: bool,
+        index: &gix_index::State,
+        attributes_source: gix_worktree::cache::state::attributes::Source,
+    ) -> Result<Pathspec<'_>, crate::pathspec::init::Error> {
+        Pathspec::new(self, patterns, inherit_ignore_case, || {
+            self.attributes(
+                index,
+                attributes_source,
+                gix_worktree::cache::state::ignore::Source::IdMapping,
+                None,
+            )
+            .map_err(Into::into)
+        })
+    }
+}
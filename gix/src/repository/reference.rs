@@ -133,11 +133,28 @@ impl crate::Repository {
         edits: impl IntoIterator<Item = RefEdit>,
     ) -> Result<Vec<RefEdit>, reference::edit::Error> {
         let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
-        self.refs
+        let transaction = self
+            .refs
             .transaction()
-            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
-            .commit(self.committer().transpose()?)
-            .map_err(Into::into)
+            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?;
+        self.commit_reference_transaction(transaction)
+    }
+
+    /// Commit the prepared `transaction` and run the `reference-transaction` hook along the way, rolling back the
+    /// transaction if the hook rejects the prepared edits.
+    pub(crate) fn commit_reference_transaction(
+        &self,
+        transaction: gix_ref::file::Transaction<'_, '_>,
+    ) -> Result<Vec<RefEdit>, reference::edit::Error> {
+        if let Err(err) = self.run_reference_transaction_hook("prepared", transaction.edits()) {
+            let edits = transaction.rollback();
+            self.run_reference_transaction_hook("aborted", &edits).ok();
+            return Err(err.into());
+        }
+        let edits = transaction.commit(self.committer().transpose()?)?;
+        // Like `git`, ignore the exit status of the hook once the transaction is committed.
+        self.run_reference_transaction_hook("committed", &edits).ok();
+        Ok(edits)
     }
 
     /// Return the repository head, an abstraction to help dealing with the `HEAD` reference.
@@ -1,4 +1,6 @@
-use crate::{bstr::BStr, revision, Id};
+use gix_hash::ObjectId;
+
+use crate::{bstr::BStr, ext::ObjectIdExt, revision, Id};
 
 /// Methods for resolving revisions by spec or working with the commit graph.
 impl crate::Repository {
@@ -39,4 +41,70 @@ impl crate::Repository {
     ) -> revision::walk::Platform<'_> {
         revision::walk::Platform::new(tips, self)
     }
+
+    /// Obtain the best merge-base between commit `one` and `two`, or fail if there is none, similar to `git merge-base <one> <two>`.
+    ///
+    /// Note that if a commit-graph is present, its generation numbers will be used to accelerate the traversal.
+    pub fn merge_base(
+        &self,
+        one: impl Into<ObjectId>,
+        two: impl Into<ObjectId>,
+    ) -> Result<Id<'_>, crate::repository::merge_base::Error> {
+        let first = one.into();
+        let second = two.into();
+        let bases = gix_revision::merge_base(first, &[second], &mut self.revision_graph())?
+            .ok_or(crate::repository::merge_base::Error::NotFound { first, second })?;
+        Ok(bases[0].attach(self))
+    }
+
+    /// Obtain all merge-bases between commit `one` and a hypothetical merge of all `others`, sorted from best to worst,
+    /// similar to `git merge-base --all <one> <others>…`.
+    ///
+    /// The returned list is empty if there is no merge-base.
+    pub fn merge_bases_many(
+        &self,
+        one: impl Into<ObjectId>,
+        others: &[ObjectId],
+    ) -> Result<Vec<Id<'_>>, gix_revision::merge_base::Error> {
+        Ok(
+            gix_revision::merge_base(one.into(), others, &mut self.revision_graph())?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|id| id.attach(self))
+                .collect(),
+        )
+    }
+
+    /// Obtain all merge-bases suitable for merging all `commits` at once in an octopus merge,
+    /// similar to `git merge-base --octopus --all <commits>…`.
+    ///
+    /// The returned list is empty if there is no merge-base.
+    pub fn merge_bases_octopus(&self, commits: &[ObjectId]) -> Result<Vec<Id<'_>>, gix_revision::merge_base::Error> {
+        Ok(gix_revision::merge_base::octopus(commits, &mut self.revision_graph())?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| id.attach(self))
+            .collect())
+    }
+
+    /// Return all `commits` that can't be reached from any other of the given `commits`, in the order in which they were provided,
+    /// similar to `git merge-base --independent <commits>…`.
+    pub fn independent_commits(&self, commits: &[ObjectId]) -> Result<Vec<Id<'_>>, gix_revision::merge_base::Error> {
+        Ok(
+            gix_revision::merge_base::independent(commits, &mut self.revision_graph())?
+                .into_iter()
+                .map(|id| id.attach(self))
+                .collect(),
+        )
+    }
+
+    /// Return `true` if `ancestor` is reachable from `descendant`, or if both are the same commit,
+    /// similar to `git merge-base --is-ancestor <ancestor> <descendant>`.
+    pub fn is_ancestor(
+        &self,
+        ancestor: impl Into<ObjectId>,
+        descendant: impl Into<ObjectId>,
+    ) -> Result<bool, gix_revision::merge_base::Error> {
+        gix_revision::merge_base::is_ancestor(ancestor.into(), descendant.into(), &mut self.revision_graph())
+    }
 }
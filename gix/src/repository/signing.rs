@@ -0,0 +1,73 @@
+use std::borrow::Cow;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::{gpg, Gpg, Key, User},
+    signing, Repository,
+};
+
+impl Repository {
+    /// Return the program to create and verify signatures of the given `format`, or of the format configured in `gpg.format`
+    /// if `None`.
+    ///
+    /// The program is configured by `gpg.<format>.program`, or `gpg.program` for OpenPGP, and signs with `user.signingKey`.
+    /// Without a signing key, OpenPGP and X.509 signatures are created for the committer identity, just like `git` does.
+    pub fn signing_program(
+        &self,
+        format: Option<signing::Format>,
+    ) -> Result<signing::Program, signing::program::Error> {
+        let config = &self.config.resolved;
+        let format = match format {
+            Some(format) => format,
+            None => self
+                .config
+                .apply_leniency(
+                    config
+                        .string_by_key(Gpg::FORMAT.logical_name().as_str())
+                        .map(|value| Gpg::FORMAT.try_into_format(value)),
+                )?
+                .unwrap_or_default(),
+        };
+        let program_keys: &[&dyn Key] = match format {
+            signing::Format::OpenPgp => &[&gpg::OpenPgp::PROGRAM, &Gpg::PROGRAM],
+            signing::Format::X509 => &[&gpg::X509::PROGRAM],
+            signing::Format::Ssh => &[&gpg::Ssh::PROGRAM],
+        };
+        let mut program = signing::Program::new(format);
+        if let Some(configured) = program_keys
+            .iter()
+            .find_map(|key| config.string_filter_by_key(key.logical_name().as_str(), &mut self.filter_config_section()))
+        {
+            program.program = gix_path::from_bstr(configured).into_owned().into_os_string();
+        }
+
+        program.signing_key = config
+            .string_by_key(User::SIGNING_KEY.logical_name().as_str())
+            .map(Cow::into_owned);
+        if program.signing_key.is_none() && format != signing::Format::Ssh {
+            if let Some(committer) = self.committer().transpose()? {
+                let mut key: BString = committer.name.to_owned();
+                key.extend_from_slice(b" <");
+                key.extend_from_slice(committer.email);
+                key.push(b'>');
+                program.signing_key = Some(key);
+            }
+        }
+        program.allowed_signers_file = self
+            .config
+            .trusted_file_path("gpg", Some("ssh".into()), gpg::Ssh::ALLOWED_SIGNERS_FILE.name)
+            .transpose()?
+            .map(Cow::into_owned);
+        Ok(program)
+    }
+
+    /// Verify the signature in `signed`, as obtained with [`Commit::signature()`][crate::Commit::signature()] or
+    /// [`Tag::signature()`][crate::Tag::signature()], using the [program][Self::signing_program()] configured for the format
+    /// of the signature.
+    ///
+    /// To verify signatures differently, use the signature and its payload directly.
+    pub fn verify_signature(&self, signed: &signing::Signed) -> Result<signing::Verification, signing::verify::Error> {
+        let program = self.signing_program(signing::Format::from_signature(signed.signature.trim_start()))?;
+        program.verify(&signed.signature, &signed.payload)
+    }
+}
@@ -0,0 +1,14 @@
+use crate::{object::tree::diff::rewrites, status, Repository};
+
+impl Repository {
+    /// Return a platform to obtain the status of the repository, comparing the tree of `HEAD` with the index, and the index with
+    /// the worktree, similar to `git status`.
+    ///
+    /// By default, similar to `git status`, rename tracking between `HEAD` and the index will be enabled if it is not configured.
+    pub fn status(&self) -> Result<status::Platform<'_>, rewrites::Error> {
+        Ok(status::Platform::new(
+            self,
+            self.config.diff_renames()?.unwrap_or_default().into(),
+        ))
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{worktree, Worktree};
+use std::path::{Path, PathBuf};
+
+use crate::{bstr::BString, worktree, Worktree};
 
 /// Interact with individual worktrees and their information.
 impl crate::Repository {
@@ -49,4 +51,110 @@ impl crate::Repository {
     pub fn is_bare(&self) -> bool {
         self.config.is_bare && self.work_dir().is_none()
     }
+
+    /// Remove the private git directories of all linked worktrees whose checkout doesn't exist anymore and which aren't locked,
+    /// similar to `git worktree prune`. If `dry_run` is `true`, nothing is removed.
+    ///
+    /// Return the id of each pruned worktree along with the reason for pruning it.
+    ///
+    /// Note that unlike `git`, `gc.worktreePruneExpire` isn't respected, so worktrees are pruned no matter how recently
+    /// they were used.
+    pub fn worktrees_prune(
+        &self,
+        dry_run: bool,
+    ) -> Result<Vec<(BString, worktree::prune::Reason)>, worktree::prune::Error> {
+        let worktrees = self.common_dir().join("worktrees");
+        let io = |path: &Path| {
+            let path = path.to_owned();
+            move |source| worktree::prune::Error::Io { path, source }
+        };
+        let iter = match std::fs::read_dir(&worktrees) {
+            Ok(iter) => iter,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(io(&worktrees)(err)),
+        };
+        let mut out = Vec::new();
+        for entry in iter {
+            let proxy = worktree::Proxy::new(self, entry.map_err(io(&worktrees))?.path());
+            let Some(reason) = proxy.prune_reason() else {
+                continue;
+            };
+            if !dry_run {
+                let path = proxy.git_dir();
+                if path.is_dir() {
+                    std::fs::remove_dir_all(path)
+                } else {
+                    std::fs::remove_file(path)
+                }
+                .map_err(io(path))?;
+            }
+            out.push((proxy.id().to_owned(), reason));
+        }
+        out.sort_by(|a, b| a.0.cmp(&b.0));
+        if !dry_run {
+            std::fs::remove_dir(&worktrees).ok();
+        }
+        Ok(out)
+    }
+
+    /// Restore the links between linked worktrees and their private git directories, similar to `git worktree repair`.
+    ///
+    /// The `.git` file of each existing checkout is rewritten to point to its private git directory if needed.
+    /// Each path in `moved` is the checkout of a worktree that was moved without [`Proxy::relocate()`](worktree::Proxy::relocate()),
+    /// and its private git directory is updated to point to it.
+    ///
+    /// Return the paths to all files that were rewritten.
+    pub fn worktrees_repair(
+        &self,
+        moved: impl IntoIterator<Item = impl AsRef<Path>>,
+    ) -> Result<Vec<PathBuf>, worktree::repair::Error> {
+        let io = |path: &Path| {
+            let path = path.to_owned();
+            move |source| worktree::repair::Error::Io { path, source }
+        };
+        let mut out = Vec::new();
+        for base in moved {
+            let base = gix_path::realpath(base.as_ref())?;
+            let dot_git = base.join(".git");
+            let git_dir = gix_discover::path::from_gitdir_file(&dot_git)
+                .ok()
+                .and_then(|git_dir| {
+                    git_dir
+                        .file_name()
+                        .map(|id| self.common_dir().join("worktrees").join(id))
+                })
+                .filter(|git_dir| git_dir.is_dir())
+                .ok_or(worktree::repair::Error::NotAWorktree { path: base.clone() })?;
+            let proxy = worktree::Proxy::new(self, git_dir);
+            if proxy
+                .base()
+                .ok()
+                .and_then(|base| gix_path::realpath(base).ok())
+                .as_ref()
+                != Some(&base)
+            {
+                let gitdir = proxy.git_dir().join("gitdir");
+                std::fs::write(&gitdir, format!("{}\n", worktree::add::display(&dot_git))).map_err(io(&gitdir))?;
+                out.push(gitdir);
+            }
+        }
+
+        for proxy in self.worktrees().map_err(io(&self.common_dir().join("worktrees")))? {
+            let Some(base) = proxy.base().ok().filter(|base| base.is_dir()) else {
+                continue;
+            };
+            let git_dir = gix_path::realpath(proxy.git_dir())?;
+            let dot_git = base.join(".git");
+            let points_to_git_dir = gix_discover::path::from_gitdir_file(&dot_git)
+                .ok()
+                .and_then(|path| gix_path::realpath(path).ok())
+                .map_or(false, |path| path == git_dir);
+            if !points_to_git_dir {
+                std::fs::write(&dot_git, format!("gitdir: {}\n", worktree::add::display(&git_dir)))
+                    .map_err(io(&dot_git))?;
+                out.push(dot_git);
+            }
+        }
+        Ok(out)
+    }
 }
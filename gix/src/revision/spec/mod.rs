@@ -75,6 +75,17 @@ impl<'repo> Spec<'repo> {
         self.second_ref.as_ref()
     }
 
+    /// Return the merge-bases of both sides of a `theirs...ours` specification, which is the set of commits excluded by it,
+    /// or `None` if this isn't a [merge specification](gix_revision::Spec::Merge).
+    ///
+    /// This is what `git rev-parse` would print as `^<merge-base>` after both sides of the range.
+    pub fn merge_bases(&self) -> Result<Option<Vec<Id<'repo>>>, gix_revision::merge_base::Error> {
+        match self.inner {
+            gix_revision::Spec::Merge { theirs, ours } => self.repo.merge_bases_many(theirs, &[ours]).map(Some),
+            _ => Ok(None),
+        }
+    }
+
     /// Return the single included object represented by this instance, or `None` if it is a range of any kind.
     pub fn single(&self) -> Option<Id<'repo>> {
         match self.inner {
@@ -140,8 +140,13 @@ impl<'repo> Delegate<'repo> {
         self.kind.unwrap_or(gix_revision::spec::Kind::IncludeReachable) != gix_revision::spec::Kind::IncludeReachable
     }
     fn disambiguate_objects_by_fallback_hint(&mut self, hint: Option<ObjectKindHint>) {
-        fn require_object_kind(repo: &Repository, obj: &gix_hash::oid, kind: gix_object::Kind) -> Result<(), Error> {
-            let obj = repo.find_object(obj)?;
+        fn require_object_kind(
+            repo: &Repository,
+            objects: &crate::OdbHandle,
+            obj: &gix_hash::oid,
+            kind: gix_object::Kind,
+        ) -> Result<(), Error> {
+            let obj = repo.find_object_in(objects, obj)?;
             if obj.kind == kind {
                 Ok(())
             } else {
@@ -158,6 +163,9 @@ impl<'repo> Delegate<'repo> {
 
             if let Some(objs) = self.objs[self.idx].as_mut() {
                 let repo = self.repo;
+                // Candidates are disambiguated by what's present, without fetching objects missing in partial clones.
+                let objects = repo.objects_without_promisor();
+                let objects = objects.as_ref().unwrap_or(&repo.objects);
                 let errors: Vec<_> = match hint {
                     Some(kind_hint) => match kind_hint {
                         ObjectKindHint::Treeish | ObjectKindHint::Committish => {
@@ -167,7 +175,7 @@ impl<'repo> Delegate<'repo> {
                                 _ => unreachable!("BUG: we narrow possibilities above"),
                             };
                             objs.iter()
-                                .filter_map(|obj| peel(repo, obj, kind).err().map(|err| (*obj, err)))
+                                .filter_map(|obj| peel(repo, objects, obj, kind).err().map(|err| (*obj, err)))
                                 .collect()
                         }
                         ObjectKindHint::Tree | ObjectKindHint::Commit | ObjectKindHint::Blob => {
@@ -178,7 +186,11 @@ impl<'repo> Delegate<'repo> {
                                 _ => unreachable!("BUG: we narrow possibilities above"),
                             };
                             objs.iter()
-                                .filter_map(|obj| require_object_kind(repo, obj, kind).err().map(|err| (*obj, err)))
+                                .filter_map(|obj| {
+                                    require_object_kind(repo, objects, obj, kind)
+                                        .err()
+                                        .map(|err| (*obj, err))
+                                })
                                 .collect()
                         }
                     },
@@ -220,11 +232,34 @@ impl<'repo> Delegate<'repo> {
     }
 }
 
-fn peel(repo: &Repository, obj: &gix_hash::oid, kind: gix_object::Kind) -> Result<ObjectId, Error> {
-    let mut obj = repo.find_object(obj)?;
-    obj = obj.peel_to_kind(kind)?;
-    debug_assert_eq!(obj.kind, kind, "bug in Object::peel_to_kind() which didn't deliver");
-    Ok(obj.id)
+/// Like [`Object::peel_to_kind()`](crate::Object::peel_to_kind()), but look up all objects in `objects`.
+fn peel(
+    repo: &Repository,
+    objects: &crate::OdbHandle,
+    obj: &gix_hash::oid,
+    kind: gix_object::Kind,
+) -> Result<ObjectId, Error> {
+    let mut obj = repo.find_object_in(objects, obj)?;
+    loop {
+        let next = match obj.kind {
+            actual if actual == kind => return Ok(obj.id),
+            gix_object::Kind::Commit => obj
+                .try_to_commit_ref_iter()
+                .expect("commit")
+                .tree_id()
+                .expect("valid commit"),
+            gix_object::Kind::Tag => obj.to_tag_ref_iter().target_id().expect("valid tag"),
+            actual @ (gix_object::Kind::Tree | gix_object::Kind::Blob) => {
+                return Err(crate::object::peel::to_kind::Error::NotFound {
+                    oid: obj.id().shorten_or_id(),
+                    actual,
+                    expected: kind,
+                }
+                .into())
+            }
+        };
+        obj = repo.find_object_in(objects, next)?;
+    }
 }
 
 fn handle_errors_and_replacements(
@@ -109,7 +109,7 @@ impl<'repo> delegate::Navigate for Delegate<'repo> {
                 }
             }
             PeelTo::ObjectKind(kind) => {
-                let peel = |obj| peel(repo, obj, kind);
+                let peel = |obj| peel(repo, &repo.objects, obj, kind);
                 for obj in objs.iter() {
                     match peel(obj) {
                         Ok(replace) => replacements.push((*obj, replace)),
@@ -119,7 +119,7 @@ impl<'repo> delegate::Navigate for Delegate<'repo> {
             }
             PeelTo::Path(path) => {
                 let lookup_path = |obj: &ObjectId| {
-                    let tree_id = peel(repo, obj, gix_object::Kind::Tree)?;
+                    let tree_id = peel(repo, &repo.objects, obj, gix_object::Kind::Tree)?;
                     if path.is_empty() {
                         return Ok(tree_id);
                     }
@@ -18,6 +18,21 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         if !self.err.is_empty() && self.refs[self.idx].is_some() {
             return None;
         }
+        if name == "FETCH_HEAD" {
+            let path = self.repo.git_dir().join("FETCH_HEAD");
+            if path.is_file() {
+                return match self.fetch_head_merge_candidate(path) {
+                    Ok(id) => {
+                        self.objs[self.idx].get_or_insert_with(HashSet::default).insert(id);
+                        Some(())
+                    }
+                    Err(err) => {
+                        self.err.push(err);
+                        None
+                    }
+                };
+            }
+        }
         match self.repo.refs.find(name) {
             Ok(r) => {
                 assert!(self.refs[self.idx].is_none(), "BUG: cannot set the same ref twice");
@@ -215,11 +230,70 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         }
     }
 
-    fn sibling_branch(&mut self, _kind: SiblingBranch) -> Option<()> {
+    fn sibling_branch(&mut self, kind: SiblingBranch) -> Option<()> {
         self.unset_disambiguate_call();
-        self.err.push(Error::Planned {
-            dependency: "remote handling and ref-specs are fleshed out more",
-        });
-        None
+        let branch = match self.refs[self.idx].as_ref() {
+            Some(r) => match &r.target {
+                gix_ref::Target::Symbolic(referent) if r.name.as_bstr() == "HEAD" => referent.clone(),
+                _ => r.name.clone(),
+            },
+            None => match self.repo.head_name() {
+                Ok(Some(name)) => name,
+                Ok(None) => {
+                    self.err.push(Error::NotABranch { name: "HEAD".into() });
+                    return None;
+                }
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            },
+        };
+        if !branch.as_bstr().starts_with(b"refs/heads/") {
+            self.err.push(Error::NotABranch {
+                name: branch.as_bstr().into(),
+            });
+            return None;
+        }
+        let direction = match kind {
+            SiblingBranch::Upstream => crate::remote::Direction::Fetch,
+            SiblingBranch::Push => crate::remote::Direction::Push,
+        };
+        let tracking_ref_name = match self.repo.branch_remote_tracking_ref_name(branch.as_ref(), direction) {
+            Some(Ok(name)) => name.into_owned(),
+            Some(Err(err)) => {
+                self.err.push(err.into());
+                return None;
+            }
+            None => {
+                let branch = branch.shorten().into();
+                self.err.push(match kind {
+                    SiblingBranch::Upstream => Error::NoUpstream { branch },
+                    SiblingBranch::Push => Error::NoPushDestination { branch },
+                });
+                return None;
+            }
+        };
+        match self.repo.refs.find(tracking_ref_name.as_ref()) {
+            Ok(r) => {
+                self.refs[self.idx] = Some(r);
+                Some(())
+            }
+            Err(err) => {
+                self.err.push(err.into());
+                None
+            }
+        }
+    }
+}
+
+impl<'repo> Delegate<'repo> {
+    /// Read `FETCH_HEAD` at `path` and return the first merge candidate, or the first entry if there is none, like `git` does.
+    fn fetch_head_merge_candidate(&self, path: std::path::PathBuf) -> Result<ObjectId, Error> {
+        let data = std::fs::read(&path).map_err(|source| Error::ReadFetchHead { path, source })?;
+        let entries = gix_fetchhead::parse(&data)?;
+        gix_fetchhead::merge_candidate(&entries)
+            .map(|entry| entry.id)
+            .ok_or(Error::EmptyFetchHead)
     }
 }
@@ -133,6 +133,23 @@ pub enum Error {
         /// The reference matching the prefix.
         reference: gix_ref::Reference,
     },
+    #[error("{name:?} is not a branch and thus has no upstream or push destination")]
+    NotABranch { name: BString },
+    #[error("No upstream configured for branch {branch:?}")]
+    NoUpstream { branch: BString },
+    #[error("No remote configured to push branch {branch:?} to")]
+    NoPushDestination { branch: BString },
+    #[error(transparent)]
+    BranchRemoteTrackingRefName(#[from] crate::repository::branch_remote_tracking_ref_name::Error),
+    #[error("Could not read FETCH_HEAD at \"{}\"", path.display())]
+    ReadFetchHead {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    DecodeFetchHead(#[from] gix_fetchhead::decode::Error),
+    #[error("FETCH_HEAD didn't contain any entry")]
+    EmptyFetchHead,
     #[error(transparent)]
     IdFromHex(#[from] gix_hash::decode::Error),
     #[error(transparent)]
@@ -0,0 +1,345 @@
+//! Create and verify signatures of commits and tags, similar to `git commit -S`, `git tag -s` and `git verify-commit`.
+//!
+//! Signatures are created and verified by running the program configured for the respective [`Format`], just like `git` does.
+use std::{
+    ffi::OsString,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+///
+pub mod program {
+    /// The error returned by [`Repository::signing_program()`][crate::Repository::signing_program()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Format(#[from] crate::config::key::GenericErrorWithValue),
+        #[error("The value of 'gpg.ssh.allowedSignersFile' could not be interpolated")]
+        AllowedSignersFile(#[from] gix_config::path::interpolate::Error),
+        #[error(transparent)]
+        ParseTime(#[from] crate::config::time::Error),
+    }
+}
+
+///
+pub mod sign {
+    use std::ffi::OsString;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Program::sign()`][super::Program::sign()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A signing key is required to create {format:?} signatures, but 'user.signingKey' isn't set")]
+        MissingSigningKey { format: super::Format },
+        #[error("Could not run {program:?} to create a signature")]
+        Spawn { program: OsString, source: std::io::Error },
+        #[error("Could not pass data to or from {program:?}")]
+        Io { program: OsString, source: std::io::Error },
+        #[error("{program:?} failed to create a signature: {stderr}")]
+        Failed { program: OsString, stderr: BString },
+    }
+}
+
+///
+pub mod verify {
+    use std::ffi::OsString;
+
+    /// The error returned by [`Program::verify()`][super::Program::verify()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("'gpg.ssh.allowedSignersFile' needs to be configured to verify SSH signatures")]
+        MissingAllowedSignersFile,
+        #[error("Could not run {program:?} to verify a signature")]
+        Spawn { program: OsString, source: std::io::Error },
+        #[error("Could not pass data to or from {program:?}")]
+        Io { program: OsString, source: std::io::Error },
+        #[error(transparent)]
+        Program(#[from] super::program::Error),
+    }
+}
+
+/// The kind of signature, as configured with `gpg.format`.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// Signatures are created with OpenPGP, typically using `gpg`.
+    #[default]
+    OpenPgp,
+    /// Signatures are created with X.509 certificates, typically using `gpgsm`.
+    X509,
+    /// Signatures are created with SSH keys using `ssh-keygen`.
+    Ssh,
+}
+
+impl Format {
+    /// Return the format of `signature` as identified by its armor, or `None` if it isn't known.
+    pub fn from_signature(signature: &[u8]) -> Option<Self> {
+        Some(if signature.starts_with(b"-----BEGIN PGP SIGNATURE-----") {
+            Format::OpenPgp
+        } else if signature.starts_with(b"-----BEGIN SIGNED MESSAGE-----") {
+            Format::X509
+        } else if signature.starts_with(b"-----BEGIN SSH SIGNATURE-----") {
+            Format::Ssh
+        } else {
+            return None;
+        })
+    }
+
+    /// The program to run if none is configured.
+    pub fn default_program(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "gpg",
+            Format::X509 => "gpgsm",
+            Format::Ssh => "ssh-keygen",
+        }
+    }
+}
+
+/// A signature along with the data it signs, as extracted from a commit or tag.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Signed {
+    /// The signature itself, including its armor.
+    pub signature: BString,
+    /// The data the signature was created for, which is the encoded object without its signature.
+    pub payload: BString,
+}
+
+/// The result of [`Program::verify()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Verification {
+    /// `true` if the signature was successfully verified to be created by a known and trusted key.
+    pub is_valid: bool,
+    /// The identity of the signer as reported by the program, if it could be determined.
+    pub signer: Option<BString>,
+    /// All output of the verifying program for further inspection.
+    pub output: BString,
+}
+
+/// A program to create or verify signatures, typically obtained with [`Repository::signing_program()`][crate::Repository::signing_program()].
+#[derive(Debug, Clone)]
+pub struct Program {
+    /// The format of signatures created and verified by the program.
+    pub format: Format,
+    /// The program to run, like `gpg`, `gpgsm` or `ssh-keygen`.
+    pub program: OsString,
+    /// The key to sign with, which is a key id for `gpg` and `gpgsm`, or the path to a key for `ssh-keygen`.
+    /// A public SSH key may also be provided literally, optionally prefixed with `key::`, to use the private key
+    /// from an `ssh-agent`.
+    pub signing_key: Option<BString>,
+    /// The file with all principals that are allowed to sign, which is required to verify SSH signatures.
+    pub allowed_signers_file: Option<PathBuf>,
+}
+
+/// Lifecycle
+impl Program {
+    /// Create a new instance to create signatures of the given `format` using the default program, without signing key.
+    pub fn new(format: Format) -> Self {
+        Program {
+            format,
+            program: format.default_program().into(),
+            signing_key: None,
+            allowed_signers_file: None,
+        }
+    }
+}
+
+/// Signing and verification
+impl Program {
+    /// Create a signature for `payload` and return it.
+    pub fn sign(&self, payload: &[u8]) -> Result<BString, sign::Error> {
+        let key = self
+            .signing_key
+            .as_ref()
+            .ok_or(sign::Error::MissingSigningKey { format: self.format })?;
+        let mut cmd = Command::new(&self.program);
+        // Keeps the temporary file with a literal key alive until the program finishes.
+        let mut _key_file = None;
+        match self.format {
+            Format::OpenPgp | Format::X509 => {
+                cmd.arg("--status-fd=2")
+                    .arg("-bsau")
+                    .arg(gix_path::from_bstr(key.as_bstr()).as_ref());
+            }
+            Format::Ssh => {
+                cmd.args(["-Y", "sign", "-n", "git", "-f"]);
+                match literal_ssh_key(key.as_bstr()) {
+                    Some(literal_key) => {
+                        let file = temporary_file(literal_key).map_err(|source| sign::Error::Io {
+                            program: self.program.clone(),
+                            source,
+                        })?;
+                        cmd.arg(&file.1).arg("-U");
+                        _key_file = Some(file);
+                    }
+                    None => {
+                        cmd.arg(gix_path::from_bstr(key.as_bstr()).as_ref());
+                    }
+                }
+            }
+        }
+        let output = run(cmd, payload).map_err(|err| match err {
+            RunError::Spawn(source) => sign::Error::Spawn {
+                program: self.program.clone(),
+                source,
+            },
+            RunError::Io(source) => sign::Error::Io {
+                program: self.program.clone(),
+                source,
+            },
+        })?;
+        if !output.status.success() || output.stdout.is_empty() {
+            return Err(sign::Error::Failed {
+                program: self.program.clone(),
+                stderr: output.stderr.into(),
+            });
+        }
+        Ok(output.stdout.into())
+    }
+
+    /// Verify that `signature` was created for `payload` by a trusted key.
+    ///
+    /// Note that an invalid signature isn't an error, and is indicated by [`Verification::is_valid`] instead.
+    pub fn verify(&self, signature: &[u8], payload: &[u8]) -> Result<Verification, verify::Error> {
+        let io_err = |source| verify::Error::Io {
+            program: self.program.clone(),
+            source,
+        };
+        let map_err = |err| match err {
+            RunError::Spawn(source) => verify::Error::Spawn {
+                program: self.program.clone(),
+                source,
+            },
+            RunError::Io(source) => io_err(source),
+        };
+        let (_signature_file, signature_path) = temporary_file(signature).map_err(io_err)?;
+        match self.format {
+            Format::OpenPgp | Format::X509 => {
+                let mut cmd = Command::new(&self.program);
+                cmd.args(["--keyid-format=long", "--status-fd=1", "--verify"])
+                    .arg(&signature_path)
+                    .arg("-");
+                let output = run(cmd, payload).map_err(map_err)?;
+                let signer = output
+                    .stdout
+                    .lines()
+                    .find_map(|line| line.strip_prefix(b"[GNUPG:] GOODSIG "))
+                    .and_then(|rest| rest.splitn_str(2, " ").nth(1))
+                    .map(|signer| signer.as_bstr().to_owned());
+                Ok(Verification {
+                    is_valid: output.status.success() && signer.is_some(),
+                    signer,
+                    output: combined_output(&output),
+                })
+            }
+            Format::Ssh => {
+                let allowed_signers_file = self
+                    .allowed_signers_file
+                    .as_deref()
+                    .ok_or(verify::Error::MissingAllowedSignersFile)?;
+                let mut cmd = Command::new(&self.program);
+                cmd.args(["-Y", "find-principals", "-f"])
+                    .arg(allowed_signers_file)
+                    .arg("-s")
+                    .arg(&signature_path);
+                let principals = run(cmd, &[]).map_err(map_err)?;
+                let mut output = combined_output(&principals);
+                if principals.status.success() {
+                    for principal in principals.stdout.lines().filter(|line| !line.is_empty()) {
+                        let verification = run(
+                            ssh_verify_command(&self.program, allowed_signers_file, principal, &signature_path),
+                            payload,
+                        )
+                        .map_err(map_err)?;
+                        output = combined_output(&verification);
+                        if verification.status.success() {
+                            return Ok(Verification {
+                                is_valid: true,
+                                signer: Some(principal.as_bstr().to_owned()),
+                                output,
+                            });
+                        }
+                    }
+                }
+                Ok(Verification {
+                    is_valid: false,
+                    signer: None,
+                    output,
+                })
+            }
+        }
+    }
+}
+
+fn ssh_verify_command(program: &OsString, allowed_signers_file: &Path, principal: &[u8], signature: &Path) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.args(["-Y", "verify", "-n", "git", "-f"])
+        .arg(allowed_signers_file)
+        .arg("-I")
+        .arg(gix_path::from_bstr(principal.as_bstr()).as_ref())
+        .arg("-s")
+        .arg(signature);
+    cmd
+}
+
+/// Return the public key in `key` if it is provided literally, like `git` does.
+fn literal_ssh_key(key: &BStr) -> Option<&[u8]> {
+    key.strip_prefix(b"key::")
+        .or_else(|| key.starts_with(b"ssh-").then_some(key.as_bytes()))
+}
+
+/// Write `data` into a temporary file that is removed when the returned handle is dropped, along with its path.
+fn temporary_file(data: &[u8]) -> std::io::Result<(gix_tempfile::Handle<gix_tempfile::handle::Writable>, PathBuf)> {
+    let mut file = gix_tempfile::new(
+        std::env::temp_dir(),
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    )?;
+    file.write_all(data)?;
+    file.flush()?;
+    let path = file.with_mut(|file| file.path().to_owned())?;
+    Ok((file, path))
+}
+
+enum RunError {
+    Spawn(std::io::Error),
+    Io(std::io::Error),
+}
+
+/// Run `cmd` with `input` passed to its standard input and collect its output.
+fn run(mut cmd: Command, input: &[u8]) -> Result<std::process::Output, RunError> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(RunError::Spawn)?;
+    let mut stdin = child.stdin.take().expect("configured");
+    let input = input.to_owned();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output().map_err(RunError::Io)?;
+    writer
+        .join()
+        .expect("no panic")
+        .or_else(|err| {
+            // The program may not read all of its input, which is fine as its exit status tells us if it succeeded.
+            if err.kind() == std::io::ErrorKind::BrokenPipe {
+                Ok(())
+            } else {
+                Err(err)
+            }
+        })
+        .map_err(RunError::Io)?;
+    Ok(output)
+}
+
+fn combined_output(output: &std::process::Output) -> BString {
+    let mut out = BString::from(output.stdout.clone());
+    out.extend_from_slice(&output.stderr);
+    out
+}
@@ -0,0 +1,782 @@
+//! Save local changes in stash commits and apply them again, similar to `git stash`.
+//!
+//! Stashes are stored just like `git` does, as commits referenced by the reflog of `refs/stash`. A stash commit records
+//! the worktree and has the commit that was checked out as first parent, a commit with the state of the index as second parent,
+//! and optionally a commit with all untracked files as third parent.
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use gix_hash::ObjectId;
+use gix_object::tree::EntryMode;
+use gix_odb::FindExt;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    ext::ObjectIdExt,
+    merge, Id, Repository,
+};
+
+/// The name of the reference whose reflog contains all stashes.
+pub const REFERENCE: &str = "refs/stash";
+
+/// A stash as listed by [`Repository::stash_list()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The id of the stash commit, which records the state of the worktree.
+    pub id: ObjectId,
+    /// The message describing the stash, like `WIP on main: 1234567 subject`.
+    pub message: BString,
+    /// The time at which the stash was created.
+    pub time: gix_date::Time,
+}
+
+/// Options for use in [`Repository::stash_save()`].
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// If set, the message to describe the stash with, similar to `git stash push --message`.
+    pub message: Option<BString>,
+    /// If `true`, untracked files which aren't ignored are stashed as well and removed from the worktree,
+    /// similar to `git stash push --include-untracked`.
+    pub include_untracked: bool,
+}
+
+///
+pub mod list {
+    /// The error returned by [`Repository::stash_list()`][crate::Repository::stash_list()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error("Could not read the reflog of the stash")]
+        ReadLog(#[from] std::io::Error),
+        #[error("Could not decode a line of the reflog of the stash")]
+        DecodeLog(#[from] gix_ref::file::log::iter::reverse::Error),
+    }
+}
+
+///
+pub mod save {
+    /// The error returned by [`Repository::stash_save()`][crate::Repository::stash_save()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A worktree is required to stash changes")]
+        MissingWorktree,
+        #[error(transparent)]
+        HeadCommit(#[from] crate::reference::head_commit::Error),
+        #[error(transparent)]
+        HeadName(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        AddToIndex(#[from] crate::add::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::repository::write_tree_from_index::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error("The {kind} is not configured")]
+        IdentityMissing { kind: &'static str },
+        #[error(transparent)]
+        IdentityTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        UpdateWorktree(#[from] super::checkout::Error),
+    }
+}
+
+///
+pub mod apply {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::stash_apply()`][crate::Repository::stash_apply()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A worktree is required to apply a stash")]
+        MissingWorktree,
+        #[error(transparent)]
+        List(#[from] super::list::Error),
+        #[error("There is no stash at index {index}")]
+        NoSuchStash { index: usize },
+        #[error("Commit {id} is not a stash commit as it doesn't have at least two parents")]
+        NotAStash { id: gix_hash::ObjectId },
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        IntoCommit(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::repository::write_tree_from_index::Error),
+        #[error(transparent)]
+        MergeOptions(#[from] Box<crate::merge::tree::options::Error>),
+        #[error(transparent)]
+        MergeTrees(#[from] crate::merge::tree::Error),
+        #[error("The changes of the stashed index conflict with the current index")]
+        IndexConflicts,
+        #[error("The untracked file '{path}' already exists in the worktree")]
+        UntrackedExists { path: BString },
+        #[error("Local changes to '{path}' would be overwritten")]
+        LocalChanges { path: BString },
+        #[error(transparent)]
+        UpdateWorktree(#[from] super::checkout::Error),
+        #[error(transparent)]
+        Drop(#[from] super::drop::Error),
+    }
+}
+
+///
+pub mod drop {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::stash_drop()`][crate::Repository::stash_drop()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There is no stash at index {index}")]
+        NoSuchStash { index: usize },
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        Lock(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        Commit(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error("Could not rewrite the reflog of the stash")]
+        Io(#[from] std::io::Error),
+        #[error("The reflog of the stash contains a malformed line: {line:?}")]
+        MalformedLog { line: BString },
+    }
+}
+
+///
+pub mod checkout {
+    /// The error returned when updating files in the worktree.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        CheckoutOptions(#[from] Box<crate::config::checkout_options::Error>),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::filter::pipeline::new::Error),
+        #[error(transparent)]
+        ConvertToWorktree(#[from] crate::filter::pipeline::convert_to_worktree::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        Time(#[from] std::time::SystemTimeError),
+        #[error("Could not update '{}' in the worktree", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+impl Repository {
+    /// Return all stashes, with the most recent one first, so that the position in the list is the index to use in
+    /// [`stash_apply()`](Self::stash_apply()), similar to `git stash list`.
+    pub fn stash_list(&self) -> Result<Vec<Entry>, list::Error> {
+        let Some(reference) = self.try_find_reference(REFERENCE)? else {
+            return Ok(Vec::new());
+        };
+        let mut out = Vec::new();
+        let mut platform = reference.log_iter();
+        if let Some(log) = platform.rev()? {
+            for line in log {
+                let line = line?;
+                out.push(Entry {
+                    id: line.new_oid,
+                    message: line.message.to_owned(),
+                    time: line.signature.time,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// Save all changes in the index and the worktree, and optionally all untracked files, in a new stash and reset the
+    /// index and the worktree to `HEAD`, similar to `git stash push`.
+    ///
+    /// Return the id of the new stash commit, or `None` if there were no local changes to save.
+    ///
+    /// Note that the stash commits are never signed, and that only files are stashed, but not submodules.
+    pub fn stash_save(&self, options: Options) -> Result<Option<Id<'_>>, save::Error> {
+        let work_dir = self.work_dir().ok_or(save::Error::MissingWorktree)?;
+        let head = self.head_commit()?;
+        let head_tree = head.tree_id()?.detach();
+        let head_summary = format!(
+            "{} {}",
+            head.id().shorten_or_id(),
+            head.message_raw()?.lines().next().unwrap_or_default().as_bstr()
+        );
+        let branch = match self.head_name()? {
+            Some(name) => name.shorten().to_owned(),
+            None => "(no branch)".into(),
+        };
+
+        let index = self.open_index()?;
+        let index_tree = self.write_tree_from_index(&mut index.clone())?.detach();
+
+        let mut worktree_index = index.clone();
+        self.add_to_index(
+            &mut worktree_index,
+            None::<&str>,
+            crate::add::Options {
+                update_only: true,
+                record_removals: true,
+                ..Default::default()
+            },
+        )?;
+        let worktree_tree = self.write_tree_from_index(&mut worktree_index)?.detach();
+
+        let untracked_tree = if options.include_untracked {
+            let mut all = index.clone();
+            let added: HashSet<_> = self
+                .add_to_index(&mut all, None::<&str>, Default::default())?
+                .added
+                .into_iter()
+                .collect();
+            if added.is_empty() {
+                None
+            } else {
+                let mut untracked = gix_index::State::new(self.object_hash());
+                for entry in all.entries() {
+                    let path = entry.path(&all);
+                    if added.contains(path) {
+                        untracked.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+                    }
+                }
+                untracked.sort_entries();
+                Some(self.write_tree_from_index(&mut untracked)?.detach())
+            }
+        } else {
+            None
+        };
+
+        if index_tree == head_tree && worktree_tree == head_tree && untracked_tree.is_none() {
+            return Ok(None);
+        }
+
+        let author = self
+            .author()
+            .ok_or(save::Error::IdentityMissing { kind: "author" })??
+            .to_owned();
+        let committer = self
+            .committer()
+            .ok_or(save::Error::IdentityMissing { kind: "committer" })??
+            .to_owned();
+        let write_commit = |tree: ObjectId, parents: Vec<ObjectId>, message: String| -> Result<ObjectId, save::Error> {
+            Ok(self
+                .write_object(&gix_object::Commit {
+                    tree,
+                    parents: parents.into(),
+                    author: author.clone(),
+                    committer: committer.clone(),
+                    encoding: None,
+                    message: message.into(),
+                    extra_headers: Vec::new(),
+                })?
+                .detach())
+        };
+
+        let head_id = head.id;
+        let index_commit = write_commit(
+            index_tree,
+            vec![head_id],
+            format!("index on {branch}: {head_summary}\n"),
+        )?;
+        let mut parents = vec![head_id, index_commit];
+        if let Some(tree) = untracked_tree {
+            parents.push(write_commit(
+                tree,
+                Vec::new(),
+                format!("untracked files on {branch}: {head_summary}\n"),
+            )?);
+        }
+        let message = match &options.message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {branch}: {head_summary}"),
+        };
+        let stash = write_commit(worktree_tree, parents, format!("{message}\n"))?;
+
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: true,
+                    message: message.into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Peeled(stash),
+            },
+            name: REFERENCE.try_into().expect("valid"),
+            deref: false,
+        })?;
+
+        let mut checkout = Checkout::new(self, work_dir, &index)?;
+        checkout.update(worktree_tree, head_tree)?;
+        if let Some(tree) = untracked_tree {
+            checkout.update(tree, ObjectId::empty_tree(self.object_hash()))?;
+        }
+        checkout.write_index(head_tree)?;
+        Ok(Some(stash.attach(self)))
+    }
+
+    /// Apply the changes of the stash at `index`, with `0` being the most recent one, to the worktree by merging trees,
+    /// similar to `git stash apply stash@{<index>}`.
+    ///
+    /// If `reinstate_index` is `true`, the changes in the index are applied to the index as well, similar to `--index`.
+    /// Otherwise, only new files of the stash are added to the index.
+    /// Untracked files of the stash are restored as well.
+    ///
+    /// Return the outcome of the merge. If it has conflicts, the worktree contains the files with conflict markers, while
+    /// the index retains their previous state.
+    ///
+    /// # Deviation
+    ///
+    /// Unlike `git`, conflicts are not recorded as unmerged entries in the index.
+    pub fn stash_apply(&self, index: usize, reinstate_index: bool) -> Result<merge::tree::Outcome<'_>, apply::Error> {
+        let work_dir = self.work_dir().ok_or(apply::Error::MissingWorktree)?;
+        let stash = self
+            .stash_list()?
+            .into_iter()
+            .nth(index)
+            .ok_or(apply::Error::NoSuchStash { index })?
+            .id;
+        let commit = self.find_object(stash)?.try_into_commit()?;
+        let commit = commit.decode()?;
+        let worktree_tree = commit.tree();
+        let parents: Vec<_> = commit.parents().collect();
+        let (base, index_commit, untracked) = match parents.as_slice() {
+            [base, index_commit] => (*base, *index_commit, None),
+            [base, index_commit, untracked] => (*base, *index_commit, Some(*untracked)),
+            _ => return Err(apply::Error::NotAStash { id: stash }),
+        };
+        let base_tree = self.find_object(base)?.try_into_commit()?.tree_id()?.detach();
+        let stashed_index_tree = self.find_object(index_commit)?.try_into_commit()?.tree_id()?.detach();
+
+        let current_index = self.open_index()?;
+        let ours = self.write_tree_from_index(&mut current_index.clone())?.detach();
+
+        let new_index_tree = if reinstate_index && stashed_index_tree != base_tree {
+            let outcome = self.merge_trees(
+                base_tree,
+                ours,
+                stashed_index_tree,
+                Default::default(),
+                self.tree_merge_options().map_err(Box::new)?,
+            )?;
+            if outcome.has_conflicts() {
+                return Err(apply::Error::IndexConflicts);
+            }
+            Some(outcome.tree.detach())
+        } else {
+            None
+        };
+
+        let outcome = self.merge_trees(
+            base_tree,
+            ours,
+            worktree_tree,
+            gix_diff::blob::merge::Labels {
+                ancestor: Some("Stash base".into()),
+                current: Some("Updated upstream".into()),
+                other: Some("Stashed changes".into()),
+            },
+            self.tree_merge_options().map_err(Box::new)?,
+        )?;
+
+        let mut checkout = Checkout::new(self, work_dir, &current_index)?;
+        let untracked_tree = match untracked {
+            Some(commit) => {
+                let tree = self.find_object(commit)?.try_into_commit()?.tree_id()?.detach();
+                let files = checkout.files(tree)?;
+                if let Some(path) = files.keys().find(|path| {
+                    work_dir
+                        .join(gix_path::from_bstr(path.as_bstr()))
+                        .symlink_metadata()
+                        .is_ok()
+                }) {
+                    return Err(apply::Error::UntrackedExists { path: path.clone() });
+                }
+                Some(tree)
+            }
+            None => None,
+        };
+        if let Some(path) = checkout.first_modified(ours, outcome.tree.detach(), &current_index)? {
+            return Err(apply::Error::LocalChanges { path });
+        }
+
+        checkout.update(ours, outcome.tree.detach())?;
+        if let Some(tree) = untracked_tree {
+            checkout.update(ObjectId::empty_tree(self.object_hash()), tree)?;
+        }
+        match new_index_tree {
+            Some(tree) => checkout.write_index(tree)?,
+            None if outcome.has_conflicts() => checkout.write_index(ours)?,
+            None => {
+                let base_files = checkout.files(base_tree)?;
+                let our_files = checkout.files(ours)?;
+                let new_files: Vec<_> = checkout
+                    .files(outcome.tree.detach())?
+                    .into_iter()
+                    .filter(|(path, _)| !base_files.contains_key(path) && !our_files.contains_key(path))
+                    .collect();
+                checkout.write_index_with(ours, new_files)?;
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Like [`stash_apply()`](Self::stash_apply()), but [drops](Self::stash_drop()) the stash at `index` if it was applied
+    /// without conflicts, similar to `git stash pop`.
+    pub fn stash_pop(&self, index: usize, reinstate_index: bool) -> Result<merge::tree::Outcome<'_>, apply::Error> {
+        let outcome = self.stash_apply(index, reinstate_index)?;
+        if !outcome.has_conflicts() {
+            self.stash_drop(index)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Remove the stash at `index`, with `0` being the most recent one, and return its id, similar to `git stash drop`.
+    ///
+    /// [`REFERENCE`] is deleted along with its reflog once the last stash is dropped.
+    pub fn stash_drop(&self, index: usize) -> Result<ObjectId, drop::Error> {
+        let name: FullName = REFERENCE.try_into().expect("valid");
+        let log_path = self.common_dir().join("logs").join(REFERENCE);
+        if !log_path.is_file() {
+            return Err(drop::Error::NoSuchStash { index });
+        }
+        // Hold the lock while reading and rewriting the log so no concurrent change to it can get lost.
+        let mut file =
+            gix_lock::File::acquire_to_update_resource(&log_path, gix_lock::acquire::Fail::Immediately, None)?;
+        let data = std::fs::read(&log_path)?;
+        let mut lines: Vec<BString> = data.lines().filter(|line| !line.is_empty()).map(Into::into).collect();
+        let parse = |line: &BString| {
+            gix_ref::file::log::LineRef::from_bytes(line)
+                .map(|line| line.new_oid())
+                .map_err(|_| drop::Error::MalformedLog { line: line.clone() })
+        };
+        let line_index = lines
+            .len()
+            .checked_sub(index + 1)
+            .ok_or(drop::Error::NoSuchStash { index })?;
+        let removed = lines.remove(line_index);
+        let dropped = parse(&removed)?;
+        if lines.is_empty() {
+            self.edit_reference(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::Any,
+                    log: RefLog::AndReference,
+                },
+                name,
+                deref: false,
+            })?;
+            return Ok(dropped);
+        }
+
+        let hex_len = self.object_hash().len_in_hex();
+        if let Some(next) = lines.get_mut(line_index) {
+            parse(next)?;
+            // Keep the chain of previous values intact, like `git reflog delete --rewrite` does.
+            next[..hex_len].copy_from_slice(&removed[..hex_len]);
+        }
+
+        if index == 0 {
+            let new_head = parse(lines.last().expect("at least one stash left"))?;
+            // The log is rewritten below, so the reference must be updated without adding to it.
+            let mut refs = self.refs.clone();
+            refs.write_reflog = gix_ref::store::WriteReflog::Disable;
+            let (file_lock_fail, packed_refs_lock_fail) = self
+                .config
+                .lock_timeout()
+                .map_err(crate::reference::edit::Error::from)?;
+            let transaction = refs
+                .transaction()
+                .prepare(
+                    Some(RefEdit {
+                        change: Change::Update {
+                            log: LogChange::default(),
+                            expected: PreviousValue::MustExistAndMatch(Target::Peeled(dropped)),
+                            new: Target::Peeled(new_head),
+                        },
+                        name,
+                        deref: false,
+                    }),
+                    file_lock_fail,
+                    packed_refs_lock_fail,
+                )
+                .map_err(crate::reference::edit::Error::from)?;
+            self.commit_reference_transaction(transaction)?;
+        }
+
+        for line in &lines {
+            std::io::Write::write_all(&mut file, line)?;
+            std::io::Write::write_all(&mut file, b"\n")?;
+        }
+        file.commit()?;
+        Ok(dropped)
+    }
+}
+
+/// The files of a tree, by their repository-relative path.
+type Files = BTreeMap<BString, (EntryMode, ObjectId)>;
+
+/// A utility to change files in the worktree to match the content of a tree.
+struct Checkout<'repo, 'index> {
+    repo: &'repo Repository,
+    work_dir: &'repo Path,
+    /// The index before the worktree was changed, to retain the stat information of unchanged files.
+    index: &'index gix_index::State,
+    pipeline: crate::filter::Pipeline<'repo>,
+    /// Creates leading directories, replacing files and symlinks that are in the way.
+    stack: gix_worktree::Cache,
+    fs: gix_fs::Capabilities,
+    /// The files we have written, along with their mode, id and stat information.
+    written: HashMap<BString, (EntryMode, ObjectId, gix_index::entry::Stat)>,
+    buf: Vec<u8>,
+}
+
+impl<'repo, 'index> Checkout<'repo, 'index> {
+    fn new(
+        repo: &'repo Repository,
+        work_dir: &'repo Path,
+        index: &'index gix_index::State,
+    ) -> Result<Self, checkout::Error> {
+        let options = repo.config.checkout_options(repo.git_dir()).map_err(Box::new)?;
+        let case = if options.fs.ignore_case {
+            gix_glob::pattern::Case::Fold
+        } else {
+            gix_glob::pattern::Case::Sensitive
+        };
+        Ok(Checkout {
+            repo,
+            work_dir,
+            index,
+            pipeline: repo.filter_pipeline(index)?,
+            stack: gix_worktree::Cache::new(
+                work_dir,
+                gix_worktree::cache::State::for_checkout(true, Default::default()),
+                case,
+                Vec::new(),
+                Vec::new(),
+            ),
+            fs: options.fs,
+            written: HashMap::new(),
+            buf: Vec::new(),
+        })
+    }
+
+    /// Return the path to `path` in the worktree after creating all of its leading directories, which are assured to not be
+    /// symlinks.
+    fn prepare_path(&mut self, path: &BStr) -> Result<PathBuf, checkout::Error> {
+        let objects = &self.repo.objects;
+        self.stack
+            .at_path(gix_path::from_bstr(path), Some(false), |id, buf| {
+                objects.find_blob(id, buf)
+            })
+            .map(|platform| platform.path().to_owned())
+            .map_err(|source| checkout::Error::Io {
+                source,
+                path: self.work_dir.join(gix_path::from_bstr(path)),
+            })
+    }
+
+    /// Return all files in `tree`.
+    fn files(&self, tree: ObjectId) -> Result<Files, checkout::Error> {
+        let tree = self.repo.find_object(tree)?.peel_to_tree()?;
+        let mut recorder = gix_traverse::tree::Recorder::default();
+        tree.traverse().breadthfirst(&mut recorder)?;
+        Ok(recorder
+            .records
+            .into_iter()
+            .filter(|record| record.mode.is_no_tree())
+            .map(|record| (record.filepath, (record.mode, record.oid)))
+            .collect())
+    }
+
+    /// Return the first path whose file would be changed when changing the worktree from `from` to `to`, but which
+    /// doesn't match its entry in `from` anymore.
+    fn first_modified(
+        &mut self,
+        from: ObjectId,
+        to: ObjectId,
+        index: &gix_index::State,
+    ) -> Result<Option<BString>, checkout::Error> {
+        let from = self.files(from)?;
+        let to = self.files(to)?;
+        for (path, from_entry) in &from {
+            if to.get(path) == Some(from_entry) {
+                continue;
+            }
+            let fs_path = self.work_dir.join(gix_path::from_bstr(path.as_bstr()));
+            let content = match std::fs::symlink_metadata(&fs_path) {
+                Ok(metadata) if metadata.is_symlink() => {
+                    gix_path::into_bstr(std::fs::read_link(&fs_path).map_err(|source| checkout::Error::Io {
+                        source,
+                        path: fs_path.clone(),
+                    })?)
+                    .into_owned()
+                }
+                Ok(_) => {
+                    let file = std::fs::File::open(&fs_path).map_err(|source| checkout::Error::Io {
+                        source,
+                        path: fs_path.clone(),
+                    })?;
+                    self.buf.clear();
+                    self.pipeline
+                        .convert_to_git(file, path.as_ref(), index, &mut self.buf)?;
+                    self.buf.clone().into()
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Some(path.clone())),
+                Err(source) => return Err(checkout::Error::Io { source, path: fs_path }),
+            };
+            if gix_object::compute_hash(self.repo.object_hash(), gix_object::Kind::Blob, &content) != from_entry.1 {
+                return Ok(Some(path.clone()));
+            }
+        }
+        for path in to.keys().filter(|path| !from.contains_key(*path)) {
+            if self
+                .work_dir
+                .join(gix_path::from_bstr(path.as_bstr()))
+                .symlink_metadata()
+                .is_ok()
+            {
+                return Ok(Some(path.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Change all files in the worktree that differ between the `from` and `to` trees to match `to`.
+    fn update(&mut self, from: ObjectId, to: ObjectId) -> Result<(), checkout::Error> {
+        let from = self.files(from)?;
+        let to = self.files(to)?;
+        for path in from.keys().filter(|path| !to.contains_key(*path)) {
+            let fs_path = self.prepare_path(path.as_ref())?;
+            match std::fs::remove_file(&fs_path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(checkout::Error::Io { source, path: fs_path }),
+            }
+            let mut dir = fs_path.parent();
+            while let Some(parent) = dir.filter(|dir| *dir != self.work_dir) {
+                if std::fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                dir = parent.parent();
+            }
+        }
+        for (path, (mode, id)) in to.iter().filter(|(path, entry)| from.get(*path) != Some(entry)) {
+            if *mode == EntryMode::Commit {
+                continue;
+            }
+            self.write_file(path.as_ref(), *mode, *id)?;
+        }
+        Ok(())
+    }
+
+    fn write_file(&mut self, path: &BStr, mode: EntryMode, id: ObjectId) -> Result<(), checkout::Error> {
+        let fs_path = self.prepare_path(path)?;
+        let io_err = |source| checkout::Error::Io {
+            source,
+            path: fs_path.clone(),
+        };
+        match fs_path.symlink_metadata() {
+            // Only empty directories are replaced so untracked files are never lost.
+            Ok(metadata) if metadata.is_dir() => std::fs::remove_dir(&fs_path).map_err(io_err)?,
+            Ok(_) => std::fs::remove_file(&fs_path).map_err(io_err)?,
+            Err(_) => {}
+        }
+        let blob = self.repo.find_object(id)?;
+        if mode == EntryMode::Link && self.fs.symlink {
+            gix_fs::symlink::create(&gix_path::from_bstr(blob.data.as_bstr()), &fs_path).map_err(io_err)?;
+        } else {
+            self.buf.clear();
+            if mode == EntryMode::Link {
+                self.buf.extend_from_slice(&blob.data);
+            } else {
+                self.pipeline.convert_to_worktree(&blob.data, path, &mut self.buf)?;
+            }
+            std::fs::write(&fs_path, &self.buf).map_err(io_err)?;
+            #[cfg(unix)]
+            if mode == EntryMode::BlobExecutable && self.fs.executable_bit {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&fs_path, std::fs::Permissions::from_mode(0o755)).map_err(io_err)?;
+            }
+        }
+        let stat = gix_index::entry::Stat::from_fs(&fs_path.symlink_metadata().map_err(io_err)?)?;
+        self.written.insert(path.to_owned(), (mode, id, stat));
+        Ok(())
+    }
+
+    /// Write the index of the repository so it matches `tree`.
+    fn write_index(&mut self, tree: ObjectId) -> Result<(), checkout::Error> {
+        self.write_index_with(tree, Vec::new())
+    }
+
+    /// Write the index of the repository so it matches `tree`, with `additional` files on top.
+    ///
+    /// Like `git`, entries of files we have written or which didn't change receive their stat information without reading
+    /// any file, so they aren't considered modified and rehashed by the next status.
+    fn write_index_with(
+        &mut self,
+        tree: ObjectId,
+        additional: Vec<(BString, (EntryMode, ObjectId))>,
+    ) -> Result<(), checkout::Error> {
+        let mut index = self.repo.index_from_tree_without_stat(&tree)?;
+        if !additional.is_empty() {
+            for (path, (mode, id)) in additional {
+                let mode = gix_index::entry::Mode::from_bits(mode as u32).expect("valid file mode");
+                index.dangerously_push_entry(
+                    Default::default(),
+                    id,
+                    gix_index::entry::Flags::empty(),
+                    mode,
+                    path.as_ref(),
+                );
+                if let Some(tree) = index.tree_mut() {
+                    tree.invalidate_path(path.as_ref());
+                }
+            }
+            index.sort_entries();
+        }
+        for (entry, path) in index.entries_mut_with_paths() {
+            let stat = match self.written.get(path) {
+                Some((mode, id, stat)) => (*id == entry.id && *mode as u32 == entry.mode.bits()).then_some(*stat),
+                None => self
+                    .index
+                    .entry_by_path_and_stage(path, 0)
+                    .filter(|previous| previous.id == entry.id && previous.mode == entry.mode)
+                    .map(|previous| previous.stat),
+            };
+            if let Some(stat) = stat {
+                entry.stat = stat;
+            }
+        }
+        index.write(Default::default())?;
+        Ok(())
+    }
+}
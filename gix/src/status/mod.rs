@@ -0,0 +1,263 @@
+//! Obtain the status of a repository, similar to `git status --porcelain=v2`.
+//!
+//! The status combines the changes between the tree of `HEAD` and the index, the *staged* changes, with the changes between
+//! the index and the worktree, the *unstaged* changes, along with conflicts, untracked and ignored files.
+use gix_index::entry::Mode;
+
+use crate::{
+    bstr::BString,
+    object::tree::diff::{change::DiffLineStats, rewrites, Rewrites},
+    Repository,
+};
+
+mod outcome;
+mod untracked;
+
+/// The error returned by [`Platform::into_outcome()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to compute the status")]
+    MissingWorktree,
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::to_commit::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    FindTree(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] gix_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    PathspecMatch(#[from] crate::pathspec::matching::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] Box<crate::config::checkout_options::Error>),
+    #[error(transparent)]
+    Excludes(#[from] crate::config::exclude_stack::Error),
+    #[error("Could not obtain a thread-safe handle to the object database")]
+    ObjectsIntoArc(#[source] std::io::Error),
+    #[error(transparent)]
+    IndexWorktree(#[from] gix_worktree::status::Error),
+    #[error(transparent)]
+    TrackRewrites(#[from] crate::object::tree::diff::for_each::Error),
+    #[error("Could not access '{}' in the worktree", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not obtain the status of the submodule at '{path}'")]
+    Submodule { path: BString, source: Box<Error> },
+}
+
+/// Which untracked files to report, similar to `git status --untracked-files`.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum UntrackedFiles {
+    /// Do not look for untracked files at all.
+    None,
+    /// Report untracked files, but collapse directories that contain no tracked files into a single entry
+    /// with a trailing slash, like `git status --untracked-files=normal`.
+    #[default]
+    Collapsed,
+    /// Report each untracked file individually, like `git status --untracked-files=all`.
+    Files,
+}
+
+/// A change of an entry, as seen when comparing it with its previous version.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Change {
+    /// The entry was added.
+    ///
+    /// For unstaged changes, this is an entry that was added with `git add --intent-to-add`.
+    Added,
+    /// The entry was deleted.
+    Deleted,
+    /// The content or the executable bit of the entry changed.
+    Modified,
+    /// The kind of the entry changed, like a file that became a symlink.
+    TypeChanged,
+    /// The entry was renamed from [`Entry::source`].
+    Renamed,
+    /// The entry was copied from [`Entry::source`].
+    Copied,
+}
+
+/// The summary of a submodule's state, similar to the `<sub>` field of `git status --porcelain=v2`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Submodule {
+    /// The commit checked out in the submodule differs from the one recorded in the index.
+    pub commit_changed: bool,
+    /// The submodule has changes to its tracked files, either staged or unstaged.
+    pub has_modifications: bool,
+    /// The submodule has untracked files.
+    pub has_untracked_files: bool,
+}
+
+/// Where a renamed or copied entry came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Source {
+    /// The path of the entry in the tree of `HEAD`, relative to the root of the repository.
+    pub rela_path: BString,
+    /// Statistics about the differences between the source and its destination, or `None` if the content is identical.
+    pub diff: Option<DiffLineStats>,
+}
+
+/// A tracked entry which changed in the index, in the worktree, or both.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// The path of the entry relative to the root of the repository.
+    pub rela_path: BString,
+    /// How the entry changed between the tree of `HEAD` and the index, or `None` if it didn't.
+    pub staged: Option<Change>,
+    /// How the entry changed between the index and the worktree, or `None` if it didn't.
+    pub unstaged: Option<Change>,
+    /// The source of a renamed or copied entry, set only if [`staged`][Self::staged] indicates a rename or copy.
+    pub source: Option<Source>,
+    /// The summary of the submodule's state if this entry is a submodule.
+    pub submodule: Option<Submodule>,
+    /// The mode of the entry in the tree of `HEAD`, or `None` if it didn't exist there.
+    pub head_mode: Option<Mode>,
+    /// The id of the entry in the tree of `HEAD`, or `None` if it didn't exist there.
+    pub head_id: Option<gix_hash::ObjectId>,
+    /// The mode of the entry in the index, or `None` if it was deleted from the index.
+    pub index_mode: Option<Mode>,
+    /// The id of the entry in the index, or `None` if it was deleted from the index.
+    pub index_id: Option<gix_hash::ObjectId>,
+    /// The mode of the entry in the worktree, or `None` if it doesn't exist there.
+    pub worktree_mode: Option<Mode>,
+}
+
+/// The way the sides of a merge conflict changed the entry, like the `XY` field of unmerged entries in `git status --porcelain=v2`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ConflictKind {
+    /// Both sides deleted the entry, `DD`.
+    BothDeleted,
+    /// Only our side added the entry, `AU`.
+    AddedByUs,
+    /// Their side deleted the entry which we modified, `UD`.
+    DeletedByThem,
+    /// Only their side added the entry, `UA`.
+    AddedByThem,
+    /// Our side deleted the entry which they modified, `DU`.
+    DeletedByUs,
+    /// Both sides added the entry, `AA`.
+    BothAdded,
+    /// Both sides modified the entry, `UU`.
+    BothModified,
+}
+
+/// The mode and id of an entry at one of the stages of a conflict.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ConflictSide {
+    /// The mode of the entry at this stage.
+    pub mode: Mode,
+    /// The id of the entry at this stage.
+    pub id: gix_hash::ObjectId,
+}
+
+/// An unmerged entry in the index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// The path of the entry relative to the root of the repository.
+    pub rela_path: BString,
+    /// How the sides of the conflict changed the entry.
+    pub kind: ConflictKind,
+    /// The common ancestor at stage 1, if present.
+    pub base: Option<ConflictSide>,
+    /// Our version at stage 2, if present.
+    pub ours: Option<ConflictSide>,
+    /// Their version at stage 3, if present.
+    pub theirs: Option<ConflictSide>,
+    /// The mode of the entry in the worktree, or `None` if it doesn't exist there.
+    pub worktree_mode: Option<Mode>,
+}
+
+/// An item of the status, with all items being ordered by path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Item {
+    /// A tracked entry that changed.
+    Changed(Entry),
+    /// An entry with conflicts.
+    Conflict(Conflict),
+    /// An untracked path, with directories ending in a slash.
+    Untracked {
+        /// The path relative to the root of the repository.
+        rela_path: BString,
+    },
+    /// An ignored path, with directories ending in a slash.
+    Ignored {
+        /// The path relative to the root of the repository.
+        rela_path: BString,
+    },
+}
+
+/// The result of [`Platform::into_outcome()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    /// The commit `HEAD` points to, or `None` if `HEAD` is unborn.
+    pub head_id: Option<gix_hash::ObjectId>,
+    /// The name of the branch `HEAD` points to, or `None` if `HEAD` is detached.
+    pub head_name: Option<gix_ref::FullName>,
+    /// All changes, ordered by path.
+    pub items: Vec<Item>,
+    /// Information about the rename tracking between the tree of `HEAD` and the index, if it was enabled.
+    pub rewrites: Option<rewrites::Outcome>,
+}
+
+/// A builder to configure how the status of a repository is obtained, created by [`Repository::status()`].
+#[derive(Clone)]
+pub struct Platform<'repo> {
+    repo: &'repo Repository,
+    rewrites: Option<Rewrites>,
+    untracked_files: UntrackedFiles,
+    ignored: bool,
+    submodules: bool,
+}
+
+/// Lifecycle
+impl<'repo> Platform<'repo> {
+    pub(crate) fn new(repo: &'repo Repository, rewrites: Option<Rewrites>) -> Self {
+        Platform {
+            repo,
+            rewrites,
+            untracked_files: Default::default(),
+            ignored: false,
+            submodules: true,
+        }
+    }
+}
+
+/// Configuration
+impl<'repo> Platform<'repo> {
+    /// Configure rename and copy tracking between the tree of `HEAD` and the index, or disable it with `None`.
+    ///
+    /// By default, it's configured just like for tree diffs, similar to `git status`.
+    pub fn track_rewrites(mut self, rewrites: Option<Rewrites>) -> Self {
+        self.rewrites = rewrites;
+        self
+    }
+
+    /// Configure which untracked files to report, with [collapsed directories][UntrackedFiles::Collapsed] being the default.
+    pub fn untracked_files(mut self, untracked_files: UntrackedFiles) -> Self {
+        self.untracked_files = untracked_files;
+        self
+    }
+
+    /// If `true`, report ignored files as well, similar to `git status --ignored`. Directories that are ignored as a whole
+    /// are reported as a single entry. This is `false` by default.
+    pub fn ignored(mut self, ignored: bool) -> Self {
+        self.ignored = ignored;
+        self
+    }
+
+    /// If `true`, the default, compute a summary of the state of each submodule that is checked out, which requires
+    /// obtaining their status as well.
+    pub fn submodules(mut self, submodules: bool) -> Self {
+        self.submodules = submodules;
+        self
+    }
+}
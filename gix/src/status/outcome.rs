@@ -0,0 +1,457 @@
+use std::collections::BTreeMap;
+
+use gix_index::entry::Mode;
+use gix_odb::FindExt;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    object::tree::diff::tracked,
+    status::{
+        untracked, Change, Conflict, ConflictKind, ConflictSide, Entry, Error, Item, Outcome, Platform, Source,
+        Submodule, UntrackedFiles,
+    },
+};
+
+impl<'repo> Platform<'repo> {
+    /// Obtain the status of all paths matching the pathspecs in `patterns`, or of all paths if there is no pattern.
+    ///
+    /// Note that the index isn't written even though the stat information of some of its entries may be outdated,
+    /// and that the worktree content is compared as is, without applying filters.
+    pub fn into_outcome(self, patterns: impl IntoIterator<Item = impl AsRef<BStr>>) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let workdir = repo.work_dir().ok_or(Error::MissingWorktree)?;
+
+        let mut head = repo.head()?;
+        let head_name = head.referent_name().map(ToOwned::to_owned);
+        let (head_id, head_tree_id) = if head.is_unborn() {
+            (None, None)
+        } else {
+            let commit = head.peel_to_commit_in_place()?;
+            (Some(commit.id), Some(commit.tree_id()?.detach()))
+        };
+        let head_index = match head_tree_id {
+            Some(tree_id) => {
+                gix_index::State::from_tree(&tree_id, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?
+            }
+            None => gix_index::State::new(repo.object_hash()),
+        };
+        let mut index = if repo.index_path().is_file() {
+            repo.open_index()?
+        } else {
+            gix_index::File::from_state(gix_index::State::new(repo.object_hash()), repo.index_path())
+        };
+        let mut pathspec = repo.pathspec(
+            patterns,
+            true, /* inherit ignore case */
+            &index,
+            gix_worktree::cache::state::attributes::Source::WorktreeThenIdMapping,
+        )?;
+
+        let mut entries = BTreeMap::<BString, Entry>::new();
+        let mut items = Vec::new();
+        let rewrites = self.staged_changes(&head_index, &index, head_tree_id, &mut pathspec, &mut entries)?;
+        self.unstaged_changes(&mut index, workdir, &mut pathspec, &mut entries)?;
+        collect_conflicts(&index, workdir, &mut pathspec, &mut items)?;
+        if self.untracked_files != UntrackedFiles::None || self.ignored {
+            let mut excludes = repo.excludes(
+                &index,
+                None,
+                gix_worktree::cache::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+            )?;
+            crate::dirwalk::Walk {
+                workdir,
+                index: &index,
+                num_tracked: index.entries().len(),
+                excludes: &mut excludes,
+                objects: &repo.objects,
+            }
+            .run(&mut untracked::Collect {
+                pathspec: &mut pathspec,
+                mode: self.untracked_files,
+                ignored: self.ignored,
+                out: &mut items,
+                dirs: Vec::new(),
+            })?;
+        }
+
+        items.extend(entries.into_values().map(Item::Changed));
+        items.sort_by(|a, b| a.rela_path().cmp(b.rela_path()));
+        Ok(Outcome {
+            head_id,
+            head_name,
+            items,
+            rewrites,
+        })
+    }
+
+    /// Compare the tree of `HEAD`, as `head_index`, with the unconflicted entries of `index`.
+    fn staged_changes(
+        &self,
+        head_index: &gix_index::State,
+        index: &gix_index::State,
+        head_tree_id: Option<gix_hash::ObjectId>,
+        pathspec: &mut crate::pathspec::Pathspec<'_>,
+        entries: &mut BTreeMap<BString, Entry>,
+    ) -> Result<Option<crate::object::tree::diff::rewrites::Outcome>, Error> {
+        let mut tracker = self.rewrites.map(|rewrites| tracked::State::new(rewrites, None));
+        let mut head_entries = head_index.entries().iter().peekable();
+        let mut index_entries = index
+            .entries()
+            .iter()
+            .filter(|e| {
+                e.stage() == 0
+                    && !e
+                        .flags
+                        .intersects(gix_index::entry::Flags::INTENT_TO_ADD | gix_index::entry::Flags::REMOVE)
+            })
+            .peekable();
+        loop {
+            let (head_entry, index_entry) = match (head_entries.peek(), index_entries.peek()) {
+                (None, None) => break,
+                (Some(_), None) => (head_entries.next(), None),
+                (None, Some(_)) => (None, index_entries.next()),
+                (Some(h), Some(i)) => match h.path(head_index).cmp(i.path(index)) {
+                    std::cmp::Ordering::Less => (head_entries.next(), None),
+                    std::cmp::Ordering::Greater => (None, index_entries.next()),
+                    std::cmp::Ordering::Equal => (head_entries.next(), index_entries.next()),
+                },
+            };
+            let rela_path = match (head_entry, index_entry) {
+                (_, Some(i)) => i.path(index),
+                (Some(h), None) => h.path(head_index),
+                (None, None) => unreachable!("at least one side is set"),
+            };
+            if (1..=3).any(|stage| index.entry_index_by_path_and_stage(rela_path, stage).is_some()) {
+                // Conflicts are reported separately.
+                continue;
+            }
+            let change = match (head_entry, index_entry) {
+                (Some(h), Some(i)) if h.id == i.id && h.mode == i.mode => continue,
+                (Some(h), Some(i)) if h.mode != i.mode && !(is_file(h.mode) && is_file(i.mode)) => Change::TypeChanged,
+                (Some(_), Some(_)) => Change::Modified,
+                (Some(_), None) => Change::Deleted,
+                (None, Some(_)) => Change::Added,
+                (None, None) => unreachable!("at least one side is set"),
+            };
+            if !pathspec.is_included(rela_path, Some(false))? {
+                continue;
+            }
+
+            if let Some(tracker) = tracker.as_mut() {
+                let tree_change = match (head_entry, index_entry) {
+                    (Some(h), Some(i)) => h.mode.to_tree_entry_mode().zip(i.mode.to_tree_entry_mode()).map(
+                        |(previous_entry_mode, entry_mode)| gix_diff::tree::visit::Change::Modification {
+                            previous_entry_mode,
+                            previous_oid: h.id,
+                            entry_mode,
+                            oid: i.id,
+                        },
+                    ),
+                    (Some(h), None) => h
+                        .mode
+                        .to_tree_entry_mode()
+                        .map(|entry_mode| gix_diff::tree::visit::Change::Deletion { entry_mode, oid: h.id }),
+                    (None, Some(i)) => i
+                        .mode
+                        .to_tree_entry_mode()
+                        .map(|entry_mode| gix_diff::tree::visit::Change::Addition { entry_mode, oid: i.id }),
+                    (None, None) => unreachable!("at least one side is set"),
+                };
+                if let Some(tree_change) = tree_change.filter(|_| change != Change::TypeChanged) {
+                    tracker.try_push_change(tree_change, rela_path);
+                }
+            }
+            entries.insert(
+                rela_path.to_owned(),
+                Entry {
+                    rela_path: rela_path.to_owned(),
+                    staged: Some(change),
+                    unstaged: None,
+                    source: None,
+                    submodule: None,
+                    head_mode: head_entry.map(|e| e.mode),
+                    head_id: head_entry.map(|e| e.id),
+                    index_mode: index_entry.map(|e| e.mode),
+                    index_id: index_entry.map(|e| e.id),
+                    worktree_mode: index_entry.map(|e| e.mode),
+                },
+            );
+        }
+
+        let Some(mut tracker) = tracker else {
+            return Ok(None);
+        };
+        let repo = self.repo;
+        let head_tree = match head_tree_id {
+            Some(id) => repo.find_object(id)?.into_tree(),
+            None => repo.empty_tree(),
+        };
+        let outcome = tracker.emit(
+            |dest, source| {
+                if let Some(source) = source {
+                    let copy = source.kind == tracked::visit::Kind::CopyDestination;
+                    if !copy {
+                        entries.remove(source.location);
+                    }
+                    if let Some(entry) = entries.get_mut(dest.location) {
+                        entry.staged = Some(if copy { Change::Copied } else { Change::Renamed });
+                        entry.head_mode = head_index.entry_by_path_and_stage(source.location, 0).map(|e| e.mode);
+                        entry.head_id = Some(source.id);
+                        entry.source = Some(Source {
+                            rela_path: source.location.to_owned(),
+                            diff: source.diff,
+                        });
+                    }
+                }
+                gix_diff::tree::visit::Action::Continue
+            },
+            &head_tree,
+        )?;
+        Ok(Some(outcome))
+    }
+
+    /// Compare the unconflicted entries of `index` with the worktree, including the state of submodules.
+    fn unstaged_changes(
+        &self,
+        index: &mut gix_index::File,
+        workdir: &std::path::Path,
+        pathspec: &mut crate::pathspec::Pathspec<'_>,
+        entries: &mut BTreeMap<BString, Entry>,
+    ) -> Result<(), Error> {
+        let repo = self.repo;
+        let checkout_options = repo.config.checkout_options(repo.git_dir()).map_err(Box::new)?;
+        let mut changes = Collect::default();
+        gix_worktree::status(
+            index,
+            workdir,
+            &mut changes,
+            gix_worktree::status::content::FastEq,
+            {
+                let objects = repo.objects.clone().into_arc().map_err(Error::ObjectsIntoArc)?;
+                move |oid, buf| objects.find_blob(oid, buf)
+            },
+            gix_worktree::status::Options {
+                fs: checkout_options.fs,
+                thread_limit: None,
+                stat: checkout_options.stat_options,
+            },
+        )?;
+
+        for (rela_path, index_mode, index_id, change) in changes.0 {
+            if !pathspec.is_included(rela_path.as_bstr(), Some(false))? {
+                continue;
+            }
+            let (unstaged, worktree_mode) = match change {
+                gix_worktree::status::Change::Removed => (Change::Deleted, None),
+                gix_worktree::status::Change::Type => (
+                    Change::TypeChanged,
+                    worktree_mode(workdir, rela_path.as_bstr(), index_mode, &checkout_options.fs),
+                ),
+                gix_worktree::status::Change::Modification { .. } => (
+                    Change::Modified,
+                    worktree_mode(workdir, rela_path.as_bstr(), index_mode, &checkout_options.fs),
+                ),
+                gix_worktree::status::Change::IntentToAdd => (Change::Added, Some(index_mode)),
+            };
+            let entry = entries.entry(rela_path.clone()).or_insert_with(|| Entry {
+                rela_path,
+                staged: None,
+                unstaged: None,
+                source: None,
+                submodule: None,
+                head_mode: Some(index_mode),
+                head_id: Some(index_id),
+                index_mode: Some(index_mode),
+                index_id: Some(index_id),
+                worktree_mode: None,
+            });
+            if unstaged == Change::Added {
+                entry.head_mode = None;
+                entry.head_id = None;
+            }
+            entry.unstaged = Some(unstaged);
+            entry.worktree_mode = worktree_mode;
+        }
+
+        if !self.submodules {
+            return Ok(());
+        }
+        for submodule in index
+            .entries()
+            .iter()
+            .filter(|e| e.stage() == 0 && e.mode == Mode::COMMIT)
+        {
+            let rela_path = submodule.path(index);
+            if !pathspec.is_included(rela_path, Some(true))? {
+                continue;
+            }
+            let Some(summary) = self.submodule_summary(workdir, rela_path, submodule.id)? else {
+                continue;
+            };
+            let has_changes = summary.commit_changed || summary.has_modifications || summary.has_untracked_files;
+            if !has_changes && !entries.contains_key(rela_path) {
+                continue;
+            }
+            let entry = entries.entry(rela_path.to_owned()).or_insert_with(|| Entry {
+                rela_path: rela_path.to_owned(),
+                staged: None,
+                unstaged: None,
+                source: None,
+                submodule: None,
+                head_mode: Some(submodule.mode),
+                head_id: Some(submodule.id),
+                index_mode: Some(submodule.mode),
+                index_id: Some(submodule.id),
+                worktree_mode: Some(submodule.mode),
+            });
+            if has_changes {
+                entry.unstaged = Some(Change::Modified);
+            }
+            entry.submodule = Some(summary);
+        }
+        Ok(())
+    }
+
+    /// Return the summary of the submodule at `rela_path` whose commit in the index is `id`, or `None` if it isn't checked out.
+    fn submodule_summary(
+        &self,
+        workdir: &std::path::Path,
+        rela_path: &BStr,
+        id: gix_hash::ObjectId,
+    ) -> Result<Option<Submodule>, Error> {
+        let path = workdir.join(gix_path::from_bstr(rela_path));
+        if !path.join(".git").exists() {
+            return Ok(None);
+        }
+        let Ok(repo) = crate::open(&path) else {
+            return Ok(None);
+        };
+        let checked_out = repo.head_id().ok().map(crate::Id::detach);
+        let status = Platform::new(&repo, None)
+            .untracked_files(match self.untracked_files {
+                UntrackedFiles::None => UntrackedFiles::None,
+                _ => UntrackedFiles::Collapsed,
+            })
+            .into_outcome(None::<&str>)
+            .map_err(|err| Error::Submodule {
+                path: rela_path.to_owned(),
+                source: Box::new(err),
+            })?;
+        Ok(Some(Submodule {
+            commit_changed: checked_out != Some(id),
+            has_modifications: status
+                .items
+                .iter()
+                .any(|item| matches!(item, Item::Changed(_) | Item::Conflict(_))),
+            has_untracked_files: status.items.iter().any(|item| matches!(item, Item::Untracked { .. })),
+        }))
+    }
+}
+
+impl Item {
+    /// Return the path of this item relative to the root of the repository.
+    pub fn rela_path(&self) -> &BStr {
+        match self {
+            Item::Changed(Entry { rela_path, .. })
+            | Item::Conflict(Conflict { rela_path, .. })
+            | Item::Untracked { rela_path }
+            | Item::Ignored { rela_path } => rela_path.as_ref(),
+        }
+    }
+}
+
+/// Collect all unconflicted entries that changed in the worktree.
+#[derive(Default)]
+struct Collect(Vec<(BString, Mode, gix_hash::ObjectId, gix_worktree::status::Change)>);
+
+impl<'index> gix_worktree::status::VisitEntry<'index> for Collect {
+    type ContentChange = ();
+
+    fn visit_entry(
+        &mut self,
+        entry: &'index gix_index::Entry,
+        rela_path: &'index BStr,
+        change: Option<gix_worktree::status::Change<Self::ContentChange>>,
+        _conflict: bool,
+    ) {
+        if let Some(change) =
+            change.filter(|_| entry.stage() == 0 && !entry.flags.contains(gix_index::entry::Flags::REMOVE))
+        {
+            self.0.push((rela_path.to_owned(), entry.mode, entry.id, change));
+        }
+    }
+}
+
+fn collect_conflicts(
+    index: &gix_index::State,
+    workdir: &std::path::Path,
+    pathspec: &mut crate::pathspec::Pathspec<'_>,
+    out: &mut Vec<Item>,
+) -> Result<(), Error> {
+    let mut entries = index.entries().iter().filter(|e| e.stage() != 0).peekable();
+    while let Some(first) = entries.next() {
+        let rela_path = first.path(index);
+        let mut sides = [None; 3];
+        for entry in
+            std::iter::once(first).chain(std::iter::from_fn(|| entries.next_if(|e| e.path(index) == rela_path)))
+        {
+            sides[entry.stage() as usize - 1] = Some(ConflictSide {
+                mode: entry.mode,
+                id: entry.id,
+            });
+        }
+        if !pathspec.is_included(rela_path, Some(false))? {
+            continue;
+        }
+        let [base, ours, theirs] = sides;
+        let kind = match (base.is_some(), ours.is_some(), theirs.is_some()) {
+            (true, false, false) => ConflictKind::BothDeleted,
+            (false, true, false) => ConflictKind::AddedByUs,
+            (true, true, false) => ConflictKind::DeletedByThem,
+            (false, false, true) => ConflictKind::AddedByThem,
+            (true, false, true) => ConflictKind::DeletedByUs,
+            (false, true, true) => ConflictKind::BothAdded,
+            (true, true, true) => ConflictKind::BothModified,
+            (false, false, false) => unreachable!("there is at least one stage"),
+        };
+        let path = workdir.join(gix_path::from_bstr(rela_path));
+        let worktree_mode = match path.symlink_metadata() {
+            Ok(md) if md.is_symlink() => Some(Mode::SYMLINK),
+            Ok(md) if md.is_dir() => None,
+            Ok(md) => Some(if gix_fs::is_executable(&md) {
+                Mode::FILE_EXECUTABLE
+            } else {
+                Mode::FILE
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(source) => return Err(Error::Io { path, source }),
+        };
+        out.push(Item::Conflict(Conflict {
+            rela_path: rela_path.to_owned(),
+            kind,
+            base,
+            ours,
+            theirs,
+            worktree_mode,
+        }));
+    }
+    Ok(())
+}
+
+/// Return the mode of the file at `rela_path` as it would be recorded in an index entry of `mode`.
+fn worktree_mode(
+    workdir: &std::path::Path,
+    rela_path: &BStr,
+    mode: Mode,
+    capabilities: &gix_fs::Capabilities,
+) -> Option<Mode> {
+    let md = workdir.join(gix_path::from_bstr(rela_path)).symlink_metadata().ok()?;
+    Some(
+        mode.change_to_match_fs(&md, capabilities.symlink, capabilities.executable_bit)
+            .map_or(mode, |change| change.apply(mode)),
+    )
+}
+
+fn is_file(mode: Mode) -> bool {
+    mode == Mode::FILE || mode == Mode::FILE_EXECUTABLE
+}
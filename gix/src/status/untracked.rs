@@ -0,0 +1,98 @@
+use crate::{
+    bstr::{BStr, BString, ByteVec},
+    dirwalk,
+    status::{Error, Item, UntrackedFiles},
+};
+
+/// Collect untracked and ignored paths found during a [walk](dirwalk::Walk) through the worktree.
+pub(super) struct Collect<'a, 'repo> {
+    pub pathspec: &'a mut crate::pathspec::Pathspec<'repo>,
+    pub mode: UntrackedFiles,
+    pub ignored: bool,
+    pub out: &'a mut Vec<Item>,
+    /// For each entered directory, the amount of items before it was entered and whether it should be collapsed.
+    pub dirs: Vec<(usize, bool)>,
+}
+
+impl dirwalk::Delegate for Collect<'_, '_> {
+    type Error = Error;
+
+    fn enter_dir(&mut self, walk: &mut dirwalk::Walk<'_>, rela_path: &BStr) -> Result<bool, Self::Error> {
+        if walk.is_tracked(rela_path) || !self.pathspec.search().can_match_relative_path(rela_path) {
+            // Submodules are handled separately, and untracked paths in here can't be matched anyway.
+            return Ok(false);
+        }
+        let has_tracked_files = walk.has_tracked_files_below(rela_path);
+        let is_excluded = walk.is_excluded(rela_path, true)?;
+        if !has_tracked_files && (is_excluded || walk.is_repository(rela_path)) {
+            let report = if is_excluded {
+                self.ignored
+            } else {
+                self.mode != UntrackedFiles::None
+            };
+            if report && self.pathspec.is_included(rela_path, Some(true))? {
+                let rela_path = dir_path(rela_path);
+                self.out.push(if is_excluded {
+                    Item::Ignored { rela_path }
+                } else {
+                    Item::Untracked { rela_path }
+                });
+            }
+            return Ok(false);
+        }
+
+        let collapse = self.mode == UntrackedFiles::Collapsed
+            && !has_tracked_files
+            && !is_excluded
+            && self.pathspec.is_included(rela_path, Some(true))?;
+        self.dirs.push((self.out.len(), collapse));
+        Ok(true)
+    }
+
+    fn leave_dir(&mut self, _walk: &mut dirwalk::Walk<'_>, rela_path: &BStr) -> Result<(), Self::Error> {
+        let (start, collapse) = self.dirs.pop().expect("balanced calls to enter_dir() and leave_dir()");
+        if collapse
+            && self.out[start..]
+                .iter()
+                .any(|item| matches!(item, Item::Untracked { .. }))
+        {
+            let items = self.out.split_off(start);
+            self.out.push(Item::Untracked {
+                rela_path: dir_path(rela_path),
+            });
+            self.out
+                .extend(items.into_iter().filter(|item| matches!(item, Item::Ignored { .. })));
+        }
+        Ok(())
+    }
+
+    fn visit_file(&mut self, walk: &mut dirwalk::Walk<'_>, rela_path: &BStr) -> Result<(), Self::Error> {
+        if walk.is_tracked(rela_path) {
+            return Ok(());
+        }
+        if walk.is_excluded(rela_path, false)? {
+            if self.ignored && self.pathspec.is_included(rela_path, Some(false))? {
+                self.out.push(Item::Ignored {
+                    rela_path: rela_path.to_owned(),
+                });
+            }
+        } else if self.mode != UntrackedFiles::None && self.pathspec.is_included(rela_path, Some(false))? {
+            self.out.push(Item::Untracked {
+                rela_path: rela_path.to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn dir_path(rela_path: &BStr) -> BString {
+    let mut rela_path = rela_path.to_owned();
+    rela_path.push_byte(b'/');
+    rela_path
+}
+
+impl From<dirwalk::Error> for Error {
+    fn from(dirwalk::Error { path, source }: dirwalk::Error) -> Self {
+        Error::Io { path, source }
+    }
+}
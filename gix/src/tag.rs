@@ -11,6 +11,12 @@ mod error {
         WriteObject(#[from] crate::object::write::Error),
         #[error(transparent)]
         ReferenceEdit(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        SigningProgram(#[from] crate::signing::program::Error),
+        #[error("Could not sign the tag")]
+        Sign(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
     }
 }
 pub use error::Error;
@@ -0,0 +1,251 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_hash::ObjectId;
+use gix_odb::FindExt;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, FullNameRef, Target,
+};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    Repository, ThreadSafeRepository,
+};
+
+/// What to check out in a new worktree.
+#[derive(Debug, Clone)]
+pub enum Head {
+    /// Check out the branch with the given `name`, which must not be checked out in any other worktree.
+    Branch {
+        /// The full name of the branch, like `refs/heads/main`.
+        name: FullName,
+        /// If set, create the branch to point to this commit first, similar to `git worktree add -b`.
+        ///
+        /// Otherwise, the branch must exist.
+        start_point: Option<ObjectId>,
+    },
+    /// Check out the given commit with a detached `HEAD`, similar to `git worktree add --detach`.
+    Detached(ObjectId),
+}
+
+/// Options for use in [`Repository::worktree_add()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// What to check out in the new worktree.
+    pub head: Head,
+    /// If set, lock the new worktree with the given reason, which may be empty, similar to `git worktree add --lock --reason`.
+    pub lock: Option<BString>,
+    /// If `true`, check out a branch even if it is checked out elsewhere, and reset the branch to create to its
+    /// start point if it already exists, similar to `git worktree add --force` and `-B`.
+    pub force: bool,
+}
+
+/// The error returned by [`Repository::worktree_add()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{}' already exists", path.display())]
+    PathExists { path: PathBuf },
+    #[error("Could not derive a worktree name from '{}'", path.display())]
+    InvalidName { path: PathBuf },
+    #[error("Branch '{name}' is already checked out at '{}'", path.display())]
+    BranchCheckedOut { name: BString, path: PathBuf },
+    #[error("Could not write '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    Realpath(#[from] gix_path::realpath::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    Open(#[from] Box<crate::open::Error>),
+    #[error("Could not create index from tree at {id}")]
+    IndexFromTree {
+        id: ObjectId,
+        source: gix_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    CheckoutOptions(#[from] Box<crate::config::checkout_options::Error>),
+    #[error(transparent)]
+    IndexCheckout(
+        #[from] gix_worktree::checkout::Error<gix_odb::find::existing_object::Error<gix_odb::store::find::Error>>,
+    ),
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[source] std::io::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+}
+
+impl Repository {
+    /// Create a new linked worktree at `path` and check out `options.head` in it, similar to `git worktree add`.
+    ///
+    /// `path` must not exist or be an empty directory. The private git directory of the worktree is created in
+    /// `worktrees/<id>` of the [common directory](Repository::common_dir()), where `<id>` is the last component of `path`,
+    /// made unique with a numeric suffix if needed. It is locked while the worktree is being initialized.
+    /// Once done, the `post-checkout` [hook](crate::hook) is run in the new worktree.
+    ///
+    /// Return the repository of the new worktree.
+    pub fn worktree_add(&self, path: impl AsRef<Path>, options: Options) -> Result<Repository, Error> {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .and_then(|name| gix_path::os_str_into_bstr(name).ok())
+            .filter(|name| !name.is_empty() && !name.starts_with(b"."))
+            .ok_or_else(|| Error::InvalidName { path: path.to_owned() })?
+            .to_owned();
+        let is_empty_or_missing = match std::fs::read_dir(path) {
+            Ok(mut entries) => entries.next().is_none(),
+            Err(err) => err.kind() == std::io::ErrorKind::NotFound,
+        };
+        if !is_empty_or_missing {
+            return Err(Error::PathExists { path: path.to_owned() });
+        }
+
+        let (head, commit) = match &options.head {
+            Head::Branch { name, start_point } => {
+                if !options.force {
+                    if let Some(path) = self.branch_checked_out_at(name.as_ref()) {
+                        return Err(Error::BranchCheckedOut {
+                            name: name.as_bstr().to_owned(),
+                            path,
+                        });
+                    }
+                }
+                let commit = match start_point {
+                    Some(id) => *id,
+                    None => self.find_reference(name.as_ref())?.peel_to_id_in_place()?.detach(),
+                };
+                (format!("ref: {}\n", name.as_bstr()), commit)
+            }
+            Head::Detached(id) => (format!("{id}\n"), *id),
+        };
+        let tree = self.find_object(commit)?.peel_to_tree()?.id;
+
+        if let Head::Branch {
+            name,
+            start_point: Some(start_point),
+        } = &options.head
+        {
+            self.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("branch: Created from {start_point}").into(),
+                    },
+                    expected: if options.force {
+                        PreviousValue::Any
+                    } else {
+                        PreviousValue::MustNotExist
+                    },
+                    new: Target::Peeled(*start_point),
+                },
+                name: name.clone(),
+                deref: false,
+            })?;
+        }
+
+        let worktrees = self.common_dir().join("worktrees");
+        let io = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        std::fs::create_dir_all(&worktrees).map_err(io(&worktrees))?;
+        let mut git_dir = worktrees.join(gix_path::from_bstr(name.as_bstr()));
+        let mut counter = 1;
+        loop {
+            match std::fs::create_dir(&git_dir) {
+                Ok(()) => break,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    git_dir = worktrees.join(gix_path::from_bstr(format!("{name}{counter}").as_bytes().as_bstr()));
+                    counter += 1;
+                }
+                Err(err) => return Err(io(&git_dir)(err)),
+            }
+        }
+        let locked = git_dir.join("locked");
+        std::fs::write(&locked, "initializing\n").map_err(io(&locked))?;
+
+        std::fs::create_dir_all(path).map_err(io(path))?;
+        let path = gix_path::realpath(path)?;
+        let git_dir = gix_path::realpath(git_dir)?;
+        for (file, content) in [
+            (git_dir.join("gitdir"), format!("{}\n", display(&path.join(".git")))),
+            (git_dir.join("commondir"), "../..\n".into()),
+            (git_dir.join("HEAD"), head),
+            (path.join(".git"), format!("gitdir: {}\n", display(&git_dir))),
+        ] {
+            std::fs::write(&file, content).map_err(io(&file))?;
+        }
+
+        let repo: Repository = ThreadSafeRepository::open_opts(&path, self.options.clone())
+            .map_err(Box::new)?
+            .into();
+        let index = gix_index::State::from_tree(&tree, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())
+            .map_err(|err| Error::IndexFromTree { id: tree, source: err })?;
+        let mut index = gix_index::File::from_state(index, repo.index_path());
+        let mut opts = repo.config.checkout_options(repo.git_dir()).map_err(Box::new)?;
+        opts.destination_is_initially_empty = true;
+        gix_worktree::checkout(
+            &mut index,
+            &path,
+            {
+                let objects = repo.objects.clone().into_arc().map_err(Error::OpenArcOdb)?;
+                move |oid, buf| objects.find_blob(oid, buf)
+            },
+            &mut gix_features::progress::Discard,
+            &mut gix_features::progress::Discard,
+            &AtomicBool::default(),
+            opts,
+        )?;
+        index.write(Default::default())?;
+
+        match options.lock {
+            Some(reason) => std::fs::write(&locked, reason),
+            None => std::fs::remove_file(&locked),
+        }
+        .map_err(io(&locked))?;
+
+        // Like `git`, ignore the exit status of the hook as the checkout is already done.
+        repo.run_hook(
+            "post-checkout",
+            [repo.object_hash().null().to_string(), commit.to_string(), "1".into()],
+            None,
+        )
+        .ok();
+        Ok(repo)
+    }
+
+    /// Return the base of the worktree in which the branch `name` is checked out, if any.
+    pub(crate) fn branch_checked_out_at(&self, name: &FullNameRef) -> Option<PathBuf> {
+        let is_checked_out = |git_dir: &Path| {
+            std::fs::read(git_dir.join("HEAD"))
+                .ok()
+                .and_then(|head| head.strip_prefix(b"ref: ").map(|head| head.trim() == name.as_bstr()))
+                .unwrap_or(false)
+        };
+        if !self.config.is_bare && is_checked_out(self.common_dir()) {
+            return Some(gix_discover::path::without_dot_git_dir(self.common_dir().to_owned()));
+        }
+        self.worktrees()
+            .ok()?
+            .into_iter()
+            .find(|worktree| is_checked_out(worktree.git_dir()))
+            .map(|worktree| worktree.base().unwrap_or_else(|_| worktree.git_dir().to_owned()))
+    }
+}
+
+/// Return `path` as it should be written into the files that link a worktree with its private git directory.
+pub(crate) fn display(path: &Path) -> BString {
+    gix_path::to_unix_separators_on_windows(gix_path::into_bstr(path)).into_owned()
+}
@@ -69,6 +69,61 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
 ///
 pub mod proxy;
 
+///
+pub mod add;
+
+///
+pub mod prune {
+    /// The reason for which a worktree is considered prunable, with the messages matching those of `git worktree prune --verbose`.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+    pub enum Reason {
+        /// The entry in the `worktrees` directory isn't a directory.
+        #[error("not a valid directory")]
+        NotADirectory,
+        /// The `gitdir` file linking to the checkout is missing.
+        #[error("gitdir file does not exist")]
+        MissingGitdirFile,
+        /// The `gitdir` file couldn't be read.
+        #[error("unable to read gitdir file")]
+        UnreadableGitdirFile,
+        /// The `gitdir` file is empty.
+        #[error("invalid gitdir file")]
+        InvalidGitdirFile,
+        /// The checkout the `gitdir` file points to doesn't exist anymore.
+        #[error("gitdir file points to non-existent location")]
+        NonExistentLocation,
+    }
+
+    /// The error returned by [`Repository::worktrees_prune()`][crate::Repository::worktrees_prune()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not access or remove '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
+///
+pub mod repair {
+    /// The error returned by [`Repository::worktrees_repair()`][crate::Repository::worktrees_repair()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("'{}' is not a linked worktree of this repository", path.display())]
+        NotAWorktree { path: std::path::PathBuf },
+        #[error(transparent)]
+        Realpath(#[from] gix_path::realpath::Error),
+        #[error("Could not read or write '{}'", path.display())]
+        Io {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+}
+
 ///
 pub mod open_index {
     /// The error returned by [`Worktree::open_index()`][crate::Worktree::open_index()].
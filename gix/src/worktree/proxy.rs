@@ -99,3 +99,183 @@ impl<'repo> Proxy<'repo> {
         Ok(repo.into())
     }
 }
+
+///
+pub mod lock {
+    /// The error returned by [`Proxy::lock()`][super::Proxy::lock()] and [`Proxy::unlock()`][super::Proxy::unlock()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is already locked")]
+        AlreadyLocked { id: crate::bstr::BString },
+        #[error("Worktree '{id}' is not locked")]
+        NotLocked { id: crate::bstr::BString },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+pub mod remove {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and cannot be removed")]
+        Locked { id: crate::bstr::BString },
+        #[error("Worktree at '{}' contains modified or untracked files", base.display())]
+        Dirty { base: PathBuf },
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        StatusPlatform(#[from] crate::object::tree::diff::rewrites::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::Error),
+        #[error("Could not remove '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
+///
+pub mod relocate {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::relocate()`][super::Proxy::relocate()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and cannot be moved")]
+        Locked { id: crate::bstr::BString },
+        #[error("Worktree at '{}' is inaccessible", .base.display())]
+        MissingWorktree { base: PathBuf },
+        #[error("'{}' already exists", path.display())]
+        DestinationExists { path: PathBuf },
+        #[error(transparent)]
+        Realpath(#[from] gix_path::realpath::Error),
+        #[error("Could not move the worktree to '{}' or update its links", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
+/// Modification
+impl<'repo> Proxy<'repo> {
+    /// Lock this worktree so it can't be pruned, moved or removed, optionally providing a `reason`, similar to `git worktree lock`.
+    pub fn lock(&self, reason: Option<&BStr>) -> Result<(), lock::Error> {
+        if self.is_locked() {
+            return Err(lock::Error::AlreadyLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::write(self.git_dir.join("locked"), reason.unwrap_or_default())?;
+        Ok(())
+    }
+
+    /// Unlock this worktree so it can be pruned, moved or removed again, similar to `git worktree unlock`.
+    pub fn unlock(&self) -> Result<(), lock::Error> {
+        if !self.is_locked() {
+            return Err(lock::Error::NotLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::remove_file(self.git_dir.join("locked"))?;
+        Ok(())
+    }
+
+    /// Delete the checkout of this worktree along with its private git directory, similar to `git worktree remove`.
+    ///
+    /// Unless `force` is `true`, the worktree must not contain modified or untracked files. Locked worktrees must be
+    /// [unlocked](Self::unlock()) first.
+    /// If the checkout doesn't exist anymore, only the private git directory is removed.
+    pub fn remove(self, force: bool) -> Result<(), remove::Error> {
+        if self.is_locked() {
+            return Err(remove::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let io = |path: &Path| {
+            let path = path.to_owned();
+            move |source| remove::Error::Io { path, source }
+        };
+        if let Some(base) = self.base().ok().filter(|base| base.is_dir()) {
+            if !force {
+                let repo = ThreadSafeRepository::open_from_paths(
+                    self.git_dir.clone(),
+                    base.clone().into(),
+                    self.parent.options.clone(),
+                )?
+                .to_thread_local();
+                if !repo.status()?.into_outcome(None::<&str>)?.items.is_empty() {
+                    return Err(remove::Error::Dirty { base });
+                }
+            }
+            std::fs::remove_dir_all(&base).map_err(io(&base))?;
+        }
+        std::fs::remove_dir_all(&self.git_dir).map_err(io(&self.git_dir))
+    }
+
+    /// Move the checkout of this worktree to `destination` and update the links between it and its private git directory,
+    /// similar to `git worktree move`.
+    ///
+    /// If `destination` is an existing directory, the worktree is moved into it, retaining its name.
+    /// Locked worktrees must be [unlocked](Self::unlock()) first.
+    ///
+    /// Return the new location of the worktree.
+    pub fn relocate(&self, destination: impl AsRef<Path>) -> Result<PathBuf, relocate::Error> {
+        if self.is_locked() {
+            return Err(relocate::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let base = self
+            .base()
+            .ok()
+            .filter(|base| base.is_dir())
+            .ok_or_else(|| relocate::Error::MissingWorktree {
+                base: self.base().unwrap_or_else(|_| self.git_dir.clone()),
+            })?;
+        let mut destination = destination.as_ref().to_owned();
+        if destination.is_dir() {
+            destination.push(base.file_name().expect("worktrees have a name"));
+        }
+        if destination.exists() {
+            return Err(relocate::Error::DestinationExists { path: destination });
+        }
+        let io = |path: &Path| {
+            let path = path.to_owned();
+            move |source| relocate::Error::Io { path, source }
+        };
+        std::fs::rename(&base, &destination).map_err(io(&destination))?;
+        let destination = gix_path::realpath(destination)?;
+        let gitdir = self.git_dir.join("gitdir");
+        std::fs::write(
+            &gitdir,
+            format!("{}\n", crate::worktree::add::display(&destination.join(".git"))),
+        )
+        .map_err(io(&gitdir))?;
+        Ok(destination)
+    }
+}
+
+/// Maintenance
+impl<'repo> Proxy<'repo> {
+    /// Return the reason for which this worktree would be removed by [`Repository::worktrees_prune()`], or `None` if it
+    /// is locked or its checkout still exists.
+    pub fn prune_reason(&self) -> Option<crate::worktree::prune::Reason> {
+        use crate::worktree::prune::Reason;
+        if !self.git_dir.is_dir() {
+            return Some(Reason::NotADirectory);
+        }
+        if self.is_locked() {
+            return None;
+        }
+        match gix_discover::path::from_plain_file(self.git_dir.join("gitdir")) {
+            None => Some(Reason::MissingGitdirFile),
+            Some(Err(_)) => Some(Reason::UnreadableGitdirFile),
+            Some(Ok(path)) if path.as_os_str().is_empty() => Some(Reason::InvalidGitdirFile),
+            Some(Ok(path)) if !path.exists() => Some(Reason::NonExistentLocation),
+            Some(Ok(_)) => None,
+        }
+    }
+}
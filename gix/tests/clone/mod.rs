@@ -552,6 +552,365 @@ mod blocking_io {
         }
         Ok(())
     }
+
+    mod bundle_uri {
+        use std::{path::Path, sync::atomic::AtomicBool};
+
+        use crate::util::restricted;
+
+        #[test]
+        fn bundle_is_unbundled_before_fetching_the_rest() -> crate::Result {
+            let root = gix_testtools::scripted_fixture_read_only("make_bundle_uri_repo.sh")?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, out) = clone(&root, tmp.path(), None)?
+                .with_bundle_uri(gix::path::into_bstr(root.join("base.bundle")).into_owned())
+                .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+            assert_eq!(
+                repo.find_reference("refs/bundles/main")?.id(),
+                read_id(&root, "base-tip")?,
+                "branches of bundles are stored in their own namespace"
+            );
+            assert_eq!(
+                received_objects(&out),
+                6,
+                "only two commits with their trees and blobs are fetched, the bundle provided the rest"
+            );
+            assert_eq!(
+                repo.find_reference("refs/remotes/origin/main")?
+                    .peel_to_id_in_place()?
+                    .object()?
+                    .into_commit()
+                    .message()?
+                    .summary()
+                    .as_ref(),
+                "c3"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn unusable_bundles_are_ignored() -> crate::Result {
+            let root = gix_testtools::scripted_fixture_read_only("make_bundle_uri_repo.sh")?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, out) = clone(&root, tmp.path(), None)?
+                .with_bundle_uri(gix::path::into_bstr(root.join("incremental.bundle")).into_owned())
+                .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+            assert!(
+                repo.try_find_reference("refs/bundles/main")?.is_none(),
+                "the bundle lacks its prerequisites in an empty repository"
+            );
+            assert_eq!(received_objects(&out), 9, "everything is fetched instead");
+            Ok(())
+        }
+
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        #[test]
+        fn bundle_list_via_http_is_applied_in_order_of_creation() -> crate::Result {
+            let root = gix_testtools::scripted_fixture_read_only("make_bundle_uri_repo.sh")?;
+            let base_url = serve(&root, &["bundle-list", "base.bundle", "incremental.bundle"])?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, out) = clone(
+                &root,
+                tmp.path(),
+                Some(format!("clone.bundleURI={base_url}/bundle-list")),
+            )?
+            .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+
+            assert_eq!(
+                repo.find_reference("refs/bundles/main")?.id(),
+                read_id(&root, "incremental-tip")?,
+                "the incremental bundle was applied on top of the base bundle"
+            );
+            assert_eq!(received_objects(&out), 3, "only the last commit is fetched");
+            Ok(())
+        }
+
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        #[test]
+        fn absolute_paths_in_bundle_lists_via_http_are_relative_to_the_host() -> crate::Result {
+            let root = gix_testtools::scripted_fixture_read_only("make_bundle_uri_repo.sh")?;
+            let base_url = serve(&root, &["bundle-list-absolute", "base.bundle"])?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let repo = gix::init_bare(tmp.path())?;
+            let out = repo.bundle_uri_fetch(
+                format!("{base_url}/bundle-list-absolute").as_str().into(),
+                gix::progress::Discard,
+                &AtomicBool::default(),
+            )?;
+            assert_eq!(out.unbundled.len(), 1);
+            assert_eq!(out.unbundled[0].uri, format!("{base_url}/base.bundle"));
+            assert_eq!(
+                repo.find_reference("refs/bundles/main")?.id(),
+                read_id(&root, "base-tip")?
+            );
+            Ok(())
+        }
+
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        #[test]
+        fn bundle_lists_via_http_may_not_refer_to_local_bundles() -> crate::Result {
+            let root = gix_testtools::scripted_fixture_read_only("make_bundle_uri_repo.sh")?;
+            let base_url = serve(&root, &["bundle-list-local"])?;
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let repo = gix::init_bare(tmp.path())?;
+            let err = repo
+                .bundle_uri_fetch(
+                    format!("{base_url}/bundle-list-local").as_str().into(),
+                    gix::progress::Discard,
+                    &AtomicBool::default(),
+                )
+                .unwrap_err();
+            assert!(
+                matches!(err, gix::bundle::uri::Error::LocalUriInRemoteList { .. }),
+                "{err:?}"
+            );
+            assert!(repo.try_find_reference("refs/bundles/main")?.is_none());
+            Ok(())
+        }
+
+        fn clone(root: &Path, dst: &Path, config: Option<String>) -> crate::Result<gix::clone::PrepareFetch> {
+            Ok(gix::clone::PrepareFetch::new(
+                root.join("server"),
+                dst,
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted().config_overrides(config),
+            )?)
+        }
+
+        fn received_objects(out: &gix::remote::fetch::Outcome) -> usize {
+            match &out.status {
+                gix::remote::fetch::Status::Change { write_pack_bundle, .. } => {
+                    write_pack_bundle.index.num_objects as usize
+                }
+                _ => unreachable!("a clone always carries a change"),
+            }
+        }
+
+        fn read_id(root: &Path, name: &str) -> crate::Result<gix::ObjectId> {
+            Ok(gix::ObjectId::from_hex(
+                std::fs::read_to_string(root.join(name))?.trim().as_bytes(),
+            )?)
+        }
+
+        /// A stand-in for a static file server, serving the given `files` of `root` until the test process ends,
+        /// and returning the URL to request them from.
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        fn serve(root: &Path, files: &[&str]) -> std::io::Result<String> {
+            use std::io::{Read, Write};
+            let files = files
+                .iter()
+                .map(|name| Ok((format!("/{name}"), std::fs::read(root.join(name))?)))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+            let url = format!("http://{}", listener.local_addr()?);
+            std::thread::spawn(move || -> std::io::Result<()> {
+                for stream in listener.incoming() {
+                    let mut stream = stream?;
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let read = stream.read(&mut buf)?;
+                        if read == 0 {
+                            break;
+                        }
+                        request.extend_from_slice(&buf[..read]);
+                    }
+                    let path = String::from_utf8_lossy(&request)
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or_default()
+                        .to_owned();
+                    match files.iter().find(|(name, _)| *name == path) {
+                        Some((_, content)) => {
+                            write!(
+                                stream,
+                                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                                content.len()
+                            )?;
+                            stream.write_all(content)?;
+                        }
+                        None => write!(
+                            stream,
+                            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        )?,
+                    }
+                    stream.flush()?;
+                }
+                Ok(())
+            });
+            Ok(url)
+        }
+    }
+
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    mod packfile_uris {
+        use std::{
+            io::{Read, Write},
+            path::{Path, PathBuf},
+            sync::atomic::AtomicBool,
+        };
+
+        use crate::util::restricted;
+
+        #[test]
+        fn additional_packs_are_downloaded_and_indexed() -> crate::Result {
+            let (root, _tmp) = fixture()?;
+            let (large_blob, pack_hash) = (read_id(&root, "large-blob-id")?, read_id(&root, "large-pack-hash")?);
+            let url = serve_once(std::fs::read(root.join(format!("large-{pack_hash}.pack")))?)?;
+            set_blob_packfile_uri(&root, format!("{large_blob} {pack_hash} {url}"))?;
+
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, out) = clone(&root, tmp.path())?.fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+            match out.status {
+                gix::remote::fetch::Status::Change {
+                    write_pack_bundle,
+                    packfile_uri_bundles,
+                    ..
+                } => {
+                    assert_eq!(packfile_uri_bundles.len(), 1, "one additional pack was advertised");
+                    let uri_bundle = &packfile_uri_bundles[0];
+                    assert_eq!(uri_bundle.index.data_hash, pack_hash);
+                    assert_eq!(uri_bundle.index.num_objects, 1);
+                    assert_eq!(
+                        write_pack_bundle.index.num_objects, 3,
+                        "commit, tree and the small blob are sent inline"
+                    );
+                    assert!(
+                        write_pack_bundle.keep_path.is_none() && uri_bundle.keep_path.is_none(),
+                        "keep files are removed as refs were written"
+                    );
+                }
+                _ => unreachable!("a clone always carries a change"),
+            }
+            assert_eq!(
+                repo.find_object(large_blob)?.data.len(),
+                48894,
+                "the large blob is available"
+            );
+            assert_eq!(pack_files(&repo)?.len(), 4, "two packs with their indices");
+            Ok(())
+        }
+
+        #[test]
+        fn hash_mismatches_remove_all_written_packs() -> crate::Result {
+            let (root, _tmp) = fixture()?;
+            let (large_blob, pack_hash) = (read_id(&root, "large-blob-id")?, read_id(&root, "large-pack-hash")?);
+            let url = serve_once(std::fs::read(root.join(format!("large-{pack_hash}.pack")))?)?;
+            set_blob_packfile_uri(&root, format!("{large_blob} {large_blob} {url}"))?;
+
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let mut prepare = clone(&root, tmp.path())?;
+            let err = prepare
+                .fetch_only(gix::progress::Discard, &AtomicBool::default())
+                .unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    gix::clone::fetch::Error::Fetch(gix::remote::fetch::Error::PackfileUriHashMismatch { expected, actual, .. })
+                        if expected == large_blob && actual == pack_hash
+                ),
+                "{err:?}"
+            );
+            let pack_dir = tmp.path().join("objects").join("pack");
+            assert_eq!(
+                std::fs::read_dir(pack_dir)?.count(),
+                0,
+                "neither the main pack nor the downloaded one are kept"
+            );
+            assert!(
+                std::fs::read_dir(tmp.path().join("objects"))?
+                    .all(|entry| !entry.map_or(false, |e| e.file_name().to_string_lossy().starts_with("incoming-"))),
+                "the quarantine of the downloaded pack is removed as well"
+            );
+            Ok(())
+        }
+
+        fn fixture() -> crate::Result<(PathBuf, gix_testtools::tempfile::TempDir)> {
+            let tmp = gix_testtools::scripted_fixture_writable("make_packfile_uris_repo.sh")?;
+            let root = tmp.path().to_owned();
+            git(&root.join("server"), &["config", "uploadpack.allowSidebandAll", "true"])?;
+            Ok((root, tmp))
+        }
+
+        fn clone(root: &Path, dst: &Path) -> crate::Result<gix::clone::PrepareFetch> {
+            Ok(gix::clone::PrepareFetch::new(
+                root.join("server"),
+                dst,
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted().config_overrides(["protocol.version=2", "fetch.uriProtocols=http"]),
+            )?)
+        }
+
+        fn set_blob_packfile_uri(root: &Path, value: String) -> crate::Result {
+            git(&root.join("server"), &["config", "uploadpack.blobPackfileUri", &value])
+        }
+
+        fn read_id(root: &Path, name: &str) -> crate::Result<gix::ObjectId> {
+            Ok(gix::ObjectId::from_hex(
+                std::fs::read_to_string(root.join(name))?.trim().as_bytes(),
+            )?)
+        }
+
+        fn pack_files(repo: &gix::Repository) -> std::io::Result<Vec<PathBuf>> {
+            Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter(|path| path.extension().map_or(false, |ext| ext == "pack" || ext == "idx"))
+                .collect())
+        }
+
+        fn git(dir: &Path, args: &[&str]) -> crate::Result {
+            let status = std::process::Command::new("git").args(args).current_dir(dir).status()?;
+            assert!(status.success(), "git {args:?} failed");
+            Ok(())
+        }
+
+        /// A stand-in for a CDN, serving `pack` to the first request it receives and returning the URL to request it from.
+        fn serve_once(pack: Vec<u8>) -> std::io::Result<String> {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+            let url = format!("http://{}/large.pack", listener.local_addr()?);
+            std::thread::spawn(move || -> std::io::Result<()> {
+                let (mut stream, _) = listener.accept()?;
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = stream.read(&mut buf)?;
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nContent-Type: application/octet-stream\r\nConnection: close\r\n\r\n",
+                    pack.len()
+                )?;
+                stream.write_all(&pack)?;
+                stream.flush()
+            });
+            Ok(url)
+        }
+    }
 }
 
 #[test]
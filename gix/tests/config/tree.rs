@@ -145,6 +145,67 @@ mod ssh {
     }
 }
 
+mod gpg {
+    use gix::{
+        config::tree::{Gpg, Key},
+        signing::Format,
+    };
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn format() -> crate::Result {
+        for (actual, expected) in [
+            ("openpgp", Format::OpenPgp),
+            ("x509", Format::X509),
+            ("ssh", Format::Ssh),
+        ] {
+            assert_eq!(Gpg::FORMAT.try_into_format(bcow(actual))?, expected);
+            assert!(Gpg::FORMAT.validate(actual.into()).is_ok());
+        }
+
+        assert_eq!(
+            Gpg::FORMAT.try_into_format(bcow("SSH")).unwrap_err().to_string(),
+            "The key \"gpg.format=SSH\" was invalid",
+            "case-sensitive comparisons"
+        );
+        assert!(Gpg::FORMAT.validate("gpg".into()).is_err());
+        Ok(())
+    }
+}
+
+mod merge {
+    use gix::{
+        config::tree::{Key, Merge},
+        diff::blob::merge::ConflictStyle,
+    };
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn conflict_style() -> crate::Result {
+        for (actual, expected) in [
+            ("merge", ConflictStyle::Merge),
+            ("diff3", ConflictStyle::Diff3),
+            ("zdiff3", ConflictStyle::ZealousDiff3),
+        ] {
+            assert_eq!(Merge::CONFLICT_STYLE.try_into_conflict_style(bcow(actual))?, expected);
+            assert!(Merge::CONFLICT_STYLE.validate(actual.into()).is_ok());
+        }
+
+        assert_eq!(
+            Merge::CONFLICT_STYLE
+                .try_into_conflict_style(bcow("Diff3"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"merge.conflictStyle=Diff3\" was invalid",
+            "case-sensitive comparisons"
+        );
+        assert!(Merge::CONFLICT_STYLE.validate("zdiff".into()).is_err());
+        Ok(())
+    }
+}
+
 mod fetch {
     use gix::{
         config::tree::{Fetch, Key},
@@ -178,6 +239,33 @@ mod fetch {
     }
 }
 
+mod push {
+    use gix::config::tree::{push::Mode, Key, Push};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn default() -> crate::Result {
+        for (actual, expected) in [
+            ("nothing", Mode::Nothing),
+            ("current", Mode::Current),
+            ("upstream", Mode::Upstream),
+            ("tracking", Mode::Upstream),
+            ("simple", Mode::Simple),
+            ("matching", Mode::Matching),
+        ] {
+            assert_eq!(Push::DEFAULT.try_into_mode(bcow(actual))?, expected);
+            assert!(Push::DEFAULT.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Push::DEFAULT.try_into_mode(bcow("foo")).unwrap_err().to_string(),
+            "The key \"push.default=foo\" was invalid"
+        );
+        assert!(Push::DEFAULT.validate("foo".into()).is_err());
+        Ok(())
+    }
+}
+
 mod diff {
     use gix::{
         config::tree::{Diff, Key},
@@ -410,6 +498,62 @@ mod core {
         assert!(Core::CHECK_STAT.validate("foo".into()).is_err());
         Ok(())
     }
+
+    #[test]
+    fn autocrlf() -> crate::Result {
+        assert_eq!(Core::AUTO_CRLF.try_into_autocrlf(bcow("input"))?, None);
+        assert_eq!(Core::AUTO_CRLF.try_into_autocrlf(bcow("true"))?, Some(true));
+        assert_eq!(Core::AUTO_CRLF.try_into_autocrlf(bcow("false"))?, Some(false));
+        assert_eq!(
+            Core::AUTO_CRLF.try_into_autocrlf(bcow("foo")).unwrap_err().to_string(),
+            "The key \"core.autocrlf=foo\" was invalid"
+        );
+
+        assert!(Core::AUTO_CRLF.validate("input".into()).is_ok());
+        assert!(Core::AUTO_CRLF.validate("yes".into()).is_ok());
+        assert!(Core::AUTO_CRLF.validate("foo".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn eol() -> crate::Result {
+        assert_eq!(Core::EOL.try_into_eol(bcow("lf"))?, Some(gix_filter::eol::Mode::Lf));
+        assert_eq!(Core::EOL.try_into_eol(bcow("crlf"))?, Some(gix_filter::eol::Mode::CrLf));
+        assert_eq!(Core::EOL.try_into_eol(bcow("native"))?, None);
+        assert_eq!(
+            Core::EOL.try_into_eol(bcow("nl")).unwrap_err().to_string(),
+            "The key \"core.eol=nl\" was invalid"
+        );
+
+        assert!(Core::EOL.validate("crlf".into()).is_ok());
+        assert!(Core::EOL.validate("nl".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn safecrlf() -> crate::Result {
+        use gix_filter::pipeline::CrlfRoundTripCheck;
+        assert_eq!(
+            Core::SAFE_CRLF.try_into_safecrlf(bcow("warn"))?,
+            CrlfRoundTripCheck::Warn
+        );
+        assert_eq!(
+            Core::SAFE_CRLF.try_into_safecrlf(bcow("true"))?,
+            CrlfRoundTripCheck::Fail
+        );
+        assert_eq!(
+            Core::SAFE_CRLF.try_into_safecrlf(bcow("false"))?,
+            CrlfRoundTripCheck::Skip
+        );
+        assert_eq!(
+            Core::SAFE_CRLF.try_into_safecrlf(bcow("foo")).unwrap_err().to_string(),
+            "The key \"core.safecrlf=foo\" was invalid"
+        );
+
+        assert!(Core::SAFE_CRLF.validate("warn".into()).is_ok());
+        assert!(Core::SAFE_CRLF.validate("foo".into()).is_err());
+        Ok(())
+    }
 }
 
 mod index {
@@ -0,0 +1,48 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git checkout -q -b main
+  git commit -q --allow-empty -m c1
+  git commit -q --allow-empty -m c2
+  git branch feature
+  git branch gone
+  git commit -q --allow-empty -m c3
+)
+
+git clone -q remote client
+(cd client
+  git branch -q --track feature origin/feature
+  git branch -q --track gone origin/gone
+  git branch -q no-upstream
+  git branch -q --track local-upstream main
+  git commit-graph write --no-progress --reachable
+)
+
+(cd remote
+  git commit -q --allow-empty -m c4
+  git branch -D -q gone
+)
+
+(cd client
+  git fetch -q --prune
+  git commit -q --allow-empty -m "local main"
+  git checkout -q feature
+  git commit -q --allow-empty -m "local feature 1"
+  git commit -q --allow-empty -m "local feature 2"
+  git checkout -q local-upstream
+  git reset -q --hard HEAD~2
+  git commit -q --allow-empty -m "local upstream"
+  git checkout -q main
+
+  git for-each-ref --format='%(refname) %(upstream)' refs/heads | while read -r name upstream; do
+    if [ -z "$upstream" ]; then
+      echo "$name - - -"
+    elif counts=$(git rev-list --left-right --count "$name...$upstream" 2>/dev/null); then
+      echo "$name $upstream $counts"
+    else
+      echo "$name $upstream - -"
+    fi
+  done | tr '\t' ' ' > baseline
+)
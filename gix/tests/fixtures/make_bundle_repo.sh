@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  echo 2 > file && git commit -q -am c2
+  git tag -a -m "annotated" v1
+  git branch old
+  echo 3 > file && mkdir dir && echo 3 > dir/other && git add dir && git commit -q -am c3
+
+  git bundle create -q ../initial.bundle old
+  git bundle create -q ../incremental.bundle old..main
+  git bundle create -q ../full.bundle main v1
+)
@@ -0,0 +1,48 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git checkout -q -b main
+  echo 1 > file && git add file && git commit -q -m c1
+  git rev-parse HEAD > ../base-tip
+  git bundle create -q ../base.bundle main
+
+  echo 2 > file && git commit -q -am c2
+  git rev-parse HEAD > ../incremental-tip
+  git bundle create -q ../incremental.bundle main~1..main
+
+  echo 3 > file && git commit -q -am c3
+)
+
+# listed out of order, the creation tokens determine the order in which the bundles are applied
+cat <<EOT > bundle-list
+[bundle]
+	version = 1
+	mode = all
+	heuristic = creationToken
+[bundle "incremental"]
+	uri = incremental.bundle
+	creationToken = 2
+[bundle "base"]
+	uri = base.bundle
+	creationToken = 1
+EOT
+
+# absolute paths are relative to the host the list was obtained from
+cat <<EOT > bundle-list-absolute
+[bundle]
+	version = 1
+	mode = all
+[bundle "base"]
+	uri = /base.bundle
+EOT
+
+# bundle lists obtained from a server may not refer to local bundles
+cat <<EOT > bundle-list-local
+[bundle]
+	version = 1
+	mode = any
+[bundle "base"]
+	uri = file://$PWD/base.bundle
+EOT
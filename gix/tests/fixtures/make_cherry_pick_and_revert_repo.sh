@@ -0,0 +1,51 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  printf '1\n2\n3\n' > a
+  echo b > b
+  git add . && git commit -q -m base
+
+  git checkout -q -b release
+  echo release > b
+  git commit -q -am r1
+
+  git checkout -q main
+  printf 'one\n2\n3\n' > a
+  git commit -q -am fix -m "Signed-off-by: Some One <someone@example.com>"
+  git tag fix
+  echo c > c
+  git add c && git commit -q -m feat
+  git tag feat
+
+  git checkout -q -b side main~2
+  echo e > e
+  git add e && git commit -q -m side
+  git checkout -q main
+  git merge -q --no-ff side -m "merge side"
+  git tag merge
+
+  echo conflicting > b
+  git commit -q -am conflicting
+  git tag conflicting
+
+  git checkout -q -b picked release
+  git cherry-pick -x fix feat >/dev/null
+  git cherry-pick -x -m 1 merge >/dev/null
+
+  git checkout -q -b reverted main
+  git revert --no-edit fix >/dev/null
+
+  git checkout -q -b latin1 release
+  echo l > l
+  git add l && printf 'caf\xe9\n' | git -c i18n.commitEncoding=ISO-8859-1 commit -q -F -
+  git tag latin1
+  git checkout -q main
+)
+
+git clone -q --bare repo bare.git
+(cd bare.git
+  git fetch -q origin 'refs/tags/*:refs/tags/*'
+)
@@ -0,0 +1,29 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git checkout -q -b main
+  git commit -q --allow-empty -m c1
+  git branch other
+)
+
+git clone -q server client
+
+(cd server
+  git commit -q --allow-empty -m c2
+  git checkout -q other
+  git commit -q --allow-empty -m c3
+)
+
+# Record what `git` writes into FETCH_HEAD in various situations, leaving the one of the current branch in place.
+(cd client
+  git checkout -q --detach
+  git fetch -q
+  cp .git/FETCH_HEAD ../detached.FETCH_HEAD
+  git checkout -q main
+  git fetch -q origin other
+  cp .git/FETCH_HEAD ../explicit.FETCH_HEAD
+  git fetch -q
+  cp .git/FETCH_HEAD ../tracking-branch.FETCH_HEAD
+)
@@ -0,0 +1,36 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo a > a
+git add a
+git commit -q -m c1
+
+hooks=.git/hooks
+cat > $hooks/pre-commit <<'HOOK'
+#!/bin/sh
+git ls-files >> "$GIT_DIR/pre-commit.log"
+test ! -f reject-commit
+HOOK
+cat > $hooks/commit-msg <<'HOOK'
+#!/bin/sh
+echo "Hook: commit-msg" >> "$1"
+HOOK
+cat > $hooks/post-commit <<'HOOK'
+#!/bin/sh
+echo post-commit >> "$GIT_DIR/hooks.log"
+HOOK
+cat > $hooks/reference-transaction <<'HOOK'
+#!/bin/sh
+{ echo "$1"; cat; } >> "$GIT_DIR/transactions.log"
+test "$1" != prepared || test ! -f "$GIT_DIR/reject-transaction"
+HOOK
+cat > $hooks/custom <<'HOOK'
+#!/bin/sh
+printf '%s\n' "$@" "$GIT_DIR" "$GIT_INDEX_FILE" "$(pwd -P)" > "$GIT_DIR/custom.log"
+cat >> "$GIT_DIR/custom.log"
+exit 3
+HOOK
+cp $hooks/custom $hooks/not-executable
+chmod +x $hooks/pre-commit $hooks/commit-msg $hooks/post-commit $hooks/reference-transaction $hooks/custom
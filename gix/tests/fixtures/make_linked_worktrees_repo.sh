@@ -0,0 +1,12 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir dir
+touch a b dir/c
+git add .
+git commit -q -m c1
+echo hello >> a
+git commit -q -am c2
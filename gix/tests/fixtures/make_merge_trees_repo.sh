@@ -0,0 +1,89 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q repo
+cd repo
+git config merge.conflictStyle merge
+
+function lines() {
+  seq 1 20 | sed "s/^/${1:-line} /"
+}
+
+# Creates the branches `<name>-base`, `<name>-ours` and `<name>-theirs`, with the changes made by the functions
+# `ours` and `theirs` on top of the state created by `base`, and records the output of `git merge-tree` in `<name>.expected`.
+function scenario() {
+  local name=${1:?need scenario name}
+  git checkout -q --orphan "$name-base"
+  git rm -rfq . 2>/dev/null || true
+  base
+  git add -A
+  git commit -qm "$name base" --allow-empty
+  git checkout -q -b "$name-ours"
+  ours
+  git add -A
+  git commit -qm "$name ours" --allow-empty
+  git checkout -q -b "$name-theirs" "$name-base"
+  theirs
+  git add -A
+  git commit -qm "$name theirs" --allow-empty
+  git merge-tree --write-tree --name-only --no-messages "$name-ours" "$name-theirs" > "../$name.expected" || true
+}
+
+function base() { lines > a; lines b > b; lines d > d; }
+function ours() { sed -i.bak 's/line 1$/line 1 ours/' a && rm a.bak; rm d; }
+function theirs() { sed -i.bak 's/b 20$/b 20 theirs/' b && rm b.bak; echo c > c; }
+scenario clean
+
+function base() { lines > a; }
+function ours() { sed -i.bak 's/line 2$/line 2 ours/' a && rm a.bak; }
+function theirs() { sed -i.bak 's/line 19$/line 19 theirs/' a && rm a.bak; }
+scenario clean-content-merge
+
+function base() { lines > a; }
+function ours() { sed -i.bak 's/line 10$/line 10 ours/' a && rm a.bak; }
+function theirs() { sed -i.bak 's/line 10$/line 10 theirs/' a && rm a.bak; }
+scenario content-conflict
+
+function base() { lines > a; echo x > x; }
+function ours() { sed -i.bak 's/line 10$/line 10 ours/' a && rm a.bak; }
+function theirs() { rm a; }
+scenario modify-delete
+
+function base() { lines > a; }
+function ours() { git mv a renamed; }
+function theirs() { sed -i.bak 's/line 10$/line 10 theirs/' a && rm a.bak; }
+scenario rename-modify
+
+function base() { lines > a; }
+function ours() { git mv a ours-name; }
+function theirs() { git mv a theirs-name; }
+scenario rename-rename
+
+function base() { lines > a; }
+function ours() { git mv a renamed; }
+function theirs() { rm a; }
+scenario rename-delete
+
+function base() { echo base > base; }
+function ours() { echo file > x; }
+function theirs() { mkdir x && echo file > x/y; }
+scenario directory-file
+
+function base() { echo base > base; }
+function ours() { echo ours > new; }
+function theirs() { echo theirs > new; }
+scenario add-add
+
+function base() { lines > a; }
+function ours() { chmod +x a; }
+function theirs() { sed -i.bak 's/line 10$/line 10 theirs/' a && rm a.bak; }
+scenario mode-and-content
+
+function base() { mkdir dir && lines > dir/a && lines b > dir/b; }
+function ours() { lines c > dir/c; }
+function theirs() { rm -r dir; }
+scenario delete-directory-with-addition
+
+git checkout -q clean-base
+cd ..
+git clone -q --bare repo bare.git
@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git checkout -q -b main
+  for i in $(seq 20); do
+    git commit -q --allow-empty -m "$i"
+  done
+)
+
+git clone -q server client
+
+(cd server
+  git commit -q --allow-empty -m "21"
+)
@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git checkout -q -b main
+  echo "small" > small
+  seq 1 10000 > large
+  git add . && git commit -q -m "initial"
+
+  # the large blob is served from a separate pack which is to be downloaded by the client
+  git rev-parse HEAD:large > ../large-blob-id
+  git rev-parse HEAD:large | git pack-objects -q ../large > ../large-pack-hash
+)
@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q server
+(cd server
+  git checkout -q -b main
+  echo "a" > a
+  mkdir dir && echo "b" > dir/b
+  git add . && git commit -q -m "initial"
+  echo "a changed" > a
+  git commit -q -am "change a"
+
+  git config uploadpack.allowFilter true
+  git config uploadpack.allowAnySHA1InWant true
+)
+
+git clone -q --no-local --filter=blob:none --no-checkout server client
+
+(cd server
+  echo "c" > c
+  git add c && git commit -q -m "add c"
+  git rev-parse HEAD > ../server-only-commit
+)
@@ -0,0 +1,41 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+printf '1\n2\n3\n' > a
+echo b > b
+git add . && git commit -q -m base
+
+git checkout -q -b feature
+printf 'one\n2\n3\n' > a
+git commit -q -am f1
+echo d > d
+git add d && git commit -q -m "f2" -m "with body"
+git commit -q --allow-empty -m "f3 empty"
+
+git checkout -q -b conflict main
+echo conflicting > b
+git commit -q -am "c1"
+
+git checkout -q -b latin1 main
+echo e > e
+git add e && printf 'caf\xe9\n' | git -c i18n.commitEncoding=ISO-8859-1 commit -q -F -
+
+git checkout -q -b applied main
+echo "main" > b
+git commit -q -am "a1 applied upstream"
+echo f > f
+git add f && git commit -q -m a2
+
+git checkout -q main
+echo "main" > b
+git commit -q -am m1
+echo c > c
+git add c && git commit -q -m m2
+
+git branch feature-rebased feature
+git rebase -q main feature-rebased
+git checkout -q main
+git clone -q --bare . bare.git
@@ -0,0 +1,59 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git checkout -q -b main
+  git commit -q --allow-empty -m c1
+  git branch feature
+  git branch published
+)
+
+git clone -q --bare remote fork
+
+git clone -q remote client
+(cd client
+  git branch -q --track renamed origin/feature
+  git branch -q --track feature origin/feature
+  git branch local-upstream
+  git config branch.local-upstream.remote .
+  git config branch.local-upstream.merge refs/heads/main
+  git branch no-upstream
+  git tag v1
+
+  git remote add fork ../fork
+  git fetch -q fork
+  git branch published
+  git config branch.published.pushRemote fork
+)
+
+# Each entry is made of three lines: the configuration overrides, the spec and the ref it resolves to, or 'error'.
+function baseline() {
+  local overrides=${1?need overrides}
+  local spec=${2:?need spec}
+  local args=()
+  for o in $overrides; do
+    args+=(-c "$o")
+  done
+  { echo "$overrides"; echo "$spec"; } >> ../baseline
+  git "${args[@]}" rev-parse --verify -q --symbolic-full-name "$spec" 2>/dev/null >> ../baseline || echo "error" >> ../baseline
+}
+
+# Capture how `git` resolves upstream and push branches with different configurations.
+(cd client
+  for spec in main@{u} main@{upstream} main@{push} @{u} HEAD@{push} feature@{u} feature@{push} renamed@{u} renamed@{push} \
+              local-upstream@{u} local-upstream@{push} no-upstream@{u} no-upstream@{push} v1@{u} published@{push}; do
+    baseline "" "$spec"
+  done
+
+  for spec in renamed@{push} no-upstream@{push} published@{push}; do
+    baseline "push.default=current" "$spec"
+  done
+  for spec in renamed@{push} main@{push}; do
+    baseline "push.default=upstream" "$spec"
+  done
+  baseline "push.default=nothing" main@{push}
+  baseline "remote.pushDefault=fork" main@{push}
+  baseline "remote.pushDefault=fork remote.fork.push=refs/heads/main:refs/heads/published" main@{push}
+  baseline "remote.pushDefault=fork remote.fork.push=refs/heads/main:refs/heads/published" feature@{push}
+)
@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo '*.ignored' > .gitignore
+printf '1\n2\n3\n' > a
+echo b > b
+mkdir dir && echo c > dir/c
+git add . && git commit -q -m "initial commit"
+
+# The same changes are made by the tests to compare the stash commits.
+echo staged >> a
+git add a
+echo unstaged >> b
+rm dir/c
+echo untracked > untracked
+echo ignored > file.ignored
+git stash push -q --include-untracked -m "from git"
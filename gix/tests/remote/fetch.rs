@@ -10,6 +10,426 @@ mod shallow {
     }
 }
 
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+mod file_protocol {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::{fetch::Status, Direction::Fetch};
+    use gix_protocol::maybe_async;
+    use gix_testtools::tempfile::TempDir;
+
+    use crate::remote;
+
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client", async_std::test)
+    )]
+    async fn fetch_by_spawning_upload_pack_without_daemon() -> crate::Result {
+        let tmp = TempDir::new()?;
+        let repo = gix::init_bare(tmp.path())?;
+        let outcome = repo
+            .remote_at(remote::repo_path("base"))?
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Fetch)?
+            .connect(Fetch)
+            .await?
+            .prepare_fetch(gix::progress::Discard, Default::default())
+            .await?
+            .receive(gix::progress::Discard, &AtomicBool::default())
+            .await?;
+
+        match outcome.status {
+            Status::Change {
+                write_pack_bundle,
+                update_refs,
+                ..
+            } => {
+                assert_eq!(
+                    write_pack_bundle.index.num_objects, 32,
+                    "all objects of the remote are received"
+                );
+                assert_eq!(
+                    update_refs.edits.len(),
+                    13,
+                    "one remote tracking branch per branch, along with the tags pointing into them"
+                );
+            }
+            _ => unreachable!("a pack is needed for the empty repository"),
+        }
+        assert!(repo.find_reference("refs/remotes/origin/main").is_ok());
+        Ok(())
+    }
+
+    mod negotiation {
+        use std::sync::atomic::AtomicBool;
+
+        use gix::{
+            protocol::transport::Protocol,
+            remote::{fetch, fetch::Status, Direction::Fetch},
+        };
+        use gix_protocol::maybe_async;
+        use gix_testtools::tempfile::TempDir;
+
+        #[maybe_async::test(
+            feature = "blocking-network-client",
+            async(feature = "async-network-client", async_std::test)
+        )]
+        async fn v1_stateful_connections_send_done_once_the_server_is_ready() -> crate::Result {
+            let (rounds, num_objects) = fetch_rounds(Protocol::V1, None, false).await?;
+            assert_eq!(num_objects, 1, "only the new commit is sent");
+            assert_eq!(
+                rounds.len(),
+                2,
+                "the server is ready after the first round, and waits for us to be done"
+            );
+            assert_eq!(rounds[0].haves_sent, 16, "all of these are in common");
+            assert_eq!(
+                rounds[1].haves_sent, 0,
+                "no more haves are needed once the server is ready"
+            );
+            Ok(())
+        }
+
+        #[maybe_async::test(
+            feature = "blocking-network-client",
+            async(feature = "async-network-client", async_std::test)
+        )]
+        async fn v2_sends_the_pack_once_the_server_is_ready() -> crate::Result {
+            let (rounds, num_objects) = fetch_rounds(Protocol::V2, None, false).await?;
+            assert_eq!(num_objects, 1, "only the new commit is sent");
+            assert_eq!(rounds.len(), 1, "the server sends the pack right away when it's ready");
+            assert_eq!(rounds[0].haves_sent, 16);
+            Ok(())
+        }
+
+        #[maybe_async::test(
+            feature = "blocking-network-client",
+            async(feature = "async-network-client", async_std::test)
+        )]
+        async fn v2_wait_for_done_lets_the_client_decide_when_negotiation_is_over() -> crate::Result {
+            let (rounds, num_objects) = fetch_rounds(Protocol::V2, None, true).await?;
+            assert_eq!(num_objects, 1, "only the new commit is sent");
+            assert_eq!(rounds.len(), 2, "the server never signals to be ready");
+            assert_eq!(
+                rounds[1].haves_sent, 0,
+                "all remaining commits are known to be in common, so we are done"
+            );
+            Ok(())
+        }
+
+        #[maybe_async::test(
+            feature = "blocking-network-client",
+            async(feature = "async-network-client", async_std::test)
+        )]
+        async fn negotiation_tips_limit_the_haves_to_send() -> crate::Result {
+            for version in [Protocol::V1, Protocol::V2] {
+                let (rounds, num_objects) = fetch_rounds(version, Some("refs/remotes/origin/main~10"), false).await?;
+                assert_eq!(
+                    num_objects, 11,
+                    "the server only knows that we have the tip and its ancestors"
+                );
+                assert_eq!(rounds.len(), 1);
+                assert_eq!(
+                    rounds[0].haves_sent, 10,
+                    "only the tip and its ancestors are sent, instead of the first 16 commits reachable from all refs"
+                );
+
+                let (rounds, num_objects) = fetch_rounds(version, Some("refs/tags/*"), false).await?;
+                assert_eq!(num_objects, 22, "all commits along with the empty tree are sent");
+                assert_eq!(rounds.len(), 1);
+                assert_eq!(rounds[0].haves_sent, 0, "the glob matches nothing, so nothing is sent");
+            }
+            Ok(())
+        }
+
+        /// Return the negotiation rounds and the amount of received objects when fetching the one new commit of the server into
+        /// a client which has all other commits, using the given protocol `version`.
+        #[maybe_async::maybe_async]
+        async fn fetch_rounds(
+            version: Protocol,
+            negotiation_tip: Option<&str>,
+            wait_for_done: bool,
+        ) -> crate::Result<(Vec<fetch::outcome::negotiate::Round>, u32)> {
+            let (mut repo, _tmp) = client()?;
+            {
+                let mut config = repo.config_snapshot_mut();
+                config.set_value(
+                    &gix::config::tree::Protocol::VERSION,
+                    (version as u8).to_string().as_str(),
+                )?;
+                if let Some(tip) = negotiation_tip {
+                    config.set_value(&gix::config::tree::Fetch::NEGOTIATION_TIP, tip)?;
+                }
+            }
+            let outcome = repo
+                .find_remote("origin")?
+                .connect(Fetch)
+                .await?
+                .prepare_fetch(gix::progress::Discard, Default::default())
+                .await?
+                .with_wait_for_done(wait_for_done)
+                .receive(gix::progress::Discard, &AtomicBool::default())
+                .await?;
+            match outcome.status {
+                Status::Change {
+                    write_pack_bundle,
+                    negotiate,
+                    ..
+                } => Ok((negotiate.rounds, write_pack_bundle.index.num_objects)),
+                _ => unreachable!("the server has a new commit"),
+            }
+        }
+
+        fn client() -> crate::Result<(gix::Repository, TempDir)> {
+            let tmp = gix_testtools::scripted_fixture_writable("make_negotiation_repos.sh")?;
+            let repo = gix::open_opts(tmp.path().join("client"), crate::restricted())?;
+            Ok((repo, tmp))
+        }
+    }
+
+    mod fetch_head {
+        use std::sync::atomic::AtomicBool;
+
+        use gix::{
+            bstr::{BString, ByteSlice},
+            remote::Direction::Fetch,
+        };
+        use gix_protocol::maybe_async;
+        use gix_testtools::tempfile::TempDir;
+
+        #[maybe_async::test(
+            feature = "blocking-network-client",
+            async(feature = "async-network-client", async_std::test)
+        )]
+        async fn is_written_like_git_does_with_merge_candidates_first() -> crate::Result {
+            let (repo, tmp) = client()?;
+            fetch(&repo, None, None).await?;
+            assert_eq!(
+                fetch_head(&repo)?,
+                expected(&tmp, "tracking-branch")?,
+                "the merge-ref of the current branch is the only merge candidate"
+            );
+
+            fetch(&repo, Some("other"), None).await?;
+            assert_eq!(
+                fetch_head(&repo)?,
+                expected(&tmp, "explicit")?,
+                "refs matched by the first refspec are merge candidates if it's not a pattern"
+            );
+
+            repo.reference(
+                "HEAD",
+                repo.head_id()?,
+                gix::refs::transaction::PreviousValue::Any,
+                "detach",
+            )?;
+            fetch(&repo, None, None).await?;
+            assert_eq!(
+                fetch_head(&repo)?,
+                expected(&tmp, "detached")?,
+                "without current branch and with a pattern refspec, there is no merge candidate"
+            );
+            Ok(())
+        }
+
+        #[maybe_async::test(
+            feature = "blocking-network-client",
+            async(feature = "async-network-client", async_std::test)
+        )]
+        async fn resolves_to_the_merge_candidate_in_rev_specs() -> crate::Result {
+            let (repo, _tmp) = client()?;
+            fetch(&repo, None, None).await?;
+            assert_eq!(
+                repo.rev_parse_single("FETCH_HEAD")?,
+                repo.rev_parse_single("refs/remotes/origin/main")?
+            );
+            assert_eq!(repo.rev_parse_single("FETCH_HEAD~1")?, repo.head_id()?);
+            Ok(())
+        }
+
+        #[maybe_async::test(
+            feature = "blocking-network-client",
+            async(feature = "async-network-client", async_std::test)
+        )]
+        async fn is_not_written_if_disabled() -> crate::Result {
+            let (mut repo, _tmp) = client()?;
+            fetch(&repo, None, Some(false)).await?;
+            assert!(!repo.git_dir().join("FETCH_HEAD").exists(), "disabled via builder");
+
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Fetch::WRITE_FETCH_HEAD, "false")?;
+            fetch(&repo, None, None).await?;
+            assert!(
+                !repo.git_dir().join("FETCH_HEAD").exists(),
+                "disabled via configuration"
+            );
+
+            fetch(&repo, None, Some(true)).await?;
+            assert!(
+                repo.git_dir().join("FETCH_HEAD").is_file(),
+                "the builder overrides the configuration"
+            );
+            Ok(())
+        }
+
+        #[maybe_async::maybe_async]
+        async fn fetch(repo: &gix::Repository, refspec: Option<&str>, write_fetch_head: Option<bool>) -> crate::Result {
+            let mut remote = repo.find_remote("origin")?;
+            if let Some(refspec) = refspec {
+                remote.replace_refspecs(Some(refspec), Fetch)?;
+            }
+            let mut prepare = remote
+                .connect(Fetch)
+                .await?
+                .prepare_fetch(gix::progress::Discard, Default::default())
+                .await?;
+            if let Some(enabled) = write_fetch_head {
+                prepare = prepare.with_write_fetch_head(enabled);
+            }
+            prepare.receive(gix::progress::Discard, &AtomicBool::default()).await?;
+            Ok(())
+        }
+
+        fn fetch_head(repo: &gix::Repository) -> crate::Result<BString> {
+            Ok(std::fs::read(repo.git_dir().join("FETCH_HEAD"))?.into())
+        }
+
+        fn expected(tmp: &TempDir, name: &str) -> crate::Result<BString> {
+            Ok(std::fs::read(tmp.path().join(format!("{name}.FETCH_HEAD")))?.into())
+        }
+
+        /// A clone whose `main` branch tracks `origin/main`, without `FETCH_HEAD`.
+        fn client() -> crate::Result<(gix::Repository, TempDir)> {
+            let tmp = gix_testtools::scripted_fixture_writable("make_fetch_head_repos.sh")?;
+            let repo = gix::open_opts(tmp.path().join("client"), crate::restricted())?;
+            std::fs::remove_file(repo.git_dir().join("FETCH_HEAD"))?;
+            assert_eq!(
+                expected(&tmp, "tracking-branch")?.lines().count(),
+                2,
+                "sanity check: git writes one line per branch"
+            );
+            Ok((repo, tmp))
+        }
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+mod in_process {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::remote::{fetch::Status, Direction::Fetch};
+    use gix_testtools::tempfile::TempDir;
+
+    use crate::remote;
+
+    fn serve_in_process(repo: &mut gix::Repository) -> crate::Result {
+        repo.config_snapshot_mut()
+            .set_raw_value("gitoxide", Some("core".into()), "inProcessUploadPack", "true")?;
+        Ok(())
+    }
+
+    fn agent(outcome: &gix::remote::fetch::Outcome) -> String {
+        outcome
+            .ref_map
+            .handshake
+            .capabilities
+            .capability("agent")
+            .and_then(|agent| agent.value())
+            .expect("agent is advertised")
+            .to_string()
+    }
+
+    #[test]
+    fn fetch_into_empty_repository() -> crate::Result {
+        let tmp = TempDir::new()?;
+        let mut repo = gix::init_bare(tmp.path())?;
+        serve_in_process(&mut repo)?;
+        let outcome = repo
+            .remote_at(remote::repo_path("base"))?
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Fetch)?
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+
+        assert!(
+            agent(&outcome).starts_with("git/oxide-"),
+            "the server is part of this process"
+        );
+        match outcome.status {
+            Status::Change {
+                write_pack_bundle,
+                update_refs,
+                negotiate,
+                ..
+            } => {
+                assert_eq!(
+                    write_pack_bundle.index.num_objects, 32,
+                    "the same amount of objects as sent by `git-upload-pack`"
+                );
+                assert_eq!(
+                    update_refs.edits.len(),
+                    13,
+                    "one remote tracking branch per branch, along with the tags pointing into them"
+                );
+                assert_eq!(negotiate.rounds.len(), 1, "there is nothing to negotiate");
+            }
+            _ => unreachable!("a pack is needed for the empty repository"),
+        }
+        let tag = repo.find_reference("refs/tags/b-tag")?.id().object()?;
+        assert_eq!(
+            tag.kind,
+            gix::object::Kind::Tag,
+            "annotated tags are included as they point to commits in the pack"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_after_negotiation() -> crate::Result {
+        let tmp = TempDir::new()?;
+        let (mut repo, _) = gix::clone::PrepareFetch::new(
+            remote::repo_path("multi_round/client"),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            gix::open::Options::isolated().config_overrides(["gitoxide.core.inProcessUploadPack=true"]),
+        )?
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            repo.references()?.remote_branches()?.count(),
+            9,
+            "cloning works as well, with all branches of the remote along with its HEAD"
+        );
+        serve_in_process(&mut repo)?;
+
+        let outcome = repo
+            .remote_at(remote::repo_path("multi_round/server"))?
+            .with_refspecs(Some("refs/heads/*:refs/remotes/origin/*"), Fetch)?
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(agent(&outcome).starts_with("git/oxide-"));
+        match outcome.status {
+            Status::Change {
+                write_pack_bundle,
+                negotiate,
+                ..
+            } => {
+                assert_eq!(
+                    negotiate.rounds.len(),
+                    1,
+                    "the server is ready to send a pack as soon as it sees a common commit"
+                );
+                assert_eq!(
+                    write_pack_bundle.index.num_objects, 6,
+                    "only what the client doesn't have is sent, which is one object less than `git-upload-pack` would send"
+                );
+            }
+            _ => unreachable!("the server has a new commit"),
+        }
+        Ok(())
+    }
+}
+
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client-async-std"))]
 mod blocking_and_async_io {
     use std::sync::atomic::AtomicBool;
@@ -449,7 +869,7 @@ mod blocking_and_async_io {
                 .await?;
 
             match res.status {
-                gix::remote::fetch::Status::Change { write_pack_bundle, update_refs, negotiate } => {
+                gix::remote::fetch::Status::Change { write_pack_bundle, update_refs, negotiate, .. } => {
                     assert_eq!(negotiate.rounds.len(), 1);
                     assert_eq!(write_pack_bundle.index.data_hash, hex_to_id(expected_data_hash), );
                     assert_eq!(write_pack_bundle.index.num_objects, 3 + num_objects_offset, "{fetch_tags:?}");
@@ -534,6 +954,7 @@ mod blocking_and_async_io {
                         write_pack_bundle,
                         update_refs,
                         negotiate,
+                        ..
                     } => {
                         assert_eq!(negotiate.rounds.len(), 1);
                         assert_eq!(write_pack_bundle.pack_version, gix::odb::pack::data::Version::V2);
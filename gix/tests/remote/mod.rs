@@ -11,11 +11,9 @@ pub(crate) fn repo(name: &str) -> gix::Repository {
     gix::open_opts(repo_path(name), gix::open::Options::isolated()).unwrap()
 }
 
-/// Spawn a git-daemon hosting all directories in or below `base_dir` if we are in async mode - currently only TCP is
-/// available in async mode, and it's probably going to stay that way as we don't want to chose a particular runtime
-/// in lower-level crates just yet.
-/// Maybe this changes one day once we implement other protocols like spawning a process via `tokio` or `async-std`, or
-/// provide async HTTP implementations as well.
+/// Spawn a git-daemon hosting all directories in or below `base_dir` if we are in async mode with `async-std`, to
+/// exercise the TCP transport which is only available with this runtime.
+/// Local repositories are also reachable in async mode by spawning `git-upload-pack`, but that is tested separately.
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client-async-std"))]
 pub(crate) fn spawn_git_daemon_if_async(
     _base_dir: impl AsRef<std::path::Path>,
@@ -70,6 +68,8 @@ pub(crate) fn cow_str(s: &str) -> Cow<str> {
 
 mod connect;
 pub(crate) mod fetch;
+#[cfg(feature = "blocking-network-client")]
+mod promisor;
 mod ref_map;
 mod save;
 mod name {
@@ -0,0 +1,143 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_odb::Find;
+use gix_testtools::tempfile::TempDir;
+
+use crate::util::restricted;
+
+#[test]
+fn repositories_without_promisor_remotes_have_none() {
+    assert!(crate::remote::repo("base").promisor_remote_names().is_empty());
+}
+
+#[test]
+fn missing_blobs_are_fetched_when_accessed() -> crate::Result {
+    let (client, _tmp) = fixture()?;
+    let repo = gix::open_opts(&client, restricted())?;
+    assert_eq!(repo.promisor_remote_names(), ["origin"]);
+
+    let id = blob_id(&repo, "a", "HEAD")?;
+    assert!(!repo.objects.contains(id), "the blob was filtered during the clone");
+    assert_eq!(repo.find_object(id)?.data, b"a changed\n", "it's fetched on demand");
+    assert!(repo.objects.contains(id));
+    assert_eq!(
+        promisor_files(&repo)?,
+        2,
+        "the new pack is marked as coming from the promisor remote"
+    );
+    Ok(())
+}
+
+#[test]
+fn graph_lookups_do_not_fetch_missing_objects() -> crate::Result {
+    let (client, _tmp) = fixture()?;
+    let repo = gix::open_opts(&client, restricted())?;
+    let id = blob_id(&repo, "a", "HEAD")?;
+    let mut graph = repo.revision_graph::<()>();
+    assert!(
+        graph.try_lookup(&id)?.is_none(),
+        "missing objects aren't commits we know"
+    );
+    assert!(
+        repo.is_ancestor(id, repo.head_id()?).is_err(),
+        "algorithms on the graph see the object as missing"
+    );
+    assert!(!repo.objects.contains(id));
+    assert_eq!(promisor_files(&repo)?, 1, "nothing was fetched");
+    Ok(())
+}
+
+#[test]
+fn lazy_fetching_can_be_disabled() -> crate::Result {
+    let (client, _tmp) = fixture()?;
+    let repo = gix::open_opts(&client, restricted().lazy_fetch(false))?;
+    let id = blob_id(&repo, "a", "HEAD")?;
+    assert!(repo.find_object(id).is_err(), "missing objects stay missing");
+    assert_eq!(promisor_files(&repo)?, 1, "nothing was fetched");
+    Ok(())
+}
+
+#[test]
+fn fetch_from_promisor_remotes_fetches_all_objects_at_once() -> crate::Result {
+    let (client, _tmp) = fixture()?;
+    let repo = gix::open_opts(&client, restricted().lazy_fetch(false))?;
+    let ids = [
+        blob_id(&repo, "a", "HEAD")?,
+        blob_id(&repo, "a", "HEAD~1")?,
+        blob_id(&repo, "dir/b", "HEAD")?,
+    ];
+    repo.fetch_from_promisor_remotes(&ids, gix::progress::Discard, &AtomicBool::default())?;
+    for id in ids {
+        assert!(repo.objects.contains(id));
+    }
+    assert_eq!(promisor_files(&repo)?, 2, "a single pack was received");
+    Ok(())
+}
+
+#[test]
+fn fetch_from_promisor_remotes_uses_the_configured_filter() -> crate::Result {
+    let (client, tmp) = fixture()?;
+    let status = std::process::Command::new("git")
+        .args(["config", "remote.origin.partialCloneFilter", "tree:0"])
+        .current_dir(&client)
+        .status()?;
+    assert!(status.success());
+    let repo = gix::open_opts(&client, restricted().lazy_fetch(false))?;
+    let commit = gix::ObjectId::from_hex(
+        std::fs::read_to_string(tmp.path().join("server-only-commit"))?
+            .trim()
+            .as_bytes(),
+    )?;
+    assert!(!repo.objects.contains(commit));
+
+    repo.fetch_from_promisor_remotes(&[commit], gix::progress::Discard, &AtomicBool::default())?;
+    let tree = repo.find_object(commit)?.into_commit().tree_id()?;
+    assert!(!repo.objects.contains(tree), "the filter omits trees");
+    Ok(())
+}
+
+#[test]
+fn fetch_missing_prefetches_all_objects_at_once() -> crate::Result {
+    let (client, _tmp) = fixture()?;
+    let repo = gix::open_opts(&client, restricted())?;
+    let ids = [blob_id(&repo, "a", "HEAD")?, blob_id(&repo, "dir/b", "HEAD")?];
+    assert_eq!(repo.objects.fetch_missing(ids).expect("fetch works"), 2);
+    assert_eq!(
+        repo.objects.fetch_missing(ids).expect("fetch works"),
+        0,
+        "nothing is missing anymore"
+    );
+    assert_eq!(promisor_files(&repo)?, 2, "a single pack was received");
+    Ok(())
+}
+
+fn fixture() -> crate::Result<(PathBuf, TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_partial_clone_repo.sh")?;
+    let client = tmp.path().join("client");
+    // Point the client to the server in our writable copy.
+    let status = std::process::Command::new("git")
+        .args(["config", "remote.origin.url"])
+        .arg(tmp.path().join("server"))
+        .current_dir(&client)
+        .status()?;
+    assert!(status.success());
+    Ok((client, tmp))
+}
+
+fn blob_id(repo: &gix::Repository, path: &str, rev: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo
+        .rev_parse_single(rev)?
+        .object()?
+        .peel_to_tree()?
+        .lookup_entry_by_path(path)?
+        .expect("present")
+        .oid()
+        .to_owned())
+}
+
+fn promisor_files(repo: &gix::Repository) -> std::io::Result<usize> {
+    Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "promisor"))
+        .count())
+}
@@ -0,0 +1,317 @@
+use gix::{add, bstr::BString, index::entry};
+
+fn blob_of(repo: &gix::Repository, index: &gix::index::State, path: &str) -> crate::Result<Vec<u8>> {
+    let entry = index.entry_by_path_and_stage(path.into(), 0).expect("present");
+    Ok(repo.find_object(entry.id)?.detach().data)
+}
+
+fn paths(paths: &[&str]) -> Vec<BString> {
+    paths.iter().map(|p| (*p).into()).collect()
+}
+
+#[test]
+fn untracked_and_modified_files_are_added_while_ignored_ones_are_skipped() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join(".gitignore"), "*.ignored\n")?;
+    std::fs::write(workdir.join("this"), "changed\n")?;
+    std::fs::write(workdir.join("some/very/new"), "new\n")?;
+    std::fs::write(workdir.join("some/file.ignored"), "ignored\n")?;
+
+    let mut index = repo.open_index()?;
+    let outcome = repo.add_to_index(&mut index, ["this", "some", ".gitignore"], Default::default())?;
+    assert_eq!(
+        outcome,
+        add::Outcome {
+            added: paths(&[".gitignore", "some/very/new"]),
+            modified: paths(&["this"]),
+            removed: vec![],
+        }
+    );
+    assert_eq!(blob_of(&repo, &index, "this")?, b"changed\n");
+    assert_eq!(blob_of(&repo, &index, "some/very/new")?, b"new\n");
+    assert!(index.entry_by_path_and_stage("some/file.ignored".into(), 0).is_none());
+    let entry = index.entry_by_path_and_stage("this".into(), 0).expect("present");
+    assert_ne!(entry.stat, Default::default(), "stat information is refreshed");
+
+    let outcome = repo.add_to_index(&mut index, ["this", "some"], Default::default())?;
+    assert_eq!(outcome, add::Outcome::default(), "nothing changed, so nothing happens");
+
+    let err = repo
+        .add_to_index(&mut index, ["some/file.ignored"], Default::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, add::Error::Ignored { .. }),
+        "explicitly named ignored files are an error"
+    );
+    let outcome = repo.add_to_index(
+        &mut index,
+        ["some/file.ignored"],
+        add::Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.added, paths(&["some/file.ignored"]), "unless they are forced");
+    Ok(())
+}
+
+#[test]
+fn pathspecs_that_do_not_match_are_an_error() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let mut index = repo.open_index()?;
+    let err = repo
+        .add_to_index(&mut index, ["does-not-exist"], Default::default())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "The pathspec 'does-not-exist' did not match any files");
+    Ok(())
+}
+
+#[test]
+fn written_index_produces_the_expected_tree() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join("new"), "new\n")?;
+
+    let mut index = repo.index_from_tree(&repo.head_commit()?.tree_id()?)?;
+    repo.add_to_index(&mut index, ["new"], Default::default())?;
+    let tree_id = repo.write_tree_from_index(&mut index)?;
+    assert_eq!(
+        tree_id
+            .object()?
+            .into_tree()
+            .lookup_entry_by_path("new")?
+            .expect("present")
+            .object_id(),
+        repo.write_blob("new\n")?,
+    );
+    assert!(
+        tree_id.object()?.into_tree().lookup_entry_by_path("this")?.is_some(),
+        "existing entries are kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn intent_to_add() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join("new"), "new\n")?;
+
+    let mut index = repo.open_index()?;
+    let opts = add::Options {
+        intent_to_add: true,
+        ..Default::default()
+    };
+    let outcome = repo.add_to_index(&mut index, ["new"], opts)?;
+    assert_eq!(outcome.added, paths(&["new"]));
+    let entry = index.entry_by_path_and_stage("new".into(), 0).expect("present");
+    assert!(entry.flags.contains(entry::Flags::INTENT_TO_ADD));
+    assert_eq!(entry.id, gix::ObjectId::empty_blob(repo.object_hash()));
+
+    let outcome = repo.add_to_index(&mut index, ["new"], Default::default())?;
+    assert_eq!(
+        outcome.modified,
+        paths(&["new"]),
+        "adding it for real updates the entry"
+    );
+    let entry = index.entry_by_path_and_stage("new".into(), 0).expect("present");
+    assert!(!entry.flags.contains(entry::Flags::INTENT_TO_ADD));
+    assert_eq!(blob_of(&repo, &index, "new")?, b"new\n");
+    Ok(())
+}
+
+#[test]
+fn update_only_with_removals() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::remove_file(workdir.join("this"))?;
+    std::fs::write(workdir.join("new"), "new\n")?;
+
+    let mut index = repo.open_index()?;
+    let outcome = repo.add_to_index(
+        &mut index,
+        None::<&str>,
+        add::Options {
+            update_only: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome, add::Outcome::default(), "removals aren't recorded by default");
+
+    let outcome = repo.add_to_index(
+        &mut index,
+        None::<&str>,
+        add::Options {
+            update_only: true,
+            record_removals: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome,
+        add::Outcome {
+            added: vec![],
+            modified: vec![],
+            removed: paths(&["this"]),
+        },
+        "untracked files are never added"
+    );
+    let entry = index.entry_by_path_and_stage("this".into(), 0).expect("still present");
+    assert!(entry.flags.contains(entry::Flags::REMOVE));
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_resolved_with_the_worktree_version() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join(".gitignore"), "*.ignored\n")?;
+    std::fs::write(workdir.join("conflict"), "resolved\n")?;
+    std::fs::write(workdir.join("conflict.ignored"), "resolved ignored\n")?;
+
+    let mut index = repo.open_index()?;
+    let id = index.entry_by_path_and_stage("this".into(), 0).expect("present").id;
+    let stage = |stage: u32| entry::Flags::from_bits_retain(stage << 12);
+    for path in ["conflict", "conflict.ignored", "deleted-conflict"] {
+        for s in [1, 2, 3] {
+            index.dangerously_push_entry(Default::default(), id, stage(s), entry::Mode::FILE, path.into());
+        }
+    }
+    index.sort_entries();
+
+    let outcome = repo.add_to_index(
+        &mut index,
+        None::<&str>,
+        add::Options {
+            update_only: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome,
+        add::Outcome {
+            added: vec![],
+            modified: paths(&["conflict", "conflict.ignored"]),
+            removed: vec![],
+        },
+        "conflicts are resolved even if they are ignored, and even with `update_only`"
+    );
+    assert_eq!(blob_of(&repo, &index, "conflict")?, b"resolved\n");
+    assert_eq!(blob_of(&repo, &index, "conflict.ignored")?, b"resolved ignored\n");
+    for path in ["conflict", "conflict.ignored"] {
+        assert!(
+            index
+                .entries()
+                .iter()
+                .filter(|e| e.path(&index) == path && e.stage() != 0)
+                .all(|e| e.flags.contains(entry::Flags::REMOVE)),
+            "all stages of {path} are removed"
+        );
+    }
+    assert!(
+        index.entry_by_path_and_stage("deleted-conflict".into(), 2).is_some(),
+        "conflicts of missing files are kept if removals aren't recorded"
+    );
+
+    let outcome = repo.add_to_index(
+        &mut index,
+        ["conflict", "conflict.ignored", ".gitignore"],
+        Default::default(),
+    )?;
+    assert_eq!(
+        outcome,
+        add::Outcome {
+            added: paths(&[".gitignore"]),
+            modified: vec![],
+            removed: vec![],
+        },
+        "resolved conflicts aren't added again"
+    );
+
+    let outcome = repo.add_to_index(
+        &mut index,
+        Some("deleted-conflict"),
+        add::Options {
+            record_removals: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.removed,
+        paths(&["deleted-conflict"]),
+        "each path is listed only once"
+    );
+    assert_eq!(
+        index
+            .entries()
+            .iter()
+            .filter(|e| e.stage() != 0 && !e.flags.contains(entry::Flags::REMOVE))
+            .count(),
+        0,
+        "no conflict is left"
+    );
+    Ok(())
+}
+
+#[test]
+fn chmod() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let mut index = repo.open_index()?;
+    let outcome = repo.add_to_index(
+        &mut index,
+        ["this"],
+        add::Options {
+            chmod: Some(add::Chmod::Executable),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.modified, paths(&["this"]));
+    let entry = index.entry_by_path_and_stage("this".into(), 0).expect("present");
+    assert_eq!(entry.mode, entry::Mode::FILE_EXECUTABLE);
+    Ok(())
+}
+
+#[test]
+fn clean_filters_are_applied() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join(".gitattributes"), "*.txt text\n")?;
+    std::fs::write(workdir.join("file.txt"), "a\r\nb\r\n")?;
+    std::fs::write(workdir.join("file.bin"), "a\r\nb\r\n")?;
+
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, ["file.txt", "file.bin"], Default::default())?;
+    assert_eq!(
+        blob_of(&repo, &index, "file.txt")?,
+        b"a\nb\n",
+        "line endings are normalized"
+    );
+    assert_eq!(
+        blob_of(&repo, &index, "file.bin")?,
+        b"a\r\nb\r\n",
+        "files without attributes are left alone"
+    );
+    Ok(())
+}
+
+#[test]
+fn overlapping_and_excluding_pathspecs() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join("some/very/new"), "new\n")?;
+    std::fs::write(workdir.join("some/other"), "other\n")?;
+
+    let mut index = repo.open_index()?;
+    let outcome = repo.add_to_index(
+        &mut index,
+        ["some", "some/very/new", ":!some/other"],
+        Default::default(),
+    )?;
+    assert_eq!(
+        outcome.added,
+        paths(&["some/very/new"]),
+        "patterns that are shadowed by earlier ones don't count as mismatches, and excludes apply"
+    );
+    Ok(())
+}
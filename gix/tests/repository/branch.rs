@@ -0,0 +1,116 @@
+use gix::{
+    branch::{AheadBehind, Kind, Tracking},
+    bstr::ByteSlice,
+};
+use gix_ref::FullName;
+
+fn client(overrides: &[&str]) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(
+        gix_testtools::scripted_fixture_read_only("make_branch_tracking_repos.sh")?.join("client"),
+        crate::restricted().config_overrides(overrides.iter().copied()),
+    )?)
+}
+
+/// Return `(branch, tracking)` as computed by `git`.
+fn baseline() -> crate::Result<Vec<(FullName, Option<Tracking>)>> {
+    let baseline = std::fs::read(
+        gix_testtools::scripted_fixture_read_only("make_branch_tracking_repos.sh")?.join("client/baseline"),
+    )?;
+    let repo = client(&[])?;
+    let mut out = Vec::new();
+    for line in baseline.lines() {
+        let mut tokens = line.split_str(" ").map(|t| t.to_str().expect("valid UTF-8"));
+        let (name, upstream, ahead, behind) = (
+            tokens.next().expect("name"),
+            tokens.next().expect("upstream"),
+            tokens.next().expect("ahead"),
+            tokens.next().expect("behind"),
+        );
+        let tracking = (upstream != "-").then(|| {
+            Ok::<_, Box<dyn std::error::Error>>(Tracking {
+                upstream: upstream.try_into()?,
+                upstream_id: repo.try_find_reference(upstream)?.map(|r| r.id().detach()),
+                ahead_behind: (ahead != "-").then(|| AheadBehind {
+                    ahead: ahead.parse().expect("number"),
+                    behind: behind.parse().expect("number"),
+                }),
+            })
+        });
+        out.push((name.try_into()?, tracking.transpose()?));
+    }
+    Ok(out)
+}
+
+#[test]
+fn branch_tracking_matches_git_with_and_without_commitgraph() -> crate::Result {
+    let baseline = baseline()?;
+    assert_eq!(baseline.len(), 5, "all local branches are in the baseline");
+    for overrides in [&[][..], &["core.commitGraph=false"]] {
+        let repo = client(overrides)?;
+        for (name, expected) in &baseline {
+            assert_eq!(&repo.branch_tracking(name.as_ref())?, expected, "{name} {overrides:?}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn branch_tracking_of_gone_and_unborn_branches_and_non_branches() -> crate::Result {
+    let repo = client(&["branch.unborn.remote=origin", "branch.unborn.merge=refs/heads/main"])?;
+    let gone = repo
+        .branch_tracking("refs/heads/gone".try_into()?)?
+        .expect("configured");
+    assert!(!gone.upstream_exists(), "the upstream was pruned");
+    assert_eq!(gone.ahead_behind, None);
+
+    let unborn = repo
+        .branch_tracking("refs/heads/unborn".try_into()?)?
+        .expect("configured");
+    assert_eq!(unborn.upstream.as_bstr(), "refs/remotes/origin/main");
+    assert!(unborn.upstream_exists());
+    assert_eq!(unborn.ahead_behind, None, "there is nothing to compare with");
+
+    assert_eq!(repo.branch_tracking("refs/heads/no-upstream".try_into()?)?, None);
+    assert_eq!(
+        repo.branch_tracking("refs/remotes/origin/main".try_into()?)?,
+        None,
+        "only local branches have an upstream"
+    );
+    Ok(())
+}
+
+#[test]
+fn branches_lists_local_and_remote_branches_with_tracking_information() -> crate::Result {
+    let repo = client(&[])?;
+    let branches = repo.branches()?;
+    assert_eq!(
+        branches
+            .iter()
+            .map(|b| (b.name.as_bstr().to_str().expect("valid UTF-8"), b.kind))
+            .collect::<Vec<_>>(),
+        [
+            ("refs/heads/feature", Kind::Local),
+            ("refs/heads/gone", Kind::Local),
+            ("refs/heads/local-upstream", Kind::Local),
+            ("refs/heads/main", Kind::Local),
+            ("refs/heads/no-upstream", Kind::Local),
+            ("refs/remotes/origin/feature", Kind::Remote),
+            ("refs/remotes/origin/main", Kind::Remote),
+        ],
+        "symbolic references like origin/HEAD are skipped"
+    );
+
+    let baseline = baseline()?;
+    for branch in &branches {
+        assert_eq!(branch.id, repo.find_reference(branch.name.as_ref())?.id());
+        let expected = match branch.kind {
+            Kind::Local => baseline
+                .iter()
+                .find_map(|(name, tracking)| (name == &branch.name).then(|| tracking.clone()))
+                .expect("all local branches are in the baseline"),
+            Kind::Remote => None,
+        };
+        assert_eq!(branch.tracking, expected, "{}", branch.name);
+    }
+    Ok(())
+}
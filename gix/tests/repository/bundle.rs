@@ -0,0 +1,202 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::bundle::{File, Header, Version};
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+use gix::remote::fetch::refs::update::Mode;
+use gix_testtools::tempfile::TempDir;
+
+use crate::util::restricted;
+
+fn fixture() -> crate::Result<TempDir> {
+    gix_testtools::scripted_fixture_writable("make_bundle_repo.sh")
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+fn receiver(root: &Path) -> crate::Result<gix::Repository> {
+    let path = root.join("receiver");
+    gix::init_bare(&path)?;
+    Ok(gix::open_opts(path, restricted())?)
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+fn specs(specs: &[&str]) -> Vec<gix::refspec::RefSpec> {
+    specs
+        .iter()
+        .map(|spec| {
+            gix::refspec::parse((*spec).into(), gix::refspec::parse::Operation::Fetch)
+                .expect("valid")
+                .to_owned()
+        })
+        .collect()
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+fn fetch(repo: &gix::Repository, root: &Path, bundle: &str, refspecs: &[&str]) -> crate::Result<Vec<Mode>> {
+    let outcome = repo.bundle_fetch(
+        File::at(root.join(bundle))?,
+        &specs(refspecs),
+        gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    Ok(outcome
+        .update_refs
+        .updates
+        .into_iter()
+        .map(|update| update.mode)
+        .collect())
+}
+
+fn git(dir: &Path, args: &[&str]) -> crate::Result<String> {
+    let out = std::process::Command::new("git").args(args).current_dir(dir).output()?;
+    assert!(
+        out.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+fn id(repo: &gix::Repository, name: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.find_reference(name)?.id().detach())
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+#[test]
+fn fetch_verifies_prerequisites_and_updates_references() -> crate::Result {
+    let tmp = fixture()?;
+    let root = tmp.path();
+    let base = gix::open_opts(root.join("base"), restricted())?;
+    let repo = receiver(root)?;
+
+    let err = fetch(&repo, root, "incremental.bundle", &["refs/heads/*:refs/heads/*"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "The repository lacks 1 prerequisite commit(s) of the bundle, like {}",
+            id(&base, "refs/heads/old")?
+        )
+    );
+
+    assert_eq!(
+        fetch(&repo, root, "initial.bundle", &["refs/heads/*:refs/heads/*"])?,
+        [Mode::New]
+    );
+    assert_eq!(id(&repo, "refs/heads/old")?, id(&base, "refs/heads/old")?);
+    assert_eq!(
+        fetch(&repo, root, "incremental.bundle", &["refs/heads/main:refs/heads/old"])?,
+        [Mode::FastForward],
+        "the prerequisites are now present, and the branch can be fast-forwarded"
+    );
+    assert_eq!(id(&repo, "refs/heads/old")?, id(&base, "refs/heads/main")?);
+
+    assert_eq!(
+        fetch(
+            &repo,
+            root,
+            "full.bundle",
+            &["refs/heads/*:refs/heads/*", "refs/tags/*:refs/tags/*"]
+        )?,
+        [Mode::New, Mode::New]
+    );
+    assert_eq!(id(&repo, "refs/tags/v1")?, id(&base, "refs/tags/v1")?);
+    assert_eq!(
+        fetch(
+            &repo,
+            root,
+            "full.bundle",
+            &["refs/heads/main:refs/heads/main", "refs/heads/main:refs/tags/v1"]
+        )?,
+        [Mode::NoChangeNeeded, Mode::RejectedTagUpdate]
+    );
+    assert_eq!(
+        fetch(&repo, root, "initial.bundle", &["refs/heads/old:refs/heads/main"])?,
+        [Mode::RejectedNonFastForward]
+    );
+    assert_eq!(
+        fetch(&repo, root, "initial.bundle", &["+refs/heads/old:refs/heads/main"])?,
+        [Mode::Forced]
+    );
+    assert_eq!(id(&repo, "refs/heads/main")?, id(&base, "refs/heads/old")?);
+
+    git(repo.git_dir(), &["fsck", "--no-dangling"])?;
+    Ok(())
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+#[test]
+fn fetch_without_refspecs_only_receives_objects() -> crate::Result {
+    let tmp = fixture()?;
+    let root = tmp.path();
+    let repo = receiver(root)?;
+    let outcome = repo.bundle_fetch(
+        File::at(root.join("full.bundle"))?,
+        &[],
+        gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert!(outcome.mappings.is_empty());
+    assert!(outcome.update_refs.updates.is_empty());
+    assert_eq!(outcome.write_pack_bundle.index.num_objects, 11);
+    for r in &outcome.header.refs {
+        assert!(repo.find_object(r.id).is_ok(), "{r:?} is present");
+    }
+    assert_eq!(repo.references()?.all()?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn create_is_compatible_with_git() -> crate::Result {
+    let tmp = fixture()?;
+    let root = tmp.path();
+    let base = gix::open_opts(root.join("base"), restricted())?;
+
+    let create = |name: &str, specs: &[&str], version: Version| -> crate::Result<Header> {
+        let mut out = std::fs::File::create(root.join(name))?;
+        Ok(base.bundle_create(
+            &mut out,
+            specs.iter().copied(),
+            gix::bundle::create::Options { version },
+            gix::progress::Discard,
+            &AtomicBool::default(),
+        )?)
+    };
+    let header_of = |name: &str| -> crate::Result<Header> { Ok(File::at(root.join(name))?.header) };
+
+    let header = create("gix-incremental.bundle", &["old..main"], Version::V2)?;
+    assert_eq!(
+        header,
+        header_of("incremental.bundle")?,
+        "prerequisites and references match the ones written by git"
+    );
+    assert_eq!(header_of("gix-incremental.bundle")?, header);
+    git(&root.join("base"), &["bundle", "verify", "../gix-incremental.bundle"])?;
+
+    let header = create("gix-full.bundle", &["main", "v1"], Version::V3)?;
+    assert_eq!(header.version, Version::V3);
+    assert!(header.prerequisites.is_empty());
+    assert_eq!(header.refs, header_of("full.bundle")?.refs);
+    git(root, &["clone", "-q", "gix-full.bundle", "cloned"])?;
+    git(&root.join("cloned"), &["fsck", "--no-dangling"])?;
+
+    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+    {
+        let repo = receiver(root)?;
+        assert_eq!(
+            fetch(&repo, root, "gix-full.bundle", &["refs/*:refs/*"])?,
+            [Mode::New, Mode::New],
+            "bundles written by us can be read by us"
+        );
+    }
+
+    let err = create(
+        "unnamed.bundle",
+        &[&id(&base, "refs/heads/main")?.to_string()],
+        Version::V2,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Refusing to create a bundle without references, as none of the revisions were named by a reference"
+    );
+    Ok(())
+}
@@ -0,0 +1,184 @@
+#![cfg(unix)]
+use gix::bstr::ByteSlice;
+use gix_ref::transaction::PreviousValue;
+
+use crate::util::{repo_rw_opts, restricted};
+
+fn repo_with(hooks: gix_sec::Permission) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let mut opts = restricted();
+    opts.permissions.hooks = hooks;
+    repo_rw_opts("make_hooks_repo.sh", opts)
+}
+
+fn read(path: impl AsRef<std::path::Path>) -> crate::Result<String> {
+    Ok(std::fs::read(path)?.to_str_lossy().into_owned())
+}
+
+#[test]
+fn run_with_arguments_environment_and_stdin() -> crate::Result {
+    let (repo, _tmp) = repo_with(gix_sec::Permission::Allow)?;
+    let status = repo
+        .run_hook("custom", ["a", "b"], Some(b"input\n"))?
+        .expect("hook ran");
+    assert_eq!(status.code(), Some(3), "the exit status is passed through");
+    assert_eq!(
+        read(repo.git_dir().join("custom.log"))?,
+        format!(
+            "a\nb\n{}\n{}\n{}\ninput\n",
+            repo.git_dir().display(),
+            repo.index_path().display(),
+            gix_path::realpath(repo.work_dir().expect("non-bare"))?.display()
+        ),
+        "hooks receive their arguments and stdin, and run in the worktree with GIT_DIR and GIT_INDEX_FILE set"
+    );
+
+    assert!(
+        repo.run_hook("not-executable", None::<&str>, None)?.is_none(),
+        "only executable hooks run"
+    );
+    assert!(repo.run_hook("missing", None::<&str>, None)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn paths_passed_to_hooks_are_absolute() -> crate::Result {
+    let (_repo, tmp) = repo_with(gix_sec::Permission::Allow)?;
+    let cwd = std::env::current_dir()?;
+    let relative_path = std::iter::repeat(std::path::Path::new(".."))
+        .take(cwd.components().count() - 1)
+        .collect::<std::path::PathBuf>()
+        .join(tmp.path().strip_prefix("/")?);
+    let mut opts = restricted();
+    opts.permissions.hooks = gix_sec::Permission::Allow;
+    let repo = gix::open_opts(relative_path, opts)?;
+    assert!(
+        repo.git_dir().is_relative(),
+        "the precondition: the repository is opened with a relative path"
+    );
+
+    repo.run_hook("custom", None::<&str>, None)?.expect("hook ran");
+    let lines = read(tmp.path().join(".git").join("custom.log"))?;
+    let mut lines = lines.lines();
+    assert_eq!(
+        lines.next(),
+        Some(cwd.join(repo.git_dir()).to_str().expect("valid UTF-8"))
+    );
+    assert_eq!(
+        lines.next(),
+        Some(cwd.join(repo.index_path()).to_str().expect("valid UTF-8")),
+        "even though hooks run in the worktree"
+    );
+    Ok(())
+}
+
+#[test]
+fn hooks_dir_can_be_configured() -> crate::Result {
+    let (mut repo, _tmp) = repo_with(gix_sec::Permission::Allow)?;
+    assert_eq!(repo.hooks_dir()?, repo.common_dir().join("hooks"));
+    repo.config_snapshot_mut()
+        .set_raw_value("core", None, "hooksPath", "custom-hooks")?;
+    assert_eq!(
+        repo.hooks_dir()?,
+        repo.work_dir().expect("non-bare").join("custom-hooks"),
+        "relative paths are relative to the worktree"
+    );
+    assert!(repo.hook_path("custom")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn permissions_control_if_hooks_run() -> crate::Result {
+    let (repo, _tmp) = repo_with(gix_sec::Permission::Deny)?;
+    assert!(repo.run_hook("custom", None::<&str>, None)?.is_none());
+    let head = repo.head_commit()?;
+    let id = repo.commit_with_hooks("HEAD", "denied\n", head.tree_id()?, Some(head.id))?;
+    assert_eq!(id.object()?.into_commit().message_raw()?, "denied\n", "hooks don't run");
+
+    let (repo, _tmp) = repo_with(gix_sec::Permission::Forbid)?;
+    assert!(matches!(
+        repo.run_hook("custom", None::<&str>, None),
+        Err(gix::hook::Error::Forbidden)
+    ));
+    Ok(())
+}
+
+#[test]
+fn commit_only_runs_the_reference_transaction_hook() -> crate::Result {
+    let (repo, _tmp) = repo_with(gix_sec::Permission::Allow)?;
+    let head = repo.head_commit()?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("reject-commit"), "")?;
+    let id = repo.commit("HEAD", "message\n", head.tree_id()?, Some(head.id))?;
+    assert_eq!(id.object()?.into_commit().message_raw()?, "message\n");
+    assert!(!repo.git_dir().join("pre-commit.log").exists());
+    assert!(!repo.git_dir().join("hooks.log").exists());
+    assert_eq!(
+        read(repo.git_dir().join("transactions.log"))?,
+        format!(
+            "prepared\n{old} {id} refs/heads/main\ncommitted\n{old} {id} refs/heads/main\n",
+            old = head.id
+        ),
+    );
+    Ok(())
+}
+
+#[test]
+fn commit_with_hooks_runs_commit_hooks_and_reference_transaction() -> crate::Result {
+    let (repo, _tmp) = repo_with(gix_sec::Permission::Allow)?;
+    let head = repo.head_commit()?;
+    let id = repo.commit_with_hooks("HEAD", "message\n", head.tree_id()?, Some(head.id))?;
+    assert_eq!(
+        id.object()?.into_commit().message_raw()?,
+        "message\nHook: commit-msg\n",
+        "commit-msg can change the message"
+    );
+    assert_eq!(read(repo.git_dir().join("hooks.log"))?, "post-commit\n");
+    assert_eq!(
+        read(repo.git_dir().join("transactions.log"))?,
+        format!(
+            "prepared\n{old} {id} refs/heads/main\ncommitted\n{old} {id} refs/heads/main\n",
+            old = head.id
+        ),
+        "the reference-transaction hook sees the branch, but not the log-only update of HEAD"
+    );
+
+    let empty_tree = gix::ObjectId::empty_tree(repo.object_hash());
+    repo.commit_with_hooks("HEAD", "empty\n", empty_tree, Some(id))?;
+    assert_eq!(
+        read(repo.git_dir().join("pre-commit.log"))?,
+        "a\n",
+        "pre-commit sees the tree to commit, not the index on disk"
+    );
+    let id = repo.head_id()?;
+
+    std::fs::write(repo.work_dir().expect("non-bare").join("reject-commit"), "")?;
+    let err = repo
+        .commit_with_hooks("HEAD", "rejected\n", head.tree_id()?, Some(id))
+        .unwrap_err();
+    assert!(
+        matches!(&err, gix::commit::Error::Hook(gix::hook::Error::Failed { name, .. }) if name == "pre-commit"),
+        "pre-commit can prevent commits"
+    );
+    assert_eq!(repo.head_id()?, id, "HEAD is unchanged");
+    Ok(())
+}
+
+#[test]
+fn rejected_reference_transactions_are_aborted() -> crate::Result {
+    let (repo, _tmp) = repo_with(gix_sec::Permission::Allow)?;
+    std::fs::write(repo.git_dir().join("reject-transaction"), "")?;
+    let head = repo.head_id()?;
+    let err = repo
+        .reference("refs/heads/new", head, PreviousValue::MustNotExist, "create")
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        gix::reference::edit::Error::Hook(gix::hook::Error::Failed { .. })
+    ));
+    assert!(repo.try_find_reference("refs/heads/new")?.is_none());
+    let null = gix::ObjectId::null(repo.object_hash());
+    assert_eq!(
+        read(repo.git_dir().join("transactions.log"))?,
+        format!("prepared\n{null} {head} refs/heads/new\naborted\n{null} {head} refs/heads/new\n")
+    );
+    Ok(())
+}
@@ -0,0 +1,66 @@
+mod write_tree_from_index {
+    #[test]
+    fn writes_trees_and_updates_the_tree_extension() -> crate::Result {
+        let (repo, _tmp) = crate::basic_rw_repo()?;
+        let mut index = repo.index_from_tree(&repo.head_commit()?.tree_id()?)?;
+        let expected_extension = index.tree().cloned();
+
+        let id = repo.write_tree_from_index(&mut index)?;
+        assert_eq!(
+            id,
+            repo.head_commit()?.tree_id()?,
+            "an unchanged index produces the same tree"
+        );
+        assert_eq!(index.tree(), expected_extension.as_ref(), "the extension didn't change");
+
+        let blob = repo.write_blob("new")?.detach();
+        index.dangerously_push_entry(
+            Default::default(),
+            blob,
+            gix::index::entry::Flags::empty(),
+            gix::index::entry::Mode::FILE,
+            "a/b".into(),
+        );
+        index.sort_entries();
+        let id = repo.write_tree_from_index(&mut index)?;
+        assert_ne!(id, repo.head_commit()?.tree_id()?);
+
+        let tree = id.object()?.into_tree();
+        assert_eq!(
+            tree.lookup_entry_by_path("a/b")?.expect("present").object_id(),
+            blob,
+            "the new tree was written"
+        );
+        let extension = index.tree().expect("present");
+        assert_eq!(extension.id, id, "the extension is updated as well");
+        assert_eq!(extension.num_entries, Some(2));
+        assert_eq!(extension.children.len(), 1, "it knows the new sub-tree");
+        Ok(())
+    }
+}
+
+mod index_from_tree {
+    #[test]
+    fn stat_information_is_refreshed_for_unchanged_files() -> crate::Result {
+        let (repo, _tmp) = crate::basic_rw_repo()?;
+        let index = repo.index_from_tree(&repo.head_commit()?.tree_id()?)?;
+        assert_eq!(index.path(), repo.index_path());
+        assert!(index.tree().is_some(), "the tree extension is set");
+        let entry = index.entry_by_path_and_stage("this".into(), 0).expect("present");
+        assert_ne!(
+            entry.stat,
+            Default::default(),
+            "the file on disk is the same as in the tree, so its stat is used"
+        );
+
+        std::fs::write(repo.work_dir().expect("non-bare").join("this"), "changed")?;
+        let index = repo.index_from_tree(&repo.head_commit()?.tree_id()?)?;
+        let entry = index.entry_by_path_and_stage("this".into(), 0).expect("present");
+        assert_eq!(
+            entry.stat,
+            Default::default(),
+            "changed files keep their default stat to force checking their content"
+        );
+        Ok(())
+    }
+}
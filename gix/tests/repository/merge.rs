@@ -0,0 +1,539 @@
+use gix::diff::blob::merge::{Favor, Labels, Resolution};
+use gix::merge::blob::Method;
+use gix_testtools::tempfile;
+
+use crate::util::restricted_and_git;
+
+fn repo_with_attributes(tmp: &tempfile::TempDir, attributes: &str) -> crate::Result<gix::Repository> {
+    let repo = gix::ThreadSafeRepository::init_opts(
+        tmp.path().join("repo"),
+        gix::create::Kind::WithWorktree,
+        Default::default(),
+        restricted_and_git(),
+    )?
+    .to_thread_local();
+    std::fs::write(repo.work_dir().expect("non-bare").join(".gitattributes"), attributes)?;
+    Ok(repo)
+}
+
+fn labels() -> Labels<'static> {
+    Labels {
+        ancestor: None,
+        current: Some("ours".into()),
+        other: Some("theirs".into()),
+    }
+}
+
+const BASE: &[u8] = b"1\n2\n3\n";
+const OURS: &[u8] = b"1\nx\n3\n";
+const THEIRS: &[u8] = b"1\ny\n3\n";
+
+#[test]
+fn text_merge_with_configured_conflict_style_and_marker_size() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = repo_with_attributes(&tmp, "*.big conflict-marker-size=10\n")?;
+    repo.config_snapshot_mut()
+        .set_raw_value("merge", None, "conflictStyle", "diff3")?;
+    let index = gix::index::State::new(repo.object_hash());
+    let mut platform = repo.blob_merge_platform(&index)?;
+    let mut out = Vec::new();
+
+    let outcome = platform.merge("file".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Conflict);
+    assert_eq!(outcome.method, Method::Text);
+    assert_eq!(
+        out, b"1\n<<<<<<< ours\nx\n|||||||\n2\n=======\ny\n>>>>>>> theirs\n3\n",
+        "diff3 style was configured"
+    );
+
+    platform.merge("file.big".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(
+        out, b"1\n<<<<<<<<<< ours\nx\n||||||||||\n2\n==========\ny\n>>>>>>>>>> theirs\n3\n",
+        "the marker size is controlled by attributes"
+    );
+
+    let outcome = platform.merge("file".into(), BASE, OURS, b"1\n2\n3\n4\n", labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Complete);
+    assert_eq!(out, b"1\nx\n3\n4\n");
+    Ok(())
+}
+
+#[test]
+fn builtin_drivers_and_binary_detection() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = repo_with_attributes(
+        &tmp,
+        "*.bin -merge\n*.union merge=union\n*.text merge=text\n*.missing merge=missing\n",
+    )?;
+    let index = gix::index::State::new(repo.object_hash());
+    let mut platform = repo.blob_merge_platform(&index)?;
+    let mut out = Vec::new();
+
+    let outcome = platform.merge("a.bin".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Conflict);
+    assert_eq!(outcome.method, Method::Binary);
+    assert_eq!(out, OURS, "binary merges keep our side");
+
+    let outcome = platform.merge("a.union".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Complete);
+    assert_eq!(out, b"1\nx\ny\n3\n");
+
+    for path in ["a.text", "a.missing"] {
+        let outcome = platform.merge(path.into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+        assert_eq!(
+            outcome.method,
+            Method::Text,
+            "unknown drivers fall back to the text driver"
+        );
+    }
+
+    let outcome = platform.merge("file".into(), BASE, b"1\0\n", THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.method, Method::Binary, "binary content can't be merged as text");
+    assert_eq!(outcome.resolution, Resolution::Conflict);
+
+    platform.options.text.favor = Some(Favor::Theirs);
+    let outcome = platform.merge("file".into(), BASE, b"1\0\n", THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Complete);
+    assert_eq!(out, THEIRS, "favoring a side resolves binary conflicts");
+
+    let outcome = platform.merge("file".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Complete);
+    assert_eq!(out, THEIRS);
+    Ok(())
+}
+
+#[test]
+fn default_driver_is_used_without_merge_attribute() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = repo_with_attributes(&tmp, "*.text merge\n")?;
+    repo.config_snapshot_mut()
+        .set_raw_value("merge", None, "default", "binary")?;
+    let index = gix::index::State::new(repo.object_hash());
+    let mut platform = repo.blob_merge_platform(&index)?;
+    let mut out = Vec::new();
+
+    let outcome = platform.merge("file".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.method, Method::Binary);
+    let outcome = platform.merge("file.text".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(
+        outcome.method,
+        Method::Text,
+        "a set merge attribute always uses the text driver"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn custom_driver() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = repo_with_attributes(&tmp, "*.custom merge=custom\n*.fail merge=fail\n")?;
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("merge", Some("custom".into()), "name", "a custom driver")?;
+        config.set_raw_value(
+            "merge",
+            Some("custom".into()),
+            "driver",
+            "{ cat %O %B; echo %L %P; } >> %A",
+        )?;
+        config.set_raw_value("merge", Some("fail".into()), "driver", "echo conflict > %A; exit 1")?;
+    }
+    let index = gix::index::State::new(repo.object_hash());
+    let mut platform = repo.blob_merge_platform(&index)?;
+    assert_eq!(
+        platform.options.drivers[0],
+        gix::merge::blob::Driver {
+            name: "custom".into(),
+            display_name: Some("a custom driver".into()),
+            command: Some("{ cat %O %B; echo %L %P; } >> %A".into()),
+            recursive: None,
+        }
+    );
+    let mut out = Vec::new();
+
+    let outcome = platform.merge("dir/a b.custom".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Complete);
+    assert_eq!(outcome.method, Method::Driver(0));
+    assert_eq!(out, b"1\nx\n3\n1\n2\n3\n1\ny\n3\n7 dir/a b.custom\n");
+
+    let outcome = platform.merge("a.fail".into(), BASE, OURS, THEIRS, labels(), &mut out)?;
+    assert_eq!(outcome.resolution, Resolution::Conflict);
+    assert_eq!(outcome.method, Method::Driver(1));
+    assert_eq!(out, b"conflict\n");
+    Ok(())
+}
+
+mod trees {
+    use gix::{
+        diff::blob::merge::Labels,
+        merge::tree::{Conflict, Side},
+    };
+
+    use crate::util::restricted_and_git;
+
+    fn labels(name: &str) -> (String, String) {
+        (format!("{name}-ours"), format!("{name}-theirs"))
+    }
+
+    #[test]
+    fn matches_git_merge_tree_in_bare_repository() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_merge_trees_repo.sh")?;
+        let repo = gix::open_opts(tmp.path().join("bare.git"), restricted_and_git())?;
+        assert!(repo.work_dir().is_none(), "merges don't need a worktree");
+
+        for (name, expected_conflicts) in [
+            ("clean", 0),
+            ("clean-content-merge", 0),
+            ("content-conflict", 1),
+            ("modify-delete", 1),
+            ("rename-modify", 0),
+            ("rename-rename", 1),
+            ("rename-delete", 1),
+            ("directory-file", 1),
+            ("add-add", 1),
+            ("mode-and-content", 0),
+            ("delete-directory-with-addition", 0),
+        ] {
+            let (ours, theirs) = labels(name);
+            let outcome = repo.merge_trees(
+                repo.rev_parse_single(format!("{name}-base").as_str())?,
+                repo.rev_parse_single(ours.as_str())?,
+                repo.rev_parse_single(theirs.as_str())?,
+                Labels {
+                    ancestor: None,
+                    current: Some(ours.as_str().into()),
+                    other: Some(theirs.as_str().into()),
+                },
+                repo.tree_merge_options()?,
+            )?;
+            let expected = std::fs::read_to_string(tmp.path().join(format!("{name}.expected")))?;
+            let expected_tree = expected.lines().next().expect("tree id");
+            assert_eq!(
+                outcome.tree.to_string(),
+                expected_tree,
+                "{name}: the merged tree matches the one of git, conflicts: {:?}",
+                outcome.conflicts
+            );
+            assert_eq!(
+                outcome.conflicts.len(),
+                expected_conflicts,
+                "{name}: {:?}",
+                outcome.conflicts
+            );
+            assert_eq!(outcome.has_conflicts(), expected_conflicts != 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn structured_conflicts() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_merge_trees_repo.sh")?;
+        let repo = gix::open_opts(tmp.path().join("bare.git"), restricted_and_git())?;
+        let merge = |name: &str| -> crate::Result<Vec<Conflict>> {
+            Ok(repo
+                .merge_trees(
+                    repo.rev_parse_single(format!("{name}-base").as_str())?,
+                    repo.rev_parse_single(format!("{name}-ours").as_str())?,
+                    repo.rev_parse_single(format!("{name}-theirs").as_str())?,
+                    Labels::default(),
+                    repo.tree_merge_options()?,
+                )?
+                .conflicts)
+        };
+
+        let conflicts = merge("content-conflict")?;
+        assert!(
+            matches!(&conflicts[..], [Conflict::Content { path, base: Some(_), .. }] if path == "a"),
+            "{conflicts:?}"
+        );
+
+        let conflicts = merge("modify-delete")?;
+        assert!(
+            matches!(&conflicts[..], [Conflict::ModifyDelete { path, deleted_by: Side::Theirs, .. }] if path == "a"),
+            "{conflicts:?}"
+        );
+
+        let conflicts = merge("rename-rename")?;
+        assert_eq!(
+            conflicts,
+            [Conflict::RenameRename {
+                base_path: "a".into(),
+                ours_path: "ours-name".into(),
+                theirs_path: "theirs-name".into(),
+            }]
+        );
+
+        let conflicts = merge("rename-delete")?;
+        assert_eq!(
+            conflicts,
+            [Conflict::RenameDelete {
+                base_path: "a".into(),
+                renamed_path: "renamed".into(),
+                deleted_by: Side::Theirs,
+            }]
+        );
+
+        let conflicts = merge("directory-file")?;
+        assert_eq!(
+            conflicts,
+            [Conflict::DirectoryFile {
+                path: "x".into(),
+                moved_to: "x~ours".into(),
+                file_side: Side::Ours,
+            }],
+            "without labels, the side is used as suffix"
+        );
+
+        let conflicts = merge("add-add")?;
+        assert!(
+            matches!(&conflicts[..], [Conflict::Content { path, base: None, .. }] if path == "new"),
+            "{conflicts:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rename_tracking_can_be_disabled() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_merge_trees_repo.sh")?;
+        let mut repo = gix::open_opts(tmp.path().join("bare.git"), restricted_and_git())?;
+        repo.config_snapshot_mut()
+            .set_raw_value("merge", None, "renames", "false")?;
+        let options = repo.tree_merge_options()?;
+        assert!(options.rewrites.is_none());
+        let outcome = repo.merge_trees(
+            repo.rev_parse_single("rename-modify-base")?,
+            repo.rev_parse_single("rename-modify-ours")?,
+            repo.rev_parse_single("rename-modify-theirs")?,
+            Labels::default(),
+            options,
+        )?;
+        assert!(
+            matches!(&outcome.conflicts[..], [Conflict::ModifyDelete { path, deleted_by: Side::Ours, .. }] if path == "a"),
+            "without rename tracking, the rename is a deletion: {:?}",
+            outcome.conflicts
+        );
+        Ok(())
+    }
+}
+
+mod base {
+    use crate::util::named_repo;
+
+    #[test]
+    fn merge_base_and_friends() -> crate::Result {
+        let repo = named_repo("make_repo_with_fork_and_dates.sh")?;
+        let main = repo.rev_parse_single("main")?.detach();
+        let c2 = repo.rev_parse_single("main~1")?.detach();
+        let c1 = repo.rev_parse_single("main~2")?.detach();
+        let branch = repo.rev_parse_single("branch1")?.detach();
+
+        assert_eq!(repo.merge_base(c2, branch)?, c1);
+        assert_eq!(
+            repo.merge_base(main, branch)?,
+            branch,
+            "a direct ancestor is its own merge-base"
+        );
+        assert_eq!(repo.merge_bases_many(c1, &[c2, branch])?, [c1]);
+        assert_eq!(repo.merge_bases_octopus(&[main, c2, branch])?, [c1]);
+        assert_eq!(repo.independent_commits(&[c2, main, branch, main])?, [main]);
+        assert_eq!(repo.independent_commits(&[c2, branch])?, [c2, branch]);
+
+        assert!(repo.is_ancestor(c1, main)?);
+        assert!(repo.is_ancestor(main, main)?);
+        assert!(!repo.is_ancestor(main, c1)?);
+        assert!(!repo.is_ancestor(branch, c2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn rev_spec_merge_bases() -> crate::Result {
+        let repo = named_repo("make_repo_with_fork_and_dates.sh")?;
+        let c1 = repo.rev_parse_single("main~2")?;
+
+        let spec = repo.rev_parse("main~1...branch1")?;
+        assert_eq!(spec.merge_bases()?.expect("merge spec"), [c1]);
+        assert!(
+            repo.rev_parse("main")?.merge_bases()?.is_none(),
+            "only set for merge specs"
+        );
+        assert!(repo.rev_parse("main~1..branch1")?.merge_bases()?.is_none());
+        Ok(())
+    }
+}
+
+mod commit {
+    use gix::{bstr::ByteSlice, merge::commit::Error};
+    use gix_testtools::tempfile;
+
+    use crate::util::restricted;
+
+    fn bare_repo() -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_cherry_pick_and_revert_repo.sh")?;
+        let repo = gix::open_opts(tmp.path().join("bare.git"), restricted())?;
+        assert!(repo.work_dir().is_none());
+        Ok((repo, tmp))
+    }
+
+    fn id(repo: &gix::Repository, spec: &str) -> gix::ObjectId {
+        repo.rev_parse_single(spec).expect("valid spec").detach()
+    }
+
+    fn assert_same_as_git(repo: &gix::Repository, actual: gix::Id<'_>, expected: &str) -> crate::Result {
+        let actual = actual.object()?.into_commit();
+        let expected = repo.find_object(id(repo, expected))?.into_commit();
+        assert_eq!(actual.tree_id()?, expected.tree_id()?);
+        assert_eq!(actual.message_raw()?, expected.message_raw()?);
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick_in_bare_repository_like_git() -> crate::Result {
+        let (repo, _tmp) = bare_repo()?;
+        let options = gix::merge::commit::Options {
+            record_origin: true,
+            ..repo.commit_merge_options()?
+        };
+
+        let mut onto = id(&repo, "release");
+        for (commit, expected) in [("fix", "picked~2"), ("feat", "picked~1")] {
+            let outcome = repo.cherry_pick(id(&repo, commit), onto, options.clone())?;
+            let new = outcome.commit.expect("no conflicts");
+            assert_same_as_git(&repo, new, expected)?;
+            assert_eq!(
+                new.object()?.into_commit().author()?.name,
+                "author",
+                "the original author is retained"
+            );
+            assert_eq!(new.object()?.into_commit().parent_ids().collect::<Vec<_>>(), [onto]);
+            onto = new.detach();
+        }
+
+        let err = repo.cherry_pick(id(&repo, "merge"), onto, options.clone()).unwrap_err();
+        assert!(matches!(err, Error::MainlineMissing { .. }));
+        let err = repo
+            .cherry_pick(
+                id(&repo, "fix"),
+                onto,
+                gix::merge::commit::Options {
+                    mainline: Some(1),
+                    ..options.clone()
+                },
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::MainlineInvalid { mainline: 1, .. }),
+            "not a merge commit"
+        );
+
+        let outcome = repo.cherry_pick(
+            id(&repo, "merge"),
+            onto,
+            gix::merge::commit::Options {
+                mainline: Some(1),
+                ..options.clone()
+            },
+        )?;
+        assert_same_as_git(&repo, outcome.commit.expect("no conflicts"), "picked")?;
+        assert_eq!(
+            repo.rev_parse_single("release")?,
+            id(&repo, "picked~3"),
+            "no reference was changed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_pick_records_the_origin_only_on_request() -> crate::Result {
+        let (repo, _tmp) = bare_repo()?;
+        let options = repo.commit_merge_options()?;
+        assert!(!options.record_origin, "like `git cherry-pick` without `-x`");
+        let outcome = repo.cherry_pick(id(&repo, "feat"), id(&repo, "release"), options.clone())?;
+        assert_eq!(outcome.message, "feat\n");
+
+        let outcome = repo.cherry_pick(
+            id(&repo, "feat"),
+            id(&repo, "release"),
+            gix::merge::commit::Options {
+                record_origin: true,
+                ..options
+            },
+        )?;
+        assert_eq!(
+            outcome.message,
+            format!("feat\n\n(cherry picked from commit {})\n", id(&repo, "feat"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn revert_in_bare_repository_like_git() -> crate::Result {
+        let (repo, _tmp) = bare_repo()?;
+        let outcome = repo.revert(id(&repo, "fix"), id(&repo, "main"), repo.commit_merge_options()?)?;
+        let new = outcome.commit.expect("no conflicts");
+        assert_same_as_git(&repo, new, "reverted")?;
+        assert_eq!(
+            new.object()?.into_commit().author()?.name,
+            "gitoxide",
+            "the author of reverts is the configured one"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn messages_are_kept_as_is_along_with_their_encoding() -> crate::Result {
+        let (repo, _tmp) = bare_repo()?;
+        let options = repo.commit_merge_options()?;
+        let latin1 = id(&repo, "latin1");
+        let picked = repo.cherry_pick(latin1, id(&repo, "main"), options.clone())?;
+        assert_eq!(picked.message, b"caf\xe9\n".as_bstr(), "the message isn't re-encoded");
+        assert_eq!(picked.encoding, Some("ISO-8859-1".into()));
+        let commit = picked.commit.expect("no conflicts").object()?.into_commit();
+        let commit = commit.decode()?;
+        assert_eq!(commit.message, picked.message);
+        assert_eq!(commit.encoding, Some("ISO-8859-1".into()));
+
+        let reverted = repo.revert(latin1, id(&repo, "latin1"), options)?;
+        let expected = [
+            b"Revert \"caf\xe9\"\n\nThis reverts commit ".as_slice(),
+            latin1.to_string().as_bytes(),
+            b".\n",
+        ]
+        .concat();
+        assert_eq!(reverted.message, expected.as_bstr(), "the subject is used as is");
+        assert_eq!(reverted.encoding, Some("ISO-8859-1".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts_and_empty_commits() -> crate::Result {
+        let (repo, _tmp) = bare_repo()?;
+        let options = gix::merge::commit::Options {
+            record_origin: true,
+            ..repo.commit_merge_options()?
+        };
+        let outcome = repo.cherry_pick(id(&repo, "conflicting"), id(&repo, "release"), options.clone())?;
+        assert!(outcome.commit.is_none(), "no commit is created if there are conflicts");
+        assert_eq!(outcome.merge.conflicts.len(), 1);
+        assert_eq!(outcome.merge.conflicts[0].path(), "b");
+        assert!(outcome
+            .message
+            .starts_with(b"conflicting\n\n(cherry picked from commit "));
+        assert_eq!(outcome.author.name, "author");
+
+        let err = repo
+            .cherry_pick(id(&repo, "fix"), id(&repo, "picked"), options.clone())
+            .unwrap_err();
+        assert!(matches!(err, Error::Empty { .. }), "the change is already present");
+        let outcome = repo.cherry_pick(
+            id(&repo, "fix"),
+            id(&repo, "picked"),
+            gix::merge::commit::Options {
+                allow_empty: true,
+                ..options
+            },
+        )?;
+        assert!(outcome.commit.is_some(), "empty commits can be allowed");
+        Ok(())
+    }
+}
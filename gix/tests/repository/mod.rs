@@ -1,12 +1,23 @@
 use gix::Repository;
 
+mod add;
+mod branch;
+mod bundle;
 mod config;
+mod hook;
+mod index;
+mod merge;
 mod object;
 mod open;
+mod pathspec;
+mod rebase;
 mod reference;
 mod remote;
 mod shallow;
+mod signing;
+mod stash;
 mod state;
+mod status;
 mod worktree;
 
 #[test]
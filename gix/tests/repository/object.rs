@@ -193,6 +193,37 @@ mod tag {
         assert_eq!(tag.message, message);
         Ok(())
     }
+
+    #[test]
+    fn signed() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let current_head_id = repo.head_id()?;
+        let mut signed_payload = None;
+        let tag_ref = repo.tag_signed(
+            "v1.0.0",
+            current_head_id,
+            gix_object::Kind::Commit,
+            Some(repo.committer().expect("present")?),
+            "message",
+            gix_ref::transaction::PreviousValue::MustNotExist,
+            |payload| {
+                signed_payload = Some(payload.to_owned());
+                Ok("-----BEGIN PGP SIGNATURE-----\nsig\n-----END PGP SIGNATURE-----\n".into())
+            },
+        )?;
+        let tag = tag_ref.id().object()?.into_tag();
+        assert_eq!(
+            tag.decode()?.pgp_signature.expect("signed"),
+            "-----BEGIN PGP SIGNATURE-----\nsig\n-----END PGP SIGNATURE-----\n"
+        );
+        let signed = tag.signature()?.expect("signed");
+        assert_eq!(
+            Some(signed.payload),
+            signed_payload,
+            "the signature can be verified against the data that was signed"
+        );
+        Ok(())
+    }
 }
 
 mod commit_as {
@@ -234,6 +265,56 @@ mod commit_as {
 
         assert_eq!(commit.committer()?, committer.to_ref());
         assert_eq!(commit.author()?, author.to_ref());
+        assert!(commit.signature().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn signed() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = gix::ThreadSafeRepository::init_opts(
+            &tmp,
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted_and_git(),
+        )?
+        .to_thread_local();
+        let signature = gix::actor::Signature {
+            name: "c".into(),
+            email: "c@example.com".into(),
+            time: gix::date::Time::new(1, 1800),
+        };
+
+        let mut signed_payload = None;
+        let commit_id = repo.commit_as_signed(
+            &signature,
+            &signature,
+            "HEAD",
+            "initial",
+            repo.empty_tree().id,
+            gix::commit::NO_PARENT_IDS,
+            |payload| {
+                signed_payload = Some(payload.to_owned());
+                Ok("-----BEGIN SSH SIGNATURE-----\nsig\n-----END SSH SIGNATURE-----\n".into())
+            },
+        )?;
+        let commit = commit_id.object()?.into_commit();
+        let decoded = commit.decode()?;
+        assert_eq!(
+            decoded.extra_headers().pgp_signature().expect("signed"),
+            "-----BEGIN SSH SIGNATURE-----\nsig\n-----END SSH SIGNATURE-----"
+        );
+        assert_eq!(decoded.message, "initial");
+        let signed = commit.signature().expect("signed");
+        assert_eq!(
+            signed.signature,
+            "-----BEGIN SSH SIGNATURE-----\nsig\n-----END SSH SIGNATURE-----\n"
+        );
+        assert_eq!(
+            Some(signed.payload),
+            signed_payload,
+            "the signature can be verified against the data that was signed"
+        );
         Ok(())
     }
 }
@@ -0,0 +1,76 @@
+use gix::worktree::cache::state::attributes::Source;
+
+#[test]
+fn no_patterns_match_everything() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let index = repo.open_index()?;
+    let mut pathspec = repo.pathspec(None::<&str>, true, &index, Source::WorktreeThenIdMapping)?;
+    assert!(
+        pathspec.attributes().is_none(),
+        "attributes are only needed if patterns use them"
+    );
+    assert!(pathspec.is_included("any/path", None)?);
+    Ok(())
+}
+
+#[test]
+fn magic_signatures_and_excludes() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let index = repo.open_index()?;
+    let mut pathspec = repo.pathspec(
+        [":(icase)SOME", ":(exclude)some/very", ":(glob)*.rs"],
+        false,
+        &index,
+        Source::WorktreeThenIdMapping,
+    )?;
+    assert!(pathspec.is_included("some/file", Some(false))?);
+    assert!(!pathspec.is_included("some/very/file", Some(false))?);
+    assert!(pathspec.is_included("lib.rs", Some(false))?);
+    assert!(
+        !pathspec.is_included("src/lib.rs", Some(false))?,
+        "path-aware globs don't match slashes"
+    );
+    let m = pathspec
+        .pattern_matching_relative_path("lib.rs", Some(false))?
+        .expect("included");
+    assert_eq!(m.sequence_number, 2);
+    Ok(())
+}
+
+#[test]
+fn attributes_are_read_from_the_worktree() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    std::fs::write(
+        repo.work_dir().expect("non-bare").join(".gitattributes"),
+        "*.txt text\n*.bin -text\n",
+    )?;
+    let index = repo.open_index()?;
+    let mut pathspec = repo.pathspec(
+        [":(attr:text)", ":(attr:-text)dir"],
+        true,
+        &index,
+        Source::WorktreeThenIdMapping,
+    )?;
+    assert!(pathspec.attributes().is_some());
+    assert!(pathspec.is_included("file.txt", Some(false))?);
+    assert!(pathspec.is_included("dir/file.bin", Some(false))?);
+    assert!(!pathspec.is_included("file.bin", Some(false))?);
+    assert!(
+        !pathspec.is_included("dir/file", Some(false))?,
+        "unspecified attributes don't match"
+    );
+    Ok(())
+}
+
+#[test]
+fn attribute_errors_are_propagated() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    std::fs::create_dir_all(repo.work_dir().expect("non-bare").join("dir/.gitattributes"))?;
+    let index = repo.open_index()?;
+    let mut pathspec = repo.pathspec([":(attr:text)"], true, &index, Source::WorktreeThenIdMapping)?;
+    let err = pathspec
+        .is_included("dir/file", Some(false))
+        .expect_err("the attributes file can't be read");
+    assert_eq!(err.path, "dir/file");
+    Ok(())
+}
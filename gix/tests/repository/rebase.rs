@@ -0,0 +1,322 @@
+use gix::{
+    bstr::ByteSlice,
+    rebase,
+    rebase::{state, Stop},
+    sequencer::todo,
+};
+use gix_ref::FullNameRef;
+
+use crate::util::repo_rw;
+
+fn name(name: &str) -> &FullNameRef {
+    name.try_into().expect("valid")
+}
+
+/// Return the tree and message of the first `count` commits starting at `tip`, from newest to oldest.
+fn trees_and_messages(
+    repo: &gix::Repository,
+    tip: gix::ObjectId,
+    count: usize,
+) -> crate::Result<Vec<(gix::ObjectId, String)>> {
+    let mut out = Vec::new();
+    let mut id = tip;
+    for _ in 0..count {
+        let commit = repo.find_object(id)?.into_commit();
+        out.push((
+            commit.tree_id()?.detach(),
+            commit.message_raw()?.to_str_lossy().into_owned(),
+        ));
+        id = commit.parent_ids().next().expect("parent").detach();
+    }
+    Ok(out)
+}
+
+#[test]
+fn rebase_branch_in_memory_like_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_rebase_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let orig_head = repo.rev_parse_single("feature")?.detach();
+
+    let mut rebase = repo.rebase(Some(name("refs/heads/feature")), main, None)?;
+    assert_eq!(
+        rebase
+            .state()
+            .todo
+            .instructions
+            .iter()
+            .map(todo::Instruction::name)
+            .collect::<Vec<_>>(),
+        ["pick", "pick", "pick"]
+    );
+    assert!(rebase.run()?.is_none(), "there are no conflicts");
+    let head = rebase.finish()?;
+
+    assert_eq!(repo.rev_parse_single("feature")?, head);
+    let expected = repo.rev_parse_single("feature-rebased")?.detach();
+    assert_eq!(
+        trees_and_messages(&repo, head.detach(), 4)?,
+        trees_and_messages(&repo, expected, 4)?,
+        "the rebased commits have the same trees and messages as the ones created by git, including the empty commit"
+    );
+    assert_eq!(
+        head.object()?.into_commit().author()?.name,
+        "author",
+        "the original author is retained"
+    );
+    assert_eq!(
+        repo.head_name()?.expect("attached").as_bstr(),
+        "refs/heads/main",
+        "HEAD isn't touched"
+    );
+    assert_eq!(
+        repo.find_reference("feature")?
+            .log_iter()
+            .rev()?
+            .expect("log")
+            .next()
+            .expect("entry")?
+            .message,
+        format!("rebase (finish): refs/heads/feature onto {}", main.to_hex_with_len(7))
+    );
+    assert_ne!(head, orig_head);
+    Ok(())
+}
+
+#[test]
+fn messages_are_kept_as_is_along_with_their_encoding() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_rebase_repo.sh")?;
+    let main = repo.rev_parse_single("main")?;
+    let mut rebase = repo.rebase(Some(name("refs/heads/latin1")), main, None)?;
+    assert!(rebase.run()?.is_none());
+    let head = rebase.finish()?;
+
+    let commit = head.object()?.into_commit();
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [main]);
+    let commit = commit.decode()?;
+    assert_eq!(commit.message, b"caf\xe9\n".as_bstr(), "the message isn't re-encoded");
+    assert_eq!(commit.encoding, Some("ISO-8859-1".into()));
+    Ok(())
+}
+
+/// Return the messages of all commits from `tip` until `base`, from newest to oldest.
+fn messages_until(repo: &gix::Repository, tip: gix::Id<'_>, base: gix::Id<'_>) -> crate::Result<Vec<String>> {
+    let mut out = Vec::new();
+    let mut id = tip.detach();
+    while id != base {
+        let commit = repo.find_object(id)?.into_commit();
+        out.push(commit.message_raw()?.to_str_lossy().trim_end().to_owned());
+        id = commit.parent_ids().next().expect("parent").detach();
+    }
+    Ok(out)
+}
+
+#[test]
+fn commits_that_become_empty_are_dropped_kept_or_stopped_at() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_rebase_repo.sh")?;
+    let main = repo.rev_parse_single("main")?;
+    let a1 = repo.rev_parse_single("applied~1")?.detach();
+
+    let mut rebase = repo.rebase(Some(name("refs/heads/applied")), main, None)?;
+    assert_eq!(rebase.options.empty, rebase::Empty::Drop, "dropping is the default");
+    assert!(rebase.run()?.is_none());
+    assert_eq!(
+        messages_until(&repo, rebase.head(), main)?,
+        ["a2"],
+        "the empty commit was dropped"
+    );
+
+    for (empty, expected) in [
+        (rebase::Empty::Keep, &["a2", "a1 applied upstream"][..]),
+        (rebase::Empty::Stop, &["a2"]),
+    ] {
+        let mut rebase = repo.rebase(Some(name("refs/heads/applied")), main, None)?;
+        rebase.options.empty = empty;
+        rebase.options.force = true;
+        if empty == rebase::Empty::Stop {
+            assert!(matches!(rebase.run()?, Some(Stop::Empty { commit }) if commit == a1));
+            assert!(rebase.step().is_err(), "the empty commit must be kept or skipped first");
+            rebase.skip()?;
+        }
+        assert!(rebase.run()?.is_none());
+        assert_eq!(messages_until(&repo, rebase.head(), main)?, expected, "{empty:?}");
+    }
+
+    let mut rebase = repo.rebase(Some(name("refs/heads/applied")), main, None)?;
+    for instruction in &mut rebase.todo_mut().instructions {
+        if let todo::Instruction::Pick { commit, subject } = instruction {
+            *instruction = todo::Instruction::Edit {
+                commit: std::mem::take(commit),
+                subject: std::mem::take(subject),
+            };
+        }
+    }
+    assert!(
+        matches!(rebase.run()?, Some(Stop::Edit { commit }) if commit == a1),
+        "edits stop even if the commit was dropped"
+    );
+    assert_eq!(rebase.head(), main, "nothing was picked");
+    Ok(())
+}
+
+#[test]
+fn commits_that_start_empty_are_kept_unless_configured_otherwise() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_rebase_repo.sh")?;
+    let main = repo.rev_parse_single("main")?;
+    for (keep_empty, expected) in [(true, &["f3 empty", "f2", "f1"][..]), (false, &["f2", "f1"])] {
+        let mut rebase = repo.rebase(Some(name("refs/heads/feature")), main, None)?;
+        rebase.options.keep_empty = keep_empty;
+        assert!(rebase.run()?.is_none());
+        assert_eq!(
+            messages_until(&repo, rebase.head(), main)?
+                .iter()
+                .map(|message| message.lines().next().expect("subject"))
+                .collect::<Vec<_>>(),
+            expected,
+            "keep_empty = {keep_empty}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn conflicts_stop_the_rebase_and_can_be_resolved_after_resuming() -> crate::Result {
+    let (repo, tmp) = repo_rw("make_rebase_repo.sh")?;
+    let main = repo.rev_parse_single("main")?;
+    let mut rebase = repo.rebase(Some(name("refs/heads/conflict")), main, None)?;
+    assert!(rebase.run()?.is_some());
+    assert!(
+        matches!(rebase.persist(), Err(rebase::Error::PersistWithWorktree)),
+        "the worktree wouldn't match HEAD anymore"
+    );
+    assert!(repo.rebase_state()?.is_none(), "nothing was written");
+
+    let repo = gix::open_opts(tmp.path().join("bare.git"), crate::restricted())?;
+    let main = repo.rev_parse_single("main")?;
+    let c1 = repo.rev_parse_single("conflict")?.detach();
+
+    let mut rebase = repo.rebase(Some(name("refs/heads/conflict")), main, None)?;
+    match rebase.run()? {
+        Some(Stop::Conflict { commit, merge }) => {
+            assert_eq!(commit, c1);
+            assert!(merge.has_conflicts());
+        }
+        _ => unreachable!("the change to 'b' conflicts"),
+    }
+    assert!(rebase.step().is_err(), "conflicts must be resolved first");
+    rebase.persist()?;
+
+    assert!(matches!(repo.rebase_state()?, Some(state::State::Merge(_))));
+    assert_eq!(repo.head_id()?, main, "HEAD is detached at the current commit");
+    drop(rebase);
+
+    let mut rebase = repo.rebase_resume()?.expect("in progress");
+    assert_eq!(rebase.state().stopped_at, Some(c1));
+    let resolved = rebase.resolve_conflicts(repo.rev_parse_single("conflict^{tree}")?)?;
+    assert!(rebase.run()?.is_none());
+    let head = rebase.finish()?;
+    assert_eq!(head, resolved);
+
+    let commit = head.object()?.into_commit();
+    assert_eq!(
+        commit.message_raw()?,
+        "c1\n",
+        "the message is taken from the original commit"
+    );
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [main]);
+    assert_eq!(repo.rev_parse_single("conflict")?, head);
+    assert_eq!(
+        repo.head_name()?.expect("attached").as_bstr(),
+        "refs/heads/conflict",
+        "HEAD is attached to the rebased branch again"
+    );
+    assert!(repo.rebase_state()?.is_none(), "the state was removed");
+    Ok(())
+}
+
+#[test]
+fn todo_list_instructions() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_rebase_repo.sh")?;
+    let main = repo.rev_parse_single("main")?.detach();
+    let feature = repo.rev_parse_single("feature")?.detach();
+    let [f1, f2, f3] = ["feature~2", "feature~1", "feature"].map(|spec| {
+        repo.rev_parse_single(spec)
+            .expect("present")
+            .to_hex_with_len(7)
+            .to_string()
+    });
+
+    let list = todo::List::from_bytes(
+        format!(
+            "label onto\n\
+             pick {f1}\n\
+             squash {f2}\n\
+             update-ref refs/heads/squashed\n\
+             label squashed\n\
+             reset onto\n\
+             pick {f3}\n\
+             fixup -C {f2}\n\
+             merge -c {f3} squashed\n\
+             exec make\n\
+             break\n"
+        )
+        .as_bytes(),
+        b'#',
+    )?;
+    let mut rebase = gix::rebase::Rebase::new(
+        &repo,
+        state::merge::State::new(Some("refs/heads/feature".into()), main, feature, list),
+        gix::rebase::Options {
+            merge: repo.tree_merge_options()?,
+            force: false,
+            empty: gix::rebase::Empty::Drop,
+            keep_empty: true,
+        },
+    );
+
+    let squashed = match rebase.run()? {
+        Some(Stop::EditMessage { .. }) => {
+            let merge = rebase.head().object()?.into_commit();
+            assert_eq!(
+                merge.message_raw()?,
+                "f3 empty\n",
+                "-c uses the message of the original merge commit"
+            );
+            assert_eq!(merge.parent_ids().count(), 2);
+            let second_parent = merge.parent_ids().nth(1).expect("second parent").detach();
+            second_parent
+        }
+        _ => unreachable!("merge -c stops to edit the message"),
+    };
+    let commit = repo.find_object(squashed)?.into_commit();
+    assert_eq!(
+        commit.message_raw()?,
+        "f1\n\nf2\n\nwith body\n",
+        "messages are combined"
+    );
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [main]);
+
+    let reworded = rebase.reword("merged\n".into())?;
+    match rebase.run()? {
+        Some(Stop::Exec { command }) => assert_eq!(command, "make"),
+        _ => unreachable!("exec is left to the caller"),
+    }
+    assert!(matches!(rebase.run()?, Some(Stop::Break)));
+    assert!(rebase.run()?.is_none());
+    let head = rebase.finish()?;
+    assert_eq!(head, reworded);
+
+    let commit = head.object()?.into_commit();
+    assert_eq!(commit.message_raw()?, "merged\n");
+    assert_eq!(commit.parent_ids().nth(1).expect("second parent"), squashed);
+    assert_eq!(repo.rev_parse_single("feature")?, head, "the branch was updated");
+    assert_eq!(
+        repo.rev_parse_single("refs/heads/squashed")?,
+        squashed,
+        "update-ref instructions are applied"
+    );
+    assert!(
+        repo.try_find_reference("refs/rewritten/onto")?.is_none(),
+        "labels are deleted when done"
+    );
+    Ok(())
+}
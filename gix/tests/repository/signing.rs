@@ -0,0 +1,141 @@
+use std::process::Command;
+
+use gix::signing::Format;
+use gix_testtools::tempfile;
+
+use crate::util::restricted_and_git;
+
+fn repo_in(tmp: &tempfile::TempDir) -> crate::Result<gix::Repository> {
+    Ok(gix::ThreadSafeRepository::init_opts(
+        tmp.path().join("repo"),
+        gix::create::Kind::WithWorktree,
+        Default::default(),
+        restricted_and_git(),
+    )?
+    .to_thread_local())
+}
+
+#[test]
+fn signing_program_from_configuration() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = repo_in(&tmp)?;
+    let program = repo.signing_program(None)?;
+    assert_eq!(program.format, Format::OpenPgp, "the default");
+    assert_eq!(program.program, "gpg");
+
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("gpg", None, "program", "custom-gpg")?;
+        config.set_raw_value("user", None, "name", "name")?;
+        config.set_raw_value("user", None, "email", "name@example.com")?;
+    }
+    let program = repo.signing_program(None)?;
+    assert_eq!(program.program, "custom-gpg");
+    assert_eq!(
+        program.signing_key.expect("derived from committer"),
+        "name <name@example.com>",
+        "without signing key, the committer is used"
+    );
+    assert_eq!(repo.signing_program(Some(Format::X509))?.program, "gpgsm");
+
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("gpg", None, "format", "ssh")?;
+        config.set_raw_value("gpg", Some("ssh".into()), "program", "custom-ssh-keygen")?;
+        config.set_raw_value("gpg", Some("ssh".into()), "allowedSignersFile", "allowed")?;
+    }
+    let program = repo.signing_program(None)?;
+    assert_eq!(program.format, Format::Ssh);
+    assert_eq!(program.program, "custom-ssh-keygen");
+    assert_eq!(program.signing_key, None, "SSH signing needs a key");
+    assert_eq!(program.allowed_signers_file.as_deref(), Some("allowed".as_ref()));
+    assert!(matches!(
+        program.sign(b"payload"),
+        Err(gix::signing::sign::Error::MissingSigningKey { format: Format::Ssh })
+    ));
+
+    repo.config_snapshot_mut()
+        .set_raw_value("gpg", None, "format", "unknown")?;
+    assert_eq!(
+        repo.signing_program(None)?.format,
+        Format::OpenPgp,
+        "invalid formats are ignored in lenient mode"
+    );
+    Ok(())
+}
+
+#[test]
+fn ssh_signed_commits_and_tags_round_trip() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let key = tmp.path().join("key");
+    let Ok(status) = Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", "test", "-f"])
+        .arg(&key)
+        .status()
+    else {
+        // `ssh-keygen` isn't available.
+        return Ok(());
+    };
+    assert!(status.success());
+    let public_key = std::fs::read_to_string(tmp.path().join("key.pub"))?;
+    let allowed_signers = tmp.path().join("allowed_signers");
+    std::fs::write(&allowed_signers, format!("signer@example.com {public_key}"))?;
+
+    let mut repo = repo_in(&tmp)?;
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value("gpg", None, "format", "ssh")?;
+        config.set_raw_value("user", None, "signingKey", key.to_str().expect("valid UTF-8"))?;
+        config.set_raw_value(
+            "gpg",
+            Some("ssh".into()),
+            "allowedSignersFile",
+            allowed_signers.to_str().expect("valid UTF-8"),
+        )?;
+        config.set_raw_value("commit", None, "gpgSign", "true")?;
+        config.set_raw_value("tag", None, "gpgSign", "true")?;
+    }
+
+    let signature = gix::actor::Signature {
+        name: "c".into(),
+        email: "c@example.com".into(),
+        time: gix::date::Time::new(1, 1800),
+    };
+    let commit = repo
+        .commit_as(
+            &signature,
+            &signature,
+            "HEAD",
+            "initial",
+            repo.empty_tree().id,
+            gix::commit::NO_PARENT_IDS,
+        )?
+        .object()?
+        .into_commit();
+    let signed = commit.signature().expect("commit.gpgSign is set");
+    assert_eq!(Format::from_signature(&signed.signature), Some(Format::Ssh));
+    let verification = repo.verify_signature(&signed)?;
+    assert!(verification.is_valid, "{verification:?}");
+    assert_eq!(verification.signer.expect("known"), "signer@example.com");
+
+    let mut tampered = signed.clone();
+    tampered.payload.extend_from_slice(b"tampered");
+    assert!(!repo.verify_signature(&tampered)?.is_valid);
+
+    let tag = repo
+        .tag(
+            "v1",
+            commit.id,
+            gix_object::Kind::Commit,
+            Some(signature.to_ref()),
+            "message",
+            gix_ref::transaction::PreviousValue::MustNotExist,
+        )?
+        .id()
+        .object()?
+        .into_tag();
+    let signed = tag.signature()?.expect("tag.gpgSign is set");
+    let verification = repo.verify_signature(&signed)?;
+    assert!(verification.is_valid, "{verification:?}");
+    Ok(())
+}
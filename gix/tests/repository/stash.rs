@@ -0,0 +1,311 @@
+use std::path::Path;
+
+use gix::{bstr::ByteSlice, stash};
+
+use crate::util::repo_rw;
+
+/// Make the same changes as the fixture script did before stashing them with `git`.
+fn make_changes(repo: &gix::Repository) -> crate::Result {
+    let work_dir = repo.work_dir().expect("non-bare");
+    append(&work_dir.join("a"), "staged\n")?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, Some("a"), Default::default())?;
+    index.write(Default::default())?;
+
+    append(&work_dir.join("b"), "unstaged\n")?;
+    std::fs::remove_file(work_dir.join("dir/c"))?;
+    std::fs::write(work_dir.join("untracked"), "untracked\n")?;
+    std::fs::write(work_dir.join("file.ignored"), "ignored\n")?;
+    Ok(())
+}
+
+fn append(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut data = std::fs::read(path)?;
+    data.extend_from_slice(content.as_bytes());
+    std::fs::write(path, data)
+}
+
+fn read(repo: &gix::Repository, path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.work_dir().expect("non-bare").join(path))
+}
+
+fn tree_of(repo: &gix::Repository, id: impl Into<gix::ObjectId>) -> crate::Result<gix::ObjectId> {
+    Ok(repo.find_object(id)?.into_commit().tree_id()?.detach())
+}
+
+fn index_tree(repo: &gix::Repository) -> crate::Result<gix::ObjectId> {
+    let mut index = repo.open_index()?;
+    Ok(repo.write_tree_from_index(&mut index)?.detach())
+}
+
+/// Return `true` if the index entry at `path` has the stat information of its file in the worktree.
+fn stat_is_up_to_date(repo: &gix::Repository, path: &str) -> crate::Result<bool> {
+    let index = repo.open_index()?;
+    let entry = index.entry_by_path_and_stage(path.into(), 0).expect("present");
+    let md = repo.work_dir().expect("non-bare").join(path).symlink_metadata()?;
+    Ok(entry.stat == gix_index::entry::Stat::from_fs(&md)?)
+}
+
+#[test]
+fn list_stashes_created_by_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].id, repo.rev_parse_single(stash::REFERENCE)?);
+    assert_eq!(list[0].message, "On main: from git");
+    Ok(())
+}
+
+#[test]
+fn save_creates_the_same_commits_as_git_and_resets_the_worktree() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    make_changes(&repo)?;
+    let gitignore_stat = |repo: &gix::Repository| -> crate::Result<_> {
+        let index = repo.open_index()?;
+        Ok(index
+            .entry_by_path_and_stage(".gitignore".into(), 0)
+            .expect("tracked")
+            .stat)
+    };
+    let unchanged_stat = gitignore_stat(&repo)?;
+
+    let id = repo
+        .stash_save(stash::Options {
+            message: None,
+            include_untracked: true,
+        })?
+        .expect("there are changes");
+    let list = repo.stash_list()?;
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0].id, id);
+    let head = repo.head_id()?;
+    let expected_summary = format!("{} initial commit", head.shorten_or_id());
+    assert_eq!(list[0].message, format!("WIP on main: {expected_summary}"));
+
+    let ours = repo.find_object(id)?.into_commit();
+    let git = repo.find_object(list[1].id)?.into_commit();
+    assert_eq!(
+        ours.tree_id()?,
+        git.tree_id()?,
+        "the worktree is recorded in the same way"
+    );
+    let our_parents: Vec<_> = ours.parent_ids().collect();
+    let git_parents: Vec<_> = git.parent_ids().collect();
+    assert_eq!(our_parents.len(), 3);
+    assert_eq!(our_parents[0], head, "the first parent is HEAD");
+    for (ours, git) in our_parents.iter().zip(git_parents.iter()).skip(1) {
+        assert_eq!(
+            tree_of(&repo, *ours)?,
+            tree_of(&repo, *git)?,
+            "the index and untracked files are recorded the same way"
+        );
+    }
+    assert_eq!(
+        repo.find_object(our_parents[1])?.into_commit().message_raw()?,
+        format!("index on main: {expected_summary}\n")
+    );
+    assert_eq!(
+        repo.find_object(our_parents[2])?.into_commit().message_raw()?,
+        format!("untracked files on main: {expected_summary}\n")
+    );
+
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\n", "the worktree is reset to HEAD");
+    assert_eq!(read(&repo, "b")?, "b\n");
+    assert_eq!(read(&repo, "dir/c")?, "c\n");
+    assert!(read(&repo, "untracked").is_err(), "untracked files were removed");
+    assert_eq!(read(&repo, "file.ignored")?, "ignored\n", "ignored files are kept");
+    assert_eq!(index_tree(&repo)?, tree_of(&repo, head)?, "the index is reset to HEAD");
+    for path in ["a", "b", "dir/c"] {
+        assert!(
+            stat_is_up_to_date(&repo, path)?,
+            "{path}: files written to the worktree are up-to-date in the index"
+        );
+    }
+    assert_eq!(
+        gitignore_stat(&repo)?,
+        unchanged_stat,
+        "unchanged files keep their stat information"
+    );
+
+    assert!(
+        repo.stash_save(Default::default())?.is_none(),
+        "without changes, there is nothing to stash"
+    );
+    Ok(())
+}
+
+#[test]
+fn pop_restores_the_worktree_and_index() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let stash = repo.stash_list()?[0].id;
+    let index_commit = repo
+        .find_object(stash)?
+        .into_commit()
+        .parent_ids()
+        .nth(1)
+        .expect("index");
+
+    let b = repo.work_dir().expect("non-bare").join("b");
+    std::fs::write(&b, "local change")?;
+    let err = repo.stash_pop(0, true).unwrap_err();
+    assert!(
+        matches!(&err, stash::apply::Error::LocalChanges { path } if path == "b"),
+        "local changes are never overwritten"
+    );
+    std::fs::write(&b, "b\n")?;
+
+    let outcome = repo.stash_pop(0, true)?;
+    assert!(!outcome.has_conflicts());
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\nstaged\n");
+    assert_eq!(read(&repo, "b")?, "b\nunstaged\n");
+    assert!(read(&repo, "dir/c").is_err(), "deletions are applied");
+    assert_eq!(read(&repo, "untracked")?, "untracked\n");
+    assert_eq!(
+        index_tree(&repo)?,
+        tree_of(&repo, index_commit)?,
+        "the index was reinstated"
+    );
+    assert!(stat_is_up_to_date(&repo, "a")?, "written files match the index");
+    assert!(repo.stash_list()?.is_empty(), "the stash was dropped");
+    assert!(repo.try_find_reference(stash::REFERENCE)?.is_none());
+
+    let err = repo.stash_apply(0, false).unwrap_err();
+    assert!(matches!(err, stash::apply::Error::NoSuchStash { index: 0 }));
+    Ok(())
+}
+
+#[test]
+fn apply_without_index_only_adds_new_files() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let head_tree = tree_of(&repo, repo.head_id()?)?;
+    std::fs::write(repo.work_dir().expect("non-bare").join("new"), "new\n")?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, Some("new"), Default::default())?;
+    index.write(Default::default())?;
+    let new_stash = repo.stash_save(Default::default())?.expect("changes");
+
+    let outcome = repo.stash_apply(1, false)?;
+    assert!(!outcome.has_conflicts());
+    assert_eq!(read(&repo, "a")?, "1\n2\n3\nstaged\n");
+    assert_eq!(index_tree(&repo)?, head_tree, "changes aren't staged");
+    assert_eq!(repo.stash_list()?.len(), 2, "apply keeps the stash");
+
+    let outcome = repo.stash_apply(0, false)?;
+    assert!(!outcome.has_conflicts());
+    assert_eq!(read(&repo, "new")?, "new\n");
+    let index = repo.open_index()?;
+    assert!(
+        index.entry_by_path_and_stage("new".into(), 0).is_some(),
+        "new files are added to the index"
+    );
+    assert_eq!(repo.stash_drop(0)?, new_stash);
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_written_to_the_worktree_and_pop_keeps_the_stash() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::write(work_dir.join("b"), "b\nconflicting\n")?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, Some("b"), Default::default())?;
+    index.write(Default::default())?;
+
+    let outcome = repo.stash_pop(0, false)?;
+    assert_eq!(outcome.conflicts.len(), 1);
+    assert_eq!(outcome.conflicts[0].path(), "b");
+    let b = read(&repo, "b")?;
+    assert!(b.contains("<<<<<<< Updated upstream") && b.contains(">>>>>>> Stashed changes"));
+    assert_eq!(repo.stash_list()?.len(), 1, "the stash is kept if there are conflicts");
+    assert!(
+        !stat_is_up_to_date(&repo, "b")?,
+        "files with conflicts don't match the index, which is unchanged"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn apply_replaces_symlinks_in_leading_directories_instead_of_following_them() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::write(work_dir.join("dir/c"), "changed\n")?;
+    repo.stash_save(Default::default())?.expect("changes");
+    assert_eq!(read(&repo, "dir/c")?, "c\n");
+
+    std::fs::create_dir(work_dir.join("outside"))?;
+    std::fs::write(work_dir.join("outside/c"), "c\n")?;
+    std::fs::remove_dir_all(work_dir.join("dir"))?;
+    std::os::unix::fs::symlink("outside", work_dir.join("dir"))?;
+
+    let outcome = repo.stash_apply(0, false)?;
+    assert!(!outcome.has_conflicts());
+    assert!(
+        !work_dir.join("dir").symlink_metadata()?.is_symlink(),
+        "the symlink was replaced by a directory"
+    );
+    assert_eq!(read(&repo, "dir/c")?, "changed\n");
+    assert_eq!(
+        read(&repo, "outside/c")?,
+        "c\n",
+        "files outside of the path aren't touched"
+    );
+    Ok(())
+}
+
+#[test]
+fn drop_rewrites_the_reflog() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_stash_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    let mut ids = vec![repo.stash_list()?[0].id];
+    for content in ["one", "two"] {
+        std::fs::write(work_dir.join("b"), content)?;
+        ids.push(
+            repo.stash_save(stash::Options {
+                message: Some(content.into()),
+                ..Default::default()
+            })?
+            .expect("changes")
+            .detach(),
+        );
+    }
+    let messages = |repo: &gix::Repository| -> crate::Result<Vec<String>> {
+        Ok(repo
+            .stash_list()?
+            .into_iter()
+            .map(|e| e.message.to_str_lossy().into_owned())
+            .collect())
+    };
+    assert_eq!(messages(&repo)?, ["On main: two", "On main: one", "On main: from git"]);
+
+    assert_eq!(repo.stash_drop(1)?, ids[1]);
+    assert_eq!(messages(&repo)?, ["On main: two", "On main: from git"]);
+    assert_eq!(repo.rev_parse_single(stash::REFERENCE)?, ids[2]);
+    let log = std::fs::read(repo.git_dir().join("logs").join(stash::REFERENCE))?;
+    let second_line = log.lines().nth(1).expect("two lines");
+    assert!(
+        second_line.starts_with(ids[0].to_string().as_bytes()),
+        "the previous value of the next entry is rewritten"
+    );
+
+    assert_eq!(repo.stash_drop(0)?, ids[2]);
+    assert_eq!(messages(&repo)?, ["On main: from git"]);
+    assert_eq!(
+        repo.rev_parse_single(stash::REFERENCE)?,
+        ids[0],
+        "the reference points to the most recent stash"
+    );
+    let log_path = repo.git_dir().join("logs").join(stash::REFERENCE);
+    assert_eq!(
+        std::fs::read(&log_path)?.lines().count(),
+        1,
+        "updating the reference doesn't add to the log"
+    );
+
+    std::fs::write(&log_path, "0123\n")?;
+    assert!(matches!(
+        repo.stash_drop(0),
+        Err(stash::drop::Error::MalformedLog { line }) if line == "0123"
+    ));
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use gix::{
+    bstr::BString,
+    index::entry,
+    status::{Change, ConflictKind, Item, UntrackedFiles},
+};
+
+fn untracked(path: &str) -> Item {
+    Item::Untracked { rela_path: path.into() }
+}
+
+fn changes(items: &[Item]) -> Vec<(BString, Option<Change>, Option<Change>)> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Changed(entry) => Some((entry.rela_path.clone(), entry.staged, entry.unstaged)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn untracked_directories_are_collapsed_by_default() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let outcome = repo.status()?.into_outcome(None::<&str>)?;
+    assert_eq!(outcome.head_id, Some(repo.head_id()?.detach()));
+    assert_eq!(
+        outcome.head_name.as_ref().map(|name| name.as_bstr().to_string()),
+        Some("refs/heads/main".into())
+    );
+    assert_eq!(
+        outcome.items,
+        [
+            untracked("bare-repo-with-index.git/"),
+            untracked("bare.git/"),
+            untracked("non-bare-repo-without-index/"),
+        ],
+        "nested repositories are untracked directories, and empty directories are not reported"
+    );
+
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::None)
+        .into_outcome(None::<&str>)?;
+    assert_eq!(outcome.items, []);
+
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::Files)
+        .into_outcome(["bare.git/refs"])?;
+    assert!(
+        outcome.items.is_empty(),
+        "the refs directory only contains empty directories"
+    );
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::Files)
+        .into_outcome(["bare.git/HEAD", "bare.git/config"])?;
+    assert_eq!(
+        outcome.items,
+        [untracked("bare.git/HEAD"), untracked("bare.git/config")]
+    );
+    Ok(())
+}
+
+#[test]
+fn staged_and_unstaged_changes() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join("new"), "new\n")?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(&mut index, ["new"], Default::default())?;
+    index.write(Default::default())?;
+    std::fs::write(workdir.join("new"), "changed\n")?;
+    std::fs::write(workdir.join("this"), "changed\n")?;
+
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::None)
+        .into_outcome(None::<&str>)?;
+    assert_eq!(
+        changes(&outcome.items),
+        [
+            ("new".into(), Some(Change::Added), Some(Change::Modified)),
+            ("this".into(), None, Some(Change::Modified)),
+        ]
+    );
+    let Item::Changed(entry) = &outcome.items[0] else {
+        unreachable!("checked above")
+    };
+    assert_eq!(entry.head_mode, None, "added entries don't exist in HEAD");
+    assert_eq!(entry.index_mode, Some(entry::Mode::FILE));
+    assert_eq!(entry.worktree_mode, Some(entry::Mode::FILE));
+
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::None)
+        .into_outcome(["th*"])?;
+    assert_eq!(
+        changes(&outcome.items),
+        [("this".into(), None, Some(Change::Modified))],
+        "pathspecs limit the output"
+    );
+
+    std::fs::remove_file(workdir.join("this"))?;
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::None)
+        .into_outcome(["this"])?;
+    assert_eq!(changes(&outcome.items), [("this".into(), None, Some(Change::Deleted))]);
+    Ok(())
+}
+
+#[test]
+fn renames_in_the_index_are_tracked() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::rename(workdir.join("this"), workdir.join("that"))?;
+    let mut index = repo.open_index()?;
+    repo.add_to_index(
+        &mut index,
+        ["this", "that"],
+        gix::add::Options {
+            record_removals: true,
+            ..Default::default()
+        },
+    )?;
+    index.write(Default::default())?;
+
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::None)
+        .into_outcome(None::<&str>)?;
+    assert_eq!(changes(&outcome.items), [("that".into(), Some(Change::Renamed), None)]);
+    let Item::Changed(entry) = &outcome.items[0] else {
+        unreachable!("checked above")
+    };
+    let source = entry.source.as_ref().expect("set for renames");
+    assert_eq!(source.rela_path, "this");
+    assert_eq!(source.diff, None, "the content is identical");
+    assert_eq!(entry.head_id, entry.index_id);
+    assert_eq!(outcome.rewrites.expect("enabled by default").num_similarity_checks, 0);
+
+    let outcome = repo
+        .status()?
+        .track_rewrites(None)
+        .untracked_files(UntrackedFiles::None)
+        .into_outcome(None::<&str>)?;
+    assert_eq!(
+        changes(&outcome.items),
+        [
+            ("that".into(), Some(Change::Added), None),
+            ("this".into(), Some(Change::Deleted), None),
+        ]
+    );
+    assert!(outcome.rewrites.is_none());
+    Ok(())
+}
+
+#[test]
+fn ignored_files_are_only_reported_on_request() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let workdir = repo.work_dir().expect("non-bare");
+    std::fs::write(workdir.join(".gitignore"), "*.git/\nignored/\n*.ignored\n")?;
+    std::fs::create_dir(workdir.join("ignored"))?;
+    std::fs::write(workdir.join("ignored/file"), "")?;
+    std::fs::write(workdir.join("some/very/file.ignored"), "")?;
+    std::fs::write(workdir.join("some/very/file"), "")?;
+
+    let outcome = repo.status()?.into_outcome(None::<&str>)?;
+    assert_eq!(
+        outcome.items,
+        [
+            untracked(".gitignore"),
+            untracked("non-bare-repo-without-index/"),
+            untracked("some/")
+        ]
+    );
+
+    let outcome = repo.status()?.ignored(true).into_outcome(None::<&str>)?;
+    assert_eq!(
+        outcome.items,
+        [
+            untracked(".gitignore"),
+            Item::Ignored {
+                rela_path: "bare-repo-with-index.git/".into()
+            },
+            Item::Ignored {
+                rela_path: "bare.git/".into()
+            },
+            Item::Ignored {
+                rela_path: "ignored/".into()
+            },
+            untracked("non-bare-repo-without-index/"),
+            untracked("some/"),
+            Item::Ignored {
+                rela_path: "some/very/file.ignored".into()
+            },
+        ],
+        "ignored directories are collapsed, while ignored files in untracked directories are listed as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_reported_by_the_stages_they_have() -> crate::Result {
+    let (repo, _tmp) = crate::basic_rw_repo()?;
+    let mut index = repo.open_index()?;
+    let id = index.entry_by_path_and_stage("this".into(), 0).expect("present").id;
+    let stage = |stage: u32| entry::Flags::from_bits_retain(stage << 12);
+    for (path, stages) in [
+        ("both-added", &[2, 3][..]),
+        ("both-modified", &[1, 2, 3]),
+        ("deleted-by-us", &[1, 3]),
+    ] {
+        for s in stages {
+            index.dangerously_push_entry(Default::default(), id, stage(*s), entry::Mode::FILE, path.into());
+        }
+    }
+    index.sort_entries();
+    index.write(Default::default())?;
+
+    let outcome = repo
+        .status()?
+        .untracked_files(UntrackedFiles::None)
+        .into_outcome(None::<&str>)?;
+    let conflicts: Vec<_> = outcome
+        .items
+        .iter()
+        .map(|item| match item {
+            Item::Conflict(conflict) => (conflict.rela_path.to_string(), conflict.kind, conflict.base.is_some()),
+            _ => unreachable!("only conflicts are expected, got {item:?}"),
+        })
+        .collect();
+    assert_eq!(
+        conflicts,
+        [
+            ("both-added".into(), ConflictKind::BothAdded, false),
+            ("both-modified".into(), ConflictKind::BothModified, true),
+            ("deleted-by-us".into(), ConflictKind::DeletedByUs, true),
+        ]
+    );
+    Ok(())
+}
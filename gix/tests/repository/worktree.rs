@@ -98,7 +98,7 @@ mod with_core_worktree_config {
 
     fn repo(name: &str) -> gix::Repository {
         let dir = gix_testtools::scripted_fixture_read_only("make_core_worktree_repo.sh").unwrap();
-        gix::open_opts(dir.join(name), crate::restricted()).unwrap()
+        gix::open_opts(dir.join(name), crate::util::restricted()).unwrap()
     }
 
     fn count_deleted(git_dir: &std::path::Path) -> usize {
@@ -262,6 +262,11 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         assert_eq!(proxy_lock_reason, expected.locked);
         let proxy_is_locked = actual.is_locked();
         assert_eq!(proxy_is_locked, proxy_lock_reason.is_some());
+        assert_eq!(
+            actual.prune_reason().map(|reason| reason.to_string()),
+            expected.prunable.as_ref().map(ToString::to_string),
+            "we detect prunable worktrees just like git"
+        );
         // TODO: check id of expected worktree, but need access to .gitdir from worktree base
         let proxy_id = actual.id().to_owned();
         assert_eq!(
@@ -300,3 +305,181 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+mod manage {
+    use std::path::{Path, PathBuf};
+
+    use gix::worktree::{add, prune, Proxy};
+
+    use crate::util::repo_rw;
+
+    /// Add a linked worktree at `path` relative to the worktree of `repo`, with `HEAD` detached at `spec`.
+    fn add(repo: &gix::Repository, path: &str, spec: &str) -> crate::Result<PathBuf> {
+        let path = repo.work_dir().expect("non-bare").join(path);
+        repo.worktree_add(
+            &path,
+            add::Options {
+                head: add::Head::Detached(repo.rev_parse_single(spec)?.detach()),
+                lock: None,
+                force: false,
+            },
+        )?;
+        Ok(path)
+    }
+
+    fn find<'repo>(repo: &'repo gix::Repository, id: &str) -> crate::Result<Proxy<'repo>> {
+        Ok(repo
+            .worktrees()?
+            .into_iter()
+            .find(|proxy| proxy.id() == id)
+            .expect("worktree present"))
+    }
+
+    fn git_status(work_dir: &Path) -> crate::Result<String> {
+        let out = std::process::Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(work_dir)
+            .output()?;
+        assert!(out.status.success(), "git accepts the worktree");
+        Ok(String::from_utf8(out.stdout)?)
+    }
+
+    #[test]
+    fn add_with_new_branch_and_detached() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_linked_worktrees_repo.sh")?;
+        let head = repo.head_id()?.detach();
+        let path = repo.work_dir().expect("non-bare").join("new");
+        let wt = repo.worktree_add(
+            &path,
+            add::Options {
+                head: add::Head::Branch {
+                    name: "refs/heads/new".try_into()?,
+                    start_point: Some(head),
+                },
+                lock: None,
+                force: false,
+            },
+        )?;
+        assert_eq!(wt.head_name()?.expect("attached").as_bstr(), "refs/heads/new");
+        assert_eq!(wt.head_id()?, head);
+        assert_eq!(repo.rev_parse_single("new")?, head, "the branch was created");
+        assert_eq!(std::fs::read(path.join("a"))?, b"hello\n", "files are checked out");
+        assert_eq!(git_status(&path)?, "", "the index matches the checkout");
+
+        let proxy = find(&repo, "new")?;
+        assert!(!proxy.is_locked(), "the lock used during initialization is removed");
+        assert_eq!(proxy.base()?, gix_path::realpath(&path)?);
+        assert_eq!(std::fs::read(proxy.git_dir().join("commondir"))?, b"../..\n");
+
+        for name in ["refs/heads/new", "refs/heads/main"] {
+            let err = repo
+                .worktree_add(
+                    repo.work_dir().expect("non-bare").join("other"),
+                    add::Options {
+                        head: add::Head::Branch {
+                            name: name.try_into()?,
+                            start_point: None,
+                        },
+                        lock: None,
+                        force: false,
+                    },
+                )
+                .unwrap_err();
+            assert!(
+                matches!(err, add::Error::BranchCheckedOut { .. }),
+                "branches can only be checked out once"
+            );
+        }
+
+        let parent = repo.rev_parse_single("HEAD~1")?.detach();
+        let nested = repo.work_dir().expect("non-bare").join("nested").join("new");
+        let wt = repo.worktree_add(
+            &nested,
+            add::Options {
+                head: add::Head::Detached(parent),
+                lock: Some("reason".into()),
+                force: false,
+            },
+        )?;
+        assert!(wt.head_name()?.is_none(), "HEAD is detached");
+        assert_eq!(wt.head_id()?, parent);
+        assert_eq!(std::fs::read(nested.join("a"))?, b"");
+        let proxy = find(&repo, "new1")?;
+        assert_eq!(proxy.lock_reason().expect("locked"), "reason", "the id is made unique");
+        assert!(matches!(
+            add(&repo, "new", "HEAD"),
+            Err(err) if err.to_string().contains("already exists")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn lock_relocate_remove_and_prune() -> crate::Result {
+        let (repo, tmp) = repo_rw("make_linked_worktrees_repo.sh")?;
+        add(&repo, "wt-a", "HEAD")?;
+        let deleted = add(&repo, "wt-deleted", "HEAD")?;
+        std::fs::remove_dir_all(deleted)?;
+
+        let proxy = find(&repo, "wt-a")?;
+        proxy.lock(Some("moving".into()))?;
+        assert!(proxy.lock(None).is_err(), "it's already locked");
+        assert!(matches!(
+            proxy.relocate(tmp.path().join("moved")),
+            Err(gix::worktree::proxy::relocate::Error::Locked { .. })
+        ));
+        proxy.unlock()?;
+
+        let moved = proxy.relocate(tmp.path().join("moved"))?;
+        assert_eq!(proxy.base()?, moved);
+        assert_eq!(git_status(&moved)?, "", "the worktree still works after moving");
+
+        std::fs::write(moved.join("untracked"), "")?;
+        assert!(matches!(
+            find(&repo, "wt-a")?.remove(false),
+            Err(gix::worktree::proxy::remove::Error::Dirty { .. })
+        ));
+        find(&repo, "wt-a")?.remove(true)?;
+        assert!(!moved.exists());
+        assert!(repo.worktrees()?.iter().all(|proxy| proxy.id() != "wt-a"));
+
+        assert_eq!(
+            repo.worktrees_prune(true)?,
+            [("wt-deleted".into(), prune::Reason::NonExistentLocation)]
+        );
+        assert_eq!(repo.worktrees_prune(false)?.len(), 1);
+        assert!(repo.worktrees_prune(false)?.is_empty(), "nothing left to prune");
+        assert!(repo.worktrees()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn repair_after_manual_move() -> crate::Result {
+        let (repo, tmp) = repo_rw("make_linked_worktrees_repo.sh")?;
+        let wt_a = add(&repo, "wt-a", "HEAD")?;
+        let wt_b = add(&repo, "wt-b", "HEAD~1")?;
+
+        let moved = tmp.path().join("moved");
+        std::fs::rename(wt_b, &moved)?;
+        assert_eq!(
+            find(&repo, "wt-b")?.prune_reason(),
+            Some(prune::Reason::NonExistentLocation)
+        );
+        std::fs::write(wt_a.join(".git"), "gitdir: /does/not/exist\n")?;
+
+        let repaired = repo.worktrees_repair(Some(&moved))?;
+        assert_eq!(
+            repaired.len(),
+            2,
+            "the moved worktree's gitdir file and the broken .git file"
+        );
+        assert_eq!(find(&repo, "wt-b")?.base()?, gix_path::realpath(&moved)?);
+        assert_eq!(find(&repo, "wt-b")?.prune_reason(), None);
+        assert_eq!(git_status(&moved)?, "");
+        assert_eq!(git_status(&wt_a)?, "");
+        assert!(
+            repo.worktrees_repair(None::<&str>)?.is_empty(),
+            "nothing left to repair"
+        );
+        Ok(())
+    }
+}
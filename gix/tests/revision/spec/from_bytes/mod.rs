@@ -13,22 +13,119 @@ mod traverse;
 mod peel;
 
 mod sibling_branch {
-    use gix::revision::spec::parse::Error;
+    use gix::{
+        bstr::{BStr, ByteSlice},
+        revision::spec::parse::Error,
+    };
 
-    use crate::revision::spec::from_bytes::{parse_spec_no_baseline, repo};
+    fn client(overrides: &[&str]) -> crate::Result<gix::Repository> {
+        Ok(gix::open_opts(
+            gix_testtools::scripted_fixture_read_only("make_sibling_branch_repos.sh")?.join("client"),
+            crate::restricted().config_overrides(overrides.iter().copied()),
+        )?)
+    }
 
     #[test]
-    fn is_planned_and_delayed_until_remotes_are_sorted() {
-        let repo = repo("complex_graph").unwrap();
+    fn upstream_and_push_branches_resolve_like_git() -> crate::Result {
+        let baseline =
+            std::fs::read(gix_testtools::scripted_fixture_read_only("make_sibling_branch_repos.sh")?.join("baseline"))?;
+        let mut lines = baseline.lines();
+        let mut count = 0;
+        while let (Some(overrides), Some(spec), Some(expected)) = (lines.next(), lines.next(), lines.next()) {
+            let overrides: Vec<_> = overrides.to_str()?.split(' ').filter(|o| !o.is_empty()).collect();
+            let repo = client(&overrides)?;
+            match repo.rev_parse(spec.as_bstr()) {
+                Ok(actual) => assert_eq!(
+                    actual
+                        .first_reference()
+                        .expect("sibling branches are references")
+                        .name
+                        .as_bstr(),
+                    expected.as_bstr(),
+                    "{spec:?} with {overrides:?}",
+                ),
+                Err(err) => assert_eq!(
+                    expected.as_bstr(),
+                    "error",
+                    "{spec:?} with {overrides:?} failed unexpectedly: {err}"
+                ),
+            }
+            count += 1;
+        }
+        assert_eq!(count, 24, "all baseline entries were checked");
+        Ok(())
+    }
+
+    #[test]
+    fn errors_are_descriptive() -> crate::Result {
+        let repo = client(&[])?;
+        let err = repo.rev_parse("no-upstream@{u}").unwrap_err();
+        assert!(matches!(err, Error::NoUpstream { .. }));
+        assert_eq!(err.to_string(), "No upstream configured for branch \"no-upstream\"");
+
+        let err = repo.rev_parse("v1@{upstream}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "\"refs/tags/v1\" is not a branch and thus has no upstream or push destination"
+        );
+
+        let err = repo.rev_parse("renamed@{push}").unwrap_err();
         assert!(matches!(
-            parse_spec_no_baseline("main@{push}", &repo).unwrap_err(),
-            Error::Planned { .. }
+            err,
+            Error::BranchRemoteTrackingRefName(
+                gix::repository::branch_remote_tracking_ref_name::Error::SimplePushMismatch { .. }
+            )
         ));
 
+        let repo = client(&["push.default=nothing"])?;
+        let err = repo.rev_parse("main@{push}").unwrap_err();
         assert!(matches!(
-            parse_spec_no_baseline("main@{upstream}", &repo).unwrap_err(),
-            Error::Planned { .. }
+            err,
+            Error::BranchRemoteTrackingRefName(
+                gix::repository::branch_remote_tracking_ref_name::Error::PushDefaultNothing { .. }
+            )
         ));
+        Ok(())
+    }
+
+    #[test]
+    fn tracking_ref_names_are_available_on_references() -> crate::Result {
+        let repo = client(&["push.default=current"])?;
+        let branch = repo.find_reference("published")?;
+        assert_eq!(
+            branch
+                .remote_tracking_ref_name(gix::remote::Direction::Push)
+                .transpose()?
+                .map(|name| name.as_bstr().to_owned()),
+            Some(BStr::new("refs/remotes/fork/published").to_owned())
+        );
+        assert!(
+            branch.remote_tracking_ref_name(gix::remote::Direction::Fetch).is_none(),
+            "there is no upstream"
+        );
+        Ok(())
+    }
+}
+
+mod fetch_head {
+    #[test]
+    fn resolves_to_the_first_merge_candidate_as_written_by_git() -> crate::Result {
+        let repo = gix::open_opts(
+            gix_testtools::scripted_fixture_read_only("make_fetch_head_repos.sh")?.join("client"),
+            crate::restricted(),
+        )?;
+        assert_eq!(
+            repo.rev_parse_single("FETCH_HEAD")?,
+            repo.rev_parse_single("refs/remotes/origin/main")?,
+            "the merge-ref of the current branch"
+        );
+        assert_eq!(repo.rev_parse_single("FETCH_HEAD~1")?, repo.head_id()?);
+        assert_eq!(
+            repo.rev_parse("FETCH_HEAD")?.first_reference(),
+            None,
+            "it's not a reference"
+        );
+        Ok(())
     }
 }
 
@@ -972,10 +972,11 @@ pub fn main() -> Result<()> {
                                 stdin_or_bail()?
                                     .byte_lines()
                                     .filter_map(Result::ok)
-                                    .filter_map(|line| gix::path::Spec::from_bytes(line.as_bstr())),
-                            ) as Box<dyn Iterator<Item = gix::path::Spec>>
+                                    .map(|line| gix::pathspec::parse(line.as_bstr()).map_err(Into::into)),
+                            )
+                                as Box<dyn Iterator<Item = anyhow::Result<gix::pathspec::Pattern>>>
                         } else {
-                            Box::new(pathspecs.into_iter())
+                            Box::new(pathspecs.into_iter().map(Ok))
                         },
                         out,
                         err,
@@ -1034,10 +1035,11 @@ pub fn main() -> Result<()> {
                                 stdin_or_bail()?
                                     .byte_lines()
                                     .filter_map(Result::ok)
-                                    .filter_map(|line| gix::path::Spec::from_bytes(line.as_bstr())),
-                            ) as Box<dyn Iterator<Item = gix::path::Spec>>
+                                    .map(|line| gix::pathspec::parse(line.as_bstr()).map_err(Into::into)),
+                            )
+                                as Box<dyn Iterator<Item = anyhow::Result<gix::pathspec::Pattern>>>
                         } else {
-                            Box::new(pathspecs.into_iter())
+                            Box::new(pathspecs.into_iter().map(Ok))
                         },
                         out,
                         err,
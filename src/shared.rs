@@ -349,11 +349,13 @@ mod clap {
     pub struct AsPathSpec;
 
     impl TypedValueParser for AsPathSpec {
-        type Value = gix::path::Spec;
+        type Value = gix::pathspec::Pattern;
 
         fn parse_ref(&self, cmd: &Command, arg: Option<&Arg>, value: &OsStr) -> Result<Self::Value, Error> {
             OsStringValueParser::new()
-                .try_map(|arg| gix::path::Spec::try_from(arg.as_os_str()))
+                .try_map(|arg| -> Result<_, Box<dyn std::error::Error + Send + Sync>> {
+                    Ok(gix::pathspec::parse(gix::path::os_str_into_bstr(&arg)?)?)
+                })
                 .parse_ref(cmd, arg, value)
         }
     }